@@ -1,18 +1,36 @@
+use super::severity::SeverityKeywords;
 use super::task::{AnalysisTask, TaskLoader};
-use crate::llm::LlmService;
+use crate::llm::config::model_context_window;
+use crate::llm::{LlmService, UsageReport};
 use crate::snapshot::format::{
     Finding, Snapshot, Evidence, Category, Severity, 
     Remediation, RemediationStep, RiskLevel
 };
 use anyhow::Result;
+use futures::stream::{self, StreamExt};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::fmt::Write;
 use tracing::{debug, info, warn};
 
+/// Most severe findings listed in the executive summary prompt
+const SUMMARY_MAX_FINDINGS: usize = 50;
+
+/// Tasks sent to the LLM at the same time by default. Kept low so a run stays within
+/// typical per-minute rate limits; requests that still hit one are retried with backoff
+/// by the OpenAI client.
+pub const DEFAULT_TASK_CONCURRENCY: usize = 4;
+
+/// Appended to a data section shortened to fit the prompt token limit
+const TRUNCATION_NOTE: &str = "\n[... truncated to fit the model's context window]";
+
 pub struct AiExecutor {
     llm_service: LlmService,
     task_loader: TaskLoader,
+    concurrency: usize,
+    severity_keywords: SeverityKeywords,
+    max_prompt_tokens: Option<usize>,
+    truncate_prompts: bool,
 }
 
 impl AiExecutor {
@@ -20,6 +38,10 @@ impl AiExecutor {
         Self {
             llm_service,
             task_loader: TaskLoader::default_tasks_dir(),
+            concurrency: DEFAULT_TASK_CONCURRENCY,
+            severity_keywords: SeverityKeywords::default(),
+            max_prompt_tokens: None,
+            truncate_prompts: false,
         }
     }
     
@@ -27,44 +49,150 @@ impl AiExecutor {
         Self {
             llm_service,
             task_loader: TaskLoader::new(tasks_dir),
+            concurrency: DEFAULT_TASK_CONCURRENCY,
+            severity_keywords: SeverityKeywords::default(),
+            max_prompt_tokens: None,
+            truncate_prompts: false,
         }
     }
+
+    /// Run up to `concurrency` tasks at the same time (at least 1)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Deployment-wide keywords checked before each task's own severity keywords
+    pub fn with_severity_keywords(mut self, keywords: SeverityKeywords) -> Self {
+        self.severity_keywords = keywords;
+        self
+    }
+    
+    /// Most estimated tokens a prompt may have, instead of the model's context window minus
+    /// the tokens reserved for the response
+    pub fn with_max_prompt_tokens(mut self, max_prompt_tokens: Option<usize>) -> Self {
+        self.max_prompt_tokens = max_prompt_tokens;
+        self
+    }
+
+    /// Shorten the largest data sections of a prompt over the token limit instead of failing the task
+    pub fn with_prompt_truncation(mut self, truncate_prompts: bool) -> Self {
+        self.truncate_prompts = truncate_prompts;
+        self
+    }
     
-    /// Run all enabled tasks on a snapshot
+    /// Token usage accumulated by the tasks run so far
+    pub fn usage_report(&self) -> UsageReport {
+        self.llm_service.usage_report()
+    }
+    
+    /// Run all enabled tasks on a snapshot, several at a time. Findings are returned
+    /// in task id order whatever order the tasks finish in.
     pub async fn analyze_all(&mut self, snapshot: &Snapshot) -> Result<Vec<Finding>> {
         info!("Starting AI-only analysis");
         
         // Load all tasks
-        let tasks = self.task_loader.load_all()?;
+        let mut tasks = self.task_loader.load_all()?;
         info!("Loaded {} analysis tasks", tasks.len());
+        tasks.sort_by(|a, b| a.id.cmp(&b.id));
         
-        let mut all_findings = Vec::new();
-        
-        // Execute each task (with cluster type filtering)
-        for task in tasks {
-            // Check if task is compatible with current cluster type
-            if !self.is_task_compatible(&task, snapshot) {
-                debug!("Skipping task '{}' - not compatible with cluster type {:?}", task.name, snapshot.cluster.mode);
-                continue;
-            }
-            
-            info!("Running task: {}", task.name);
-            
-            match self.execute_task(&task, snapshot).await {
-                Ok(mut findings) => {
-                    info!("Task '{}' found {} issues", task.name, findings.len());
-                    all_findings.append(&mut findings);
+        // Skip tasks not meant for this cluster type
+        let tasks: Vec<AnalysisTask> = tasks.into_iter()
+            .filter(|task| {
+                let compatible = self.is_task_compatible(task, snapshot);
+                if !compatible {
+                    debug!("Skipping task '{}' - not compatible with cluster type {:?}", task.name, snapshot.cluster.mode);
                 }
-                Err(e) => {
-                    warn!("Task '{}' failed: {}", task.name, e);
+                compatible
+            })
+            .collect();
+        info!("Running {} task(s), up to {} at a time", tasks.len(), self.concurrency);
+        
+        let this = &*self;
+        let runs: Vec<_> = tasks.iter().enumerate()
+            .map(|(index, task)| async move {
+                info!("Running task: {}", task.name);
+                // A failed task is logged and contributes no findings
+                match this.execute_task(task, snapshot).await {
+                    Ok(findings) => {
+                        info!("Task '{}' found {} issues", task.name, findings.len());
+                        (index, findings)
+                    }
+                    Err(e) => {
+                        warn!("Task '{}' failed: {}", task.name, e);
+                        (index, Vec::new())
+                    }
                 }
-            }
-        }
+            })
+            .collect();
+        let mut results: Vec<(usize, Vec<Finding>)> = stream::iter(runs)
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+        results.sort_by_key(|(index, _)| *index);
         
+        let all_findings: Vec<Finding> = results.into_iter().flat_map(|(_, findings)| findings).collect();
         info!("Analysis complete. Total findings: {}", all_findings.len());
         Ok(all_findings)
     }
     
+    /// Merge findings describing the same issue, compared by the provider's embeddings of
+    /// their titles and descriptions
+    pub async fn group_similar_findings(&self, findings: Vec<Finding>, threshold: f32) -> Vec<Finding> {
+        let embed = |texts| async { Ok(self.llm_service.embed(texts).await?) };
+        super::grouping::group_similar(findings, embed, threshold).await
+    }
+    
+    /// Ask the LLM for a remediation script for every high or critical finding with
+    /// automatable steps, returning how many findings got one
+    pub async fn generate_remediation_scripts(&self, findings: &mut [Finding]) -> usize {
+        let mut generated = 0;
+
+        for finding in findings.iter_mut().filter(|f| needs_remediation_script(f)) {
+            info!("Generating remediation script for {}", finding.id);
+
+            let automatable: Vec<String> = finding.remediation.steps.iter()
+                .filter(|step| step.can_automate)
+                .map(|step| match &step.command {
+                    Some(command) => format!("{}. {} ({})", step.order, step.description, command),
+                    None => format!("{}. {}", step.order, step.description),
+                })
+                .collect();
+            let issue = format!("{}\n\n{}", finding.title, finding.description);
+            let evidence = serde_json::to_string_pretty(&finding.evidence).unwrap_or_default();
+            let constraints = format!(
+                "Only automate these remediation steps:\n{}\nOutput a single bash script in one fenced code block.",
+                automatable.join("\n")
+            );
+
+            match self.llm_service.generate_remediation(&issue, &evidence, Some(&constraints)).await {
+                Ok(remediation) if !remediation.script.is_empty() => {
+                    finding.remediation.script = Some(remediation.script);
+                    generated += 1;
+                }
+                Ok(_) => warn!("LLM returned an empty remediation script for {}", finding.id),
+                Err(e) => warn!("Failed to generate remediation script for {}: {}", finding.id, e),
+            }
+        }
+
+        generated
+    }
+
+    /// Ask the LLM for a short executive narrative of the findings with a prioritized action list
+    pub async fn summarize(&self, findings: &[Finding], snapshot: &Snapshot) -> Result<String> {
+        info!("Generating executive summary of {} finding(s)", findings.len());
+
+        let response = self.llm_service.chat(vec![
+            crate::llm::service::ChatMessage::system(
+                "You are KCPilot, an expert Kafka administrator writing for engineering leadership. \
+                 Respond in markdown without headings."
+            ),
+            crate::llm::service::ChatMessage::user(&summary_prompt(findings, snapshot)),
+        ]).await?;
+
+        Ok(response.trim().to_string())
+    }
+
     /// Execute a single task
     pub async fn execute_task(&self, task: &AnalysisTask, snapshot: &Snapshot) -> Result<Vec<Finding>> {
         debug!("Executing task: {} ({})", task.name, task.id);
@@ -74,15 +202,8 @@ impl AiExecutor {
             return self.execute_task_per_broker(task, snapshot).await;
         }
         
-        // Build the prompt with available data
-        let prompt = self.build_prompt(task, snapshot)?;
-        
-        // Add examples if provided
-        let full_prompt = if let Some(examples) = &task.examples {
-            format!("{}\n\nExamples:\n{}", prompt, examples)
-        } else {
-            prompt
-        };
+        // Build the prompt with available data, fitted to the model's context window
+        let full_prompt = self.fitted_prompt(task, snapshot, "the cluster")?;
         
         // Check token count and warn if too large
         let estimated_tokens = self.estimate_tokens(&full_prompt);
@@ -160,15 +281,8 @@ impl AiExecutor {
     
     /// Execute a single prompt (used by both regular and per-broker analysis)
     async fn execute_single_prompt(&self, task: &AnalysisTask, snapshot: &Snapshot, context: &str) -> Result<Vec<Finding>> {
-        // Build the prompt with available data
-        let prompt = self.build_prompt(task, snapshot)?;
-        
-        // Add examples if provided
-        let full_prompt = if let Some(examples) = &task.examples {
-            format!("{}\n\nExamples:\n{}", prompt, examples)
-        } else {
-            prompt
-        };
+        // Build the prompt with available data, fitted to the model's context window
+        let full_prompt = self.fitted_prompt(task, snapshot, context)?;
         
         // Check token count
         let estimated_tokens = self.estimate_tokens(&full_prompt);
@@ -245,18 +359,87 @@ impl AiExecutor {
         Ok(broker_snapshot)
     }
 
-    /// Build the prompt with actual data
-    fn build_prompt(&self, task: &AnalysisTask, snapshot: &Snapshot) -> Result<String> {
-        let mut prompt = task.prompt.clone();
-        let data_map = self.prepare_data(task, snapshot)?;
-        
-        // Replace placeholders with actual data
-        for (key, value) in data_map {
-            let placeholder = format!("{{{}}}", key);
-            prompt = prompt.replace(&placeholder, &value);
+    /// Most estimated tokens a prompt may have, and where that limit comes from. `None` for
+    /// models of unknown context window without `--max-prompt-tokens`.
+    fn prompt_token_limit(&self) -> Option<(usize, String)> {
+        if let Some(limit) = self.max_prompt_tokens {
+            return Some((limit, "set with --max-prompt-tokens".to_string()));
         }
-        
-        Ok(prompt)
+        let config = self.llm_service.config();
+        let window = model_context_window(&config.model)?;
+        let reserved = config.max_tokens as usize;
+        Some((window.saturating_sub(reserved), format!(
+            "the {}-token context window of {} minus {} tokens reserved for the response",
+            window, config.model, reserved
+        )))
+    }
+
+    /// The task's prompt with its data and examples, checked against the prompt token limit
+    /// before it is sent. A prompt over the limit fails with the data section to narrow, or
+    /// has its largest data sections shortened when prompt truncation is enabled.
+    fn fitted_prompt(&self, task: &AnalysisTask, snapshot: &Snapshot, context: &str) -> Result<String> {
+        let mut data = self.prepare_data(task, snapshot)?;
+        let with_examples = |prompt: String| match &task.examples {
+            Some(examples) => format!("{}\n\nExamples:\n{}", prompt, examples),
+            None => prompt,
+        };
+        let mut prompt = with_examples(fill_prompt(&task.prompt, &data));
+
+        let Some((limit, limit_source)) = self.prompt_token_limit() else {
+            return Ok(prompt);
+        };
+        let mut estimated_tokens = self.estimate_tokens(&prompt);
+        if estimated_tokens <= limit {
+            return Ok(prompt);
+        }
+
+        // Data sections that appear in the prompt, largest first
+        let mut sections: Vec<(String, usize)> = data.iter()
+            .map(|(key, value)| (key.clone(), task.prompt.matches(&format!("{{{}}}", key)).count(), value.len()))
+            .filter(|&(_, occurrences, _)| occurrences > 0)
+            .map(|(key, occurrences, len)| (key, occurrences * len))
+            .collect();
+        sections.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        if !self.truncate_prompts {
+            let largest = match sections.first() {
+                Some((key, len)) => format!("The largest data section is {{{}}} at ~{} tokens.", key, len / 4),
+                None => "The task prompt itself is too large.".to_string(),
+            };
+            return Err(anyhow::anyhow!(
+                "Task '{}' prompt for {} is ~{} tokens, over the limit of {} tokens ({}). {} \
+                 Narrow the task's include_data, enable per_broker_analysis, pass --truncate-prompts \
+                 to shorten the largest data sections, or raise --max-prompt-tokens.",
+                task.name, context, estimated_tokens, limit, limit_source, largest
+            ));
+        }
+
+        let mut truncated = Vec::new();
+        for (key, _) in &sections {
+            let occurrences = task.prompt.matches(&format!("{{{}}}", key)).count();
+            let excess = (estimated_tokens - limit) * 4;
+            let value = &data[key];
+            let keep = value.len().saturating_sub(excess.div_ceil(occurrences) + TRUNCATION_NOTE.len());
+            let shortened = format!("{}{}", truncate_at_line(value, keep), TRUNCATION_NOTE);
+            warn!("{}: truncated {{{}}} from ~{} to ~{} tokens to fit the prompt limit of {} tokens",
+                  task.name, key, value.len() / 4, shortened.len() / 4, limit);
+            data.insert(key.clone(), shortened);
+            truncated.push(format!("{{{}}}", key));
+
+            prompt = with_examples(fill_prompt(&task.prompt, &data));
+            estimated_tokens = self.estimate_tokens(&prompt);
+            if estimated_tokens <= limit {
+                return Ok(prompt);
+            }
+        }
+
+        Err(anyhow::anyhow!(
+            "Task '{}' prompt for {} is still ~{} tokens after truncating {}, over the limit of {} tokens ({}). \
+             Shorten the task prompt or examples, or raise --max-prompt-tokens.",
+            task.name, context, estimated_tokens,
+            if truncated.is_empty() { "no data sections".to_string() } else { truncated.join(", ") },
+            limit, limit_source
+        ))
     }
     
     /// Prepare data based on task requirements
@@ -290,13 +473,27 @@ impl AiExecutor {
         // Add admin data (topics, brokers, etc.)
         if should_include("admin") {
             if let Some(admin_data) = &snapshot.collectors.admin {
-                data.insert("admin".to_string(), 
-                           serde_json::to_string_pretty(admin_data)?);
-                
-                // Also make topics available separately
-                if let Some(topics) = admin_data.get("topics") {
-                    data.insert("topics".to_string(), 
-                               serde_json::to_string_pretty(topics)?);
+                let sampled = admin_data.get("topics")
+                    .and_then(Value::as_array)
+                    .and_then(|topics| sample_topics(topics, task.max_topics));
+
+                if let Some((topics, note)) = sampled {
+                    info!("{}: {}", task.name, note);
+                    let mut admin_data = admin_data.clone();
+                    admin_data["topics"] = Value::Array(topics);
+                    data.insert("admin".to_string(),
+                               format!("{}\n{}", note, serde_json::to_string_pretty(&admin_data)?));
+                    data.insert("topics".to_string(),
+                               format!("{}\n{}", note, serde_json::to_string_pretty(&admin_data["topics"])?));
+                } else {
+                    data.insert("admin".to_string(), 
+                               serde_json::to_string_pretty(admin_data)?);
+                    
+                    // Also make topics available separately
+                    if let Some(topics) = admin_data.get("topics") {
+                        data.insert("topics".to_string(), 
+                                   serde_json::to_string_pretty(topics)?);
+                    }
                 }
             } else {
                 data.insert("admin".to_string(), 
@@ -602,6 +799,7 @@ impl AiExecutor {
                 metrics: vec![],
                 configs: vec![],
                 raw_data: Some(json.clone()),
+                timeline: Vec::new(),
             },
             root_cause,
             remediation,
@@ -623,6 +821,7 @@ impl AiExecutor {
                 metrics: vec![],
                 configs: vec![],
                 raw_data: Some(json!({ "response": response })),
+                timeline: Vec::new(),
             },
             root_cause: None,
             remediation: Remediation {
@@ -649,10 +848,15 @@ impl AiExecutor {
         }
     }
     
-    /// Determine severity based on keywords
+    /// Determine severity based on keywords: deployment-wide keywords first, then the
+    /// task's own, then the task's default
     fn determine_severity(&self, title: &str, description: &str, task: &AnalysisTask) -> Severity {
         let combined = format!("{} {}", title.to_lowercase(), description.to_lowercase());
         
+        if let Some(severity) = self.severity_keywords.severity_for(&combined) {
+            return severity;
+        }
+        
         // Check severity keywords
         for (keyword, severity) in &task.severity_keywords {
             if combined.contains(&keyword.to_lowercase()) {
@@ -677,6 +881,42 @@ impl AiExecutor {
     }
 }
 
+/// Cluster context and findings, most severe first, for the executive summary
+fn summary_prompt(findings: &[Finding], snapshot: &Snapshot) -> String {
+    let scale = crate::report::scoring::ClusterScale::from_snapshot(snapshot);
+    let mut prompt = String::new();
+
+    let _ = writeln!(prompt, "Cluster:");
+    let _ = writeln!(prompt, "- Mode: {:?}", snapshot.cluster.mode);
+    if let Some(version) = &snapshot.cluster.version {
+        let _ = writeln!(prompt, "- Kafka version: {}", version);
+    }
+    let _ = writeln!(prompt, "- Brokers: {}, topics: {}, partitions: {}", scale.brokers, scale.topics, scale.partitions);
+
+    let mut sorted: Vec<&Finding> = findings.iter().collect();
+    sorted.sort_by_key(|f| f.severity);
+    let _ = writeln!(prompt, "\nFindings ({} total):", findings.len());
+    for finding in sorted.iter().take(SUMMARY_MAX_FINDINGS) {
+        let _ = writeln!(prompt, "- [{:?}] {}: {}", finding.severity, finding.title, finding.impact);
+    }
+    if findings.len() > SUMMARY_MAX_FINDINGS {
+        let _ = writeln!(prompt, "- ... and {} less severe finding(s)", findings.len() - SUMMARY_MAX_FINDINGS);
+    }
+
+    prompt.push_str(
+        "\nWrite one concise paragraph summarizing the cluster's health and business risk for leadership, \
+         followed by a numbered list of at most 5 prioritized actions."
+    );
+    prompt
+}
+
+/// Scripts are only generated for severe findings that have steps which can be automated
+fn needs_remediation_script(finding: &Finding) -> bool {
+    finding.severity <= Severity::High
+        && finding.remediation.script.is_none()
+        && finding.remediation.steps.iter().any(|step| step.can_automate)
+}
+
 // Implement Analyzer trait for compatibility
 use crate::analyzers::{Analyzer, AnalyzerResult};
 use async_trait::async_trait;
@@ -710,3 +950,353 @@ impl Analyzer for AiExecutor {
         "Executes AI-powered analysis tasks defined in YAML files"
     }
 }
+
+/// `template` with each `{section}` placeholder replaced by that section's data
+fn fill_prompt(template: &str, data: &HashMap<String, String>) -> String {
+    let mut prompt = template.to_string();
+    for (key, value) in data {
+        let placeholder = format!("{{{}}}", key);
+        prompt = prompt.replace(&placeholder, value);
+    }
+    prompt
+}
+
+/// The start of `text` up to `max_len` bytes, cut after the last whole line unless that
+/// would drop more than half of it, as for single-line JSON values
+fn truncate_at_line(text: &str, max_len: usize) -> &str {
+    if text.len() <= max_len {
+        return text;
+    }
+    let mut end = max_len;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    match text[..end].rfind('\n') {
+        Some(newline) if newline >= end / 2 => &text[..newline],
+        _ => &text[..end],
+    }
+}
+
+/// Pick at most `max_topics` topics when there are more: the half with the most
+/// partitions plus a pseudo-random subset of the rest. The subset is keyed on topic
+/// names so repeated runs send the same sample. Returns `None` when no sampling is needed.
+fn sample_topics(topics: &[Value], max_topics: usize) -> Option<(Vec<Value>, String)> {
+    if topics.len() <= max_topics {
+        return None;
+    }
+
+    let partition_count = |topic: &Value| match &topic["partitions"] {
+        Value::Array(partitions) => partitions.len() as u64,
+        other => other.as_u64().unwrap_or(0),
+    };
+    let shuffle_key = |topic: &Value| {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+        let mut hasher = DefaultHasher::new();
+        topic["name"].as_str().unwrap_or_default().hash(&mut hasher);
+        hasher.finish()
+    };
+
+    let mut by_size: Vec<&Value> = topics.iter().collect();
+    by_size.sort_by_key(|topic| std::cmp::Reverse(partition_count(topic)));
+
+    let largest_count = max_topics.div_ceil(2);
+    let mut rest = by_size.split_off(largest_count);
+    rest.sort_by_key(|topic| shuffle_key(topic));
+    rest.truncate(max_topics - largest_count);
+
+    let note = format!(
+        "NOTE: The cluster has {} topics; only a sample of {} is included \
+         (the {} with the most partitions plus {} others chosen at random). \
+         Do not assume topics missing from the sample do not exist.",
+        topics.len(), largest_count + rest.len(), largest_count, rest.len()
+    );
+    let sample = by_size.into_iter().chain(rest).cloned().collect();
+    Some((sample, note))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::LlmConfig;
+
+    fn finding(id: &str, severity: Severity, can_automate: bool) -> Finding {
+        Finding {
+            id: id.to_string(),
+            title: "Under-replicated partitions".to_string(),
+            description: "3 partitions are under-replicated".to_string(),
+            severity,
+            remediation: Remediation {
+                steps: vec![RemediationStep {
+                    order: 1,
+                    description: "Reassign partitions".to_string(),
+                    command: Some("kafka-reassign-partitions.sh --execute".to_string()),
+                    verification: None,
+                    can_automate,
+                }],
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: None,
+                rollback_plan: None,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn executor(api_base: String) -> AiExecutor {
+        let config = LlmConfig {
+            api_key: "test-key".to_string(),
+            api_base: Some(api_base),
+            ..Default::default()
+        };
+        AiExecutor::new(LlmService::new(config).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_script_generated_for_automatable_findings() {
+        let mut server = mockito::Server::new_async().await;
+        let chunk = json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion.chunk",
+            "created": 1700000000,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "delta": {
+                    "role": "assistant",
+                    "content": "```bash\nkafka-reassign-partitions.sh --execute\n```"
+                },
+                "finish_reason": "stop"
+            }]
+        });
+        let mock = server.mock("POST", "/chat/completions")
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(format!("data: {}\n\ndata: [DONE]\n\n", chunk))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut findings = vec![
+            finding("critical-automatable", Severity::Critical, true),
+            finding("critical-manual", Severity::Critical, false),
+            finding("medium-automatable", Severity::Medium, true),
+        ];
+
+        let generated = executor(server.url()).generate_remediation_scripts(&mut findings).await;
+
+        assert_eq!(generated, 1);
+        assert_eq!(findings[0].remediation.script.as_deref(), Some("kafka-reassign-partitions.sh --execute"));
+        assert!(findings[1].remediation.script.is_none());
+        assert!(findings[2].remediation.script.is_none());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_summarize_sends_findings_and_context() {
+        let mut server = mockito::Server::new_async().await;
+        let chunk = json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion.chunk",
+            "created": 1700000000,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "delta": {"role": "assistant", "content": "  The cluster is at risk.\n\n1. Fix replication  "},
+                "finish_reason": "stop"
+            }]
+        });
+        let mock = server.mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::Regex(r"Brokers: 2.*\[Critical\] Under-replicated partitions".to_string()))
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(format!("data: {}\n\ndata: [DONE]\n\n", chunk))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut snapshot = Snapshot::new(crate::snapshot::format::SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.admin = Some(json!({"brokers": [{"id": 1}, {"id": 2}], "topics": []}));
+        let findings = vec![finding("urp", Severity::Critical, false)];
+
+        let summary = executor(server.url()).summarize(&findings, &snapshot).await.unwrap();
+
+        assert_eq!(summary, "The cluster is at risk.\n\n1. Fix replication");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_tasks_run_concurrently_and_aggregate_in_order() {
+        let mut server = mockito::Server::new_async().await;
+        let response = |title: &str| {
+            let content = json!({"findings": [{"title": title, "severity": "medium"}]}).to_string();
+            let chunk = json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion.chunk",
+                "created": 1700000000,
+                "model": "gpt-4o",
+                "choices": [{"index": 0, "delta": {"role": "assistant", "content": content}, "finish_reason": "stop"}]
+            });
+            format!("data: {}\n\ndata: [DONE]\n\n", chunk)
+        };
+        let mut mocks = Vec::new();
+        for (task, title) in [("alpha", "Alpha issue"), ("gamma", "Gamma issue"), ("delta", "Delta issue")] {
+            mocks.push(server.mock("POST", "/chat/completions")
+                .match_body(mockito::Matcher::Regex(format!("Check {}", task)))
+                .with_status(200)
+                .with_header("content-type", "text/event-stream")
+                .with_body(response(title))
+                .expect(1)
+                .create_async()
+                .await);
+        }
+        // A failing task must not affect the others
+        let failing = server.mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::Regex("Check beta".to_string()))
+            .with_status(400)
+            .with_body(r#"{"error": {"message": "bad request", "type": "invalid_request_error"}}"#)
+            .create_async()
+            .await;
+
+        let tasks_dir = tempfile::TempDir::new().unwrap();
+        for id in ["delta", "beta", "alpha", "gamma", "zookeeper-only"] {
+            let filter = if id == "zookeeper-only" { "cluster_type_filter: [zookeeper]\n" } else { "" };
+            std::fs::write(
+                tasks_dir.path().join(format!("{}.yaml", id)),
+                format!("id: {id}\nname: {id}\ndescription: {id}\nprompt: \"Check {id}\"\n{filter}"),
+            ).unwrap();
+        }
+
+        let config = LlmConfig { api_key: "test-key".to_string(), api_base: Some(server.url()), ..Default::default() };
+        let mut executor = AiExecutor::with_tasks_dir(LlmService::new(config).unwrap(), tasks_dir.path())
+            .with_concurrency(3);
+        let snapshot = Snapshot::new(crate::snapshot::format::SnapshotMetadata::new("test".to_string()));
+
+        let findings = executor.analyze_all(&snapshot).await.unwrap();
+
+        let titles: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(titles, vec!["Alpha issue", "Delta issue", "Gamma issue"]);
+        assert_eq!(findings[0].id, "alpha-001");
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+        failing.assert_async().await;
+    }
+
+    fn topics_task(max_topics: usize) -> AnalysisTask {
+        serde_yaml::from_str(&format!(
+            "id: topics\nname: Topics\ndescription: Topic review\nprompt: \"Topics: {{topics}}\"\nmax_topics: {}\n",
+            max_topics
+        )).unwrap()
+    }
+
+    fn snapshot_with_topics(count: usize) -> Snapshot {
+        let topics: Vec<Value> = (0..count)
+            .map(|i| json!({
+                "name": format!("topic-{:05}", i),
+                "partitions": vec![json!({"id": 0}); 1 + i % 7],
+                "replication_factor": 3,
+                "is_internal": false,
+            }))
+            .collect();
+        let mut snapshot = Snapshot::new(crate::snapshot::format::SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.admin = Some(json!({"brokers": [], "topics": topics}));
+        snapshot
+    }
+
+    #[test]
+    fn test_large_topic_list_is_sampled() {
+        let executor = executor("http://localhost".to_string());
+        let snapshot = snapshot_with_topics(10_000);
+
+        let prompt = executor.fitted_prompt(&topics_task(100), &snapshot, "the cluster").unwrap();
+        assert!(prompt.contains("The cluster has 10000 topics; only a sample of 100 is included"));
+        assert_eq!(prompt.matches("\"name\": \"topic-").count(), 100);
+        assert!(prompt.len() < 50_000, "prompt is {} bytes", prompt.len());
+
+        // The largest topics by partition count are always part of the sample
+        let data = executor.prepare_data(&topics_task(100), &snapshot).unwrap();
+        let sampled: Vec<Value> = serde_json::from_str(data["topics"].split_once('\n').unwrap().1).unwrap();
+        let full_partitions = sampled.iter().filter(|t| t["partitions"].as_array().unwrap().len() == 7).count();
+        assert!(full_partitions >= 50);
+
+        // Sampling is stable across runs
+        assert_eq!(prompt, executor.fitted_prompt(&topics_task(100), &snapshot, "the cluster").unwrap());
+    }
+
+    #[test]
+    fn test_small_topic_list_is_sent_in_full() {
+        let executor = executor("http://localhost".to_string());
+        let snapshot = snapshot_with_topics(20);
+
+        let prompt = executor.fitted_prompt(&topics_task(100), &snapshot, "the cluster").unwrap();
+        assert!(!prompt.contains("only a sample"));
+        assert_eq!(prompt.matches("\"name\": \"topic-").count(), 20);
+    }
+
+    fn snapshot_with_large_config(lines: usize) -> Snapshot {
+        let mut snapshot = snapshot_with_topics(20);
+        let properties: Vec<String> = (0..lines).map(|i| format!("custom.property.{:06}=value", i)).collect();
+        snapshot.collectors.config = Some(json!({"broker_1/server.properties": properties.join("\n")}));
+        snapshot
+    }
+
+    fn config_task() -> AnalysisTask {
+        serde_yaml::from_str(
+            "id: config\nname: Config review\ndescription: Config review\nprompt: \"Config: {config}\\nTopics: {topics}\"\n"
+        ).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_prompt_over_context_window_is_rejected() {
+        // gpt-4o: 128k context window minus the default 8000 tokens reserved for the response
+        let executor = executor("http://localhost:1".to_string());
+        let snapshot = snapshot_with_large_config(20_000);
+
+        let error = executor.execute_task(&config_task(), &snapshot).await.unwrap_err().to_string();
+        assert!(error.contains("over the limit of 120000 tokens"), "{}", error);
+        assert!(error.contains("context window of gpt-4o"), "{}", error);
+        assert!(error.contains("The largest data section is {config}"), "{}", error);
+        assert!(error.contains("--truncate-prompts"), "{}", error);
+    }
+
+    #[test]
+    fn test_prompt_over_limit_is_truncated() {
+        let truncating = executor("http://localhost".to_string())
+            .with_max_prompt_tokens(Some(2_000))
+            .with_prompt_truncation(true);
+        let snapshot = snapshot_with_large_config(1_000);
+
+        let prompt = truncating.fitted_prompt(&config_task(), &snapshot, "the cluster").unwrap();
+        assert!(truncating.estimate_tokens(&prompt) <= 2_000);
+        assert!(prompt.contains("custom.property.000000=value"));
+        assert!(prompt.contains(TRUNCATION_NOTE));
+        // Smaller sections are left whole
+        assert_eq!(prompt.matches("\"name\": \"topic-").count(), 20);
+
+        // Without truncation the same prompt fails, naming the limit's source
+        let strict = executor("http://localhost".to_string()).with_max_prompt_tokens(Some(2_000));
+        let error = strict.fitted_prompt(&config_task(), &snapshot, "the cluster").unwrap_err().to_string();
+        assert!(error.contains("set with --max-prompt-tokens"), "{}", error);
+    }
+
+    #[test]
+    fn test_severity_precedence() {
+        let task: AnalysisTask = serde_yaml::from_str(
+            "id: replication\nname: Replication\ndescription: Replication review\nprompt: \"{admin}\"\n\
+             default_severity: low\nseverity_keywords:\n  data loss: medium\n  offline: high\n"
+        ).unwrap();
+        let executor = executor("http://localhost".to_string())
+            .with_severity_keywords(SeverityKeywords::new([("Data Loss".to_string(), Severity::Critical)]));
+        let severity = |finding: Value| executor.json_to_finding(&finding, &task, 0).unwrap().severity;
+
+        // A severity given by the LLM is kept
+        assert_eq!(severity(json!({"title": "Risk of data loss", "severity": "info"})), Severity::Info);
+        // Deployment keywords override the task's keywords for the same text
+        assert_eq!(severity(json!({"title": "Risk of data loss", "description": "2 partitions offline"})), Severity::Critical);
+        // Task keywords apply when no deployment keyword matches
+        assert_eq!(severity(json!({"title": "Partitions offline"})), Severity::High);
+        // Otherwise the task default
+        assert_eq!(severity(json!({"title": "Uneven leaders"})), Severity::Low);
+    }
+}
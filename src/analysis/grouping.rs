@@ -0,0 +1,194 @@
+use crate::snapshot::format::Finding;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::future::Future;
+use tracing::{info, warn};
+
+/// Cosine similarity at or above which two findings are treated as the same issue
+pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.9;
+
+/// Merge findings that describe the same issue in different words. `embed` returns one
+/// vector per text; when it fails, only findings with identical titles are merged.
+pub async fn group_similar<F, Fut>(findings: Vec<Finding>, embed: F, threshold: f32) -> Vec<Finding>
+where
+    F: FnOnce(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<Vec<Vec<f32>>>>,
+{
+    if findings.len() < 2 {
+        return findings;
+    }
+
+    let texts = findings.iter()
+        .map(|f| format!("{}\n{}", f.title, f.description))
+        .collect();
+    let before = findings.len();
+    let grouped = match embed(texts).await {
+        Ok(embeddings) if embeddings.len() == findings.len() => group_by_embeddings(findings, &embeddings, threshold),
+        Ok(embeddings) => {
+            warn!("Got {} embeddings for {} findings, merging identical titles only", embeddings.len(), findings.len());
+            dedupe_exact(findings)
+        }
+        Err(e) => {
+            warn!("Embeddings unavailable, merging identical titles only: {}", e);
+            dedupe_exact(findings)
+        }
+    };
+    info!("Grouped {} findings into {}", before, grouped.len());
+    grouped
+}
+
+/// Merge findings of the same category whose titles match ignoring case and whitespace
+pub fn dedupe_exact(findings: Vec<Finding>) -> Vec<Finding> {
+    let mut groups: Vec<Vec<Finding>> = Vec::new();
+    let mut index: HashMap<(String, String), usize> = HashMap::new();
+
+    for finding in findings {
+        let key = (format!("{:?}", finding.category), normalize(&finding.title));
+        match index.get(&key) {
+            Some(&group) => groups[group].push(finding),
+            None => {
+                index.insert(key, groups.len());
+                groups.push(vec![finding]);
+            }
+        }
+    }
+
+    groups.into_iter().map(merge).collect()
+}
+
+/// Greedily assign each finding, most severe first, to the first group whose leading
+/// finding is at least `threshold` similar, and merge every group
+fn group_by_embeddings(findings: Vec<Finding>, embeddings: &[Vec<f32>], threshold: f32) -> Vec<Finding> {
+    let mut order: Vec<usize> = (0..findings.len()).collect();
+    order.sort_by_key(|&i| findings[i].severity);
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for i in order {
+        match groups.iter_mut().find(|group| cosine_similarity(&embeddings[group[0]], &embeddings[i]) >= threshold) {
+            Some(group) => group.push(i),
+            None => groups.push(vec![i]),
+        }
+    }
+
+    let mut findings: Vec<Option<Finding>> = findings.into_iter().map(Some).collect();
+    groups.into_iter()
+        .map(|group| merge(group.into_iter().filter_map(|i| findings[i].take()).collect()))
+        .collect()
+}
+
+/// The first finding of a group, with the evidence of all and the ids and titles of the
+/// others in its `merged_findings` metadata
+fn merge(group: Vec<Finding>) -> Finding {
+    let mut group = group.into_iter();
+    let mut representative = group.next().expect("groups are never empty");
+
+    let mut merged = Vec::new();
+    for finding in group {
+        representative.severity = representative.severity.min(finding.severity);
+        representative.evidence.metrics.extend(finding.evidence.metrics);
+        representative.evidence.logs.extend(finding.evidence.logs);
+        representative.evidence.configs.extend(finding.evidence.configs);
+        merged.push(serde_json::json!({"id": finding.id, "title": finding.title}));
+    }
+    if !merged.is_empty() {
+        representative.metadata.insert("merged_findings".to_string(), serde_json::Value::Array(merged));
+    }
+    representative
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 || a.len() != b.len() {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::{Category, LogEvidence, Severity};
+
+    fn finding(id: &str, title: &str, severity: Severity) -> Finding {
+        Finding {
+            id: id.to_string(),
+            title: title.to_string(),
+            severity,
+            category: Category::Availability,
+            ..Default::default()
+        }
+    }
+
+    fn with_log(mut finding: Finding, message: &str) -> Finding {
+        finding.evidence.logs.push(LogEvidence {
+            level: "ERROR".to_string(),
+            message: message.to_string(),
+            source_file: "server.log".to_string(),
+            line_number: None,
+            timestamp: String::new(),
+            count: 1,
+        });
+        finding
+    }
+
+    /// Embeds a text by the topic it is about, standing in for the provider's model
+    async fn fake_embed(texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter()
+            .map(|text| {
+                let text = text.to_lowercase();
+                if text.contains("isr") || text.contains("replica") {
+                    vec![1.0, 0.1, 0.0]
+                } else if text.contains("disk") {
+                    vec![0.0, 1.0, 0.1]
+                } else {
+                    vec![0.1, 0.0, 1.0]
+                }
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_paraphrased_findings_are_grouped() {
+        let findings = vec![
+            with_log(finding("replication-001", "Partitions below min ISR", Severity::Medium), "Shrinking ISR for orders-0"),
+            finding("disk-001", "Disk almost full on broker 2", Severity::High),
+            with_log(finding("replication-002", "Under-replicated partitions on broker 1", Severity::High), "Shrinking ISR for orders-3"),
+            finding("leaders-001", "Uneven leader distribution", Severity::Low),
+        ];
+
+        let grouped = group_similar(findings, fake_embed, DEFAULT_SIMILARITY_THRESHOLD).await;
+
+        let ids: Vec<&str> = grouped.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["disk-001", "replication-002", "leaders-001"]);
+        let replication = &grouped[1];
+        assert_eq!(replication.severity, Severity::High);
+        assert_eq!(replication.evidence.logs.len(), 2);
+        assert_eq!(replication.metadata["merged_findings"][0]["id"], "replication-001");
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_exact_dedupe() {
+        let findings = vec![
+            finding("replication-001", "Under-replicated  partitions", Severity::Medium),
+            finding("replication-002", "under-replicated partitions", Severity::High),
+            finding("replication-003", "Partitions below min ISR", Severity::High),
+        ];
+
+        let grouped = group_similar(
+            findings,
+            |_| async { Err(anyhow::anyhow!("embeddings not supported")) },
+            DEFAULT_SIMILARITY_THRESHOLD,
+        ).await;
+
+        let ids: Vec<&str> = grouped.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["replication-001", "replication-003"]);
+        assert_eq!(grouped[0].severity, Severity::High);
+    }
+}
@@ -1,5 +1,9 @@
 pub mod task;
 pub mod executor;
+pub mod grouping;
+pub mod severity;
+pub mod timeline;
 
 pub use task::{AnalysisTask, TaskLoader};
 pub use executor::AiExecutor;
+pub use severity::SeverityKeywords;
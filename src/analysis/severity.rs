@@ -0,0 +1,85 @@
+use crate::snapshot::format::Severity;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Environment variable with the path of a YAML file of deployment-wide severity keywords
+pub const SEVERITY_KEYWORDS_FILE_ENV: &str = "KCPILOT_SEVERITY_KEYWORDS_FILE";
+
+#[derive(Debug, Deserialize)]
+struct SeverityKeywordsFile {
+    keywords: BTreeMap<String, Severity>,
+}
+
+/// Keywords that set the severity of AI findings across all tasks. They take precedence
+/// over a task's own `severity_keywords`, but not over a severity the LLM gave explicitly.
+#[derive(Debug, Clone, Default)]
+pub struct SeverityKeywords {
+    /// Lowercased keyword and the severity it sets
+    keywords: Vec<(String, Severity)>,
+}
+
+impl SeverityKeywords {
+    pub fn new(keywords: impl IntoIterator<Item = (String, Severity)>) -> Self {
+        Self {
+            keywords: keywords.into_iter()
+                .map(|(keyword, severity)| (keyword.to_lowercase(), severity))
+                .collect(),
+        }
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let file: SeverityKeywordsFile = serde_yaml::from_str(yaml)
+            .context("Invalid severity keywords")?;
+        Ok(Self::new(file.keywords))
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let yaml = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read severity keywords {}", path.display()))?;
+        Self::from_yaml(&yaml)
+    }
+
+    /// Load the keywords file named by `KCPILOT_SEVERITY_KEYWORDS_FILE`, if set
+    pub fn from_env() -> Result<Option<Self>> {
+        std::env::var(SEVERITY_KEYWORDS_FILE_ENV).ok()
+            .map(|path| Self::from_file(Path::new(&path)))
+            .transpose()
+    }
+
+    /// Most severe level among the keywords found in `text`
+    pub fn severity_for(&self, text: &str) -> Option<Severity> {
+        let text = text.to_lowercase();
+        self.keywords.iter()
+            .filter(|(keyword, _)| text.contains(keyword.as_str()))
+            .map(|(_, severity)| *severity)
+            .min()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keywords.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keywords.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_severe_matching_keyword_wins() {
+        let keywords = SeverityKeywords::from_yaml(
+            "keywords:\n  data loss: critical\n  under-replicated: high\n  deprecated: low\n"
+        ).unwrap();
+
+        assert_eq!(keywords.len(), 3);
+        assert_eq!(keywords.severity_for("Possible DATA LOSS on under-replicated partitions"), Some(Severity::Critical));
+        assert_eq!(keywords.severity_for("Deprecated setting"), Some(Severity::Low));
+        assert_eq!(keywords.severity_for("All good"), None);
+        assert!(SeverityKeywords::from_yaml("keywords:\n  data loss: severe\n").is_err());
+    }
+}
@@ -56,6 +56,10 @@ pub struct AnalysisTask {
     /// Maximum tokens to use per request (default: 100000 to leave room for response)
     #[serde(default = "default_max_tokens")]
     pub max_tokens_per_request: usize,
+
+    /// Maximum number of topics sent in {admin}/{topics}; larger clusters get a sample (default: 500)
+    #[serde(default = "default_max_topics")]
+    pub max_topics: usize,
 }
 
 fn default_severity() -> String {
@@ -74,6 +78,10 @@ fn default_max_tokens() -> usize {
     100000  // Leave room for response tokens
 }
 
+fn default_max_topics() -> usize {
+    500
+}
+
 /// Loads tasks from YAML files
 pub struct TaskLoader {
     tasks_dir: PathBuf,
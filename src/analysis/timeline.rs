@@ -0,0 +1,227 @@
+use crate::snapshot::format::{Finding, Severity, Snapshot, TimelineEvent};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Minutes of log events kept on either side of the issue
+pub const TIMELINE_WINDOW_MINUTES: i64 = 30;
+
+/// Most events kept in a timeline, the latest ones
+pub const MAX_TIMELINE_EVENTS: usize = 50;
+
+fn timestamp_regexes() -> &'static [Regex; 2] {
+    static REGEXES: OnceLock<[Regex; 2]> = OnceLock::new();
+    REGEXES.get_or_init(|| [
+        // log4j "[2024-01-15 10:00:00,123]", ISO 8601 "2024-01-15T10:00:00.123+02:00" and GC "[2024-01-15T10:00:00.123+0000]"
+        Regex::new(r"^\[?(\d{4}-\d{2}-\d{2})[ T](\d{2}:\d{2}:\d{2})(?:[.,](\d{1,9}))?(Z|[+-]\d{2}:?\d{2})?\]?\s*")
+            .expect("valid regex"),
+        // syslog and journald "Jan 15 10:00:00 host kafka[123]:", without a year
+        Regex::new(r"^([A-Z][a-z]{2}) +(\d{1,2}) (\d{2}:\d{2}:\d{2})\s+").expect("valid regex"),
+    ])
+}
+
+fn relevant_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"(?i)\b(WARN|WARNING|ERROR|FATAL)\b|shutting down|shut down|started|elect|shrinking isr|expanding isr")
+            .expect("valid regex")
+    })
+}
+
+/// Timestamp at the start of a log line and the rest of the line. Timestamps without an
+/// offset are taken as UTC; syslog timestamps, which have no year, get `year`.
+pub fn parse_log_timestamp(line: &str, year: i32) -> Option<(DateTime<Utc>, &str)> {
+    let [iso, syslog] = timestamp_regexes();
+
+    if let Some(caps) = iso.captures(line) {
+        let date = NaiveDate::parse_from_str(&caps[1], "%Y-%m-%d").ok()?;
+        let time = NaiveTime::parse_from_str(&caps[2], "%H:%M:%S").ok()?;
+        let nanos = caps.get(3).map_or(0, |fraction| {
+            let digits = fraction.as_str();
+            digits.parse::<u32>().unwrap_or(0) * 10u32.pow(9 - digits.len() as u32)
+        });
+        let local = NaiveDateTime::new(date, time) + Duration::nanoseconds(nanos as i64);
+        let timestamp = match caps.get(4).map(|offset| offset.as_str()) {
+            None | Some("Z") => local.and_utc(),
+            Some(offset) => parse_offset(offset)?.from_local_datetime(&local).single()?.with_timezone(&Utc),
+        };
+        return Some((timestamp, &line[caps[0].len()..]));
+    }
+
+    let caps = syslog.captures(line)?;
+    let stamp = format!("{} {} {} {}", year, &caps[1], &caps[2], &caps[3]);
+    let timestamp = NaiveDateTime::parse_from_str(&stamp, "%Y %b %d %H:%M:%S").ok()?.and_utc();
+    Some((timestamp, &line[caps[0].len()..]))
+}
+
+/// `+02:00`, `+0200` or `-05:00` as an offset
+fn parse_offset(offset: &str) -> Option<FixedOffset> {
+    let digits = offset[1..].replace(':', "");
+    let hours: i32 = digits.get(..2)?.parse().ok()?;
+    let minutes: i32 = digits.get(2..)?.parse().ok()?;
+    let seconds = hours * 3600 + minutes * 60;
+    if offset.starts_with('-') {
+        FixedOffset::west_opt(seconds)
+    } else {
+        FixedOffset::east_opt(seconds)
+    }
+}
+
+/// Warnings, errors and lifecycle events (startup, shutdown, elections, ISR changes) of the
+/// given `(source, content)` logs within `TIMELINE_WINDOW_MINUTES` of `anchor`, or of the
+/// latest event when there is no anchor, ordered by time. Lines without a timestamp, such
+/// as stack traces, are left out.
+pub fn build_timeline(logs: &[(String, String)], anchor: Option<DateTime<Utc>>, year: i32) -> Vec<TimelineEvent> {
+    let mut events: Vec<TimelineEvent> = logs.iter()
+        .flat_map(|(source, content)| content.lines().filter_map(move |line| {
+            let (timestamp, message) = parse_log_timestamp(line.trim_start(), year)?;
+            relevant_regex().is_match(message).then(|| TimelineEvent {
+                timestamp,
+                source: source.clone(),
+                message: message.trim().to_string(),
+            })
+        }))
+        .collect();
+
+    let Some(anchor) = anchor.or_else(|| events.iter().map(|event| event.timestamp).max()) else {
+        return Vec::new();
+    };
+    let window = Duration::minutes(TIMELINE_WINDOW_MINUTES);
+    events.retain(|event| (event.timestamp - anchor).abs() <= window);
+
+    // Stable, so events with the same timestamp keep their order within a file
+    events.sort_by_key(|event| event.timestamp);
+    let skip = events.len().saturating_sub(MAX_TIMELINE_EVENTS);
+    events.drain(..skip);
+    events
+}
+
+/// Collected log contents by log name: raw files of a scan directory, or the parsed
+/// entries of the log collector
+pub fn log_contents(snapshot: &Snapshot) -> Vec<(String, String)> {
+    let Some(logs) = snapshot.collectors.logs.as_ref().and_then(|logs| logs.as_object()) else {
+        return Vec::new();
+    };
+
+    let mut contents = Vec::new();
+    for (name, value) in logs {
+        match value {
+            serde_json::Value::String(content) => contents.push((name.clone(), content.clone())),
+            serde_json::Value::Object(files) if name == "logs" => {
+                for (file, entries) in files {
+                    let lines: Vec<&str> = entries.as_array().into_iter().flatten()
+                        .filter_map(|entry| entry.get("raw").and_then(|raw| raw.as_str()))
+                        .collect();
+                    contents.push((file.clone(), lines.join("\n")));
+                }
+            }
+            _ => {}
+        }
+    }
+    contents
+}
+
+/// Attach a timeline of the collected logs to every critical finding that has none,
+/// centered on the latest timestamp of its log evidence. Returns how many were attached.
+pub fn attach_timelines(findings: &mut [Finding], snapshot: &Snapshot) -> usize {
+    let logs = log_contents(snapshot);
+    if logs.is_empty() {
+        return 0;
+    }
+
+    let year = snapshot.timestamp.year();
+    let mut attached = 0;
+    for finding in findings.iter_mut().filter(|f| f.severity == Severity::Critical && f.evidence.timeline.is_empty()) {
+        let anchor = finding.evidence.logs.iter()
+            .filter_map(|log| parse_log_timestamp(&log.timestamp, year))
+            .map(|(timestamp, _)| timestamp)
+            .max();
+        finding.evidence.timeline = build_timeline(&logs, anchor, year);
+        if !finding.evidence.timeline.is_empty() {
+            attached += 1;
+        }
+    }
+    attached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::{Category, LogEvidence, SnapshotMetadata};
+
+    fn at(hour: u32, minute: u32, second: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 1, hour, minute, second).unwrap()
+    }
+
+    #[test]
+    fn test_parse_log_timestamp_formats() {
+        let parse = |line| parse_log_timestamp(line, 2024).map(|(timestamp, rest)| (timestamp, rest.to_string()));
+
+        assert_eq!(parse("[2024-06-01 10:00:00,250] ERROR boom"), Some((at(10, 0, 0) + Duration::milliseconds(250), "ERROR boom".to_string())));
+        assert_eq!(parse("2024-06-01T12:00:00.5+02:00 WARN skew").unwrap().0, at(10, 0, 0) + Duration::milliseconds(500));
+        assert_eq!(parse("[2024-06-01T10:00:00.000+0000][gc] Pause").unwrap().0, at(10, 0, 0));
+        assert_eq!(parse("Jun  1 10:00:00 kafka-1 kafka[42]: shutting down").unwrap().0, at(10, 0, 0));
+        assert_eq!(parse("\tat kafka.Foo.bar(Foo.scala:1)"), None);
+    }
+
+    #[test]
+    fn test_timeline_from_interleaved_logs() {
+        let logs = vec![
+            ("broker_1/server.log".to_string(), "\
+[2024-06-01 09:00:00,000] ERROR long before the incident (kafka.server)
+[2024-06-01 10:00:00,000] INFO Registered broker 1 (kafka.server)
+[2024-06-01 10:00:05,000] WARN [ReplicaFetcher] Error in fetch from broker 2 (kafka.server)
+java.io.IOException: Connection to 2 was disconnected
+\tat kafka.Foo.bar(Foo.scala:1)
+[2024-06-01 10:00:20,000] INFO [Partition orders-0] Shrinking ISR from 1,2 to 1 (kafka.cluster.Partition)
+".to_string()),
+            ("broker_2/journald.log".to_string(), "\
+Jun  1 10:00:01 kafka-2 kafka[42]: [KafkaServer id=2] shutting down
+Jun  1 10:00:10 kafka-2 systemd[1]: kafka.service: Main process exited, status=137
+".to_string()),
+            ("broker_3/server.log".to_string(), "2024-06-01T12:00:15+02:00 ERROR Controller 3 lost leadership\n".to_string()),
+        ];
+
+        let timeline = build_timeline(&logs, Some(at(10, 0, 10)), 2024);
+
+        let summary: Vec<(DateTime<Utc>, &str)> = timeline.iter()
+            .map(|event| (event.timestamp, event.source.as_str()))
+            .collect();
+        assert_eq!(summary, vec![
+            (at(10, 0, 1), "broker_2/journald.log"),
+            (at(10, 0, 5), "broker_1/server.log"),
+            (at(10, 0, 15), "broker_3/server.log"),
+            (at(10, 0, 20), "broker_1/server.log"),
+        ]);
+        assert_eq!(timeline[0].message, "kafka-2 kafka[42]: [KafkaServer id=2] shutting down");
+        assert!(timeline[1].message.starts_with("WARN [ReplicaFetcher]"));
+    }
+
+    #[test]
+    fn test_attach_timelines_to_critical_findings() {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.logs = Some(serde_json::json!({
+            "broker_1/server.log": "[2024-06-01 10:00:00,000] ERROR Disk failure on /var/lib/kafka\n",
+        }));
+        let finding = |severity| Finding {
+            id: "disk-001".to_string(),
+            severity,
+            category: Category::Availability,
+            ..Default::default()
+        };
+        let mut critical = finding(Severity::Critical);
+        critical.evidence.logs.push(LogEvidence {
+            level: "ERROR".to_string(),
+            message: "Disk failure".to_string(),
+            source_file: "server.log".to_string(),
+            line_number: None,
+            timestamp: "2024-06-01T10:05:00Z".to_string(),
+            count: 1,
+        });
+        let mut findings = vec![critical, finding(Severity::High)];
+
+        assert_eq!(attach_timelines(&mut findings, &snapshot), 1);
+        assert_eq!(findings[0].evidence.timeline[0].timestamp, at(10, 0, 0));
+        assert!(findings[1].evidence.timeline.is_empty());
+    }
+}
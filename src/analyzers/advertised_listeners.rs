@@ -0,0 +1,387 @@
+use crate::analyzers::{server_properties, Analyzer, AnalyzerResult};
+use crate::scan::listeners::{format_address, parse_listeners};
+use crate::scan::types::ListenerInfo;
+use crate::snapshot::format::{
+    Category, ConfigEvidence, Evidence, Finding, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::{BTreeSet, HashMap};
+use std::net::{IpAddr, ToSocketAddrs};
+
+/// Listener Kafka binds, and advertises, when neither `listeners` nor `advertised.listeners` is set
+const DEFAULT_LISTENERS: &str = "PLAINTEXT://:9092";
+
+/// Domain suffixes that only resolve inside a private network
+const INTERNAL_SUFFIXES: [&str; 6] = [".local", ".localdomain", ".internal", ".lan", ".svc", ".cluster.local"];
+
+/// Resolve a hostname to its addresses, `None` when the lookup fails
+pub type Resolver = fn(&str) -> Option<Vec<IpAddr>>;
+
+fn resolve_host(host: &str) -> Option<Vec<IpAddr>> {
+    let addresses: Vec<IpAddr> = (host, 0).to_socket_addrs().ok()?.map(|addr| addr.ip()).collect();
+    (!addresses.is_empty()).then_some(addresses)
+}
+
+fn is_loopback(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Whether clients outside the cluster's private network are unlikely to reach `host`:
+/// private or link-local addresses, single-label names and internal DNS zones
+fn is_internal_only(host: &str) -> bool {
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => ip.is_private() || ip.is_link_local(),
+        // Unique local fc00::/7 and link-local fe80::/10
+        Ok(IpAddr::V6(ip)) => (ip.segments()[0] & 0xfe00) == 0xfc00 || (ip.segments()[0] & 0xffc0) == 0xfe80,
+        Err(_) => {
+            let host = host.to_lowercase();
+            !host.contains('.') || INTERNAL_SUFFIXES.iter().any(|suffix| host.ends_with(suffix))
+        }
+    }
+}
+
+/// Hostname the scan reached each broker at, keyed by broker directory (`broker_1`)
+fn discovered_hostnames(snapshot: &Snapshot) -> HashMap<String, String> {
+    snapshot.collectors.custom.get("brokers")
+        .and_then(|brokers| brokers.as_object())
+        .map(|brokers| {
+            brokers.iter()
+                .filter_map(|(dir, broker)| {
+                    let hostname = broker.get("broker_info.json")?.get("hostname")?.as_str()?;
+                    Some((dir.clone(), hostname.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Listeners the broker hands out to clients in metadata responses, without controller listeners
+fn advertised_listeners(props: &HashMap<String, String>) -> Vec<ListenerInfo> {
+    let value = props.get("advertised.listeners")
+        .or_else(|| props.get("listeners"))
+        .map(String::as_str)
+        .unwrap_or(DEFAULT_LISTENERS);
+    let controllers: Vec<String> = props.get("controller.listener.names")
+        .map(|names| names.split(',').map(|name| name.trim().to_uppercase()).collect())
+        .unwrap_or_default();
+
+    parse_listeners(value).into_iter()
+        .filter(|listener| !controllers.contains(&listener.name.to_uppercase()))
+        .collect()
+}
+
+/// A problem with one advertised listener
+struct ListenerProblem {
+    file: String,
+    listener: ListenerInfo,
+    reason: String,
+    /// Whether DNS confirmed the problem, or it could not be tested from here
+    confirmed: bool,
+}
+
+/// Flags advertised listeners clients cannot connect to: meta-addresses, loopback,
+/// internal-only and unresolvable hosts, and hosts that are not the broker
+pub struct AdvertisedListenersAnalyzer {
+    resolver: Resolver,
+}
+
+impl Default for AdvertisedListenersAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AdvertisedListenersAnalyzer {
+    pub fn new() -> Self {
+        Self { resolver: resolve_host }
+    }
+
+    /// Resolve hostnames with `resolver` instead of the system resolver
+    pub fn with_resolver(mut self, resolver: Resolver) -> Self {
+        self.resolver = resolver;
+        self
+    }
+
+    fn addresses(&self, host: &str) -> Option<Vec<IpAddr>> {
+        match host.parse::<IpAddr>() {
+            Ok(ip) => Some(vec![ip]),
+            Err(_) => (self.resolver)(host),
+        }
+    }
+
+    /// Check one advertised listener against the hostname the broker was reached at
+    fn check(&self, listener: &ListenerInfo, discovered: Option<&str>) -> Option<(String, bool)> {
+        let host = listener.host.as_str();
+
+        // An empty host advertises the broker's canonical hostname
+        if host.is_empty() {
+            return None;
+        }
+        if host.parse::<IpAddr>().is_ok_and(|ip| ip.is_unspecified()) {
+            return Some((format!("advertises the non-routable meta-address {}", host), true));
+        }
+        if is_loopback(host) {
+            return Some(("advertises a loopback address, so remote clients connect to themselves".to_string(), true));
+        }
+
+        let Some(discovered) = discovered else {
+            return match self.addresses(host) {
+                Some(_) => None,
+                None => Some((format!("{} could not be resolved from the analysis host", host), false)),
+            };
+        };
+        if host.eq_ignore_ascii_case(discovered) {
+            return None;
+        }
+        if is_internal_only(host) && !is_internal_only(discovered) {
+            return Some((
+                format!("advertises internal-only address {} although the broker is reached at {}", host, discovered),
+                true,
+            ));
+        }
+
+        // The reachable hostname resolving shows that DNS works from here
+        match (self.addresses(host), self.addresses(discovered)) {
+            (Some(advertised), Some(reachable)) if advertised.iter().any(|ip| reachable.contains(ip)) => None,
+            (Some(advertised), Some(reachable)) => Some((
+                format!(
+                    "{} resolves to {} but the broker is reached at {} ({})",
+                    host, join_ips(&advertised), discovered, join_ips(&reachable)
+                ),
+                true,
+            )),
+            (None, Some(_)) => Some((format!("{} does not resolve, although {} does", host, discovered), true)),
+            (_, None) => Some((
+                format!("{} differs from the hostname the broker is reached at ({}); DNS could not be checked from the analysis host", host, discovered),
+                false,
+            )),
+        }
+    }
+
+    fn finding(title: String, description: String, severity: Severity, problems: &[ListenerProblem]) -> Finding {
+        let steps = [
+            (
+                "Set advertised.listeners to a hostname or address clients can resolve and reach, one entry per listener",
+                None,
+            ),
+            (
+                "Check what clients are told by requesting metadata from outside the broker's network",
+                Some("kafka-broker-api-versions.sh --bootstrap-server <advertised host>:<port>"),
+            ),
+            (
+                "Roll the brokers one at a time to apply the new advertised.listeners",
+                None,
+            ),
+        ];
+
+        Finding {
+            id: String::new(),
+            title,
+            description,
+            severity,
+            category: Category::Client,
+            impact: "Clients bootstrap successfully but then fail to connect to partition leaders, because metadata points them at the advertised addresses".to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs: problems.iter().map(|problem| ConfigEvidence {
+                    resource_type: "broker".to_string(),
+                    resource_name: problem.file.split('/').next().unwrap_or(&problem.file).to_string(),
+                    config_key: "advertised.listeners".to_string(),
+                    current_value: format!("{}://{}", problem.listener.name, format_address(&problem.listener.host, problem.listener.port)),
+                    recommended_value: Some("a hostname clients can resolve and reach".to_string()),
+                    reason: problem.reason.clone(),
+                    source_files: vec![problem.file.clone()],
+                }).collect(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("advertised.listeners left at its default, copied from another broker, or set to an address only valid inside the broker's network".to_string()),
+            remediation: Remediation {
+                steps: steps.iter().enumerate().map(|(i, (description, command))| RemediationStep {
+                    order: i as u32 + 1,
+                    description: description.to_string(),
+                    command: command.map(str::to_string),
+                    verification: Some("Clients produce and consume without connection errors to individual brokers".to_string()),
+                    can_automate: false,
+                }).collect(),
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(60),
+                rollback_plan: Some("Restore the previous advertised.listeners and restart the broker".to_string()),
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+fn join_ips(addresses: &[IpAddr]) -> String {
+    addresses.iter().map(IpAddr::to_string).collect::<Vec<_>>().join(", ")
+}
+
+fn broker_count(problems: &[ListenerProblem]) -> usize {
+    problems.iter().map(|p| p.file.as_str()).collect::<BTreeSet<_>>().len()
+}
+
+#[async_trait]
+impl Analyzer for AdvertisedListenersAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let discovered = discovered_hostnames(snapshot);
+        let mut problems = Vec::new();
+
+        for (file, props) in server_properties(snapshot) {
+            let broker_dir = file.split('/').next().unwrap_or(&file);
+            let reachable = discovered.get(broker_dir).map(String::as_str);
+            for listener in advertised_listeners(&props) {
+                if let Some((reason, confirmed)) = self.check(&listener, reachable) {
+                    problems.push(ListenerProblem { file: file.clone(), listener, reason, confirmed });
+                }
+            }
+        }
+
+        let (confirmed, unverified): (Vec<_>, Vec<_>) = problems.into_iter().partition(|p| p.confirmed);
+        let mut findings = Vec::new();
+
+        if !confirmed.is_empty() {
+            findings.push(Self::finding(
+                format!("Unreachable advertised listeners on {} broker(s)", broker_count(&confirmed)),
+                format!(
+                    "{} advertised listener(s) point clients at addresses they cannot connect to: {}.",
+                    confirmed.len(),
+                    confirmed.iter().map(|p| format!("{} {}", p.listener.name, p.reason)).collect::<Vec<_>>().join("; ")
+                ),
+                Severity::High,
+                &confirmed,
+            ));
+        }
+
+        if !unverified.is_empty() {
+            findings.push(Self::finding(
+                format!("Advertised listeners could not be verified on {} broker(s)", broker_count(&unverified)),
+                format!(
+                    "{} advertised listener(s) may not be reachable by clients: {}. Name resolution could not be \
+                    tested from where the analysis ran, so check these addresses from a client host.",
+                    unverified.len(),
+                    unverified.iter().map(|p| format!("{} {}", p.listener.name, p.reason)).collect::<Vec<_>>().join("; ")
+                ),
+                Severity::Medium,
+                &unverified,
+            ));
+        }
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("LISTENER-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Advertised Listeners Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags advertised listeners that point clients at meta, loopback, internal-only or unresolvable addresses"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    /// Resolver of a small private zone, standing in for DNS
+    fn zone(host: &str) -> Option<Vec<IpAddr>> {
+        match host {
+            "kafka-1.example.com" => Some(vec!["203.0.113.11".parse().unwrap()]),
+            "kafka-2.example.com" => Some(vec!["203.0.113.12".parse().unwrap()]),
+            "kafka-1.ec2.internal" => Some(vec!["10.0.0.11".parse().unwrap()]),
+            "kafka.example.com" => Some(vec!["203.0.113.11".parse().unwrap(), "203.0.113.12".parse().unwrap()]),
+            _ => None,
+        }
+    }
+
+    fn no_dns(_: &str) -> Option<Vec<IpAddr>> {
+        None
+    }
+
+    fn snapshot(config: &str, hostname: Option<&str>) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.config = Some(json!({
+            "broker_1/server.properties": format!("broker.id=1\n{}", config),
+        }));
+        if let Some(hostname) = hostname {
+            snapshot.collectors.custom.insert("brokers".to_string(), json!({
+                "broker_1": {"broker_info.json": {"id": 1, "hostname": hostname}}
+            }));
+        }
+        snapshot
+    }
+
+    async fn analyze(resolver: Resolver, config: &str, hostname: Option<&str>) -> Vec<Finding> {
+        AdvertisedListenersAnalyzer::new()
+            .with_resolver(resolver)
+            .analyze(&snapshot(config, hostname))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_meta_and_loopback_addresses_flagged() {
+        let findings = analyze(zone, "advertised.listeners=PLAINTEXT://0.0.0.0:9092,SSL://localhost:9093\n", None).await;
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].id, "LISTENER-001");
+        assert_eq!(findings[0].severity, Severity::High);
+        assert!(matches!(findings[0].category, Category::Client));
+        let values: Vec<&str> = findings[0].evidence.configs.iter().map(|c| c.current_value.as_str()).collect();
+        assert_eq!(values, vec!["PLAINTEXT://0.0.0.0:9092", "SSL://localhost:9093"]);
+
+        // Without advertised.listeners the bound listeners are advertised
+        let findings = analyze(zone, "listeners=PLAINTEXT://127.0.0.1:9092\n", None).await;
+        assert!(findings[0].evidence.configs[0].reason.contains("loopback"));
+    }
+
+    #[tokio::test]
+    async fn test_internal_only_address_flagged_against_discovered_hostname() {
+        let config = "listeners=PLAINTEXT://:9092\nadvertised.listeners=PLAINTEXT://kafka-1.ec2.internal:9092\n";
+        let findings = analyze(zone, config, Some("kafka-1.example.com")).await;
+        assert_eq!(findings[0].severity, Severity::High);
+        assert!(findings[0].evidence.configs[0].reason.contains("internal-only"));
+
+        // Inside a private network advertising private addresses is expected
+        let config = "advertised.listeners=PLAINTEXT://10.0.0.11:9092\n";
+        assert!(analyze(zone, config, Some("kafka-1.ec2.internal")).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_advertised_host_cross_referenced_with_dns() {
+        // Another broker's hostname, a typical copy-paste mistake
+        let findings = analyze(zone, "advertised.listeners=PLAINTEXT://kafka-2.example.com:9092\n", Some("kafka-1.example.com")).await;
+        assert_eq!(findings[0].severity, Severity::High);
+        assert!(findings[0].evidence.configs[0].reason.contains("resolves to 203.0.113.12"));
+
+        let findings = analyze(zone, "advertised.listeners=PLAINTEXT://kafka-1.exmaple.com:9092\n", Some("kafka-1.example.com")).await;
+        assert!(findings[0].evidence.configs[0].reason.contains("does not resolve"));
+
+        // An alias resolving to the broker's address is fine
+        assert!(analyze(zone, "advertised.listeners=PLAINTEXT://kafka.example.com:9092\n", Some("kafka-1.example.com")).await.is_empty());
+
+        // Controller listeners are not advertised to clients
+        let config = "advertised.listeners=PLAINTEXT://kafka-1.example.com:9092\nlisteners=PLAINTEXT://:9092,CONTROLLER://localhost:9093\ncontroller.listener.names=CONTROLLER\n";
+        assert!(analyze(zone, config, Some("kafka-1.example.com")).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_untestable_resolution_downgraded() {
+        let findings = analyze(no_dns, "advertised.listeners=PLAINTEXT://broker1.example.com:9092\n", Some("kafka-1.example.com")).await;
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert!(findings[0].title.contains("could not be verified"));
+        assert!(findings[0].description.contains("Name resolution could not be tested"));
+    }
+}
@@ -0,0 +1,317 @@
+use crate::analyzers::{server_properties, Analyzer, AnalyzerError, AnalyzerResult};
+use crate::scan::cluster_detection::parse_server_properties;
+use crate::snapshot::format::{
+    Category, ConfigEvidence, Evidence, Finding, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Keys expected to differ between brokers, never reported as drift
+pub const DEFAULT_IGNORED_KEYS: [&str; 5] = [
+    "broker.id",
+    "node.id",
+    "advertised.listeners",
+    "listeners",
+    "broker.rack",
+];
+
+/// How a broker's setting differs from the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Drift {
+    Changed,
+    Missing,
+    Extra,
+}
+
+/// Diffs every broker's server.properties against a golden baseline config
+pub struct BaselineConfigAnalyzer {
+    baseline: HashMap<String, String>,
+    baseline_source: String,
+    ignored_keys: HashSet<String>,
+}
+
+impl BaselineConfigAnalyzer {
+    pub fn new(baseline: HashMap<String, String>, baseline_source: impl Into<String>) -> Self {
+        Self {
+            baseline,
+            baseline_source: baseline_source.into(),
+            ignored_keys: DEFAULT_IGNORED_KEYS.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    /// Load the baseline from a server.properties file
+    pub fn from_file(path: &Path) -> AnalyzerResult<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AnalyzerError::InvalidData(format!("Failed to read baseline {}: {}", path.display(), e)))?;
+        let baseline = parse_server_properties(&content);
+        if baseline.is_empty() {
+            return Err(AnalyzerError::InvalidData(format!("Baseline {} has no properties", path.display())));
+        }
+        Ok(Self::new(baseline, path.display().to_string()))
+    }
+
+    /// Also ignore these keys, in addition to the per-broker defaults
+    pub fn with_ignored_keys(mut self, keys: impl IntoIterator<Item = String>) -> Self {
+        self.ignored_keys.extend(keys);
+        self
+    }
+
+    /// Every setting of one broker that drifts from the baseline, with its actual value
+    fn diff<'a>(&'a self, props: &'a HashMap<String, String>) -> Vec<(Drift, &'a str, Option<&'a str>)> {
+        let mut drift = Vec::new();
+
+        for (key, expected) in &self.baseline {
+            if self.ignored_keys.contains(key) {
+                continue;
+            }
+            match props.get(key) {
+                None => drift.push((Drift::Missing, key.as_str(), None)),
+                Some(actual) if actual != expected => drift.push((Drift::Changed, key.as_str(), Some(actual.as_str()))),
+                Some(_) => {}
+            }
+        }
+
+        for (key, actual) in props {
+            if !self.baseline.contains_key(key) && !self.ignored_keys.contains(key) {
+                drift.push((Drift::Extra, key.as_str(), Some(actual.as_str())));
+            }
+        }
+
+        drift
+    }
+
+    fn finding(&self, drift: Drift, key: &str, brokers: &[(&str, Option<&str>)], total_brokers: usize) -> Finding {
+        let expected = self.baseline.get(key).map(String::as_str);
+        let broker_names: Vec<&str> = brokers.iter().map(|(file, _)| broker_name(file)).collect();
+
+        let (severity, title, description, reason) = match drift {
+            Drift::Changed => (
+                Severity::Medium,
+                format!("{} differs from the baseline on {} of {} broker(s)", key, brokers.len(), total_brokers),
+                format!(
+                    "{} is {} in the baseline but set differently on {}.",
+                    key, expected.unwrap_or_default(), broker_names.join(", ")
+                ),
+                "Value differs from the baseline configuration",
+            ),
+            Drift::Missing => (
+                Severity::Medium,
+                format!("{} from the baseline is missing on {} of {} broker(s)", key, brokers.len(), total_brokers),
+                format!(
+                    "The baseline sets {}={} but it is not set on {}, so the broker default applies.",
+                    key, expected.unwrap_or_default(), broker_names.join(", ")
+                ),
+                "Key is set in the baseline but not on the broker",
+            ),
+            Drift::Extra => (
+                Severity::Low,
+                format!("{} is set on {} of {} broker(s) but not in the baseline", key, brokers.len(), total_brokers),
+                format!(
+                    "{} is not part of the baseline configuration but is set on {}.",
+                    key, broker_names.join(", ")
+                ),
+                "Key is set on the broker but not in the baseline",
+            ),
+        };
+
+        let configs = brokers.iter().map(|(file, actual)| ConfigEvidence {
+            resource_type: "broker".to_string(),
+            resource_name: broker_name(file).to_string(),
+            config_key: key.to_string(),
+            current_value: actual.unwrap_or("(not set)").to_string(),
+            recommended_value: expected.map(str::to_string),
+            reason: reason.to_string(),
+            source_files: vec![file.to_string(), self.baseline_source.clone()],
+        }).collect();
+
+        let step = match drift {
+            Drift::Changed | Drift::Missing => format!(
+                "Set {}={} in server.properties on the listed brokers, or update the baseline if the change is intended",
+                key, expected.unwrap_or_default()
+            ),
+            Drift::Extra => format!(
+                "Remove {} from server.properties on the listed brokers, or add it to the baseline",
+                key
+            ),
+        };
+
+        Finding {
+            id: String::new(),
+            title,
+            description,
+            severity,
+            category: Category::Configuration,
+            impact: "Brokers that drift from the standard configuration behave inconsistently and make incidents harder to diagnose.".to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs,
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: step,
+                        command: None,
+                        verification: Some(format!(
+                            "kafka-configs.sh --bootstrap-server <broker> --entity-type brokers --entity-name <id> --describe --all | grep {}",
+                            key
+                        )),
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Perform a rolling restart for settings that are not dynamically updatable".to_string(),
+                        command: None,
+                        verification: None,
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(30),
+                rollback_plan: Some("Restore the previous server.properties and restart the broker".to_string()),
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+/// `broker_11/server.properties` -> `broker_11`
+fn broker_name(file: &str) -> &str {
+    file.split('/').next().unwrap_or(file)
+}
+
+#[async_trait]
+impl Analyzer for BaselineConfigAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let configs = server_properties(snapshot);
+
+        let mut drifted: BTreeMap<(Drift, &str), Vec<(&str, Option<&str>)>> = BTreeMap::new();
+        for (file, props) in &configs {
+            for (drift, key, actual) in self.diff(props) {
+                drifted.entry((drift, key)).or_default().push((file.as_str(), actual));
+            }
+        }
+
+        let mut findings: Vec<Finding> = drifted.iter()
+            .map(|((drift, key), brokers)| self.finding(*drift, key, brokers, configs.len()))
+            .collect();
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("BASELINE-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Baseline Config Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Compares each broker's server.properties against a baseline configuration"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    const BASELINE: &str = "# golden config\n\
+        broker.id=0\n\
+        num.network.threads=8\n\
+        min.insync.replicas=2\n\
+        log.retention.hours=168\n\
+        advertised.listeners=PLAINTEXT://template:9092\n";
+
+    fn snapshot(brokers: &[(&str, &str)]) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let config: serde_json::Map<String, serde_json::Value> = brokers.iter()
+            .map(|(name, content)| (format!("{}/server.properties", name), json!(content)))
+            .collect();
+        snapshot.collectors.config = Some(serde_json::Value::Object(config));
+        snapshot
+    }
+
+    fn analyzer() -> BaselineConfigAnalyzer {
+        BaselineConfigAnalyzer::new(parse_server_properties(BASELINE), "baseline.properties")
+    }
+
+    #[tokio::test]
+    async fn test_matching_broker_has_no_drift() {
+        let snapshot = snapshot(&[(
+            "broker_1",
+            "broker.id=1\nnum.network.threads=8\nmin.insync.replicas=2\nlog.retention.hours=168\nadvertised.listeners=PLAINTEXT://kafka-1:9092",
+        )]);
+
+        assert!(analyzer().analyze(&snapshot).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_drifted_broker() {
+        let snapshot = snapshot(&[
+            ("broker_1", "broker.id=1\nnum.network.threads=8\nmin.insync.replicas=2\nlog.retention.hours=168"),
+            ("broker_2", "broker.id=2\nnum.network.threads=3\nlog.retention.hours=168\nauto.create.topics.enable=true"),
+        ]);
+
+        let findings = analyzer().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 3);
+        assert!(findings.iter().all(|f| matches!(f.category, Category::Configuration)));
+
+        let changed = &findings[0];
+        assert_eq!(changed.id, "BASELINE-001");
+        assert_eq!(changed.severity, Severity::Medium);
+        assert!(changed.title.contains("num.network.threads differs from the baseline on 1 of 2"));
+        let evidence = &changed.evidence.configs[0];
+        assert_eq!(evidence.resource_name, "broker_2");
+        assert_eq!(evidence.current_value, "3");
+        assert_eq!(evidence.recommended_value.as_deref(), Some("8"));
+        assert_eq!(evidence.source_files, vec!["broker_2/server.properties", "baseline.properties"]);
+
+        let missing = &findings[1];
+        assert!(missing.title.contains("min.insync.replicas from the baseline is missing"));
+        assert_eq!(missing.evidence.configs[0].current_value, "(not set)");
+
+        let extra = &findings[2];
+        assert_eq!(extra.severity, Severity::Low);
+        assert!(extra.title.contains("auto.create.topics.enable is set on 1 of 2"));
+        assert!(extra.evidence.configs[0].recommended_value.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_custom_ignored_keys() {
+        let snapshot = snapshot(&[(
+            "broker_1",
+            "broker.id=1\nnum.network.threads=16\nmin.insync.replicas=2\nlog.retention.hours=168\nbroker.rack=eu-1a",
+        )]);
+
+        let findings = analyzer()
+            .with_ignored_keys(vec!["num.network.threads".to_string()])
+            .analyze(&snapshot)
+            .await
+            .unwrap();
+
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn test_from_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), BASELINE).unwrap();
+
+        let analyzer = BaselineConfigAnalyzer::from_file(file.path()).unwrap();
+        assert_eq!(analyzer.baseline.get("num.network.threads").map(String::as_str), Some("8"));
+
+        let empty = tempfile::NamedTempFile::new().unwrap();
+        assert!(BaselineConfigAnalyzer::from_file(empty.path()).is_err());
+    }
+}
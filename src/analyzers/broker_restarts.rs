@@ -0,0 +1,239 @@
+use crate::analyzers::{broker_id, Analyzer, AnalyzerResult};
+use crate::collectors::admin::AdminCollectorOutput;
+use crate::scan::process_start::{ProcessStart, PROCESS_START_FILE};
+use crate::snapshot::format::{
+    Category, Evidence, Finding, MetricEvidence, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use chrono::Duration;
+use std::collections::HashMap;
+
+/// Uptime below which a broker counts as recently restarted
+const DEFAULT_RECENT_RESTART_MINUTES: i64 = 60;
+
+/// Uptime as the largest two units, e.g. `42 minute(s)` or `3 hour(s) 5 minute(s)`
+fn describe_uptime(uptime: Duration) -> String {
+    let (days, hours, minutes) = (uptime.num_days(), uptime.num_hours() % 24, uptime.num_minutes() % 60);
+    if days > 0 {
+        format!("{} day(s) {} hour(s)", days, hours)
+    } else if hours > 0 {
+        format!("{} hour(s) {} minute(s)", hours, minutes)
+    } else if minutes > 0 {
+        format!("{} minute(s)", minutes)
+    } else {
+        format!("{} second(s)", uptime.num_seconds().max(0))
+    }
+}
+
+/// Flags brokers whose Kafka process started recently, as context for other findings, and
+/// points out the under-replicated partitions still waiting for them
+pub struct BrokerRestartAnalyzer {
+    recent: Duration,
+}
+
+impl Default for BrokerRestartAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BrokerRestartAnalyzer {
+    pub fn new() -> Self {
+        Self { recent: Duration::minutes(DEFAULT_RECENT_RESTART_MINUTES) }
+    }
+
+    /// Report brokers up for less than `recent` (default: one hour)
+    pub fn with_recent(mut self, recent: Duration) -> Self {
+        self.recent = recent;
+        self
+    }
+
+    /// Partitions replicated to `broker` that it has not caught up on yet, as `topic-partition`
+    fn lagging_partitions(admin: Option<&AdminCollectorOutput>, broker: i32) -> Vec<String> {
+        admin.into_iter()
+            .flat_map(|admin| &admin.topics)
+            .flat_map(|topic| topic.partitions.iter()
+                .filter(|p| p.replicas.contains(&broker) && !p.isr.contains(&broker))
+                .map(|p| format!("{}-{}", topic.name, p.id)))
+            .collect()
+    }
+
+    fn finding(&self, broker: &str, broker_id: Option<i32>, start: &ProcessStart, uptime: Duration, lagging: &[String]) -> Finding {
+        let started_at = start.started_at.map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string()).unwrap_or_default();
+        let mut description = format!(
+            "The Kafka process on {} started at {}, {} before the scan. ",
+            broker, started_at, describe_uptime(uptime)
+        );
+        if lagging.is_empty() {
+            description.push_str("It is back in the ISR of all its partitions; transient ISR shrinks, leader elections and client errors around that time are likely caused by the restart.");
+        } else {
+            description.push_str(&format!(
+                "It is still missing from the ISR of {} partition(s), so under-replication reported for them is most likely the broker catching up after the restart: {}{}.",
+                lagging.len(),
+                lagging.iter().take(10).cloned().collect::<Vec<_>>().join(", "),
+                if lagging.len() > 10 { format!(" and {} more", lagging.len() - 10) } else { String::new() }
+            ));
+        }
+
+        let mut metadata = HashMap::new();
+        if let Some(id) = broker_id {
+            metadata.insert("broker_id".to_string(), serde_json::json!(id));
+        }
+        metadata.insert("lagging_partitions".to_string(), serde_json::json!(lagging));
+
+        Finding {
+            id: String::new(),
+            title: format!("{} restarted {} ago", broker, describe_uptime(uptime)),
+            description,
+            severity: Severity::Info,
+            category: Category::Availability,
+            impact: "INFO: A restarting broker hands over its leaders and drops out of the ISR until it has caught up, which explains transient replication and client findings.".to_string(),
+            evidence: Evidence {
+                metrics: vec![MetricEvidence {
+                    name: "broker_uptime".to_string(),
+                    value: uptime.num_seconds() as f64,
+                    threshold: Some(self.recent.num_seconds() as f64),
+                    unit: Some("seconds".to_string()),
+                    source: format!("{}/system/{}", broker, PROCESS_START_FILE),
+                    timestamp: start.collected_at,
+                }],
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("A planned restart, a crash or the process being killed, e.g. by the OOM killer".to_string()),
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Check whether the restart was planned; otherwise look for the cause at the end of the previous server.log and in the kernel log".to_string(),
+                        command: Some("journalctl -k --since '-2h' | grep -i -E 'oom|killed process'".to_string()),
+                        verification: None,
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Wait for the broker to rejoin the ISR of all its partitions before restarting another broker".to_string(),
+                        command: Some("kafka-topics.sh --bootstrap-server <broker> --describe --under-replicated-partitions".to_string()),
+                        verification: Some("No under-replicated partitions are listed".to_string()),
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(15),
+                rollback_plan: None,
+            },
+            metadata,
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for BrokerRestartAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let Some(brokers) = snapshot.collectors.custom.get("brokers").and_then(|b| b.as_object()) else {
+            return Ok(Vec::new());
+        };
+        // Correlating with under-replication is best effort
+        let admin: Option<AdminCollectorOutput> = snapshot.collectors.admin.as_ref()
+            .and_then(|admin| serde_json::from_value(admin.clone()).ok());
+
+        let mut starts: Vec<(&String, Option<i32>, ProcessStart)> = brokers.iter()
+            .filter_map(|(name, broker)| {
+                let start = broker.get("system")?.get(PROCESS_START_FILE)?;
+                Some((name, broker_id(name, broker), serde_json::from_value(start.clone()).ok()?))
+            })
+            .collect();
+        starts.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut findings = Vec::new();
+        for (broker, broker_id, start) in starts {
+            let Some(uptime) = start.uptime().filter(|uptime| *uptime < self.recent) else {
+                continue;
+            };
+            let lagging = broker_id
+                .map(|id| Self::lagging_partitions(admin.as_ref(), id))
+                .unwrap_or_default();
+            findings.push(self.finding(broker, broker_id, &start, uptime, &lagging));
+        }
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("RESTART-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Broker Restart Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags brokers restarted within the last hour and the under-replicated partitions waiting for them"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde_json::json;
+
+    fn collected_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 10, 14, 10, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_describe_uptime() {
+        assert_eq!(describe_uptime(Duration::seconds(2847)), "47 minute(s)");
+        assert_eq!(describe_uptime(Duration::seconds(3 * 3600 + 300)), "3 hour(s) 5 minute(s)");
+        assert_eq!(describe_uptime(Duration::days(12)), "12 day(s) 0 hour(s)");
+    }
+
+    #[tokio::test]
+    async fn test_recent_restart_correlated_with_under_replication() {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let start = |uptime_secs: i64| json!(ProcessStart {
+            pid: 4242,
+            started_at: Some(collected_at() - Duration::seconds(uptime_secs)),
+            collected_at: collected_at(),
+        });
+        snapshot.collectors.custom.insert("brokers".to_string(), json!({
+            "broker_1": {"broker_info.json": {"id": 1}, "system": {PROCESS_START_FILE: start(90 * 86400)}},
+            "broker_2": {"broker_info.json": {"id": 2}, "system": {PROCESS_START_FILE: start(600)}},
+            "broker_3": {"system": {}},
+        }));
+        snapshot.collectors.admin = Some(json!({
+            "cluster": {"cluster_id": null, "controller_id": 1, "broker_count": 3, "topic_count": 1, "partition_count": 2},
+            "brokers": [],
+            "topics": [{
+                "name": "orders",
+                "partitions": [
+                    {"id": 0, "leader": 1, "replicas": [1, 2], "isr": [1], "offline_replicas": []},
+                    {"id": 1, "leader": 1, "replicas": [1, 3], "isr": [1, 3], "offline_replicas": []},
+                ],
+                "replication_factor": 2,
+                "is_internal": false,
+            }],
+        }));
+
+        let findings = BrokerRestartAnalyzer::new().analyze(&snapshot).await.unwrap();
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.id, "RESTART-001");
+        assert_eq!(finding.title, "broker_2 restarted 10 minute(s) ago");
+        assert_eq!(finding.severity, Severity::Info);
+        assert!(matches!(finding.category, Category::Availability));
+        assert!(finding.description.contains("still missing from the ISR of 1 partition(s)"));
+        assert_eq!(finding.metadata["lagging_partitions"], json!(["orders-0"]));
+
+        // A wider window also reports the long-running broker, which has nothing to catch up on
+        let findings = BrokerRestartAnalyzer::new().with_recent(Duration::days(365)).analyze(&snapshot).await.unwrap();
+        assert_eq!(findings.len(), 2);
+        assert!(findings[0].description.contains("It is back in the ISR of all its partitions"));
+    }
+}
@@ -0,0 +1,260 @@
+use crate::analyzers::{broker_id, Analyzer, AnalyzerResult};
+use crate::scan::broker_clock::{BrokerClock, BROKER_CLOCK_FILE};
+use crate::snapshot::format::{
+    Category, Evidence, Finding, MetricEvidence, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use chrono::Duration;
+use std::collections::{BTreeSet, HashMap};
+
+/// Skew between two brokers above which they are reported
+const DEFAULT_MAX_SKEW_MS: i64 = 500;
+
+/// Pairs listed in the finding description, most skewed first
+const LISTED_PAIRS: usize = 10;
+
+/// Clock difference between two brokers
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClockSkew {
+    pub brokers: (String, String),
+    pub skew_ms: i64,
+    /// Part of the skew the collection round trips to both brokers could account for
+    pub uncertainty_ms: i64,
+}
+
+impl ClockSkew {
+    /// Skew the collection latency cannot explain
+    pub fn min_skew_ms(&self) -> i64 {
+        (self.skew_ms - self.uncertainty_ms).max(0)
+    }
+}
+
+/// Skew of every pair of brokers with a clock reading, most skewed first once the round trips
+/// are allowed for. Each broker is read against the same scan host clock, so it cancels out.
+pub fn pairwise_skew(clocks: &[(String, BrokerClock)]) -> Vec<ClockSkew> {
+    let offsets: Vec<(&str, i64, i64)> = clocks.iter()
+        .filter_map(|(broker, clock)| Some((broker.as_str(), clock.offset_ms()?, clock.uncertainty_ms())))
+        .collect();
+
+    let mut skews: Vec<ClockSkew> = offsets.iter().enumerate()
+        .flat_map(|(i, a)| offsets[i + 1..].iter().map(move |b| ClockSkew {
+            brokers: (a.0.to_string(), b.0.to_string()),
+            skew_ms: (a.1 - b.1).abs(),
+            uncertainty_ms: a.2 + b.2,
+        }))
+        .collect();
+    skews.sort_by(|a, b| b.min_skew_ms().cmp(&a.min_skew_ms()).then_with(|| a.brokers.cmp(&b.brokers)));
+    skews
+}
+
+/// Flags brokers whose clocks disagree by more than the collection latency can explain
+pub struct ClockSkewAnalyzer {
+    max_skew: Duration,
+}
+
+impl Default for ClockSkewAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockSkewAnalyzer {
+    pub fn new() -> Self {
+        Self { max_skew: Duration::milliseconds(DEFAULT_MAX_SKEW_MS) }
+    }
+
+    /// Report brokers whose clocks differ by more than `max_skew` (default: 500 ms)
+    pub fn with_max_skew(mut self, max_skew: Duration) -> Self {
+        self.max_skew = max_skew;
+        self
+    }
+
+    /// Clock readings of every scanned broker, by broker directory name
+    fn clocks(snapshot: &Snapshot) -> Vec<(String, BrokerClock)> {
+        let mut clocks: Vec<(String, BrokerClock)> = snapshot.collectors.custom.get("brokers")
+            .and_then(|brokers| brokers.as_object())
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, broker)| {
+                let clock = broker.get("system")?.get(BROKER_CLOCK_FILE)?;
+                Some((name.clone(), serde_json::from_value(clock.clone()).ok()?))
+            })
+            .collect();
+        clocks.sort_by(|a, b| a.0.cmp(&b.0));
+        clocks
+    }
+
+    fn finding(&self, snapshot: &Snapshot, clocks: &[(String, BrokerClock)], skewed: &[ClockSkew]) -> Finding {
+        let brokers: BTreeSet<&str> = skewed.iter()
+            .flat_map(|skew| [skew.brokers.0.as_str(), skew.brokers.1.as_str()])
+            .collect();
+        let pairs: Vec<String> = skewed.iter().take(LISTED_PAIRS)
+            .map(|skew| format!("{} and {}: {} ms (±{} ms)", skew.brokers.0, skew.brokers.1, skew.skew_ms, skew.uncertainty_ms))
+            .collect();
+        let offsets: Vec<String> = clocks.iter()
+            .filter(|(broker, _)| brokers.contains(broker.as_str()))
+            .filter_map(|(broker, clock)| Some(format!("{} {:+} ms", broker, clock.offset_ms()?)))
+            .collect();
+        let more = skewed.len().saturating_sub(LISTED_PAIRS);
+        let max_skew_ms = skewed.iter().map(|skew| skew.skew_ms).max().unwrap_or_default();
+
+        let mut metadata = HashMap::new();
+        metadata.insert("brokers".to_string(), serde_json::json!(brokers));
+        let broker_ids: Vec<i32> = snapshot.collectors.custom.get("brokers")
+            .and_then(|entries| entries.as_object())
+            .map(|entries| brokers.iter()
+                .filter_map(|broker| broker_id(broker, entries.get(*broker)?))
+                .collect())
+            .unwrap_or_default();
+        if !broker_ids.is_empty() {
+            metadata.insert("broker_ids".to_string(), serde_json::json!(broker_ids));
+        }
+        metadata.insert("max_skew_ms".to_string(), serde_json::json!(max_skew_ms));
+
+        Finding {
+            id: "CLOCK-001".to_string(),
+            title: format!("Clock skew of up to {} ms between brokers", max_skew_ms),
+            description: format!(
+                "The clocks of {} broker(s) differ by more than {} ms, beyond what the collection round trips \
+                 (given as ±) can explain: {}{}. Offsets against the scan host: {}.",
+                brokers.len(),
+                self.max_skew.num_milliseconds(),
+                pairs.join("; "),
+                if more > 0 { format!("; and {} more pair(s)", more) } else { String::new() },
+                offsets.join(", ")
+            ),
+            severity: Severity::Medium,
+            category: Category::Availability,
+            impact: "MEDIUM: Broker-assigned timestamps jump between partitions, so time-based retention, log roll and \
+                     offset lookups by time misbehave, and transaction and session timeouts expire early or late.".to_string(),
+            evidence: Evidence {
+                metrics: skewed.iter().map(|skew| MetricEvidence {
+                    name: format!("clock_skew_{}_{}", skew.brokers.0, skew.brokers.1),
+                    value: skew.skew_ms as f64,
+                    threshold: Some(self.max_skew.num_milliseconds() as f64),
+                    unit: Some("ms".to_string()),
+                    source: format!("{}/system/{}", skew.brokers.0, BROKER_CLOCK_FILE),
+                    timestamp: snapshot.timestamp,
+                }).collect(),
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Time synchronization (NTP or chrony) stopped, misconfigured or missing on some brokers".to_string()),
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Check time synchronization on the listed brokers".to_string(),
+                        command: Some("timedatectl status; chronyc tracking".to_string()),
+                        verification: Some("'System clock synchronized: yes' and a small 'System time' offset".to_string()),
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Enable time synchronization against the same time sources on every broker".to_string(),
+                        command: Some("sudo timedatectl set-ntp true".to_string()),
+                        verification: Some("chronyc sources".to_string()),
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(30),
+                rollback_plan: None,
+            },
+            metadata,
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for ClockSkewAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let clocks = Self::clocks(snapshot);
+        let max_skew_ms = self.max_skew.num_milliseconds();
+        let skewed: Vec<ClockSkew> = pairwise_skew(&clocks).into_iter()
+            .filter(|skew| skew.min_skew_ms() > max_skew_ms)
+            .collect();
+
+        if skewed.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(vec![self.finding(snapshot, &clocks, &skewed)])
+    }
+
+    fn name(&self) -> &'static str {
+        "Clock Skew Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags brokers whose clocks differ by more than 500 ms"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    /// Broker read `round_trip_ms` after the scan host's `sent_at`, with its clock `offset_ms` ahead
+    fn clock(sent_offset_ms: i64, round_trip_ms: i64, offset_ms: i64) -> BrokerClock {
+        let sent_at = Utc.with_ymd_and_hms(2024, 10, 14, 10, 0, 0).unwrap() + Duration::milliseconds(sent_offset_ms);
+        BrokerClock {
+            broker_time_ms: Some(sent_at.timestamp_millis() + round_trip_ms / 2 + offset_ms),
+            sent_at,
+            received_at: sent_at + Duration::milliseconds(round_trip_ms),
+        }
+    }
+
+    #[test]
+    fn test_skew_computed_from_collected_timestamps() {
+        let clocks = vec![
+            ("broker_1".to_string(), clock(0, 200, 0)),
+            // Read three seconds later over a slower connection, with a clock 40 ms ahead
+            ("broker_2".to_string(), clock(3000, 400, 40)),
+            ("broker_3".to_string(), clock(6000, 100, -900)),
+            ("broker_4".to_string(), BrokerClock { broker_time_ms: None, ..clock(9000, 100, 0) }),
+        ];
+        assert_eq!(clocks[1].1.offset_ms(), Some(40));
+        assert_eq!(clocks[1].1.uncertainty_ms(), 200);
+
+        let skews = pairwise_skew(&clocks);
+        assert_eq!(skews.len(), 3);
+        assert_eq!(skews[0], ClockSkew { brokers: ("broker_1".to_string(), "broker_3".to_string()), skew_ms: 900, uncertainty_ms: 150 });
+        assert_eq!(skews[0].min_skew_ms(), 750);
+        assert_eq!(skews[1].brokers, ("broker_2".to_string(), "broker_3".to_string()));
+        assert_eq!(skews[1].min_skew_ms(), 690);
+        assert_eq!(skews[2].min_skew_ms(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_skewed_brokers_flagged() {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.custom.insert("brokers".to_string(), json!({
+            "broker_1": {"broker_info.json": {"id": 1}, "system": {BROKER_CLOCK_FILE: clock(0, 200, 0)}},
+            "broker_2": {"broker_info.json": {"id": 2}, "system": {BROKER_CLOCK_FILE: clock(3000, 400, 40)}},
+            "broker_3": {"broker_info.json": {"id": 3}, "system": {BROKER_CLOCK_FILE: clock(6000, 100, -900)}},
+        }));
+
+        let findings = ClockSkewAnalyzer::new().analyze(&snapshot).await.unwrap();
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.id, "CLOCK-001");
+        assert_eq!(finding.title, "Clock skew of up to 940 ms between brokers");
+        assert_eq!(finding.severity, Severity::Medium);
+        assert_eq!(finding.category, Category::Availability);
+        assert!(finding.description.contains("broker_1 and broker_3: 900 ms (±150 ms); broker_2 and broker_3: 940 ms (±250 ms)"));
+        assert!(finding.description.contains("Offsets against the scan host: broker_1 +0 ms, broker_2 +40 ms, broker_3 -900 ms."));
+        assert_eq!(finding.metadata["brokers"], json!(["broker_1", "broker_2", "broker_3"]));
+        assert_eq!(finding.metadata["broker_ids"], json!([1, 2, 3]));
+
+        // Skew within what the round trips explain is not reported
+        let findings = ClockSkewAnalyzer::new().with_max_skew(Duration::milliseconds(800)).analyze(&snapshot).await.unwrap();
+        assert!(findings.is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::analyzers::{Analyzer, AnalyzerResult};
+use crate::analyzers::{server_properties, Analyzer, AnalyzerResult};
 use crate::snapshot::format::{
     Category, ConfigEvidence, Evidence, Finding, 
     Remediation, RemediationStep, RiskLevel, Severity, Snapshot
@@ -6,6 +6,44 @@ use crate::snapshot::format::{
 use async_trait::async_trait;
 use std::collections::HashMap;
 
+const UNCLEAN_LEADER_ELECTION_KEY: &str = "unclean.leader.election.enable";
+const AUTO_CREATE_TOPICS_KEY: &str = "auto.create.topics.enable";
+
+/// Broker thread pool settings checked against CPU cores
+struct ThreadPoolSetting {
+    key: &'static str,
+    id: &'static str,
+    /// Kafka's default, also the lowest value reported as fine without core counts
+    default: u32,
+    /// One thread is recommended per this many cores
+    cores_per_thread: u32,
+    purpose: &'static str,
+}
+
+const THREAD_POOL_SETTINGS: [ThreadPoolSetting; 3] = [
+    ThreadPoolSetting {
+        key: "num.io.threads",
+        id: "CONFIG-IO-THREADS-001",
+        default: 8,
+        cores_per_thread: 1,
+        purpose: "process requests, including disk I/O",
+    },
+    ThreadPoolSetting {
+        key: "num.network.threads",
+        id: "CONFIG-NETWORK-THREADS-001",
+        default: 3,
+        cores_per_thread: 4,
+        purpose: "receive requests from and send responses to the network",
+    },
+    ThreadPoolSetting {
+        key: "num.replica.fetchers",
+        id: "CONFIG-REPLICA-FETCHERS-001",
+        default: 1,
+        cores_per_thread: 8,
+        purpose: "replicate messages from leaders",
+    },
+];
+
 /// Configuration validator that checks for common Kafka configuration issues
 pub struct ConfigValidator;
 
@@ -174,6 +212,7 @@ impl ConfigValidator {
                         logs: vec![],
                         metrics: vec![],
                         raw_data: None,
+                        timeline: Vec::new(),
                     },
                     root_cause: Some(if has_actual_ids {
                         format!(
@@ -333,6 +372,7 @@ impl ConfigValidator {
                     logs: vec![],
                     metrics: vec![],
                     raw_data: None,
+                    timeline: Vec::new(),
                 },
                 root_cause: Some("Insufficient replication configuration".to_string()),
                 remediation: Remediation {
@@ -377,6 +417,7 @@ impl ConfigValidator {
                     logs: vec![],
                     metrics: vec![],
                     raw_data: None,
+                    timeline: Vec::new(),
                 },
                 root_cause: Some("Inappropriate storage location for Kafka logs".to_string()),
                 remediation: Remediation {
@@ -408,6 +449,324 @@ impl ConfigValidator {
         
         findings
     }
+    
+    /// Topics with a configuration override set to `value`, from `kafka-topics.sh --describe` output
+    fn topic_overrides(snapshot: &Snapshot, key: &str, value: &str) -> Vec<String> {
+        let Some(describe) = snapshot.collectors.custom.get("cluster")
+            .and_then(|cluster| cluster.get("tools"))
+            .and_then(|tools| tools.get("topics_kafka_tools.txt"))
+            .and_then(|output| output.as_str())
+        else {
+            return Vec::new();
+        };
+        
+        // "Topic: orders\tTopicId: ...\tPartitionCount: 3\tReplicationFactor: 3\tConfigs: min.insync.replicas=2,..."
+        describe.lines()
+            .filter_map(|line| {
+                let topic = line.trim().strip_prefix("Topic:")?.split('\t').next()?.trim();
+                let (_, configs) = line.split_once("Configs:")?;
+                configs.split(',')
+                    .filter_map(|entry| entry.trim().split_once('='))
+                    .any(|(k, v)| k == key && v.trim().eq_ignore_ascii_case(value))
+                    .then(|| topic.to_string())
+            })
+            .collect()
+    }
+    
+    /// CPU core count of a scanned broker, from `lscpu` or `/proc/cpuinfo`
+    fn cpu_cores(snapshot: &Snapshot, broker: &str) -> Option<u32> {
+        let system = snapshot.collectors.custom.get("brokers")?.get(broker)?.get("system")?;
+
+        let from_lscpu = system.get("lscpu.txt").and_then(|c| c.as_str()).and_then(|lscpu| {
+            lscpu.lines()
+                .find_map(|line| line.strip_prefix("CPU(s):"))
+                .and_then(|count| count.trim().parse().ok())
+        });
+        let from_cpuinfo = || system.get("cpuinfo.txt").and_then(|c| c.as_str()).and_then(|cpuinfo| {
+            let count = cpuinfo.lines()
+                .filter(|line| line.split(':').next().is_some_and(|key| key.trim() == "processor"))
+                .count() as u32;
+            (count > 0).then_some(count)
+        });
+
+        from_lscpu.or_else(from_cpuinfo).filter(|&cores| cores > 0)
+    }
+
+    /// Check for thread pools that are small for the broker's CPU cores. Without
+    /// core counts, only values below Kafka's defaults are reported.
+    fn check_thread_provisioning(&self, snapshot: &Snapshot) -> Vec<Finding> {
+        let brokers: Vec<(String, HashMap<String, String>, Option<u32>)> = server_properties(snapshot).into_iter()
+            .map(|(file_path, props)| {
+                let broker = file_path.split('/').next().unwrap_or_default();
+                let cores = Self::cpu_cores(snapshot, broker);
+                (file_path, props, cores)
+            })
+            .collect();
+
+        let mut findings = Vec::new();
+        for setting in &THREAD_POOL_SETTINGS {
+            let configs: Vec<ConfigEvidence> = brokers.iter()
+                .filter_map(|(file_path, props, cores)| {
+                    let current = match props.get(setting.key) {
+                        Some(value) => value.trim().parse::<u32>().ok()?,
+                        None => setting.default,
+                    };
+                    let recommended = cores
+                        .map(|cores| (cores / setting.cores_per_thread).max(setting.default))
+                        .unwrap_or(setting.default);
+
+                    // Tolerate up to half the recommendation before reporting a core mismatch
+                    if current >= setting.default && current * 2 >= recommended {
+                        return None;
+                    }
+
+                    let reason = match cores {
+                        Some(cores) => format!("{} CPU cores, one thread per {} core(s) recommended", cores, setting.cores_per_thread),
+                        None => format!("Below Kafka's default of {}; CPU core count not collected", setting.default),
+                    };
+                    Some(ConfigEvidence {
+                        resource_type: "broker".to_string(),
+                        resource_name: file_path.split('/').next().unwrap_or_default().to_string(),
+                        config_key: setting.key.to_string(),
+                        current_value: current.to_string(),
+                        recommended_value: Some(recommended.to_string()),
+                        reason,
+                        source_files: vec![format!("brokers/{}", file_path)],
+                    })
+                })
+                .collect();
+
+            if configs.is_empty() {
+                continue;
+            }
+
+            findings.push(Finding {
+                id: setting.id.to_string(),
+                severity: Severity::Medium,
+                category: Category::Performance,
+                title: format!("{} too low on {} broker(s)", setting.key, configs.len()),
+                description: format!(
+                    "{} sets how many threads a broker uses to {}. The configured value is low for the \
+                    broker's hardware, so requests queue up while CPU cores sit idle under load.",
+                    setting.key, setting.purpose
+                ),
+                impact: "MEDIUM: Higher request latency and lower throughput than the hardware allows.".to_string(),
+                evidence: Evidence {
+                    configs,
+                    logs: vec![],
+                    metrics: vec![],
+                    raw_data: None,
+                    timeline: Vec::new(),
+                },
+                root_cause: Some("Thread pools left at defaults or sized for smaller hardware".to_string()),
+                remediation: Remediation {
+                    steps: vec![
+                        RemediationStep {
+                            order: 1,
+                            description: format!("Raise {} to the recommended value on the affected brokers", setting.key),
+                            command: Some(format!(
+                                "kafka-configs.sh --bootstrap-server <broker> --entity-type brokers --entity-name <broker-id> --alter --add-config {}=<recommended>",
+                                setting.key
+                            )),
+                            verification: Some("Watch RequestHandlerAvgIdlePercent and NetworkProcessorAvgIdlePercent stay above 0.3".to_string()),
+                            can_automate: false,
+                        },
+                    ],
+                    script: None,
+                    risk_level: RiskLevel::Low,
+                    requires_downtime: false,
+                    estimated_duration_minutes: Some(15),
+                    rollback_plan: Some(format!("Set {} back to its previous value", setting.key)),
+                },
+                metadata: HashMap::new(),
+            });
+        }
+
+        findings
+    }
+
+    /// Check for unclean leader election enabled on brokers or topics.
+    /// The setting defaults to false, so only an explicit `true` is reported.
+    fn check_unclean_leader_election(&self, snapshot: &Snapshot) -> Vec<Finding> {
+        let brokers: Vec<String> = server_properties(snapshot).into_iter()
+            .filter(|(_, props)| {
+                props.get(UNCLEAN_LEADER_ELECTION_KEY)
+                    .map(|v| v.trim().eq_ignore_ascii_case("true"))
+                    .unwrap_or(false)
+            })
+            .map(|(file_path, _)| format!("brokers/{}", file_path))
+            .collect();
+        let topics = Self::topic_overrides(snapshot, UNCLEAN_LEADER_ELECTION_KEY, "true");
+        
+        if brokers.is_empty() && topics.is_empty() {
+            return Vec::new();
+        }
+        
+        let mut configs = Vec::new();
+        if !brokers.is_empty() {
+            configs.push(ConfigEvidence {
+                resource_type: "broker".to_string(),
+                resource_name: "multiple".to_string(),
+                config_key: UNCLEAN_LEADER_ELECTION_KEY.to_string(),
+                current_value: "true".to_string(),
+                recommended_value: Some("false".to_string()),
+                reason: "Out-of-sync replicas must not become leader".to_string(),
+                source_files: brokers.clone(),
+            });
+        }
+        configs.extend(topics.iter().map(|topic| ConfigEvidence {
+            resource_type: "topic".to_string(),
+            resource_name: topic.clone(),
+            config_key: UNCLEAN_LEADER_ELECTION_KEY.to_string(),
+            current_value: "true".to_string(),
+            recommended_value: Some("false".to_string()),
+            reason: "Topic-level override enables unclean leader election".to_string(),
+            source_files: vec!["cluster/tools/topics_kafka_tools.txt".to_string()],
+        }));
+        
+        let mut steps = Vec::new();
+        if !brokers.is_empty() {
+            steps.push(RemediationStep {
+                order: 1,
+                description: format!("Set {}=false in server.properties on every broker", UNCLEAN_LEADER_ELECTION_KEY),
+                command: Some(format!(
+                    "kafka-configs.sh --bootstrap-server <broker> --entity-type brokers --entity-default --alter --add-config {}=false",
+                    UNCLEAN_LEADER_ELECTION_KEY
+                )),
+                verification: Some(format!("grep {} /etc/kafka/server.properties", UNCLEAN_LEADER_ELECTION_KEY)),
+                can_automate: false,
+            });
+        }
+        if !topics.is_empty() {
+            steps.push(RemediationStep {
+                order: steps.len() as u32 + 1,
+                description: format!("Remove the {} override from the affected topics", UNCLEAN_LEADER_ELECTION_KEY),
+                command: Some(format!(
+                    "kafka-configs.sh --bootstrap-server <broker> --entity-type topics --entity-name <topic> --alter --delete-config {}",
+                    UNCLEAN_LEADER_ELECTION_KEY
+                )),
+                verification: Some("kafka-configs.sh --bootstrap-server <broker> --entity-type topics --entity-name <topic> --describe".to_string()),
+                can_automate: false,
+            });
+        }
+        
+        let scope = match (brokers.len(), topics.len()) {
+            (0, t) => format!("{} topic(s)", t),
+            (b, 0) => format!("{} broker(s)", b),
+            (b, t) => format!("{} broker(s) and {} topic(s)", b, t),
+        };
+        
+        vec![Finding {
+            id: "CONFIG-UNCLEAN-LEADER-001".to_string(),
+            severity: Severity::High,
+            category: Category::Availability,
+            title: format!("Unclean leader election enabled on {}", scope),
+            description: format!(
+                "{} is set to true. When every in-sync replica of a partition is offline, Kafka will \
+                elect an out-of-sync replica as leader to keep the partition available. Messages that \
+                replica had not yet copied are silently lost, and consumers may see offsets go backwards.",
+                UNCLEAN_LEADER_ELECTION_KEY
+            ),
+            impact: "HIGH: Acknowledged messages can be lost permanently after a broker failure, even with acks=all.".to_string(),
+            evidence: Evidence {
+                configs,
+                logs: vec![],
+                metrics: vec![],
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Availability was favoured over durability, often by accident or as a leftover from an incident".to_string()),
+            remediation: Remediation {
+                steps,
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(15),
+                rollback_plan: Some(format!("Set {}=true again", UNCLEAN_LEADER_ELECTION_KEY)),
+            },
+            metadata: HashMap::new(),
+        }]
+    }
+
+    /// Check for brokers creating topics on first use. The setting defaults to true,
+    /// so brokers that do not set it are reported along with an explicit `true`.
+    fn check_auto_create_topics(&self, snapshot: &Snapshot) -> Vec<Finding> {
+        let mut explicit = Vec::new();
+        let mut defaulted = Vec::new();
+        for (file_path, props) in server_properties(snapshot) {
+            match props.get(AUTO_CREATE_TOPICS_KEY).map(|v| v.trim()) {
+                Some(value) if value.eq_ignore_ascii_case("true") => explicit.push(format!("brokers/{}", file_path)),
+                Some(_) => {}
+                None => defaulted.push(format!("brokers/{}", file_path)),
+            }
+        }
+        
+        let enabled = explicit.len() + defaulted.len();
+        if enabled == 0 {
+            return Vec::new();
+        }
+        
+        let configs = [(explicit, "true"), (defaulted, "true (default)")].into_iter()
+            .filter(|(files, _)| !files.is_empty())
+            .map(|(files, current_value)| ConfigEvidence {
+                resource_type: "broker".to_string(),
+                resource_name: "multiple".to_string(),
+                config_key: AUTO_CREATE_TOPICS_KEY.to_string(),
+                current_value: current_value.to_string(),
+                recommended_value: Some("false".to_string()),
+                reason: "Topics should be created explicitly, with settings chosen for them".to_string(),
+                source_files: files,
+            })
+            .collect();
+        
+        vec![Finding {
+            id: "CONFIG-AUTO-CREATE-001".to_string(),
+            severity: Severity::Medium,
+            category: Category::ClusterHygiene,
+            title: format!("Automatic topic creation enabled on {} broker(s)", enabled),
+            description: format!(
+                "{} is true or left at its default of true. Any producer or consumer that names a missing \
+                topic, for example through a typo or a test client pointed at the wrong cluster, creates it \
+                with the broker defaults (num.partitions and default.replication.factor, often 1 partition \
+                with a single replica). Topics multiply without an owner, and the ones created this way are \
+                neither sized for their load nor protected against a broker failure.",
+                AUTO_CREATE_TOPICS_KEY
+            ),
+            impact: "MEDIUM: Topic sprawl with unsafe defaults, and data that may be lost with a single broker.".to_string(),
+            evidence: Evidence {
+                configs,
+                logs: vec![],
+                metrics: vec![],
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Kafka creates topics on first use unless automatic creation is disabled".to_string()),
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Create the topics applications need explicitly, with their partition count and replication factor, e.g. from infrastructure code".to_string(),
+                        command: Some("kafka-topics.sh --bootstrap-server <broker> --create --topic <topic> --partitions <n> --replication-factor 3".to_string()),
+                        verification: Some("kafka-topics.sh --bootstrap-server <broker> --describe --topic <topic>".to_string()),
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: format!("Set {}=false in server.properties on every broker and restart them one at a time", AUTO_CREATE_TOPICS_KEY),
+                        command: None,
+                        verification: Some(format!("grep {} /etc/kafka/server.properties", AUTO_CREATE_TOPICS_KEY)),
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(60),
+                rollback_plan: Some(format!("Set {}=true again", AUTO_CREATE_TOPICS_KEY)),
+            },
+            metadata: HashMap::new(),
+        }]
+    }
 }
 
 #[async_trait]
@@ -421,6 +780,15 @@ impl Analyzer for ConfigValidator {
         // Check for other common issues
         findings.extend(self.check_common_issues(snapshot));
         
+        // Check for unclean leader election
+        findings.extend(self.check_unclean_leader_election(snapshot));
+        
+        // Check for automatic topic creation
+        findings.extend(self.check_auto_create_topics(snapshot));
+        
+        // Check for undersized thread pools
+        findings.extend(self.check_thread_provisioning(snapshot));
+        
         Ok(findings)
     }
     
@@ -431,4 +799,172 @@ impl Analyzer for ConfigValidator {
     fn description(&self) -> &'static str {
         "Validates Kafka configuration for common issues and best practices"
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    fn snapshot(unclean_leader_election: Option<&str>) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let setting = unclean_leader_election
+            .map(|value| format!("\nunclean.leader.election.enable={}", value))
+            .unwrap_or_default();
+        snapshot.collectors.config = Some(json!({
+            "broker_1/server.properties": format!("broker.id=1\nlog.dirs=/var/lib/kafka{}", setting),
+            "broker_2/server.properties": format!("broker.id=2\nlog.dirs=/var/lib/kafka{}", setting),
+        }));
+        snapshot
+    }
+
+    fn unclean_findings(snapshot: &Snapshot) -> Vec<Finding> {
+        ConfigValidator::new().check_unclean_leader_election(snapshot)
+    }
+
+    #[test]
+    fn test_unclean_leader_election_enabled() {
+        let findings = unclean_findings(&snapshot(Some("true")));
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::High);
+        assert!(matches!(findings[0].category, Category::Availability));
+        assert!(findings[0].title.contains("2 broker(s)"));
+        assert!(findings[0].description.contains("lost"));
+        assert_eq!(
+            findings[0].evidence.configs[0].source_files,
+            vec!["brokers/broker_1/server.properties", "brokers/broker_2/server.properties"]
+        );
+    }
+
+    #[test]
+    fn test_unclean_leader_election_disabled_or_absent() {
+        assert!(unclean_findings(&snapshot(Some("false"))).is_empty());
+        assert!(unclean_findings(&snapshot(None)).is_empty());
+    }
+
+    #[test]
+    fn test_unclean_leader_election_topic_override() {
+        let mut snapshot = snapshot(None);
+        snapshot.collectors.custom.insert("cluster".to_string(), json!({
+            "tools": {
+                "topics_kafka_tools.txt": "Topic: orders\tTopicId: abc\tPartitionCount: 3\tReplicationFactor: 3\tConfigs: min.insync.replicas=2,unclean.leader.election.enable=true\n\
+                    \tTopic: orders\tPartition: 0\tLeader: 1\tReplicas: 1,2,3\tIsr: 1,2,3\n\
+                    Topic: payments\tTopicId: def\tPartitionCount: 1\tReplicationFactor: 3\tConfigs: unclean.leader.election.enable=false\n"
+            }
+        }));
+
+        let findings = unclean_findings(&snapshot);
+
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("1 topic(s)"));
+        assert_eq!(findings[0].evidence.configs.len(), 1);
+        assert_eq!(findings[0].evidence.configs[0].resource_type, "topic");
+        assert_eq!(findings[0].evidence.configs[0].resource_name, "orders");
+    }
+
+    fn auto_create_snapshot(values: &[Option<&str>]) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let config: serde_json::Map<_, _> = values.iter().enumerate()
+            .map(|(i, value)| {
+                let setting = value.map(|value| format!("\nauto.create.topics.enable={}", value)).unwrap_or_default();
+                (format!("broker_{}/server.properties", i + 1), json!(format!("broker.id={}{}", i + 1, setting)))
+            })
+            .collect();
+        snapshot.collectors.config = Some(serde_json::Value::Object(config));
+        snapshot
+    }
+
+    #[test]
+    fn test_auto_create_topics_enabled() {
+        let findings = ConfigValidator::new().check_auto_create_topics(&auto_create_snapshot(&[Some("true"), Some("TRUE")]));
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert_eq!(findings[0].category, Category::ClusterHygiene);
+        assert_eq!(findings[0].title, "Automatic topic creation enabled on 2 broker(s)");
+        assert!(findings[0].description.contains("default.replication.factor"));
+        assert_eq!(findings[0].evidence.configs.len(), 1);
+        assert_eq!(findings[0].evidence.configs[0].recommended_value.as_deref(), Some("false"));
+    }
+
+    #[test]
+    fn test_auto_create_topics_disabled() {
+        assert!(ConfigValidator::new().check_auto_create_topics(&auto_create_snapshot(&[Some("false"), Some(" false ")])).is_empty());
+        // No broker configuration collected
+        assert!(ConfigValidator::new().check_auto_create_topics(&auto_create_snapshot(&[])).is_empty());
+    }
+
+    #[test]
+    fn test_auto_create_topics_absent_defaults_to_enabled() {
+        let findings = ConfigValidator::new().check_auto_create_topics(&auto_create_snapshot(&[None, Some("false"), Some("true")]));
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].title, "Automatic topic creation enabled on 2 broker(s)");
+        let configs = &findings[0].evidence.configs;
+        assert_eq!(configs[0].current_value, "true");
+        assert_eq!(configs[0].source_files, vec!["brokers/broker_3/server.properties"]);
+        assert_eq!(configs[1].current_value, "true (default)");
+        assert_eq!(configs[1].source_files, vec!["brokers/broker_1/server.properties"]);
+    }
+
+    fn thread_snapshot(properties: &str, lscpu: Option<&str>) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.config = Some(json!({
+            "broker_1/server.properties": format!("broker.id=1\n{}", properties),
+        }));
+        if let Some(lscpu) = lscpu {
+            snapshot.collectors.custom.insert("brokers".to_string(), json!({
+                "broker_1": {"system": {"lscpu.txt": lscpu}}
+            }));
+        }
+        snapshot
+    }
+
+    fn thread_findings(snapshot: &Snapshot) -> Vec<Finding> {
+        ConfigValidator::new().check_thread_provisioning(snapshot)
+    }
+
+    #[test]
+    fn test_thread_pools_too_small_for_cores() {
+        let lscpu = "Architecture:        x86_64\nCPU(s):              32\nThread(s) per core:  2\n";
+        let findings = thread_findings(&thread_snapshot("num.io.threads=8\nnum.network.threads=3", Some(lscpu)));
+
+        let ids: Vec<&str> = findings.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["CONFIG-IO-THREADS-001", "CONFIG-NETWORK-THREADS-001", "CONFIG-REPLICA-FETCHERS-001"]);
+        assert!(findings.iter().all(|f| f.severity == Severity::Medium && matches!(f.category, Category::Performance)));
+
+        let io = &findings[0].evidence.configs[0];
+        assert_eq!(io.current_value, "8");
+        assert_eq!(io.recommended_value.as_deref(), Some("32"));
+        assert!(io.reason.contains("32 CPU cores"));
+        assert_eq!(findings[1].evidence.configs[0].recommended_value.as_deref(), Some("8"));
+        assert_eq!(findings[2].evidence.configs[0].recommended_value.as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn test_thread_pools_sized_for_cores() {
+        let cpuinfo = (0..16).map(|i| format!("processor\t: {}\nmodel name\t: Xeon\n", i)).collect::<String>();
+        let mut snapshot = thread_snapshot("num.io.threads=16\nnum.network.threads=4\nnum.replica.fetchers=2", None);
+        snapshot.collectors.custom.insert("brokers".to_string(), json!({
+            "broker_1": {"system": {"cpuinfo.txt": cpuinfo}}
+        }));
+
+        assert_eq!(ConfigValidator::cpu_cores(&snapshot, "broker_1"), Some(16));
+        assert!(thread_findings(&snapshot).is_empty());
+    }
+
+    #[test]
+    fn test_thread_pools_without_system_info() {
+        // Defaults are fine when the core count is unknown
+        assert!(thread_findings(&thread_snapshot("", None)).is_empty());
+
+        // Values below Kafka's defaults are still reported
+        let findings = thread_findings(&thread_snapshot("num.io.threads=2", None));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].id, "CONFIG-IO-THREADS-001");
+        assert_eq!(findings[0].evidence.configs[0].recommended_value.as_deref(), Some("8"));
+        assert!(findings[0].evidence.configs[0].reason.contains("not collected"));
+    }
+}
@@ -0,0 +1,355 @@
+use crate::analyzers::{Analyzer, AnalyzerResult};
+use crate::scan::cluster_detection::parse_server_properties;
+use crate::scan::connections::CONNECTIONS_FILE;
+use crate::scan::listeners::{parse_listeners, DEFAULT_BROKER_PORT};
+use crate::snapshot::format::{
+    Category, ConfigEvidence, Evidence, Finding, MetricEvidence, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// How many times the average of the other brokers a broker's connection count must reach to be a hotspot
+const DEFAULT_HOTSPOT_FACTOR: f64 = 3.0;
+
+/// Connections below which a broker is never a hotspot, however uneven the cluster
+const DEFAULT_MIN_HOTSPOT_CONNECTIONS: usize = 100;
+
+/// Share of `max.connections` in use at which a broker is close to refusing connections
+const DEFAULT_LIMIT_WARNING_PERCENT: f64 = 80.0;
+
+/// One TCP connection of a connection table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connection {
+    pub local_port: u16,
+    pub peer: String,
+}
+
+/// Established connections to one broker's listeners
+#[derive(Debug, Clone)]
+pub struct BrokerConnections {
+    pub broker: String,
+    /// Path of the connection table within the scan, e.g. `broker_1/system/connections.txt`
+    pub source: String,
+    pub established: usize,
+    pub max_connections: Option<usize>,
+}
+
+/// `(host, port)` of a connection table address: `10.0.0.1:9092`, `[::1]:9092` (ss) or
+/// `::ffff:10.0.0.1:9092` (netstat)
+fn split_socket(address: &str) -> Option<(&str, u16)> {
+    let (host, port) = address.rsplit_once(':')?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    Some((host, port.parse().ok()?))
+}
+
+/// Established connections of `netstat -tn` or `ss -tn` output. ss filtered with
+/// `state established` prints no state column, so lines without one count as established.
+pub fn parse_connections(output: &str) -> Vec<Connection> {
+    output.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let state = fields.iter()
+                .find(|field| field.len() > 2 && field.chars().all(|c| c.is_ascii_uppercase() || c == '-' || c == '_'));
+            if state.is_some_and(|state| !matches!(*state, "ESTAB" | "ESTABLISHED")) {
+                return None;
+            }
+            let mut sockets = fields.iter().filter_map(|field| split_socket(field));
+            let (_, local_port) = sockets.next()?;
+            let (peer, _) = sockets.next()?;
+            Some(Connection { local_port, peer: peer.to_string() })
+        })
+        .collect()
+}
+
+/// Ports a broker listens on according to its server.properties
+fn listener_ports(properties: &HashMap<String, String>) -> Vec<u16> {
+    let ports: Vec<u16> = properties.get("listeners")
+        .map(|listeners| parse_listeners(listeners).into_iter().map(|listener| listener.port).collect())
+        .unwrap_or_default();
+    if ports.is_empty() {
+        vec![DEFAULT_BROKER_PORT]
+    } else {
+        ports
+    }
+}
+
+/// Flags brokers holding far more connections than the rest of the cluster, or close to their `max.connections`
+pub struct ConnectionAnalyzer {
+    hotspot_factor: f64,
+    min_hotspot_connections: usize,
+    limit_warning_percent: f64,
+}
+
+impl Default for ConnectionAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectionAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            hotspot_factor: DEFAULT_HOTSPOT_FACTOR,
+            min_hotspot_connections: DEFAULT_MIN_HOTSPOT_CONNECTIONS,
+            limit_warning_percent: DEFAULT_LIMIT_WARNING_PERCENT,
+        }
+    }
+
+    /// Flag brokers with at least `min_connections` and `factor` times the average of the other brokers
+    pub fn with_hotspot(mut self, factor: f64, min_connections: usize) -> Self {
+        self.hotspot_factor = factor;
+        self.min_hotspot_connections = min_connections;
+        self
+    }
+
+    /// Established connections of every scanned broker that has a connection table
+    pub fn broker_connections(&self, snapshot: &Snapshot) -> Vec<BrokerConnections> {
+        let Some(brokers) = snapshot.collectors.custom.get("brokers").and_then(|b| b.as_object()) else {
+            return Vec::new();
+        };
+
+        let mut connections: Vec<BrokerConnections> = brokers.iter()
+            .filter_map(|(name, broker)| {
+                let table = broker.get("system")?.get(CONNECTIONS_FILE)?.as_str()?;
+                let properties = broker.get("configs")
+                    .and_then(|configs| configs.get("server.properties"))
+                    .and_then(|content| content.as_str())
+                    .map(parse_server_properties)
+                    .unwrap_or_default();
+                let ports = listener_ports(&properties);
+                Some(BrokerConnections {
+                    broker: name.clone(),
+                    source: format!("{}/system/{}", name, CONNECTIONS_FILE),
+                    established: parse_connections(table).iter()
+                        .filter(|connection| ports.contains(&connection.local_port))
+                        .count(),
+                    max_connections: properties.get("max.connections").and_then(|v| v.trim().parse().ok()),
+                })
+            })
+            .collect();
+        connections.sort_by(|a, b| a.broker.cmp(&b.broker));
+        connections
+    }
+
+    fn hotspot_finding(&self, snapshot: &Snapshot, broker: &BrokerConnections, others_average: f64) -> Finding {
+        finding(
+            format!("Connection hotspot on {}", broker.broker),
+            format!(
+                "{} holds {} established connections, {:.1} times the {:.0} the other brokers hold on average.",
+                broker.broker, broker.established, broker.established as f64 / others_average, others_average
+            ),
+            vec![self.metric(snapshot, broker, broker.established as f64, Some(others_average * self.hotspot_factor))],
+            Vec::new(),
+            &[
+                "Check which clients the connections come from (ss -tn state established '( sport = :9092 )') and whether they bootstrap from this broker only",
+                "List every broker in the clients' bootstrap.servers and make sure they reuse producers and consumers instead of reconnecting",
+                "Rebalance partition leadership (kafka-leader-election.sh --election-type preferred) so clients spread over all brokers",
+            ],
+        )
+    }
+
+    fn limit_finding(&self, snapshot: &Snapshot, broker: &BrokerConnections, max_connections: usize) -> Finding {
+        let percent = broker.established as f64 * 100.0 / max_connections as f64;
+        finding(
+            format!("{} is close to its connection limit", broker.broker),
+            format!(
+                "{} holds {} established connections, {:.0}% of its max.connections of {}; new connections are refused once the limit is reached.",
+                broker.broker, broker.established, percent, max_connections
+            ),
+            vec![self.metric(snapshot, broker, broker.established as f64, Some(max_connections as f64))],
+            vec![ConfigEvidence {
+                resource_type: "broker".to_string(),
+                resource_name: broker.broker.clone(),
+                config_key: "max.connections".to_string(),
+                current_value: max_connections.to_string(),
+                recommended_value: None,
+                reason: format!("{:.0}% of the limit is in use", percent),
+                source_files: vec![format!("{}/configs/server.properties", broker.broker)],
+            }],
+            &[
+                "Find clients holding many connections (ss -tn state established | awk '{print $4}' | cut -d: -f1 | sort | uniq -c | sort -rn | head)",
+                "Fix clients that leak connections or create a producer per request",
+                "Raise max.connections (a dynamic broker config) if the load is legitimate: kafka-configs.sh --alter --entity-type brokers --entity-default --add-config max.connections=<n>",
+            ],
+        )
+    }
+
+    fn metric(&self, snapshot: &Snapshot, broker: &BrokerConnections, value: f64, threshold: Option<f64>) -> MetricEvidence {
+        MetricEvidence {
+            name: "established_connections".to_string(),
+            value,
+            threshold,
+            unit: Some("connections".to_string()),
+            source: broker.source.clone(),
+            timestamp: snapshot.timestamp,
+        }
+    }
+}
+
+fn finding(title: String, description: String, metrics: Vec<MetricEvidence>, configs: Vec<ConfigEvidence>, steps: &[&str]) -> Finding {
+    Finding {
+        id: String::new(),
+        title,
+        description,
+        severity: Severity::Medium,
+        category: Category::Capacity,
+        impact: "MEDIUM: Each connection costs broker memory and network threads; a broker at its limit refuses new clients, and a hotspot saturates before the rest of the cluster.".to_string(),
+        evidence: Evidence {
+            metrics,
+            logs: Vec::new(),
+            configs,
+            raw_data: None,
+            timeline: Vec::new(),
+        },
+        root_cause: Some("Clients connecting to one broker only, leaking connections, or more clients than the brokers were sized for".to_string()),
+        remediation: Remediation {
+            steps: steps.iter().enumerate().map(|(i, step)| RemediationStep {
+                order: i as u32 + 1,
+                description: step.to_string(),
+                command: None,
+                verification: None,
+                can_automate: false,
+            }).collect(),
+            script: None,
+            risk_level: RiskLevel::Low,
+            requires_downtime: false,
+            estimated_duration_minutes: Some(60),
+            rollback_plan: None,
+        },
+        metadata: HashMap::new(),
+    }
+}
+
+#[async_trait]
+impl Analyzer for ConnectionAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let brokers = self.broker_connections(snapshot);
+        let total: usize = brokers.iter().map(|broker| broker.established).sum();
+
+        let mut findings = Vec::new();
+        for broker in &brokers {
+            if brokers.len() > 1 && broker.established >= self.min_hotspot_connections {
+                let others_average = (total - broker.established) as f64 / (brokers.len() - 1) as f64;
+                if broker.established as f64 >= others_average.max(1.0) * self.hotspot_factor {
+                    findings.push(self.hotspot_finding(snapshot, broker, others_average));
+                }
+            }
+            if let Some(max_connections) = broker.max_connections.filter(|max| *max > 0) {
+                if broker.established as f64 * 100.0 / max_connections as f64 >= self.limit_warning_percent {
+                    findings.push(self.limit_finding(snapshot, broker, max_connections));
+                }
+            }
+        }
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("CONN-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Connection Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags brokers with far more established connections than the others or close to max.connections"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    const NETSTAT: &str = "\
+Active Internet connections (w/o servers)
+Proto Recv-Q Send-Q Local Address           Foreign Address         State
+tcp        0      0 10.0.0.1:9092           10.0.1.15:53422         ESTABLISHED
+tcp        0      0 10.0.0.1:9092           10.0.1.16:40110         ESTABLISHED
+tcp        0      0 10.0.0.1:9092           10.0.1.17:40111         TIME_WAIT
+tcp        0      0 10.0.0.1:22             10.0.9.1:61000          ESTABLISHED
+tcp6       0      0 ::ffff:10.0.0.1:9093    ::ffff:10.0.0.2:38000   ESTABLISHED
+";
+
+    const SS: &str = "\
+State  Recv-Q Send-Q Local Address:Port     Peer Address:Port Process
+ESTAB  0      0      10.0.0.1:9092          10.0.1.15:53422
+ESTAB  0      0      [::ffff:10.0.0.1]:9093 [::ffff:10.0.0.2]:38000
+TIME-WAIT 0   0      10.0.0.1:9092          10.0.1.17:40111
+";
+
+    const SS_ESTABLISHED: &str = "\
+Recv-Q Send-Q Local Address:Port Peer Address:Port Process
+0      0      10.0.0.1:9092      10.0.1.15:53422
+0      0      10.0.0.1:9092      10.0.1.16:40110
+";
+
+    #[test]
+    fn test_parse_connection_tables() {
+        let netstat = parse_connections(NETSTAT);
+        assert_eq!(netstat.len(), 4);
+        assert_eq!(netstat[0], Connection { local_port: 9092, peer: "10.0.1.15".to_string() });
+        assert_eq!(netstat[3], Connection { local_port: 9093, peer: "::ffff:10.0.0.2".to_string() });
+
+        let ss = parse_connections(SS);
+        assert_eq!(ss.len(), 2);
+        assert_eq!(ss[1], Connection { local_port: 9093, peer: "::ffff:10.0.0.2".to_string() });
+
+        assert_eq!(parse_connections(SS_ESTABLISHED).len(), 2);
+    }
+
+    fn table(connections: usize) -> String {
+        (0..connections)
+            .map(|i| format!("tcp 0 0 10.0.0.1:9092 10.0.{}.{}:50000 ESTABLISHED\n", i / 250, i % 250))
+            .collect()
+    }
+
+    fn snapshot(brokers: &[(&str, String, &str)]) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let brokers: serde_json::Map<String, serde_json::Value> = brokers.iter()
+            .map(|(broker, connections, properties)| (broker.to_string(), json!({
+                "system": {CONNECTIONS_FILE: connections},
+                "configs": {"server.properties": properties},
+            })))
+            .collect();
+        snapshot.collectors.custom.insert("brokers".to_string(), json!(brokers));
+        snapshot
+    }
+
+    #[tokio::test]
+    async fn test_connection_counts_per_broker() {
+        let properties = "listeners=PLAINTEXT://0.0.0.0:9092,CONTROLLER://0.0.0.0:9093\n";
+        let snapshot = snapshot(&[("broker_1", NETSTAT.to_string(), properties), ("broker_2", SS.to_string(), "")]);
+
+        let counts: Vec<(String, usize)> = ConnectionAnalyzer::new().broker_connections(&snapshot).into_iter()
+            .map(|broker| (broker.broker, broker.established))
+            .collect();
+        assert_eq!(counts, vec![("broker_1".to_string(), 3), ("broker_2".to_string(), 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_hotspot_and_limit_flagged() {
+        let snapshot = snapshot(&[
+            ("broker_1", table(60), ""),
+            ("broker_2", table(900), ""),
+            ("broker_3", table(90), "max.connections=100\n"),
+        ]);
+        let findings = ConnectionAnalyzer::new().analyze(&snapshot).await.unwrap();
+
+        let titles: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(titles, vec!["Connection hotspot on broker_2", "broker_3 is close to its connection limit"]);
+        assert!(findings.iter().all(|f| f.severity == Severity::Medium && matches!(f.category, Category::Capacity)));
+        assert!(findings[0].description.contains("900 established connections, 12.0 times the 75"));
+        assert_eq!(findings[1].evidence.configs[0].current_value, "100");
+        assert_eq!(findings[1].id, "CONN-002");
+    }
+
+    #[tokio::test]
+    async fn test_balanced_cluster() {
+        let snapshot = snapshot(&[("broker_1", table(400), ""), ("broker_2", table(500), "")]);
+        assert!(ConnectionAnalyzer::new().analyze(&snapshot).await.unwrap().is_empty());
+        assert!(ConnectionAnalyzer::new().analyze(&Snapshot::new(SnapshotMetadata::new("test".to_string()))).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,340 @@
+use crate::analyzers::{Analyzer, AnalyzerError, AnalyzerResult};
+use crate::snapshot::format::{
+    Category, Evidence, Finding, MetricEvidence, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{debug, warn};
+
+/// Environment variable with the path of a YAML file of custom threshold rules
+pub const CUSTOM_RULES_FILE_ENV: &str = "KCPILOT_RULES_FILE";
+
+/// How the values matched by a rule's path are reduced before comparing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregate {
+    /// Compare every matched number on its own; the rule fires if any of them does
+    #[default]
+    Value,
+    /// Number of matches, or the length of a single matched array or object
+    Count,
+    Sum,
+    Min,
+    Max,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operator {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Eq,
+    Ne,
+}
+
+impl Operator {
+    fn matches(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Gt => value > threshold,
+            Self::Gte => value >= threshold,
+            Self::Lt => value < threshold,
+            Self::Lte => value <= threshold,
+            Self::Eq => value == threshold,
+            Self::Ne => value != threshold,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Gt => ">",
+            Self::Gte => ">=",
+            Self::Lt => "<",
+            Self::Lte => "<=",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+        }
+    }
+}
+
+/// A threshold check over a JSONPath into the snapshot
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomRule {
+    pub id: String,
+    /// JSONPath (RFC 9535) evaluated against the serialized snapshot
+    pub path: String,
+    #[serde(default)]
+    pub aggregate: Aggregate,
+    pub operator: Operator,
+    pub threshold: f64,
+    pub severity: Severity,
+    pub category: Category,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub impact: Option<String>,
+    /// Remediation steps, in order
+    #[serde(default)]
+    pub remediation: Vec<String>,
+}
+
+/// Top-level layout of a rules file
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    rules: Vec<CustomRule>,
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+/// Rule with its path compiled
+struct CompiledRule {
+    rule: CustomRule,
+    path: JsonPath,
+}
+
+impl CompiledRule {
+    /// Values of the matched nodes that break the threshold, after aggregation
+    fn violations(&self, snapshot: &Value) -> Vec<f64> {
+        let nodes = self.path.query(snapshot).all();
+        if nodes.is_empty() {
+            return Vec::new();
+        }
+
+        let numbers = || nodes.iter().filter_map(|node| number(node));
+        let values: Vec<f64> = match self.rule.aggregate {
+            Aggregate::Value => numbers().collect(),
+            Aggregate::Count => vec![match nodes.as_slice() {
+                [Value::Array(items)] => items.len() as f64,
+                [Value::Object(fields)] => fields.len() as f64,
+                _ => nodes.len() as f64,
+            }],
+            Aggregate::Sum => vec![numbers().sum()],
+            Aggregate::Min => numbers().reduce(f64::min).into_iter().collect(),
+            Aggregate::Max => numbers().reduce(f64::max).into_iter().collect(),
+        };
+
+        values.into_iter()
+            .filter(|&value| self.rule.operator.matches(value, self.rule.threshold))
+            .collect()
+    }
+
+    fn finding(&self, snapshot: &Snapshot, violations: &[f64]) -> Finding {
+        let rule = &self.rule;
+        let condition = format!("{} {} {}", rule.path, rule.operator.symbol(), rule.threshold);
+
+        Finding {
+            id: rule.id.clone(),
+            title: rule.title.clone(),
+            description: rule.description.clone().unwrap_or_else(|| format!(
+                "Custom rule {} matched {} value(s) where {}.", rule.id, violations.len(), condition
+            )),
+            severity: rule.severity,
+            category: rule.category,
+            impact: rule.impact.clone().unwrap_or_else(|| format!("Custom rule {} fired", rule.id)),
+            evidence: Evidence {
+                metrics: violations.iter().map(|&value| MetricEvidence {
+                    name: rule.path.clone(),
+                    value,
+                    threshold: Some(rule.threshold),
+                    unit: None,
+                    source: format!("custom rule {}", rule.id),
+                    timestamp: snapshot.timestamp,
+                }).collect(),
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some(format!("Snapshot value breaks the custom threshold {}", condition)),
+            remediation: Remediation {
+                steps: rule.remediation.iter().enumerate().map(|(i, step)| RemediationStep {
+                    order: i as u32 + 1,
+                    description: step.clone(),
+                    command: None,
+                    verification: None,
+                    can_automate: false,
+                }).collect(),
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: None,
+                rollback_plan: None,
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+/// Evaluates user-defined threshold rules loaded from YAML
+pub struct CustomRuleAnalyzer {
+    rules: Vec<CompiledRule>,
+}
+
+impl CustomRuleAnalyzer {
+    /// Compile the rules, failing on the first invalid path
+    pub fn new(rules: Vec<CustomRule>) -> AnalyzerResult<Self> {
+        let rules = rules.into_iter()
+            .map(|rule| {
+                let path = JsonPath::parse(&rule.path).map_err(|e| AnalyzerError::RuleError(
+                    format!("Invalid path '{}' in rule {}: {}", rule.path, rule.id, e)
+                ))?;
+                Ok(CompiledRule { rule, path })
+            })
+            .collect::<AnalyzerResult<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Parse a YAML document with a top-level `rules` list
+    pub fn from_yaml(yaml: &str) -> AnalyzerResult<Self> {
+        let file: RulesFile = serde_yaml::from_str(yaml)
+            .map_err(|e| AnalyzerError::InvalidData(format!("Invalid rules file: {}", e)))?;
+        Self::new(file.rules)
+    }
+
+    pub fn from_file(path: &Path) -> AnalyzerResult<Self> {
+        let yaml = std::fs::read_to_string(path)
+            .map_err(|e| AnalyzerError::InvalidData(format!("Failed to read rules {}: {}", path.display(), e)))?;
+        Self::from_yaml(&yaml)
+    }
+
+    /// Load the rules file named by `KCPILOT_RULES_FILE`, if set
+    pub fn from_env() -> AnalyzerResult<Option<Self>> {
+        std::env::var(CUSTOM_RULES_FILE_ENV).ok()
+            .map(|path| Self::from_file(Path::new(&path)))
+            .transpose()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+#[async_trait]
+impl Analyzer for CustomRuleAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let document = serde_json::to_value(snapshot)
+            .map_err(|e| AnalyzerError::AnalysisFailed(format!("Failed to serialize snapshot: {}", e)))?;
+
+        let mut findings = Vec::new();
+        for compiled in &self.rules {
+            let violations = compiled.violations(&document);
+            if violations.is_empty() {
+                debug!("Custom rule {} found no issues", compiled.rule.id);
+                continue;
+            }
+            warn!("Custom rule {} fired on {} value(s)", compiled.rule.id, violations.len());
+            findings.push(compiled.finding(snapshot, &violations));
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Custom Rule Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Evaluates user-defined threshold rules from a YAML file"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    const RULES: &str = r#"
+rules:
+  - id: CUSTOM-MIN-BROKERS
+    path: $.collectors.admin.brokers
+    aggregate: count
+    operator: lt
+    threshold: 3
+    severity: high
+    category: availability
+    title: Cluster has fewer than 3 brokers
+    remediation:
+      - Add brokers so topics can use a replication factor of 3
+  - id: CUSTOM-MAX-PARTITIONS
+    path: $.collectors.admin.topics[*].partition_count
+    operator: gt
+    threshold: 100
+    severity: low
+    category: performance
+    title: Topic with more than 100 partitions
+"#;
+
+    fn snapshot(brokers: usize) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.admin = Some(json!({
+            "brokers": (1..=brokers).map(|id| json!({"id": id})).collect::<Vec<_>>(),
+            "topics": [
+                {"name": "orders", "partition_count": 12},
+                {"name": "clickstream", "partition_count": 240},
+            ],
+        }));
+        snapshot
+    }
+
+    #[tokio::test]
+    async fn test_broker_count_rule_fires_on_small_cluster() {
+        let analyzer = CustomRuleAnalyzer::from_yaml(RULES).unwrap();
+        assert_eq!(analyzer.len(), 2);
+
+        let findings = analyzer.analyze(&snapshot(2)).await.unwrap();
+        let small = findings.iter().find(|f| f.id == "CUSTOM-MIN-BROKERS").unwrap();
+        assert_eq!(small.severity, Severity::High);
+        assert!(matches!(small.category, Category::Availability));
+        assert_eq!(small.evidence.metrics[0].value, 2.0);
+        assert_eq!(small.remediation.steps.len(), 1);
+
+        let findings = analyzer.analyze(&snapshot(3)).await.unwrap();
+        assert!(findings.iter().all(|f| f.id != "CUSTOM-MIN-BROKERS"));
+    }
+
+    #[tokio::test]
+    async fn test_value_rule_reports_each_offending_value() {
+        let analyzer = CustomRuleAnalyzer::from_yaml(RULES).unwrap();
+        let findings = analyzer.analyze(&snapshot(3)).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].id, "CUSTOM-MAX-PARTITIONS");
+        assert_eq!(findings[0].evidence.metrics.iter().map(|m| m.value).collect::<Vec<_>>(), vec![240.0]);
+    }
+
+    #[tokio::test]
+    async fn test_unmatched_path_is_skipped() {
+        // Without admin data there is nothing to count, so the broker rule stays quiet
+        let analyzer = CustomRuleAnalyzer::from_yaml(RULES).unwrap();
+        let empty = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        assert!(analyzer.analyze(&empty).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        let bad_path = RULES.replace("$.collectors.admin.brokers", "collectors[");
+        assert!(matches!(CustomRuleAnalyzer::from_yaml(&bad_path), Err(AnalyzerError::RuleError(_))));
+        assert!(matches!(
+            CustomRuleAnalyzer::from_yaml("rules:\n  - id: x\n    operator: between\n"),
+            Err(AnalyzerError::InvalidData(_))
+        ));
+    }
+}
@@ -0,0 +1,341 @@
+use crate::analyzers::{Analyzer, AnalyzerResult};
+use crate::snapshot::format::{
+    Category, Evidence, Finding, MetricEvidence, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+
+/// Environment variable with the number of days ahead within which a filling disk is reported
+pub const DISK_FULL_HORIZON_DAYS_ENV: &str = "DISK_FULL_HORIZON_DAYS";
+
+pub const DEFAULT_DISK_FULL_HORIZON_DAYS: f64 = 7.0;
+
+/// Pseudo filesystems never holding Kafka log directories
+const IGNORED_FILESYSTEMS: [&str; 5] = ["tmpfs", "devtmpfs", "udev", "overlay", "shm"];
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Usage of one mounted filesystem from `df` output
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiskUsage {
+    pub mount: String,
+    pub size_bytes: f64,
+    pub used_bytes: f64,
+}
+
+/// Where a broker's filesystem is heading, from two usage samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiskProjection {
+    pub broker: String,
+    pub mount: String,
+    pub used_bytes: f64,
+    pub size_bytes: f64,
+    pub growth_bytes_per_day: f64,
+    /// Days until the filesystem is full at the current growth rate, `None` when not growing
+    pub days_to_full: Option<f64>,
+}
+
+/// `1.5G` -> bytes, using the binary units of `df -h`
+fn parse_size(value: &str, block_size: f64) -> Option<f64> {
+    let value = value.trim();
+    let (number, multiplier) = match value.chars().last()? {
+        'K' | 'k' => (&value[..value.len() - 1], 1024f64),
+        'M' => (&value[..value.len() - 1], 1024f64.powi(2)),
+        'G' => (&value[..value.len() - 1], 1024f64.powi(3)),
+        'T' => (&value[..value.len() - 1], 1024f64.powi(4)),
+        'P' => (&value[..value.len() - 1], 1024f64.powi(5)),
+        _ => (value, block_size),
+    };
+    number.parse::<f64>().ok().map(|n| n * multiplier)
+}
+
+/// Parse `df` or `df -h` output into per-mount usage, skipping pseudo filesystems
+pub fn parse_df(output: &str) -> Vec<DiskUsage> {
+    let mut lines = output.lines();
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    // Plain `df` reports 1K blocks, `df -h` suffixed sizes
+    let block_size = if header.contains("1K-blocks") { 1024.0 } else { 1.0 };
+
+    lines
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.len() < 6 || IGNORED_FILESYSTEMS.contains(&columns[0]) {
+                return None;
+            }
+            Some(DiskUsage {
+                mount: columns[5..].join(" "),
+                size_bytes: parse_size(columns[1], block_size)?,
+                used_bytes: parse_size(columns[2], block_size)?,
+            })
+        })
+        .filter(|usage| usage.size_bytes > 0.0)
+        .collect()
+}
+
+/// Disk usage of every scanned broker, from the collected `df -h` output
+pub fn disk_usage(snapshot: &Snapshot) -> BTreeMap<String, Vec<DiskUsage>> {
+    let Some(brokers) = snapshot.collectors.custom.get("brokers").and_then(|b| b.as_object()) else {
+        return BTreeMap::new();
+    };
+
+    brokers.iter()
+        .filter_map(|(broker, data)| {
+            let df = data.get("system")?.get("disk.txt")?.as_str()?;
+            Some((broker.clone(), parse_df(df)))
+        })
+        .filter(|(_, usage)| !usage.is_empty())
+        .collect()
+}
+
+/// Project time-to-full for every broker filesystem present in both snapshots
+pub fn project_disk_usage(previous: &Snapshot, current: &Snapshot) -> Vec<DiskProjection> {
+    let elapsed_days = (current.timestamp - previous.timestamp).num_seconds() as f64 / SECONDS_PER_DAY;
+    if elapsed_days <= 0.0 {
+        return Vec::new();
+    }
+
+    let before = disk_usage(previous);
+    let mut projections = Vec::new();
+    for (broker, mounts) in disk_usage(current) {
+        let Some(before_mounts) = before.get(&broker) else {
+            continue;
+        };
+        for usage in mounts {
+            let Some(earlier) = before_mounts.iter().find(|m| m.mount == usage.mount) else {
+                continue;
+            };
+            let growth_bytes_per_day = (usage.used_bytes - earlier.used_bytes) / elapsed_days;
+            let free_bytes = (usage.size_bytes - usage.used_bytes).max(0.0);
+            let days_to_full = (growth_bytes_per_day > 0.0).then(|| free_bytes / growth_bytes_per_day);
+            projections.push(DiskProjection {
+                broker: broker.clone(),
+                mount: usage.mount,
+                used_bytes: usage.used_bytes,
+                size_bytes: usage.size_bytes,
+                growth_bytes_per_day,
+                days_to_full,
+            });
+        }
+    }
+    projections
+}
+
+fn gib(bytes: f64) -> f64 {
+    bytes / 1024f64.powi(3)
+}
+
+/// Compares broker disk usage with an earlier snapshot and flags filesystems
+/// projected to fill up within the horizon
+pub struct DiskGrowthAnalyzer {
+    previous: Snapshot,
+    horizon_days: f64,
+}
+
+impl DiskGrowthAnalyzer {
+    pub fn new(previous: Snapshot) -> Self {
+        Self {
+            previous,
+            horizon_days: DEFAULT_DISK_FULL_HORIZON_DAYS,
+        }
+    }
+
+    /// Report filesystems projected to be full within this many days
+    pub fn with_horizon_days(mut self, days: f64) -> Self {
+        self.horizon_days = days;
+        self
+    }
+
+    /// Build the analyzer with the horizon from `DISK_FULL_HORIZON_DAYS`, if set
+    pub fn from_env(previous: Snapshot) -> Self {
+        match std::env::var(DISK_FULL_HORIZON_DAYS_ENV).ok().and_then(|v| v.trim().parse::<f64>().ok()) {
+            Some(days) if days > 0.0 => Self::new(previous).with_horizon_days(days),
+            _ => Self::new(previous),
+        }
+    }
+
+    fn finding(&self, snapshot: &Snapshot, projection: &DiskProjection, days_to_full: f64) -> Finding {
+        let metric = |name: &str, value: f64, threshold: Option<f64>, unit: &str| MetricEvidence {
+            name: format!("{} {}", projection.mount, name),
+            value,
+            threshold,
+            unit: Some(unit.to_string()),
+            source: format!("{}/system/disk.txt", projection.broker),
+            timestamp: snapshot.timestamp,
+        };
+
+        Finding {
+            id: String::new(),
+            title: format!(
+                "{} on {} projected to fill in {:.1} day(s)",
+                projection.mount, projection.broker, days_to_full
+            ),
+            description: format!(
+                "{} on {} grew by {:.1} GiB/day since the snapshot of {}. With {:.1} of {:.1} GiB used, \
+                it will be full in about {:.1} day(s), within the {:.0}-day horizon.",
+                projection.mount,
+                projection.broker,
+                gib(projection.growth_bytes_per_day),
+                self.previous.timestamp.format("%Y-%m-%d %H:%M UTC"),
+                gib(projection.used_bytes),
+                gib(projection.size_bytes),
+                days_to_full,
+                self.horizon_days
+            ),
+            severity: Severity::High,
+            category: Category::Capacity,
+            impact: "HIGH: A broker whose log directory fills up stops accepting writes and takes its partitions offline.".to_string(),
+            evidence: Evidence {
+                metrics: vec![
+                    metric("growth", gib(projection.growth_bytes_per_day), None, "GiB/day"),
+                    metric("used", gib(projection.used_bytes), Some(gib(projection.size_bytes)), "GiB"),
+                    metric("days to full", days_to_full, Some(self.horizon_days), "days"),
+                ],
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Data is written faster than log retention deletes old segments".to_string()),
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Find the topics growing fastest on the broker".to_string(),
+                        command: Some("kafka-log-dirs.sh --bootstrap-server <broker> --describe --broker-list <id>".to_string()),
+                        verification: None,
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Lower retention.ms or retention.bytes on those topics, or add disk capacity".to_string(),
+                        command: Some(
+                            "kafka-configs.sh --bootstrap-server <broker> --entity-type topics --entity-name <topic> --alter --add-config retention.ms=<ms>".to_string()
+                        ),
+                        verification: Some(format!("df -h {}", projection.mount)),
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(60),
+                rollback_plan: Some("Restore the previous retention settings".to_string()),
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for DiskGrowthAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let mut findings: Vec<Finding> = project_disk_usage(&self.previous, snapshot).iter()
+            .filter_map(|projection| {
+                let days_to_full = projection.days_to_full.filter(|&days| days <= self.horizon_days)?;
+                Some(self.finding(snapshot, projection, days_to_full))
+            })
+            .collect();
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("DISK-GROWTH-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Disk Growth Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Projects when broker disks fill up from usage growth between two snapshots"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use chrono::{Duration, TimeZone, Utc};
+    use serde_json::json;
+
+    fn df(kafka_used: &str) -> String {
+        format!(
+            "Filesystem      Size  Used Avail Use% Mounted on\n\
+            tmpfs           1.6G  2.1M  1.6G   1% /run\n\
+            /dev/sda1        50G   12G   38G  24% /\n\
+            /dev/sdb1       1.0T  {} 100G  90% /var/lib/kafka\n",
+            kafka_used
+        )
+    }
+
+    fn snapshot(hours: i64, brokers: &[(&str, &str)]) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.timestamp = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap() + Duration::hours(hours);
+        let brokers: serde_json::Map<String, serde_json::Value> = brokers.iter()
+            .map(|(broker, used)| (broker.to_string(), json!({"system": {"disk.txt": df(used)}})))
+            .collect();
+        snapshot.collectors.custom.insert("brokers".to_string(), json!(brokers));
+        snapshot
+    }
+
+    #[test]
+    fn test_parse_df() {
+        let usage = parse_df(&df("900G"));
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[1], DiskUsage {
+            mount: "/var/lib/kafka".to_string(),
+            size_bytes: 1024f64.powi(4),
+            used_bytes: 900.0 * 1024f64.powi(3),
+        });
+
+        let blocks = "Filesystem 1K-blocks Used Available Use% Mounted on\n/dev/sdb1 1000 250 750 25% /data\n";
+        assert_eq!(parse_df(blocks)[0].used_bytes, 250.0 * 1024.0);
+    }
+
+    #[tokio::test]
+    async fn test_rapid_growth_projected_to_fill() {
+        // 824G -> 874G in two days: 25 GiB/day with 150 GiB free leaves 6 days
+        let previous = snapshot(0, &[("broker_1", "824G"), ("broker_2", "500G")]);
+        let current = snapshot(48, &[("broker_1", "874G"), ("broker_2", "502G")]);
+
+        let projections = project_disk_usage(&previous, &current);
+        let kafka_1 = projections.iter()
+            .find(|p| p.broker == "broker_1" && p.mount == "/var/lib/kafka")
+            .unwrap();
+        assert_eq!(gib(kafka_1.growth_bytes_per_day), 25.0);
+        assert_eq!(kafka_1.days_to_full, Some(6.0));
+
+        let findings = DiskGrowthAnalyzer::new(previous).analyze(&current).await.unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].id, "DISK-GROWTH-001");
+        assert_eq!(findings[0].severity, Severity::High);
+        assert!(matches!(findings[0].category, Category::Capacity));
+        assert!(findings[0].title.contains("/var/lib/kafka on broker_1 projected to fill in 6.0 day(s)"));
+    }
+
+    #[tokio::test]
+    async fn test_growth_beyond_horizon_or_shrinking_not_reported() {
+        let previous = snapshot(0, &[("broker_1", "824G")]);
+        let current = snapshot(48, &[("broker_1", "874G")]);
+        let findings = DiskGrowthAnalyzer::new(previous.clone()).with_horizon_days(5.0).analyze(&current).await.unwrap();
+        assert!(findings.is_empty());
+
+        let shrinking = snapshot(48, &[("broker_1", "800G")]);
+        let projections = project_disk_usage(&previous, &shrinking);
+        assert!(projections.iter().all(|p| p.days_to_full.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_requires_two_data_points() {
+        // Broker missing from the earlier snapshot, and snapshots taken out of order
+        let previous = snapshot(0, &[("broker_1", "824G")]);
+        let current = snapshot(48, &[("broker_2", "1000G")]);
+        assert!(DiskGrowthAnalyzer::new(previous.clone()).analyze(&current).await.unwrap().is_empty());
+
+        let earlier = snapshot(-48, &[("broker_1", "500G")]);
+        assert!(project_disk_usage(&previous, &earlier).is_empty());
+    }
+}
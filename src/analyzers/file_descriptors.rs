@@ -0,0 +1,201 @@
+use crate::analyzers::{Analyzer, AnalyzerResult};
+use crate::scan::file_descriptors::{FileDescriptorUsage, FILE_DESCRIPTORS_FILE};
+use crate::snapshot::format::{
+    Category, Evidence, Finding, MetricEvidence, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// Share of the open files limit in use from which a broker is reported
+const DEFAULT_WARNING_PERCENT: f64 = 80.0;
+
+/// Soft `Max open files` limit of a `/proc/PID/limits` file, the one the kernel enforces.
+/// `None` when the limit is missing or unlimited.
+pub fn parse_open_files_limit(limits: &str) -> Option<u64> {
+    limits.lines()
+        .find_map(|line| line.strip_prefix("Max open files"))
+        .and_then(|values| values.split_whitespace().next())
+        .and_then(|soft| soft.parse().ok())
+}
+
+/// Percentage of `limit` used by `open` descriptors
+pub fn fd_utilization(open: u64, limit: u64) -> f64 {
+    if limit == 0 {
+        return 100.0;
+    }
+    open as f64 * 100.0 / limit as f64
+}
+
+/// Flags brokers whose Kafka process is close to its open files limit
+pub struct FileDescriptorAnalyzer {
+    warning_percent: f64,
+}
+
+impl Default for FileDescriptorAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileDescriptorAnalyzer {
+    pub fn new() -> Self {
+        Self { warning_percent: DEFAULT_WARNING_PERCENT }
+    }
+
+    /// Report brokers using at least `percent` of their open files limit (default: 80)
+    pub fn with_warning_percent(mut self, percent: f64) -> Self {
+        self.warning_percent = percent;
+        self
+    }
+
+    fn finding(&self, snapshot: &Snapshot, broker: &str, open: u64, limit: u64) -> Finding {
+        let percent = fd_utilization(open, limit);
+        Finding {
+            id: String::new(),
+            title: format!("{} is close to its open files limit", broker),
+            description: format!(
+                "The Kafka process on {} holds {} open file descriptors, {:.0}% of its limit of {}. \
+                 Every log segment, index and client connection takes a descriptor.",
+                broker, open, percent, limit
+            ),
+            severity: Severity::High,
+            category: Category::Capacity,
+            impact: "HIGH: Once the limit is reached the broker fails with \"Too many open files\", cannot roll segments or accept connections, and usually shuts down.".to_string(),
+            evidence: Evidence {
+                metrics: vec![MetricEvidence {
+                    name: "open_file_descriptors".to_string(),
+                    value: open as f64,
+                    threshold: Some(limit as f64 * self.warning_percent / 100.0),
+                    unit: Some("descriptors".to_string()),
+                    source: format!("{}/system/{}", broker, FILE_DESCRIPTORS_FILE),
+                    timestamp: snapshot.timestamp,
+                }],
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("An open files limit sized below the broker's partitions, segments and connections".to_string()),
+            remediation: Remediation {
+                steps: [
+                    "Raise the limit of the Kafka service (LimitNOFILE=100000 or more in its systemd unit, or nofile in /etc/security/limits.conf) and restart the broker",
+                    "Check which files dominate (sudo ls -l /proc/$(pgrep -f 'kafka\\.Kafka')/fd | awk '{print $NF}' | sed 's|/[^/]*$||' | sort | uniq -c | sort -rn | head)",
+                    "Reduce segment count where it is excessive by raising segment.bytes or segment.ms on topics with many small segments",
+                ].iter().enumerate().map(|(i, step)| RemediationStep {
+                    order: i as u32 + 1,
+                    description: step.to_string(),
+                    command: None,
+                    verification: None,
+                    can_automate: false,
+                }).collect(),
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(30),
+                rollback_plan: None,
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for FileDescriptorAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let Some(brokers) = snapshot.collectors.custom.get("brokers").and_then(|b| b.as_object()) else {
+            return Ok(Vec::new());
+        };
+
+        let mut usages: Vec<(&String, FileDescriptorUsage)> = brokers.iter()
+            .filter_map(|(name, broker)| {
+                let usage = broker.get("system")?.get(FILE_DESCRIPTORS_FILE)?;
+                Some((name, serde_json::from_value(usage.clone()).ok()?))
+            })
+            .collect();
+        usages.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut findings = Vec::new();
+        for (broker, usage) in usages {
+            // Unreadable /proc entries or an unlimited limit leave nothing to compare
+            let (Some(open), Some(limit)) = (usage.open_fds, parse_open_files_limit(&usage.limits)) else {
+                continue;
+            };
+            if fd_utilization(open, limit) >= self.warning_percent {
+                findings.push(self.finding(snapshot, broker, open, limit));
+            }
+        }
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("FD-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "File Descriptor Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags brokers whose Kafka process uses most of its open files limit"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    fn limits(soft: &str) -> String {
+        format!("\
+Limit                     Soft Limit           Hard Limit           Units
+Max cpu time              unlimited            unlimited            seconds
+Max processes             63704                63704                processes
+Max open files            {}               262144               files
+Max locked memory         65536                65536                bytes
+", soft)
+    }
+
+    #[test]
+    fn test_parse_limits_and_utilization() {
+        assert_eq!(parse_open_files_limit(&limits("100000")), Some(100000));
+        assert_eq!(parse_open_files_limit(&limits("unlimited")), None);
+        assert_eq!(parse_open_files_limit(""), None);
+
+        assert_eq!(fd_utilization(850, 1000), 85.0);
+        assert_eq!(fd_utilization(10, 0), 100.0);
+    }
+
+    fn snapshot(brokers: &[(&str, Option<u64>, &str)]) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let brokers: serde_json::Map<String, serde_json::Value> = brokers.iter()
+            .map(|(broker, open_fds, soft)| (broker.to_string(), json!({
+                "system": {FILE_DESCRIPTORS_FILE: FileDescriptorUsage { pid: 4242, open_fds: *open_fds, limits: limits(soft) }},
+            })))
+            .collect();
+        snapshot.collectors.custom.insert("brokers".to_string(), json!(brokers));
+        snapshot
+    }
+
+    #[tokio::test]
+    async fn test_brokers_near_limit_flagged() {
+        let snapshot = snapshot(&[
+            ("broker_1", Some(9000), "10000"),
+            ("broker_2", Some(2000), "10000"),
+            ("broker_3", None, "10000"),
+            ("broker_4", Some(90000), "unlimited"),
+        ]);
+        let findings = FileDescriptorAnalyzer::new().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].id, "FD-001");
+        assert_eq!(findings[0].title, "broker_1 is close to its open files limit");
+        assert_eq!(findings[0].severity, Severity::High);
+        assert!(matches!(findings[0].category, Category::Capacity));
+        assert!(findings[0].description.contains("9000 open file descriptors, 90% of its limit of 10000"));
+
+        let lenient = FileDescriptorAnalyzer::new().with_warning_percent(95.0);
+        assert!(lenient.analyze(&snapshot).await.unwrap().is_empty());
+    }
+}
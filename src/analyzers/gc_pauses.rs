@@ -0,0 +1,251 @@
+use crate::analyzers::{Analyzer, AnalyzerResult};
+use crate::scan::log_discovery::gc_parser::{GcLogParser, GcMetrics};
+use crate::snapshot::format::{
+    Category, Evidence, Finding, MetricEvidence, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// Pauses longer than this stall replica fetches long enough to risk ISR shrinks
+const DEFAULT_LONG_PAUSE_MS: f64 = 1000.0;
+
+/// Long pauses needed in one log before they count as frequent
+const DEFAULT_MIN_LONG_PAUSES: usize = 3;
+
+/// Share of time spent paused above which the broker is GC-bound
+const DEFAULT_MAX_GC_TIME_PERCENT: f64 = 5.0;
+
+/// Pause statistics of one broker's GC log
+#[derive(Debug, Clone)]
+pub struct BrokerGcLog {
+    pub broker: String,
+    /// Path of the log within the scan, e.g. `broker_1/logs/gc_kafkaServer-gc.log`
+    pub source: String,
+    pub metrics: GcMetrics,
+}
+
+/// Flags brokers whose GC logs show frequent long pauses or a high share of time in GC
+pub struct GcPauseAnalyzer {
+    long_pause_ms: f64,
+    min_long_pauses: usize,
+    max_gc_time_percent: f64,
+}
+
+impl Default for GcPauseAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GcPauseAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            long_pause_ms: DEFAULT_LONG_PAUSE_MS,
+            min_long_pauses: DEFAULT_MIN_LONG_PAUSES,
+            max_gc_time_percent: DEFAULT_MAX_GC_TIME_PERCENT,
+        }
+    }
+
+    /// Flag brokers with at least `min_count` pauses longer than `pause_ms`
+    pub fn with_long_pauses(mut self, pause_ms: f64, min_count: usize) -> Self {
+        self.long_pause_ms = pause_ms;
+        self.min_long_pauses = min_count.max(1);
+        self
+    }
+
+    pub fn with_max_gc_time_percent(mut self, percent: f64) -> Self {
+        self.max_gc_time_percent = percent;
+        self
+    }
+
+    /// Parsed GC logs of every scanned broker; logs without any pause are skipped
+    pub fn broker_gc_logs(&self, snapshot: &Snapshot) -> Vec<BrokerGcLog> {
+        let Some(brokers) = snapshot.collectors.custom.get("brokers").and_then(|b| b.as_object()) else {
+            return Vec::new();
+        };
+
+        let mut logs: Vec<BrokerGcLog> = brokers.iter()
+            .flat_map(|(name, broker)| {
+                broker.get("logs")
+                    .and_then(|logs| logs.as_object())
+                    .into_iter()
+                    .flatten()
+                    .filter(|(log, _)| log.to_lowercase().contains("gc"))
+                    .filter_map(move |(log, content)| {
+                        let pauses = GcLogParser::parse(content.as_str()?);
+                        (!pauses.is_empty()).then(|| BrokerGcLog {
+                            broker: name.clone(),
+                            source: format!("{}/logs/{}", name, log),
+                            metrics: GcMetrics::from_pauses(&pauses, self.long_pause_ms),
+                        })
+                    })
+            })
+            .collect();
+        logs.sort_by(|a, b| a.source.cmp(&b.source));
+        logs
+    }
+
+    fn finding(&self, snapshot: &Snapshot, log: &BrokerGcLog) -> Option<Finding> {
+        let metrics = &log.metrics;
+        let frequent_long_pauses = metrics.long_pause_count >= self.min_long_pauses;
+        let gc_bound = metrics.gc_time_percent.is_some_and(|percent| percent > self.max_gc_time_percent);
+        if !frequent_long_pauses && !gc_bound {
+            return None;
+        }
+
+        let mut reasons = Vec::new();
+        if frequent_long_pauses {
+            reasons.push(format!(
+                "{} of {} pauses exceeded {:.0} ms, the longest taking {:.0} ms",
+                metrics.long_pause_count, metrics.pause_count, self.long_pause_ms, metrics.max_pause_ms
+            ));
+        }
+        if let (true, Some(percent), Some(span)) = (gc_bound, metrics.gc_time_percent, metrics.span_secs) {
+            reasons.push(format!("the JVM was paused {:.1}% of the {:.0} s covered by the log", percent, span));
+        }
+
+        let metric = |name: &str, value: f64, threshold: Option<f64>, unit: &str| MetricEvidence {
+            name: name.to_string(),
+            value,
+            threshold,
+            unit: Some(unit.to_string()),
+            source: log.source.clone(),
+            timestamp: snapshot.timestamp,
+        };
+        let mut metrics_evidence = vec![
+            metric("gc_max_pause", metrics.max_pause_ms, Some(self.long_pause_ms), "ms"),
+            metric("gc_long_pauses", metrics.long_pause_count as f64, Some(self.min_long_pauses as f64), "pauses"),
+        ];
+        if let Some(percent) = metrics.gc_time_percent {
+            metrics_evidence.push(metric("gc_time", percent, Some(self.max_gc_time_percent), "%"));
+        }
+        if let Some(rate) = metrics.pauses_per_minute {
+            metrics_evidence.push(metric("gc_pause_rate", rate, None, "pauses/min"));
+        }
+
+        let steps = [
+            "Check heap usage after collections in the GC log; if the old generation stays full, raise -Xmx (Kafka rarely needs more than 6-8 GB since data lives in the page cache)",
+            "Use G1 with a pause target, e.g. KAFKA_JVM_PERFORMANCE_OPTS=\"-XX:+UseG1GC -XX:MaxGCPauseMillis=20 -XX:InitiatingHeapOccupancyPercent=35\"",
+            "Look for Full GC causes such as humongous allocations or metaspace exhaustion and size the regions or metaspace accordingly",
+            "Restart the broker to apply the JVM options and compare pause times in the new GC log",
+        ];
+
+        Some(Finding {
+            id: String::new(),
+            title: format!("Long GC pauses on {}", log.broker),
+            description: format!("The GC log of {} shows that {}.", log.broker, reasons.join(", and ")),
+            severity: Severity::High,
+            category: Category::Performance,
+            impact: "HIGH: While the JVM is paused the broker serves no requests and stops fetching, so followers fall out of the ISR and clients time out.".to_string(),
+            evidence: Evidence {
+                metrics: metrics_evidence,
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Heap too small or too large for the workload, or a collector not tuned for low pause times".to_string()),
+            remediation: Remediation {
+                steps: steps.iter().enumerate().map(|(i, step)| RemediationStep {
+                    order: i as u32 + 1,
+                    description: step.to_string(),
+                    command: None,
+                    verification: None,
+                    can_automate: false,
+                }).collect(),
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(60),
+                rollback_plan: Some("Restore the previous KAFKA_HEAP_OPTS and KAFKA_JVM_PERFORMANCE_OPTS and restart the broker".to_string()),
+            },
+            metadata: HashMap::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl Analyzer for GcPauseAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let mut findings: Vec<Finding> = self.broker_gc_logs(snapshot).iter()
+            .filter_map(|log| self.finding(snapshot, log))
+            .collect();
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("GC-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "GC Pause Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags brokers with frequent long GC pauses or a high share of time spent in GC"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    /// Unified G1 log with a young pause every 10 s and `full` Full GCs of 1.5 s
+    fn gc_log(full: usize) -> String {
+        (0..10)
+            .map(|i| {
+                let uptime = 10.0 * (i + 1) as f64;
+                if i < full {
+                    format!("[{:.3}s][info][gc] GC({}) Pause Full (G1 Compaction Pause) 1000M->400M(1024M) 1500.000ms", uptime, i)
+                } else {
+                    format!("[{:.3}s][info][gc] GC({}) Pause Young (Normal) (G1 Evacuation Pause) 512M->128M(1024M) 12.000ms", uptime, i)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn snapshot(logs: &[(&str, String)]) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let brokers: serde_json::Map<String, serde_json::Value> = logs.iter()
+            .map(|(broker, log)| (broker.to_string(), json!({"logs": {"gc_kafkaServer-gc.log": log, "server.log": "INFO started"}})))
+            .collect();
+        snapshot.collectors.custom.insert("brokers".to_string(), json!(brokers));
+        snapshot
+    }
+
+    #[tokio::test]
+    async fn test_frequent_long_pauses_flagged() {
+        let snapshot = snapshot(&[("broker_1", gc_log(0)), ("broker_2", gc_log(4))]);
+        let findings = GcPauseAnalyzer::new().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.id, "GC-001");
+        assert_eq!(finding.title, "Long GC pauses on broker_2");
+        assert_eq!(finding.severity, Severity::High);
+        assert!(matches!(finding.category, Category::Performance));
+        assert!(finding.description.contains("4 of 10 pauses exceeded 1000 ms"));
+        assert_eq!(finding.evidence.metrics[0].source, "broker_2/logs/gc_kafkaServer-gc.log");
+    }
+
+    #[tokio::test]
+    async fn test_time_in_gc_flagged() {
+        // Two 1.5 s pauses in 90 s are not frequent, but add up to over 3% of the time
+        let snapshot = snapshot(&[("broker_1", gc_log(2))]);
+        assert!(GcPauseAnalyzer::new().analyze(&snapshot).await.unwrap().is_empty());
+
+        let findings = GcPauseAnalyzer::new().with_max_gc_time_percent(3.0).analyze(&snapshot).await.unwrap();
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].description.contains("paused 3.4% of the 90 s"));
+    }
+
+    #[tokio::test]
+    async fn test_no_gc_logs() {
+        let snapshot = snapshot(&[]);
+        assert!(GcPauseAnalyzer::new().analyze(&snapshot).await.unwrap().is_empty());
+    }
+}
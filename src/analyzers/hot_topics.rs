@@ -0,0 +1,388 @@
+use crate::analyzers::{Analyzer, AnalyzerResult};
+use crate::collectors::prometheus_scrape::{PrometheusScrapeOutput, PROMETHEUS_METRICS_KEY};
+use crate::snapshot::format::{
+    Category, Evidence, Finding, MetricEvidence, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+
+/// Environment variable with the share of cluster throughput, in percent, above which a topic is a hotspot
+pub const HOT_TOPIC_SHARE_ENV: &str = "HOT_TOPIC_SHARE";
+
+pub const DEFAULT_HOT_TOPIC_SHARE_PERCENT: f64 = 50.0;
+
+/// Number of topics listed in the throughput ranking
+const TOP_TOPICS: usize = 5;
+
+/// Inbound throughput of one topic, summed over all scraped brokers
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicThroughput {
+    pub topic: String,
+    pub bytes_in_per_sec: f64,
+    pub messages_in_per_sec: f64,
+}
+
+/// How well a sample name describes a current rate; lower is better.
+/// JMX exporters publish the same meter as several series (`_oneminuterate`, `_meanrate`, `_count`, ...).
+fn rate_preference(sample_name: &str) -> u8 {
+    if sample_name.contains("oneminuterate") {
+        0
+    } else if sample_name.contains("fiveminuterate") {
+        1
+    } else if sample_name.contains("meanrate") {
+        2
+    } else {
+        3
+    }
+}
+
+/// Per-topic `BrokerTopicMetrics` BytesInPerSec / MessagesInPerSec from scraped broker
+/// metrics, highest byte rate first
+pub fn topic_throughput(snapshot: &Snapshot) -> Vec<TopicThroughput> {
+    let Some(scrape) = snapshot.collectors.metrics.as_ref()
+        .and_then(|metrics| metrics.get(PROMETHEUS_METRICS_KEY))
+        .and_then(|scrape| serde_json::from_value::<PrometheusScrapeOutput>(scrape.clone()).ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut totals: BTreeMap<String, TopicThroughput> = BTreeMap::new();
+    for endpoint in scrape.endpoints.values() {
+        // (topic, is_bytes) -> (preference, value) of the best series on this broker
+        let mut best: HashMap<(&str, bool), (u8, f64)> = HashMap::new();
+        for (family_name, family) in &endpoint.families {
+            let family_name = family_name.to_lowercase();
+            if !family_name.contains("brokertopicmetrics") {
+                continue;
+            }
+            let is_bytes = if family_name.contains("bytesin") {
+                true
+            } else if family_name.contains("messagesin") {
+                false
+            } else {
+                continue;
+            };
+
+            for sample in &family.samples {
+                let Some(topic) = sample.labels.get("topic") else {
+                    continue;
+                };
+                let preference = rate_preference(&sample.name.to_lowercase());
+                let entry = best.entry((topic.as_str(), is_bytes)).or_insert((u8::MAX, 0.0));
+                if preference < entry.0 {
+                    *entry = (preference, sample.value);
+                }
+            }
+        }
+
+        for ((topic, is_bytes), (_, value)) in best {
+            let total = totals.entry(topic.to_string()).or_insert_with(|| TopicThroughput {
+                topic: topic.to_string(),
+                bytes_in_per_sec: 0.0,
+                messages_in_per_sec: 0.0,
+            });
+            if is_bytes {
+                total.bytes_in_per_sec += value;
+            } else {
+                total.messages_in_per_sec += value;
+            }
+        }
+    }
+
+    let mut topics: Vec<TopicThroughput> = totals.into_values().collect();
+    topics.sort_by(|a, b| {
+        b.bytes_in_per_sec.total_cmp(&a.bytes_in_per_sec)
+            .then(b.messages_in_per_sec.total_cmp(&a.messages_in_per_sec))
+            .then(a.topic.cmp(&b.topic))
+    });
+    topics
+}
+
+/// Ranks topics by inbound throughput and flags topics taking most of the cluster's traffic
+pub struct HotTopicAnalyzer {
+    hotspot_share_percent: f64,
+}
+
+impl Default for HotTopicAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HotTopicAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            hotspot_share_percent: DEFAULT_HOT_TOPIC_SHARE_PERCENT,
+        }
+    }
+
+    /// Report a topic taking more than this percentage of cluster throughput
+    pub fn with_hotspot_share(mut self, percent: f64) -> Self {
+        self.hotspot_share_percent = percent;
+        self
+    }
+
+    /// Build the analyzer with the hotspot share from `HOT_TOPIC_SHARE`, if set
+    pub fn from_env() -> Self {
+        match std::env::var(HOT_TOPIC_SHARE_ENV).ok().and_then(|v| v.trim().trim_end_matches('%').parse::<f64>().ok()) {
+            Some(percent) if percent > 0.0 && percent <= 100.0 => Self::new().with_hotspot_share(percent),
+            _ => Self::new(),
+        }
+    }
+
+    fn metric(snapshot: &Snapshot, topic: &TopicThroughput, by_bytes: bool) -> MetricEvidence {
+        let (name, value, unit) = if by_bytes {
+            ("BytesInPerSec", topic.bytes_in_per_sec, "bytes/s")
+        } else {
+            ("MessagesInPerSec", topic.messages_in_per_sec, "messages/s")
+        };
+        MetricEvidence {
+            name: format!("kafka.server:type=BrokerTopicMetrics,name={},topic={}", name, topic.topic),
+            value,
+            threshold: None,
+            unit: Some(unit.to_string()),
+            source: format!("metrics/{}", PROMETHEUS_METRICS_KEY),
+            timestamp: snapshot.timestamp,
+        }
+    }
+
+    fn ranking_finding(snapshot: &Snapshot, ranked: &[(&TopicThroughput, f64)], by_bytes: bool) -> Finding {
+        let summary = ranked.iter()
+            .map(|(topic, share)| format!("{} ({:.1}%)", topic.topic, share))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Finding {
+            id: String::new(),
+            title: format!("Top {} topic(s) by inbound throughput", ranked.len()),
+            description: format!(
+                "Topics taking the largest share of inbound {} across the cluster: {}.",
+                if by_bytes { "bytes" } else { "messages" },
+                summary
+            ),
+            severity: Severity::Info,
+            category: Category::Capacity,
+            impact: "INFO: These topics drive broker network, disk and replication load; plan capacity around them.".to_string(),
+            evidence: Evidence {
+                metrics: ranked.iter().map(|(topic, _)| Self::metric(snapshot, topic, by_bytes)).collect(),
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: vec![RemediationStep {
+                    order: 1,
+                    description: "Check that the busiest topics have enough partitions, spread evenly over brokers".to_string(),
+                    command: Some("kafka-topics.sh --bootstrap-server <broker> --describe --topic <topic>".to_string()),
+                    verification: None,
+                    can_automate: false,
+                }],
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: None,
+                rollback_plan: None,
+            },
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn hotspot_finding(&self, snapshot: &Snapshot, topic: &TopicThroughput, share: f64, by_bytes: bool) -> Finding {
+        Finding {
+            id: String::new(),
+            title: format!("Topic {} takes {:.0}% of cluster throughput", topic.topic, share),
+            description: format!(
+                "{} receives {:.1}% of all inbound {} in the cluster, above the {:.0}% hotspot threshold. \
+                Brokers leading its partitions carry a disproportionate share of the load.",
+                topic.topic, share, if by_bytes { "bytes" } else { "messages" }, self.hotspot_share_percent
+            ),
+            severity: Severity::Medium,
+            category: Category::Capacity,
+            impact: "MEDIUM: A traffic spike on one topic can saturate its leaders' network and disks, slowing every \
+                other topic they host.".to_string(),
+            evidence: Evidence {
+                metrics: vec![Self::metric(snapshot, topic, by_bytes)],
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Traffic is concentrated on one topic rather than spread across the cluster".to_string()),
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Make sure the topic has at least as many partitions as brokers, with leaders balanced across them".to_string(),
+                        command: Some(format!("kafka-topics.sh --bootstrap-server <broker> --describe --topic {}", topic.topic)),
+                        verification: None,
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Add partitions or set a producer quota if the traffic is not expected".to_string(),
+                        command: Some(format!(
+                            "kafka-topics.sh --bootstrap-server <broker> --alter --topic {} --partitions <count>",
+                            topic.topic
+                        )),
+                        verification: None,
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(30),
+                rollback_plan: None,
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for HotTopicAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let topics = topic_throughput(snapshot);
+        let total_bytes: f64 = topics.iter().map(|t| t.bytes_in_per_sec).sum();
+        let total_messages: f64 = topics.iter().map(|t| t.messages_in_per_sec).sum();
+
+        // Rank by bytes when brokers report them, by messages otherwise
+        let by_bytes = total_bytes > 0.0;
+        let (value, total): (fn(&TopicThroughput) -> f64, f64) = if by_bytes {
+            (|t| t.bytes_in_per_sec, total_bytes)
+        } else {
+            (|t| t.messages_in_per_sec, total_messages)
+        };
+        if total <= 0.0 {
+            return Ok(Vec::new());
+        }
+
+        let mut ranked: Vec<(&TopicThroughput, f64)> = topics.iter()
+            .filter(|t| value(t) > 0.0)
+            .map(|t| (t, value(t) / total * 100.0))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.topic.cmp(&b.0.topic)));
+
+        let mut findings = Vec::new();
+        if ranked.len() > 1 {
+            findings.extend(ranked.iter()
+                .filter(|(_, share)| *share > self.hotspot_share_percent)
+                .map(|(topic, share)| self.hotspot_finding(snapshot, topic, *share, by_bytes)));
+        }
+        ranked.truncate(TOP_TOPICS);
+        findings.push(Self::ranking_finding(snapshot, &ranked, by_bytes));
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("HOT-TOPIC-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Hot Topic Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ranks topics by inbound throughput and flags topics dominating cluster traffic"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    /// Scraped metrics as the JMX exporter names them, one series per rate
+    fn snapshot(brokers: &[&[(&str, f64)]]) -> Snapshot {
+        let endpoints: serde_json::Map<String, serde_json::Value> = brokers.iter().enumerate()
+            .map(|(i, topics)| {
+                let series = |suffix: &str, scale: f64| -> Vec<serde_json::Value> {
+                    topics.iter()
+                        .map(|(topic, bytes)| json!({
+                            "name": format!("kafka_server_brokertopicmetrics_{}", suffix),
+                            "labels": {"topic": topic},
+                            "value": bytes * scale,
+                        }))
+                        .collect()
+                };
+                (format!("kafka-{}", i + 1), json!({
+                    "url": format!("http://kafka-{}:7071/metrics", i + 1),
+                    "families": {
+                        "kafka_server_brokertopicmetrics_bytesinpersec_oneminuterate": {
+                            "type": "gauge", "samples": series("bytesinpersec_oneminuterate", 1.0)
+                        },
+                        "kafka_server_brokertopicmetrics_bytesinpersec_count": {
+                            "type": "counter", "samples": series("bytesinpersec_count", 3600.0)
+                        },
+                        "kafka_server_brokertopicmetrics_messagesinpersec_oneminuterate": {
+                            "type": "gauge", "samples": series("messagesinpersec_oneminuterate", 0.01)
+                        },
+                    },
+                }))
+            })
+            .collect();
+
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.metrics = Some(json!({ PROMETHEUS_METRICS_KEY: { "endpoints": endpoints } }));
+        snapshot
+    }
+
+    #[test]
+    fn test_topic_throughput_ranked_and_summed_over_brokers() {
+        let snapshot = snapshot(&[
+            &[("orders", 1000.0), ("payments", 300.0), ("audit", 50.0)],
+            &[("orders", 500.0), ("payments", 700.0)],
+        ]);
+
+        let topics = topic_throughput(&snapshot);
+
+        let ranking: Vec<(&str, f64)> = topics.iter().map(|t| (t.topic.as_str(), t.bytes_in_per_sec)).collect();
+        assert_eq!(ranking, vec![("orders", 1500.0), ("payments", 1000.0), ("audit", 50.0)]);
+        assert_eq!(topics[0].messages_in_per_sec, 15.0);
+    }
+
+    #[tokio::test]
+    async fn test_hotspot_detected() {
+        let snapshot = snapshot(&[&[("clickstream", 9000.0), ("orders", 600.0), ("audit", 400.0)]]);
+
+        let findings = HotTopicAnalyzer::new().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 2);
+        let hotspot = &findings[0];
+        assert_eq!(hotspot.id, "HOT-TOPIC-001");
+        assert_eq!(hotspot.severity, Severity::Medium);
+        assert!(matches!(hotspot.category, Category::Capacity));
+        assert!(hotspot.title.contains("clickstream takes 90%"));
+
+        let ranking = &findings[1];
+        assert_eq!(ranking.severity, Severity::Info);
+        assert!(ranking.description.contains("clickstream (90.0%), orders (6.0%), audit (4.0%)"));
+        assert_eq!(ranking.evidence.metrics.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_no_hotspot_with_balanced_traffic_or_higher_threshold() {
+        let balanced = snapshot(&[&[("orders", 400.0), ("payments", 350.0), ("audit", 250.0)]]);
+        let findings = HotTopicAnalyzer::new().analyze(&balanced).await.unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Info);
+
+        let skewed = snapshot(&[&[("clickstream", 9000.0), ("orders", 1000.0)]]);
+        let findings = HotTopicAnalyzer::new().with_hotspot_share(95.0).analyze(&skewed).await.unwrap();
+        assert!(findings.iter().all(|f| f.severity == Severity::Info));
+    }
+
+    #[tokio::test]
+    async fn test_single_topic_or_no_metrics() {
+        let single = snapshot(&[&[("orders", 1000.0)]]);
+        let findings = HotTopicAnalyzer::new().analyze(&single).await.unwrap();
+        assert!(findings.iter().all(|f| f.severity == Severity::Info));
+
+        let empty = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        assert!(HotTopicAnalyzer::new().analyze(&empty).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,327 @@
+use crate::analyzers::security_protocol::SECURITY_PROTOCOLS;
+use crate::analyzers::{server_properties, Analyzer, AnalyzerResult};
+use crate::scan::listeners::{parse_listeners, parse_security_protocol_map};
+use crate::snapshot::format::{
+    Category, ConfigEvidence, Evidence, Finding, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// Listener Kafka binds when `listeners` is not set
+const DEFAULT_LISTENERS: &str = "PLAINTEXT://:9092";
+
+/// Listener names of a `listeners`/`advertised.listeners` value, upper-cased as Kafka compares them
+fn listener_names(value: &str) -> Vec<String> {
+    parse_listeners(value).into_iter().map(|listener| listener.name.to_uppercase()).collect()
+}
+
+fn comma_list(props: &HashMap<String, String>, key: &str) -> Vec<String> {
+    props.get(key)
+        .map(|value| value.split(',').map(|name| name.trim().to_uppercase()).filter(|name| !name.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+/// One inconsistency between a broker's listeners and its inter-broker security settings
+#[derive(Debug, Clone, PartialEq)]
+struct ListenerProblem {
+    key: &'static str,
+    value: String,
+    recommended: String,
+    reason: String,
+}
+
+impl ListenerProblem {
+    fn new(key: &'static str, value: impl Into<String>, recommended: impl Into<String>, reason: String) -> Self {
+        Self { key, value: value.into(), recommended: recommended.into(), reason }
+    }
+}
+
+/// Cross-check the inter-broker listener and protocol of one broker's server.properties against
+/// its `listeners`, `advertised.listeners` and `listener.security.protocol.map`
+fn check_broker(props: &HashMap<String, String>) -> Vec<ListenerProblem> {
+    let mut problems = Vec::new();
+    let controllers = comma_list(props, "controller.listener.names");
+
+    // An explicit map replaces Kafka's default one, which maps each protocol name to itself
+    let explicit_map = props.get("listener.security.protocol.map");
+    let mut protocols: HashMap<String, String> = HashMap::new();
+    match explicit_map {
+        Some(value) => {
+            for entry in parse_security_protocol_map(value) {
+                match entry {
+                    Ok((name, protocol)) if SECURITY_PROTOCOLS.contains(&protocol.as_str()) => {
+                        protocols.insert(name, protocol);
+                    }
+                    Ok((name, protocol)) => problems.push(ListenerProblem::new(
+                        "listener.security.protocol.map",
+                        format!("{}:{}", name, protocol),
+                        format!("{}:<one of {}>", name, SECURITY_PROTOCOLS.join(", ")),
+                        format!("{} is not a security protocol, so the broker fails to start", protocol),
+                    )),
+                    Err(entry) => problems.push(ListenerProblem::new(
+                        "listener.security.protocol.map",
+                        entry.clone(),
+                        "LISTENER_NAME:PROTOCOL",
+                        format!("Entry '{}' is not a listener name and protocol separated by ':'", entry),
+                    )),
+                }
+            }
+        }
+        None => protocols.extend(SECURITY_PROTOCOLS.iter().map(|protocol| (protocol.to_string(), protocol.to_string()))),
+    }
+
+    let listeners = listener_names(props.get("listeners").map(String::as_str).unwrap_or(DEFAULT_LISTENERS));
+    for name in &listeners {
+        // Without an explicit map, KRaft maps controller listeners to PLAINTEXT itself
+        let implicit_controller = explicit_map.is_none() && controllers.contains(name);
+        if !protocols.contains_key(name) && !implicit_controller {
+            problems.push(ListenerProblem::new(
+                "listener.security.protocol.map",
+                explicit_map.cloned().unwrap_or_else(|| "(not set)".to_string()),
+                format!("an entry {}:<protocol>", name),
+                format!("Listener {} has no security protocol, so the broker fails to start", name),
+            ));
+        }
+    }
+
+    let advertised = props.get("advertised.listeners")
+        .map(|value| listener_names(value))
+        .unwrap_or_else(|| listeners.clone());
+    for name in advertised.iter().filter(|name| !listeners.contains(name)) {
+        problems.push(ListenerProblem::new(
+            "advertised.listeners",
+            props.get("advertised.listeners").cloned().unwrap_or_default(),
+            format!("only listeners defined in listeners ({})", listeners.join(", ")),
+            format!("Advertised listener {} is not bound by listeners", name),
+        ));
+    }
+
+    let listener_name = props.get("inter.broker.listener.name").map(|name| name.trim().to_uppercase());
+    let protocol = props.get("security.inter.broker.protocol").map(|protocol| protocol.trim().to_uppercase());
+    if let (Some(name), Some(protocol)) = (&listener_name, &protocol) {
+        let reason = match protocols.get(name) {
+            Some(actual) if actual != protocol => format!(
+                "Only one of inter.broker.listener.name and security.inter.broker.protocol may be set, and they disagree: listener {} uses {}",
+                name, actual
+            ),
+            _ => "Only one of inter.broker.listener.name and security.inter.broker.protocol may be set, so the broker fails to start".to_string(),
+        };
+        problems.push(ListenerProblem::new("security.inter.broker.protocol", protocol.clone(), "(remove)", reason));
+    }
+
+    // Without a listener name, brokers replicate over the listener named after the protocol
+    let (key, inter_broker) = match (listener_name, protocol) {
+        (Some(name), _) => ("inter.broker.listener.name", name),
+        (None, Some(protocol)) => ("security.inter.broker.protocol", protocol),
+        (None, None) => ("security.inter.broker.protocol", "PLAINTEXT".to_string()),
+    };
+    if !listeners.contains(&inter_broker) {
+        problems.push(ListenerProblem::new(
+            key,
+            inter_broker.clone(),
+            format!("one of the defined listeners ({})", listeners.join(", ")),
+            format!("No listener is named {}, so brokers have no listener to replicate over", inter_broker),
+        ));
+    } else if !advertised.contains(&inter_broker) {
+        problems.push(ListenerProblem::new(
+            "advertised.listeners",
+            props.get("advertised.listeners").cloned().unwrap_or_default(),
+            format!("an entry for {}", inter_broker),
+            format!("Inter-broker listener {} is not advertised, so other brokers cannot find this one", inter_broker),
+        ));
+    } else if controllers.contains(&inter_broker) {
+        problems.push(ListenerProblem::new(
+            key,
+            inter_broker.clone(),
+            "a listener not in controller.listener.names",
+            format!("Inter-broker listener {} is a controller listener", inter_broker),
+        ));
+    }
+
+    problems
+}
+
+/// Whether the file belongs to a KRaft controller-only node, which has no inter-broker listener
+fn is_controller_only(props: &HashMap<String, String>) -> bool {
+    props.get("process.roles")
+        .is_some_and(|roles| !roles.split(',').any(|role| role.trim() == "broker"))
+}
+
+/// Flags brokers whose inter-broker listener or protocol does not match the listeners they define
+pub struct InterBrokerListenerAnalyzer;
+
+impl Default for InterBrokerListenerAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InterBrokerListenerAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn finding(file: &str, problems: Vec<ListenerProblem>) -> Finding {
+        let broker = file.split('/').next().unwrap_or(file).to_string();
+        let steps = [
+            "Pick the listener brokers replicate over, define it in listeners and advertised.listeners, and map it to its protocol in listener.security.protocol.map",
+            "Set inter.broker.listener.name to that listener and remove security.inter.broker.protocol",
+            "Apply the change to one broker, check it rejoins the ISR of its partitions, then roll the remaining brokers",
+        ];
+
+        Finding {
+            id: String::new(),
+            title: format!("Inter-broker listener misconfigured on {}", broker),
+            description: format!(
+                "The listener settings in {} are inconsistent: {}.",
+                file,
+                problems.iter().map(|p| p.reason.as_str()).collect::<Vec<_>>().join("; ")
+            ),
+            severity: Severity::High,
+            category: Category::Configuration,
+            impact: "HIGH: The broker fails to start or cannot replicate with the other brokers, leaving its partitions under-replicated.".to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs: problems.into_iter().map(|problem| ConfigEvidence {
+                    resource_type: "broker".to_string(),
+                    resource_name: broker.clone(),
+                    config_key: problem.key.to_string(),
+                    current_value: problem.value,
+                    recommended_value: Some(problem.recommended),
+                    reason: problem.reason,
+                    source_files: vec![file.to_string()],
+                }).collect(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Listener names, the security protocol map and the inter-broker settings edited separately".to_string()),
+            remediation: Remediation {
+                steps: steps.iter().enumerate().map(|(i, step)| RemediationStep {
+                    order: i as u32 + 1,
+                    description: step.to_string(),
+                    command: None,
+                    verification: Some("kafka-topics.sh --bootstrap-server <broker> --describe --under-replicated-partitions".to_string()),
+                    can_automate: false,
+                }).collect(),
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(60),
+                rollback_plan: Some("Restore the previous server.properties and restart the broker".to_string()),
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for InterBrokerListenerAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let mut findings: Vec<Finding> = server_properties(snapshot).into_iter()
+            .filter(|(_, props)| !is_controller_only(props))
+            .filter_map(|(file, props)| {
+                let problems = check_broker(&props);
+                (!problems.is_empty()).then(|| Self::finding(&file, problems))
+            })
+            .collect();
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("IBL-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Inter-Broker Listener Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Cross-checks the inter-broker listener and protocol against the listeners and security protocol map"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::cluster_detection::parse_server_properties;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    fn problems(config: &str) -> Vec<String> {
+        check_broker(&parse_server_properties(config)).into_iter()
+            .map(|problem| format!("{}: {}", problem.key, problem.reason))
+            .collect()
+    }
+
+    #[test]
+    fn test_consistent_listeners_pass() {
+        assert!(problems("").is_empty());
+        assert!(problems("listeners=SSL://:9093\nsecurity.inter.broker.protocol=SSL\n").is_empty());
+        assert!(problems("\
+listeners=CLIENTS://0.0.0.0:9092,REPLICATION://0.0.0.0:9093,CONTROLLER://0.0.0.0:9094
+advertised.listeners=CLIENTS://kafka-1.example.com:9092,REPLICATION://kafka-1.internal:9093
+listener.security.protocol.map=clients:sasl_ssl, REPLICATION:SSL,CONTROLLER:SSL
+inter.broker.listener.name=REPLICATION
+controller.listener.names=CONTROLLER
+").is_empty());
+        // KRaft maps an unmapped controller listener to PLAINTEXT when no map is set
+        assert!(problems("listeners=PLAINTEXT://:9092,CONTROLLER://:9093\ncontroller.listener.names=CONTROLLER\n").is_empty());
+    }
+
+    #[test]
+    fn test_broken_combinations_flagged() {
+        // The protocol names no listener
+        assert_eq!(
+            problems("listeners=CLIENTS://:9092,REPLICATION://:9093\nlistener.security.protocol.map=CLIENTS:SASL_SSL,REPLICATION:SSL\nsecurity.inter.broker.protocol=SSL\n"),
+            vec!["security.inter.broker.protocol: No listener is named SSL, so brokers have no listener to replicate over"]
+        );
+
+        // Both settings, disagreeing
+        assert_eq!(
+            problems("listeners=REPLICATION://:9093\nlistener.security.protocol.map=REPLICATION:SSL\ninter.broker.listener.name=REPLICATION\nsecurity.inter.broker.protocol=SASL_SSL\n"),
+            vec!["security.inter.broker.protocol: Only one of inter.broker.listener.name and security.inter.broker.protocol may be set, and they disagree: listener REPLICATION uses SSL"]
+        );
+
+        // An unmapped listener, a malformed entry and an unknown protocol
+        assert_eq!(
+            problems("listeners=CLIENTS://:9092,REPLICATION://:9093\nlistener.security.protocol.map=CLIENTS;SASL_SSL,REPLICATION:TLS\ninter.broker.listener.name=REPLICATION\n"),
+            vec![
+                "listener.security.protocol.map: Entry 'CLIENTS;SASL_SSL' is not a listener name and protocol separated by ':'",
+                "listener.security.protocol.map: TLS is not a security protocol, so the broker fails to start",
+                "listener.security.protocol.map: Listener CLIENTS has no security protocol, so the broker fails to start",
+                "listener.security.protocol.map: Listener REPLICATION has no security protocol, so the broker fails to start",
+            ]
+        );
+
+        // The inter-broker listener is bound but not advertised, and an advertised one is not bound
+        assert_eq!(
+            problems("listeners=CLIENTS://:9092,REPLICATION://:9093\nadvertised.listeners=CLIENTS://kafka-1:9092,EXTERNAL://kafka-1:9094\nlistener.security.protocol.map=CLIENTS:SSL,REPLICATION:SSL,EXTERNAL:SSL\ninter.broker.listener.name=REPLICATION\n"),
+            vec![
+                "advertised.listeners: Advertised listener EXTERNAL is not bound by listeners",
+                "advertised.listeners: Inter-broker listener REPLICATION is not advertised, so other brokers cannot find this one",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_findings_per_broker() {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.config = Some(json!({
+            "broker_1/server.properties": "listeners=SSL://:9093\nsecurity.inter.broker.protocol=SSL\n",
+            "broker_2/server.properties": "listeners=SSL://:9093\n",
+            "controller_1/server.properties": "process.roles=controller\nlisteners=CONTROLLER://:9094\ncontroller.listener.names=CONTROLLER\nlistener.security.protocol.map=CONTROLLER:SSL\n",
+        }));
+        let findings = InterBrokerListenerAnalyzer::new().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.id, "IBL-001");
+        assert_eq!(finding.title, "Inter-broker listener misconfigured on broker_2");
+        assert_eq!(finding.severity, Severity::High);
+        assert!(matches!(finding.category, Category::Configuration));
+        assert_eq!(finding.evidence.configs[0].config_key, "security.inter.broker.protocol");
+        assert_eq!(finding.evidence.configs[0].current_value, "PLAINTEXT");
+    }
+}
@@ -0,0 +1,368 @@
+use crate::analyzers::{server_properties, Analyzer, AnalyzerResult};
+use crate::snapshot::format::{
+    Category, ClusterMode, ConfigEvidence, Evidence, Finding,
+    Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::{BTreeSet, HashMap};
+
+const QUORUM_VOTERS_KEY: &str = "controller.quorum.voters";
+
+/// A voter entry of `controller.quorum.voters` (`id@host:port`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuorumVoter {
+    pub id: i32,
+    pub host: String,
+    pub port: u16,
+}
+
+/// Parse `controller.quorum.voters`, skipping malformed entries
+pub fn parse_quorum_voters(value: &str) -> Vec<QuorumVoter> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (id, address) = entry.trim().split_once('@')?;
+            let (host, port) = address.rsplit_once(':')?;
+            Some(QuorumVoter {
+                id: id.trim().parse().ok()?,
+                host: host.trim_start_matches('[').trim_end_matches(']').to_string(),
+                port: port.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Checks the KRaft controller quorum for size, majority and reachability problems
+pub struct KraftQuorumAnalyzer;
+
+impl Default for KraftQuorumAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KraftQuorumAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Node ids and hostnames of the brokers that were scanned
+    fn scanned_nodes(snapshot: &Snapshot, configs: &[(String, HashMap<String, String>)]) -> (BTreeSet<i32>, BTreeSet<String>) {
+        let mut ids: BTreeSet<i32> = configs.iter()
+            .filter_map(|(_, props)| props.get("node.id").or_else(|| props.get("broker.id")))
+            .filter_map(|id| id.trim().parse().ok())
+            .collect();
+        let mut hostnames = BTreeSet::new();
+
+        if let Some(brokers) = snapshot.collectors.custom.get("brokers").and_then(|b| b.as_object()) {
+            for broker in brokers.values() {
+                if let Some(info) = broker.get("broker_info.json") {
+                    if let Some(id) = info.get("id").and_then(|id| id.as_i64()) {
+                        ids.insert(id as i32);
+                    }
+                    if let Some(hostname) = info.get("hostname").and_then(|h| h.as_str()) {
+                        hostnames.insert(hostname.to_string());
+                    }
+                }
+            }
+        }
+
+        (ids, hostnames)
+    }
+}
+
+/// The `controller.quorum.voters` setting findings are reported against
+struct QuorumConfig<'a> {
+    voters_value: &'a str,
+    voters_file: &'a str,
+}
+
+impl QuorumConfig<'_> {
+    fn finding(
+        &self,
+        severity: Severity,
+        title: String,
+        description: String,
+        impact: &str,
+        recommended_value: Option<&str>,
+        steps: &[&str],
+    ) -> Finding {
+        Finding {
+            id: String::new(),
+            title,
+            description,
+            severity,
+            category: Category::Availability,
+            impact: impact.to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs: vec![ConfigEvidence {
+                    resource_type: "broker".to_string(),
+                    resource_name: "controller quorum".to_string(),
+                    config_key: QUORUM_VOTERS_KEY.to_string(),
+                    current_value: self.voters_value.to_string(),
+                    recommended_value: recommended_value.map(str::to_string),
+                    reason: "The controller quorum needs a majority of voters online to elect a leader".to_string(),
+                    source_files: vec![self.voters_file.to_string()],
+                }],
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: steps.iter().enumerate().map(|(i, step)| RemediationStep {
+                    order: i as u32 + 1,
+                    description: step.to_string(),
+                    command: None,
+                    verification: Some("kafka-metadata-quorum.sh --bootstrap-server <broker> describe --status".to_string()),
+                    can_automate: false,
+                }).collect(),
+                script: None,
+                risk_level: RiskLevel::High,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(60),
+                rollback_plan: None,
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for KraftQuorumAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let mut findings = Vec::new();
+        if matches!(snapshot.cluster.mode, ClusterMode::Zookeeper) {
+            return Ok(findings);
+        }
+
+        let configs = server_properties(snapshot);
+        let Some((voters_file, voters_value)) = configs.iter()
+            .find_map(|(path, props)| props.get(QUORUM_VOTERS_KEY).map(|v| (path.as_str(), v.as_str())))
+        else {
+            return Ok(findings);
+        };
+        let quorum = QuorumConfig { voters_value, voters_file };
+        let voters = parse_quorum_voters(voters_value);
+        if voters.is_empty() {
+            return Ok(findings);
+        }
+
+        let voter_count = voters.len();
+        let majority = voter_count / 2 + 1;
+        let tolerated_failures = voter_count - majority;
+
+        if voter_count < 3 {
+            findings.push(quorum.finding(
+                Severity::High,
+                format!("KRaft controller quorum has only {} voter(s)", voter_count),
+                format!(
+                    "controller.quorum.voters lists {} voter(s). A quorum needs {} voter(s) online, so it \
+                    cannot tolerate losing any controller. Production clusters should run at least 3 controllers.",
+                    voter_count, majority
+                ),
+                "HIGH: Losing a single controller stops metadata updates. Leader elections, topic changes and broker registration fail until it returns.",
+                Some("3 or 5 voters"),
+                &[
+                    "Provision additional controller nodes so the quorum has 3 (or 5) voters",
+                    "Add the new voters to controller.quorum.voters on every broker and controller",
+                ],
+            ));
+        } else if voter_count.is_multiple_of(2) {
+            findings.push(quorum.finding(
+                Severity::Medium,
+                format!("KRaft controller quorum has an even number of voters ({})", voter_count),
+                format!(
+                    "controller.quorum.voters lists {} voters. A majority is {}, so the quorum tolerates only {} \
+                    failure(s), the same as {} voters. The extra voter adds replication load without adding fault \
+                    tolerance, and a network split into two halves of {} leaves no side with a majority.",
+                    voter_count, majority, tolerated_failures, voter_count - 1, voter_count / 2
+                ),
+                "MEDIUM: The quorum is no more fault tolerant than with one fewer voter, and an even split leaves no partition with a majority.",
+                Some(&format!("{} or {} voters", voter_count - 1, voter_count + 1)),
+                &[
+                    "Use an odd number of controller voters, either by adding one voter or by removing one",
+                ],
+            ));
+        }
+
+        let has_controller_role = configs.iter().any(|(_, props)| {
+            props.get("process.roles").map(|roles| roles.contains("controller")).unwrap_or(false)
+        });
+
+        if !has_controller_role {
+            findings.push(quorum.finding(
+                Severity::Medium,
+                "No scanned node runs the KRaft controller role".to_string(),
+                format!(
+                    "None of the {} scanned server.properties set process.roles to include 'controller'. \
+                    The {} configured voters are therefore dedicated controllers that were not scanned, \
+                    so their health could not be verified.",
+                    configs.len(), voter_count
+                ),
+                "MEDIUM: Controller quorum health is unknown. A failing controller would go unnoticed by this scan.",
+                None,
+                &[
+                    "Include the dedicated controller nodes in the scan",
+                    "Check the quorum with kafka-metadata-quorum.sh describe --status",
+                ],
+            ));
+        } else {
+            let (scanned_ids, scanned_hosts) = Self::scanned_nodes(snapshot, &configs);
+            let missing: Vec<&QuorumVoter> = voters.iter()
+                .filter(|v| !scanned_ids.contains(&v.id) && !scanned_hosts.contains(&v.host))
+                .collect();
+
+            if !missing.is_empty() {
+                let present = voter_count - missing.len();
+                let severity = if present < majority { Severity::High } else { Severity::Medium };
+                findings.push(quorum.finding(
+                    severity,
+                    format!("{} of {} KRaft quorum voters were not among the scanned brokers", missing.len(), voter_count),
+                    format!(
+                        "Only {} of {} configured voters were discovered and scanned; a majority is {}. \
+                        Voters not found: {}. They may be down, unreachable over SSH, or no longer part of the cluster.",
+                        present,
+                        voter_count,
+                        majority,
+                        missing.iter().map(|v| format!("{}@{}:{}", v.id, v.host, v.port)).collect::<Vec<_>>().join(", ")
+                    ),
+                    "Controllers that cannot be reached reduce the number of failures the quorum can survive.",
+                    None,
+                    &[
+                        "Verify the missing voters are running and reachable",
+                        "Remove decommissioned voters from controller.quorum.voters",
+                    ],
+                ));
+            }
+        }
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("KRAFT-QUORUM-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "KRaft Quorum Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Checks the KRaft controller quorum size and that its voters are present"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    fn kraft_snapshot(voters: &str, node_ids: &[i32], roles: &str) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.cluster.mode = ClusterMode::Kraft;
+
+        let mut config = serde_json::Map::new();
+        for id in node_ids {
+            config.insert(
+                format!("broker_{}/server.properties", id),
+                json!(format!(
+                    "process.roles={}\nnode.id={}\ncontroller.quorum.voters={}\nlisteners=PLAINTEXT://:9092,CONTROLLER://:9093",
+                    roles, id, voters
+                )),
+            );
+        }
+        snapshot.collectors.config = Some(serde_json::Value::Object(config));
+        snapshot
+    }
+
+    async fn analyze(snapshot: &Snapshot) -> Vec<Finding> {
+        KraftQuorumAnalyzer::new().analyze(snapshot).await.unwrap()
+    }
+
+    #[test]
+    fn test_parse_quorum_voters() {
+        let voters = parse_quorum_voters("1@kafka-1:9093, 2@kafka-2:9093,3@[::1]:9093,bad");
+
+        assert_eq!(voters.len(), 3);
+        assert_eq!(voters[0], QuorumVoter { id: 1, host: "kafka-1".to_string(), port: 9093 });
+        assert_eq!(voters[2].host, "::1");
+    }
+
+    #[tokio::test]
+    async fn test_two_voters() {
+        let snapshot = kraft_snapshot("1@kafka-1:9093,2@kafka-2:9093", &[1, 2], "broker,controller");
+
+        let findings = analyze(&snapshot).await;
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::High);
+        assert!(findings[0].title.contains("only 2 voter"));
+        assert!(matches!(findings[0].category, Category::Availability));
+    }
+
+    #[tokio::test]
+    async fn test_three_voters_all_scanned() {
+        let snapshot = kraft_snapshot("1@kafka-1:9093,2@kafka-2:9093,3@kafka-3:9093", &[1, 2, 3], "broker,controller");
+
+        assert!(analyze(&snapshot).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_four_voters() {
+        let snapshot = kraft_snapshot(
+            "1@kafka-1:9093,2@kafka-2:9093,3@kafka-3:9093,4@kafka-4:9093",
+            &[1, 2, 3, 4],
+            "broker,controller",
+        );
+
+        let findings = analyze(&snapshot).await;
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert!(findings[0].title.contains("even number of voters (4)"));
+        assert!(findings[0].description.contains("tolerates only 1 failure"));
+    }
+
+    #[tokio::test]
+    async fn test_voters_missing_from_scan() {
+        let snapshot = kraft_snapshot("1@kafka-1:9093,2@kafka-2:9093,3@kafka-3:9093", &[1], "broker,controller");
+
+        let findings = analyze(&snapshot).await;
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::High);
+        assert!(findings[0].description.contains("2@kafka-2:9093, 3@kafka-3:9093"));
+    }
+
+    #[tokio::test]
+    async fn test_voter_matched_by_hostname() {
+        let mut snapshot = kraft_snapshot("1@kafka-1:9093,2@kafka-2:9093,3@kafka-3:9093", &[1, 2], "broker,controller");
+        snapshot.collectors.custom.insert(
+            "brokers".to_string(),
+            json!({"broker_x": {"broker_info.json": {"id": 30, "hostname": "kafka-3"}}}),
+        );
+
+        assert!(analyze(&snapshot).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_no_controller_role() {
+        let snapshot = kraft_snapshot("1@ctrl-1:9093,2@ctrl-2:9093,3@ctrl-3:9093", &[4, 5, 6], "broker");
+
+        let findings = analyze(&snapshot).await;
+
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("No scanned node runs the KRaft controller role"));
+    }
+
+    #[tokio::test]
+    async fn test_zookeeper_cluster_skipped() {
+        let mut snapshot = kraft_snapshot("1@kafka-1:9093,2@kafka-2:9093", &[1, 2], "broker,controller");
+        snapshot.cluster.mode = ClusterMode::Zookeeper;
+
+        assert!(analyze(&snapshot).await.is_empty());
+    }
+}
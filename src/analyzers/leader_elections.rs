@@ -0,0 +1,220 @@
+use crate::analyzers::{Analyzer, AnalyzerResult};
+use crate::scan::log_discovery::controller_parser::ElectionMetrics;
+use crate::snapshot::format::{
+    Category, Evidence, Finding, MetricEvidence, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// Leader elections per hour above which the cluster is considered unstable
+const DEFAULT_MAX_ELECTIONS_PER_HOUR: f64 = 10.0;
+
+/// Partitions listed in a finding, most elected first
+const TOP_PARTITIONS: usize = 5;
+
+/// Election statistics of one broker's controller log
+#[derive(Debug, Clone)]
+pub struct BrokerControllerLog {
+    pub broker: String,
+    /// Path of the log within the scan, e.g. `broker_1/logs/controller_controller.log`
+    pub source: String,
+    pub metrics: ElectionMetrics,
+}
+
+/// Flags controller logs recording partition leader elections at a high rate
+pub struct LeaderElectionAnalyzer {
+    max_elections_per_hour: f64,
+}
+
+impl Default for LeaderElectionAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LeaderElectionAnalyzer {
+    pub fn new() -> Self {
+        Self { max_elections_per_hour: DEFAULT_MAX_ELECTIONS_PER_HOUR }
+    }
+
+    /// Flag controller logs with more than `rate` elections per hour (default: 10)
+    pub fn with_max_elections_per_hour(mut self, rate: f64) -> Self {
+        self.max_elections_per_hour = rate;
+        self
+    }
+
+    /// Parsed controller logs of every scanned broker; logs without any election are skipped
+    pub fn broker_controller_logs(&self, snapshot: &Snapshot) -> Vec<BrokerControllerLog> {
+        let Some(brokers) = snapshot.collectors.custom.get("brokers").and_then(|b| b.as_object()) else {
+            return Vec::new();
+        };
+
+        let mut logs: Vec<BrokerControllerLog> = brokers.iter()
+            .flat_map(|(name, broker)| {
+                broker.get("logs")
+                    .and_then(|logs| logs.as_object())
+                    .into_iter()
+                    .flatten()
+                    .filter(|(log, _)| log.to_lowercase().contains("controller"))
+                    .filter_map(move |(log, content)| {
+                        let metrics = ElectionMetrics::from_log(content.as_str()?);
+                        (metrics.election_count > 0).then(|| BrokerControllerLog {
+                            broker: name.clone(),
+                            source: format!("{}/logs/{}", name, log),
+                            metrics,
+                        })
+                    })
+            })
+            .collect();
+        logs.sort_by(|a, b| a.source.cmp(&b.source));
+        logs
+    }
+
+    fn finding(&self, snapshot: &Snapshot, log: &BrokerControllerLog) -> Option<Finding> {
+        let metrics = &log.metrics;
+        let rate = metrics.elections_per_hour.filter(|rate| *rate > self.max_elections_per_hour)?;
+
+        let top: Vec<String> = metrics.partitions.iter()
+            .take(TOP_PARTITIONS)
+            .map(|(partition, count)| format!("{} ({})", partition, count))
+            .collect();
+        let hours = metrics.span_secs.unwrap_or_default() / 3600.0;
+
+        let steps = [
+            "Check the server logs of the brokers losing leadership for restarts, ZooKeeper/KRaft session expirations or long GC pauses around the election times",
+            "Look for brokers flapping in and out of the ISR (replica.lag.time.max.ms) and for network issues between brokers",
+            "If automatic rebalancing causes the churn, raise leader.imbalance.per.broker.percentage or leader.imbalance.check.interval.seconds",
+            "Confirm the rate dropped by re-scanning and comparing the controller log",
+        ];
+
+        Some(Finding {
+            id: String::new(),
+            title: format!("Frequent leader elections in the controller log of {}", log.broker),
+            description: format!(
+                "The controller log of {} records {} leader election(s) over {:.1} h, {:.1} per hour, above the threshold of {:.0}. \
+                 Most affected partitions: {}.",
+                log.broker, metrics.election_count, hours, rate, self.max_elections_per_hour, top.join(", ")
+            ),
+            severity: Severity::High,
+            category: Category::Availability,
+            impact: "HIGH: Every election makes clients of the partition refresh metadata and retry, raising latency and risking unavailability; frequent elections point to unstable brokers.".to_string(),
+            evidence: Evidence {
+                metrics: vec![
+                    MetricEvidence {
+                        name: "leader_elections_per_hour".to_string(),
+                        value: rate,
+                        threshold: Some(self.max_elections_per_hour),
+                        unit: Some("elections/h".to_string()),
+                        source: log.source.clone(),
+                        timestamp: snapshot.timestamp,
+                    },
+                    MetricEvidence {
+                        name: "leader_elections".to_string(),
+                        value: metrics.election_count as f64,
+                        threshold: None,
+                        unit: Some("elections".to_string()),
+                        source: log.source.clone(),
+                        timestamp: snapshot.timestamp,
+                    },
+                ],
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Brokers restarting, losing their ZooKeeper or controller session, or stalling long enough to drop out of the ISR".to_string()),
+            remediation: Remediation {
+                steps: steps.iter().enumerate().map(|(i, step)| RemediationStep {
+                    order: i as u32 + 1,
+                    description: step.to_string(),
+                    command: None,
+                    verification: None,
+                    can_automate: false,
+                }).collect(),
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(60),
+                rollback_plan: None,
+            },
+            metadata: HashMap::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl Analyzer for LeaderElectionAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let mut findings: Vec<Finding> = self.broker_controller_logs(snapshot).iter()
+            .filter_map(|log| self.finding(snapshot, log))
+            .collect();
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("ELECT-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Leader Election Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags controller logs recording partition leader elections at a high rate"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    /// ZooKeeper-mode controller log over one hour with `elections` preferred elections,
+    /// most of them of orders-0
+    fn controller_log(elections: usize) -> String {
+        let mut lines = vec!["[2024-05-01 10:00:00,000] INFO [Controller id=1] Starting up (kafka.controller.KafkaController)".to_string()];
+        lines.extend((0..elections).map(|i| format!(
+            "[2024-05-01 10:{:02}:00,000] INFO [Controller id=1] Starting replica leader election (PREFERRED) for partitions orders-{} triggered by AutoTriggered (kafka.controller.KafkaController)",
+            i % 60, if i % 3 == 0 { 1 } else { 0 }
+        )));
+        lines.push("[2024-05-01 11:00:00,000] INFO [Controller id=1] Processing automatic preferred replica leader election (kafka.controller.KafkaController)".to_string());
+        lines.join("\n")
+    }
+
+    fn snapshot(logs: &[(&str, String)]) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let brokers: serde_json::Map<String, serde_json::Value> = logs.iter()
+            .map(|(broker, log)| (broker.to_string(), json!({"logs": {"controller_controller.log": log, "server_server.log": "INFO started"}})))
+            .collect();
+        snapshot.collectors.custom.insert("brokers".to_string(), json!(brokers));
+        snapshot
+    }
+
+    #[tokio::test]
+    async fn test_frequent_elections_flagged() {
+        let snapshot = snapshot(&[("broker_1", controller_log(30)), ("broker_2", controller_log(4))]);
+        let findings = LeaderElectionAnalyzer::new().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.id, "ELECT-001");
+        assert_eq!(finding.title, "Frequent leader elections in the controller log of broker_1");
+        assert_eq!(finding.severity, Severity::High);
+        assert!(matches!(finding.category, Category::Availability));
+        assert!(finding.description.contains("30 leader election(s) over 1.0 h, 30.0 per hour"));
+        assert!(finding.description.contains("Most affected partitions: orders-0 (20), orders-1 (10)."));
+        assert_eq!(finding.evidence.metrics[0].source, "broker_1/logs/controller_controller.log");
+    }
+
+    #[tokio::test]
+    async fn test_threshold_configurable() {
+        let snapshot = snapshot(&[("broker_2", controller_log(4))]);
+        assert!(LeaderElectionAnalyzer::new().analyze(&snapshot).await.unwrap().is_empty());
+
+        let findings = LeaderElectionAnalyzer::new().with_max_elections_per_hour(2.0).analyze(&snapshot).await.unwrap();
+        assert_eq!(findings.len(), 1);
+        assert!(LeaderElectionAnalyzer::new().analyze(&Snapshot::new(SnapshotMetadata::new("test".to_string()))).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+use crate::analyzers::version_consistency::VersionConsistencyAnalyzer;
+use crate::analyzers::{Analyzer, AnalyzerResult};
+use crate::scan::features::{
+    compare_metadata_versions, metadata_version_release, parse_kafka_features, FeatureLevel,
+    KAFKA_FEATURES_FILE, METADATA_VERSION_FEATURE,
+};
+use crate::snapshot::format::{
+    Category, ConfigEvidence, Evidence, Finding, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Feature levels collected from `kafka-features.sh describe`, empty when they were not collected
+pub fn cluster_features(snapshot: &Snapshot) -> Vec<FeatureLevel> {
+    snapshot.collectors.custom.get("features")
+        .and_then(|features| features.get(KAFKA_FEATURES_FILE))
+        .and_then(|output| output.as_str())
+        .map(parse_kafka_features)
+        .unwrap_or_default()
+}
+
+/// Flags a KRaft metadata version left behind the broker software after an upgrade
+pub struct MetadataVersionAnalyzer;
+
+impl MetadataVersionAnalyzer {
+    /// Oldest Kafka release detected on the scanned brokers, with the file it was read from
+    fn oldest_broker_release(snapshot: &Snapshot) -> Option<((u32, u32), String, String)> {
+        VersionConsistencyAnalyzer::broker_versions(snapshot).into_iter()
+            .filter_map(|broker| broker.kafka_version)
+            .filter_map(|detected| {
+                let release = metadata_version_release(&detected.version)?;
+                Some((release, detected.version, detected.source))
+            })
+            .min_by_key(|(release, _, _)| *release)
+    }
+
+    fn lagging_finding(finalized: &str, target: &str, evidence: Vec<ConfigEvidence>) -> Finding {
+        let release = metadata_version_release(target)
+            .map(|(major, minor)| format!("{}.{}", major, minor))
+            .unwrap_or_else(|| target.to_string());
+
+        Finding {
+            id: String::new(),
+            title: "KRaft metadata version upgrade not finalized".to_string(),
+            description: format!(
+                "The cluster's finalized metadata.version is {} while the brokers run Kafka {}. \
+                The software was upgraded but the metadata version was not bumped, so the cluster \
+                still behaves as the older release.",
+                finalized, target
+            ),
+            severity: Severity::Medium,
+            category: Category::ClusterHygiene,
+            impact: "MEDIUM: Features and fixes gated on the metadata version stay disabled, and the next \
+                upgrade may not support a metadata version this old.".to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs: evidence,
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("The final `kafka-features.sh upgrade` step of the rolling upgrade was skipped".to_string()),
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Confirm every broker and controller runs the new Kafka version and the cluster is healthy".to_string(),
+                        command: None,
+                        verification: Some("kafka-topics.sh --bootstrap-server <broker> --describe --under-replicated-partitions".to_string()),
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: format!("Finalize the metadata version for Kafka {}", release),
+                        command: Some(format!("kafka-features.sh --bootstrap-server <broker> upgrade --metadata {}", release)),
+                        verification: Some("kafka-features.sh --bootstrap-server <broker> describe".to_string()),
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(15),
+                rollback_plan: Some("Metadata version downgrades are not supported across most releases; \
+                    only finalize once downgrading the software is no longer needed".to_string()),
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for MetadataVersionAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let features = cluster_features(snapshot);
+        let Some(metadata_version) = features.iter().find(|f| f.name == METADATA_VERSION_FEATURE) else {
+            return Ok(Vec::new());
+        };
+        // ZooKeeper clusters never finalize a metadata version
+        let Some(finalized) = metadata_version.finalized.as_deref() else {
+            return Ok(Vec::new());
+        };
+
+        let features_source = format!("features/{}", KAFKA_FEATURES_FILE);
+        let metadata_evidence = |recommended: &str, reason: &str| ConfigEvidence {
+            resource_type: "cluster".to_string(),
+            resource_name: "cluster".to_string(),
+            config_key: METADATA_VERSION_FEATURE.to_string(),
+            current_value: finalized.to_string(),
+            recommended_value: Some(recommended.to_string()),
+            reason: reason.to_string(),
+            source_files: vec![features_source.clone()],
+        };
+
+        // Prefer the version the brokers actually run; otherwise use the highest level the software supports
+        let mut findings = Vec::new();
+        if let Some((release, version, source)) = Self::oldest_broker_release(snapshot) {
+            if metadata_version_release(finalized).is_some_and(|finalized| finalized < release) {
+                let evidence = vec![
+                    metadata_evidence(
+                        &format!("{}.{}", release.0, release.1),
+                        "The metadata version should be finalized once all brokers run the new release",
+                    ),
+                    ConfigEvidence {
+                        resource_type: "broker".to_string(),
+                        resource_name: source.split('/').next().unwrap_or(&source).to_string(),
+                        config_key: "kafka.version".to_string(),
+                        current_value: version.clone(),
+                        recommended_value: None,
+                        reason: "Oldest Kafka version detected on the scanned brokers".to_string(),
+                        source_files: vec![source],
+                    },
+                ];
+                findings.push(Self::lagging_finding(finalized, &version, evidence));
+            }
+        } else if let Some(supported_max) = metadata_version.supported_max.as_deref() {
+            if compare_metadata_versions(finalized, supported_max) == Some(Ordering::Less) {
+                let evidence = vec![metadata_evidence(
+                    supported_max,
+                    "The brokers support a newer metadata version than the one finalized",
+                )];
+                findings.push(Self::lagging_finding(finalized, supported_max, evidence));
+            }
+        }
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("METAVER-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Metadata Version Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags a KRaft metadata version lagging the broker software version"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    fn snapshot(finalized: &str, broker_log: Option<&str>) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let describe = format!(
+            "Feature: kraft.version\tSupportedMinVersion: 0\tSupportedMaxVersion: 1\tFinalizedVersionLevel: 0\tEpoch: 7\n\
+            Feature: metadata.version\tSupportedMinVersion: 3.0-IV1\tSupportedMaxVersion: 3.7-IV4\tFinalizedVersionLevel: {}\tEpoch: 7\n",
+            finalized
+        );
+        snapshot.collectors.custom.insert("features".to_string(), json!({ KAFKA_FEATURES_FILE: describe }));
+        if let Some(log) = broker_log {
+            snapshot.collectors.custom.insert("brokers".to_string(), json!({"broker_1": {"logs": {"server.log": log}}}));
+        }
+        snapshot
+    }
+
+    #[tokio::test]
+    async fn test_lagging_metadata_version_flagged() {
+        let snapshot = snapshot("3.6-IV2", Some("INFO Kafka version: 3.7.0 (org.apache.kafka.common.utils.AppInfoParser)"));
+        let findings = MetadataVersionAnalyzer.analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.id, "METAVER-001");
+        assert_eq!(finding.severity, Severity::Medium);
+        assert!(finding.description.contains("3.6-IV2"));
+        assert!(finding.description.contains("Kafka 3.7.0"));
+        assert_eq!(finding.evidence.configs[0].recommended_value.as_deref(), Some("3.7"));
+        assert_eq!(finding.evidence.configs[1].resource_name, "broker_1");
+        assert_eq!(
+            finding.remediation.steps[1].command.as_deref(),
+            Some("kafka-features.sh --bootstrap-server <broker> upgrade --metadata 3.7")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_finalized_metadata_version_not_flagged() {
+        let finalized = snapshot("3.7-IV4", Some("INFO Kafka version: 3.7.0"));
+        assert!(MetadataVersionAnalyzer.analyze(&finalized).await.unwrap().is_empty());
+
+        // Within the same release the broker version alone does not count as lagging
+        let same_release = snapshot("3.7-IV1", Some("INFO Kafka version: 3.7.1"));
+        assert!(MetadataVersionAnalyzer.analyze(&same_release).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_supported_max_used_without_broker_version() {
+        let findings = MetadataVersionAnalyzer.analyze(&snapshot("3.5-IV2", None)).await.unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].evidence.configs[0].recommended_value.as_deref(), Some("3.7-IV4"));
+
+        // ZooKeeper clusters and scans without kafka-features.sh output produce nothing
+        assert!(MetadataVersionAnalyzer.analyze(&snapshot("-", None)).await.unwrap().is_empty());
+        let empty = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        assert!(MetadataVersionAnalyzer.analyze(&empty).await.unwrap().is_empty());
+    }
+}
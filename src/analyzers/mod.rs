@@ -1,8 +1,34 @@
 pub mod rules;
 pub mod config_validator;
+pub mod kraft_quorum;
+pub mod topic_naming;
+pub mod baseline;
+pub mod version_consistency;
+pub mod hot_topics;
+pub mod disk_growth;
+pub mod custom_rules;
+pub mod rack_awareness;
+pub mod security_protocol;
+pub mod gc_pauses;
+pub mod metadata_version;
+pub mod retention_capacity;
+pub mod advertised_listeners;
+pub mod connections;
+pub mod tls_expiry;
+pub mod file_descriptors;
+pub mod leader_elections;
+pub mod inter_broker_listener;
+pub mod topic_skew;
+pub mod orphaned_topics;
+pub mod broker_restarts;
+pub mod clock_skew;
+pub mod severity_overrides;
 
+use crate::analyzers::severity_overrides::SeverityOverrides;
+use crate::scan::cluster_detection::parse_server_properties;
 use crate::snapshot::format::{Finding, Snapshot};
 use async_trait::async_trait;
+use std::collections::HashMap;
 
 /// Result type for analyzer operations
 pub type AnalyzerResult<T> = Result<T, AnalyzerError>;
@@ -36,9 +62,34 @@ pub trait Analyzer: Send + Sync {
     fn description(&self) -> &'static str;
 }
 
+/// Broker id of a `brokers` entry, from its `broker_info.json` or its `broker_<id>` name
+pub(crate) fn broker_id(name: &str, broker: &serde_json::Value) -> Option<i32> {
+    broker.get("broker_info.json").and_then(|info| info.get("id")).and_then(|id| id.as_i64())
+        .and_then(|id| i32::try_from(id).ok())
+        .or_else(|| name.strip_prefix("broker_")?.parse().ok())
+}
+
+/// Parsed server.properties of every scanned broker, ordered by file path
+pub(crate) fn server_properties(snapshot: &Snapshot) -> Vec<(String, HashMap<String, String>)> {
+    let mut configs: Vec<(String, HashMap<String, String>)> = snapshot.collectors.config.as_ref()
+        .and_then(|config| config.as_object())
+        .map(|config| {
+            config.iter()
+                .filter(|(path, _)| path.ends_with("server.properties"))
+                .filter_map(|(path, content)| {
+                    content.as_str().map(|c| (path.clone(), parse_server_properties(c)))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    configs.sort_by(|a, b| a.0.cmp(&b.0));
+    configs
+}
+
 /// Registry for analyzers
 pub struct AnalyzerRegistry {
     analyzers: Vec<Box<dyn Analyzer>>,
+    severity_overrides: SeverityOverrides,
 }
 
 impl Default for AnalyzerRegistry {
@@ -51,8 +102,15 @@ impl AnalyzerRegistry {
     pub fn new() -> Self {
         Self {
             analyzers: Vec::new(),
+            severity_overrides: SeverityOverrides::default(),
         }
     }
+
+    /// Remap the severity of findings by rule name or id prefix after all analyzers ran
+    pub fn with_severity_overrides(mut self, overrides: SeverityOverrides) -> Self {
+        self.severity_overrides = overrides;
+        self
+    }
     
     pub fn register(&mut self, analyzer: Box<dyn Analyzer>) {
         self.analyzers.push(analyzer);
@@ -73,7 +131,12 @@ impl AnalyzerRegistry {
         }
         
         // Sort findings by severity
-        all_findings.sort_by(|a, b| a.severity.cmp(&b.severity));
+        all_findings.sort_by_key(|f| f.severity);
+
+        let overridden = self.severity_overrides.apply(&mut all_findings);
+        if overridden > 0 {
+            tracing::info!("Overrode the severity of {} finding(s)", overridden);
+        }
         
         Ok(all_findings)
     }
@@ -82,3 +145,83 @@ impl AnalyzerRegistry {
         self.analyzers.iter().map(|a| a.name().to_string()).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rules::RuleAnalyzer;
+    use crate::analyzers::severity_overrides::ORIGINAL_SEVERITY_METADATA_KEY;
+    use crate::snapshot::format::{Severity, SnapshotMetadata};
+
+    struct HighFindingAnalyzer;
+
+    #[async_trait]
+    impl Analyzer for HighFindingAnalyzer {
+        async fn analyze(&self, _snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+            Ok(vec![Finding { id: "TEST-001".to_string(), severity: Severity::High, ..Default::default() }])
+        }
+
+        fn name(&self) -> &'static str {
+            "High Finding Analyzer"
+        }
+
+        fn description(&self) -> &'static str {
+            "Always reports one high finding"
+        }
+    }
+
+    /// Broker 1 leads four of five partitions
+    fn imbalanced_snapshot() -> Snapshot {
+        let partitions: Vec<_> = [1, 1, 1, 1, 2].iter().enumerate()
+            .map(|(id, leader)| serde_json::json!({
+                "id": id, "leader": leader, "replicas": [1, 2], "isr": [1, 2], "offline_replicas": []
+            }))
+            .collect();
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.admin = Some(serde_json::json!({
+            "cluster": {
+                "cluster_id": null, "controller_id": null,
+                "broker_count": 2, "topic_count": 1, "partition_count": 5
+            },
+            "brokers": [
+                {"id": 1, "host": "kafka-1", "port": 9092, "rack": null},
+                {"id": 2, "host": "kafka-2", "port": 9092, "rack": null},
+            ],
+            "topics": [{"name": "orders", "partitions": partitions, "replication_factor": 2, "is_internal": false}],
+        }));
+        snapshot
+    }
+
+    fn registry(overrides: SeverityOverrides) -> AnalyzerRegistry {
+        let mut registry = AnalyzerRegistry::new().with_severity_overrides(overrides);
+        registry.register(Box::new(RuleAnalyzer::new()));
+        registry.register(Box::new(HighFindingAnalyzer));
+        registry
+    }
+
+    fn leader_imbalance(findings: &[Finding]) -> (usize, &Finding) {
+        findings.iter().enumerate()
+            .find(|(_, f)| f.metadata.get("rule").and_then(|rule| rule.as_str()) == Some("leader_imbalance"))
+            .expect("leader imbalance finding")
+    }
+
+    #[tokio::test]
+    async fn test_severity_override_by_rule_name() {
+        let snapshot = imbalanced_snapshot();
+
+        let findings = registry(SeverityOverrides::default()).analyze_all(&snapshot).await.unwrap();
+        let (position, finding) = leader_imbalance(&findings);
+        assert_eq!(finding.severity, Severity::Medium);
+        assert!(position > 0);
+        assert!(!finding.metadata.contains_key(ORIGINAL_SEVERITY_METADATA_KEY));
+
+        let overrides = SeverityOverrides::from_yaml("severity_overrides:\n  leader_imbalance: critical\n").unwrap();
+        let findings = registry(overrides).analyze_all(&snapshot).await.unwrap();
+        let (position, finding) = leader_imbalance(&findings);
+        assert_eq!(finding.severity, Severity::Critical);
+        assert_eq!(finding.metadata[ORIGINAL_SEVERITY_METADATA_KEY], "medium");
+        // Re-sorted ahead of the high finding
+        assert_eq!(position, 0);
+        assert!(findings.windows(2).all(|pair| pair[0].severity <= pair[1].severity));
+    }
+}
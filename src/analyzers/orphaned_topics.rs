@@ -0,0 +1,323 @@
+use crate::analyzers::hot_topics::topic_throughput;
+use crate::analyzers::{Analyzer, AnalyzerError, AnalyzerResult};
+use crate::collectors::admin::AdminCollectorOutput;
+use crate::snapshot::format::{
+    Category, ConsumerGroupLag, Evidence, Finding, Remediation, RemediationStep, RiskLevel, Severity, Snapshot,
+    CONSUMER_LAG_KEY,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use tracing::debug;
+
+/// Path of `kafkactl get consumer-groups -o yaml` output in the scan's `cluster` data
+const KAFKACTL_CONSUMER_GROUPS_POINTER: &str = "/kafkactl/consumer_groups.yaml";
+
+/// Consumer group states in which no member is consuming
+const INACTIVE_GROUP_STATES: &[&str] = &["empty", "dead"];
+
+/// Consumer group as listed by kafkactl, with the topics it has committed offsets for
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ConsumerGroupSubscription {
+    #[serde(alias = "group")]
+    pub name: String,
+    #[serde(default)]
+    pub topics: Vec<String>,
+}
+
+/// Consumer groups and their topics collected by kafkactl, or `None` when the scan has none.
+/// kafkactl prints either one YAML list or one document per group.
+pub fn consumer_group_subscriptions(snapshot: &Snapshot) -> Option<Vec<ConsumerGroupSubscription>> {
+    let yaml = snapshot.collectors.custom.get("cluster")?
+        .pointer(KAFKACTL_CONSUMER_GROUPS_POINTER)?
+        .as_str()?;
+
+    let mut groups = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(yaml) {
+        let value = serde_yaml::Value::deserialize(document).ok()?;
+        match value {
+            serde_yaml::Value::Null => {}
+            serde_yaml::Value::Sequence(_) => groups.extend(serde_yaml::from_value::<Vec<ConsumerGroupSubscription>>(value).ok()?),
+            _ => groups.push(serde_yaml::from_value(value).ok()?),
+        }
+    }
+    Some(groups)
+}
+
+/// Consumer group lag data the scan summarized from the group descriptions, or `None` when
+/// the scan has none
+fn consumer_group_lag(snapshot: &Snapshot) -> Option<Vec<ConsumerGroupLag>> {
+    snapshot.collectors.custom.get(CONSUMER_LAG_KEY)
+        .and_then(|lag| serde_json::from_value(lag.clone()).ok())
+}
+
+/// Whether the lag data reports the group as empty or dead
+fn is_inactive(group: &ConsumerGroupLag) -> bool {
+    group.state.as_deref()
+        .is_some_and(|state| INACTIVE_GROUP_STATES.contains(&state.to_lowercase().as_str()))
+}
+
+/// Flags topics no active consumer group reads and, when per-topic metrics were scraped,
+/// nothing produces to
+pub struct OrphanedTopicsAnalyzer;
+
+impl Default for OrphanedTopicsAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrphanedTopicsAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn finding(orphaned: &[&str], checked_produce: bool) -> Finding {
+        let mut metadata = HashMap::new();
+        metadata.insert("topics".to_string(), serde_json::json!(orphaned));
+
+        Finding {
+            id: "ORPHAN-001".to_string(),
+            title: format!("{} topic(s) without consumers", orphaned.len()),
+            description: format!(
+                "No active consumer group reads these topics{}: {}",
+                if checked_produce { " and no producer wrote to them recently" } else { "" },
+                orphaned.join(", ")
+            ),
+            severity: Severity::Info,
+            category: Category::ClusterHygiene,
+            impact: "INFO: Unused topics keep their retained data on disk and hold partitions that every broker has to track.".to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: Some(serde_json::json!({ "topics": orphaned, "produce_activity_checked": checked_produce })),
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Topics left behind after their applications were retired or moved to other topics".to_string()),
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Confirm with the topic owners that no application still needs the listed topics".to_string(),
+                        command: Some("kafka-consumer-groups.sh --bootstrap-server <broker> --describe --all-groups".to_string()),
+                        verification: None,
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Delete the topics that are no longer needed".to_string(),
+                        command: Some("kafka-topics.sh --bootstrap-server <broker> --delete --topic <topic>".to_string()),
+                        verification: Some("kafka-topics.sh --bootstrap-server <broker> --list".to_string()),
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(30),
+                rollback_plan: None,
+            },
+            metadata,
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for OrphanedTopicsAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let Some(admin_data) = snapshot.collectors.admin.as_ref() else {
+            return Ok(Vec::new());
+        };
+        let groups = consumer_group_subscriptions(snapshot);
+        let lag = consumer_group_lag(snapshot);
+        if groups.is_none() && lag.is_none() {
+            debug!("No consumer group data, skipping orphaned topic detection");
+            return Ok(Vec::new());
+        }
+        let admin: AdminCollectorOutput = serde_json::from_value(admin_data.clone())
+            .map_err(|e| AnalyzerError::InvalidData(format!("Failed to parse admin data: {}", e)))?;
+
+        // kafkactl lists groups without their topics unless asked, so the topics of the
+        // group descriptions count as well
+        let lag = lag.unwrap_or_default();
+        let inactive: HashSet<&str> = lag.iter()
+            .filter(|group| is_inactive(group))
+            .map(|group| group.group.as_str())
+            .collect();
+        let consumed: HashSet<&str> = groups.iter().flatten()
+            .filter(|group| !inactive.contains(group.name.as_str()))
+            .flat_map(|group| group.topics.iter().map(String::as_str))
+            .chain(lag.iter()
+                .filter(|group| !is_inactive(group))
+                .flat_map(|group| group.topics.iter().map(String::as_str)))
+            .collect();
+
+        // Without per-topic metrics only consumers are checked
+        let throughput = topic_throughput(snapshot);
+        let checked_produce = !throughput.is_empty();
+        let produced: HashSet<&str> = throughput.iter()
+            .filter(|topic| topic.bytes_in_per_sec > 0.0 || topic.messages_in_per_sec > 0.0)
+            .map(|topic| topic.topic.as_str())
+            .collect();
+
+        let orphaned: Vec<&str> = admin.topics.iter()
+            .filter(|topic| !topic.is_internal && !topic.name.starts_with('_'))
+            .map(|topic| topic.name.as_str())
+            .filter(|name| !consumed.contains(name) && !produced.contains(name))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        if orphaned.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(vec![Self::finding(&orphaned, checked_produce)])
+    }
+
+    fn name(&self) -> &'static str {
+        "Orphaned Topics Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags topics that no active consumer group reads and nothing produces to"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collectors::prometheus_scrape::PROMETHEUS_METRICS_KEY;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    fn snapshot(topics: &[&str], consumer_groups: Option<&str>) -> Snapshot {
+        let topics: Vec<_> = topics.iter()
+            .map(|name| json!({
+                "name": name,
+                "partitions": [],
+                "replication_factor": 3,
+                "is_internal": name.starts_with("__"),
+            }))
+            .collect();
+
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.admin = Some(json!({
+            "cluster": {"cluster_id": null, "controller_id": 1, "broker_count": 3, "topic_count": topics.len(), "partition_count": 0},
+            "brokers": [],
+            "topics": topics,
+        }));
+        if let Some(yaml) = consumer_groups {
+            snapshot.collectors.custom.insert("cluster".to_string(), json!({"kafkactl": {"consumer_groups.yaml": yaml}}));
+        }
+        snapshot
+    }
+
+    const CONSUMER_GROUPS: &str = "\
+- name: billing
+  protocolType: consumer
+  topics:
+  - orders
+  - payments
+- name: old-reporting
+  protocolType: consumer
+  topics:
+  - reports
+";
+
+    #[tokio::test]
+    async fn test_consumed_and_orphaned_topics() {
+        let mut snapshot = snapshot(&["orders", "payments", "reports", "legacy-events", "__consumer_offsets"], Some(CONSUMER_GROUPS));
+        snapshot.collectors.custom.insert(CONSUMER_LAG_KEY.to_string(), json!([
+            {"group": "billing", "state": "Stable", "members": 2, "total_lag": 10},
+            {"group": "old-reporting", "state": "Empty", "members": 0, "total_lag": 5000},
+        ]));
+
+        let findings = OrphanedTopicsAnalyzer::new().analyze(&snapshot).await.unwrap();
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.severity, Severity::Info);
+        assert!(matches!(finding.category, Category::ClusterHygiene));
+        assert_eq!(finding.metadata["topics"], json!(["legacy-events", "reports"]));
+        assert_eq!(finding.description, "No active consumer group reads these topics: legacy-events, reports");
+
+        // Produce activity keeps a topic out, once per-topic metrics are available
+        snapshot.collectors.metrics = Some(json!({ PROMETHEUS_METRICS_KEY: { "endpoints": { "kafka-1": {
+            "url": "http://kafka-1:7071/metrics",
+            "families": { "kafka_server_brokertopicmetrics_bytesinpersec_oneminuterate": { "type": "gauge", "samples": [
+                {"name": "kafka_server_brokertopicmetrics_bytesinpersec_oneminuterate", "labels": {"topic": "reports"}, "value": 2048.0},
+                {"name": "kafka_server_brokertopicmetrics_bytesinpersec_oneminuterate", "labels": {"topic": "legacy-events"}, "value": 0.0},
+            ]}},
+        }}}}));
+        let findings = OrphanedTopicsAnalyzer::new().analyze(&snapshot).await.unwrap();
+        assert_eq!(findings[0].metadata["topics"], json!(["legacy-events"]));
+        assert!(findings[0].description.contains("and no producer wrote to them recently"));
+    }
+
+    #[tokio::test]
+    async fn test_skipped_without_consumer_data() {
+        let without_groups = snapshot(&["orders", "legacy-events"], None);
+        assert!(OrphanedTopicsAnalyzer::new().analyze(&without_groups).await.unwrap().is_empty());
+
+        // One document per group parses the same as a list
+        let snapshot = snapshot(&["orders", "legacy-events"], Some("name: billing\ntopics:\n- orders\n---\nname: audit\n"));
+        let groups = consumer_group_subscriptions(&snapshot).unwrap();
+        assert_eq!(groups.len(), 2);
+        let findings = OrphanedTopicsAnalyzer::new().analyze(&snapshot).await.unwrap();
+        assert_eq!(findings[0].metadata["topics"], json!(["legacy-events"]));
+    }
+
+    #[tokio::test]
+    async fn test_scanned_consumer_groups() {
+        // Scan directory as written by the scan: the admin collector output, the kafkactl group
+        // list (without topics) and the lag summarized from the group descriptions
+        let dir = tempfile::tempdir().unwrap();
+        let cluster = dir.path().join("cluster");
+        std::fs::create_dir_all(cluster.join("collectors")).unwrap();
+        std::fs::create_dir_all(cluster.join("kafkactl")).unwrap();
+        let topics: Vec<_> = ["orders", "reports", "legacy-events", "__consumer_offsets"].iter()
+            .map(|name| json!({"name": name, "partitions": [], "replication_factor": 3, "is_internal": name.starts_with("__")}))
+            .collect();
+        std::fs::write(cluster.join("collectors").join("admin.json"), json!({
+            "cluster": {"cluster_id": "abc", "controller_id": 1, "broker_count": 3, "topic_count": 4, "partition_count": 0},
+            "brokers": [],
+            "topics": topics,
+        }).to_string()).unwrap();
+        std::fs::write(cluster.join("kafkactl").join("consumer_groups.yaml"),
+            "- name: billing\n  protocolType: consumer\n- name: old-reporting\n  protocolType: consumer\n").unwrap();
+        let descriptions = "\
+---
+group:
+  name: billing
+  protocolType: consumer
+  state: Stable
+topics:
+- name: orders
+  partitions:
+  - partition: 0
+    lag: 3
+members:
+- consumerId: billing-1
+---
+group:
+  name: old-reporting
+  protocolType: consumer
+  state: Empty
+topics:
+- name: reports
+  partitions:
+  - partition: 0
+    lag: 5000
+";
+        let lag = crate::scan::consumer_lag::parse_group_descriptions(descriptions);
+        std::fs::write(cluster.join("kafkactl").join(crate::scan::consumer_lag::CONSUMER_LAG_FILE),
+            serde_json::to_string_pretty(&lag).unwrap()).unwrap();
+
+        let snapshot = crate::cli::utils::load_snapshot_from_directory(dir.path()).unwrap();
+        let findings = OrphanedTopicsAnalyzer::new().analyze(&snapshot).await.unwrap();
+
+        // Only the empty group reads reports, so it is orphaned along with the unread topic
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].metadata["topics"], json!(["legacy-events", "reports"]));
+    }
+}
@@ -0,0 +1,282 @@
+use crate::analyzers::{server_properties, Analyzer, AnalyzerError, AnalyzerResult};
+use crate::collectors::admin::AdminCollectorOutput;
+use crate::snapshot::format::{
+    Category, ConfigEvidence, Evidence, Finding, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Rack of one broker and where it was read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokerRack {
+    pub rack: Option<String>,
+    /// server.properties path, or `admin` when only cluster metadata knew the broker
+    pub source: String,
+}
+
+/// `broker.rack` of every known broker, from server.properties first and cluster metadata second
+pub fn broker_racks(snapshot: &Snapshot, admin: Option<&AdminCollectorOutput>) -> BTreeMap<i32, BrokerRack> {
+    let mut racks = BTreeMap::new();
+
+    for (file, props) in server_properties(snapshot) {
+        let Some(id) = props.get("broker.id").or_else(|| props.get("node.id")).and_then(|id| id.trim().parse().ok()) else {
+            continue;
+        };
+        let rack = props.get("broker.rack").map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+        racks.insert(id, BrokerRack { rack, source: file });
+    }
+
+    for broker in admin.map(|admin| admin.brokers.as_slice()).unwrap_or_default() {
+        let known = racks.entry(broker.id).or_insert_with(|| BrokerRack { rack: None, source: "admin".to_string() });
+        if known.rack.is_none() {
+            known.rack = broker.rack.clone().filter(|r| !r.is_empty());
+        }
+    }
+
+    racks
+}
+
+/// Checks that brokers set `broker.rack` and that topic replicas span racks
+pub struct RackAwarenessAnalyzer;
+
+impl Default for RackAwarenessAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RackAwarenessAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn finding(
+        title: String,
+        description: String,
+        severity: Severity,
+        impact: &str,
+        steps: &[&str],
+        configs: Vec<ConfigEvidence>,
+    ) -> Finding {
+        Finding {
+            id: String::new(),
+            title,
+            description,
+            severity,
+            category: Category::Availability,
+            impact: impact.to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs,
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: steps.iter().enumerate().map(|(i, step)| RemediationStep {
+                    order: i as u32 + 1,
+                    description: step.to_string(),
+                    command: None,
+                    verification: None,
+                    can_automate: false,
+                }).collect(),
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: None,
+                rollback_plan: None,
+            },
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn rack_evidence(id: i32, broker: &BrokerRack, reason: &str) -> ConfigEvidence {
+        ConfigEvidence {
+            resource_type: "broker".to_string(),
+            resource_name: id.to_string(),
+            config_key: "broker.rack".to_string(),
+            current_value: broker.rack.clone().unwrap_or_else(|| "(not set)".to_string()),
+            recommended_value: None,
+            reason: reason.to_string(),
+            source_files: vec![broker.source.clone()],
+        }
+    }
+
+    /// Topics with partitions whose replicas, on brokers with a known rack, all share one rack
+    fn single_rack_topics(admin: &AdminCollectorOutput, racks: &BTreeMap<i32, BrokerRack>) -> Vec<(String, usize, usize)> {
+        admin.topics.iter()
+            .filter_map(|topic| {
+                let single_rack = topic.partitions.iter()
+                    .filter(|partition| {
+                        let known: Vec<&str> = partition.replicas.iter()
+                            .filter_map(|id| racks.get(id)?.rack.as_deref())
+                            .collect();
+                        known.len() > 1 && known.iter().collect::<BTreeSet<_>>().len() == 1
+                    })
+                    .count();
+                (single_rack > 0).then(|| (topic.name.clone(), single_rack, topic.partitions.len()))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Analyzer for RackAwarenessAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let admin: Option<AdminCollectorOutput> = snapshot.collectors.admin.as_ref()
+            .map(|admin| serde_json::from_value(admin.clone()))
+            .transpose()
+            .map_err(|e| AnalyzerError::InvalidData(format!("Failed to parse admin data: {}", e)))?;
+        let racks = broker_racks(snapshot, admin.as_ref());
+        if racks.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let distinct_racks: BTreeSet<&str> = racks.values().filter_map(|b| b.rack.as_deref()).collect();
+        let mut findings = Vec::new();
+
+        if distinct_racks.is_empty() {
+            // Nothing to check on a single-AZ cluster beyond noting it
+            findings.push(Self::finding(
+                "No broker sets broker.rack".to_string(),
+                format!(
+                    "None of the {} broker(s) sets broker.rack, so Kafka cannot spread replicas across racks or \
+                    availability zones. This is expected for a cluster in a single zone.",
+                    racks.len()
+                ),
+                Severity::Info,
+                "INFO: If the brokers span zones, losing one zone can take every replica of a partition offline.",
+                &["If brokers run in several racks or availability zones, set broker.rack on each and reassign partitions"],
+                Vec::new(),
+            ));
+        } else {
+            let missing: Vec<(&i32, &BrokerRack)> = racks.iter().filter(|(_, b)| b.rack.is_none()).collect();
+            if !missing.is_empty() {
+                let ids: Vec<String> = missing.iter().map(|(id, _)| id.to_string()).collect();
+                findings.push(Self::finding(
+                    format!("{} broker(s) missing broker.rack", missing.len()),
+                    format!(
+                        "Brokers {} do not set broker.rack while others use racks {}. Their replicas are placed \
+                        without regard to rack.",
+                        ids.join(", "),
+                        distinct_racks.iter().copied().collect::<Vec<_>>().join(", ")
+                    ),
+                    Severity::Info,
+                    "INFO: Replicas on brokers without a rack may end up in the same zone as their peers.",
+                    &["Set broker.rack on the listed brokers and restart them one at a time"],
+                    missing.iter().map(|(id, broker)| Self::rack_evidence(**id, broker, "broker.rack is not set")).collect(),
+                ));
+            }
+
+            if distinct_racks.len() > 1 {
+                if let Some(admin) = &admin {
+                    for (topic, single_rack, partitions) in Self::single_rack_topics(admin, &racks) {
+                        findings.push(Self::finding(
+                            format!("Topic {} has replicas confined to one rack", topic),
+                            format!(
+                                "{} of {} partition(s) of {} keep all replicas in a single rack although the \
+                                cluster spans {} racks.",
+                                single_rack, partitions, topic, distinct_racks.len()
+                            ),
+                            Severity::Medium,
+                            "MEDIUM: An outage of that rack or zone takes these partitions offline.",
+                            &[
+                                "Generate a rack-aware reassignment plan with kafka-reassign-partitions.sh --generate",
+                                "Execute the plan and verify the replicas span racks",
+                            ],
+                            Vec::new(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("RACK-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Rack Awareness Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Checks broker.rack settings and that topic replicas are spread across racks"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    fn snapshot(racks: &[Option<&str>], replicas: &[&[i32]]) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let configs: serde_json::Map<String, serde_json::Value> = racks.iter().enumerate()
+            .map(|(i, rack)| {
+                let mut props = format!("broker.id={}\n", i + 1);
+                if let Some(rack) = rack {
+                    props.push_str(&format!("broker.rack={}\n", rack));
+                }
+                (format!("broker_{}/server.properties", i + 1), json!(props))
+            })
+            .collect();
+        snapshot.collectors.config = Some(json!(configs));
+
+        let partitions: Vec<serde_json::Value> = replicas.iter().enumerate()
+            .map(|(id, replicas)| json!({
+                "id": id, "leader": replicas[0], "replicas": replicas, "isr": replicas, "offline_replicas": []
+            }))
+            .collect();
+        snapshot.collectors.admin = Some(json!({
+            "cluster": {"cluster_id": null, "controller_id": 1, "broker_count": racks.len(), "topic_count": 1, "partition_count": partitions.len()},
+            "brokers": [],
+            "topics": [{"name": "orders", "partitions": partitions, "replication_factor": 2, "is_internal": false}],
+        }));
+        snapshot
+    }
+
+    #[tokio::test]
+    async fn test_racked_layout_spread_across_racks() {
+        let snapshot = snapshot(&[Some("az-a"), Some("az-b"), Some("az-c")], &[&[1, 2], &[2, 3], &[3, 1]]);
+        let findings = RackAwarenessAnalyzer::new().analyze(&snapshot).await.unwrap();
+        assert!(findings.is_empty(), "{:?}", findings);
+    }
+
+    #[tokio::test]
+    async fn test_replicas_sharing_a_rack_flagged() {
+        let snapshot = snapshot(&[Some("az-a"), Some("az-a"), Some("az-b")], &[&[1, 2], &[2, 3], &[1, 3]]);
+        let findings = RackAwarenessAnalyzer::new().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].id, "RACK-001");
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert!(matches!(findings[0].category, Category::Availability));
+        assert!(findings[0].description.starts_with("1 of 3 partition(s) of orders"));
+    }
+
+    #[tokio::test]
+    async fn test_broker_missing_rack_is_info() {
+        let snapshot = snapshot(&[Some("az-a"), Some("az-b"), None], &[&[1, 2], &[2, 3]]);
+        let findings = RackAwarenessAnalyzer::new().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Info);
+        assert_eq!(findings[0].title, "1 broker(s) missing broker.rack");
+        assert_eq!(findings[0].evidence.configs[0].resource_name, "3");
+    }
+
+    #[tokio::test]
+    async fn test_non_racked_cluster_only_notes() {
+        let snapshot = snapshot(&[None, None, None], &[&[1, 2], &[2, 3]]);
+        let findings = RackAwarenessAnalyzer::new().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Info);
+        assert_eq!(findings[0].title, "No broker sets broker.rack");
+    }
+}
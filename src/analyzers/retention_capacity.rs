@@ -0,0 +1,452 @@
+use crate::analyzers::disk_growth::{disk_usage, DiskUsage};
+use crate::analyzers::hot_topics::topic_throughput;
+use crate::analyzers::{server_properties, Analyzer, AnalyzerResult};
+use crate::collectors::admin::AdminCollectorOutput;
+use crate::snapshot::format::{
+    Category, ConfigEvidence, Evidence, Finding, MetricEvidence, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// Share of the log directory capacity retained data may take before it is reported
+pub const RETENTION_DISK_HEADROOM: f64 = 0.85;
+
+/// Kafka's default `log.retention.hours`
+const DEFAULT_RETENTION_HOURS: f64 = 168.0;
+
+/// Kafka's default `log.dirs` when neither `log.dirs` nor `log.dir` is set
+const DEFAULT_LOG_DIR: &str = "/tmp/kafka-logs";
+
+/// Time-based retention settings, most specific first, with their unit in milliseconds
+const RETENTION_TIME_KEYS: [(&str, f64); 3] = [
+    ("log.retention.ms", 1.0),
+    ("log.retention.minutes", 60_000.0),
+    ("log.retention.hours", 3_600_000.0),
+];
+
+/// Broker-wide retention from server.properties; `None` limits mean unlimited
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetentionPolicy {
+    pub retention_ms: Option<f64>,
+    /// Setting the time limit was read from, with its raw value
+    pub time_setting: (String, String),
+    /// `log.retention.bytes`, a limit per partition
+    pub retention_bytes: Option<f64>,
+}
+
+impl RetentionPolicy {
+    pub fn from_properties(props: &HashMap<String, String>) -> Self {
+        let (time_setting, retention_ms) = RETENTION_TIME_KEYS.iter()
+            .find_map(|(key, unit_ms)| {
+                let value = props.get(*key)?.trim();
+                let number: f64 = value.parse().ok()?;
+                Some(((key.to_string(), value.to_string()), (number >= 0.0).then_some(number * unit_ms)))
+            })
+            .unwrap_or_else(|| (
+                ("log.retention.hours".to_string(), format!("{}", DEFAULT_RETENTION_HOURS)),
+                Some(DEFAULT_RETENTION_HOURS * 3_600_000.0),
+            ));
+        let retention_bytes = props.get("log.retention.bytes")
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .filter(|bytes| *bytes >= 0.0);
+
+        Self { retention_ms, time_setting, retention_bytes }
+    }
+}
+
+/// Total size of the filesystems holding the broker's `log.dirs`, each counted once
+pub fn log_dir_capacity(props: &HashMap<String, String>, mounts: &[DiskUsage]) -> Option<f64> {
+    let log_dirs = props.get("log.dirs").or_else(|| props.get("log.dir"))
+        .map(|dirs| dirs.as_str())
+        .unwrap_or(DEFAULT_LOG_DIR);
+
+    let mut backing: Vec<&DiskUsage> = Vec::new();
+    for dir in log_dirs.split(',').map(str::trim).filter(|dir| !dir.is_empty()) {
+        let mount = mounts.iter()
+            .filter(|m| m.mount == "/" || dir == m.mount || dir.starts_with(&format!("{}/", m.mount)))
+            .max_by_key(|m| m.mount.len());
+        if let Some(mount) = mount.filter(|mount| !backing.iter().any(|b| b.mount == mount.mount)) {
+            backing.push(mount);
+        }
+    }
+    (!backing.is_empty()).then(|| backing.iter().map(|m| m.size_bytes).sum())
+}
+
+/// Estimate of how much data one broker retains under its retention settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetentionProjection {
+    pub broker: String,
+    pub policy: RetentionPolicy,
+    pub capacity_bytes: f64,
+    /// Bytes written to the broker per second, replicas included, when throughput was scraped
+    pub write_bytes_per_sec: Option<f64>,
+    /// Partition replicas hosted by the broker, when cluster metadata was collected
+    pub replicas: Option<usize>,
+    /// Retained bytes at steady state; `None` when nothing bounds retention
+    pub retained_bytes: Option<f64>,
+    pub method: String,
+}
+
+impl RetentionProjection {
+    /// Whether the settings risk filling the log directories
+    pub fn is_risky(&self) -> bool {
+        match self.retained_bytes {
+            Some(retained) => retained > self.capacity_bytes * RETENTION_DISK_HEADROOM,
+            // Unlimited retention fills any disk eventually
+            None => self.policy.retention_ms.is_none() && self.policy.retention_bytes.is_none(),
+        }
+    }
+}
+
+fn gib(bytes: f64) -> f64 {
+    bytes / 1024f64.powi(3)
+}
+
+/// Combine retention settings, disk size and, when scraped, inbound throughput into a
+/// per-broker estimate of retained data
+pub fn project_retention(snapshot: &Snapshot) -> Vec<RetentionProjection> {
+    let disks = disk_usage(snapshot);
+    let configs = server_properties(snapshot);
+    let admin: Option<AdminCollectorOutput> = snapshot.collectors.admin.as_ref()
+        .and_then(|admin| serde_json::from_value(admin.clone()).ok());
+
+    // Inbound bytes are written once per replica and assumed to spread evenly over the brokers
+    let bytes_in: f64 = topic_throughput(snapshot).iter().map(|t| t.bytes_in_per_sec).sum();
+    let (partitions, replicas) = admin.iter()
+        .flat_map(|admin| &admin.topics)
+        .flat_map(|topic| &topic.partitions)
+        .fold((0usize, 0usize), |(partitions, replicas), p| (partitions + 1, replicas + p.replicas.len()));
+    let replication_factor = if partitions > 0 { replicas as f64 / partitions as f64 } else { 1.0 };
+    let broker_count = admin.as_ref().map(|admin| admin.brokers.len()).filter(|n| *n > 0).unwrap_or(configs.len()).max(1);
+    let write_rate = (bytes_in > 0.0).then(|| bytes_in * replication_factor / broker_count as f64);
+
+    configs.iter()
+        .filter_map(|(file, props)| {
+            let broker = file.split('/').next().unwrap_or(file).to_string();
+            let capacity_bytes = log_dir_capacity(props, disks.get(&broker)?)?;
+            let policy = RetentionPolicy::from_properties(props);
+
+            let broker_id: Option<i32> = props.get("broker.id").or_else(|| props.get("node.id"))
+                .and_then(|id| id.trim().parse().ok());
+            let hosted = admin.as_ref().zip(broker_id).map(|(admin, id)| {
+                admin.topics.iter()
+                    .flat_map(|topic| &topic.partitions)
+                    .filter(|p| p.replicas.contains(&id))
+                    .count()
+            });
+
+            let by_time = write_rate.zip(policy.retention_ms).map(|(rate, ms)| rate * ms / 1000.0);
+            let by_size = policy.retention_bytes.zip(hosted).map(|(bytes, hosted)| bytes * hosted as f64);
+            let (retained_bytes, method) = match write_rate {
+                Some(rate) => (
+                    by_time.into_iter().chain(by_size).reduce(f64::min),
+                    format!(
+                        "throughput: {:.2} MiB/s cluster inbound x {:.1} average replication factor / {} brokers \
+                        = {:.2} MiB/s written to this broker, kept for the retention time{}",
+                        bytes_in / 1024f64.powi(2),
+                        replication_factor,
+                        broker_count,
+                        rate / 1024f64.powi(2),
+                        if by_size.is_some() { ", capped by log.retention.bytes x hosted partitions" } else { "" }
+                    ),
+                ),
+                None if policy.retention_ms.is_none() && by_size.is_some() => (
+                    by_size,
+                    "settings: no throughput metrics; log.retention.bytes x partitions hosted by the broker, \
+                    as time-based retention is unlimited".to_string(),
+                ),
+                None => (
+                    None,
+                    "settings: no throughput metrics; only retention limits were checked".to_string(),
+                ),
+            };
+
+            Some(RetentionProjection {
+                broker,
+                policy,
+                capacity_bytes,
+                write_bytes_per_sec: write_rate,
+                replicas: hosted,
+                retained_bytes,
+                method,
+            })
+        })
+        .collect()
+}
+
+/// Flags broker retention settings that would keep more data than the log directories hold
+pub struct RetentionCapacityAnalyzer;
+
+impl RetentionCapacityAnalyzer {
+    fn finding(snapshot: &Snapshot, projection: &RetentionProjection) -> Finding {
+        let source_file = format!("{}/server.properties", projection.broker);
+        let (time_key, time_value) = &projection.policy.time_setting;
+        let config = |key: &str, value: &str, reason: &str| ConfigEvidence {
+            resource_type: "broker".to_string(),
+            resource_name: projection.broker.clone(),
+            config_key: key.to_string(),
+            current_value: value.to_string(),
+            recommended_value: None,
+            reason: reason.to_string(),
+            source_files: vec![source_file.clone()],
+        };
+        let retention_bytes = projection.policy.retention_bytes
+            .map(|bytes| format!("{}", bytes))
+            .unwrap_or_else(|| "-1".to_string());
+
+        let mut metrics = vec![MetricEvidence {
+            name: "log directory capacity".to_string(),
+            value: gib(projection.capacity_bytes),
+            threshold: None,
+            unit: Some("GiB".to_string()),
+            source: format!("{}/system/disk.txt", projection.broker),
+            timestamp: snapshot.timestamp,
+        }];
+        if let Some(retained) = projection.retained_bytes {
+            metrics.push(MetricEvidence {
+                name: "projected retained data".to_string(),
+                value: gib(retained),
+                threshold: Some(gib(projection.capacity_bytes * RETENTION_DISK_HEADROOM)),
+                unit: Some("GiB".to_string()),
+                source: "retention estimate".to_string(),
+                timestamp: snapshot.timestamp,
+            });
+        }
+        if let Some(rate) = projection.write_bytes_per_sec {
+            metrics.push(MetricEvidence {
+                name: "estimated write rate".to_string(),
+                value: rate / 1024f64.powi(2),
+                threshold: None,
+                unit: Some("MiB/s".to_string()),
+                source: "metrics/prometheus".to_string(),
+                timestamp: snapshot.timestamp,
+            });
+        }
+
+        let (title, description) = match projection.retained_bytes {
+            Some(retained) => (
+                format!("Retention on {} may exceed log directory capacity", projection.broker),
+                format!(
+                    "At its current retention settings {} is estimated to keep {:.1} GiB, more than {:.0}% of \
+                    its {:.1} GiB of log directory capacity.",
+                    projection.broker,
+                    gib(retained),
+                    RETENTION_DISK_HEADROOM * 100.0,
+                    gib(projection.capacity_bytes)
+                ),
+            ),
+            None => (
+                format!("Unlimited retention on {} with bounded disk", projection.broker),
+                format!(
+                    "{} keeps data forever ({}={}, log.retention.bytes={}) on {:.1} GiB of log directory capacity. \
+                    Unless every topic overrides retention, the disk fills up eventually.",
+                    projection.broker,
+                    time_key,
+                    time_value,
+                    retention_bytes,
+                    gib(projection.capacity_bytes)
+                ),
+            ),
+        };
+
+        Finding {
+            id: String::new(),
+            title,
+            description,
+            severity: Severity::Medium,
+            category: Category::Capacity,
+            impact: "MEDIUM: A broker whose log directory fills up stops accepting writes and takes its partitions offline.".to_string(),
+            evidence: Evidence {
+                metrics,
+                logs: Vec::new(),
+                configs: vec![
+                    config(time_key, time_value, "Broker-wide time-based retention"),
+                    config("log.retention.bytes", &retention_bytes, "Broker-wide size-based retention per partition"),
+                ],
+                raw_data: Some(serde_json::json!({
+                    "estimation_method": projection.method,
+                    "capacity_bytes": projection.capacity_bytes,
+                    "retained_bytes": projection.retained_bytes,
+                    "write_bytes_per_sec": projection.write_bytes_per_sec,
+                    "hosted_replicas": projection.replicas,
+                    "note": "Topic-level retention.ms and retention.bytes overrides are not taken into account",
+                })),
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Retention limits sized without regard to disk capacity and write rate".to_string()),
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Check how much each topic actually keeps on the broker".to_string(),
+                        command: Some("kafka-log-dirs.sh --bootstrap-server <broker> --describe --broker-list <id>".to_string()),
+                        verification: None,
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Bound retention with log.retention.hours or log.retention.bytes, or add disk capacity".to_string(),
+                        command: Some(
+                            "kafka-configs.sh --bootstrap-server <broker> --entity-type brokers --entity-default --alter --add-config log.retention.bytes=<bytes>".to_string()
+                        ),
+                        verification: Some("df -h <log.dirs>".to_string()),
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(30),
+                rollback_plan: Some("Restore the previous retention settings".to_string()),
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for RetentionCapacityAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let mut findings: Vec<Finding> = project_retention(snapshot).iter()
+            .filter(|projection| projection.is_risky())
+            .map(|projection| Self::finding(snapshot, projection))
+            .collect();
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("RETENTION-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Retention Capacity Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Estimates whether broker retention settings keep more data than the log directories hold"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collectors::prometheus_scrape::PROMETHEUS_METRICS_KEY;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    const DF: &str = "Filesystem      Size  Used Avail Use% Mounted on\n\
+        /dev/sda1        50G   12G   38G  24% /\n\
+        /dev/sdb1       1.0T  200G  824G  20% /var/lib/kafka\n";
+
+    /// Three brokers with `properties` and a 1 TiB log disk, 30 RF-3 partitions spread over them
+    fn snapshot(properties: &str, bytes_in_per_broker: Option<f64>) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let mut config = serde_json::Map::new();
+        let mut brokers = serde_json::Map::new();
+        for id in 1..=3 {
+            config.insert(
+                format!("broker_{}/server.properties", id),
+                json!(format!("broker.id={}\nlog.dirs=/var/lib/kafka/data\n{}", id, properties)),
+            );
+            brokers.insert(format!("broker_{}", id), json!({"system": {"disk.txt": DF}}));
+        }
+        snapshot.collectors.config = Some(json!(config));
+        snapshot.collectors.custom.insert("brokers".to_string(), json!(brokers));
+
+        let partitions: Vec<_> = (0..30)
+            .map(|id| json!({"id": id, "leader": 1, "replicas": [1, 2, 3], "isr": [1, 2, 3], "offline_replicas": []}))
+            .collect();
+        snapshot.collectors.admin = Some(json!({
+            "cluster": {"cluster_id": null, "controller_id": 1, "broker_count": 3, "topic_count": 1, "partition_count": 30},
+            "brokers": (1..=3).map(|id| json!({"id": id, "host": format!("kafka-{}", id), "port": 9092, "rack": null})).collect::<Vec<_>>(),
+            "topics": [{"name": "events", "partitions": partitions, "replication_factor": 3, "is_internal": false}],
+        }));
+
+        if let Some(rate) = bytes_in_per_broker {
+            let endpoints: serde_json::Map<String, serde_json::Value> = (1..=3)
+                .map(|id| (format!("kafka-{}", id), json!({
+                    "url": format!("http://kafka-{}:9404/metrics", id),
+                    "families": {"kafka_server_brokertopicmetrics_bytesinpersec": {"type": "gauge", "samples": [
+                        {"name": "kafka_server_brokertopicmetrics_bytesinpersec_oneminuterate", "labels": {"topic": "events"}, "value": rate}
+                    ]}},
+                })))
+                .collect();
+            snapshot.collectors.metrics = Some(json!({ PROMETHEUS_METRICS_KEY: { "endpoints": endpoints } }));
+        }
+        snapshot
+    }
+
+    #[test]
+    fn test_retention_policy_and_capacity() {
+        let props = |text: &str| crate::scan::cluster_detection::parse_server_properties(text);
+
+        let policy = RetentionPolicy::from_properties(&props("log.retention.hours=24\nlog.retention.ms=3600000\n"));
+        assert_eq!(policy.retention_ms, Some(3_600_000.0));
+        assert_eq!(policy.time_setting.0, "log.retention.ms");
+        assert_eq!(policy.retention_bytes, None);
+
+        let unlimited = RetentionPolicy::from_properties(&props("log.retention.ms=-1\nlog.retention.bytes=-1\n"));
+        assert_eq!((unlimited.retention_ms, unlimited.retention_bytes), (None, None));
+        assert_eq!(RetentionPolicy::from_properties(&HashMap::new()).retention_ms, Some(168.0 * 3_600_000.0));
+
+        let mounts = crate::analyzers::disk_growth::parse_df(DF);
+        let capacity = log_dir_capacity(&props("log.dirs=/var/lib/kafka/a,/var/lib/kafka/b\n"), &mounts);
+        assert_eq!(capacity, Some(1024f64.powi(4)));
+        assert_eq!(log_dir_capacity(&HashMap::new(), &mounts), Some(50.0 * 1024f64.powi(3)));
+    }
+
+    #[tokio::test]
+    async fn test_projection_with_throughput() {
+        // 3 x 10 MiB/s inbound, replicated 3 times over 3 brokers: 30 MiB/s per broker.
+        // A week of that is ~17.3 TiB, far more than the 1 TiB disk.
+        let week = snapshot("log.retention.hours=168\n", Some(10.0 * 1024f64.powi(2)));
+        let findings = RetentionCapacityAnalyzer.analyze(&week).await.unwrap();
+
+        assert_eq!(findings.len(), 3);
+        let finding = &findings[0];
+        assert_eq!(finding.id, "RETENTION-001");
+        assert_eq!(finding.severity, Severity::Medium);
+        assert!(matches!(finding.category, Category::Capacity));
+        assert!(finding.title.contains("broker_1"));
+        let retained = &finding.evidence.metrics[1];
+        assert!((retained.value - 30.0 * 168.0 * 3600.0 / 1024.0).abs() < 1.0, "{}", retained.value);
+        let method = finding.evidence.raw_data.as_ref().unwrap()["estimation_method"].as_str().unwrap();
+        assert!(method.starts_with("throughput: 30.00 MiB/s cluster inbound x 3.0 average replication factor / 3 brokers"));
+
+        // Six hours is ~0.6 TiB, within the headroom
+        let six_hours = snapshot("log.retention.hours=6\n", Some(10.0 * 1024f64.powi(2)));
+        assert!(RetentionCapacityAnalyzer.analyze(&six_hours).await.unwrap().is_empty());
+
+        // A size cap of 10 GiB for each of the 30 hosted partitions bounds a week to 300 GiB
+        let capped = snapshot("log.retention.hours=168\nlog.retention.bytes=10737418240\n", Some(10.0 * 1024f64.powi(2)));
+        assert!(RetentionCapacityAnalyzer.analyze(&capped).await.unwrap().is_empty());
+        assert_eq!(project_retention(&capped)[0].retained_bytes, Some(300.0 * 1024f64.powi(3)));
+    }
+
+    #[tokio::test]
+    async fn test_projection_without_throughput() {
+        // A week of retention is not flagged without knowing the write rate
+        assert!(RetentionCapacityAnalyzer.analyze(&snapshot("log.retention.hours=168\n", None)).await.unwrap().is_empty());
+
+        let unlimited = snapshot("log.retention.ms=-1\n", None);
+        let findings = RetentionCapacityAnalyzer.analyze(&unlimited).await.unwrap();
+        assert_eq!(findings.len(), 3);
+        assert!(findings[0].title.starts_with("Unlimited retention on broker_1"));
+        assert_eq!(findings[0].evidence.configs[0].config_key, "log.retention.ms");
+        assert_eq!(findings[0].evidence.configs[0].current_value, "-1");
+        let method = findings[0].evidence.raw_data.as_ref().unwrap()["estimation_method"].as_str().unwrap();
+        assert!(method.starts_with("settings: no throughput metrics"));
+
+        // Unlimited time but 50 GiB per partition over 30 partitions is 1.5 TiB on a 1 TiB disk
+        let size_only = snapshot("log.retention.ms=-1\nlog.retention.bytes=53687091200\n", None);
+        let findings = RetentionCapacityAnalyzer.analyze(&size_only).await.unwrap();
+        assert_eq!(findings.len(), 3);
+        assert!(findings[0].title.contains("may exceed log directory capacity"));
+
+        // Without disk data nothing can be said
+        let mut no_disk = snapshot("log.retention.ms=-1\n", None);
+        no_disk.collectors.custom.remove("brokers");
+        assert!(RetentionCapacityAnalyzer.analyze(&no_disk).await.unwrap().is_empty());
+    }
+}
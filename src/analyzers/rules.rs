@@ -1,10 +1,16 @@
-use super::{Analyzer, AnalyzerError, AnalyzerResult};
+use super::{server_properties, Analyzer, AnalyzerError, AnalyzerResult};
+use super::severity_overrides::RULE_METADATA_KEY;
 use crate::collectors::admin::AdminCollectorOutput;
+use crate::collectors::connect::{ConnectCollectorOutput, CONNECT_DATA_KEY};
+use crate::collectors::logs::{classify_error, ErrorCategory, LogCollectorOutput, LogLevel};
+use crate::scan::peer_latency::PeerLatency;
 use crate::snapshot::format::{
-    Category, Evidence, Finding, LogEvidence, MetricEvidence, 
+    Category, ConfigEvidence, Evidence, Finding, LogEvidence, MetricEvidence, 
     Remediation, RemediationStep, RiskLevel, Severity, Snapshot
 };
 use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::fmt::Display;
 use tracing::{debug, info};
 
 /// Rule-based analyzer for deterministic health checks
@@ -25,6 +31,21 @@ impl RuleAnalyzer {
         }
     }
     
+    /// Build the analyzer with the partition limit from `MAX_PARTITIONS_PER_TOPIC`, if set
+    pub fn from_env() -> Self {
+        match std::env::var(MAX_PARTITIONS_PER_TOPIC_ENV).ok().and_then(|v| v.trim().parse::<usize>().ok()) {
+            Some(max_partitions) if max_partitions > 0 => Self::new().with_max_partitions_per_topic(max_partitions),
+            _ => Self::new(),
+        }
+    }
+
+    /// Set the partition count above which a single topic is reported as over-partitioned
+    pub fn with_max_partitions_per_topic(mut self, max_partitions: usize) -> Self {
+        self.enabled_rules.retain(|rule| rule.name() != "over_partitioned_topics");
+        self.enabled_rules.push(Box::new(OverPartitionedTopicsRule { max_partitions }));
+        self
+    }
+
     fn default_rules() -> Vec<Box<dyn Rule>> {
         vec![
             Box::new(UnderReplicatedPartitionsRule),
@@ -32,6 +53,17 @@ impl RuleAnalyzer {
             Box::new(LeaderImbalanceRule),
             Box::new(IsrShrinkageRule),
             Box::new(HighErrorRateRule),
+            Box::new(LogErrorCategoryRule { category: ErrorCategory::DiskFull }),
+            Box::new(LogErrorCategoryRule { category: ErrorCategory::LeaderElection }),
+            Box::new(LogErrorCategoryRule { category: ErrorCategory::NetworkTimeout }),
+            Box::new(LogErrorCategoryRule { category: ErrorCategory::AuthFailure }),
+            Box::new(LogErrorCategoryRule { category: ErrorCategory::CorruptSegment }),
+            Box::new(FailedConnectorsRule),
+            Box::new(PausedConnectorsRule),
+            Box::new(PeerLatencyRule),
+            Box::new(UnderPartitionedTopicsRule),
+            Box::new(OverPartitionedTopicsRule { max_partitions: DEFAULT_MAX_PARTITIONS_PER_TOPIC }),
+            Box::new(InternalTopicsRule),
         ]
     }
 }
@@ -45,8 +77,9 @@ impl Analyzer for RuleAnalyzer {
         
         for rule in &self.enabled_rules {
             match rule.evaluate(snapshot) {
-                Ok(Some(finding)) => {
+                Ok(Some(mut finding)) => {
                     debug!("Rule {} produced finding: {}", rule.name(), finding.title);
+                    finding.metadata.insert(RULE_METADATA_KEY.to_string(), serde_json::json!(rule.name()));
                     findings.push(finding);
                 }
                 Ok(None) => {
@@ -77,6 +110,23 @@ trait Rule: Send + Sync {
     fn name(&self) -> &'static str;
 }
 
+/// Id of a rule finding: the rule's prefix and a UUID derived from the rule name and the
+/// affected resources, so the same condition gets the same id on every run
+fn finding_id<R: Display>(prefix: &str, rule: &str, resources: impl IntoIterator<Item = R>) -> String {
+    let mut resources: Vec<String> = resources.into_iter().map(|r| r.to_string()).collect();
+    resources.sort();
+
+    let mut hasher = Sha256::new();
+    hasher.update(rule.as_bytes());
+    for resource in &resources {
+        hasher.update([0]);
+        hasher.update(resource.as_bytes());
+    }
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&hasher.finalize()[..16]);
+    format!("{}-{}", prefix, uuid::Builder::from_custom_bytes(bytes).into_uuid())
+}
+
 /// Rule: Check for under-replicated partitions
 struct UnderReplicatedPartitionsRule;
 
@@ -104,7 +154,7 @@ impl Rule for UnderReplicatedPartitionsRule {
         }
         
         let finding = Finding {
-            id: format!("FND-001-{}", uuid::Uuid::new_v4()),
+            id: finding_id("FND-001", self.name(), under_replicated.iter().map(|(topic, partition)| format!("{}-{}", topic, partition))),
             severity: Severity::High,
             category: Category::Availability,
             title: format!("Under-replicated partitions detected: {} affected", under_replicated.len()),
@@ -127,6 +177,7 @@ impl Rule for UnderReplicatedPartitionsRule {
                 raw_data: Some(serde_json::json!({
                     "affected_partitions": under_replicated
                 })),
+                timeline: Vec::new(),
             },
             root_cause: Some("Possible causes: broker failures, network issues, disk problems, or high load".to_string()),
             remediation: Remediation {
@@ -196,7 +247,7 @@ impl Rule for OfflinePartitionsRule {
         }
         
         let finding = Finding {
-            id: format!("FND-002-{}", uuid::Uuid::new_v4()),
+            id: finding_id("FND-002", self.name(), offline.iter().map(|(topic, partition)| format!("{}-{}", topic, partition))),
             severity: Severity::Critical,
             category: Category::Availability,
             title: format!("Offline partitions detected: {} affected", offline.len()),
@@ -219,6 +270,7 @@ impl Rule for OfflinePartitionsRule {
                 raw_data: Some(serde_json::json!({
                     "offline_partitions": offline
                 })),
+                timeline: Vec::new(),
             },
             root_cause: Some("All replicas for these partitions are down or unreachable".to_string()),
             remediation: Remediation {
@@ -300,8 +352,13 @@ impl Rule for LeaderImbalanceRule {
             return Ok(None);
         }
         
+        // Brokers leading more partitions than the threshold allows
+        let overloaded = leader_count.iter()
+            .filter(|(_, count)| **count as f64 > avg_leaders as f64 * (1.0 + imbalance_threshold))
+            .map(|(broker, _)| broker);
+        
         let finding = Finding {
-            id: format!("FND-003-{}", uuid::Uuid::new_v4()),
+            id: finding_id("FND-003", self.name(), overloaded),
             severity: Severity::Medium,
             category: Category::Performance,
             title: "Leader distribution imbalance detected".to_string(),
@@ -334,6 +391,7 @@ impl Rule for LeaderImbalanceRule {
                 raw_data: Some(serde_json::json!({
                     "leader_distribution": leader_count
                 })),
+                timeline: Vec::new(),
             },
             root_cause: Some("Preferred leader election not running or broker failures causing imbalance".to_string()),
             remediation: Remediation {
@@ -397,7 +455,7 @@ impl Rule for IsrShrinkageRule {
         }
         
         let finding = Finding {
-            id: format!("FND-004-{}", uuid::Uuid::new_v4()),
+            id: finding_id("FND-004", self.name(), shrunk_isr.iter().map(|(topic, partition, _)| format!("{}-{}", topic, partition))),
             severity: Severity::High,
             category: Category::Availability,
             title: format!("ISR shrinkage detected: {} partitions at risk", shrunk_isr.len()),
@@ -420,6 +478,7 @@ impl Rule for IsrShrinkageRule {
                 raw_data: Some(serde_json::json!({
                     "affected_partitions": shrunk_isr
                 })),
+                timeline: Vec::new(),
             },
             root_cause: Some("Brokers falling behind on replication due to network, disk, or load issues".to_string()),
             remediation: Remediation {
@@ -463,34 +522,41 @@ impl Rule for IsrShrinkageRule {
     }
 }
 
-/// Rule: Check for high error rate in logs
+/// Parse collected broker logs if they were collected
+fn logs_data(snapshot: &Snapshot) -> AnalyzerResult<Option<LogCollectorOutput>> {
+    match snapshot.collectors.logs.as_ref() {
+        Some(data) => serde_json::from_value(data.clone())
+            .map(Some)
+            .map_err(|e| AnalyzerError::InvalidData(format!("Failed to parse logs data: {}", e))),
+        None => Ok(None),
+    }
+}
+
+/// Rule: Check for high error rate in logs. Errors in a known category are
+/// reported by `LogErrorCategoryRule` with targeted remediation instead.
 struct HighErrorRateRule;
 
 impl Rule for HighErrorRateRule {
     fn evaluate(&self, snapshot: &Snapshot) -> AnalyzerResult<Option<Finding>> {
-        let logs_data = snapshot.collectors.logs.as_ref();
-        
-        if logs_data.is_none() {
+        let Some(logs) = logs_data(snapshot)? else {
             return Ok(None);
-        }
-        
-        let logs: crate::collectors::logs::LogCollectorOutput = serde_json::from_value(logs_data.unwrap().clone())
-            .map_err(|e| AnalyzerError::InvalidData(format!("Failed to parse logs data: {}", e)))?;
+        };
         
         let error_rate_threshold = 10; // errors per minute threshold
-        let total_errors = logs.summary.error_count;
+        let categorized: usize = logs.summary.error_categories.values().sum();
+        let total_errors = logs.summary.error_count.saturating_sub(categorized);
         
         if total_errors < error_rate_threshold {
             return Ok(None);
         }
         
         let finding = Finding {
-            id: format!("FND-005-{}", uuid::Uuid::new_v4()),
+            id: finding_id("FND-005", self.name(), std::iter::empty::<&str>()),
             severity: Severity::High,
             category: Category::Performance,
             title: format!("High error rate in logs: {} errors found", total_errors),
             description: format!(
-                "Detected {} errors in recent logs, which exceeds the threshold of {} errors",
+                "Detected {} uncategorized errors in recent logs, which exceeds the threshold of {} errors",
                 total_errors, error_rate_threshold
             ),
             impact: "High error rate indicates system instability and potential service degradation".to_string(),
@@ -513,6 +579,7 @@ impl Rule for HighErrorRateRule {
                 }).collect(),
                 configs: Vec::new(),
                 raw_data: None,
+                timeline: Vec::new(),
             },
             root_cause: Some("Review top error patterns to identify specific issues".to_string()),
             remediation: Remediation {
@@ -548,3 +615,1107 @@ impl Rule for HighErrorRateRule {
         "high_error_rate"
     }
 }
+
+/// Rule: Report broker log errors of one known failure category, with remediation for that kind of failure
+struct LogErrorCategoryRule {
+    category: ErrorCategory,
+}
+
+impl LogErrorCategoryRule {
+    /// Finding id prefix, errors at which a finding is raised, severity and finding category
+    fn profile(&self) -> (&'static str, usize, Severity, Category) {
+        match self.category {
+            // A full disk or a corrupt segment takes log directories offline, so one line is enough
+            ErrorCategory::DiskFull => ("FND-012", 1, Severity::Critical, Category::Capacity),
+            ErrorCategory::CorruptSegment => ("FND-016", 1, Severity::Critical, Category::Availability),
+            ErrorCategory::LeaderElection => ("FND-013", 10, Severity::High, Category::Availability),
+            ErrorCategory::NetworkTimeout => ("FND-014", 10, Severity::High, Category::Performance),
+            ErrorCategory::AuthFailure => ("FND-015", 10, Severity::Medium, Category::Security),
+        }
+    }
+
+    fn impact_and_root_cause(&self) -> (&'static str, &'static str) {
+        match self.category {
+            ErrorCategory::DiskFull => (
+                "Brokers take a log directory offline when writes to it fail, moving its partitions away or leaving them offline",
+                "A log directory ran out of space, usually because retention is longer than the disk can hold at the current ingest rate",
+            ),
+            ErrorCategory::LeaderElection => (
+                "Producers and consumers retry while partition leadership moves, raising latency and causing failed requests",
+                "Brokers restarting or losing their controller/ZooKeeper session, or long GC pauses causing repeated leader changes",
+            ),
+            ErrorCategory::NetworkTimeout => (
+                "Replication and client requests time out, which slows replication and can shrink ISRs",
+                "Unreachable brokers, overloaded network threads, or firewall and DNS problems between brokers",
+            ),
+            ErrorCategory::AuthFailure => (
+                "Clients or brokers failing authentication cannot produce or consume, and repeated failures can indicate probing",
+                "Expired credentials or certificates, mismatched SASL mechanisms, or clients using the wrong listener",
+            ),
+            ErrorCategory::CorruptSegment => (
+                "Corrupt segments can lose records and stop replicas from fetching the affected partitions",
+                "An unclean shutdown, a failing disk, or a filesystem issue damaged log or index files",
+            ),
+        }
+    }
+
+    fn remediation_steps(&self) -> Vec<RemediationStep> {
+        let step = |order, description: &str, command: Option<&str>, verification: &str, can_automate| RemediationStep {
+            order,
+            description: description.to_string(),
+            command: command.map(str::to_string),
+            verification: Some(verification.to_string()),
+            can_automate,
+        };
+        match self.category {
+            ErrorCategory::DiskFull => vec![
+                step(1, "Check free space in every log directory", Some("kafka-log-dirs.sh --bootstrap-server localhost:9092 --describe"), "All log directories have free space", true),
+                step(2, "Lower retention.ms or retention.bytes of the largest topics to free space", Some("kafka-configs.sh --bootstrap-server localhost:9092 --alter --entity-type topics --entity-name <topic> --add-config retention.ms=<ms>"), "Disk usage dropping after the next log cleanup", false),
+                step(3, "Add disk capacity or reassign partitions to brokers with free space", None, "No more 'No space left on device' errors", false),
+            ],
+            ErrorCategory::LeaderElection => vec![
+                step(1, "Find partitions without a leader or with a shrinking ISR", Some("kafka-topics.sh --bootstrap-server localhost:9092 --describe --unavailable-partitions"), "No unavailable partitions", true),
+                step(2, "Check for broker restarts, controller changes and long GC pauses around the errors", Some("grep -E 'Controller|GC pause|Shutting down' /var/log/kafka/server.log"), "Root cause of leadership changes identified", false),
+                step(3, "Rebalance leadership once brokers are stable", Some("kafka-leader-election.sh --bootstrap-server localhost:9092 --election-type preferred --all-topic-partitions"), "Leaders back on preferred replicas", false),
+            ],
+            ErrorCategory::NetworkTimeout => vec![
+                step(1, "Check connectivity between brokers on the inter-broker listener", Some("nc -vz <broker-host> 9092"), "Every broker reaches every other broker", true),
+                step(2, "Check network thread and request handler saturation", Some("kafka-run-class.sh kafka.tools.JmxTool --object-name kafka.network:type=SocketServer,name=NetworkProcessorAvgIdlePercent"), "Network processors idle above 30%", false),
+                step(3, "Raise replica.socket.timeout.ms or request.timeout.ms only after ruling out network problems", None, "Timeout errors stop", false),
+            ],
+            ErrorCategory::AuthFailure => vec![
+                step(1, "Find the client addresses and principals failing authentication", Some("grep -iE 'authentication|authorized' /var/log/kafka/server.log | tail -50"), "Failing clients identified", true),
+                step(2, "Check credential and certificate expiry and the SASL mechanisms of the listener", Some("kafka-configs.sh --bootstrap-server localhost:9092 --describe --entity-type users"), "Credentials valid and mechanisms match", false),
+                step(3, "Block unknown sources if the failures are not from known clients", None, "Authentication errors stop", false),
+            ],
+            ErrorCategory::CorruptSegment => vec![
+                step(1, "Identify the corrupt segments and check the disk for errors", Some("kafka-dump-log.sh --files <segment>.log --verify-index-only"), "Corrupt files identified", true),
+                step(2, "Stop the broker and delete the corrupt index files so they are rebuilt on start", None, "Broker starts and rebuilds indexes", false),
+                step(3, "If log segments are corrupt, remove the partition directory and let it re-replicate from the leader", None, "Partition back in sync", false),
+            ],
+        }
+    }
+}
+
+impl Rule for LogErrorCategoryRule {
+    fn evaluate(&self, snapshot: &Snapshot) -> AnalyzerResult<Option<Finding>> {
+        let Some(logs) = logs_data(snapshot)? else {
+            return Ok(None);
+        };
+
+        let (id, threshold, severity, category) = self.profile();
+        let count = logs.summary.error_categories.get(&self.category).copied().unwrap_or(0);
+        if count < threshold {
+            return Ok(None);
+        }
+
+        let samples: Vec<LogEvidence> = logs.logs.iter()
+            .flat_map(|(file, entries)| entries.iter().map(move |entry| (file, entry)))
+            .filter(|(_, entry)| matches!(entry.level, LogLevel::Error | LogLevel::Fatal))
+            .filter(|(_, entry)| classify_error(&entry.raw) == Some(self.category))
+            .take(3)
+            .map(|(file, entry)| LogEvidence {
+                level: format!("{:?}", entry.level).to_uppercase(),
+                message: entry.message.clone(),
+                source_file: file.clone(),
+                line_number: None,
+                timestamp: entry.timestamp.clone(),
+                count: 1,
+            })
+            .collect();
+
+        let label = self.category.label();
+        let (impact, root_cause) = self.impact_and_root_cause();
+        let finding = Finding {
+            id: finding_id(id, self.name(), std::iter::empty::<&str>()),
+            severity,
+            category,
+            title: format!("Broker logs show {} errors: {} found", label, count),
+            description: format!(
+                "Detected {} error lines classified as {} in recent broker logs (threshold: {})",
+                count, label, threshold
+            ),
+            impact: impact.to_string(),
+            evidence: Evidence {
+                metrics: vec![MetricEvidence {
+                    name: self.name().to_string(),
+                    value: count as f64,
+                    threshold: Some(threshold as f64),
+                    unit: Some("errors".to_string()),
+                    source: "logs".to_string(),
+                    timestamp: snapshot.timestamp,
+                }],
+                logs: samples,
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some(root_cause.to_string()),
+            remediation: Remediation {
+                steps: self.remediation_steps(),
+                script: None,
+                risk_level: if severity == Severity::Critical { RiskLevel::High } else { RiskLevel::Medium },
+                requires_downtime: self.category == ErrorCategory::CorruptSegment,
+                estimated_duration_minutes: Some(30),
+                rollback_plan: None,
+            },
+            metadata: std::collections::HashMap::new(),
+        };
+
+        Ok(Some(finding))
+    }
+
+    fn name(&self) -> &'static str {
+        match self.category {
+            ErrorCategory::DiskFull => "log_errors_disk_full",
+            ErrorCategory::LeaderElection => "log_errors_leader_election",
+            ErrorCategory::NetworkTimeout => "log_errors_network_timeout",
+            ErrorCategory::AuthFailure => "log_errors_auth_failure",
+            ErrorCategory::CorruptSegment => "log_errors_corrupt_segment",
+        }
+    }
+}
+
+/// Parse Kafka Connect data if it was collected
+fn connect_data(snapshot: &Snapshot) -> AnalyzerResult<Option<ConnectCollectorOutput>> {
+    match snapshot.collectors.custom.get(CONNECT_DATA_KEY) {
+        Some(data) => serde_json::from_value(data.clone())
+            .map(Some)
+            .map_err(|e| AnalyzerError::InvalidData(format!("Failed to parse connect data: {}", e))),
+        None => Ok(None),
+    }
+}
+
+/// Rule: Check for failed Kafka Connect connectors and tasks
+struct FailedConnectorsRule;
+
+impl Rule for FailedConnectorsRule {
+    fn evaluate(&self, snapshot: &Snapshot) -> AnalyzerResult<Option<Finding>> {
+        let Some(connect) = connect_data(snapshot)? else {
+            return Ok(None);
+        };
+
+        let mut failed = Vec::new();
+        let mut traces = Vec::new();
+
+        for connector in &connect.connectors {
+            if connector.is_failed() {
+                failed.push(connector.name.clone());
+                if let Some(trace) = &connector.connector.trace {
+                    traces.push((connector.name.clone(), trace.clone()));
+                }
+            }
+            for task in connector.failed_tasks() {
+                let task_name = format!("{}/task-{}", connector.name, task.id);
+                if let Some(trace) = &task.trace {
+                    traces.push((task_name.clone(), trace.clone()));
+                }
+                failed.push(task_name);
+            }
+        }
+
+        if failed.is_empty() {
+            return Ok(None);
+        }
+
+        let finding = Finding {
+            id: finding_id("FND-006", self.name(), &failed),
+            severity: Severity::High,
+            category: Category::Client,
+            title: format!("Failed Kafka Connect connectors or tasks: {} affected", failed.len()),
+            description: format!(
+                "The following connectors or tasks are in FAILED state: {}",
+                failed.join(", ")
+            ),
+            impact: "Failed connectors and tasks stop moving data until they are restarted".to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: traces.into_iter().map(|(source, trace)| LogEvidence {
+                    level: "ERROR".to_string(),
+                    message: trace,
+                    source_file: source,
+                    line_number: None,
+                    timestamp: snapshot.timestamp.to_rfc3339(),
+                    count: 1,
+                }).collect(),
+                configs: Vec::new(),
+                raw_data: Some(serde_json::json!({
+                    "connect_url": connect.connect_url,
+                    "failed": failed,
+                })),
+                timeline: Vec::new(),
+            },
+            root_cause: Some("See the task traces for the exception that stopped the connector".to_string()),
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Inspect the failure trace and fix the underlying issue".to_string(),
+                        command: Some(format!("curl -s {}/connectors/<name>/status", connect.connect_url)),
+                        verification: Some("Cause of the failure identified".to_string()),
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Restart the failed connector and its tasks".to_string(),
+                        command: Some(format!(
+                            "curl -s -X POST '{}/connectors/<name>/restart?includeTasks=true&onlyFailed=true'",
+                            connect.connect_url
+                        )),
+                        verification: Some("Connector and tasks report RUNNING".to_string()),
+                        can_automate: true,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(15),
+                rollback_plan: None,
+            },
+            metadata: std::collections::HashMap::new(),
+        };
+
+        Ok(Some(finding))
+    }
+
+    fn name(&self) -> &'static str {
+        "failed_connectors"
+    }
+}
+
+/// Rule: Report paused Kafka Connect connectors
+struct PausedConnectorsRule;
+
+impl Rule for PausedConnectorsRule {
+    fn evaluate(&self, snapshot: &Snapshot) -> AnalyzerResult<Option<Finding>> {
+        let Some(connect) = connect_data(snapshot)? else {
+            return Ok(None);
+        };
+
+        let paused: Vec<String> = connect.connectors.iter()
+            .filter(|c| c.is_paused())
+            .map(|c| c.name.clone())
+            .collect();
+
+        if paused.is_empty() {
+            return Ok(None);
+        }
+
+        let finding = Finding {
+            id: finding_id("FND-007", self.name(), &paused),
+            severity: Severity::Info,
+            category: Category::Client,
+            title: format!("Paused Kafka Connect connectors: {}", paused.len()),
+            description: format!("The following connectors are paused: {}", paused.join(", ")),
+            impact: "Paused connectors do not process data; verify the pause is intentional".to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: Some(serde_json::json!({
+                    "connect_url": connect.connect_url,
+                    "paused": paused,
+                })),
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: vec![RemediationStep {
+                    order: 1,
+                    description: "Resume the connector if the pause is no longer needed".to_string(),
+                    command: Some(format!("curl -s -X PUT {}/connectors/<name>/resume", connect.connect_url)),
+                    verification: Some("Connector reports RUNNING".to_string()),
+                    can_automate: true,
+                }],
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(5),
+                rollback_plan: Some(format!("curl -s -X PUT {}/connectors/<name>/pause", connect.connect_url)),
+            },
+            metadata: std::collections::HashMap::new(),
+        };
+
+        Ok(Some(finding))
+    }
+
+    fn name(&self) -> &'static str {
+        "paused_connectors"
+    }
+}
+
+/// Average round-trip time between brokers above which latency is flagged
+const PEER_RTT_THRESHOLD_MS: f64 = 10.0;
+
+/// Rule: Check network latency and packet loss between brokers
+struct PeerLatencyRule;
+
+impl Rule for PeerLatencyRule {
+    fn evaluate(&self, snapshot: &Snapshot) -> AnalyzerResult<Option<Finding>> {
+        let Some(brokers) = snapshot.collectors.custom.get("brokers").and_then(|b| b.as_object()) else {
+            return Ok(None);
+        };
+
+        let mut slow_pairs = Vec::new();
+        let mut affected_links = Vec::new();
+        let mut max_rtt: f64 = 0.0;
+
+        for (broker_name, broker_data) in brokers {
+            let Some(latency_data) = broker_data.get("network").and_then(|n| n.get("peer_latency.json")) else {
+                continue;
+            };
+            let latencies: Vec<PeerLatency> = serde_json::from_value(latency_data.clone())
+                .map_err(|e| AnalyzerError::InvalidData(format!("Failed to parse peer latency of {}: {}", broker_name, e)))?;
+
+            for latency in latencies {
+                let loss = latency.packet_loss_percent.unwrap_or(0.0);
+                let rtt = latency.rtt_avg_ms.unwrap_or(0.0);
+                max_rtt = max_rtt.max(rtt);
+
+                let problem = if !latency.reachable {
+                    Some(format!("unreachable ({})", latency.error.as_deref().unwrap_or("no response")))
+                } else if loss > 0.0 {
+                    Some(format!("{:.1}% packet loss, avg RTT {:.2} ms", loss, rtt))
+                } else if rtt > PEER_RTT_THRESHOLD_MS {
+                    Some(format!("avg RTT {:.2} ms", rtt))
+                } else {
+                    None
+                };
+
+                if let Some(problem) = problem {
+                    slow_pairs.push(format!("{} -> {}: {}", broker_name, latency.peer_hostname, problem));
+                    affected_links.push(format!("{} -> {}", broker_name, latency.peer_hostname));
+                }
+            }
+        }
+
+        if slow_pairs.is_empty() {
+            return Ok(None);
+        }
+
+        let finding = Finding {
+            id: finding_id("FND-008", self.name(), &affected_links),
+            severity: Severity::Medium,
+            category: Category::Performance,
+            title: format!("High inter-broker latency or packet loss: {} broker pairs affected", slow_pairs.len()),
+            description: format!(
+                "Network between brokers is degraded (threshold: {} ms avg RTT, no packet loss):\n{}",
+                PEER_RTT_THRESHOLD_MS,
+                slow_pairs.join("\n")
+            ),
+            impact: "Slow or lossy links between brokers delay replication and can cause ISR shrinkage".to_string(),
+            evidence: Evidence {
+                metrics: vec![MetricEvidence {
+                    name: "max_peer_rtt_avg".to_string(),
+                    value: max_rtt,
+                    threshold: Some(PEER_RTT_THRESHOLD_MS),
+                    unit: Some("ms".to_string()),
+                    source: "peer_latency".to_string(),
+                    timestamp: snapshot.timestamp,
+                }],
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: Some(serde_json::json!({
+                    "affected_pairs": slow_pairs
+                })),
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Possible causes: network congestion, cross-zone placement, firewall rules or NIC issues".to_string()),
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Verify connectivity and latency between the affected brokers".to_string(),
+                        command: Some("mtr --report <peer-broker>".to_string()),
+                        verification: Some("Latency and loss along the path identified".to_string()),
+                        can_automate: true,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Check ISR shrink/expand rate on the affected brokers".to_string(),
+                        command: None,
+                        verification: Some("ISR remains stable".to_string()),
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(30),
+                rollback_plan: None,
+            },
+            metadata: std::collections::HashMap::new(),
+        };
+
+        Ok(Some(finding))
+    }
+
+    fn name(&self) -> &'static str {
+        "peer_latency"
+    }
+}
+
+/// Minimum cluster size for which low partition counts are reported
+const MIN_BROKERS_FOR_PARTITION_SPREAD: usize = 6;
+
+/// A topic spreading over fewer than 1/N of the brokers is considered under-partitioned
+const PARTITION_SPREAD_RATIO: usize = 4;
+
+/// Default partition count above which a single topic is considered over-partitioned
+pub const DEFAULT_MAX_PARTITIONS_PER_TOPIC: usize = 1000;
+
+/// Environment variable overriding the partition count above which a topic is over-partitioned
+pub const MAX_PARTITIONS_PER_TOPIC_ENV: &str = "MAX_PARTITIONS_PER_TOPIC";
+
+/// Parse admin data for rules that depend on topic metadata
+fn admin_data(snapshot: &Snapshot) -> AnalyzerResult<AdminCollectorOutput> {
+    let admin_data = snapshot.collectors.admin.as_ref()
+        .ok_or_else(|| AnalyzerError::InvalidData("No admin data available".to_string()))?;
+
+    serde_json::from_value(admin_data.clone())
+        .map_err(|e| AnalyzerError::InvalidData(format!("Failed to parse admin data: {}", e)))
+}
+
+/// Rule: Check for topics using only a small part of a large cluster
+struct UnderPartitionedTopicsRule;
+
+impl Rule for UnderPartitionedTopicsRule {
+    fn evaluate(&self, snapshot: &Snapshot) -> AnalyzerResult<Option<Finding>> {
+        let admin = admin_data(snapshot)?;
+        let broker_count = admin.brokers.len();
+
+        if broker_count < MIN_BROKERS_FOR_PARTITION_SPREAD {
+            return Ok(None);
+        }
+
+        // Without throughput metrics, only user topics are considered; internal and
+        // underscore-prefixed topics (e.g. _schemas) are expected to be small
+        let under_partitioned: Vec<(String, usize)> = admin.topics.iter()
+            .filter(|t| !t.is_internal && !t.name.starts_with('_'))
+            .filter(|t| t.partitions.len() * PARTITION_SPREAD_RATIO <= broker_count)
+            .map(|t| (t.name.clone(), t.partitions.len()))
+            .collect();
+
+        if under_partitioned.is_empty() {
+            return Ok(None);
+        }
+
+        let finding = Finding {
+            id: finding_id("FND-009", self.name(), under_partitioned.iter().map(|(topic, _)| topic)),
+            severity: Severity::Info,
+            category: Category::Performance,
+            title: format!("Topics with few partitions for cluster size: {} topics", under_partitioned.len()),
+            description: format!(
+                "{} topics have at most {} partitions on a {}-broker cluster, so their load is served by a \
+                 small subset of brokers. This matters for high-throughput topics: {}",
+                under_partitioned.len(),
+                broker_count / PARTITION_SPREAD_RATIO,
+                broker_count,
+                under_partitioned.iter()
+                    .map(|(name, partitions)| format!("{} ({})", name, partitions))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            impact: "Busy topics with few partitions cannot spread load across brokers and limit consumer parallelism".to_string(),
+            evidence: Evidence {
+                metrics: vec![MetricEvidence {
+                    name: "broker_count".to_string(),
+                    value: broker_count as f64,
+                    threshold: None,
+                    unit: Some("brokers".to_string()),
+                    source: "admin".to_string(),
+                    timestamp: snapshot.timestamp,
+                }],
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: Some(serde_json::json!({
+                    "topics": under_partitioned
+                })),
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Review the throughput of the listed topics and increase partitions for busy ones".to_string(),
+                        command: Some("kafka-topics.sh --bootstrap-server localhost:9092 --alter --topic <topic> --partitions <count>".to_string()),
+                        verification: Some("Topic partitions spread across brokers".to_string()),
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(15),
+                rollback_plan: Some("Partition count cannot be decreased; plan key-based ordering changes before increasing".to_string()),
+            },
+            metadata: std::collections::HashMap::new(),
+        };
+
+        Ok(Some(finding))
+    }
+
+    fn name(&self) -> &'static str {
+        "under_partitioned_topics"
+    }
+}
+
+/// Rule: Check for topics with an excessive number of partitions
+struct OverPartitionedTopicsRule {
+    max_partitions: usize,
+}
+
+impl Rule for OverPartitionedTopicsRule {
+    fn evaluate(&self, snapshot: &Snapshot) -> AnalyzerResult<Option<Finding>> {
+        let admin = admin_data(snapshot)?;
+
+        let total_partitions: usize = admin.topics.iter().map(|t| t.partitions.len()).sum();
+        let over_partitioned: Vec<(String, usize)> = admin.topics.iter()
+            .filter(|t| t.partitions.len() > self.max_partitions)
+            .map(|t| (t.name.clone(), t.partitions.len()))
+            .collect();
+
+        if over_partitioned.is_empty() {
+            return Ok(None);
+        }
+
+        let finding = Finding {
+            id: finding_id("FND-010", self.name(), over_partitioned.iter().map(|(topic, _)| topic)),
+            severity: Severity::Medium,
+            category: Category::Performance,
+            title: format!("Topics with excessive partition count: {} topics", over_partitioned.len()),
+            description: format!(
+                "{} topics exceed {} partitions: {}. The cluster has {} partitions in total across {} brokers.",
+                over_partitioned.len(),
+                self.max_partitions,
+                over_partitioned.iter()
+                    .map(|(name, partitions)| format!("{} ({})", name, partitions))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                total_partitions,
+                admin.brokers.len()
+            ),
+            impact: "Each partition adds file handles, memory and controller work; very large topics slow down leader elections and recovery".to_string(),
+            evidence: Evidence {
+                metrics: vec![MetricEvidence {
+                    name: "total_partitions".to_string(),
+                    value: total_partitions as f64,
+                    threshold: None,
+                    unit: Some("partitions".to_string()),
+                    source: "admin".to_string(),
+                    timestamp: snapshot.timestamp,
+                }],
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: Some(serde_json::json!({
+                    "topics": over_partitioned,
+                    "max_partitions_per_topic": self.max_partitions,
+                    "total_partitions": total_partitions,
+                })),
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Partition counts sized for peak parallelism that is not needed".to_string()),
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Verify whether consumers actually need this level of parallelism".to_string(),
+                        command: None,
+                        verification: Some("Required partition count determined".to_string()),
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Migrate to a new topic with fewer partitions".to_string(),
+                        command: None,
+                        verification: Some("Producers and consumers switched to the new topic".to_string()),
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::High,
+                requires_downtime: false,
+                estimated_duration_minutes: None,
+                rollback_plan: Some("Switch clients back to the original topic".to_string()),
+            },
+            metadata: std::collections::HashMap::new(),
+        };
+
+        Ok(Some(finding))
+    }
+
+    fn name(&self) -> &'static str {
+        "over_partitioned_topics"
+    }
+}
+
+/// Internal topics checked by `InternalTopicsRule`, with the broker config setting their replication factor
+const INTERNAL_TOPICS: [(&str, &str); 2] = [
+    ("__consumer_offsets", "offsets.topic.replication.factor"),
+    ("__transaction_state", "transaction.state.log.replication.factor"),
+];
+
+/// Kafka's default for both internal topic replication factor settings
+const DEFAULT_INTERNAL_TOPIC_REPLICATION_FACTOR: usize = 3;
+
+/// Rule: Check replication and partition health of `__consumer_offsets` and `__transaction_state`
+struct InternalTopicsRule;
+
+impl InternalTopicsRule {
+    /// Replication factor the brokers are configured to use for an internal topic, with the file it
+    /// was read from. The highest configured value wins; without server.properties Kafka's default applies.
+    fn expected_replication_factor(snapshot: &Snapshot, config_key: &str) -> (usize, Option<String>) {
+        server_properties(snapshot).into_iter()
+            .filter_map(|(file, props)| Some((props.get(config_key)?.trim().parse::<usize>().ok()?, file)))
+            .max_by_key(|(factor, _)| *factor)
+            .map(|(factor, file)| (factor, Some(file)))
+            .unwrap_or((DEFAULT_INTERNAL_TOPIC_REPLICATION_FACTOR, None))
+    }
+}
+
+impl Rule for InternalTopicsRule {
+    fn evaluate(&self, snapshot: &Snapshot) -> AnalyzerResult<Option<Finding>> {
+        let admin = admin_data(snapshot)?;
+
+        let mut problems = Vec::new();
+        let mut configs = Vec::new();
+        let mut affected_partitions = Vec::new();
+        for (name, config_key) in INTERNAL_TOPICS {
+            let Some(topic) = admin.topics.iter().find(|t| t.name == name) else {
+                continue;
+            };
+
+            let (expected, source) = Self::expected_replication_factor(snapshot, config_key);
+            let actual = topic.partitions.iter().map(|p| p.replicas.len()).min()
+                .unwrap_or(topic.replication_factor.max(0) as usize);
+            if actual < expected {
+                problems.push(format!("{} has replication factor {} but {} is {}", name, actual, config_key, expected));
+                configs.push(ConfigEvidence {
+                    resource_type: "topic".to_string(),
+                    resource_name: name.to_string(),
+                    config_key: config_key.to_string(),
+                    current_value: actual.to_string(),
+                    recommended_value: Some(expected.to_string()),
+                    reason: format!("{} should be replicated as configured by {}", name, config_key),
+                    source_files: source.into_iter().collect(),
+                });
+            }
+
+            let offline: Vec<i32> = topic.partitions.iter().filter(|p| p.leader.is_none()).map(|p| p.id).collect();
+            let under_replicated: Vec<i32> = topic.partitions.iter()
+                .filter(|p| p.leader.is_some() && p.isr.len() < p.replicas.len())
+                .map(|p| p.id)
+                .collect();
+            if !offline.is_empty() {
+                problems.push(format!("{} has {} offline partitions", name, offline.len()));
+            }
+            if !under_replicated.is_empty() {
+                problems.push(format!("{} has {} under-replicated partitions", name, under_replicated.len()));
+            }
+            affected_partitions.extend(offline.iter().chain(&under_replicated).map(|id| (name, *id)));
+        }
+
+        if problems.is_empty() {
+            return Ok(None);
+        }
+
+        // Under-replicated internal topics and their unavailable partitions
+        let resources = configs.iter().map(|c| c.resource_name.clone())
+            .chain(affected_partitions.iter().map(|(name, id)| format!("{}-{}", name, id)));
+        let finding = Finding {
+            id: finding_id("FND-011", self.name(), resources),
+            severity: Severity::Critical,
+            category: Category::Availability,
+            title: format!("Internal topic problems detected: {} issues", problems.len()),
+            description: format!(
+                "The internal consumer offset and transaction state topics are unhealthy: {}.",
+                problems.join("; ")
+            ),
+            impact: "Consumer groups cannot commit or fetch offsets and transactional producers fail when these topics \
+                lose partitions, which affects every application using the cluster".to_string(),
+            evidence: Evidence {
+                metrics: vec![MetricEvidence {
+                    name: "internal_topic_unhealthy_partitions".to_string(),
+                    value: affected_partitions.len() as f64,
+                    threshold: Some(0.0),
+                    unit: Some("partitions".to_string()),
+                    source: "admin".to_string(),
+                    timestamp: snapshot.timestamp,
+                }],
+                logs: Vec::new(),
+                configs,
+                raw_data: Some(serde_json::json!({
+                    "affected_partitions": affected_partitions
+                })),
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Internal topics created while the cluster had fewer brokers, or brokers hosting their replicas are down".to_string()),
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Bring back brokers hosting offline or out-of-sync internal topic replicas".to_string(),
+                        command: Some("kafka-topics.sh --bootstrap-server localhost:9092 --describe --topic __consumer_offsets --under-replicated-partitions".to_string()),
+                        verification: Some("No under-replicated or offline internal topic partitions".to_string()),
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Raise the replication factor of the internal topic with a partition reassignment".to_string(),
+                        command: Some("kafka-reassign-partitions.sh --bootstrap-server localhost:9092 --reassignment-json-file increase-rf.json --execute".to_string()),
+                        verification: Some("kafka-reassign-partitions.sh --bootstrap-server localhost:9092 --reassignment-json-file increase-rf.json --verify".to_string()),
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::High,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(30),
+                rollback_plan: Some("Reassign the partitions back to the original replica lists".to_string()),
+            },
+            metadata: std::collections::HashMap::new(),
+        };
+
+        Ok(Some(finding))
+    }
+
+    fn name(&self) -> &'static str {
+        "internal_topics"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+
+    fn snapshot_with_connect(connect: serde_json::Value) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.custom.insert(CONNECT_DATA_KEY.to_string(), connect);
+        snapshot
+    }
+
+    fn sample_connect() -> serde_json::Value {
+        serde_json::json!({
+            "connect_url": "http://connect:8083",
+            "connectors": [
+                {
+                    "name": "jdbc-sink",
+                    "connector": {"state": "RUNNING", "worker_id": "w1:8083"},
+                    "tasks": [
+                        {"id": 0, "state": "FAILED", "worker_id": "w1:8083",
+                         "trace": "org.apache.kafka.connect.errors.ConnectException: connection refused"}
+                    ],
+                    "type": "sink"
+                },
+                {
+                    "name": "s3-sink",
+                    "connector": {"state": "PAUSED", "worker_id": "w2:8083"},
+                    "tasks": [{"id": 0, "state": "PAUSED", "worker_id": "w2:8083"}],
+                    "type": "sink"
+                },
+                {
+                    "name": "debezium-source",
+                    "connector": {"state": "RUNNING", "worker_id": "w2:8083"},
+                    "tasks": [{"id": 0, "state": "RUNNING", "worker_id": "w2:8083"}],
+                    "type": "source"
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_failed_connectors_rule() {
+        let snapshot = snapshot_with_connect(sample_connect());
+
+        let finding = FailedConnectorsRule.evaluate(&snapshot).unwrap().unwrap();
+
+        assert_eq!(finding.severity, Severity::High);
+        assert!(finding.description.contains("jdbc-sink/task-0"));
+        assert_eq!(finding.evidence.logs.len(), 1);
+        assert_eq!(finding.evidence.logs[0].source_file, "jdbc-sink/task-0");
+        assert!(finding.evidence.logs[0].message.contains("ConnectException"));
+    }
+
+    #[test]
+    fn test_paused_connectors_rule() {
+        let snapshot = snapshot_with_connect(sample_connect());
+
+        let finding = PausedConnectorsRule.evaluate(&snapshot).unwrap().unwrap();
+
+        assert_eq!(finding.severity, Severity::Info);
+        assert!(finding.description.contains("s3-sink"));
+        assert!(!finding.description.contains("jdbc-sink"));
+    }
+
+    #[test]
+    fn test_peer_latency_rule() {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.custom.insert("brokers".to_string(), serde_json::json!({
+            "broker_11": {
+                "network": {
+                    "peer_latency.json": [
+                        {"peer_id": 12, "peer_hostname": "kafka-2", "reachable": true,
+                         "packet_loss_percent": 0.0, "rtt_min_ms": 0.2, "rtt_avg_ms": 0.3, "rtt_max_ms": 0.5, "error": null},
+                        {"peer_id": 13, "peer_hostname": "kafka-3", "reachable": true,
+                         "packet_loss_percent": 0.0, "rtt_min_ms": 20.0, "rtt_avg_ms": 35.0, "rtt_max_ms": 50.0, "error": null}
+                    ]
+                }
+            },
+            "broker_12": {
+                "network": {
+                    "peer_latency.json": [
+                        {"peer_id": 14, "peer_hostname": "kafka-4", "reachable": false,
+                         "packet_loss_percent": 100.0, "rtt_min_ms": null, "rtt_avg_ms": null, "rtt_max_ms": null,
+                         "error": "100% packet loss"}
+                    ]
+                }
+            },
+            "broker_13": {"system": {}}
+        }));
+
+        let finding = PeerLatencyRule.evaluate(&snapshot).unwrap().unwrap();
+
+        assert_eq!(finding.severity, Severity::Medium);
+        assert!(matches!(finding.category, Category::Performance));
+        assert!(finding.description.contains("broker_11 -> kafka-3: avg RTT 35.00 ms"));
+        assert!(finding.description.contains("broker_12 -> kafka-4: unreachable"));
+        assert!(!finding.description.contains("kafka-2"));
+        assert_eq!(finding.evidence.metrics[0].value, 35.0);
+    }
+
+    fn snapshot_with_topics(broker_count: usize, topics: &[(&str, usize)]) -> Snapshot {
+        let brokers: Vec<_> = (0..broker_count)
+            .map(|id| serde_json::json!({"id": id, "host": format!("kafka-{}", id), "port": 9092, "rack": null}))
+            .collect();
+        let topics: Vec<_> = topics.iter()
+            .map(|(name, partitions)| serde_json::json!({
+                "name": name,
+                "partitions": (0..*partitions).map(|id| serde_json::json!({
+                    "id": id, "leader": 0, "replicas": [0], "isr": [0], "offline_replicas": []
+                })).collect::<Vec<_>>(),
+                "replication_factor": 1,
+                "is_internal": name.starts_with("__"),
+            }))
+            .collect();
+
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.admin = Some(serde_json::json!({
+            "cluster": {
+                "cluster_id": null, "controller_id": null,
+                "broker_count": broker_count, "topic_count": topics.len(), "partition_count": 0
+            },
+            "brokers": brokers,
+            "topics": topics,
+        }));
+        snapshot
+    }
+
+    #[test]
+    fn test_under_partitioned_topics() {
+        let snapshot = snapshot_with_topics(12, &[("orders", 1), ("payments", 24), ("__consumer_offsets", 1)]);
+
+        let finding = UnderPartitionedTopicsRule.evaluate(&snapshot).unwrap().unwrap();
+
+        assert_eq!(finding.severity, Severity::Info);
+        assert!(finding.description.contains("orders (1)"));
+        assert!(!finding.description.contains("payments"));
+        assert!(!finding.description.contains("__consumer_offsets"));
+    }
+
+    #[test]
+    fn test_over_partitioned_topics() {
+        let snapshot = snapshot_with_topics(3, &[("clicks", 5000), ("orders", 12)]);
+
+        let finding = OverPartitionedTopicsRule { max_partitions: DEFAULT_MAX_PARTITIONS_PER_TOPIC }
+            .evaluate(&snapshot).unwrap().unwrap();
+
+        assert_eq!(finding.severity, Severity::Medium);
+        assert!(finding.description.contains("clicks (5000)"));
+        assert!(finding.description.contains("5012 partitions in total across 3 brokers"));
+        assert!(!finding.description.contains("orders"));
+    }
+
+    #[test]
+    fn test_over_partitioned_topics_custom_threshold() {
+        let snapshot = snapshot_with_topics(3, &[("orders", 12)]);
+
+        assert!(OverPartitionedTopicsRule { max_partitions: 10 }.evaluate(&snapshot).unwrap().is_some());
+        assert!(OverPartitionedTopicsRule { max_partitions: 12 }.evaluate(&snapshot).unwrap().is_none());
+
+        let analyzer = RuleAnalyzer::new().with_max_partitions_per_topic(10);
+        assert_eq!(analyzer.enabled_rules.iter().filter(|r| r.name() == "over_partitioned_topics").count(), 1);
+    }
+
+    #[test]
+    fn test_partition_count_rules_normal_cluster() {
+        let snapshot = snapshot_with_topics(12, &[("orders", 12), ("payments", 24), ("audit", 6)]);
+
+        assert!(UnderPartitionedTopicsRule.evaluate(&snapshot).unwrap().is_none());
+        assert!(OverPartitionedTopicsRule { max_partitions: DEFAULT_MAX_PARTITIONS_PER_TOPIC }
+            .evaluate(&snapshot).unwrap().is_none());
+    }
+
+    fn snapshot_with_internal_topics(consumer_offsets: serde_json::Value, offsets_rf: Option<&str>) -> Snapshot {
+        let mut snapshot = snapshot_with_topics(3, &[("orders", 3)]);
+        let admin = snapshot.collectors.admin.as_mut().unwrap();
+        admin["topics"].as_array_mut().unwrap().push(serde_json::json!({
+            "name": "__consumer_offsets",
+            "partitions": consumer_offsets,
+            "replication_factor": 3,
+            "is_internal": true,
+        }));
+        if let Some(rf) = offsets_rf {
+            snapshot.collectors.config = Some(serde_json::json!({
+                "broker_0/server.properties": format!("broker.id=0\noffsets.topic.replication.factor={}\n", rf)
+            }));
+        }
+        snapshot
+    }
+
+    #[test]
+    fn test_internal_topics_healthy() {
+        let partitions = serde_json::json!([
+            {"id": 0, "leader": 0, "replicas": [0, 1, 2], "isr": [0, 1, 2], "offline_replicas": []},
+            {"id": 1, "leader": 1, "replicas": [1, 2, 0], "isr": [1, 2, 0], "offline_replicas": []}
+        ]);
+        let snapshot = snapshot_with_internal_topics(partitions, Some("3"));
+
+        assert!(InternalTopicsRule.evaluate(&snapshot).unwrap().is_none());
+        // Clusters without the internal topics, e.g. before the first consumer group, are fine
+        assert!(InternalTopicsRule.evaluate(&snapshot_with_topics(3, &[("orders", 3)])).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_internal_topics_degraded() {
+        let partitions = serde_json::json!([
+            {"id": 0, "leader": 0, "replicas": [0], "isr": [0], "offline_replicas": []},
+            {"id": 1, "leader": null, "replicas": [1], "isr": [], "offline_replicas": [1]},
+            {"id": 2, "leader": 2, "replicas": [2, 0], "isr": [2], "offline_replicas": []}
+        ]);
+        let snapshot = snapshot_with_internal_topics(partitions, Some("3"));
+
+        let finding = InternalTopicsRule.evaluate(&snapshot).unwrap().unwrap();
+
+        assert_eq!(finding.severity, Severity::Critical);
+        assert!(matches!(finding.category, Category::Availability));
+        assert!(finding.description.contains("__consumer_offsets has replication factor 1 but offsets.topic.replication.factor is 3"));
+        assert!(finding.description.contains("__consumer_offsets has 1 offline partitions"));
+        assert!(finding.description.contains("__consumer_offsets has 1 under-replicated partitions"));
+        assert_eq!(finding.evidence.configs[0].source_files, vec!["broker_0/server.properties"]);
+        assert_eq!(finding.evidence.metrics[0].value, 2.0);
+    }
+
+    #[test]
+    fn test_internal_topics_replication_factor_from_config() {
+        let partitions = serde_json::json!([
+            {"id": 0, "leader": 0, "replicas": [0], "isr": [0], "offline_replicas": []}
+        ]);
+
+        // A single-replica offsets topic is what the brokers were told to create
+        assert!(InternalTopicsRule.evaluate(&snapshot_with_internal_topics(partitions.clone(), Some("1"))).unwrap().is_none());
+
+        // Without server.properties Kafka's default of 3 applies
+        let finding = InternalTopicsRule.evaluate(&snapshot_with_internal_topics(partitions, None)).unwrap().unwrap();
+        assert_eq!(finding.evidence.configs[0].recommended_value.as_deref(), Some("3"));
+        assert!(finding.evidence.configs[0].source_files.is_empty());
+    }
+
+    fn snapshot_with_logs(lines: &[&str], error_count: usize) -> Snapshot {
+        let entries: Vec<serde_json::Value> = lines.iter()
+            .map(|line| serde_json::json!({
+                "timestamp": "2024-01-15T10:00:00",
+                "level": "Error",
+                "source": "kafka.log.LogManager",
+                "message": line,
+                "raw": line,
+            }))
+            .collect();
+        let mut categories = std::collections::BTreeMap::new();
+        for line in lines {
+            if let Some(category) = classify_error(line) {
+                *categories.entry(category).or_insert(0usize) += 1;
+            }
+        }
+
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.logs = Some(serde_json::json!({
+            "logs": {"/var/log/kafka/server.log": entries},
+            "summary": {
+                "total_entries": error_count,
+                "error_count": error_count,
+                "warn_count": 0,
+                "files_processed": 1,
+                "patterns_matched": {},
+                "top_errors": [],
+                "error_categories": categories,
+            },
+            "collection_timestamp": "2024-01-15T10:00:00Z",
+        }));
+        snapshot
+    }
+
+    #[test]
+    fn test_log_error_category_rules() {
+        let mut lines = vec!["ERROR Error while appending records to orders-0: java.io.IOException: No space left on device"];
+        lines.extend(["ERROR Error for partition orders-3: NotLeaderOrFollowerException"; 4]);
+        let snapshot = snapshot_with_logs(&lines, 20);
+
+        let finding = LogErrorCategoryRule { category: ErrorCategory::DiskFull }.evaluate(&snapshot).unwrap().unwrap();
+        assert!(finding.id.starts_with("FND-012-"));
+        assert_eq!(finding.severity, Severity::Critical);
+        assert!(matches!(finding.category, Category::Capacity));
+        assert_eq!(finding.evidence.metrics[0].name, "log_errors_disk_full");
+        assert_eq!(finding.evidence.logs.len(), 1);
+        assert_eq!(finding.evidence.logs[0].source_file, "/var/log/kafka/server.log");
+        assert!(finding.remediation.steps[0].command.as_deref().unwrap().contains("kafka-log-dirs.sh"));
+
+        // A few leadership errors are expected during rolling restarts
+        assert!(LogErrorCategoryRule { category: ErrorCategory::LeaderElection }.evaluate(&snapshot).unwrap().is_none());
+        assert!(LogErrorCategoryRule { category: ErrorCategory::AuthFailure }.evaluate(&snapshot).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_high_error_rate_skips_categorized_errors() {
+        let lines = ["ERROR Request timed out: TimeoutException"; 12];
+
+        // All errors have a category of their own
+        let snapshot = snapshot_with_logs(&lines, 12);
+        assert!(HighErrorRateRule.evaluate(&snapshot).unwrap().is_none());
+        let finding = LogErrorCategoryRule { category: ErrorCategory::NetworkTimeout }.evaluate(&snapshot).unwrap().unwrap();
+        assert_eq!(finding.evidence.metrics[0].value, 12.0);
+
+        // Only the uncategorized rest counts towards the generic rate
+        let finding = HighErrorRateRule.evaluate(&snapshot_with_logs(&lines, 25)).unwrap().unwrap();
+        assert_eq!(finding.evidence.metrics[0].value, 13.0);
+    }
+
+    #[tokio::test]
+    async fn test_rule_finding_ids_are_stable_across_runs() {
+        let mut snapshot = snapshot_with_topics(12, &[("orders", 1), ("clicks", 5000)]);
+        snapshot.collectors.custom.insert(CONNECT_DATA_KEY.to_string(), sample_connect());
+
+        let first: Vec<String> = RuleAnalyzer::new().analyze(&snapshot).await.unwrap()
+            .into_iter().map(|f| f.id).collect();
+        let second: Vec<String> = RuleAnalyzer::new().analyze(&snapshot).await.unwrap()
+            .into_iter().map(|f| f.id).collect();
+        assert!(first.len() >= 4, "{:?}", first);
+        assert_eq!(first, second);
+        assert!(first.iter().all(|id| crate::report::stable_finding_id(id).len() + 37 == id.len()));
+
+        // A different set of affected resources is a different finding
+        let other = UnderPartitionedTopicsRule.evaluate(&snapshot_with_topics(12, &[("payments", 1)])).unwrap().unwrap();
+        let orders = UnderPartitionedTopicsRule.evaluate(&snapshot).unwrap().unwrap();
+        assert_ne!(other.id, orders.id);
+        assert_eq!(crate::report::stable_finding_id(&other.id), "FND-009");
+    }
+
+    #[test]
+    fn test_connect_rules_without_connect_data() {
+        let snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+
+        assert!(FailedConnectorsRule.evaluate(&snapshot).unwrap().is_none());
+        assert!(PausedConnectorsRule.evaluate(&snapshot).unwrap().is_none());
+    }
+}
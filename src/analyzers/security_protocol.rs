@@ -0,0 +1,326 @@
+use crate::analyzers::{server_properties, Analyzer, AnalyzerResult};
+use crate::scan::listeners::{format_address, parse_listeners, parse_security_protocol_map};
+use crate::scan::types::ListenerInfo;
+use crate::snapshot::format::{
+    Category, ConfigEvidence, Evidence, Finding, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::{BTreeSet, HashMap};
+
+/// Listener Kafka binds when `listeners` is not set
+const DEFAULT_LISTENERS: &str = "PLAINTEXT://:9092";
+
+pub(crate) const SECURITY_PROTOCOLS: [&str; 4] = ["PLAINTEXT", "SSL", "SASL_PLAINTEXT", "SASL_SSL"];
+
+/// Security protocol of each listener name, from `listener.security.protocol.map`.
+/// Listener names that are themselves protocols map to that protocol, as in Kafka's default map.
+pub(crate) fn protocol_map(props: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut map: HashMap<String, String> = SECURITY_PROTOCOLS.iter()
+        .map(|protocol| (protocol.to_string(), protocol.to_string()))
+        .collect();
+    if let Some(value) = props.get("listener.security.protocol.map") {
+        map.extend(parse_security_protocol_map(value).into_iter().flatten());
+    }
+    map
+}
+
+/// Whether a listener only accepts connections from the broker host itself
+fn is_loopback(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host.starts_with("127.")
+        || host == "::1"
+}
+
+/// A bound listener with its resolved security protocol
+struct SecuredListener {
+    listener: ListenerInfo,
+    protocol: Option<String>,
+}
+
+impl SecuredListener {
+    fn requires_authentication(&self, props: &HashMap<String, String>) -> bool {
+        match self.protocol.as_deref() {
+            // An explicitly empty mechanism list leaves SASL listeners without a way to log in
+            Some("SASL_SSL") | Some("SASL_PLAINTEXT") => props.get("sasl.enabled.mechanisms")
+                .map(|mechanisms| !mechanisms.trim().is_empty())
+                .unwrap_or(true),
+            Some("SSL") => props.get("ssl.client.auth").map(|auth| auth.trim() == "required").unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    fn describe(&self) -> String {
+        let address = if self.listener.host.is_empty() {
+            format!(":{}", self.listener.port)
+        } else {
+            format_address(&self.listener.host, self.listener.port)
+        };
+        format!("{}://{}", self.listener.name, address)
+    }
+}
+
+/// Listeners a broker binds on interfaces other than loopback
+fn exposed_listeners(props: &HashMap<String, String>) -> Vec<SecuredListener> {
+    let protocols = protocol_map(props);
+    let listeners = props.get("listeners").map(String::as_str).unwrap_or(DEFAULT_LISTENERS);
+
+    parse_listeners(listeners).into_iter()
+        .filter(|listener| !is_loopback(&listener.host))
+        .map(|listener| SecuredListener {
+            protocol: protocols.get(&listener.name.to_uppercase()).cloned(),
+            listener,
+        })
+        .collect()
+}
+
+/// Protocol used between brokers: that of `inter.broker.listener.name`, else
+/// `security.inter.broker.protocol`, which defaults to PLAINTEXT
+fn inter_broker_protocol(props: &HashMap<String, String>) -> String {
+    props.get("inter.broker.listener.name")
+        .and_then(|name| protocol_map(props).get(&name.trim().to_uppercase()).cloned())
+        .or_else(|| props.get("security.inter.broker.protocol").map(|p| p.trim().to_uppercase()))
+        .unwrap_or_else(|| "PLAINTEXT".to_string())
+}
+
+/// Flags PLAINTEXT listeners reachable from the network and brokers without client authentication
+pub struct SecurityProtocolAnalyzer;
+
+impl Default for SecurityProtocolAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecurityProtocolAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn finding(title: String, description: String, impact: &str, configs: Vec<ConfigEvidence>) -> Finding {
+        let steps = [
+            (
+                "Issue a keystore for every broker and a truststore for brokers and clients, and set ssl.keystore.location, ssl.keystore.password and ssl.truststore.location in server.properties",
+                None,
+            ),
+            (
+                "Enable a SASL mechanism (sasl.enabled.mechanisms=SCRAM-SHA-512) and create client credentials",
+                Some("kafka-configs.sh --bootstrap-server <broker> --alter --add-config 'SCRAM-SHA-512=[password=<secret>]' --entity-type users --entity-name <user>"),
+            ),
+            (
+                "Add a SASL_SSL listener next to the existing one, map it in listener.security.protocol.map and roll the brokers one at a time",
+                None,
+            ),
+            (
+                "Move clients and inter-broker traffic (inter.broker.listener.name) to the SASL_SSL listener, then remove the PLAINTEXT listener or bind it to localhost",
+                None,
+            ),
+        ];
+
+        Finding {
+            id: String::new(),
+            title,
+            description,
+            severity: Severity::High,
+            category: Category::Security,
+            impact: impact.to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs,
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: steps.iter().enumerate().map(|(i, (description, command))| RemediationStep {
+                    order: i as u32 + 1,
+                    description: description.to_string(),
+                    command: command.map(str::to_string),
+                    verification: Some("openssl s_client -connect <broker>:<port> </dev/null".to_string()),
+                    can_automate: false,
+                }).collect(),
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(240),
+                rollback_plan: Some("Keep the old listener until every client has moved, and point clients back to it if they fail to connect".to_string()),
+            },
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn evidence(file: &str, key: &str, value: String, recommended: &str, reason: &str) -> ConfigEvidence {
+        ConfigEvidence {
+            resource_type: "broker".to_string(),
+            resource_name: file.split('/').next().unwrap_or(file).to_string(),
+            config_key: key.to_string(),
+            current_value: value,
+            recommended_value: Some(recommended.to_string()),
+            reason: reason.to_string(),
+            source_files: vec![file.to_string()],
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for SecurityProtocolAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let configs = server_properties(snapshot);
+        let mut plaintext = Vec::new();
+        let mut unauthenticated = Vec::new();
+
+        for (file, props) in &configs {
+            let exposed = exposed_listeners(props);
+            let open: Vec<&SecuredListener> = exposed.iter()
+                .filter(|l| l.protocol.as_deref() == Some("PLAINTEXT"))
+                .collect();
+
+            for listener in &open {
+                plaintext.push(Self::evidence(
+                    file,
+                    "listeners",
+                    listener.describe(),
+                    "SASL_SSL, or bind PLAINTEXT to localhost",
+                    "PLAINTEXT listener accepts unencrypted, unauthenticated connections from the network",
+                ));
+            }
+            let inter_broker = inter_broker_protocol(props);
+            if !open.is_empty() && inter_broker == "PLAINTEXT" {
+                plaintext.push(Self::evidence(
+                    file,
+                    "security.inter.broker.protocol",
+                    inter_broker,
+                    "SASL_SSL",
+                    "Replication traffic between brokers is sent in clear text",
+                ));
+            }
+
+            if !exposed.is_empty() && !exposed.iter().any(|l| l.requires_authentication(props)) {
+                unauthenticated.push(Self::evidence(
+                    file,
+                    "sasl.enabled.mechanisms",
+                    props.get("sasl.enabled.mechanisms").cloned().unwrap_or_else(|| "(not set)".to_string()),
+                    "SCRAM-SHA-512 on a SASL_SSL listener",
+                    "No network listener uses SASL or requires TLS client certificates",
+                ));
+            }
+        }
+
+        let mut findings = Vec::new();
+
+        if !plaintext.is_empty() {
+            let brokers = plaintext.iter().map(|c| &c.resource_name).collect::<BTreeSet<_>>().len();
+            findings.push(Self::finding(
+                format!("PLAINTEXT listeners exposed on {} broker(s)", brokers),
+                format!(
+                    "{} of {} broker(s) bind a PLAINTEXT listener on a non-loopback interface. Anyone who can \
+                    reach these ports can read and write data without credentials, and traffic, including \
+                    passwords sent by clients, is not encrypted.",
+                    brokers, configs.len()
+                ),
+                "HIGH: Data in transit can be read or altered on the network, and any host that reaches the broker can produce and consume.",
+                plaintext,
+            ));
+        }
+
+        if !unauthenticated.is_empty() {
+            findings.push(Self::finding(
+                format!("Client authentication disabled on {} broker(s)", unauthenticated.len()),
+                format!(
+                    "{} of {} broker(s) accept network connections without authenticating the client: no listener \
+                    uses SASL and none requires TLS client certificates (ssl.client.auth=required). ACLs cannot \
+                    tell clients apart, so every connection acts as the same anonymous user.",
+                    unauthenticated.len(), configs.len()
+                ),
+                "HIGH: Any client that reaches the cluster can read, write or delete topics without credentials.",
+                unauthenticated,
+            ));
+        }
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("SEC-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Security Protocol Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags PLAINTEXT listeners reachable from the network and brokers without client authentication"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    fn snapshot(config: &str) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.config = Some(json!({
+            "broker_1/server.properties": format!("broker.id=1\n{}", config),
+        }));
+        snapshot
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_cluster_flagged() {
+        let findings = SecurityProtocolAnalyzer::new()
+            .analyze(&snapshot("listeners=PLAINTEXT://0.0.0.0:9092\n"))
+            .await
+            .unwrap();
+
+        assert_eq!(findings.len(), 2);
+        assert!(findings.iter().all(|f| f.severity == Severity::High && matches!(f.category, Category::Security)));
+        assert_eq!(findings[0].title, "PLAINTEXT listeners exposed on 1 broker(s)");
+        let keys: Vec<&str> = findings[0].evidence.configs.iter().map(|c| c.config_key.as_str()).collect();
+        assert_eq!(keys, vec!["listeners", "security.inter.broker.protocol"]);
+        assert_eq!(findings[1].title, "Client authentication disabled on 1 broker(s)");
+        assert!(findings[1].remediation.steps.iter().any(|s| s.description.contains("SASL_SSL")));
+    }
+
+    #[tokio::test]
+    async fn test_default_listener_is_plaintext() {
+        let findings = SecurityProtocolAnalyzer::new().analyze(&snapshot("")).await.unwrap();
+        assert_eq!(findings[0].evidence.configs[0].current_value, "PLAINTEXT://:9092");
+    }
+
+    #[tokio::test]
+    async fn test_sasl_ssl_cluster_passes() {
+        let config = "listeners=CLIENTS://0.0.0.0:9093,REPLICATION://10.0.0.1:9094\n\
+            listener.security.protocol.map=CLIENTS:SASL_SSL,REPLICATION:SSL\n\
+            inter.broker.listener.name=REPLICATION\n\
+            sasl.enabled.mechanisms=SCRAM-SHA-512\n";
+        let findings = SecurityProtocolAnalyzer::new().analyze(&snapshot(config)).await.unwrap();
+        assert!(findings.is_empty(), "{:?}", findings);
+    }
+
+    #[tokio::test]
+    async fn test_localhost_plaintext_listener_not_flagged() {
+        let config = "listeners=INTERNAL://127.0.0.1:9092,EXTERNAL://:9093\n\
+            listener.security.protocol.map=INTERNAL:PLAINTEXT,EXTERNAL:SASL_SSL\n\
+            inter.broker.listener.name=INTERNAL\n";
+        let findings = SecurityProtocolAnalyzer::new().analyze(&snapshot(config)).await.unwrap();
+        assert!(findings.is_empty(), "{:?}", findings);
+    }
+
+    #[tokio::test]
+    async fn test_ssl_without_client_auth_is_unauthenticated() {
+        let findings = SecurityProtocolAnalyzer::new()
+            .analyze(&snapshot("listeners=SSL://:9093\nsecurity.inter.broker.protocol=SSL\n"))
+            .await
+            .unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].title, "Client authentication disabled on 1 broker(s)");
+
+        let findings = SecurityProtocolAnalyzer::new()
+            .analyze(&snapshot("listeners=SSL://:9093\nssl.client.auth=required\n"))
+            .await
+            .unwrap();
+        assert!(findings.is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+use crate::snapshot::format::{Finding, Severity};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Environment variable with the path of a YAML file of finding severity overrides
+pub const SEVERITY_OVERRIDES_FILE_ENV: &str = "KCPILOT_SEVERITY_OVERRIDES_FILE";
+
+/// Finding metadata key naming the rule that produced a rule finding
+pub const RULE_METADATA_KEY: &str = "rule";
+
+/// Finding metadata key keeping the severity an override replaced
+pub const ORIGINAL_SEVERITY_METADATA_KEY: &str = "original_severity";
+
+#[derive(Debug, Deserialize)]
+struct SeverityOverridesFile {
+    severity_overrides: HashMap<String, Severity>,
+}
+
+/// Severities an organization assigns to findings instead of the built-in ones, keyed by
+/// rule name (`leader_imbalance`) or finding id prefix (`CONN`, `TLS-002`)
+#[derive(Debug, Clone, Default)]
+pub struct SeverityOverrides {
+    severity_overrides: HashMap<String, Severity>,
+}
+
+impl SeverityOverrides {
+    pub fn new(severity_overrides: HashMap<String, Severity>) -> Self {
+        Self { severity_overrides }
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let file: SeverityOverridesFile = serde_yaml::from_str(yaml)
+            .context("Invalid severity overrides")?;
+        Ok(Self::new(file.severity_overrides))
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let yaml = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read severity overrides {}", path.display()))?;
+        Self::from_yaml(&yaml)
+    }
+
+    /// Load the overrides file named by `KCPILOT_SEVERITY_OVERRIDES_FILE`, if set
+    pub fn from_env() -> Result<Option<Self>> {
+        std::env::var(SEVERITY_OVERRIDES_FILE_ENV).ok()
+            .map(|path| Self::from_file(Path::new(&path)))
+            .transpose()
+    }
+
+    /// Severity configured for a finding: by the name of the rule that produced it, otherwise
+    /// by the longest key its id starts with
+    pub fn severity_for(&self, finding: &Finding) -> Option<Severity> {
+        let rule = finding.metadata.get(RULE_METADATA_KEY).and_then(|rule| rule.as_str());
+        if let Some(severity) = rule.and_then(|rule| self.severity_overrides.get(rule)) {
+            return Some(*severity);
+        }
+        self.severity_overrides.iter()
+            .filter(|(key, _)| finding.id.starts_with(key.as_str()))
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, severity)| *severity)
+    }
+
+    /// Remap the severity of matching findings, keeping the replaced one in their metadata,
+    /// and sort the findings by severity again. Returns how many findings changed.
+    pub fn apply(&self, findings: &mut [Finding]) -> usize {
+        let mut changed = 0;
+        for finding in findings.iter_mut() {
+            let Some(severity) = self.severity_for(finding).filter(|severity| *severity != finding.severity) else {
+                continue;
+            };
+            finding.metadata.entry(ORIGINAL_SEVERITY_METADATA_KEY.to_string())
+                .or_insert_with(|| serde_json::json!(finding.severity));
+            finding.severity = severity;
+            changed += 1;
+        }
+        findings.sort_by_key(|f| f.severity);
+        changed
+    }
+
+    pub fn len(&self) -> usize {
+        self.severity_overrides.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.severity_overrides.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(id: &str, severity: Severity) -> Finding {
+        Finding { id: id.to_string(), severity, ..Default::default() }
+    }
+
+    #[test]
+    fn test_rule_name_and_id_prefix_overrides() {
+        let overrides = SeverityOverrides::from_yaml(
+            "severity_overrides:\n  leader_imbalance: critical\n  CONN: low\n  CONN-002: info\n"
+        ).unwrap();
+        assert_eq!(overrides.len(), 3);
+
+        let mut by_rule = finding("FND-003-0c8b", Severity::Medium);
+        by_rule.metadata.insert(RULE_METADATA_KEY.to_string(), serde_json::json!("leader_imbalance"));
+        assert_eq!(overrides.severity_for(&by_rule), Some(Severity::Critical));
+
+        assert_eq!(overrides.severity_for(&finding("CONN-001", Severity::Medium)), Some(Severity::Low));
+        // The most specific prefix wins
+        assert_eq!(overrides.severity_for(&finding("CONN-002", Severity::Medium)), Some(Severity::Info));
+        assert_eq!(overrides.severity_for(&finding("GC-001", Severity::High)), None);
+
+        assert!(SeverityOverrides::from_yaml("severity_overrides:\n  CONN: severe\n").is_err());
+    }
+
+    #[test]
+    fn test_apply_records_original_severity() {
+        let overrides = SeverityOverrides::new(HashMap::from([("CONN".to_string(), Severity::Low)]));
+        let mut findings = vec![finding("CONN-001", Severity::Medium), finding("GC-001", Severity::High)];
+
+        assert_eq!(overrides.apply(&mut findings), 1);
+        assert_eq!(findings[1].id, "CONN-001");
+        assert_eq!(findings[1].severity, Severity::Low);
+        assert_eq!(findings[1].metadata[ORIGINAL_SEVERITY_METADATA_KEY], "medium");
+
+        // Applying again keeps the severity the analyzer gave
+        assert_eq!(overrides.apply(&mut findings), 0);
+        assert_eq!(findings[1].metadata[ORIGINAL_SEVERITY_METADATA_KEY], "medium");
+    }
+}
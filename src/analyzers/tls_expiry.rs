@@ -0,0 +1,239 @@
+use crate::analyzers::{Analyzer, AnalyzerResult};
+use crate::scan::tls_certificates::{CertificateCheck, TLS_CERTIFICATES_FILE};
+use crate::snapshot::format::{
+    Category, ConfigEvidence, Evidence, Finding, MetricEvidence, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::collections::HashMap;
+
+/// Days before expiry from which a certificate is reported
+const DEFAULT_WARNING_DAYS: i64 = 30;
+
+/// Expiry date of `openssl x509 -enddate` output, e.g. `notAfter=Jun  1 10:00:00 2025 GMT`
+pub fn parse_enddate(output: &str) -> Option<DateTime<Utc>> {
+    let date = output.lines().find_map(|line| line.trim().strip_prefix("notAfter="))?;
+    let date = date.trim().trim_end_matches("GMT").trim();
+    let date = date.split_whitespace().collect::<Vec<_>>().join(" ");
+    NaiveDateTime::parse_from_str(&date, "%b %d %H:%M:%S %Y").ok().map(|date| date.and_utc())
+}
+
+/// Subject of `openssl x509 -subject` output
+fn parse_subject(output: &str) -> Option<&str> {
+    output.lines().find_map(|line| line.trim().strip_prefix("subject=")).map(str::trim)
+}
+
+/// Severity of a certificate expiring at `not_after`, seen at `now`: critical once expired,
+/// high within `warning_days`, none before
+pub fn expiry_severity(not_after: DateTime<Utc>, now: DateTime<Utc>, warning_days: i64) -> Option<Severity> {
+    let remaining = not_after - now;
+    if remaining <= chrono::Duration::zero() {
+        Some(Severity::Critical)
+    } else if remaining <= chrono::Duration::days(warning_days) {
+        Some(Severity::High)
+    } else {
+        None
+    }
+}
+
+/// Flags broker certificates that expired or expire soon, from the keystores and truststores
+/// of their server.properties
+pub struct TlsExpiryAnalyzer {
+    warning_days: i64,
+}
+
+impl Default for TlsExpiryAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TlsExpiryAnalyzer {
+    pub fn new() -> Self {
+        Self { warning_days: DEFAULT_WARNING_DAYS }
+    }
+
+    /// Report certificates expiring within `days` of the scan
+    pub fn with_warning_days(mut self, days: i64) -> Self {
+        self.warning_days = days;
+        self
+    }
+
+    fn finding(&self, snapshot: &Snapshot, broker: &str, check: &CertificateCheck, not_after: DateTime<Utc>, severity: Severity) -> Finding {
+        let days = (not_after - snapshot.timestamp).num_days();
+        let subject = parse_subject(&check.output)
+            .map(|subject| format!(" ({})", subject))
+            .unwrap_or_default();
+        let (title, description, impact) = if severity == Severity::Critical {
+            (
+                format!("TLS certificate on {} has expired", broker),
+                format!(
+                    "The certificate{} in {} ({}) expired on {}, {} day(s) before the scan.",
+                    subject, check.path, check.setting, not_after.format("%Y-%m-%d %H:%M UTC"), -days
+                ),
+                "CRITICAL: Clients and other brokers reject the expired certificate, so every TLS connection to the broker fails",
+            )
+        } else {
+            (
+                format!("TLS certificate on {} expires in {} day(s)", broker, days),
+                format!(
+                    "The certificate{} in {} ({}) expires on {}.",
+                    subject, check.path, check.setting, not_after.format("%Y-%m-%d %H:%M UTC")
+                ),
+                "HIGH: Once the certificate expires, every TLS connection to the broker fails at the same moment",
+            )
+        };
+
+        let steps = [
+            format!("Issue a new certificate for {} from the same CA, keeping the broker hostname in its subject alternative names", broker),
+            format!("Replace the certificate in {} and reload it without a restart: kafka-configs.sh --bootstrap-server <broker> --entity-type brokers --entity-name <id> --alter --add-config {}={}", check.path, check.setting, check.path),
+            "Check the certificate served on each TLS listener: openssl s_client -connect <broker>:<port> </dev/null | openssl x509 -noout -enddate".to_string(),
+            "Monitor certificate expiry so renewals happen well before the last month".to_string(),
+        ];
+
+        Finding {
+            id: String::new(),
+            title,
+            description,
+            severity,
+            category: Category::Security,
+            impact: impact.to_string(),
+            evidence: Evidence {
+                metrics: vec![MetricEvidence {
+                    name: "tls_certificate_days_remaining".to_string(),
+                    value: days as f64,
+                    threshold: Some(self.warning_days as f64),
+                    unit: Some("days".to_string()),
+                    source: format!("{}/system/{}", broker, TLS_CERTIFICATES_FILE),
+                    timestamp: snapshot.timestamp,
+                }],
+                logs: Vec::new(),
+                configs: vec![ConfigEvidence {
+                    resource_type: "broker".to_string(),
+                    resource_name: broker.to_string(),
+                    config_key: check.setting.clone(),
+                    current_value: check.path.clone(),
+                    recommended_value: None,
+                    reason: format!("Certificate valid until {}", not_after.to_rfc3339()),
+                    source_files: vec![format!("{}/configs/server.properties", broker)],
+                }],
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Certificate not renewed before its validity period ended".to_string()),
+            remediation: Remediation {
+                steps: steps.into_iter().enumerate().map(|(i, description)| RemediationStep {
+                    order: i as u32 + 1,
+                    description,
+                    command: None,
+                    verification: None,
+                    can_automate: false,
+                }).collect(),
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(60),
+                rollback_plan: Some("Put the previous keystore back and reload it the same way".to_string()),
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for TlsExpiryAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        // Brokers without TLS have no entry, or an empty list of checks
+        let Some(brokers) = snapshot.collectors.custom.get("tls").and_then(|tls| tls.as_object()) else {
+            return Ok(Vec::new());
+        };
+
+        let mut findings = Vec::new();
+        for (broker, checks) in brokers {
+            let checks: Vec<CertificateCheck> = serde_json::from_value(checks.clone()).unwrap_or_default();
+            for check in &checks {
+                let Some(not_after) = parse_enddate(&check.output) else {
+                    continue;
+                };
+                if let Some(severity) = expiry_severity(not_after, snapshot.timestamp, self.warning_days) {
+                    findings.push(self.finding(snapshot, broker, check, not_after, severity));
+                }
+            }
+        }
+
+        findings.sort_by(|a, b| a.severity.cmp(&b.severity).then_with(|| a.title.cmp(&b.title)));
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("TLS-{:03}", i + 1);
+        }
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "TLS Expiry Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags broker TLS certificates that expired or expire within 30 days"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_enddate() {
+        let output = "notAfter=Jun  1 10:00:00 2025 GMT\nsubject=CN = kafka-1.internal\n";
+        assert_eq!(parse_enddate(output), Some(Utc.with_ymd_and_hms(2025, 6, 1, 10, 0, 0).unwrap()));
+        assert_eq!(parse_subject(output), Some("CN = kafka-1.internal"));
+        assert_eq!(parse_enddate("notAfter=Dec 31 23:59:59 2030 GMT"), Some(Utc.with_ymd_and_hms(2030, 12, 31, 23, 59, 59).unwrap()));
+        assert_eq!(parse_enddate("unable to load certificate"), None);
+        assert_eq!(parse_enddate(""), None);
+    }
+
+    #[test]
+    fn test_expiry_severity() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+        let in_days = |days| now + chrono::Duration::days(days);
+
+        assert_eq!(expiry_severity(in_days(-1), now, 30), Some(Severity::Critical));
+        assert_eq!(expiry_severity(now, now, 30), Some(Severity::Critical));
+        assert_eq!(expiry_severity(in_days(10), now, 30), Some(Severity::High));
+        assert_eq!(expiry_severity(in_days(30), now, 30), Some(Severity::High));
+        assert_eq!(expiry_severity(in_days(31), now, 30), None);
+    }
+
+    #[tokio::test]
+    async fn test_expiring_and_expired_certificates() {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.timestamp = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+        let check = |path: &str, enddate: &str| json!({
+            "setting": "ssl.keystore.location",
+            "path": path,
+            "output": format!("notAfter={}\nsubject=CN = kafka\n", enddate),
+        });
+        snapshot.collectors.custom.insert("tls".to_string(), json!({
+            "broker_1": [check("/etc/kafka/ssl/kafka.keystore.jks", "Jun 15 00:00:00 2025 GMT")],
+            "broker_2": [check("/etc/kafka/ssl/kafka.keystore.jks", "May 20 00:00:00 2025 GMT")],
+            "broker_3": [check("/etc/kafka/ssl/kafka.keystore.jks", "Jun  1 00:00:00 2027 GMT")],
+            "broker_4": [{"setting": "ssl.keystore.location", "path": "/missing.jks", "output": ""}],
+            "broker_5": [],
+        }));
+
+        let findings = TlsExpiryAnalyzer::new().analyze(&snapshot).await.unwrap();
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].id, "TLS-001");
+        assert_eq!(findings[0].severity, Severity::Critical);
+        assert_eq!(findings[0].title, "TLS certificate on broker_2 has expired");
+        assert_eq!(findings[1].severity, Severity::High);
+        assert_eq!(findings[1].title, "TLS certificate on broker_1 expires in 14 day(s)");
+        assert!(findings.iter().all(|f| matches!(f.category, Category::Security)));
+
+        // A scan without TLS data has nothing to report
+        let plain = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        assert!(TlsExpiryAnalyzer::new().analyze(&plain).await.unwrap().is_empty());
+    }
+}
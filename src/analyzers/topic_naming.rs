@@ -0,0 +1,274 @@
+use crate::analyzers::{Analyzer, AnalyzerError, AnalyzerResult};
+use crate::collectors::admin::AdminCollectorOutput;
+use crate::snapshot::format::{
+    Category, Evidence, Finding, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use regex::Regex;
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Environment variable holding the regex every topic name must match
+pub const TOPIC_NAME_PATTERN_ENV: &str = "TOPIC_NAME_PATTERN";
+
+/// Names typically left behind by manual testing (`test`, `foo`, `tmp-orders`, ...)
+const LEFTOVER_TOPIC_PATTERN: &str =
+    r"(?i)^(test|tests|testing|foo|bar|baz|tmp|temp|dummy|demo|scratch|delete-?me)([-_.].*)?$";
+
+/// Kafka and ecosystem internal topics (`__consumer_offsets`, `_schemas`, `_confluent-*`)
+fn is_internal_topic(name: &str, is_internal: bool) -> bool {
+    is_internal || name.starts_with('_')
+}
+
+/// Lints topic names against a naming convention and flags leftover test topics
+pub struct TopicNamingAnalyzer {
+    pattern: Option<Regex>,
+    leftover: Regex,
+}
+
+impl Default for TopicNamingAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TopicNamingAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            pattern: None,
+            leftover: Regex::new(LEFTOVER_TOPIC_PATTERN).expect("valid leftover topic pattern"),
+        }
+    }
+
+    /// Require topic names to match `pattern` in full
+    pub fn with_pattern(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.pattern = Some(Regex::new(&format!("^(?:{})$", pattern))?);
+        Ok(self)
+    }
+
+    /// Build the analyzer with the naming convention from `TOPIC_NAME_PATTERN`, if set
+    pub fn from_env() -> Self {
+        let Ok(pattern) = std::env::var(TOPIC_NAME_PATTERN_ENV) else {
+            return Self::new();
+        };
+        if pattern.trim().is_empty() {
+            return Self::new();
+        }
+
+        Self::new().with_pattern(pattern.trim()).unwrap_or_else(|e| {
+            warn!("Ignoring invalid {}: {}", TOPIC_NAME_PATTERN_ENV, e);
+            Self::new()
+        })
+    }
+
+    fn finding(title: String, description: String, impact: &str, steps: &[&str], topics: &[&str]) -> Finding {
+        let mut metadata = HashMap::new();
+        metadata.insert("topics".to_string(), serde_json::json!(topics));
+
+        Finding {
+            id: String::new(),
+            title,
+            description,
+            severity: Severity::Info,
+            category: Category::ClusterHygiene,
+            impact: impact.to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: Some(serde_json::json!({ "topics": topics })),
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: steps.iter().enumerate().map(|(i, step)| RemediationStep {
+                    order: i as u32 + 1,
+                    description: step.to_string(),
+                    command: None,
+                    verification: Some("kafka-topics.sh --bootstrap-server <broker> --list".to_string()),
+                    can_automate: false,
+                }).collect(),
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(30),
+                rollback_plan: None,
+            },
+            metadata,
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for TopicNamingAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let mut findings = Vec::new();
+        let Some(admin_data) = snapshot.collectors.admin.as_ref() else {
+            return Ok(findings);
+        };
+        let admin: AdminCollectorOutput = serde_json::from_value(admin_data.clone())
+            .map_err(|e| AnalyzerError::InvalidData(format!("Failed to parse admin data: {}", e)))?;
+
+        let mut names: Vec<&str> = admin.topics.iter()
+            .filter(|t| !is_internal_topic(&t.name, t.is_internal))
+            .map(|t| t.name.as_str())
+            .collect();
+        names.sort_unstable();
+
+        let leftovers: Vec<&str> = names.iter()
+            .copied()
+            .filter(|name| self.leftover.is_match(name))
+            .collect();
+
+        if let Some(pattern) = &self.pattern {
+            let violations: Vec<&str> = names.iter()
+                .copied()
+                .filter(|name| !pattern.is_match(name) && !leftovers.contains(name))
+                .collect();
+
+            if !violations.is_empty() {
+                findings.push(Self::finding(
+                    format!("{} topic(s) do not follow the naming convention", violations.len()),
+                    format!(
+                        "Topic names must match {} ({}). Non-conforming topics: {}",
+                        pattern.as_str(), TOPIC_NAME_PATTERN_ENV, violations.join(", ")
+                    ),
+                    "INFO: Inconsistent names make ownership, ACL prefixes and quotas harder to manage.",
+                    &[
+                        "Confirm the owners of the listed topics",
+                        "Migrate producers and consumers to conforming topic names and delete the old topics",
+                    ],
+                    &violations,
+                ));
+            }
+        }
+
+        if !leftovers.is_empty() {
+            findings.push(Self::finding(
+                format!("{} topic(s) look like leftovers from testing", leftovers.len()),
+                format!(
+                    "These topic names suggest they were created for tests or experiments: {}",
+                    leftovers.join(", ")
+                ),
+                "INFO: Unused topics hold partitions, disk space and metadata that every broker has to track.",
+                &[
+                    "Check that nothing still produces to or consumes from the listed topics",
+                    "Delete the topics that are no longer needed",
+                ],
+                &leftovers,
+            ));
+        }
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("TOPIC-NAMING-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Topic Naming Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Checks topic names against the configured naming convention and flags leftover test topics"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    fn snapshot_with_topics(names: &[&str]) -> Snapshot {
+        let topics: Vec<_> = names.iter()
+            .map(|name| json!({
+                "name": name,
+                "partitions": [],
+                "replication_factor": 3,
+                "is_internal": name.starts_with("__"),
+            }))
+            .collect();
+
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.admin = Some(json!({
+            "cluster": {
+                "cluster_id": null, "controller_id": null,
+                "broker_count": 3, "topic_count": topics.len(), "partition_count": 0
+            },
+            "brokers": [],
+            "topics": topics,
+        }));
+        snapshot
+    }
+
+    fn analyzer() -> TopicNamingAnalyzer {
+        TopicNamingAnalyzer::new()
+            .with_pattern(r"[a-z]+\.[a-z]+\.[a-z0-9-]+")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_conforming_topics() {
+        let snapshot = snapshot_with_topics(&[
+            "payments.orders.created",
+            "payments.orders.v2",
+            "__consumer_offsets",
+            "_schemas",
+        ]);
+
+        assert!(analyzer().analyze(&snapshot).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_non_conforming_topics() {
+        let snapshot = snapshot_with_topics(&["payments.orders.created", "OrdersTopic", "orders"]);
+
+        let findings = analyzer().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].id, "TOPIC-NAMING-001");
+        assert_eq!(findings[0].severity, Severity::Info);
+        assert!(matches!(findings[0].category, Category::ClusterHygiene));
+        assert!(findings[0].description.contains("OrdersTopic, orders"));
+        assert!(!findings[0].description.contains("payments.orders.created"));
+    }
+
+    #[tokio::test]
+    async fn test_pattern_must_match_whole_name() {
+        let snapshot = snapshot_with_topics(&["payments.orders.created.Extra_Suffix"]);
+
+        let findings = analyzer().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_leftover_topics_without_pattern() {
+        let snapshot = snapshot_with_topics(&["test", "foo", "tmp-orders", "TEST_2", "latest-prices", "__consumer_offsets"]);
+
+        let findings = TopicNamingAnalyzer::new().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("4 topic(s) look like leftovers"));
+        assert_eq!(findings[0].metadata["topics"], json!(["TEST_2", "foo", "test", "tmp-orders"]));
+    }
+
+    #[tokio::test]
+    async fn test_leftovers_not_reported_twice() {
+        let snapshot = snapshot_with_topics(&["test", "Orders"]);
+
+        let findings = analyzer().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].metadata["topics"], json!(["Orders"]));
+        assert_eq!(findings[1].metadata["topics"], json!(["test"]));
+        assert_eq!(findings[1].id, "TOPIC-NAMING-002");
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        assert!(TopicNamingAnalyzer::new().with_pattern("orders.(").is_err());
+    }
+}
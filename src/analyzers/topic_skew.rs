@@ -0,0 +1,252 @@
+use crate::analyzers::{Analyzer, AnalyzerError, AnalyzerResult};
+use crate::collectors::admin::{AdminCollectorOutput, TopicInfo};
+use crate::snapshot::format::{
+    Category, Evidence, Finding, MetricEvidence, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+
+/// Topics listed in the finding description, most concentrated first
+const LISTED_TOPICS: usize = 10;
+
+/// How one topic's replicas and leaders are spread over the brokers
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicDistribution {
+    pub topic: String,
+    pub partitions: usize,
+    /// Replicas hosted by each broker
+    pub replicas_per_broker: BTreeMap<i32, usize>,
+    /// Partitions led by each broker
+    pub leaders_per_broker: BTreeMap<i32, usize>,
+    /// Brokers the topic could spread over: every broker, or one per replica if it has fewer
+    pub brokers_available: usize,
+}
+
+impl TopicDistribution {
+    pub fn new(topic: &TopicInfo, broker_count: usize) -> Self {
+        let mut replicas_per_broker = BTreeMap::new();
+        let mut leaders_per_broker = BTreeMap::new();
+        for partition in &topic.partitions {
+            for replica in &partition.replicas {
+                *replicas_per_broker.entry(*replica).or_insert(0) += 1;
+            }
+            if let Some(leader) = partition.leader.filter(|leader| *leader >= 0) {
+                *leaders_per_broker.entry(leader).or_insert(0) += 1;
+            }
+        }
+        let replica_count: usize = replicas_per_broker.values().sum();
+
+        Self {
+            topic: topic.name.clone(),
+            partitions: topic.partitions.len(),
+            brokers_available: broker_count.max(replicas_per_broker.len()).min(replica_count),
+            replicas_per_broker,
+            leaders_per_broker,
+        }
+    }
+
+    pub fn brokers_used(&self) -> usize {
+        self.replicas_per_broker.len()
+    }
+
+    /// Whether the replicas sit on fewer brokers than the topic could use
+    pub fn is_concentrated(&self) -> bool {
+        self.brokers_used() < self.brokers_available
+    }
+
+    fn describe(counts: &BTreeMap<i32, usize>) -> String {
+        counts.iter().map(|(broker, count)| format!("{}: {}", broker, count)).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// Flags topics whose replicas sit on fewer brokers than their partitions and replication factor allow
+pub struct TopicSkewAnalyzer;
+
+impl Default for TopicSkewAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TopicSkewAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Distribution of every topic with partitions, given the cluster's broker count
+    pub fn distributions(admin: &AdminCollectorOutput) -> Vec<TopicDistribution> {
+        let broker_count = admin.brokers.len().max(admin.cluster.broker_count);
+        admin.topics.iter()
+            .filter(|topic| !topic.partitions.is_empty())
+            .map(|topic| TopicDistribution::new(topic, broker_count))
+            .collect()
+    }
+
+    fn finding(snapshot: &Snapshot, mut skewed: Vec<TopicDistribution>) -> Finding {
+        skewed.sort_by(|a, b| {
+            (b.brokers_available - b.brokers_used()).cmp(&(a.brokers_available - a.brokers_used()))
+                .then_with(|| a.topic.cmp(&b.topic))
+        });
+
+        let listed: Vec<String> = skewed.iter().take(LISTED_TOPICS)
+            .map(|d| format!(
+                "{} ({} partition(s) on {} of {} possible broker(s); replicas per broker {}; leaders per broker {})",
+                d.topic, d.partitions, d.brokers_used(), d.brokers_available,
+                TopicDistribution::describe(&d.replicas_per_broker),
+                TopicDistribution::describe(&d.leaders_per_broker),
+            ))
+            .collect();
+        let more = skewed.len().saturating_sub(LISTED_TOPICS);
+
+        let steps = [
+            (
+                "List the topics to move in a topics-to-move.json file and let Kafka propose an even assignment over all brokers",
+                Some("kafka-reassign-partitions.sh --bootstrap-server <broker> --topics-to-move-json-file topics-to-move.json --broker-list <all broker ids> --generate"),
+            ),
+            (
+                "Review the proposed assignment, then execute it with a replication throttle so the move does not starve clients",
+                Some("kafka-reassign-partitions.sh --bootstrap-server <broker> --reassignment-json-file reassignment.json --execute --throttle 50000000"),
+            ),
+            (
+                "Wait for the reassignment to finish, which also removes the throttle",
+                Some("kafka-reassign-partitions.sh --bootstrap-server <broker> --reassignment-json-file reassignment.json --verify"),
+            ),
+            (
+                "Rebalance leadership over the new replicas",
+                Some("kafka-leader-election.sh --bootstrap-server <broker> --election-type preferred --all-topic-partitions"),
+            ),
+        ];
+
+        Finding {
+            id: "SKEW-001".to_string(),
+            title: format!("{} topic(s) with replicas concentrated on few brokers", skewed.len()),
+            description: format!(
+                "These topics keep their replicas on fewer brokers than their partitions and replication factor allow, \
+                 so a few brokers carry all of their traffic while the others sit idle: {}{}.",
+                listed.join("; "),
+                if more > 0 { format!("; and {} more", more) } else { String::new() }
+            ),
+            severity: Severity::Medium,
+            category: Category::Performance,
+            impact: "MEDIUM: The brokers holding these topics see more disk, network and request load than the rest, and become the bottleneck for their producers and consumers.".to_string(),
+            evidence: Evidence {
+                metrics: skewed.iter().map(|d| MetricEvidence {
+                    name: format!("{}_brokers_used", d.topic),
+                    value: d.brokers_used() as f64,
+                    threshold: Some(d.brokers_available as f64),
+                    unit: Some("brokers".to_string()),
+                    source: "admin".to_string(),
+                    timestamp: snapshot.timestamp,
+                }).collect(),
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: Some("Topics created while the cluster had fewer brokers, or moved by hand, and never reassigned after brokers were added".to_string()),
+            remediation: Remediation {
+                steps: steps.iter().enumerate().map(|(i, (description, command))| RemediationStep {
+                    order: i as u32 + 1,
+                    description: description.to_string(),
+                    command: command.map(str::to_string),
+                    verification: None,
+                    can_automate: false,
+                }).collect(),
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(120),
+                rollback_plan: Some("Run the reassignment again with the original assignment that --generate printed".to_string()),
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for TopicSkewAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let Some(admin) = snapshot.collectors.admin.as_ref() else {
+            return Ok(Vec::new());
+        };
+        let admin: AdminCollectorOutput = serde_json::from_value(admin.clone())
+            .map_err(|e| AnalyzerError::InvalidData(format!("Failed to parse admin data: {}", e)))?;
+
+        let skewed: Vec<TopicDistribution> = Self::distributions(&admin).into_iter()
+            .filter(TopicDistribution::is_concentrated)
+            .collect();
+        if skewed.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(vec![Self::finding(snapshot, skewed)])
+    }
+
+    fn name(&self) -> &'static str {
+        "Topic Skew Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags topics whose replicas are concentrated on fewer brokers than they could spread over"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    fn snapshot(broker_count: usize, topics: &[(&str, &[&[i32]])]) -> Snapshot {
+        let brokers: Vec<_> = (1..=broker_count)
+            .map(|id| json!({"id": id, "host": format!("kafka-{}", id), "port": 9092, "rack": null}))
+            .collect();
+        let topics: Vec<_> = topics.iter()
+            .map(|(name, replicas)| json!({
+                "name": name,
+                "partitions": replicas.iter().enumerate().map(|(id, replicas)| json!({
+                    "id": id, "leader": replicas[0], "replicas": replicas, "isr": replicas, "offline_replicas": []
+                })).collect::<Vec<_>>(),
+                "replication_factor": replicas[0].len(),
+                "is_internal": false,
+            }))
+            .collect();
+
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.admin = Some(json!({
+            "cluster": {"cluster_id": null, "controller_id": 1, "broker_count": broker_count, "topic_count": topics.len(), "partition_count": 0},
+            "brokers": brokers,
+            "topics": topics,
+        }));
+        snapshot
+    }
+
+    #[tokio::test]
+    async fn test_evenly_spread_topic_passes() {
+        let snapshot = snapshot(4, &[
+            ("orders", &[&[1, 2], &[2, 3], &[3, 4], &[4, 1]]),
+            // One partition of RF 2 can only ever use two brokers
+            ("config", &[&[3, 4]]),
+        ]);
+        assert!(TopicSkewAnalyzer::new().analyze(&snapshot).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_concentrated_topic_flagged() {
+        let snapshot = snapshot(4, &[
+            ("orders", &[&[1, 2], &[2, 3], &[3, 4], &[4, 1]]),
+            ("payments", &[&[1, 2], &[2, 1], &[1, 2], &[2, 1]]),
+        ]);
+        let findings = TopicSkewAnalyzer::new().analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.title, "1 topic(s) with replicas concentrated on few brokers");
+        assert_eq!(finding.severity, Severity::Medium);
+        assert!(matches!(finding.category, Category::Performance));
+        assert!(finding.description.contains(
+            "payments (4 partition(s) on 2 of 4 possible broker(s); replicas per broker 1: 4, 2: 4; leaders per broker 1: 2, 2: 2)"
+        ));
+        assert_eq!(finding.evidence.metrics[0].name, "payments_brokers_used");
+        assert!(finding.remediation.steps[0].command.as_deref().unwrap().contains("--generate"));
+    }
+}
@@ -0,0 +1,457 @@
+use crate::analyzers::{Analyzer, AnalyzerResult};
+use crate::snapshot::format::{
+    Category, ConfigEvidence, Evidence, Finding, Remediation, RemediationStep, RiskLevel, Severity, Snapshot
+};
+use async_trait::async_trait;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+/// Environment variable overriding the end-of-life Kafka releases (comma-separated, e.g. `2,3.0,3.1`)
+pub const KAFKA_EOL_VERSIONS_ENV: &str = "KAFKA_EOL_VERSIONS";
+
+/// Kafka releases no longer receiving fixes. An entry matches every version it is a prefix of,
+/// component-wise, so `3.1` matches `3.1.2` but not `3.10.0`.
+pub const DEFAULT_EOL_KAFKA_VERSIONS: &[&str] = &[
+    "0", "1", "2", "3.0", "3.1", "3.2", "3.3", "3.4", "3.5", "3.6",
+];
+
+/// Kafka and Java versions detected on one scanned broker
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokerVersions {
+    pub broker: String,
+    pub kafka_version: Option<DetectedVersion>,
+    pub java_version: Option<DetectedVersion>,
+}
+
+/// A version string and the scanned file it was read from
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedVersion {
+    pub version: String,
+    pub source: String,
+}
+
+fn version_regexes() -> &'static [Regex; 3] {
+    static REGEXES: OnceLock<[Regex; 3]> = OnceLock::new();
+    REGEXES.get_or_init(|| [
+        // AppInfoParser at startup: "Kafka version: 3.6.1"
+        Regex::new(r"Kafka version: ?(\d+(?:\.\d+)+[\w.-]*)").expect("valid regex"),
+        // Broker jar or installation directory: "kafka_2.13-3.6.1"
+        Regex::new(r"kafka_\d+\.\d+-(\d+(?:\.\d+)+)").expect("valid regex"),
+        // Client jar on the classpath: "kafka-clients-3.6.1.jar"
+        Regex::new(r"kafka-clients-(\d+(?:\.\d+)+)").expect("valid regex"),
+    ])
+}
+
+/// Find the Kafka version in a log, process listing or installation path.
+/// Log lines take the last match so a restart after an upgrade wins.
+pub fn parse_kafka_version(text: &str) -> Option<String> {
+    let [log, jar, clients] = version_regexes();
+    log.captures_iter(text).last()
+        .or_else(|| jar.captures(text))
+        .or_else(|| clients.captures(text))
+        .map(|caps| caps[1].to_string())
+}
+
+/// Extract the version from `java -version` output
+pub fn parse_java_version(output: &str) -> Option<String> {
+    let (_, rest) = output.split_once("version \"")?;
+    let (version, _) = rest.split_once('"')?;
+    Some(version.to_string())
+}
+
+/// Whether `version` belongs to `release`, comparing dot-separated components
+fn matches_release(version: &str, release: &str) -> bool {
+    let mut components = version.split(['.', '-']);
+    release.trim().split('.').all(|part| components.next() == Some(part))
+}
+
+/// Flags brokers running different Kafka or Java versions, and Kafka releases past end of life
+pub struct VersionConsistencyAnalyzer {
+    eol_versions: Vec<String>,
+}
+
+impl Default for VersionConsistencyAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VersionConsistencyAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            eol_versions: DEFAULT_EOL_KAFKA_VERSIONS.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+
+    /// Replace the list of end-of-life Kafka releases
+    pub fn with_eol_versions(mut self, versions: Vec<String>) -> Self {
+        self.eol_versions = versions;
+        self
+    }
+
+    /// Build the analyzer with the end-of-life list from `KAFKA_EOL_VERSIONS`, if set
+    pub fn from_env() -> Self {
+        match std::env::var(KAFKA_EOL_VERSIONS_ENV) {
+            Ok(value) if !value.trim().is_empty() => Self::new().with_eol_versions(
+                value.split(',')
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+                    .collect(),
+            ),
+            _ => Self::new(),
+        }
+    }
+
+    fn is_eol(&self, version: &str) -> bool {
+        self.eol_versions.iter().any(|release| matches_release(version, release))
+    }
+
+    /// Versions of every scanned broker, paired with the file each was read from
+    pub fn broker_versions(snapshot: &Snapshot) -> Vec<BrokerVersions> {
+        let Some(brokers) = snapshot.collectors.custom.get("brokers").and_then(|b| b.as_object()) else {
+            return Vec::new();
+        };
+
+        let mut versions: Vec<BrokerVersions> = brokers.iter()
+            .filter(|(_, broker)| broker.is_object())
+            .map(|(name, broker)| {
+                let file = |dir: &str, file: &str| broker.get(dir).and_then(|d| d.get(file)).and_then(|c| c.as_str());
+
+                let log_sources = broker.get("logs")
+                    .and_then(|logs| logs.as_object())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|(log, content)| content.as_str().map(|c| (format!("logs/{}", log), c)));
+                let kafka_version = log_sources
+                    .chain(["kafka_process.txt", "kafka_installation_path.txt"].into_iter().filter_map(|f| {
+                        file("system", f).map(|c| (format!("system/{}", f), c))
+                    }))
+                    .find_map(|(path, content)| {
+                        parse_kafka_version(content).map(|version| DetectedVersion { version, source: format!("{}/{}", name, path) })
+                    });
+
+                let java_version = file("system", "java_version.txt")
+                    .and_then(parse_java_version)
+                    .map(|version| DetectedVersion { version, source: format!("{}/system/java_version.txt", name) });
+
+                BrokerVersions { broker: name.clone(), kafka_version, java_version }
+            })
+            .collect();
+        versions.sort_by(|a, b| a.broker.cmp(&b.broker));
+        versions
+    }
+
+    fn mismatch_finding(
+        software: &str,
+        config_key: &str,
+        versions: &[(&str, &DetectedVersion)],
+        by_version: &BTreeMap<&str, Vec<&str>>,
+    ) -> Finding {
+        let summary = by_version.iter()
+            .map(|(version, brokers)| format!("{} on {}", version, brokers.join(", ")))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        Finding {
+            id: String::new(),
+            title: format!("Brokers run {} different {} versions", by_version.len(), software),
+            description: format!(
+                "Scanned brokers do not agree on the {} version: {}. \
+                This usually means a rolling upgrade was left unfinished.",
+                software, summary
+            ),
+            severity: Severity::Medium,
+            category: Category::ClusterHygiene,
+            impact: format!(
+                "MEDIUM: Mixed {} versions behave differently under the same load and configuration, \
+                causing bugs that only show up on some brokers.",
+                software
+            ),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs: versions.iter().map(|(broker, detected)| ConfigEvidence {
+                    resource_type: "broker".to_string(),
+                    resource_name: broker.to_string(),
+                    config_key: config_key.to_string(),
+                    current_value: detected.version.clone(),
+                    recommended_value: None,
+                    reason: format!("All brokers should run the same {} version", software),
+                    source_files: vec![detected.source.clone()],
+                }).collect(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: format!("Decide on the target {} version for the cluster", software),
+                        command: None,
+                        verification: None,
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Finish the rolling upgrade, restarting one broker at a time and waiting for \
+                            under-replicated partitions to return to 0 between brokers".to_string(),
+                        command: None,
+                        verification: Some("kafka-topics.sh --bootstrap-server <broker> --describe --under-replicated-partitions".to_string()),
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(120),
+                rollback_plan: Some("Roll the upgraded brokers back to the previous version one at a time".to_string()),
+            },
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn eol_finding(eol: &[(&str, &DetectedVersion)]) -> Finding {
+        let summary = eol.iter()
+            .map(|(broker, detected)| format!("{} ({})", broker, detected.version))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Finding {
+            id: String::new(),
+            title: format!("{} broker(s) run an end-of-life Kafka version", eol.len()),
+            description: format!(
+                "These brokers run Kafka releases that no longer receive bug or security fixes: {}.",
+                summary
+            ),
+            severity: Severity::High,
+            category: Category::ClusterHygiene,
+            impact: "HIGH: Known bugs and vulnerabilities in end-of-life releases will not be fixed.".to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs: eol.iter().map(|(broker, detected)| ConfigEvidence {
+                    resource_type: "broker".to_string(),
+                    resource_name: broker.to_string(),
+                    config_key: "kafka.version".to_string(),
+                    current_value: detected.version.clone(),
+                    recommended_value: Some("a supported Kafka release".to_string()),
+                    reason: "This Kafka release is past end of life".to_string(),
+                    source_files: vec![detected.source.clone()],
+                }).collect(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: vec![
+                    RemediationStep {
+                        order: 1,
+                        description: "Plan an upgrade to a supported Kafka release, checking the upgrade notes \
+                            for every release in between".to_string(),
+                        command: None,
+                        verification: None,
+                        can_automate: false,
+                    },
+                    RemediationStep {
+                        order: 2,
+                        description: "Upgrade the brokers one at a time, keeping the inter-broker protocol / \
+                            metadata version pinned until every broker runs the new release".to_string(),
+                        command: None,
+                        verification: None,
+                        can_automate: false,
+                    },
+                ],
+                script: None,
+                risk_level: RiskLevel::High,
+                requires_downtime: false,
+                estimated_duration_minutes: Some(240),
+                rollback_plan: Some("Downgrade is possible until the inter-broker protocol / metadata version is bumped".to_string()),
+            },
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for VersionConsistencyAnalyzer {
+    async fn analyze(&self, snapshot: &Snapshot) -> AnalyzerResult<Vec<Finding>> {
+        let brokers = Self::broker_versions(snapshot);
+        let mut findings = Vec::new();
+
+        let kafka: Vec<(&str, &DetectedVersion)> = brokers.iter()
+            .filter_map(|b| b.kafka_version.as_ref().map(|v| (b.broker.as_str(), v)))
+            .collect();
+        let java: Vec<(&str, &DetectedVersion)> = brokers.iter()
+            .filter_map(|b| b.java_version.as_ref().map(|v| (b.broker.as_str(), v)))
+            .collect();
+
+        for (software, config_key, versions) in [("Kafka", "kafka.version", &kafka), ("Java", "java.version", &java)] {
+            let mut by_version: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+            for (broker, detected) in versions {
+                by_version.entry(detected.version.as_str()).or_default().push(broker);
+            }
+            if by_version.len() > 1 {
+                findings.push(Self::mismatch_finding(software, config_key, versions, &by_version));
+            }
+        }
+
+        let eol: Vec<(&str, &DetectedVersion)> = kafka.iter()
+            .filter(|(_, detected)| self.is_eol(&detected.version))
+            .copied()
+            .collect();
+        if !eol.is_empty() {
+            findings.push(Self::eol_finding(&eol));
+        }
+
+        for (i, finding) in findings.iter_mut().enumerate() {
+            finding.id = format!("VERSION-{:03}", i + 1);
+        }
+
+        Ok(findings)
+    }
+
+    fn name(&self) -> &'static str {
+        "Version Consistency Analyzer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Checks that all brokers run the same Kafka and Java versions and that Kafka is not end of life"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    const JAVA_17: &str = "openjdk version \"17.0.8\" 2023-07-18\nOpenJDK Runtime Environment (build 17.0.8+7)";
+    const JAVA_11: &str = "openjdk version \"11.0.20\" 2023-07-18";
+
+    fn snapshot(brokers: &[(&str, &str, &str)]) -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let mut dirs = serde_json::Map::new();
+        for (name, kafka, java) in brokers {
+            dirs.insert(name.to_string(), json!({
+                "system": {
+                    "java_version.txt": java,
+                    "kafka_process.txt": format!(
+                        "kafka 1234 java -cp /opt/kafka/libs/kafka_2.13-{}.jar:/opt/kafka/libs/kafka-clients-{}.jar kafka.Kafka",
+                        kafka, kafka
+                    ),
+                },
+            }));
+        }
+        snapshot.collectors.custom.insert("brokers".to_string(), serde_json::Value::Object(dirs));
+        snapshot
+    }
+
+    async fn analyze(snapshot: &Snapshot) -> Vec<Finding> {
+        VersionConsistencyAnalyzer::new().analyze(snapshot).await.unwrap()
+    }
+
+    #[test]
+    fn test_parse_versions() {
+        assert_eq!(parse_java_version(JAVA_17).as_deref(), Some("17.0.8"));
+        assert_eq!(parse_java_version("java version \"1.8.0_382\"").as_deref(), Some("1.8.0_382"));
+        assert_eq!(parse_java_version("bash: java: command not found"), None);
+
+        let log = "[2024-01-01] INFO Kafka version: 3.5.1 (AppInfoParser)\n[2024-02-01] INFO Kafka version: 3.7.0 (AppInfoParser)";
+        assert_eq!(parse_kafka_version(log).as_deref(), Some("3.7.0"));
+        assert_eq!(parse_kafka_version("/opt/kafka_2.13-3.6.1").as_deref(), Some("3.6.1"));
+        assert_eq!(parse_kafka_version("libs/kafka-clients-7.5.0-ccs.jar").as_deref(), Some("7.5.0"));
+        assert_eq!(parse_kafka_version("no version here"), None);
+    }
+
+    #[test]
+    fn test_matches_release() {
+        assert!(matches_release("3.1.2", "3.1"));
+        assert!(!matches_release("3.10.0", "3.1"));
+        assert!(matches_release("2.8.2", "2"));
+        assert!(!matches_release("3.1", "3.1.2"));
+    }
+
+    #[tokio::test]
+    async fn test_uniform_versions() {
+        let snapshot = snapshot(&[
+            ("broker_1", "3.7.1", JAVA_17),
+            ("broker_2", "3.7.1", JAVA_17),
+            ("broker_3", "3.7.1", JAVA_17),
+        ]);
+
+        assert!(analyze(&snapshot).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mixed_kafka_and_java_versions() {
+        let snapshot = snapshot(&[
+            ("broker_1", "3.7.1", JAVA_17),
+            ("broker_2", "3.8.0", JAVA_17),
+            ("broker_3", "3.7.1", JAVA_11),
+        ]);
+
+        let findings = analyze(&snapshot).await;
+
+        assert_eq!(findings.len(), 2);
+        assert!(findings.iter().all(|f| f.severity == Severity::Medium));
+        assert!(findings.iter().all(|f| matches!(f.category, Category::ClusterHygiene)));
+
+        let kafka = &findings[0];
+        assert_eq!(kafka.id, "VERSION-001");
+        assert!(kafka.title.contains("2 different Kafka versions"));
+        assert!(kafka.description.contains("3.7.1 on broker_1, broker_3; 3.8.0 on broker_2"));
+        assert_eq!(kafka.evidence.configs.len(), 3);
+        assert_eq!(kafka.evidence.configs[1].resource_name, "broker_2");
+        assert_eq!(kafka.evidence.configs[1].current_value, "3.8.0");
+        assert_eq!(kafka.evidence.configs[1].source_files, vec!["broker_2/system/kafka_process.txt"]);
+
+        assert!(findings[1].title.contains("Java"));
+        assert_eq!(findings[1].evidence.configs[2].current_value, "11.0.20");
+    }
+
+    #[tokio::test]
+    async fn test_server_log_version_preferred() {
+        let mut snapshot = snapshot(&[("broker_1", "3.7.1", JAVA_17), ("broker_2", "3.7.1", JAVA_17)]);
+        let brokers = snapshot.collectors.custom.get_mut("brokers").unwrap();
+        brokers["broker_2"]["logs"] = json!({"server.log": "INFO Kafka version: 3.8.0 (org.apache.kafka.common.utils.AppInfoParser)"});
+
+        let versions = VersionConsistencyAnalyzer::broker_versions(&snapshot);
+
+        assert_eq!(versions[1].kafka_version, Some(DetectedVersion {
+            version: "3.8.0".to_string(),
+            source: "broker_2/logs/server.log".to_string(),
+        }));
+        assert_eq!(analyze(&snapshot).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_eol_kafka_version() {
+        let snapshot = snapshot(&[("broker_1", "2.8.2", JAVA_11), ("broker_2", "2.8.2", JAVA_11)]);
+
+        let findings = analyze(&snapshot).await;
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::High);
+        assert!(findings[0].description.contains("broker_1 (2.8.2), broker_2 (2.8.2)"));
+    }
+
+    #[tokio::test]
+    async fn test_custom_eol_list() {
+        let snapshot = snapshot(&[("broker_1", "3.7.1", JAVA_17)]);
+
+        let analyzer = VersionConsistencyAnalyzer::new().with_eol_versions(vec!["3.7".to_string()]);
+        let findings = analyzer.analyze(&snapshot).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::High);
+    }
+
+    #[tokio::test]
+    async fn test_no_broker_data() {
+        let snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+
+        assert!(analyze(&snapshot).await.is_empty());
+    }
+}
@@ -1,4 +1,9 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use crate::collectors::RedactionLevel;
+use crate::scan::collector::ScanCollector;
+use crate::scan::enhanced_log_discovery::{DEFAULT_LOG_BUDGET_BYTES, DEFAULT_LOG_LINES};
+use crate::snapshot::format::{Category, Severity};
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -25,44 +30,139 @@ pub struct Cli {
 pub enum Commands {
     /// Scan a Kafka cluster for health issues
     Scan {
-        /// SSH bastion alias (from ~/.ssh/config). If not provided, assumes running locally on bastion
+        /// SSH bastion alias (from ~/.ssh/config), or a comma-separated chain jump1,jump2,bastion reached through ProxyJump. If not provided, assumes running locally on bastion
         #[arg(short, long)]
         bastion: Option<String>,
         
-        /// Output directory for the scan results
+        /// Output directory for the scan results, or s3://bucket/prefix or gs://bucket/prefix to upload them
         #[arg(short, long)]
         output: Option<PathBuf>,
         
-        /// Single broker hostname:port to discover cluster from. If not provided, uses hardcoded broker list
-        #[arg(long)]
-        broker: Option<String>,
+        #[command(flatten)]
+        brokers: BrokerArgs,
+        
+        #[command(flatten)]
+        collection: CollectionArgs,
+
+        #[command(flatten)]
+        display: ScanDisplayArgs,
     },
     
+    /// Scan every cluster listed in an inventory file
+    Batch {
+        /// YAML inventory listing the clusters to scan, each with a name and optional bastion/broker
+        #[arg(short, long)]
+        inventory: PathBuf,
+
+        /// Output directory, or an s3:// or gs:// prefix; each cluster is scanned into a subdirectory named after it
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Number of clusters to scan at the same time
+        #[arg(long, default_value = "1")]
+        concurrency: usize,
+
+        /// Only collect log entries newer than this: an RFC 3339 timestamp, "YYYY-MM-DD HH:MM:SS" (UTC) or an age like 30m, 6h, 2d
+        #[arg(long, value_parser = crate::scan::enhanced_log_discovery::parse_since)]
+        since: Option<DateTime<Utc>>,
+    },
+
     /// Analyze previously collected scan data
     Analyze {
         /// Path to the scanned data directory
         #[arg(value_name = "SCANNED_DATA")]
         scanned_data: PathBuf,
         
-        /// Report format
-        #[arg(short, long, value_enum, default_value = "terminal")]
-        report: ReportFormat,
-        
-        /// Output file path (optional for json, required for markdown, ignored for terminal)
-        #[arg(short, long)]
-        output: Option<PathBuf>,
+        #[command(flatten)]
+        report: ReportArgs,
         
-        /// Enable LLM debug logging to llmdbg.txt
-        #[arg(long)]
-        llmdbg: bool,
-        
-        /// LLM request timeout in seconds (default: 300)
-        #[arg(long, default_value = "300")]
-        llm_timeout: u64,
+        #[command(flatten)]
+        llm: LlmArgs,
+
+        /// Generate remediation scripts for high and critical findings and write them to this directory
+        #[arg(long, value_name = "DIR")]
+        export_scripts: Option<PathBuf>,
+
+        #[command(flatten)]
+        baseline: BaselineArgs,
+
+        /// Exit with code 2 when any finding is at or above this severity (default: never fail)
+        #[arg(long, value_enum, value_name = "SEVERITY")]
+        fail_on: Option<SeverityLevel>,
+
+        /// Run only this static analyzer (e.g. gc-pause or configuration-validator), even when an LLM is configured
+        #[arg(long, value_name = "NAME")]
+        only_analyzer: Option<String>,
     },
     
     
     
+    /// Watch a directory and re-analyze each new snapshot file (.snapshot or .tar archive) as it lands, updating the report
+    Watch {
+        /// Directory new snapshot files are written to
+        #[arg(long, value_name = "PATH")]
+        dir: PathBuf,
+
+        #[command(flatten)]
+        report: ReportArgs,
+
+        #[command(flatten)]
+        llm: LlmArgs,
+
+        /// Milliseconds a snapshot file must go without changes, and then keep the same size, before it is analyzed
+        #[arg(long, value_name = "MS", default_value_t = crate::cli::handlers::watch::DEFAULT_SETTLE_MS)]
+        settle_ms: u64,
+    },
+
+    /// Serve the HTML report of a snapshot over HTTP for viewing in a browser
+    Serve {
+        /// Scan directory, snapshot file (.snapshot or .tar archive) or JSON snapshot to report on
+        #[arg(long, value_name = "PATH")]
+        snapshot: PathBuf,
+
+        /// Port to listen on
+        #[arg(long, default_value_t = crate::cli::handlers::serve::DEFAULT_SERVE_PORT)]
+        port: u16,
+
+        /// Address to listen on; use 0.0.0.0 to make the report reachable from other hosts
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1")]
+        bind: std::net::IpAddr,
+    },
+
+    /// Browse findings interactively in the terminal
+    #[cfg(feature = "tui")]
+    Browse {
+        /// JSON report or snapshot file with findings, or a scan directory to analyze
+        #[arg(value_name = "SNAPSHOT")]
+        snapshot: PathBuf,
+    },
+
+    /// Redact an existing snapshot, e.g. one captured with --redact-level none, and write it as a new snapshot
+    Redact {
+        /// Scan directory, snapshot file (.snapshot or .tar archive) or JSON snapshot to redact
+        #[arg(long, value_name = "PATH")]
+        input: PathBuf,
+
+        /// Redacted snapshot to write: a .snapshot file, a .tar archive, or JSON for any other extension
+        #[arg(long, value_name = "PATH")]
+        output: PathBuf,
+
+        /// How much to scrub: none, standard (credentials and keys) or aggressive (also hostnames, IPs and sensitive topic names)
+        #[arg(long, value_enum, default_value = "standard")]
+        level: RedactLevel,
+    },
+
+    /// Print the JSON Schema of the JSON report or of a single finding
+    Schema {
+        /// Document the schema describes
+        #[arg(value_enum, default_value = "report")]
+        kind: SchemaKind,
+
+        /// Write the schema to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
     /// Display current KCPilot configuration
     Config,
 
@@ -77,7 +177,7 @@ pub enum Commands {
     
     /// Test SSH connectivity to brokers
     TestSsh {
-        /// SSH bastion alias (from ~/.ssh/config). If not provided, assumes running locally on bastion
+        /// SSH bastion alias (from ~/.ssh/config), or a comma-separated chain jump1,jump2,bastion reached through ProxyJump. If not provided, assumes running locally on bastion
         #[arg(short, long)]
         bastion: Option<String>,
     },
@@ -127,9 +227,525 @@ pub enum TaskCommand {
 }
 
 
+/// Which brokers `kcpilot scan` collects from
+#[derive(Args, Debug, Clone)]
+pub struct BrokerArgs {
+    /// Single broker hostname[:port] to discover cluster from. If not provided, uses hardcoded broker list
+    #[arg(long)]
+    pub broker: Option<String>,
+
+    /// Port used when --broker or a --broker-list line has none
+    #[arg(long, default_value_t = crate::scan::listeners::DEFAULT_BROKER_PORT)]
+    pub broker_port: u16,
+
+    /// File listing every broker as hostname[:port], one per line (# comments allowed), scanned without discovery
+    #[arg(long, value_name = "FILE", conflicts_with = "broker")]
+    pub broker_list: Option<PathBuf>,
+}
+
+/// What `kcpilot scan` collects and how much of it is redacted
+#[derive(Args, Debug, Clone)]
+pub struct CollectionArgs {
+    /// Only collect log entries newer than this: an RFC 3339 timestamp, "YYYY-MM-DD HH:MM:SS" (UTC) or an age like 30m, 6h, 2d
+    #[arg(long, value_parser = crate::scan::enhanced_log_discovery::parse_since)]
+    pub since: Option<DateTime<Utc>>,
+
+    /// How much to scrub from collector output: none, standard (credentials and keys) or aggressive (also hostnames, IPs and sensitive topic names)
+    #[arg(long, value_enum, default_value = "standard")]
+    pub redact_level: RedactLevel,
+
+    /// Only run these collectors (comma-separated): admin, metrics, bastion, system, configs, logs, data, network
+    #[arg(long, value_delimiter = ',', value_name = "COLLECTOR")]
+    pub only: Vec<ScanCollector>,
+
+    /// Trailing lines collected from each broker log when no --since window is given
+    #[arg(long, default_value_t = DEFAULT_LOG_LINES)]
+    pub log_lines: usize,
+
+    /// Megabytes of logs collected from each broker at most; server and controller logs are collected first
+    #[arg(long, default_value_t = DEFAULT_LOG_BUDGET_BYTES / (1024 * 1024))]
+    pub log_budget_mb: usize,
+
+    /// Kafka Connect REST URL (e.g. http://connect-1:8083) to collect connector and task status from
+    #[arg(long, value_name = "URL")]
+    pub connect_url: Option<String>,
+
+    /// Port of the brokers' Prometheus metrics endpoint (e.g. the JMX exporter's 7071) to scrape per-topic and JVM metrics from
+    #[arg(long, value_name = "PORT")]
+    pub metrics_port: Option<u16>,
+
+    /// Earlier scan directory to collect incrementally against: broker configs that still match its manifest hashes are copied forward instead of fetched
+    #[arg(long, value_name = "DIR")]
+    pub incremental_from: Option<PathBuf>,
+}
+
+/// How `kcpilot scan` reports on its progress and result
+#[derive(Args, Debug, Clone)]
+pub struct ScanDisplayArgs {
+    /// Do not show progress bars (they are drawn on stderr and hidden when it is not a terminal)
+    #[arg(long)]
+    pub no_progress: bool,
+
+    /// Output format: human-readable banners, or a JSON summary of the scan on stdout
+    #[arg(long, value_enum, default_value = "text")]
+    pub output_format: ScanOutputFormat,
+
+    /// Print banners in ASCII, without emoji or box drawing (the default when NO_COLOR is set or stdout is not a terminal)
+    #[arg(long)]
+    pub ascii: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ScanOutputFormat {
+    Text,
+    Json,
+}
+
+/// Format and destination of the analysis report
+#[derive(Args, Debug, Clone)]
+pub struct ReportArgs {
+    /// Report format
+    #[arg(short, long, value_enum, default_value = "terminal")]
+    pub report: ReportFormat,
+
+    /// Output file path or s3:// / gs:// object URL (optional for json, ndjson and es-bulk, defaults to a timestamped file for markdown, html and runbook, ignored for terminal)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Overwrite output files that kcpilot did not write (reports from earlier runs are always replaced)
+    #[arg(long)]
+    pub force: bool,
+
+    /// Replace broker hostnames and cluster names with stable pseudonyms (broker-1, cluster-a) so the report can be shared
+    #[arg(long)]
+    pub anonymize: bool,
+
+    /// Where to write the pseudonym-to-real-name mapping; keep it private
+    #[arg(long, value_name = "PATH", default_value = "kcpilot_anonymization_map.json", requires = "anonymize")]
+    pub anonymize_map: PathBuf,
+
+    /// Elasticsearch index the es-bulk report indexes findings into
+    #[arg(long, value_name = "INDEX", default_value = crate::report::esbulk::DEFAULT_ES_INDEX)]
+    pub es_index: String,
+
+    /// Print the terminal report in ASCII, without emoji or box drawing (the default when NO_COLOR is set or stdout is not a terminal)
+    #[arg(long)]
+    pub ascii: bool,
+
+    /// Only include findings of these categories in the report, comma-separated (default: all)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "CATEGORIES")]
+    pub categories: Vec<FindingCategory>,
+
+    /// With --report html, also write a page per topic (partitions, replication, configs) into a topics directory next to the report
+    #[arg(long)]
+    pub topic_pages: bool,
+
+    /// With --report html, write a page comparing this scan with an earlier scan directory or snapshot JSON instead: new, resolved and persistent findings and the health score change
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["anonymize", "topic_pages"])]
+    pub compare_with: Option<PathBuf>,
+
+    /// Open PagerDuty incidents for findings at or above this severity, when PAGERDUTY_ROUTING_KEY is set
+    #[arg(long, value_enum, value_name = "SEVERITY", default_value = "critical")]
+    pub pagerduty_severity: SeverityLevel,
+}
+
+impl ReportArgs {
+    /// Whether the report is printed to stdout, which then has to stay free of log lines
+    pub fn writes_to_stdout(&self) -> bool {
+        let to_stdout = |default: bool| self.output.as_ref().map_or(default, |path| path.as_os_str() == "-");
+        match self.report {
+            ReportFormat::Terminal => true,
+            ReportFormat::Json | ReportFormat::Ndjson | ReportFormat::EsBulk => to_stdout(true),
+            ReportFormat::Markdown | ReportFormat::Html | ReportFormat::Runbook => to_stdout(false),
+        }
+    }
+}
+
+/// Options of the LLM used for analysis
+#[derive(Args, Debug, Clone)]
+pub struct LlmArgs {
+    /// Enable LLM debug logging to llmdbg.txt
+    #[arg(long)]
+    pub llmdbg: bool,
+
+    /// LLM request timeout in seconds (default: 300)
+    #[arg(long, default_value = "300")]
+    pub llm_timeout: u64,
+
+    /// Have the LLM write the report's executive summary (markdown reports; falls back to the templated summary without an LLM)
+    #[arg(long)]
+    pub ai_summary: bool,
+
+    /// Number of analysis tasks sent to the LLM at the same time; lower it if the API rate-limits requests. --verbose streams responses only at 1
+    #[arg(long, default_value_t = crate::analysis::executor::DEFAULT_TASK_CONCURRENCY)]
+    pub llm_concurrency: usize,
+
+    /// Most estimated tokens per analysis prompt (default: the model's context window minus the response's max tokens)
+    #[arg(long, value_name = "TOKENS")]
+    pub max_prompt_tokens: Option<usize>,
+
+    /// Shorten the largest data sections of prompts over the token limit instead of failing those tasks
+    #[arg(long)]
+    pub truncate_prompts: bool,
+
+    /// Merge AI findings describing the same issue, compared with the provider's embeddings (identical titles only when embeddings are unavailable)
+    #[arg(long)]
+    pub group_similar: bool,
+
+    /// Cosine similarity at or above which --group-similar merges two findings
+    #[arg(long, default_value_t = crate::analysis::grouping::DEFAULT_SIMILARITY_THRESHOLD, requires = "group_similar")]
+    pub similarity_threshold: f32,
+}
+
+/// Options comparing the cluster against a baseline configuration or an earlier scan
+#[derive(Args, Debug, Clone, Default)]
+pub struct BaselineArgs {
+    /// Golden server.properties to compare every broker's configuration against
+    #[arg(long, value_name = "PATH")]
+    pub baseline: Option<PathBuf>,
+
+    /// Comma-separated keys expected to differ per broker, in addition to broker.id, node.id, listeners, advertised.listeners and broker.rack
+    #[arg(long, value_delimiter = ',', requires = "baseline")]
+    pub baseline_ignore: Vec<String>,
+
+    /// Earlier scan directory or snapshot JSON of the same cluster, used to project disk growth
+    #[arg(long, value_name = "PATH")]
+    pub previous: Option<PathBuf>,
+}
+
 #[derive(Debug, Clone, ValueEnum)]
 pub enum ReportFormat {
     Terminal,
     Json,
+    /// One JSON object per line, for log pipelines
+    Ndjson,
+    /// Elasticsearch `_bulk` API body: an index action line before each finding
+    EsBulk,
     Markdown,
-}
\ No newline at end of file
+    /// Standalone HTML page
+    Html,
+    /// Markdown checklist of the remediation steps of all findings, most severe first
+    Runbook,
+}
+
+/// Document described by `kcpilot schema`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SchemaKind {
+    /// The whole `analyze --report json` output
+    Report,
+    /// One finding, as in the report's `findings` array
+    Finding,
+}
+
+/// Redaction level as given on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RedactLevel {
+    None,
+    Standard,
+    Aggressive,
+}
+
+impl From<RedactLevel> for RedactionLevel {
+    fn from(level: RedactLevel) -> Self {
+        match level {
+            RedactLevel::None => RedactionLevel::None,
+            RedactLevel::Standard => RedactionLevel::Standard,
+            RedactLevel::Aggressive => RedactionLevel::Aggressive,
+        }
+    }
+}
+
+/// Finding severity as given on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SeverityLevel {
+    Critical,
+    High,
+    Medium,
+    Low,
+    Info,
+}
+
+impl From<SeverityLevel> for Severity {
+    fn from(level: SeverityLevel) -> Self {
+        match level {
+            SeverityLevel::Critical => Severity::Critical,
+            SeverityLevel::High => Severity::High,
+            SeverityLevel::Medium => Severity::Medium,
+            SeverityLevel::Low => Severity::Low,
+            SeverityLevel::Info => Severity::Info,
+        }
+    }
+}
+
+/// Finding category as given on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FindingCategory {
+    ClusterHygiene,
+    Performance,
+    Configuration,
+    Security,
+    Availability,
+    Client,
+    Capacity,
+    Other,
+}
+
+impl From<FindingCategory> for Category {
+    fn from(category: FindingCategory) -> Self {
+        match category {
+            FindingCategory::ClusterHygiene => Category::ClusterHygiene,
+            FindingCategory::Performance => Category::Performance,
+            FindingCategory::Configuration => Category::Configuration,
+            FindingCategory::Security => Category::Security,
+            FindingCategory::Availability => Category::Availability,
+            FindingCategory::Client => Category::Client,
+            FindingCategory::Capacity => Category::Capacity,
+            FindingCategory::Other => Category::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_no_progress_flag() {
+        let cli = Cli::try_parse_from(["kcpilot", "scan", "--broker", "kafka-1:9092", "--no-progress"]).unwrap();
+        assert!(matches!(cli.command, Commands::Scan { display: ScanDisplayArgs { no_progress: true, .. }, .. }));
+
+        let cli = Cli::try_parse_from(["kcpilot", "scan"]).unwrap();
+        assert!(matches!(cli.command, Commands::Scan { display: ScanDisplayArgs { no_progress: false, .. }, .. }));
+    }
+
+    #[test]
+    fn test_scan_output_format_flag() {
+        let cli = Cli::try_parse_from(["kcpilot", "scan", "--output-format", "json"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Scan { display: ScanDisplayArgs { output_format: ScanOutputFormat::Json, .. }, .. }
+        ));
+
+        let cli = Cli::try_parse_from(["kcpilot", "scan"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Scan { display: ScanDisplayArgs { output_format: ScanOutputFormat::Text, .. }, .. }
+        ));
+    }
+
+    #[test]
+    fn test_scan_broker_port_flag() {
+        let cli = Cli::try_parse_from(["kcpilot", "scan", "--broker", "kafka-1", "--broker-port", "19092"]).unwrap();
+        assert!(matches!(cli.command, Commands::Scan { brokers: BrokerArgs { broker_port: 19092, .. }, .. }));
+
+        let cli = Cli::try_parse_from(["kcpilot", "scan"]).unwrap();
+        assert!(matches!(cli.command, Commands::Scan { brokers: BrokerArgs { broker_port: 9092, .. }, .. }));
+    }
+
+    #[test]
+    fn test_scan_optional_collector_flags() {
+        let cli = Cli::try_parse_from(["kcpilot", "scan", "--connect-url", "http://connect-1:8083"]).unwrap();
+        match cli.command {
+            Commands::Scan { collection, .. } => assert_eq!(collection.connect_url.as_deref(), Some("http://connect-1:8083")),
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["kcpilot", "scan", "--metrics-port", "7071"]).unwrap();
+        match cli.command {
+            Commands::Scan { collection, .. } => assert_eq!(collection.metrics_port, Some(7071)),
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_scan_broker_list_flag() {
+        let cli = Cli::try_parse_from(["kcpilot", "scan", "--broker-list", "brokers.txt"]).unwrap();
+        match cli.command {
+            Commands::Scan { brokers, .. } => assert_eq!(brokers.broker_list, Some(PathBuf::from("brokers.txt"))),
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        assert!(Cli::try_parse_from(["kcpilot", "scan", "--broker", "kafka-1", "--broker-list", "brokers.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_scan_redact_level_flag() {
+        let cli = Cli::try_parse_from(["kcpilot", "scan", "--redact-level", "aggressive"]).unwrap();
+        match cli.command {
+            Commands::Scan { collection, .. } => {
+                assert_eq!(RedactionLevel::from(collection.redact_level), RedactionLevel::Aggressive);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["kcpilot", "scan"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Scan { collection: CollectionArgs { redact_level: RedactLevel::Standard, .. }, .. }
+        ));
+        assert!(Cli::try_parse_from(["kcpilot", "scan", "--redact-level", "paranoid"]).is_err());
+    }
+
+    #[test]
+    fn test_redact_command_args() {
+        let cli = Cli::try_parse_from([
+            "kcpilot", "redact", "--input", "scan.snapshot", "--output", "shared.snapshot", "--level", "aggressive",
+        ]).unwrap();
+        match cli.command {
+            Commands::Redact { input, output, level } => {
+                assert_eq!(input, PathBuf::from("scan.snapshot"));
+                assert_eq!(output, PathBuf::from("shared.snapshot"));
+                assert_eq!(RedactionLevel::from(level), RedactionLevel::Aggressive);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["kcpilot", "redact", "--input", "scan", "--output", "shared.json"]).unwrap();
+        assert!(matches!(cli.command, Commands::Redact { level: RedactLevel::Standard, .. }));
+        assert!(Cli::try_parse_from(["kcpilot", "redact", "--input", "scan"]).is_err());
+    }
+
+    #[test]
+    fn test_scan_only_collectors() {
+        let cli = Cli::try_parse_from(["kcpilot", "scan", "--only", "logs,configs"]).unwrap();
+        match cli.command {
+            Commands::Scan { collection, .. } => {
+                assert_eq!(collection.only, vec![ScanCollector::Logs, ScanCollector::Configs]);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let err = Cli::try_parse_from(["kcpilot", "scan", "--only", "logs,jmx"]).unwrap_err().to_string();
+        assert!(err.contains("unknown collector 'jmx'; available collectors: admin, metrics, bastion"), "{}", err);
+    }
+
+    #[test]
+    fn test_scan_log_budget_flags() {
+        let cli = Cli::try_parse_from(["kcpilot", "scan", "--log-lines", "2000", "--log-budget-mb", "5"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Scan { collection: CollectionArgs { log_lines: 2000, log_budget_mb: 5, .. }, .. }
+        ));
+
+        let cli = Cli::try_parse_from(["kcpilot", "scan"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Scan { collection: CollectionArgs { log_lines: 500, log_budget_mb: 50, .. }, .. }
+        ));
+    }
+
+    #[test]
+    fn test_schema_command() {
+        let cli = Cli::try_parse_from(["kcpilot", "schema"]).unwrap();
+        assert!(matches!(cli.command, Commands::Schema { kind: SchemaKind::Report, output: None }));
+
+        let cli = Cli::try_parse_from(["kcpilot", "schema", "finding", "-o", "finding.schema.json"]).unwrap();
+        assert!(matches!(cli.command, Commands::Schema { kind: SchemaKind::Finding, output: Some(_) }));
+    }
+
+    #[test]
+    fn test_analyze_fail_on_flag() {
+        let cli = Cli::try_parse_from(["kcpilot", "analyze", "./scan", "--fail-on", "high", "--llm-timeout", "60"]).unwrap();
+        match cli.command {
+            Commands::Analyze { fail_on, llm, .. } => {
+                assert_eq!(fail_on, Some(SeverityLevel::High));
+                assert_eq!(llm.llm_timeout, 60);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["kcpilot", "analyze", "./scan"]).unwrap();
+        assert!(matches!(cli.command, Commands::Analyze { fail_on: None, .. }));
+        assert!(Cli::try_parse_from(["kcpilot", "analyze", "./scan", "--fail-on", "severe"]).is_err());
+    }
+
+    #[test]
+    fn test_analyze_anonymize_flags() {
+        let cli = Cli::try_parse_from(["kcpilot", "analyze", "./scan", "-r", "json", "--anonymize"]).unwrap();
+        match cli.command {
+            Commands::Analyze { report, .. } => {
+                assert!(matches!(report.report, ReportFormat::Json));
+                assert!(report.anonymize);
+                assert_eq!(report.anonymize_map, PathBuf::from("kcpilot_anonymization_map.json"));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        assert!(Cli::try_parse_from(["kcpilot", "analyze", "./scan", "--anonymize-map", "map.json"]).is_err());
+    }
+
+    #[test]
+    fn test_report_writes_to_stdout() {
+        let report = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["kcpilot", "analyze", "./scan"], args].concat()).unwrap();
+            match cli.command {
+                Commands::Analyze { report, .. } => report,
+                other => panic!("unexpected command: {:?}", other),
+            }
+        };
+
+        assert!(report(&[]).writes_to_stdout());
+        assert!(report(&["-r", "ndjson"]).writes_to_stdout());
+        assert!(report(&["-r", "json", "-o", "-"]).writes_to_stdout());
+        assert!(!report(&["-r", "json", "-o", "report.json"]).writes_to_stdout());
+        assert!(!report(&["-r", "markdown"]).writes_to_stdout());
+    }
+
+    #[test]
+    fn test_analyze_topic_pages_flag() {
+        let cli = Cli::try_parse_from(["kcpilot", "analyze", "./scan", "-r", "html", "--topic-pages"]).unwrap();
+        assert!(matches!(cli.command, Commands::Analyze { report: ReportArgs { topic_pages: true, .. }, .. }));
+        let cli = Cli::try_parse_from(["kcpilot", "analyze", "./scan", "-r", "html"]).unwrap();
+        assert!(matches!(cli.command, Commands::Analyze { report: ReportArgs { topic_pages: false, .. }, .. }));
+    }
+
+    #[test]
+    fn test_analyze_pagerduty_severity_flag() {
+        let cli = Cli::try_parse_from(["kcpilot", "analyze", "./scan"]).unwrap();
+        assert!(matches!(cli.command, Commands::Analyze { report: ReportArgs { pagerduty_severity: SeverityLevel::Critical, .. }, .. }));
+
+        let cli = Cli::try_parse_from(["kcpilot", "watch", "--dir", "./incoming", "--pagerduty-severity", "high"]).unwrap();
+        assert!(matches!(cli.command, Commands::Watch { report: ReportArgs { pagerduty_severity: SeverityLevel::High, .. }, .. }));
+        assert!(Cli::try_parse_from(["kcpilot", "analyze", "./scan", "--pagerduty-severity", "urgent"]).is_err());
+    }
+
+    #[test]
+    fn test_analyze_compare_with_flag() {
+        let cli = Cli::try_parse_from(["kcpilot", "analyze", "./scan", "-r", "html", "--compare-with", "./old-scan"]).unwrap();
+        assert!(matches!(cli.command, Commands::Analyze { report: ReportArgs { compare_with: Some(path), .. }, .. } if path == std::path::Path::new("./old-scan")));
+        assert!(Cli::try_parse_from(["kcpilot", "analyze", "./scan", "-r", "html", "--compare-with", "old", "--anonymize"]).is_err());
+    }
+
+    #[test]
+    fn test_analyze_force_flag() {
+        let cli = Cli::try_parse_from(["kcpilot", "analyze", "./scan", "-r", "markdown", "-o", "report.md", "--force"]).unwrap();
+        assert!(matches!(cli.command, Commands::Analyze { report: ReportArgs { force: true, .. }, .. }));
+
+        let cli = Cli::try_parse_from(["kcpilot", "analyze", "./scan"]).unwrap();
+        assert!(matches!(cli.command, Commands::Analyze { report: ReportArgs { force: false, .. }, .. }));
+    }
+
+    #[test]
+    fn test_analyze_categories_flag() {
+        let cli = Cli::try_parse_from(["kcpilot", "analyze", "./scan", "--categories", "security,cluster-hygiene"]).unwrap();
+        match cli.command {
+            Commands::Analyze { report, .. } => {
+                assert_eq!(report.categories, vec![FindingCategory::Security, FindingCategory::ClusterHygiene]);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["kcpilot", "analyze", "./scan"]).unwrap();
+        assert!(matches!(cli.command, Commands::Analyze { report: ReportArgs { categories, .. }, .. } if categories.is_empty()));
+        assert!(Cli::try_parse_from(["kcpilot", "analyze", "./scan", "--categories", "security,latency"]).is_err());
+    }
+
+    #[test]
+    fn test_similarity_threshold_requires_group_similar() {
+        let cli = Cli::try_parse_from(["kcpilot", "analyze", "./scan", "--group-similar", "--similarity-threshold", "0.85"]).unwrap();
+        assert!(matches!(cli.command, Commands::Analyze { llm: LlmArgs { group_similar: true, similarity_threshold, .. }, .. } if similarity_threshold == 0.85));
+
+        assert!(Cli::try_parse_from(["kcpilot", "analyze", "./scan", "--group-similar"]).is_ok());
+        assert!(Cli::try_parse_from(["kcpilot", "analyze", "./scan", "--similarity-threshold", "0.85"]).is_err());
+    }
+}
@@ -1,28 +1,141 @@
 use anyhow::Result;
-use crate::analyzers::{AnalyzerRegistry, config_validator::ConfigValidator};
-use crate::analysis::AiExecutor;
+use crate::analyzers::{Analyzer, AnalyzerRegistry, baseline::BaselineConfigAnalyzer, config_validator::ConfigValidator, kraft_quorum::KraftQuorumAnalyzer, topic_naming::TopicNamingAnalyzer, version_consistency::VersionConsistencyAnalyzer, hot_topics::HotTopicAnalyzer, disk_growth::DiskGrowthAnalyzer, custom_rules::CustomRuleAnalyzer, rack_awareness::RackAwarenessAnalyzer, security_protocol::SecurityProtocolAnalyzer, gc_pauses::GcPauseAnalyzer, metadata_version::MetadataVersionAnalyzer, retention_capacity::RetentionCapacityAnalyzer, advertised_listeners::AdvertisedListenersAnalyzer, connections::ConnectionAnalyzer, tls_expiry::TlsExpiryAnalyzer, file_descriptors::FileDescriptorAnalyzer, leader_elections::LeaderElectionAnalyzer, inter_broker_listener::InterBrokerListenerAnalyzer, topic_skew::TopicSkewAnalyzer, orphaned_topics::OrphanedTopicsAnalyzer, broker_restarts::BrokerRestartAnalyzer, clock_skew::ClockSkewAnalyzer, rules::RuleAnalyzer, severity_overrides::SeverityOverrides};
+use crate::analysis::{AiExecutor, SeverityKeywords};
+use crate::cli::commands::{BaselineArgs, LlmArgs, ReportArgs};
 use crate::cli::utils::load_snapshot_from_directory;
-use crate::snapshot::format::Snapshot;
+use crate::llm::{LlmService, UsageReport};
+use crate::snapshot::format::{Category, Evidence, Finding, MetricEvidence, Severity, Snapshot};
+use crate::snapshot::{is_snapshot_file, SnapshotManager};
 use crate::report::terminal::TerminalReporter;
 use crate::report::markdown::MarkdownReporter;
+use crate::report::html::HtmlReporter;
+use crate::report::runbook::RunbookReporter;
 use crate::report::json::JsonReporter;
+use crate::report::ndjson::NdjsonReporter;
+use crate::report::esbulk::EsBulkReporter;
+use crate::report::jira::JiraReporter;
+use crate::report::pagerduty::{PagerDutyReporter, PAGERDUTY_ROUTING_KEY_ENV};
+use crate::report::scripts::export_remediation_scripts;
+use crate::report::scoring::HealthScoreMethod;
+use crate::report::symbols;
+use crate::report::{filter_by_category, Anonymizer};
+use crate::output::StagedOutput;
+use chrono::{DateTime, Duration, Utc};
 use std::fs;
-use std::path::PathBuf;
-use tracing::{info, warn};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
 
+/// Exit code when a finding is at or above the `--fail-on` severity
+pub const FINDINGS_EXIT_CODE: u8 = 2;
+
+/// Exit code for the findings of a run: `FINDINGS_EXIT_CODE` when any finding is at or above
+/// `fail_on`, 0 otherwise or when no threshold is set
+pub fn findings_exit_code(findings: &[Finding], fail_on: Option<Severity>) -> u8 {
+    match fail_on {
+        Some(threshold) if findings.iter().any(|f| f.severity <= threshold) => FINDINGS_EXIT_CODE,
+        _ => 0,
+    }
+}
+
+/// Environment variable with the age in days above which a snapshot is reported as stale
+pub const SNAPSHOT_MAX_AGE_DAYS_ENV: &str = "SNAPSHOT_MAX_AGE_DAYS";
+
+/// Snapshot age above which findings may no longer reflect the cluster
+pub const DEFAULT_SNAPSHOT_MAX_AGE_DAYS: f64 = 7.0;
+
+/// Maximum snapshot age from `SNAPSHOT_MAX_AGE_DAYS`, or the default
+fn snapshot_max_age() -> Duration {
+    let days = std::env::var(SNAPSHOT_MAX_AGE_DAYS_ENV).ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|days| *days > 0.0)
+        .unwrap_or(DEFAULT_SNAPSHOT_MAX_AGE_DAYS);
+    Duration::seconds((days * 86_400.0) as i64)
+}
+
+/// Info finding noting the age of a snapshot collected more than `max_age` before `now`
+pub(crate) fn stale_snapshot_finding(snapshot: &Snapshot, now: DateTime<Utc>, max_age: Duration) -> Option<Finding> {
+    let age = now - snapshot.timestamp;
+    if age <= max_age {
+        return None;
+    }
+
+    let age_days = age.num_seconds() as f64 / 86_400.0;
+    let max_age_days = max_age.num_seconds() as f64 / 86_400.0;
+    Some(Finding {
+        id: "STALE-001".to_string(),
+        severity: Severity::Info,
+        category: Category::ClusterHygiene,
+        title: format!("Snapshot is {:.0} day(s) old", age_days),
+        description: format!(
+            "The data was collected at {}, {:.1} day(s) before this analysis, which is more than the {:.0} day(s) allowed by {}.",
+            snapshot.timestamp.format("%Y-%m-%d %H:%M:%S UTC"), age_days, max_age_days, SNAPSHOT_MAX_AGE_DAYS_ENV
+        ),
+        impact: "Findings describe the cluster as it was when scanned and may no longer apply; re-scan it for current results".to_string(),
+        evidence: Evidence {
+            metrics: vec![MetricEvidence {
+                name: "snapshot_age_days".to_string(),
+                value: age_days,
+                threshold: Some(max_age_days),
+                unit: Some("days".to_string()),
+                source: "snapshot".to_string(),
+                timestamp: now,
+            }],
+            logs: Vec::new(),
+            configs: Vec::new(),
+            raw_data: None,
+            timeline: Vec::new(),
+        },
+        ..Default::default()
+    })
+}
+
+/// Analyze scanned data and report the findings, returning the process exit code for `fail_on`
 pub async fn handle_analyze_command(
     scanned_data: PathBuf,
-    report: crate::cli::commands::ReportFormat,
-    output: Option<PathBuf>,
-    llmdbg: bool,
-    llm_timeout: u64,
-) -> Result<()> {
+    report_args: ReportArgs,
+    llm: LlmArgs,
+    export_scripts: Option<PathBuf>,
+    baseline: BaselineArgs,
+    fail_on: Option<Severity>,
+    only_analyzer: Option<String>,
+) -> Result<u8> {
     info!("Starting analysis of scanned data: {}", scanned_data.display());
 
+    let only_analyzer = only_analyzer.as_deref().map(select_analyzer).transpose()?;
+
+    let disk_growth = baseline.previous
+        .map(|path| load_earlier_snapshot(&path))
+        .transpose()?
+        .map(DiskGrowthAnalyzer::from_env);
+
+    let baseline = baseline.baseline
+        .map(|path| BaselineConfigAnalyzer::from_file(&path))
+        .transpose()?
+        .map(|analyzer| analyzer.with_ignored_keys(baseline.baseline_ignore));
+
+    let custom_rules = CustomRuleAnalyzer::from_env()?;
+    if let Some(rules) = &custom_rules {
+        info!("Loaded {} custom rule(s)", rules.len());
+    }
+
+    let severity_keywords = SeverityKeywords::from_env()?.unwrap_or_default();
+    if !severity_keywords.is_empty() {
+        info!("Loaded {} severity keyword(s)", severity_keywords.len());
+    }
+
+    let severity_overrides = SeverityOverrides::from_env()?.unwrap_or_default();
+    if !severity_overrides.is_empty() {
+        info!("Loaded {} severity override(s)", severity_overrides.len());
+    }
+
     // Load snapshot data
     let snapshot_data = if scanned_data.is_dir() {
         // Load from scan directory
         load_snapshot_from_directory(&scanned_data)?
+    } else if is_snapshot_file(&scanned_data) {
+        // Load from a saved snapshot or snapshot archive
+        info!("\n📦 Loading snapshot file: {}", scanned_data.display());
+        SnapshotManager::new().load_file(&scanned_data)?
     } else {
         // Load from JSON file
         info!("\n📄 Loading snapshot from JSON file: {}", scanned_data.display());
@@ -93,34 +206,83 @@ pub async fn handle_analyze_command(
     // Use AI-only analysis
     info!("🤖 Using AI-powered analysis...");
 
-    let findings = if let Ok(llm_service) = crate::llm::LlmService::from_env_with_options(llmdbg, llm_timeout) {
-        info!("✓ AI executor initialized");
-        if llm_timeout != 300 {
-            info!("  Using custom timeout: {} seconds", llm_timeout);
-        }
+    // In verbose mode, show the LLM response as it is generated. Responses of tasks running
+    // at the same time would interleave on stderr, so only when they run one at a time.
+    let stream_output = tracing::enabled!(tracing::Level::DEBUG) && llm.llm_concurrency <= 1;
+    if tracing::enabled!(tracing::Level::DEBUG) && !stream_output {
+        debug!("Not streaming LLM responses while {} tasks run at the same time; pass --llm-concurrency 1 to see them", llm.llm_concurrency);
+    }
+    let llm_service = crate::llm::LlmService::from_env_with_options(llm.llmdbg, llm.llm_timeout)
+        .ok()
+        .map(|service| service.with_stream_output(stream_output));
+    if llm_service.is_some() && llm.llm_timeout != 300 {
+        info!("  Using custom timeout: {} seconds", llm.llm_timeout);
+    }
+    let options = AnalysisOptions {
+        generate_scripts: export_scripts.is_some(),
+        baseline,
+        disk_growth,
+        custom_rules,
+        severity_keywords,
+        severity_overrides,
+        ai_summary: llm.ai_summary,
+        llm_concurrency: llm.llm_concurrency,
+        max_prompt_tokens: llm.max_prompt_tokens,
+        truncate_prompts: llm.truncate_prompts,
+        group_similar: llm.group_similar.then_some(llm.similarity_threshold),
+        only_analyzer,
+    };
+    let AnalysisRun { mut findings, usage: llm_usage, summary } = run_analysis(&snapshot_data, llm_service, &options).await?;
 
-        let mut executor = AiExecutor::new(llm_service);
-        info!("  Loading analysis tasks from 'analysis_tasks' directory...");
+    if let Some(stale) = stale_snapshot_finding(&snapshot_data, Utc::now(), snapshot_max_age()) {
+        warn!("⚠ WARNING: {}", stale.title);
+        warn!("  {}", stale.description);
+        warn!("  Findings may no longer apply; re-scan the cluster for current results.");
+        findings.push(stale);
+    }
 
-        executor.analyze_all(&snapshot_data).await?
-    } else {
-        warn!("AI analysis not available - LLM API key not configured");
-        warn!("Please set OPENAI_API_KEY or LLM_API_KEY environment variable");
+    info!("Analysis complete. Found {} findings", findings.len());
 
-        // Fall back to basic static analysis if no LLM available
-        info!("Falling back to static configuration validator...");
-        let mut registry = AnalyzerRegistry::new();
-        registry.register(Box::new(ConfigValidator::new()));
-        registry.analyze_all(&snapshot_data).await?
+    // Everything from here on may leave the machine, so anonymize before reporting
+    let ReportArgs { report, output, force, anonymize, anonymize_map, es_index, ascii, categories, topic_pages, compare_with, pagerduty_severity } = report_args;
+    let pagerduty_severity = Severity::from(pagerduty_severity);
+    let (snapshot_data, findings, summary) = if anonymize {
+        let anonymizer = Anonymizer::from_snapshot(&snapshot_data);
+        anonymizer.save_map(&anonymize_map)?;
+        info!(
+            "🕶️  Anonymized {} hostname(s) and {} cluster name(s); mapping saved to {}",
+            anonymizer.map().hosts.len(),
+            anonymizer.map().clusters.len(),
+            anonymize_map.display()
+        );
+        (
+            anonymizer.anonymize_snapshot(&snapshot_data)?,
+            anonymizer.anonymize_findings(&findings)?,
+            summary.map(|summary| anonymizer.anonymize_text(&summary)),
+        )
+    } else {
+        (snapshot_data, findings, summary)
     };
 
-    info!("Analysis complete. Found {} findings", findings.len());
+    if compare_with.is_some() && !matches!(report, crate::cli::commands::ReportFormat::Html) {
+        warn!("--compare-with only applies to HTML reports, ignoring it");
+    }
+
+    // Reports may be scoped to some categories; alerts, scripts and the exit code still cover every finding
+    let categories: Vec<Category> = categories.into_iter().map(Category::from).collect();
+    let report_findings = filter_by_category(&findings, &categories);
+    if report_findings.len() < findings.len() {
+        info!("Reporting {} of {} findings in the selected categories", report_findings.len(), findings.len());
+    }
 
     // Generate report based on format
+    let score_method = HealthScoreMethod::from_env();
     match report {
         crate::cli::commands::ReportFormat::Terminal => {
-            let reporter = TerminalReporter::new();
-            reporter.report(&snapshot_data, &findings)?;
+            let reporter = TerminalReporter::new()
+                .with_score_method(score_method)
+                .with_ascii(symbols::detect_ascii(ascii));
+            reporter.report(&snapshot_data, &report_findings)?;
         }
         crate::cli::commands::ReportFormat::Json => {
             let output_path = output.unwrap_or_else(|| {
@@ -128,17 +290,55 @@ pub async fn handle_analyze_command(
                 PathBuf::from("-")
             });
             
-            if output_path != PathBuf::from("-") {
+            if output_path.as_os_str() != "-" {
                 info!("Generating JSON report: {}", output_path.display());
             }
             
-            let reporter = JsonReporter::new();
-            reporter.save_report(&snapshot_data, &findings, &output_path)?;
+            let reporter = JsonReporter::new()
+                .with_llm_usage(llm_usage.clone())
+                .with_score_method(score_method)
+                .with_force(force);
+            let staged = StagedOutput::new(output_path.clone());
+            reporter.save_report(&snapshot_data, &report_findings, staged.local_path())?;
+            staged.finish_file().await?;
             
-            if output_path != PathBuf::from("-") {
+            if output_path.as_os_str() != "-" {
                 info!("✅ JSON report saved to: {}", output_path.display());
             }
         }
+        crate::cli::commands::ReportFormat::Ndjson => {
+            let output_path = output.unwrap_or_else(|| PathBuf::from("-"));
+
+            if output_path.as_os_str() != "-" {
+                info!("Generating NDJSON report: {}", output_path.display());
+            }
+
+            let staged = StagedOutput::new(output_path.clone());
+            NdjsonReporter::new().with_force(force).save_report(&snapshot_data, &report_findings, staged.local_path())?;
+            staged.finish_file().await?;
+
+            if output_path.as_os_str() != "-" {
+                info!("✅ NDJSON report saved to: {}", output_path.display());
+            }
+        }
+        crate::cli::commands::ReportFormat::EsBulk => {
+            let output_path = output.unwrap_or_else(|| PathBuf::from("-"));
+
+            if output_path.as_os_str() != "-" {
+                info!("Generating Elasticsearch bulk report: {}", output_path.display());
+            }
+
+            let staged = StagedOutput::new(output_path.clone());
+            EsBulkReporter::new()
+                .with_index(es_index)
+                .with_force(force)
+                .save_report(&snapshot_data, &report_findings, staged.local_path())?;
+            staged.finish_file().await?;
+
+            if output_path.as_os_str() != "-" {
+                info!("✅ Elasticsearch bulk report saved to: {}", output_path.display());
+            }
+        }
         crate::cli::commands::ReportFormat::Markdown => {
             let output_path = output.unwrap_or_else(|| {
                 // Generate default filename with timestamp
@@ -147,11 +347,406 @@ pub async fn handle_analyze_command(
             });
 
             info!("Generating markdown report: {}", output_path.display());
-            let reporter = MarkdownReporter::new();
-            reporter.save_report(&snapshot_data, &findings, &output_path)?;
+            let reporter = MarkdownReporter::new()
+                .with_score_method(score_method)
+                .with_executive_summary(summary)
+                .with_force(force);
+            let staged = StagedOutput::new(output_path.clone());
+            reporter.save_report(&snapshot_data, &report_findings, staged.local_path())?;
+            staged.finish_file().await?;
+            info!("✅ Report saved to: {}", output_path.display());
+        }
+        crate::cli::commands::ReportFormat::Html => {
+            let output_path = output.unwrap_or_else(|| {
+                let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+                PathBuf::from(format!("kafka_report_{}.html", timestamp))
+            });
+
+            info!("Generating HTML report: {}", output_path.display());
+            let staged = StagedOutput::new(output_path.clone());
+            if topic_pages && staged.is_remote() {
+                warn!("Topic pages are only written next to local reports, skipping them for {}", output_path.display());
+            }
+            let reporter = HtmlReporter::new()
+                .with_score_method(score_method)
+                .with_executive_summary(summary)
+                .with_force(force)
+                .with_topic_pages(topic_pages && !staged.is_remote());
+            if let Some(previous) = &compare_with {
+                info!("Comparing with the earlier scan {}", previous.display());
+                let mut old = load_earlier_snapshot(previous)?;
+                if old.findings.is_empty() {
+                    // Scan directories carry no findings, so analyze the earlier scan statically
+                    let mut registry = AnalyzerRegistry::new().with_severity_overrides(options.severity_overrides.clone());
+                    for analyzer in static_analyzers() {
+                        registry.register(analyzer);
+                    }
+                    old.findings = registry.analyze_all(&old).await?;
+                }
+                old.findings = filter_by_category(&old.findings, &categories);
+                let mut new = snapshot_data.clone();
+                new.findings = report_findings.clone();
+                reporter.save_comparison(&old, &new, staged.local_path())?;
+            } else {
+                reporter.save_report(&snapshot_data, &report_findings, staged.local_path())?;
+            }
+            staged.finish_file().await?;
             info!("✅ Report saved to: {}", output_path.display());
         }
+        crate::cli::commands::ReportFormat::Runbook => {
+            let output_path = output.unwrap_or_else(|| {
+                let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+                PathBuf::from(format!("kafka_runbook_{}.md", timestamp))
+            });
+
+            info!("Generating remediation runbook: {}", output_path.display());
+            let staged = StagedOutput::new(output_path.clone());
+            RunbookReporter::new().with_force(force).save_report(&snapshot_data, &report_findings, staged.local_path())?;
+            staged.finish_file().await?;
+            info!("✅ Runbook saved to: {}", output_path.display());
+        }
+    }
+
+    if let Some(scripts_dir) = &export_scripts {
+        export_remediation_scripts(&findings, scripts_dir, force)?;
+    }
+
+    if let Ok(routing_key) = std::env::var(PAGERDUTY_ROUTING_KEY_ENV) {
+        let pagerduty = PagerDutyReporter::new(routing_key).with_min_severity(pagerduty_severity);
+        match pagerduty.trigger(&findings).await {
+            Ok(0) => info!("No findings at or above {:?} severity, no PagerDuty incident triggered", pagerduty_severity),
+            Ok(sent) => info!("🚨 Triggered {} PagerDuty incident(s)", sent),
+            Err(e) => warn!("Failed to trigger PagerDuty incidents: {}", e),
+        }
+    }
+
+    if let Some(jira) = JiraReporter::from_env() {
+        match jira.create_issues(&findings, Severity::High).await {
+            Ok(keys) if keys.is_empty() => info!("No new high or critical findings, no Jira issue created"),
+            Ok(keys) => info!("🎫 Created Jira issue(s): {}", keys.join(", ")),
+            Err(e) => warn!("Failed to create Jira issues: {}", e),
+        }
     }
 
-    Ok(())
-}
\ No newline at end of file
+    if let Some(usage) = llm_usage {
+        info!("💰 LLM usage: {}", usage);
+    }
+
+    let exit_code = findings_exit_code(&findings, fail_on);
+    if exit_code != 0 {
+        warn!("Findings at or above {:?} severity, exiting with code {}", fail_on.unwrap_or(Severity::Info), exit_code);
+    }
+
+    Ok(exit_code)
+}
+
+/// Optional steps of an analysis run
+#[derive(Default)]
+pub(crate) struct AnalysisOptions {
+    /// Ask the LLM for remediation scripts of severe findings
+    pub generate_scripts: bool,
+    /// Compare broker configs against a baseline, with both AI and static analysis
+    pub baseline: Option<BaselineConfigAnalyzer>,
+    /// Project disk growth against an earlier snapshot
+    pub disk_growth: Option<DiskGrowthAnalyzer>,
+    /// User-defined threshold rules, evaluated with both AI and static analysis
+    pub custom_rules: Option<CustomRuleAnalyzer>,
+    /// Deployment-wide keywords setting the severity of AI findings
+    pub severity_keywords: SeverityKeywords,
+    /// Severities set by rule name or finding id prefix, replacing those of every finding
+    pub severity_overrides: SeverityOverrides,
+    /// Ask the LLM for an executive summary of all findings
+    pub ai_summary: bool,
+    /// Analysis tasks run at the same time
+    pub llm_concurrency: usize,
+    /// Most estimated tokens per analysis prompt, instead of the model's context window
+    pub max_prompt_tokens: Option<usize>,
+    /// Shorten oversized prompts instead of failing their tasks
+    pub truncate_prompts: bool,
+    /// Merge AI findings whose embeddings are at least this similar
+    pub group_similar: Option<f32>,
+    /// Run only this static analyzer, instead of the AI tasks or all static analyzers
+    pub only_analyzer: Option<Box<dyn Analyzer>>,
+}
+
+/// An earlier scan directory or snapshot JSON of the same cluster
+fn load_earlier_snapshot(path: &Path) -> Result<Snapshot> {
+    if path.is_dir() {
+        load_snapshot_from_directory(path)
+    } else {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// Static analyzers run when no LLM is configured
+fn static_analyzers() -> Vec<Box<dyn Analyzer>> {
+    vec![
+        Box::new(ConfigValidator::new()),
+        Box::new(KraftQuorumAnalyzer::new()),
+        Box::new(TopicNamingAnalyzer::from_env()),
+        Box::new(VersionConsistencyAnalyzer::from_env()),
+        Box::new(HotTopicAnalyzer::from_env()),
+        Box::new(RackAwarenessAnalyzer::new()),
+        Box::new(SecurityProtocolAnalyzer::new()),
+        Box::new(GcPauseAnalyzer::new()),
+        Box::new(MetadataVersionAnalyzer),
+        Box::new(RetentionCapacityAnalyzer),
+        Box::new(AdvertisedListenersAnalyzer::new()),
+        Box::new(ConnectionAnalyzer::new()),
+        Box::new(TlsExpiryAnalyzer::new()),
+        Box::new(FileDescriptorAnalyzer::new()),
+        Box::new(LeaderElectionAnalyzer::new()),
+        Box::new(InterBrokerListenerAnalyzer::new()),
+        Box::new(TopicSkewAnalyzer::new()),
+        Box::new(OrphanedTopicsAnalyzer::new()),
+        Box::new(BrokerRestartAnalyzer::new()),
+        Box::new(ClockSkewAnalyzer::new()),
+        Box::new(RuleAnalyzer::from_env()),
+    ]
+}
+
+/// Name of an analyzer as given to `--only-analyzer`: "GC Pause Analyzer" becomes `gc-pause`
+fn analyzer_key(name: &str) -> String {
+    let key = name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase();
+    match key.strip_suffix("-analyzer") {
+        Some(stripped) => stripped.to_string(),
+        None => key,
+    }
+}
+
+/// The static analyzer named `name`, either as listed (`gc-pause`) or by its full name
+pub(crate) fn select_analyzer(name: &str) -> Result<Box<dyn Analyzer>> {
+    let key = analyzer_key(name);
+    let mut analyzers = static_analyzers();
+    match analyzers.iter().position(|analyzer| analyzer_key(analyzer.name()) == key) {
+        Some(index) => Ok(analyzers.swap_remove(index)),
+        None => Err(anyhow::anyhow!(
+            "Unknown analyzer '{}'; available analyzers: {}",
+            name,
+            analyzers.iter().map(|analyzer| analyzer_key(analyzer.name())).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+/// Outcome of an analysis run
+pub(crate) struct AnalysisRun {
+    pub findings: Vec<Finding>,
+    /// Token usage, only for AI analysis
+    pub usage: Option<UsageReport>,
+    /// LLM-written executive summary, when requested and an LLM is configured
+    pub summary: Option<String>,
+}
+
+/// Run the AI analysis tasks, or the static configuration validator when no LLM is configured.
+/// Token usage, remediation scripts and the executive summary are only produced by AI analysis.
+#[tracing::instrument(name = "analyze", skip_all)]
+pub(crate) async fn run_analysis(
+    snapshot_data: &Snapshot,
+    llm_service: Option<LlmService>,
+    options: &AnalysisOptions,
+) -> Result<AnalysisRun> {
+    let start = std::time::Instant::now();
+
+    let (mut findings, executor) = if let Some(analyzer) = &options.only_analyzer {
+        info!("Running only the {}", analyzer.name());
+        (analyzer.analyze(snapshot_data).await?, None)
+    } else if let Some(llm_service) = llm_service {
+        info!("✓ AI executor initialized");
+
+        let mut executor = AiExecutor::new(llm_service)
+            .with_concurrency(options.llm_concurrency)
+            .with_max_prompt_tokens(options.max_prompt_tokens)
+            .with_prompt_truncation(options.truncate_prompts)
+            .with_severity_keywords(options.severity_keywords.clone());
+        info!("  Loading analysis tasks from 'analysis_tasks' directory...");
+
+        let mut findings = executor.analyze_all(snapshot_data).await?;
+        if let Some(threshold) = options.group_similar {
+            findings = executor.group_similar_findings(findings, threshold).await;
+        }
+        if options.generate_scripts {
+            let generated = executor.generate_remediation_scripts(&mut findings).await;
+            info!("Generated {} remediation script(s)", generated);
+        }
+        (findings, Some(executor))
+    } else {
+        warn!("AI analysis not available - LLM API key not configured");
+        warn!("Please set OPENAI_API_KEY or LLM_API_KEY environment variable");
+
+        if options.generate_scripts {
+            warn!("Remediation scripts are generated by the LLM and will not be exported");
+        }
+        if options.group_similar.is_some() {
+            warn!("Similar findings are only grouped for AI analysis");
+        }
+
+        // Fall back to basic static analysis if no LLM available
+        info!("Falling back to static configuration validator...");
+        let mut registry = AnalyzerRegistry::new().with_severity_overrides(options.severity_overrides.clone());
+        for analyzer in static_analyzers() {
+            registry.register(analyzer);
+        }
+        (registry.analyze_all(snapshot_data).await?, None)
+    };
+
+    if let Some(baseline) = &options.baseline {
+        let drift = baseline.analyze(snapshot_data).await?;
+        info!("Baseline comparison found {} drifted setting(s)", drift.len());
+        findings.extend(drift);
+        findings.sort_by_key(|f| f.severity);
+    }
+
+    if let Some(disk_growth) = &options.disk_growth {
+        let filling = disk_growth.analyze(snapshot_data).await?;
+        info!("Disk growth projection found {} filesystem(s) filling up", filling.len());
+        findings.extend(filling);
+        findings.sort_by_key(|f| f.severity);
+    }
+
+    if let Some(custom_rules) = &options.custom_rules {
+        let fired = custom_rules.analyze(snapshot_data).await?;
+        info!("{} of {} custom rule(s) fired", fired.len(), custom_rules.len());
+        findings.extend(fired);
+        findings.sort_by_key(|f| f.severity);
+    }
+
+    // The registry already remapped static findings; this covers AI, baseline and custom rule ones
+    options.severity_overrides.apply(&mut findings);
+
+    let with_timeline = crate::analysis::timeline::attach_timelines(&mut findings, snapshot_data);
+    if with_timeline > 0 {
+        info!("Built log timelines for {} critical finding(s)", with_timeline);
+    }
+
+    let summary = match &executor {
+        Some(executor) if options.ai_summary => match executor.summarize(&findings, snapshot_data).await {
+            Ok(summary) => Some(summary),
+            Err(e) => {
+                warn!("Failed to generate the executive summary, using the templated one: {}", e);
+                None
+            }
+        },
+        None if options.ai_summary => {
+            warn!("The AI executive summary needs an LLM, using the templated one");
+            None
+        }
+        _ => None,
+    };
+
+    crate::telemetry::record_phase_duration("analyze", start.elapsed());
+    crate::telemetry::record_findings(findings.len());
+
+    Ok(AnalysisRun {
+        findings,
+        usage: executor.map(|executor| executor.usage_report()),
+        summary,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+
+    fn duplicate_broker_id_snapshot() -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.config = Some(serde_json::json!({
+            "broker_1/server.properties": "broker.id=1\nlog.dirs=/var/lib/kafka",
+            "broker_2/server.properties": "broker.id=1\nlog.dirs=/var/lib/kafka",
+        }));
+        snapshot
+    }
+
+    #[test]
+    fn test_stale_snapshot_finding() {
+        let snapshot = duplicate_broker_id_snapshot();
+        let max_age = Duration::days(7);
+
+        let fresh = snapshot.timestamp + Duration::days(2);
+        assert!(stale_snapshot_finding(&snapshot, fresh, max_age).is_none());
+
+        let stale = stale_snapshot_finding(&snapshot, snapshot.timestamp + Duration::days(30), max_age).unwrap();
+        assert_eq!(stale.severity, Severity::Info);
+        assert_eq!(stale.title, "Snapshot is 30 day(s) old");
+        assert_eq!(stale.evidence.metrics[0].value, 30.0);
+        assert_eq!(stale.evidence.metrics[0].threshold, Some(7.0));
+    }
+
+    #[tokio::test]
+    async fn test_only_analyzer() {
+        assert_eq!(select_analyzer("gc-pause").unwrap().name(), "GC Pause Analyzer");
+        assert_eq!(select_analyzer("Configuration Validator").unwrap().name(), "Configuration Validator");
+
+        let err = select_analyzer("jvm-tuning").err().unwrap().to_string();
+        assert!(err.starts_with("Unknown analyzer 'jvm-tuning'; available analyzers: configuration-validator, kraft-quorum,"), "{}", err);
+
+        let options = AnalysisOptions {
+            only_analyzer: Some(select_analyzer("topic-naming").unwrap()),
+            ..Default::default()
+        };
+        let findings = run_analysis(&duplicate_broker_id_snapshot(), None, &options)
+            .await
+            .unwrap()
+            .findings;
+        assert!(findings.iter().all(|f| f.severity != Severity::Critical));
+    }
+
+    #[tokio::test]
+    async fn test_rule_findings_reported() {
+        let mut snapshot = duplicate_broker_id_snapshot();
+        snapshot.collectors.admin = Some(serde_json::json!({
+            "cluster": {"cluster_id": null, "controller_id": 1, "broker_count": 2, "topic_count": 1, "partition_count": 1},
+            "brokers": [
+                {"id": 1, "host": "kafka-1", "port": 9092, "rack": null},
+                {"id": 2, "host": "kafka-2", "port": 9092, "rack": null},
+            ],
+            "topics": [{
+                "name": "orders",
+                "partitions": [{"id": 0, "leader": null, "replicas": [1, 2], "isr": [], "offline_replicas": [1, 2]}],
+                "replication_factor": 2,
+                "is_internal": false,
+            }],
+        }));
+
+        let findings = run_analysis(&snapshot, None, &AnalysisOptions::default())
+            .await
+            .unwrap()
+            .findings;
+        let offline = findings.iter()
+            .find(|f| f.metadata.get("rule").and_then(|rule| rule.as_str()) == Some("offline_partitions"))
+            .expect("offline partitions finding");
+        assert_eq!(offline.severity, Severity::Critical);
+        assert_eq!(select_analyzer("rule").unwrap().name(), "rule_analyzer");
+    }
+
+    #[tokio::test]
+    async fn test_critical_finding_fails_on_high() {
+        let findings = run_analysis(&duplicate_broker_id_snapshot(), None, &AnalysisOptions::default())
+            .await
+            .unwrap()
+            .findings;
+        assert!(findings.iter().any(|f| f.severity == Severity::Critical));
+
+        assert_eq!(findings_exit_code(&findings, Some(Severity::High)), FINDINGS_EXIT_CODE);
+        assert_eq!(findings_exit_code(&findings, Some(Severity::Critical)), FINDINGS_EXIT_CODE);
+        assert_eq!(findings_exit_code(&findings, None), 0);
+    }
+
+    #[tokio::test]
+    async fn test_findings_below_threshold_exit_zero() {
+        let findings = run_analysis(&duplicate_broker_id_snapshot(), None, &AnalysisOptions::default())
+            .await
+            .unwrap()
+            .findings;
+        let below_critical: Vec<Finding> = findings.into_iter()
+            .filter(|f| f.severity != Severity::Critical)
+            .collect();
+
+        assert_eq!(findings_exit_code(&below_critical, Some(Severity::Critical)), 0);
+        assert_eq!(findings_exit_code(&[], Some(Severity::Info)), 0);
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use crate::output::StagedOutput;
+use crate::scan::batch::{BatchScanStatus, BatchScanner, Inventory, BATCH_INDEX_FILE};
+use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+pub async fn handle_batch_command(
+    inventory_path: PathBuf,
+    output: Option<PathBuf>,
+    concurrency: usize,
+    since: Option<DateTime<Utc>>,
+    cancel: CancellationToken,
+) -> Result<()> {
+    let inventory = Inventory::from_file(&inventory_path)?;
+    info!("Starting batch scan of {} clusters from {}", inventory.clusters.len(), inventory_path.display());
+
+    let output = StagedOutput::new(output.unwrap_or_else(|| {
+        PathBuf::from(format!("kafka-batch-{}", Utc::now().format("%Y%m%d-%H%M%S")))
+    }));
+
+    let index = BatchScanner::new(inventory, output.local_path().to_path_buf())
+        .with_concurrency(concurrency)
+        .with_log_since(since)
+        .with_cancellation(cancel)
+        .run()
+        .await?;
+    output.finish_dir().await?;
+
+    println!();
+    println!("📋 Batch scan summary:");
+    for cluster in &index.clusters {
+        let status = match cluster.status {
+            BatchScanStatus::Completed => "✅ completed",
+            BatchScanStatus::Partial => "⏹  partial",
+            BatchScanStatus::Failed => "❌ failed",
+            BatchScanStatus::Skipped => "⏭  skipped",
+        };
+        match &cluster.error {
+            Some(error) => println!("  • {}: {} ({})", cluster.name, status, error),
+            None => println!(
+                "  • {}: {} ({}/{} brokers) → {}",
+                cluster.name, status, cluster.accessible_brokers, cluster.broker_count, cluster.output_directory
+            ),
+        }
+    }
+    println!("📄 Index saved to {}/{}", output.destination().display(), BATCH_INDEX_FILE);
+
+    let failed = index.failed();
+    if failed > 0 {
+        anyhow::bail!("{} of {} cluster scans failed", failed, index.clusters.len());
+    }
+
+    Ok(())
+}
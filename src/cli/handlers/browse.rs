@@ -0,0 +1,31 @@
+use anyhow::{Context, Result};
+use crate::cli::handlers::analyze::{run_analysis, AnalysisOptions};
+use crate::cli::utils::load_snapshot_from_directory;
+use crate::snapshot::format::Finding;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use tracing::info;
+
+/// The `findings` array shared by JSON reports and snapshot files
+#[derive(Deserialize)]
+struct FindingsFile {
+    findings: Vec<Finding>,
+}
+
+pub async fn handle_browse_command(snapshot: PathBuf) -> Result<()> {
+    let findings = if snapshot.is_dir() {
+        // Scan directories have no findings yet, run the static analysis
+        info!("Analyzing scan directory: {}", snapshot.display());
+        let snapshot_data = load_snapshot_from_directory(&snapshot)?;
+        run_analysis(&snapshot_data, None, &AnalysisOptions::default()).await?.findings
+    } else {
+        let content = fs::read_to_string(&snapshot)
+            .with_context(|| format!("Failed to read {}", snapshot.display()))?;
+        let file: FindingsFile = serde_json::from_str(&content)
+            .with_context(|| format!("{} is not a JSON report or snapshot", snapshot.display()))?;
+        file.findings
+    };
+
+    crate::cli::tui::run(findings)
+}
@@ -44,6 +44,54 @@ pub fn handle_config_command() -> Result<()> {
         Err(_) => println!("  • LLM_DEBUG: false (default)"),
     }
     
+    // Check for topic naming convention
+    match env::var("TOPIC_NAME_PATTERN") {
+        Ok(val) => println!("  • TOPIC_NAME_PATTERN: {}", val),
+        Err(_) => println!("  • TOPIC_NAME_PATTERN: not set (naming convention not checked)"),
+    }
+    
+    // Check for end-of-life Kafka releases
+    match env::var("KAFKA_EOL_VERSIONS") {
+        Ok(val) => println!("  • KAFKA_EOL_VERSIONS: {}", val),
+        Err(_) => println!("  • KAFKA_EOL_VERSIONS: not set (built-in end-of-life list)"),
+    }
+    
+    // Check for the hot topic threshold
+    match env::var("HOT_TOPIC_SHARE") {
+        Ok(val) => println!("  • HOT_TOPIC_SHARE: {}%", val.trim_end_matches('%')),
+        Err(_) => println!("  • HOT_TOPIC_SHARE: 50% (default)"),
+    }
+    
+    // Check for the over-partitioned topic threshold
+    match env::var("MAX_PARTITIONS_PER_TOPIC") {
+        Ok(val) => println!("  • MAX_PARTITIONS_PER_TOPIC: {}", val),
+        Err(_) => println!("  • MAX_PARTITIONS_PER_TOPIC: 1000 (default)"),
+    }
+    
+    // Check for the disk-full projection horizon
+    match env::var("DISK_FULL_HORIZON_DAYS") {
+        Ok(val) => println!("  • DISK_FULL_HORIZON_DAYS: {} days", val),
+        Err(_) => println!("  • DISK_FULL_HORIZON_DAYS: 7 days (default)"),
+    }
+    
+    // Check for custom threshold rules
+    match env::var("KCPILOT_RULES_FILE") {
+        Ok(val) => println!("  • KCPILOT_RULES_FILE: {}", val),
+        Err(_) => println!("  • KCPILOT_RULES_FILE: (not set)"),
+    }
+    
+    // Check for deployment-wide severity keywords of AI findings
+    match env::var("KCPILOT_SEVERITY_KEYWORDS_FILE") {
+        Ok(val) => println!("  • KCPILOT_SEVERITY_KEYWORDS_FILE: {}", val),
+        Err(_) => println!("  • KCPILOT_SEVERITY_KEYWORDS_FILE: (not set)"),
+    }
+    
+    // Check for the health score methodology
+    match env::var("KCPILOT_HEALTH_SCORE_MODEL") {
+        Ok(val) => println!("  • KCPILOT_HEALTH_SCORE_MODEL: {}", val),
+        Err(_) => println!("  • KCPILOT_HEALTH_SCORE_MODEL: flat (default)"),
+    }
+    
     // Check for Rust log level
     match env::var("RUST_LOG") {
         Ok(val) => println!("  • RUST_LOG: {}", val),
@@ -1,12 +1,26 @@
 pub mod scan;
+pub mod batch;
 pub mod analyze;
 pub mod task;
 pub mod ssh_test;
 pub mod config;
+pub mod schema;
+pub mod watch;
+pub mod serve;
+pub mod redact;
+#[cfg(feature = "tui")]
+pub mod browse;
 
 // Re-export handler functions for convenience
 pub use scan::handle_scan_command;
+pub use batch::handle_batch_command;
 pub use analyze::handle_analyze_command;
 pub use task::handle_task_command;
 pub use ssh_test::handle_ssh_test_command;
-pub use config::handle_config_command;
\ No newline at end of file
+pub use config::handle_config_command;
+pub use schema::handle_schema_command;
+pub use watch::handle_watch_command;
+pub use serve::handle_serve_command;
+pub use redact::handle_redact_command;
+#[cfg(feature = "tui")]
+pub use browse::handle_browse_command;
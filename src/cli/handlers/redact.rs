@@ -0,0 +1,101 @@
+use anyhow::{bail, Context, Result};
+use crate::cli::utils::load_snapshot;
+use crate::collectors::{redact_snapshot, RedactionLevel};
+use crate::snapshot::format::Snapshot;
+use crate::snapshot::SnapshotManager;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Redact a snapshot captured with less redaction than it needs for sharing, and write the
+/// result as a new snapshot; the input is left as it is
+pub fn handle_redact_command(input: PathBuf, output: PathBuf, level: RedactionLevel) -> Result<()> {
+    if input == output {
+        bail!("--output must differ from --input; the input snapshot is never modified");
+    }
+
+    let mut snapshot = load_snapshot(&input)?;
+    let report = redact_snapshot(&mut snapshot, level);
+    info!("🔒 Redacted {} value(s) in {} field(s) at the {:?} level", report.count, report.fields_redacted.len(), level);
+
+    save_snapshot(&snapshot, &output)?;
+    info!("✅ Redacted snapshot written to {}", output.display());
+    Ok(())
+}
+
+/// Write `snapshot` as a .tar archive or .snapshot file by the extension of `path`, otherwise as JSON
+fn save_snapshot(snapshot: &Snapshot, path: &Path) -> Result<()> {
+    let manager = SnapshotManager::new();
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("tar") => manager.create_archive(snapshot, HashMap::new(), path)?,
+        Some("snapshot") => manager.save(snapshot, path)?,
+        _ => fs::write(path, serde_json::to_vec_pretty(snapshot)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collectors::REDACTED;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    #[test]
+    fn test_sasl_password_scrubbed() {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+        snapshot.collectors.admin = Some(json!({
+            "cluster": {"cluster_id": "abc", "controller_id": 1, "broker_count": 1, "topic_count": 0, "partition_count": 0},
+            "brokers": [{"id": 1, "host": "kafka-1.prod.internal", "port": 9092, "rack": null}],
+            "topics": [],
+            "connection": {
+                "bootstrap_servers": ["kafka-1.prod.internal:9092"],
+                "security_protocol": "SASL_SSL",
+                "sasl_mechanism": "SCRAM-SHA-512",
+                "sasl_username": "admin",
+                "sasl_password": "s3cr3t-admin-pw",
+                "ssl_ca_cert": null,
+                "ssl_cert": null,
+                "ssl_key": null,
+                "timeout_secs": 30,
+                "client_id": "kcpilot",
+            },
+        }));
+        snapshot.collectors.config = Some(json!({
+            "broker_1/server.properties": "broker.id=1\n\
+                listener.name.sasl_ssl.scram-sha-512.sasl.jaas.config=org.apache.kafka.common.security.scram.ScramLoginModule required \\\n    \
+                username=\"admin\" \\\n    password=\"s3cr3t-broker-pw\";\n\
+                ssl.keystore.password=s3cr3t-keystore-pw\n\
+                log.dirs=/var/lib/kafka",
+        }));
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("unredacted.json");
+        let output = dir.path().join("redacted.snapshot");
+        fs::write(&input, serde_json::to_vec(&snapshot).unwrap()).unwrap();
+
+        handle_redact_command(input.clone(), output.clone(), RedactionLevel::Standard).unwrap();
+
+        let redacted = SnapshotManager::new().load(&output).unwrap();
+        let serialized = serde_json::to_string(&redacted.collectors).unwrap();
+        for secret in ["s3cr3t-admin-pw", "s3cr3t-broker-pw", "s3cr3t-keystore-pw"] {
+            assert!(!serialized.contains(secret), "{} left in the redacted snapshot", secret);
+        }
+        assert_eq!(redacted.collectors.admin.as_ref().unwrap()["connection"]["sasl_password"], REDACTED);
+        let properties = redacted.collectors.config.as_ref().unwrap()["broker_1/server.properties"].as_str().unwrap();
+        assert!(properties.contains("log.dirs=/var/lib/kafka"));
+        // Hostnames are kept below the aggressive level
+        assert!(serialized.contains("kafka-1.prod.internal"));
+
+        assert!(redacted.metadata.redaction_applied);
+        let fields = &redacted.metadata.redaction_report.fields_redacted;
+        assert!(fields.contains(&"admin.connection.sasl_password".to_string()));
+        assert!(fields.contains(&"config.broker_1/server.properties".to_string()));
+
+        // The input is left unredacted
+        assert!(fs::read_to_string(&input).unwrap().contains("s3cr3t-admin-pw"));
+        assert!(handle_redact_command(input.clone(), input, RedactionLevel::Standard).is_err());
+    }
+}
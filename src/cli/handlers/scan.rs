@@ -1,41 +1,117 @@
 use anyhow::Result;
-use crate::scan::Scanner;
+use crate::cli::commands::{BrokerArgs, CollectionArgs, ScanDisplayArgs, ScanOutputFormat};
+use crate::output::StagedOutput;
+use crate::scan::{CollectionStats, PreviousScan, ScanMetadata, ScanResult, Scanner};
+use crate::scan::collector::CollectorSelection;
+use crate::scan::enhanced_log_discovery::LogCollectionBudget;
+use serde::Serialize;
+use std::io::Write;
 use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
 use tracing::info;
 
+/// Machine-readable summary printed by `scan --output-format json`
+#[derive(Debug, Serialize)]
+struct ScanSummary<'a> {
+    metadata: &'a ScanMetadata,
+    collection_stats: &'a CollectionStats,
+    brokers: Vec<BrokerSummary<'a>>,
+}
+
+/// A collected broker, without the collected file contents
+#[derive(Debug, Serialize)]
+struct BrokerSummary<'a> {
+    broker_id: i32,
+    hostname: &'a str,
+    accessible: bool,
+}
+
+/// Write the scan result as one JSON document
+fn write_scan_json<W: Write>(result: &ScanResult, mut writer: W) -> Result<()> {
+    let summary = ScanSummary {
+        metadata: &result.metadata,
+        collection_stats: &result.collection_stats,
+        brokers: result.broker_data.iter().map(|broker| BrokerSummary {
+            broker_id: broker.broker_id,
+            hostname: &broker.hostname,
+            accessible: broker.accessible,
+        }).collect(),
+    };
+    serde_json::to_writer_pretty(&mut writer, &summary)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
 pub async fn handle_scan_command(
     bastion: Option<String>,
     output: Option<PathBuf>,
-    broker: Option<String>,
+    brokers: BrokerArgs,
+    collection: CollectionArgs,
+    display: ScanDisplayArgs,
+    cancel: CancellationToken,
 ) -> Result<()> {
     info!("Starting Kafka cluster scan");
 
+    // JSON output owns stdout, so banners and progress bars stay off
+    let json = display.output_format == ScanOutputFormat::Json;
+    crate::scan::set_banners(!json);
+    crate::report::symbols::set_ascii(crate::report::symbols::detect_ascii(display.ascii));
+
+    let previous_scan = collection.incremental_from.as_deref().map(PreviousScan::load).transpose()?;
+    if let Some(previous) = &previous_scan {
+        info!("Collecting incrementally against {} previously collected file(s)", previous.len());
+    }
+
     // bastion is already Option<String>, pass it directly
     // None means we're running locally on the bastion
-    let mut scanner = Scanner::new(bastion)?;
+    let mut scanner = Scanner::new(bastion)?
+        .with_broker_port(brokers.broker_port)
+        .with_log_since(collection.since)
+        .with_log_budget(LogCollectionBudget {
+            max_lines_per_file: collection.log_lines,
+            max_total_bytes: collection.log_budget_mb * 1024 * 1024,
+            ..Default::default()
+        })
+        .with_redaction_level(collection.redact_level.into())
+        .with_collectors(CollectorSelection::only(collection.only))
+        .with_previous_scan(previous_scan)
+        .with_connect_url(collection.connect_url)
+        .with_metrics_port(collection.metrics_port)
+        .with_progress(!display.no_progress && !json)
+        .with_cancellation(cancel);
 
-    // Set custom output directory if provided
-    if let Some(output_path) = output {
-        scanner = scanner.with_output_dir(output_path);
+    // Set custom output directory if provided; s3:// and gs:// outputs are staged locally and uploaded
+    let staged = output.map(StagedOutput::new);
+    if let Some(staged) = &staged {
+        scanner = scanner.with_output_dir(staged.local_path().to_path_buf());
     }
 
-    // Handle broker discovery based on input parameters
-    match broker {
-        Some(broker_address) => {
-            // Single broker provided - discover cluster from it
-            info!("Using broker discovery from: {}", broker_address);
-            scanner = scanner.discover_brokers_from_single(&broker_address).await?;
-        }
-        None => {
-            // No broker provided - try to discover from kafkactl
-            info!("No broker provided, attempting to discover brokers from kafkactl");
-            scanner = scanner.discover_brokers_from_kafkactl().await?;
-        }
-    }
+    // Use the listed brokers, or discover from the given broker or from kafkactl when none was provided
+    scanner = match &brokers.broker_list {
+        Some(broker_list) => scanner.with_broker_list(broker_list)?.verify_cluster().await?,
+        None => scanner.discover(brokers.broker.as_deref()).await?,
+    };
 
     // Run the scan
     let result = scanner.scan().await?;
 
+    if let Some(staged) = &staged {
+        staged.finish_dir().await?;
+    }
+
+    if json {
+        write_scan_json(&result, std::io::stdout().lock())?;
+    }
+
+    if result.metadata.partial {
+        info!(
+            "Scan cancelled. Partial data from {} brokers saved to {}",
+            result.broker_data.len(),
+            result.metadata.output_directory
+        );
+        return Ok(());
+    }
+
     // Log results
     info!(
         "Scan completed. Collected data from {} brokers out of {}",
@@ -44,4 +120,43 @@ pub async fn handle_scan_command(
     );
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::BrokerInfo;
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_json_output_is_valid_and_banners_suppressed() {
+        let temp_dir = TempDir::new().unwrap();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        crate::scan::set_banners(false);
+        let mut scanner = Scanner::new(None)
+            .unwrap()
+            .with_output_dir(temp_dir.path().join("scan"))
+            .with_brokers(vec![BrokerInfo { id: 1, hostname: "kafka-1".to_string(), listeners: Vec::new() }])
+            .with_cancellation(cancel)
+            .with_progress(false);
+        let result = scanner.scan().await.unwrap();
+        assert!(!crate::scan::banners_enabled());
+        crate::scan::set_banners(true);
+
+        let mut output = Vec::new();
+        write_scan_json(&result, &mut output).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(!text.contains("═══"));
+
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json["metadata"]["broker_count"], 1);
+        assert_eq!(json["metadata"]["accessible_brokers"], 0);
+        assert_eq!(json["metadata"]["partial"], true);
+        assert!(json["metadata"].get("cluster_mode").is_some());
+        assert!(json["brokers"].as_array().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,24 @@
+use anyhow::{Context, Result};
+use crate::cli::commands::SchemaKind;
+use crate::report::json::{finding_schema, report_schema};
+use std::fs;
+use std::path::PathBuf;
+use tracing::info;
+
+pub fn handle_schema_command(kind: SchemaKind, output: Option<PathBuf>) -> Result<()> {
+    let schema = match kind {
+        SchemaKind::Report => report_schema(),
+        SchemaKind::Finding => finding_schema(),
+    };
+    let json = serde_json::to_string_pretty(&schema)?;
+
+    match output {
+        Some(path) => {
+            fs::write(&path, format!("{}\n", json))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            info!("JSON Schema written to {}", path.display());
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use anyhow::{Context, Result};
+use crate::cli::handlers::analyze::{run_analysis, AnalysisOptions};
+use crate::cli::utils::load_snapshot;
+use crate::report::html::HtmlReporter;
+use crate::report::scoring::HealthScoreMethod;
+use crate::snapshot::format::{Finding, Snapshot};
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+use tower_http::services::ServeDir;
+use tracing::info;
+
+/// Port the report is served on unless `--port` is given
+pub const DEFAULT_SERVE_PORT: u16 = 8080;
+
+/// Analyze a snapshot and serve its HTML report over HTTP until Ctrl-C
+pub async fn handle_serve_command(snapshot: PathBuf, bind: IpAddr, port: u16) -> Result<()> {
+    let snapshot_data = load_snapshot(&snapshot)?;
+
+    let listener = TcpListener::bind((bind, port)).await
+        .with_context(|| format!("Failed to listen on {}:{}", bind, port))?;
+
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            token.cancel();
+        }
+    });
+
+    serve_report(&snapshot_data, listener, cancel).await
+}
+
+/// Run the static analysis of `snapshot`, write its HTML report to a temporary directory and
+/// serve that directory on `listener` until `cancel` fires
+pub async fn serve_report(snapshot: &Snapshot, listener: TcpListener, cancel: CancellationToken) -> Result<()> {
+    let findings = run_analysis(snapshot, None, &AnalysisOptions::default()).await?.findings;
+
+    let site_dir = std::env::temp_dir().join(format!("kcpilot-serve-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&site_dir)?;
+    let served = write_and_serve(snapshot, &findings, &site_dir, listener, cancel).await;
+    let _ = fs::remove_dir_all(&site_dir);
+    served
+}
+
+async fn write_and_serve(
+    snapshot: &Snapshot,
+    findings: &[Finding],
+    site_dir: &Path,
+    listener: TcpListener,
+    cancel: CancellationToken,
+) -> Result<()> {
+    HtmlReporter::new()
+        .with_score_method(HealthScoreMethod::from_env())
+        .save_report(snapshot, findings, &site_dir.join("index.html"))?;
+
+    let app = axum::Router::new().fallback_service(ServeDir::new(site_dir));
+    info!("🌐 Serving the report at http://{}/ ; press Ctrl-C to stop", listener.local_addr()?);
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move { cancel.cancelled().await })
+        .await?;
+    info!("Report server stopped");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+
+    #[tokio::test]
+    async fn test_report_served_at_root() {
+        let snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let cancel = CancellationToken::new();
+        let server = {
+            let cancel = cancel.clone();
+            tokio::spawn(async move { serve_report(&snapshot, listener, cancel).await })
+        };
+
+        // The listener is already bound, so the request waits until the report is written
+        let response = reqwest::get(&url).await.unwrap();
+
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let body = response.text().await.unwrap();
+        assert!(body.starts_with("<!DOCTYPE html>"));
+        assert!(body.contains("Kafka Cluster Health Report"));
+
+        cancel.cancel();
+        server.await.unwrap().unwrap();
+    }
+}
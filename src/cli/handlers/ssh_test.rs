@@ -21,6 +21,7 @@ pub async fn handle_ssh_test_command(bastion: Option<String>) -> Result<()> {
                 &BrokerInfo {
                     id: 0,
                     hostname: "localhost".to_string(),
+                    listeners: Vec::new(),
                 }
             };
             
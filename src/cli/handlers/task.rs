@@ -1,5 +1,5 @@
 use anyhow::Result;
-use crate::analysis::{TaskLoader, AiExecutor};
+use crate::analysis::{TaskLoader, AiExecutor, SeverityKeywords};
 use crate::cli::utils::load_snapshot_from_directory;
 use std::fs;
 
@@ -55,7 +55,8 @@ pub async fn handle_task_command(action: crate::cli::commands::TaskCommand) -> R
 
             // Run the task
             if let Ok(llm_service) = crate::llm::LlmService::from_env_with_debug(debug) {
-                let executor = AiExecutor::new(llm_service);
+                let executor = AiExecutor::new(llm_service)
+                    .with_severity_keywords(SeverityKeywords::from_env()?.unwrap_or_default());
 
                 match executor.execute_task(&task, &snapshot_data).await {
                     Ok(findings) => {
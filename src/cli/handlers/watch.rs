@@ -0,0 +1,193 @@
+use anyhow::{Context, Result};
+use crate::cli::commands::{BaselineArgs, LlmArgs, ReportArgs};
+use crate::cli::handlers::analyze::handle_analyze_command;
+use crate::snapshot::is_snapshot_file;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// Default quiet period after the last change to a snapshot file before it is analyzed
+pub const DEFAULT_SETTLE_MS: u64 = 2000;
+
+/// A file changed since it was last seen complete
+struct PendingFile {
+    /// Last change event, or last size check that found the size still changing
+    changed_at: Instant,
+    /// Size at the last check after the quiet period
+    size: Option<u64>,
+}
+
+/// Re-analyze every new or rewritten snapshot file in `dir` and update the report each time
+pub async fn handle_watch_command(dir: PathBuf, report: ReportArgs, llm: LlmArgs, settle_ms: u64) -> Result<()> {
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            token.cancel();
+        }
+    });
+
+    let report_path = report.output.clone();
+    info!("👀 Watching {} for new snapshot files (.snapshot, .tar); press Ctrl-C to stop", dir.display());
+    watch_snapshots(&dir, Duration::from_millis(settle_ms), cancel, |snapshot| {
+        let report = report.clone();
+        let llm = llm.clone();
+        let report_path = report_path.clone();
+        async move {
+            info!("📥 New snapshot: {}", snapshot.display());
+            match handle_analyze_command(snapshot.clone(), report, llm, None, BaselineArgs::default(), None, None).await {
+                Ok(_) => match &report_path {
+                    Some(path) => info!("✅ Report {} updated from {}", path.display(), snapshot.display()),
+                    None => info!("✅ Analyzed {}", snapshot.display()),
+                },
+                Err(e) => warn!("❌ Failed to analyze {}: {:#}", snapshot.display(), e),
+            }
+        }
+    }).await
+}
+
+/// Call `on_snapshot` once for each snapshot file created or rewritten in `dir` until `cancel`
+/// fires. Bursts of change events are debounced: a file is handed over once no event arrived
+/// for `settle` and its size stayed the same over another `settle`, so partially written or
+/// still-copied files are not read. Files already in `dir` are not analyzed.
+pub async fn watch_snapshots<F, Fut>(dir: &Path, settle: Duration, cancel: CancellationToken, mut on_snapshot: F) -> Result<()>
+where
+    F: FnMut(PathBuf) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let _ = events_tx.send(event);
+    })?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)
+        .with_context(|| format!("Failed to watch {}", dir.display()))?;
+
+    let mut pending: HashMap<PathBuf, PendingFile> = HashMap::new();
+    let mut ticks = tokio::time::interval((settle / 4).max(Duration::from_millis(10)));
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return Ok(()),
+            event = events_rx.recv() => {
+                let Some(event) = event else {
+                    return Ok(());
+                };
+                match event {
+                    Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                        for path in event.paths.into_iter().filter(|path| is_watched_file(path)) {
+                            debug!("Change to {}", path.display());
+                            pending.insert(path, PendingFile { changed_at: Instant::now(), size: None });
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Error watching {}: {}", dir.display(), e),
+                }
+            }
+            _ = ticks.tick() => {
+                for path in settled_files(&mut pending, settle) {
+                    on_snapshot(path).await;
+                }
+            }
+        }
+    }
+}
+
+/// Remove and return the pending files that had no changes for `settle` and kept the same
+/// size since the previous check
+fn settled_files(pending: &mut HashMap<PathBuf, PendingFile>, settle: Duration) -> Vec<PathBuf> {
+    let mut settled = Vec::new();
+    pending.retain(|path, file| {
+        if file.changed_at.elapsed() < settle {
+            return true;
+        }
+        // Removed or renamed away before it settled
+        let Ok(size) = std::fs::metadata(path).map(|metadata| metadata.len()) else {
+            return false;
+        };
+        if size > 0 && file.size == Some(size) {
+            settled.push(path.clone());
+            return false;
+        }
+        file.size = Some(size);
+        file.changed_at = Instant::now();
+        true
+    });
+    settled.sort();
+    settled
+}
+
+/// Snapshot files, skipping hidden files such as partial downloads and editor swap files
+fn is_watched_file(path: &Path) -> bool {
+    let hidden = path.file_name()
+        .and_then(|name| name.to_str())
+        .is_none_or(|name| name.starts_with('.'));
+    !hidden && is_snapshot_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_new_snapshot_triggers_one_run() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("existing.snapshot"), "{}").unwrap();
+
+        let runs = Arc::new(Mutex::new(Vec::new()));
+        let cancel = CancellationToken::new();
+        let watch = {
+            let runs = runs.clone();
+            let dir = dir.path().to_path_buf();
+            let cancel = cancel.clone();
+            tokio::spawn(async move {
+                watch_snapshots(&dir, Duration::from_millis(200), cancel, |path| {
+                    runs.lock().unwrap().push(path);
+                    async {}
+                }).await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Written in several chunks, with other files changing alongside it
+        let path = dir.path().join("cluster-2024-06-01.snapshot");
+        let mut file = std::fs::File::create(&path).unwrap();
+        for chunk in 0..5 {
+            writeln!(file, "{{\"chunk\": {}}}", chunk).unwrap();
+            file.flush().unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        drop(file);
+        std::fs::write(dir.path().join("notes.txt"), "not a snapshot").unwrap();
+        std::fs::write(dir.path().join(".partial.snapshot"), "{").unwrap();
+
+        tokio::time::sleep(Duration::from_millis(1000)).await;
+        cancel.cancel();
+        watch.await.unwrap().unwrap();
+
+        assert_eq!(*runs.lock().unwrap(), vec![path]);
+    }
+
+    #[test]
+    fn test_file_settles_once_its_size_is_stable() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cluster.snapshot");
+        std::fs::write(&path, "{\"partial\":").unwrap();
+        let mut pending = HashMap::from([(path.clone(), PendingFile { changed_at: Instant::now(), size: None })]);
+
+        // Still inside the quiet period
+        assert!(settled_files(&mut pending, Duration::from_secs(60)).is_empty());
+
+        // First check after the quiet period records the size, the next one sees it unchanged
+        assert!(settled_files(&mut pending, Duration::ZERO).is_empty());
+        std::fs::write(&path, "{\"partial\": false}").unwrap();
+        assert!(settled_files(&mut pending, Duration::ZERO).is_empty());
+        assert_eq!(settled_files(&mut pending, Duration::ZERO), vec![path]);
+        assert!(pending.is_empty());
+    }
+}
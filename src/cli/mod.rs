@@ -1,5 +1,7 @@
 pub mod commands;
 pub mod handlers;
 pub mod utils;
+#[cfg(feature = "tui")]
+pub mod tui;
 
 pub use commands::{Cli, Commands};
@@ -0,0 +1,445 @@
+use crate::snapshot::format::{Category, Finding, Severity};
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::cursor::Show;
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{Frame, Terminal};
+use std::io;
+
+const SEVERITIES: [Severity; 5] = [
+    Severity::Critical,
+    Severity::High,
+    Severity::Medium,
+    Severity::Low,
+    Severity::Info,
+];
+
+const CATEGORIES: [Category; 8] = [
+    Category::ClusterHygiene,
+    Category::Performance,
+    Category::Configuration,
+    Category::Security,
+    Category::Availability,
+    Category::Client,
+    Category::Capacity,
+    Category::Other,
+];
+
+const HELP: &str = "↑/↓ select  PgUp/PgDn scroll  s/1-5 severity  c category  0 clear  q quit";
+
+/// State of the findings browser
+pub struct App {
+    findings: Vec<Finding>,
+    severity_filter: Option<Severity>,
+    category_filter: Option<Category>,
+    /// Indices into `findings` that pass the filters
+    visible: Vec<usize>,
+    list_state: ListState,
+    detail_scroll: u16,
+    should_quit: bool,
+}
+
+impl App {
+    pub fn new(mut findings: Vec<Finding>) -> Self {
+        findings.sort_by_key(|f| f.severity);
+        let mut app = Self {
+            findings,
+            severity_filter: None,
+            category_filter: None,
+            visible: Vec::new(),
+            list_state: ListState::default(),
+            detail_scroll: 0,
+            should_quit: false,
+        };
+        app.apply_filters();
+        app
+    }
+
+    pub fn visible_findings(&self) -> impl Iterator<Item = &Finding> {
+        self.visible.iter().map(|&i| &self.findings[i])
+    }
+
+    pub fn selected_finding(&self) -> Option<&Finding> {
+        self.list_state.selected()
+            .and_then(|i| self.visible.get(i))
+            .map(|&i| &self.findings[i])
+    }
+
+    pub fn set_severity_filter(&mut self, severity: Option<Severity>) {
+        self.severity_filter = severity;
+        self.apply_filters();
+    }
+
+    pub fn set_category_filter(&mut self, category: Option<Category>) {
+        self.category_filter = category;
+        self.apply_filters();
+    }
+
+    /// Step the severity filter through all severities and back to showing everything
+    pub fn cycle_severity(&mut self) {
+        let next = match self.severity_filter {
+            None => Some(SEVERITIES[0]),
+            Some(current) => SEVERITIES.iter()
+                .position(|s| *s == current)
+                .and_then(|i| SEVERITIES.get(i + 1))
+                .copied(),
+        };
+        self.set_severity_filter(next);
+    }
+
+    /// Step the category filter through all categories and back to showing everything
+    pub fn cycle_category(&mut self) {
+        let next = match self.category_filter {
+            None => Some(CATEGORIES[0]),
+            Some(current) => CATEGORIES.iter()
+                .position(|c| same_category(*c, current))
+                .and_then(|i| CATEGORIES.get(i + 1))
+                .copied(),
+        };
+        self.set_category_filter(next);
+    }
+
+    pub fn select_next(&mut self) {
+        if let Some(i) = self.list_state.selected() {
+            if i + 1 < self.visible.len() {
+                self.list_state.select(Some(i + 1));
+                self.detail_scroll = 0;
+            }
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if let Some(i) = self.list_state.selected() {
+            if i > 0 {
+                self.list_state.select(Some(i - 1));
+                self.detail_scroll = 0;
+            }
+        }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Down | KeyCode::Char('j') => self.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.select_previous(),
+            KeyCode::PageDown => self.detail_scroll = self.detail_scroll.saturating_add(10),
+            KeyCode::PageUp => self.detail_scroll = self.detail_scroll.saturating_sub(10),
+            KeyCode::Char('s') => self.cycle_severity(),
+            KeyCode::Char('c') => self.cycle_category(),
+            KeyCode::Char('0') => {
+                self.severity_filter = None;
+                self.set_category_filter(None);
+            }
+            KeyCode::Char(digit @ '1'..='5') => {
+                let index = digit as usize - '1' as usize;
+                self.set_severity_filter(Some(SEVERITIES[index]));
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_filters(&mut self) {
+        self.visible = self.findings.iter()
+            .enumerate()
+            .filter(|(_, f)| self.severity_filter.map(|s| f.severity == s).unwrap_or(true))
+            .filter(|(_, f)| self.category_filter.map(|c| same_category(f.category, c)).unwrap_or(true))
+            .map(|(i, _)| i)
+            .collect();
+        self.list_state.select(if self.visible.is_empty() { None } else { Some(0) });
+        self.detail_scroll = 0;
+    }
+
+    fn filter_summary(&self) -> String {
+        format!(
+            "{}/{} findings | severity: {} | category: {}",
+            self.visible.len(),
+            self.findings.len(),
+            self.severity_filter.map(severity_label).unwrap_or("all"),
+            self.category_filter.map(category_label).unwrap_or("all"),
+        )
+    }
+}
+
+fn same_category(a: Category, b: Category) -> bool {
+    std::mem::discriminant(&a) == std::mem::discriminant(&b)
+}
+
+fn severity_label(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "critical",
+        Severity::High => "high",
+        Severity::Medium => "medium",
+        Severity::Low => "low",
+        Severity::Info => "info",
+    }
+}
+
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Critical => Color::Red,
+        Severity::High => Color::LightRed,
+        Severity::Medium => Color::Yellow,
+        Severity::Low => Color::LightYellow,
+        Severity::Info => Color::Blue,
+    }
+}
+
+fn category_label(category: Category) -> &'static str {
+    match category {
+        Category::ClusterHygiene => "cluster hygiene",
+        Category::Performance => "performance",
+        Category::Configuration => "configuration",
+        Category::Security => "security",
+        Category::Availability => "availability",
+        Category::Client => "client",
+        Category::Capacity => "capacity",
+        Category::Other => "other",
+    }
+}
+
+/// Lines of the detail pane for a finding
+fn detail_lines(finding: &Finding) -> Vec<Line<'_>> {
+    let heading = |text: &'static str| Line::from(Span::styled(text, Style::default().add_modifier(Modifier::BOLD)));
+    let mut lines = vec![
+        Line::from(Span::styled(
+            finding.title.as_str(),
+            Style::default().fg(severity_color(finding.severity)).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(format!(
+            "{} | {} | {}",
+            finding.id,
+            severity_label(finding.severity),
+            category_label(finding.category)
+        )),
+        Line::default(),
+        heading("Description"),
+        Line::from(finding.description.as_str()),
+        Line::default(),
+        heading("Impact"),
+        Line::from(finding.impact.as_str()),
+    ];
+
+    if let Some(root_cause) = &finding.root_cause {
+        lines.extend([Line::default(), heading("Root cause"), Line::from(root_cause.as_str())]);
+    }
+
+    let evidence = &finding.evidence;
+    if !evidence.configs.is_empty() || !evidence.metrics.is_empty() || !evidence.logs.is_empty() {
+        lines.extend([Line::default(), heading("Evidence")]);
+        for config in &evidence.configs {
+            let recommended = config.recommended_value.as_deref()
+                .map(|v| format!(" (recommended: {})", v))
+                .unwrap_or_default();
+            lines.push(Line::from(format!(
+                "• {} {}: {} = {}{}",
+                config.resource_type, config.resource_name, config.config_key, config.current_value, recommended
+            )));
+        }
+        for metric in &evidence.metrics {
+            lines.push(Line::from(format!(
+                "• {} = {}{} ({})",
+                metric.name, metric.value, metric.unit.as_deref().unwrap_or(""), metric.source
+            )));
+        }
+        for log in &evidence.logs {
+            lines.push(Line::from(format!("• [{}] {} ({})", log.level, log.message, log.source_file)));
+        }
+    }
+
+    if !finding.remediation.steps.is_empty() {
+        lines.extend([Line::default(), heading("Remediation")]);
+        for step in &finding.remediation.steps {
+            lines.push(Line::from(format!("{}. {}", step.order, step.description)));
+            if let Some(command) = &step.command {
+                lines.push(Line::from(Span::styled(format!("   $ {}", command), Style::default().fg(Color::Cyan))));
+            }
+        }
+    }
+
+    lines
+}
+
+fn render(frame: &mut Frame, app: &mut App) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(frame.area());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(rows[0]);
+
+    let items: Vec<ListItem> = app.visible_findings()
+        .map(|f| ListItem::new(Line::from(vec![
+            Span::styled(format!("{:<8} ", severity_label(f.severity)), Style::default().fg(severity_color(f.severity))),
+            Span::raw(f.title.clone()),
+        ])))
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(app.filter_summary()))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, columns[0], &mut app.list_state);
+
+    let detail = match app.selected_finding() {
+        Some(finding) => Paragraph::new(detail_lines(finding)),
+        None => Paragraph::new("No findings match the current filters"),
+    };
+    frame.render_widget(
+        detail
+            .block(Block::default().borders(Borders::ALL).title("Details"))
+            .wrap(Wrap { trim: false })
+            .scroll((app.detail_scroll, 0)),
+        columns[1],
+    );
+
+    frame.render_widget(Paragraph::new(HELP).style(Style::default().fg(Color::DarkGray)), rows[1]);
+}
+
+fn event_loop<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
+    while !app.should_quit {
+        terminal.draw(|frame| render(frame, app))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                app.handle_key(key.code);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Leave raw mode and the alternate screen and show the cursor again
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, Show)
+}
+
+/// Restores the terminal when dropped, so an error or a panic in the event loop does not leave
+/// the shell in raw mode
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        enable_raw_mode()?;
+        let guard = Self;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore_terminal();
+    }
+}
+
+/// Browse findings interactively until the user quits
+pub fn run(findings: Vec<Finding>) -> Result<()> {
+    let mut app = App::new(findings);
+
+    // Restore the terminal before the panic message is printed, or it is lost with the
+    // alternate screen
+    let default_hook = std::sync::Arc::new(std::panic::take_hook());
+    let hook = default_hook.clone();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        hook(info);
+    }));
+
+    let result = TerminalGuard::enter().and_then(|_guard| {
+        let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+        event_loop(&mut terminal, &mut app)
+    });
+
+    // Put back the hook that was in place, dropping the one above
+    let _ = std::panic::take_hook();
+    if let Ok(default_hook) = std::sync::Arc::try_unwrap(default_hook) {
+        std::panic::set_hook(default_hook);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    fn finding(title: &str, severity: Severity, category: Category) -> Finding {
+        Finding {
+            id: title.to_lowercase().replace(' ', "-"),
+            title: title.to_string(),
+            description: format!("{} description", title),
+            severity,
+            category,
+            impact: "Some impact".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn app() -> App {
+        App::new(vec![
+            finding("Low disk", Severity::Medium, Category::Capacity),
+            finding("Under-replicated partitions", Severity::Critical, Category::Availability),
+            finding("Plaintext listener", Severity::High, Category::Security),
+            finding("Single voter quorum", Severity::High, Category::Availability),
+        ])
+    }
+
+    #[test]
+    fn test_app_initializes_with_all_findings() {
+        let app = app();
+
+        assert_eq!(app.visible_findings().count(), 4);
+        assert_eq!(app.selected_finding().unwrap().title, "Under-replicated partitions");
+    }
+
+    #[test]
+    fn test_filters_narrow_visible_findings() {
+        let mut app = app();
+
+        app.handle_key(KeyCode::Char('2'));
+        assert_eq!(app.visible_findings().count(), 2);
+
+        app.set_category_filter(Some(Category::Availability));
+        let titles: Vec<&str> = app.visible_findings().map(|f| f.title.as_str()).collect();
+        assert_eq!(titles, vec!["Single voter quorum"]);
+
+        app.set_severity_filter(Some(Severity::Low));
+        assert_eq!(app.visible_findings().count(), 0);
+        assert!(app.selected_finding().is_none());
+
+        app.handle_key(KeyCode::Char('0'));
+        assert_eq!(app.visible_findings().count(), 4);
+    }
+
+    #[test]
+    fn test_cycle_severity_returns_to_all() {
+        let mut app = app();
+
+        for _ in 0..SEVERITIES.len() {
+            app.cycle_severity();
+            assert!(app.severity_filter.is_some());
+        }
+        app.cycle_severity();
+        assert!(app.severity_filter.is_none());
+    }
+
+    #[test]
+    fn test_render_selected_finding() {
+        let mut app = app();
+        app.handle_key(KeyCode::Down);
+        let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+
+        terminal.draw(|frame| render(frame, &mut app)).unwrap();
+
+        let screen: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(screen.contains("4/4 findings"));
+        assert!(screen.contains("Plaintext listener description"));
+    }
+}
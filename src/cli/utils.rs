@@ -1,26 +1,41 @@
-use anyhow::Result;
-use crate::snapshot::format::{Snapshot, SnapshotMetadata, ClusterMode};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use crate::collectors::connect::CONNECT_DATA_KEY;
+use crate::collectors::prometheus_scrape::{PrometheusScrapeOutput, PROMETHEUS_METRICS_KEY};
+use crate::scan::broker_discovery::COLLECTORS_DIR;
+use crate::scan::consumer_lag::CONSUMER_LAG_FILE;
+use crate::snapshot::format::{ClusterMode, CollectionEnvironment, Snapshot, SnapshotMetadata, CONSUMER_LAG_KEY};
+use crate::snapshot::{is_snapshot_file, SnapshotManager};
 use std::fs;
 use std::path::Path;
 use tracing::{info, warn};
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use tracing_subscriber::{fmt, fmt::writer::BoxMakeWriter, prelude::*, EnvFilter};
 use walkdir::WalkDir;
 
-pub fn init_logging(verbose: bool, log_format: &str) {
+/// Set up tracing; logs go to stdout unless `to_stderr` is set
+pub fn init_logging(verbose: bool, log_format: &str, to_stderr: bool) {
     let env_filter = if verbose {
         EnvFilter::new("debug")
     } else {
         EnvFilter::new("info")
     };
 
+    let writer = || if to_stderr {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+
     let fmt_layer = if log_format == "json" {
         fmt::layer()
+            .with_writer(writer())
             .json()
             .with_current_span(false)
             .with_span_list(false)
             .boxed()
     } else {
         fmt::layer()
+            .with_writer(writer())
             .with_target(false)
             .with_thread_ids(false)
             .with_thread_names(false)
@@ -30,6 +45,7 @@ pub fn init_logging(verbose: bool, log_format: &str) {
     tracing_subscriber::registry()
         .with(env_filter)
         .with(fmt_layer)
+        .with(crate::telemetry::otel_layer())
         .try_init()
         .expect("Failed to initialize tracing subscriber");
 }
@@ -89,6 +105,20 @@ pub fn load_directory_recursive(dir: &Path, base_path: &Path) -> Result<serde_js
     Ok(serde_json::Value::Object(result))
 }
 
+/// Load a scan directory, snapshot file (.snapshot or .tar archive) or JSON snapshot
+pub fn load_snapshot(path: &Path) -> Result<Snapshot> {
+    if path.is_dir() {
+        load_snapshot_from_directory(path)
+    } else if is_snapshot_file(path) {
+        Ok(SnapshotManager::new().load_file(path)?)
+    } else {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("{} is not a snapshot", path.display()))
+    }
+}
+
 /// Load snapshot from a scan directory
 pub fn load_snapshot_from_directory(path: &Path) -> Result<Snapshot> {
     info!("\n📂 Loading ALL files from directory: {}", path.display());
@@ -118,6 +148,27 @@ pub fn load_snapshot_from_directory(path: &Path) -> Result<Snapshot> {
                     info!("  • Cluster mode: {}", cluster_mode_str);
                 }
 
+                if let Some(cluster_id) = metadata.get("cluster_id").and_then(|v| v.as_str()) {
+                    snapshot.cluster.id = Some(cluster_id.to_string());
+                    info!("  • Cluster id: {}", cluster_id);
+                }
+
+                // Date the snapshot by the scan rather than by when it was loaded
+                if let Some(scanned_at) = metadata.get("scan_timestamp")
+                    .and_then(|v| v.as_str())
+                    .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+                {
+                    snapshot.timestamp = scanned_at.with_timezone(&Utc);
+                }
+
+                // Keep where and with what the scan ran, falling back to the bastion of older scans
+                snapshot.metadata.environment = metadata.get("environment")
+                    .and_then(|environment| serde_json::from_value(environment.clone()).ok())
+                    .or_else(|| {
+                        let bastion = metadata.get("bastion")?.as_str()?;
+                        Some(CollectionEnvironment { bastion: Some(bastion.to_string()), ..Default::default() })
+                    });
+
                 // Extract other useful metadata
                 if let Some(broker_count) = metadata.get("broker_count").and_then(|v| v.as_u64()) {
                     info!("  • Broker count: {}", broker_count);
@@ -126,6 +177,21 @@ pub fn load_snapshot_from_directory(path: &Path) -> Result<Snapshot> {
         }
     }
 
+    // Check the collected files against the scan manifest, when the scan wrote one
+    match crate::scan::Manifest::load(path) {
+        Ok(Some(manifest)) => {
+            let problems = manifest.verify(path);
+            if problems.is_empty() {
+                info!("✓ All {} files match the scan manifest", manifest.files.len());
+            }
+            for problem in &problems {
+                warn!("  ⚠ {}", problem);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Ignoring unreadable scan manifest: {}", e),
+    }
+
     // Count total files first
     let total_files: usize = WalkDir::new(path)
         .into_iter()
@@ -145,6 +211,7 @@ pub fn load_snapshot_from_directory(path: &Path) -> Result<Snapshot> {
         // For backward compatibility, also extract logs and configs
         let mut logs_data = serde_json::Map::new();
         let mut config_data = serde_json::Map::new();
+        let mut tls_data = serde_json::Map::new();
 
         if let Some(brokers_obj) = brokers_data.as_object() {
             for (broker_name, broker_value) in brokers_obj {
@@ -177,6 +244,13 @@ pub fn load_snapshot_from_directory(path: &Path) -> Result<Snapshot> {
                         }
                     }
 
+                    // Certificate checks of the broker's TLS stores, for the TLS expiry analyzer
+                    if let Some(certificates) = broker_obj.get("system")
+                        .and_then(|system| system.get(crate::scan::tls_certificates::TLS_CERTIFICATES_FILE))
+                    {
+                        tls_data.insert(broker_name.clone(), certificates.clone());
+                    }
+
                     // Check for other data types
                     if broker_obj.contains_key("metrics") {
                         data_summary.has_metrics = true;
@@ -198,6 +272,9 @@ pub fn load_snapshot_from_directory(path: &Path) -> Result<Snapshot> {
         if !config_data.is_empty() {
             snapshot.collectors.config = Some(serde_json::Value::Object(config_data));
         }
+        if !tls_data.is_empty() {
+            snapshot.collectors.custom.insert("tls".to_string(), serde_json::Value::Object(tls_data));
+        }
 
         // Store full brokers data in custom field
         snapshot.collectors.custom.insert("brokers".to_string(), brokers_data);
@@ -209,11 +286,26 @@ pub fn load_snapshot_from_directory(path: &Path) -> Result<Snapshot> {
         info!("\n  📂 Loading cluster/...");
         let cluster_data = load_directory_recursive(&cluster_dir, path)?;
 
+        // Outputs of the optional collectors, under the keys their analyzers read
+        let connect_pointer = format!("/{}/{}.json", COLLECTORS_DIR, CONNECT_DATA_KEY);
+        if let Some(connect) = cluster_data.pointer(&connect_pointer) {
+            snapshot.collectors.custom.insert(CONNECT_DATA_KEY.to_string(), connect.clone());
+        }
+
+        // Consumer group lag summarized from the kafkactl group descriptions
+        let lag_pointer = format!("/kafkactl/{}", CONSUMER_LAG_FILE);
+        if let Some(lag) = cluster_data.pointer(&lag_pointer).filter(|lag| lag.is_array()) {
+            snapshot.collectors.custom.insert(CONSUMER_LAG_KEY.to_string(), lag.clone());
+        }
+
         // Store in custom field
         snapshot.collectors.custom.insert("cluster".to_string(), cluster_data.clone());
 
-        // Also store as admin data since cluster data contains admin information (brokers, topics, etc.)
-        snapshot.collectors.admin = Some(cluster_data.clone());
+        // The admin collector's output is the admin data; scans without it keep the whole
+        // cluster data, which holds the brokers and topics kafkactl collected
+        let admin_pointer = format!("/{}/admin.json", COLLECTORS_DIR);
+        let admin = cluster_data.pointer(&admin_pointer).filter(|admin| admin.is_object());
+        snapshot.collectors.admin = Some(admin.unwrap_or(&cluster_data).clone());
 
         // Check for kafkactl data
         if let Some(cluster_obj) = cluster_data.as_object() {
@@ -235,6 +327,18 @@ pub fn load_snapshot_from_directory(path: &Path) -> Result<Snapshot> {
         data_summary.has_metrics = true;
     }
 
+    // Scraped broker metrics go under `collectors.metrics.prometheus`, next to the bastion's metrics
+    let scraped = path.join("cluster").join(COLLECTORS_DIR).join(format!("{}.json", PROMETHEUS_METRICS_KEY));
+    if let Ok(content) = fs::read_to_string(&scraped) {
+        match serde_json::from_str::<PrometheusScrapeOutput>(&content) {
+            Ok(output) => {
+                output.store_in(&mut snapshot)?;
+                data_summary.has_metrics = true;
+            }
+            Err(e) => warn!("Ignoring unreadable broker metrics {}: {}", scraped.display(), e),
+        }
+    }
+
     // Load system data
     let system_dir = path.join("system");
     if system_dir.exists() {
@@ -245,6 +349,15 @@ pub fn load_snapshot_from_directory(path: &Path) -> Result<Snapshot> {
         data_summary.has_system_info = true;
     }
 
+    // Load cluster feature levels (kafka-features.sh describe)
+    let features_dir = path.join("features");
+    if features_dir.exists() {
+        info!("\n  📂 Loading features/...");
+        let features_data = load_directory_recursive(&features_dir, path)?;
+
+        snapshot.collectors.custom.insert("features".to_string(), features_data);
+    }
+
     // Load any other top-level files
     info!("\n  📂 Loading other files...");
     for entry in fs::read_dir(path)? {
@@ -253,7 +366,11 @@ pub fn load_snapshot_from_directory(path: &Path) -> Result<Snapshot> {
         let file_name = entry.file_name().to_string_lossy().to_string();
 
         // Skip directories we've already processed
-        if entry_path.is_file() && file_name != "scan_metadata.json" {
+        if entry_path.is_file()
+            && file_name != "scan_metadata.json"
+            && file_name != crate::scan::COLLECTION_STATS_FILE
+            && file_name != crate::scan::MANIFEST_FILE
+        {
             if let Ok(content) = fs::read_to_string(&entry_path) {
                 if file_name.ends_with(".json") {
                     if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(&content) {
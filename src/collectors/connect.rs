@@ -0,0 +1,320 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+use super::{scrub_hosts, Collector, CollectorError, CollectorResult, RedactionLevel, REDACTED};
+use crate::snapshot::format::RedactionReport;
+
+/// Key under which Connect data is stored in `CollectorOutputs::custom`
+pub const CONNECT_DATA_KEY: &str = "connect";
+
+/// Configuration for the Kafka Connect REST API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectConfig {
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub timeout_secs: u64,
+}
+
+impl Default for ConnectConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:8083".to_string(),
+            username: None,
+            password: None,
+            timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectCollectorOutput {
+    pub connect_url: String,
+    pub connectors: Vec<ConnectorStatus>,
+}
+
+/// Response of `GET /connectors/{name}/status`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectorStatus {
+    pub name: String,
+    pub connector: ConnectorState,
+    #[serde(default)]
+    pub tasks: Vec<TaskState>,
+    #[serde(rename = "type", default)]
+    pub connector_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectorState {
+    pub state: String,
+    pub worker_id: String,
+    #[serde(default)]
+    pub trace: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskState {
+    pub id: i32,
+    pub state: String,
+    pub worker_id: String,
+    #[serde(default)]
+    pub trace: Option<String>,
+}
+
+impl ConnectorStatus {
+    pub fn is_failed(&self) -> bool {
+        self.connector.state == "FAILED"
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.connector.state == "PAUSED"
+    }
+
+    pub fn failed_tasks(&self) -> impl Iterator<Item = &TaskState> {
+        self.tasks.iter().filter(|t| t.state == "FAILED")
+    }
+}
+
+/// Collector for Kafka Connect connector and task states
+pub struct ConnectCollector;
+
+impl Default for ConnectCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectCollector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn create_client(config: &ConnectConfig) -> CollectorResult<reqwest::Client> {
+        crate::http::client_builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| CollectorError::ConfigurationError(format!("Failed to create HTTP client: {}", e)))
+    }
+
+    async fn get_json<T: for<'de> Deserialize<'de>>(
+        client: &reqwest::Client,
+        config: &ConnectConfig,
+        path: &str,
+    ) -> CollectorResult<T> {
+        let url = format!("{}{}", config.url.trim_end_matches('/'), path);
+        debug!("Requesting {}", url);
+
+        let mut request = client.get(&url);
+        if let Some(username) = &config.username {
+            request = request.basic_auth(username, config.password.as_ref());
+        }
+
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                CollectorError::Timeout(config.timeout_secs)
+            } else {
+                CollectorError::ConnectionFailed(format!("{}: {}", url, e))
+            }
+        })?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(CollectorError::AuthenticationFailed(format!("{} returned {}", url, status)));
+        }
+        if !status.is_success() {
+            return Err(CollectorError::Other(format!("{} returned {}", url, status)));
+        }
+
+        let body = response.text().await
+            .map_err(|e| CollectorError::ConnectionFailed(format!("{}: {}", url, e)))?;
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+#[async_trait]
+impl Collector for ConnectCollector {
+    type Config = ConnectConfig;
+    type Output = ConnectCollectorOutput;
+
+    async fn collect(&self, config: &Self::Config) -> CollectorResult<Self::Output> {
+        info!("Starting Kafka Connect data collection from {}", config.url);
+        self.validate_config(config)?;
+
+        let client = Self::create_client(config)?;
+        let names: Vec<String> = Self::get_json(&client, config, "/connectors").await
+            .inspect_err(|e| error!("Failed to list connectors: {}", e))?;
+
+        let mut connectors = Vec::new();
+        for name in names {
+            match Self::get_json::<ConnectorStatus>(&client, config, &format!("/connectors/{}/status", name)).await {
+                Ok(status) => connectors.push(status),
+                Err(e) => warn!("Failed to get status of connector {}: {}", name, e),
+            }
+        }
+
+        info!("Collected status of {} connectors", connectors.len());
+        Ok(ConnectCollectorOutput {
+            connect_url: config.url.clone(),
+            connectors,
+        })
+    }
+
+    fn redact(&self, mut output: Self::Output, level: RedactionLevel, report: &mut RedactionReport) -> Self::Output {
+        if level == RedactionLevel::None {
+            return output;
+        }
+        let aggressive = level == RedactionLevel::Aggressive;
+        if aggressive {
+            if let Some(scrubbed) = scrub_hosts(&output.connect_url) {
+                output.connect_url = scrubbed;
+                report.record("connect.connect_url");
+            }
+        }
+
+        for connector in &mut output.connectors {
+            let traces = std::iter::once(&mut connector.connector.trace)
+                .chain(connector.tasks.iter_mut().map(|t| &mut t.trace));
+            for trace in traces.flatten() {
+                if trace.contains("password") {
+                    *trace = REDACTED.to_string();
+                    report.record(format!("connect.{}.trace", connector.name));
+                } else if let Some(scrubbed) = aggressive.then(|| scrub_hosts(trace)).flatten() {
+                    *trace = scrubbed;
+                    report.record(format!("connect.{}.trace.host", connector.name));
+                }
+            }
+
+            if aggressive {
+                let workers = std::iter::once(&mut connector.connector.worker_id)
+                    .chain(connector.tasks.iter_mut().map(|t| &mut t.worker_id));
+                for worker_id in workers {
+                    if let Some(scrubbed) = scrub_hosts(worker_id) {
+                        *worker_id = scrubbed;
+                        report.record(format!("connect.{}.worker_id", connector.name));
+                    }
+                }
+            }
+        }
+        output
+    }
+
+    fn name(&self) -> &'static str {
+        "ConnectCollector"
+    }
+
+    fn validate_config(&self, config: &Self::Config) -> CollectorResult<()> {
+        if !config.url.starts_with("http://") && !config.url.starts_with("https://") {
+            return Err(CollectorError::ConfigurationError(
+                format!("Connect URL must start with http:// or https://, got '{}'", config.url)
+            ));
+        }
+
+        if config.password.is_some() && config.username.is_none() {
+            return Err(CollectorError::ConfigurationError(
+                "Connect password given without a username".to_string()
+            ));
+        }
+
+        if config.timeout_secs == 0 {
+            return Err(CollectorError::ConfigurationError(
+                "Timeout must be greater than 0".to_string()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_STATUS: &str = r#"{
+        "name": "jdbc-sink",
+        "connector": {"state": "RUNNING", "worker_id": "10.0.0.5:8083"},
+        "tasks": [
+            {"id": 0, "state": "RUNNING", "worker_id": "10.0.0.5:8083"},
+            {"id": 1, "state": "FAILED", "worker_id": "10.0.0.6:8083",
+             "trace": "org.apache.kafka.connect.errors.ConnectException: connection refused"}
+        ],
+        "type": "sink"
+    }"#;
+
+    #[test]
+    fn test_parse_connector_status() {
+        let status: ConnectorStatus = serde_json::from_str(SAMPLE_STATUS).unwrap();
+
+        assert_eq!(status.name, "jdbc-sink");
+        assert_eq!(status.connector_type.as_deref(), Some("sink"));
+        assert!(!status.is_failed());
+        assert!(!status.is_paused());
+
+        let failed: Vec<_> = status.failed_tasks().collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].id, 1);
+        assert!(failed[0].trace.as_deref().unwrap().contains("ConnectException"));
+    }
+
+    #[test]
+    fn test_parse_paused_connector_without_tasks() {
+        let status: ConnectorStatus = serde_json::from_str(
+            r#"{"name": "s3-sink", "connector": {"state": "PAUSED", "worker_id": "w1:8083"}}"#
+        ).unwrap();
+
+        assert!(status.is_paused());
+        assert!(status.tasks.is_empty());
+        assert!(status.connector_type.is_none());
+    }
+
+    #[test]
+    fn test_redact_traces() {
+        let mut status: ConnectorStatus = serde_json::from_str(SAMPLE_STATUS).unwrap();
+        status.tasks[1].trace = Some("Login failed for password=hunter2".to_string());
+        let output = ConnectCollectorOutput {
+            connect_url: "http://localhost:8083".to_string(),
+            connectors: vec![status],
+        };
+
+        let mut report = RedactionReport::default();
+        let redacted = ConnectCollector::new().redact(output.clone(), RedactionLevel::Standard, &mut report);
+        assert_eq!(redacted.connectors[0].tasks[1].trace.as_deref(), Some(REDACTED));
+        assert_eq!(report.fields_redacted, vec!["connect.jdbc-sink.trace"]);
+
+        let untouched = ConnectCollector::new().redact(output, RedactionLevel::None, &mut RedactionReport::default());
+        assert!(untouched.connectors[0].tasks[1].trace.as_deref().unwrap().contains("hunter2"));
+    }
+
+    #[test]
+    fn test_aggressive_redaction_scrubs_workers() {
+        let status: ConnectorStatus = serde_json::from_str(SAMPLE_STATUS).unwrap();
+        let output = ConnectCollectorOutput {
+            connect_url: "http://connect-1.prod.internal:8083".to_string(),
+            connectors: vec![status],
+        };
+
+        let standard = ConnectCollector::new()
+            .redact(output.clone(), RedactionLevel::Standard, &mut RedactionReport::default());
+        assert_eq!(standard.connect_url, output.connect_url);
+        assert_eq!(standard.connectors[0].connector.worker_id, output.connectors[0].connector.worker_id);
+
+        let aggressive = ConnectCollector::new()
+            .redact(output, RedactionLevel::Aggressive, &mut RedactionReport::default());
+        assert_eq!(aggressive.connect_url, "http://redacted-host:8083");
+        assert_eq!(aggressive.connectors[0].connector.worker_id, "10.0.0.xxx:8083");
+        assert_eq!(aggressive.connectors[0].tasks[1].worker_id, "10.0.0.xxx:8083");
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let collector = ConnectCollector::new();
+        assert!(collector.validate_config(&ConnectConfig::default()).is_ok());
+
+        let bad_url = ConnectConfig { url: "localhost:8083".to_string(), ..Default::default() };
+        assert!(collector.validate_config(&bad_url).is_err());
+
+        let password_only = ConnectConfig { password: Some("secret".to_string()), ..Default::default() };
+        assert!(collector.validate_config(&password_only).is_err());
+    }
+}
@@ -1,10 +1,13 @@
-use super::{Collector, CollectorError, CollectorResult, KafkaConfig};
+use super::{scrub_hosts, Collector, CollectorError, CollectorResult, KafkaConfig, RedactionLevel};
+use crate::snapshot::format::RedactionReport;
 use async_trait::async_trait;
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use tracing::{debug, info, warn};
 
 /// Log collector for gathering Kafka broker and application logs
@@ -195,6 +198,72 @@ pub struct LogSummary {
     pub files_processed: usize,
     pub patterns_matched: HashMap<String, usize>,
     pub top_errors: Vec<ErrorSummary>,
+    /// Error and fatal lines per known failure category; uncategorized errors are not listed
+    #[serde(default)]
+    pub error_categories: BTreeMap<ErrorCategory, usize>,
+}
+
+/// Known kinds of Kafka broker failures an error line can point to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    DiskFull,
+    LeaderElection,
+    NetworkTimeout,
+    AuthFailure,
+    CorruptSegment,
+}
+
+impl ErrorCategory {
+    pub fn label(self) -> &'static str {
+        match self {
+            ErrorCategory::DiskFull => "disk full",
+            ErrorCategory::LeaderElection => "leader election",
+            ErrorCategory::NetworkTimeout => "network timeout",
+            ErrorCategory::AuthFailure => "authentication failure",
+            ErrorCategory::CorruptSegment => "corrupt log segment",
+        }
+    }
+}
+
+/// Case-insensitive patterns per category, tried in order so the most specific category wins:
+/// a corrupt segment found while the disk filled up is reported as corruption
+const ERROR_CATEGORY_PATTERNS: [(ErrorCategory, &str); 5] = [
+    (
+        ErrorCategory::CorruptSegment,
+        r"CorruptRecordException|InvalidRecordException|CorruptIndexException|corrupt(ed)? index|invalid messages in log segment|record is corrupt|checksum",
+    ),
+    (
+        ErrorCategory::DiskFull,
+        r"No space left on device|disk (is )?full|KafkaStorageException|offline log dir",
+    ),
+    (
+        ErrorCategory::AuthFailure,
+        r"AuthenticationException|AuthorizationException|authentication failed|SSL handshake failed|not authorized|invalid (username or )?password",
+    ),
+    (
+        ErrorCategory::LeaderElection,
+        r"NotLeaderOrFollowerException|NotLeaderForPartitionException|LeaderNotAvailableException|leader election|no leader|NotControllerException",
+    ),
+    (
+        ErrorCategory::NetworkTimeout,
+        r"TimeoutException|timed out|Connection refused|DisconnectException|could not be established|was disconnected",
+    ),
+];
+
+fn category_regexes() -> &'static [(ErrorCategory, Regex); 5] {
+    static REGEXES: OnceLock<[(ErrorCategory, Regex); 5]> = OnceLock::new();
+    REGEXES.get_or_init(|| ERROR_CATEGORY_PATTERNS.map(|(category, pattern)| {
+        let regex = RegexBuilder::new(pattern).case_insensitive(true).build().expect("valid regex");
+        (category, regex)
+    }))
+}
+
+/// Bucket a log line into the first known failure category it matches
+pub fn classify_error(line: &str) -> Option<ErrorCategory> {
+    category_regexes().iter()
+        .find(|(_, regex)| regex.is_match(line))
+        .map(|(category, _)| *category)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -243,6 +312,7 @@ impl Collector for LogCollector {
         let mut warn_count = 0;
         let mut files_processed = 0;
         let mut pattern_counts = HashMap::new();
+        let mut error_categories = BTreeMap::new();
         
         // Determine which paths to use
         let default_paths;
@@ -270,7 +340,12 @@ impl Collector for LogCollector {
                         for entry in &entries {
                             total_entries += 1;
                             match entry.level {
-                                LogLevel::Error | LogLevel::Fatal => error_count += 1,
+                                LogLevel::Error | LogLevel::Fatal => {
+                                    error_count += 1;
+                                    if let Some(category) = classify_error(&entry.raw) {
+                                        *error_categories.entry(category).or_insert(0) += 1;
+                                    }
+                                }
                                 LogLevel::Warn => warn_count += 1,
                                 _ => {}
                             }
@@ -344,7 +419,7 @@ impl Collector for LogCollector {
         }
         
         // Sort by count descending
-        error_summaries.sort_by(|a, b| b.count.cmp(&a.count));
+        error_summaries.sort_by_key(|e| std::cmp::Reverse(e.count));
         error_summaries.truncate(10); // Keep top 10
         
         let summary = LogSummary {
@@ -354,6 +429,7 @@ impl Collector for LogCollector {
             files_processed,
             patterns_matched: pattern_counts,
             top_errors: error_summaries,
+            error_categories,
         };
         
         let output = LogCollectorOutput {
@@ -368,34 +444,31 @@ impl Collector for LogCollector {
         Ok(output)
     }
 
-    fn redact(&self, mut output: Self::Output) -> Self::Output {
-        // Redact sensitive information from log messages
-        for logs in output.logs.values_mut() {
+    fn redact(&self, mut output: Self::Output, level: RedactionLevel, report: &mut RedactionReport) -> Self::Output {
+        if level == RedactionLevel::None {
+            return output;
+        }
+        for (source, logs) in output.logs.iter_mut() {
             for entry in logs {
-                // Redact IP addresses (keep first 3 octets)
-                entry.message = entry.message
-                    .split_whitespace()
-                    .map(|word| {
-                        if word.parse::<std::net::IpAddr>().is_ok() {
-                            let parts: Vec<&str> = word.split('.').collect();
-                            if parts.len() == 4 {
-                                format!("{}.{}.{}.xxx", parts[0], parts[1], parts[2])
-                            } else {
-                                word.to_string()
-                            }
-                        } else {
-                            word.to_string()
-                        }
-                    })
-                    .collect::<Vec<_>>()
-                    .join(" ");
-                
+                // Hostnames and IP addresses only at the aggressive level
+                if level == RedactionLevel::Aggressive {
+                    if let Some(scrubbed) = scrub_hosts(&entry.message) {
+                        entry.message = scrubbed;
+                        entry.raw = scrub_hosts(&entry.raw).unwrap_or_else(|| entry.raw.clone());
+                        report.record(format!("logs.{}.message.host", source));
+                    }
+                }
+
                 // Redact potential passwords or tokens
                 if entry.message.to_lowercase().contains("password") {
-                    entry.message = entry.message
-                        .chars()
-                        .map(|c| if c.is_alphanumeric() { '*' } else { c })
-                        .collect();
+                    let mask = |text: &str| -> String {
+                        text.chars()
+                            .map(|c| if c.is_alphanumeric() { '*' } else { c })
+                            .collect()
+                    };
+                    entry.message = mask(&entry.message);
+                    entry.raw = mask(&entry.raw);
+                    report.record(format!("logs.{}.message.password", source));
                 }
             }
         }
@@ -412,3 +485,79 @@ impl Collector for LogCollector {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output_with_message(message: &str) -> LogCollectorOutput {
+        let entry = LogEntry {
+            timestamp: "2024-01-15 10:00:00,000".to_string(),
+            level: LogLevel::Warn,
+            source: "server.log".to_string(),
+            message: message.to_string(),
+            raw: format!("[2024-01-15 10:00:00,000] WARN {}", message),
+        };
+        LogCollectorOutput {
+            logs: HashMap::from([("server".to_string(), vec![entry])]),
+            summary: LogSummary {
+                total_entries: 1,
+                error_count: 0,
+                warn_count: 1,
+                files_processed: 1,
+                patterns_matched: HashMap::new(),
+                top_errors: Vec::new(),
+                error_categories: BTreeMap::new(),
+            },
+            collection_timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_classify_error_lines() {
+        let lines = [
+            ("[2024-01-15 10:00:00,000] ERROR Error while appending records to orders-0 in dir /var/lib/kafka (kafka.server.LogDirFailureChannel)\njava.io.IOException: No space left on device", ErrorCategory::DiskFull),
+            ("[2024-01-15 10:00:00,000] ERROR [ReplicaFetcher replicaId=1, leaderId=2, fetcherId=0] Error for partition orders-3 at offset 120 (kafka.server.ReplicaFetcherThread) org.apache.kafka.common.errors.NotLeaderOrFollowerException", ErrorCategory::LeaderElection),
+            ("[2024-01-15 10:00:00,000] ERROR [Controller id=1] Connection to node 3 (kafka-3/10.0.0.3:9092) could not be established. Broker may not be available.", ErrorCategory::NetworkTimeout),
+            ("[2024-01-15 10:00:00,000] ERROR [ReplicaFetcher replicaId=1] Error sending fetch request: org.apache.kafka.common.errors.TimeoutException: Request timed out", ErrorCategory::NetworkTimeout),
+            ("[2024-01-15 10:00:00,000] ERROR [SocketServer listenerType=ZK_BROKER, nodeId=1] Failed authentication with /10.0.0.9 (Authentication failed: Invalid username or password)", ErrorCategory::AuthFailure),
+            ("[2024-01-15 10:00:00,000] ERROR Found invalid messages in log segment /var/lib/kafka/orders-0/00000000000000000000.log at byte offset 4096: CorruptRecordException", ErrorCategory::CorruptSegment),
+            // Corruption takes precedence over the storage error that reports it
+            ("[2024-01-15 10:00:00,000] ERROR KafkaStorageException: Corrupt index found, index file (/var/lib/kafka/orders-0/00000000000000000000.index)", ErrorCategory::CorruptSegment),
+        ];
+
+        for (line, expected) in lines {
+            assert_eq!(classify_error(line), Some(expected), "{}", line);
+        }
+        assert_eq!(classify_error("[2024-01-15 10:00:00,000] ERROR Unexpected error in request handler"), None);
+    }
+
+    #[test]
+    fn test_aggressive_redaction_scrubs_hosts() {
+        let output = output_with_message("Connection to node 2 (kafka-2.prod.internal/10.0.12.34:9092) could not be established");
+
+        let standard = LogCollector::new()
+            .redact(output.clone(), RedactionLevel::Standard, &mut RedactionReport::default());
+        assert_eq!(standard.logs["server"][0].message, output.logs["server"][0].message);
+
+        let mut report = RedactionReport::default();
+        let aggressive = LogCollector::new().redact(output, RedactionLevel::Aggressive, &mut report);
+        let entry = &aggressive.logs["server"][0];
+        assert_eq!(entry.message, "Connection to node 2 (redacted-host/10.0.12.xxx:9092) could not be established");
+        assert!(!entry.raw.contains("10.0.12.34"));
+        assert_eq!(report.fields_redacted, vec!["logs.server.message.host"]);
+    }
+
+    #[test]
+    fn test_password_redacted_unless_disabled() {
+        let output = output_with_message("Invalid password for user admin");
+
+        let redacted = LogCollector::new()
+            .redact(output.clone(), RedactionLevel::Standard, &mut RedactionReport::default());
+        assert!(!redacted.logs["server"][0].message.contains("admin"));
+        assert!(!redacted.logs["server"][0].raw.contains("admin"));
+
+        let untouched = LogCollector::new().redact(output, RedactionLevel::None, &mut RedactionReport::default());
+        assert_eq!(untouched.logs["server"][0].message, "Invalid password for user admin");
+    }
+}
@@ -1,9 +1,200 @@
 pub mod admin;
+pub mod connect;
 pub mod logs;
+pub mod prometheus_scrape;
 
 use async_trait::async_trait;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+use crate::snapshot::format::{RedactionReport, Snapshot};
+use admin::AdminCollector;
+use connect::{ConnectCollector, ConnectConfig};
+use logs::{LogCollector, LogConfig};
+use prometheus_scrape::{PrometheusScrapeCollector, PrometheusScrapeConfig};
+
+/// Replacement written over redacted values
+pub const REDACTED: &str = "*** REDACTED ***";
+
+/// How much of the collected data `Collector::redact` scrubs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionLevel {
+    /// Keep everything as collected, including credentials
+    None,
+    /// Scrub credentials, keys and passwords
+    #[default]
+    Standard,
+    /// Also scrub hostnames, IP addresses and topic names matching sensitive patterns
+    Aggressive,
+}
+
+/// Replacement written over hostnames at `RedactionLevel::Aggressive`
+pub const REDACTED_HOST: &str = "redacted-host";
+
+fn host_regexes() -> &'static [Regex; 4] {
+    static REGEXES: OnceLock<[Regex; 4]> = OnceLock::new();
+    REGEXES.get_or_init(|| [
+        // IPv4 address, masked down to its /24 network: "10.0.12.34" -> "10.0.12.xxx"
+        Regex::new(r"\b(\d{1,3})\.(\d{1,3})\.(\d{1,3})\.\d{1,3}\b").expect("valid regex"),
+        // Host of a URL: "http://connect-1.internal/connectors"
+        Regex::new(r"://[A-Za-z][A-Za-z0-9.-]*").expect("valid regex"),
+        // Hostname with a port: "kafka-1.prod.internal:9092"
+        Regex::new(r"\b[A-Za-z][A-Za-z0-9-]*(?:\.[A-Za-z0-9-]+)*:(\d{2,5})\b").expect("valid regex"),
+        // Resolved hostname as the Kafka client logs it: "kafka-1.prod.internal/10.0.12.34"
+        Regex::new(r"\b[A-Za-z][A-Za-z0-9-]*(?:\.[A-Za-z0-9-]+)*/(\d{1,3}(?:\.\d{1,3}){3})\b").expect("valid regex"),
+    ])
+}
+
+/// Mask IPv4 addresses and replace hostnames given with a port or in a URL.
+/// Returns `None` when the text holds neither.
+pub fn scrub_hosts(text: &str) -> Option<String> {
+    let [ip, url, host_port, resolved] = host_regexes();
+    // Hostnames first, so the masked "xxx" of an address is not taken for one
+    let scrubbed = url.replace_all(text, format!("://{}", REDACTED_HOST));
+    let scrubbed = host_port.replace_all(&scrubbed, format!("{}:$1", REDACTED_HOST));
+    let scrubbed = resolved.replace_all(&scrubbed, format!("{}/$1", REDACTED_HOST));
+    let scrubbed = ip.replace_all(&scrubbed, "$1.$2.$3.xxx");
+    (scrubbed != text).then(|| scrubbed.into_owned())
+}
+
+fn secret_regexes() -> &'static [Regex; 2] {
+    static REGEXES: OnceLock<[Regex; 2]> = OnceLock::new();
+    REGEXES.get_or_init(|| [
+        // Property named like a credential: "ssl.keystore.password=changeit", "sasl.jaas.config=..."
+        Regex::new(r"(?im)^(\s*[\w.-]*(?:password|secret|jaas\.config)[\w.-]*\s*[=:]\s*)\S.*?\s*$").expect("valid regex"),
+        // JAAS option within a line: password="secret"
+        Regex::new(r#"(?i)\b(password|secret)\s*=\s*"[^"]*""#).expect("valid regex"),
+    ])
+}
+
+/// Replace the values of properties and JAAS options named like credentials.
+/// Returns `None` when the text holds none.
+pub fn scrub_secrets(text: &str) -> Option<String> {
+    let [property, jaas_option] = secret_regexes();
+    let scrubbed = jaas_option.replace_all(text, format!("$1=\"{}\"", REDACTED));
+    let scrubbed = property.replace_all(&scrubbed, format!("${{1}}{}", REDACTED));
+    (scrubbed != text).then(|| scrubbed.into_owned())
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    ["password", "secret", "jaas.config"].iter().any(|name| key.contains(name))
+}
+
+/// Scrub credentials, and hostnames at `RedactionLevel::Aggressive`, from every string in a
+/// collector output regardless of its structure, recording each field under `path`
+fn scrub_value(value: &mut serde_json::Value, path: &str, level: RedactionLevel, report: &mut RedactionReport) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                let field_path = format!("{}.{}", path, key);
+                if is_secret_key(key) && field.as_str().is_some_and(|value| value != REDACTED) {
+                    *field = serde_json::Value::String(REDACTED.to_string());
+                    report.record(field_path);
+                } else {
+                    scrub_value(field, &field_path, level, report);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                scrub_value(item, path, level, report);
+            }
+        }
+        serde_json::Value::String(text) => {
+            if let Some(scrubbed) = scrub_secrets(text) {
+                *text = scrubbed;
+                report.record(path);
+            }
+            if level == RedactionLevel::Aggressive {
+                if let Some(scrubbed) = scrub_hosts(text) {
+                    *text = scrubbed;
+                    report.record(format!("{}.host", path));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Redact a JSON collector output with `collector`'s rules. Outputs that do not parse as the
+/// collector's output are returned unchanged.
+fn redact_output<C: Collector>(collector: &C, output: serde_json::Value, level: RedactionLevel, report: &mut RedactionReport) -> serde_json::Value {
+    match serde_json::from_value::<C::Output>(output.clone()) {
+        Ok(typed) => serde_json::to_value(collector.redact(typed, level, report)).unwrap_or(output),
+        Err(_) => output,
+    }
+}
+
+/// Redact the output of the default collector named `name` with that collector's rules
+fn redact_named_output(name: &str, output: serde_json::Value, level: RedactionLevel, report: &mut RedactionReport) -> serde_json::Value {
+    match name {
+        "admin" => redact_output(&AdminCollector::new(), output, level, report),
+        "logs" => redact_output(&LogCollector::with_dynamic_discovery(), output, level, report),
+        connect::CONNECT_DATA_KEY => redact_output(&ConnectCollector::new(), output, level, report),
+        prometheus_scrape::PROMETHEUS_METRICS_KEY => redact_output(&PrometheusScrapeCollector::new(), output, level, report),
+        _ => output,
+    }
+}
+
+/// Redact every collector output of an existing snapshot at `level`, e.g. one captured with
+/// redaction disabled. Outputs of the default collectors get their own rules first; then
+/// credentials in any output, such as passwords in collected configuration files, and at
+/// `RedactionLevel::Aggressive` hostnames, are scrubbed. The redactions are recorded in the
+/// snapshot's metadata and returned.
+pub fn redact_snapshot(snapshot: &mut Snapshot, level: RedactionLevel) -> RedactionReport {
+    let mut report = RedactionReport::default();
+    if level == RedactionLevel::None {
+        return report;
+    }
+
+    let outputs = &mut snapshot.collectors;
+    for (name, output) in [("admin", &mut outputs.admin), ("logs", &mut outputs.logs)] {
+        if let Some(output) = output.as_mut() {
+            *output = redact_named_output(name, output.take(), level, &mut report);
+        }
+    }
+    if let Some(metrics) = outputs.metrics.as_mut().and_then(|metrics| metrics.get_mut(prometheus_scrape::PROMETHEUS_METRICS_KEY)) {
+        *metrics = redact_named_output(prometheus_scrape::PROMETHEUS_METRICS_KEY, metrics.take(), level, &mut report);
+    }
+    if let Some(output) = outputs.custom.get_mut(connect::CONNECT_DATA_KEY) {
+        *output = redact_named_output(connect::CONNECT_DATA_KEY, output.take(), level, &mut report);
+    }
+    // Outputs a scan saved as cluster/collectors/<name>.json
+    if let Some(saved) = outputs.custom.get_mut("cluster")
+        .and_then(|cluster| cluster.get_mut("collectors"))
+        .and_then(|collectors| collectors.as_object_mut())
+    {
+        for (file, output) in saved.iter_mut() {
+            *output = redact_named_output(file.trim_end_matches(".json"), output.take(), level, &mut report);
+        }
+    }
+
+    for (name, output) in [
+        ("admin", outputs.admin.as_mut()),
+        ("logs", outputs.logs.as_mut()),
+        ("config", outputs.config.as_mut()),
+        ("metrics", outputs.metrics.as_mut()),
+        ("cloud", outputs.cloud.as_mut()),
+    ] {
+        if let Some(output) = output {
+            scrub_value(output, name, level, &mut report);
+        }
+    }
+    for (name, output) in outputs.custom.iter_mut() {
+        scrub_value(output, name, level, &mut report);
+    }
+
+    if !report.is_empty() {
+        snapshot.metadata.record_redactions(report.clone());
+    }
+    report
+}
 
 /// Result type for collector operations
 pub type CollectorResult<T> = Result<T, CollectorError>;
@@ -45,8 +236,8 @@ pub trait Collector: Send + Sync {
     /// Collect data from the source
     async fn collect(&self, config: &Self::Config) -> CollectorResult<Self::Output>;
     
-    /// Redact sensitive information from the output
-    fn redact(&self, output: Self::Output) -> Self::Output;
+    /// Redact sensitive information from the output at `level`, recording each redacted field in `report`
+    fn redact(&self, output: Self::Output, level: RedactionLevel, report: &mut RedactionReport) -> Self::Output;
     
     /// Get collector name
     fn name(&self) -> &'static str;
@@ -87,16 +278,87 @@ impl Default for KafkaConfig {
     }
 }
 
+impl KafkaConfig {
+    /// Remove credentials, recording each removed field under `prefix`
+    pub fn redact_credentials(&mut self, prefix: &str, report: &mut RedactionReport) {
+        for (name, value) in [
+            ("sasl_password", &mut self.sasl_password),
+            ("ssl_key", &mut self.ssl_key),
+        ] {
+            if let Some(value) = value.as_mut().filter(|v| v.as_str() != REDACTED) {
+                *value = REDACTED.to_string();
+                report.record(format!("{}.{}", prefix, name));
+            }
+        }
+    }
+}
+
 /// Context for collection operations
 #[derive(Debug, Clone)]
 pub struct CollectionContext {
     pub kafka_config: KafkaConfig,
     pub metadata: HashMap<String, String>,
     pub dry_run: bool,
+    /// How much `CollectorRegistry::redact` scrubs from collector outputs
+    pub redaction_level: RedactionLevel,
 }
 
+/// Context metadata key holding a comma-separated list of log files for the log collector
+pub const LOG_PATHS_METADATA_KEY: &str = "log_paths";
+
+/// Context metadata key holding the Kafka Connect REST URL; the Connect collector only runs when set
+pub const CONNECT_URL_METADATA_KEY: &str = "connect_url";
+
+/// Context metadata key holding the brokers' Prometheus metrics port; metrics are only scraped when set
+pub const METRICS_PORT_METADATA_KEY: &str = "metrics_port";
+
 /// Type alias for boxed collectors to simplify type signatures
-pub type BoxedCollector = Box<dyn Collector<Config = KafkaConfig, Output = serde_json::Value>>;
+pub type BoxedCollector = Box<dyn Collector<Config = CollectionContext, Output = serde_json::Value>>;
+
+/// Adapts a typed collector to the registry: its config is derived from the
+/// `CollectionContext` and its output is serialized to JSON
+pub struct ContextCollector<C, F> {
+    inner: C,
+    config: F,
+}
+
+impl<C, F> ContextCollector<C, F>
+where
+    C: Collector,
+    F: Fn(&CollectionContext) -> C::Config + Send + Sync,
+{
+    pub fn new(inner: C, config: F) -> Self {
+        Self { inner, config }
+    }
+}
+
+#[async_trait]
+impl<C, F> Collector for ContextCollector<C, F>
+where
+    C: Collector,
+    F: Fn(&CollectionContext) -> C::Config + Send + Sync,
+{
+    type Config = CollectionContext;
+    type Output = serde_json::Value;
+
+    async fn collect(&self, ctx: &Self::Config) -> CollectorResult<Self::Output> {
+        let config = (self.config)(ctx);
+        let output = self.inner.collect(&config).await?;
+        Ok(serde_json::to_value(output)?)
+    }
+
+    fn redact(&self, output: Self::Output, level: RedactionLevel, report: &mut RedactionReport) -> Self::Output {
+        redact_output(&self.inner, output, level, report)
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn validate_config(&self, ctx: &Self::Config) -> CollectorResult<()> {
+        self.inner.validate_config(&(self.config)(ctx))
+    }
+}
 
 /// Registry for collectors
 pub struct CollectorRegistry {
@@ -127,4 +389,268 @@ impl CollectorRegistry {
     pub fn list(&self) -> Vec<String> {
         self.collectors.keys().cloned().collect()
     }
+
+    /// Register the admin and log collectors, plus the optional collectors enabled in `ctx.metadata`
+    pub fn register_default_collectors(&mut self, ctx: &CollectionContext) {
+        self.register_admin_collector();
+
+        self.register(
+            "logs".to_string(),
+            Box::new(ContextCollector::new(LogCollector::with_dynamic_discovery(), |ctx: &CollectionContext| {
+                LogConfig {
+                    kafka_config: ctx.kafka_config.clone(),
+                    log_paths: ctx.metadata.get(LOG_PATHS_METADATA_KEY)
+                        .map(|paths| paths.split(',')
+                            .map(str::trim)
+                            .filter(|p| !p.is_empty())
+                            .map(PathBuf::from)
+                            .collect())
+                        .unwrap_or_default(),
+                    remote_logs: None,
+                }
+            })),
+        );
+
+        self.register_optional_collectors(ctx);
+    }
+
+    /// Register the admin collector, reading cluster metadata through `ctx.kafka_config`
+    pub fn register_admin_collector(&mut self) {
+        self.register(
+            "admin".to_string(),
+            Box::new(ContextCollector::new(AdminCollector::new(), |ctx: &CollectionContext| {
+                ctx.kafka_config.clone()
+            })),
+        );
+    }
+
+    /// Register the collectors that only run when `ctx.metadata` enables them: Kafka Connect
+    /// with `CONNECT_URL_METADATA_KEY` and Prometheus scraping with `METRICS_PORT_METADATA_KEY`
+    pub fn register_optional_collectors(&mut self, ctx: &CollectionContext) {
+        if ctx.metadata.contains_key(CONNECT_URL_METADATA_KEY) {
+            self.register(
+                connect::CONNECT_DATA_KEY.to_string(),
+                Box::new(ContextCollector::new(ConnectCollector::new(), |ctx: &CollectionContext| {
+                    ConnectConfig {
+                        url: ctx.metadata.get(CONNECT_URL_METADATA_KEY).cloned().unwrap_or_default(),
+                        ..Default::default()
+                    }
+                })),
+            );
+        }
+
+        if ctx.metadata.contains_key(METRICS_PORT_METADATA_KEY) {
+            self.register(
+                prometheus_scrape::PROMETHEUS_METRICS_KEY.to_string(),
+                Box::new(ContextCollector::new(PrometheusScrapeCollector::new(), |ctx: &CollectionContext| {
+                    let defaults = PrometheusScrapeConfig::default();
+                    PrometheusScrapeConfig {
+                        hosts: ctx.kafka_config.bootstrap_servers.iter()
+                            .map(|server| server.rsplit_once(':').map_or(server.as_str(), |(host, _)| host).to_string())
+                            .collect(),
+                        port: ctx.metadata.get(METRICS_PORT_METADATA_KEY)
+                            .and_then(|port| port.parse().ok())
+                            .unwrap_or(defaults.port),
+                        use_tls: ctx.kafka_config.security_protocol.contains("SSL"),
+                        ca_cert: ctx.kafka_config.ssl_ca_cert.clone(),
+                        ..defaults
+                    }
+                })),
+            );
+        }
+    }
+
+    /// Redact the output of the collector registered as `name` at `ctx.redaction_level`.
+    /// Outputs of unknown collectors are returned unchanged.
+    pub fn redact(&self, name: &str, output: serde_json::Value, ctx: &CollectionContext, report: &mut RedactionReport) -> serde_json::Value {
+        match self.collectors.get(name) {
+            Some(collector) => collector.redact(output, ctx.redaction_level, report),
+            None => output,
+        }
+    }
+
+    /// Run every registered collector and return each one's outcome by name, so a
+    /// failing collector does not hide the others' results. Outputs are not redacted;
+    /// use `redact` before storing them.
+    /// In dry-run mode collectors only validate their config and produce `null`.
+    pub async fn run_all(&self, ctx: &CollectionContext) -> HashMap<String, CollectorResult<serde_json::Value>> {
+        let mut names: Vec<&String> = self.collectors.keys().collect();
+        names.sort();
+
+        let mut results = HashMap::new();
+        for name in names {
+            let collector = &self.collectors[name];
+            let result = if ctx.dry_run {
+                collector.validate_config(ctx).map(|_| serde_json::Value::Null)
+            } else {
+                collector.collect(ctx).await
+            };
+
+            match &result {
+                Ok(_) => info!("Collector '{}' ({}) succeeded", name, collector.name()),
+                Err(e) => warn!("Collector '{}' ({}) failed: {}", name, collector.name(), e),
+            }
+            results.insert(name.clone(), result);
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct StaticCollector(serde_json::Value);
+
+    #[async_trait]
+    impl Collector for StaticCollector {
+        type Config = CollectionContext;
+        type Output = serde_json::Value;
+
+        async fn collect(&self, _ctx: &Self::Config) -> CollectorResult<Self::Output> {
+            Ok(self.0.clone())
+        }
+
+        fn redact(&self, output: Self::Output, _level: RedactionLevel, _report: &mut RedactionReport) -> Self::Output {
+            output
+        }
+
+        fn name(&self) -> &'static str {
+            "StaticCollector"
+        }
+
+        fn validate_config(&self, _ctx: &Self::Config) -> CollectorResult<()> {
+            Ok(())
+        }
+    }
+
+    struct FailingCollector;
+
+    #[async_trait]
+    impl Collector for FailingCollector {
+        type Config = CollectionContext;
+        type Output = serde_json::Value;
+
+        async fn collect(&self, _ctx: &Self::Config) -> CollectorResult<Self::Output> {
+            Err(CollectorError::ConnectionFailed("broker unreachable".to_string()))
+        }
+
+        fn redact(&self, output: Self::Output, _level: RedactionLevel, _report: &mut RedactionReport) -> Self::Output {
+            output
+        }
+
+        fn name(&self) -> &'static str {
+            "FailingCollector"
+        }
+
+        fn validate_config(&self, _ctx: &Self::Config) -> CollectorResult<()> {
+            Err(CollectorError::ConfigurationError("missing endpoint".to_string()))
+        }
+    }
+
+    fn context(metadata: &[(&str, &str)]) -> CollectionContext {
+        CollectionContext {
+            kafka_config: KafkaConfig::default(),
+            metadata: metadata.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            dry_run: false,
+            redaction_level: RedactionLevel::default(),
+        }
+    }
+
+    fn mixed_registry() -> CollectorRegistry {
+        let mut registry = CollectorRegistry::new();
+        registry.register("first".to_string(), Box::new(StaticCollector(json!({"brokers": 3}))));
+        registry.register("broken".to_string(), Box::new(FailingCollector));
+        registry.register("second".to_string(), Box::new(StaticCollector(json!(["a", "b"]))));
+        registry
+    }
+
+    #[tokio::test]
+    async fn test_run_all_reports_each_collector_outcome() {
+        let results = mixed_registry().run_all(&context(&[])).await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results["first"].as_ref().unwrap(), &json!({"brokers": 3}));
+        assert_eq!(results["second"].as_ref().unwrap(), &json!(["a", "b"]));
+        assert!(matches!(results["broken"], Err(CollectorError::ConnectionFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_run_all_dry_run_only_validates() {
+        let ctx = CollectionContext { dry_run: true, ..context(&[]) };
+        let results = mixed_registry().run_all(&ctx).await;
+
+        assert_eq!(results["first"].as_ref().unwrap(), &serde_json::Value::Null);
+        assert!(matches!(results["broken"], Err(CollectorError::ConfigurationError(_))));
+    }
+
+    #[test]
+    fn test_register_default_collectors() {
+        let mut registry = CollectorRegistry::new();
+        registry.register_default_collectors(&context(&[]));
+        let mut names = registry.list();
+        names.sort();
+        assert_eq!(names, vec!["admin", "logs"]);
+
+        let mut registry = CollectorRegistry::new();
+        registry.register_default_collectors(&context(&[(CONNECT_URL_METADATA_KEY, "http://connect:8083")]));
+        let mut names = registry.list();
+        names.sort();
+        assert_eq!(names, vec!["admin", "connect", "logs"]);
+        assert_eq!(registry.get("connect").unwrap().name(), "ConnectCollector");
+
+        let mut registry = CollectorRegistry::new();
+        registry.register_default_collectors(&context(&[(METRICS_PORT_METADATA_KEY, "9404")]));
+        assert_eq!(registry.get("prometheus").unwrap().name(), "PrometheusScrapeCollector");
+    }
+
+    #[tokio::test]
+    async fn test_default_log_collector_uses_context_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("server.log");
+        std::fs::write(&log, "[2024-01-01 00:00:00,000] ERROR Broken pipe (kafka.network.Processor)\n").unwrap();
+
+        let mut registry = CollectorRegistry::new();
+        let ctx = context(&[(LOG_PATHS_METADATA_KEY, log.to_str().unwrap())]);
+        registry.register_default_collectors(&ctx);
+
+        let output = registry.get("logs").unwrap().collect(&ctx).await.unwrap();
+        assert_eq!(output["summary"]["files_processed"], 1);
+    }
+
+    #[test]
+    fn test_scrub_secrets() {
+        let properties = "broker.id=1\nssl.truststore.password = changeit\nlog.dirs=/var/lib/kafka\n";
+        assert_eq!(
+            scrub_secrets(properties).unwrap(),
+            "broker.id=1\nssl.truststore.password = *** REDACTED ***\nlog.dirs=/var/lib/kafka\n"
+        );
+        assert_eq!(
+            scrub_secrets(r#"KafkaClient { ScramLoginModule required username="app" password="hunter2"; };"#).unwrap(),
+            r#"KafkaClient { ScramLoginModule required username="app" password="*** REDACTED ***"; };"#
+        );
+        assert_eq!(scrub_secrets("num.network.threads=8\nsasl.enabled.mechanisms=SCRAM-SHA-512"), None);
+    }
+
+    #[test]
+    fn test_redact_snapshot_levels() {
+        let mut snapshot = Snapshot::new(crate::snapshot::format::SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.custom.insert("brokers".to_string(), json!({
+            "broker_1": {"configs": {"server.properties": "listeners=SASL_SSL://kafka-1.prod.internal:9093\nssl.key.password=secret-pw"}},
+        }));
+
+        let mut unredacted = snapshot.clone();
+        assert!(redact_snapshot(&mut unredacted, RedactionLevel::None).is_empty());
+        assert!(!unredacted.metadata.redaction_applied);
+
+        let report = redact_snapshot(&mut snapshot, RedactionLevel::Aggressive);
+        let properties = snapshot.collectors.custom["brokers"]["broker_1"]["configs"]["server.properties"].as_str().unwrap();
+        assert_eq!(properties, "listeners=SASL_SSL://redacted-host:9093\nssl.key.password=*** REDACTED ***");
+        assert_eq!(report.fields_redacted, vec![
+            "brokers.broker_1.configs.server.properties",
+            "brokers.broker_1.configs.server.properties.host",
+        ]);
+        assert!(snapshot.metadata.redaction_applied);
+    }
 }
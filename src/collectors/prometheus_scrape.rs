@@ -0,0 +1,472 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use super::{scrub_hosts, Collector, CollectorError, CollectorResult, RedactionLevel, REDACTED_HOST};
+use crate::snapshot::format::{RedactionReport, Snapshot};
+
+/// Key under which scraped metrics are stored in `CollectorOutputs::metrics`
+pub const PROMETHEUS_METRICS_KEY: &str = "prometheus";
+
+/// Metric family prefixes kept by default; everything else is dropped to bound snapshot size
+pub const DEFAULT_METRIC_ALLOWLIST: [&str; 3] = ["kafka_", "jvm_", "process_"];
+
+/// Configuration for scraping broker Prometheus endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrometheusScrapeConfig {
+    /// Broker hostnames; each is scraped at `<scheme>://<host>:<port><path>`
+    pub hosts: Vec<String>,
+    pub port: u16,
+    pub path: String,
+    /// Scrape over HTTPS
+    pub use_tls: bool,
+    /// PEM file with the CA that signed the brokers' certificates
+    pub ca_cert: Option<String>,
+    pub timeout_secs: u64,
+    /// Metric family name prefixes to keep; empty keeps everything
+    pub allowlist: Vec<String>,
+}
+
+impl Default for PrometheusScrapeConfig {
+    fn default() -> Self {
+        Self {
+            hosts: Vec::new(),
+            port: 7071,
+            path: "/metrics".to_string(),
+            use_tls: false,
+            ca_cert: None,
+            timeout_secs: 10,
+            allowlist: DEFAULT_METRIC_ALLOWLIST.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+impl PrometheusScrapeConfig {
+    fn url(&self, host: &str) -> String {
+        let scheme = if self.use_tls { "https" } else { "http" };
+        format!("{}://{}:{}{}", scheme, host, self.port, self.path)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrometheusScrapeOutput {
+    /// Scrape result of each host
+    pub endpoints: BTreeMap<String, EndpointMetrics>,
+}
+
+impl PrometheusScrapeOutput {
+    /// Store the scraped metrics under `collectors.metrics.prometheus`, keeping other metrics data
+    pub fn store_in(&self, snapshot: &mut Snapshot) -> CollectorResult<()> {
+        let value = serde_json::to_value(self)?;
+        match snapshot.collectors.metrics.as_mut().and_then(|m| m.as_object_mut()) {
+            Some(metrics) => {
+                metrics.insert(PROMETHEUS_METRICS_KEY.to_string(), value);
+            }
+            None => {
+                snapshot.collectors.metrics = Some(serde_json::json!({ PROMETHEUS_METRICS_KEY: value }));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EndpointMetrics {
+    pub url: String,
+    /// Metric families keyed by family name
+    pub families: BTreeMap<String, MetricFamily>,
+    /// Why the endpoint could not be scraped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricFamily {
+    /// `counter`, `gauge`, `histogram`, `summary` or `untyped`
+    #[serde(rename = "type")]
+    pub metric_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    pub samples: Vec<MetricSample>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSample {
+    /// Sample name, which differs from the family name for histogram and summary series
+    pub name: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    pub value: f64,
+}
+
+/// Parse the Prometheus text exposition format into metric families, keeping
+/// families matching one of `allowlist`'s prefixes (all when empty). Samples
+/// with NaN or infinite values are skipped since JSON cannot represent them.
+pub fn parse_exposition(text: &str, allowlist: &[String]) -> BTreeMap<String, MetricFamily> {
+    let allowed = |name: &str| allowlist.is_empty() || allowlist.iter().any(|prefix| name.starts_with(prefix.as_str()));
+    let mut families: BTreeMap<String, MetricFamily> = BTreeMap::new();
+
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut parts = comment.trim_start().splitn(3, ' ');
+            let (Some(kind), Some(name)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            if !allowed(name) {
+                continue;
+            }
+            let rest = parts.next().unwrap_or_default().trim();
+            match kind {
+                "HELP" => families.entry(name.to_string()).or_default().help = Some(rest.to_string()),
+                "TYPE" => families.entry(name.to_string()).or_default().metric_type = rest.to_string(),
+                _ => {}
+            }
+            continue;
+        }
+
+        let Some(sample) = parse_sample(line) else {
+            debug!("Skipping unparsable metrics line: {}", line);
+            continue;
+        };
+        if !sample.value.is_finite() {
+            continue;
+        }
+
+        // Histogram and summary series belong to the family declared without their suffix
+        let family = ["_bucket", "_sum", "_count"].iter()
+            .filter_map(|suffix| sample.name.strip_suffix(suffix))
+            .find(|base| families.get(*base).is_some_and(|f| matches!(f.metric_type.as_str(), "histogram" | "summary")))
+            .unwrap_or(&sample.name)
+            .to_string();
+        if !allowed(&family) {
+            continue;
+        }
+        families.entry(family).or_default().samples.push(sample);
+    }
+
+    for family in families.values_mut().filter(|f| f.metric_type.is_empty()) {
+        family.metric_type = "untyped".to_string();
+    }
+    families
+}
+
+/// Parse `name{label="value",...} value [timestamp]`
+fn parse_sample(line: &str) -> Option<MetricSample> {
+    let (name, labels, rest) = match line.find('{') {
+        Some(open) => {
+            let (labels, rest) = parse_labels(&line[open + 1..])?;
+            (&line[..open], labels, rest)
+        }
+        None => {
+            let (name, rest) = line.split_once(char::is_whitespace)?;
+            (name, BTreeMap::new(), rest)
+        }
+    };
+    let value = rest.split_whitespace().next()?.parse().ok()?;
+    Some(MetricSample {
+        name: name.trim().to_string(),
+        labels,
+        value,
+    })
+}
+
+/// Parse labels up to the closing brace, returning them and the text after it
+fn parse_labels(text: &str) -> Option<(BTreeMap<String, String>, &str)> {
+    let mut labels = BTreeMap::new();
+    let mut rest = text;
+    loop {
+        rest = rest.trim_start_matches([' ', ',']);
+        if let Some(after) = rest.strip_prefix('}') {
+            return Some((labels, after));
+        }
+
+        let (key, after_key) = rest.split_once('=')?;
+        let mut chars = after_key.strip_prefix('"')?.char_indices();
+        let mut value = String::new();
+        let end = loop {
+            match chars.next()? {
+                (i, '"') => break i,
+                (_, '\\') => match chars.next()?.1 {
+                    'n' => value.push('\n'),
+                    other => value.push(other),
+                },
+                (_, c) => value.push(c),
+            }
+        };
+        labels.insert(key.trim().to_string(), value);
+        rest = &after_key[end + 2..];
+    }
+}
+
+/// Collector scraping brokers' native Prometheus `/metrics` endpoints
+pub struct PrometheusScrapeCollector;
+
+impl Default for PrometheusScrapeCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrometheusScrapeCollector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn create_client(config: &PrometheusScrapeConfig) -> CollectorResult<reqwest::Client> {
+        let mut builder = crate::http::client_builder().timeout(Duration::from_secs(config.timeout_secs));
+        if let Some(ca_cert) = &config.ca_cert {
+            let pem = std::fs::read(ca_cert)?;
+            let cert = reqwest::Certificate::from_pem(&pem)
+                .map_err(|e| CollectorError::ConfigurationError(format!("Invalid CA certificate {}: {}", ca_cert, e)))?;
+            builder = builder.add_root_certificate(cert);
+        }
+        builder.build()
+            .map_err(|e| CollectorError::ConfigurationError(format!("Failed to create HTTP client: {}", e)))
+    }
+
+    async fn scrape(client: &reqwest::Client, config: &PrometheusScrapeConfig, url: &str) -> CollectorResult<String> {
+        let response = client.get(url).send().await.map_err(|e| {
+            if e.is_timeout() {
+                CollectorError::Timeout(config.timeout_secs)
+            } else {
+                CollectorError::ConnectionFailed(format!("{}: {}", url, e))
+            }
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(CollectorError::Other(format!("{} returned {}", url, status)));
+        }
+        response.text().await
+            .map_err(|e| CollectorError::ConnectionFailed(format!("{}: {}", url, e)))
+    }
+}
+
+#[async_trait]
+impl Collector for PrometheusScrapeCollector {
+    type Config = PrometheusScrapeConfig;
+    type Output = PrometheusScrapeOutput;
+
+    async fn collect(&self, config: &Self::Config) -> CollectorResult<Self::Output> {
+        info!("Scraping Prometheus metrics from {} broker(s)", config.hosts.len());
+        self.validate_config(config)?;
+
+        let client = Self::create_client(config)?;
+        let mut output = PrometheusScrapeOutput::default();
+        for host in &config.hosts {
+            let url = config.url(host);
+            let endpoint = match Self::scrape(&client, config, &url).await {
+                Ok(text) => EndpointMetrics {
+                    families: parse_exposition(&text, &config.allowlist),
+                    url,
+                    error: None,
+                },
+                Err(e) => {
+                    warn!("Failed to scrape {}: {}", url, e);
+                    EndpointMetrics { url, families: BTreeMap::new(), error: Some(e.to_string()) }
+                }
+            };
+            output.endpoints.insert(host.clone(), endpoint);
+        }
+
+        if output.endpoints.values().all(|e| e.error.is_some()) {
+            return Err(CollectorError::ConnectionFailed(format!(
+                "No broker metrics endpoint could be scraped on port {}", config.port
+            )));
+        }
+
+        info!("Scraped metrics from {} endpoint(s)", output.endpoints.values().filter(|e| e.error.is_none()).count());
+        Ok(output)
+    }
+
+    fn redact(&self, mut output: Self::Output, level: RedactionLevel, report: &mut RedactionReport) -> Self::Output {
+        // Allowlisted Kafka, JVM and process metrics carry no credentials, only the scraped hosts
+        if level != RedactionLevel::Aggressive {
+            return output;
+        }
+        // Numbered rather than masked, so hosts in the same subnet stay distinct
+        output.endpoints = std::mem::take(&mut output.endpoints).into_values()
+            .enumerate()
+            .map(|(i, mut endpoint)| {
+                endpoint.url = scrub_hosts(&endpoint.url).unwrap_or(endpoint.url);
+                endpoint.error = endpoint.error.map(|error| scrub_hosts(&error).unwrap_or(error));
+                report.record("metrics.prometheus.endpoints.host");
+                (format!("{}-{}", REDACTED_HOST, i + 1), endpoint)
+            })
+            .collect();
+        output
+    }
+
+    fn name(&self) -> &'static str {
+        "PrometheusScrapeCollector"
+    }
+
+    fn validate_config(&self, config: &Self::Config) -> CollectorResult<()> {
+        if config.hosts.is_empty() {
+            return Err(CollectorError::ConfigurationError("No hosts to scrape".to_string()));
+        }
+
+        if config.port == 0 {
+            return Err(CollectorError::ConfigurationError("Metrics port must be greater than 0".to_string()));
+        }
+
+        if !config.path.starts_with('/') {
+            return Err(CollectorError::ConfigurationError(
+                format!("Metrics path must start with '/', got '{}'", config.path)
+            ));
+        }
+
+        if let Some(ca_cert) = &config.ca_cert {
+            if !config.use_tls {
+                return Err(CollectorError::ConfigurationError("CA certificate given without TLS".to_string()));
+            }
+            if !Path::new(ca_cert).is_file() {
+                return Err(CollectorError::ConfigurationError(format!("CA certificate not found: {}", ca_cert)));
+            }
+        }
+
+        if config.timeout_secs == 0 {
+            return Err(CollectorError::ConfigurationError("Timeout must be greater than 0".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+
+    const SAMPLE_EXPOSITION: &str = r#"# HELP kafka_server_replicamanager_underreplicatedpartitions Attribute exposed for management
+# TYPE kafka_server_replicamanager_underreplicatedpartitions gauge
+kafka_server_replicamanager_underreplicatedpartitions 2.0
+# HELP kafka_network_requestmetrics_totaltimems Total time of a request
+# TYPE kafka_network_requestmetrics_totaltimems summary
+kafka_network_requestmetrics_totaltimems{request="Produce",quantile="0.99",} 12.5
+kafka_network_requestmetrics_totaltimems{request="Produce",quantile="0.999",} NaN
+kafka_network_requestmetrics_totaltimems_count{request="Produce",} 1042.0
+kafka_network_requestmetrics_totaltimems_sum{request="Produce",} 5210.0
+# TYPE jvm_memory_bytes_used gauge
+jvm_memory_bytes_used{area="heap"} 1.073741824E9 1700000000000
+# TYPE python_gc_objects_collected_total counter
+python_gc_objects_collected_total{generation="0"} 120.0
+kafka_log_size{topic="orders",partition="0",note="say \"hi\""} 4096
+"#;
+
+    fn families() -> BTreeMap<String, MetricFamily> {
+        let config = PrometheusScrapeConfig::default();
+        parse_exposition(SAMPLE_EXPOSITION, &config.allowlist)
+    }
+
+    #[test]
+    fn test_parse_exposition() {
+        let families = families();
+
+        assert_eq!(
+            families.keys().collect::<Vec<_>>(),
+            vec![
+                "jvm_memory_bytes_used",
+                "kafka_log_size",
+                "kafka_network_requestmetrics_totaltimems",
+                "kafka_server_replicamanager_underreplicatedpartitions",
+            ]
+        );
+
+        let urp = &families["kafka_server_replicamanager_underreplicatedpartitions"];
+        assert_eq!(urp.metric_type, "gauge");
+        assert_eq!(urp.help.as_deref(), Some("Attribute exposed for management"));
+        assert_eq!(urp.samples, vec![MetricSample {
+            name: "kafka_server_replicamanager_underreplicatedpartitions".to_string(),
+            labels: BTreeMap::new(),
+            value: 2.0,
+        }]);
+
+        assert_eq!(families["jvm_memory_bytes_used"].samples[0].value, 1_073_741_824.0);
+        assert_eq!(families["kafka_log_size"].metric_type, "untyped");
+        assert_eq!(families["kafka_log_size"].samples[0].labels["note"], "say \"hi\"");
+    }
+
+    #[test]
+    fn test_summary_series_grouped_and_nan_dropped() {
+        let families = families();
+        let request_time = &families["kafka_network_requestmetrics_totaltimems"];
+
+        assert_eq!(request_time.metric_type, "summary");
+        let names: Vec<&str> = request_time.samples.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec![
+            "kafka_network_requestmetrics_totaltimems",
+            "kafka_network_requestmetrics_totaltimems_count",
+            "kafka_network_requestmetrics_totaltimems_sum",
+        ]);
+        assert_eq!(request_time.samples[0].labels["quantile"], "0.99");
+        assert_eq!(request_time.samples[0].labels["request"], "Produce");
+    }
+
+    #[test]
+    fn test_empty_allowlist_keeps_everything() {
+        let families = parse_exposition(SAMPLE_EXPOSITION, &[]);
+        assert!(families.contains_key("python_gc_objects_collected_total"));
+    }
+
+    #[test]
+    fn test_store_in_snapshot_keeps_other_metrics() {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.metrics = Some(serde_json::json!({"kafka_exporter": {"prometheus_metrics.txt": "..."}}));
+        let mut output = PrometheusScrapeOutput::default();
+        output.endpoints.insert("kafka-1".to_string(), EndpointMetrics {
+            url: "http://kafka-1:7071/metrics".to_string(),
+            families: families(),
+            error: None,
+        });
+
+        output.store_in(&mut snapshot).unwrap();
+
+        let metrics = snapshot.collectors.metrics.unwrap();
+        assert!(metrics.get("kafka_exporter").is_some());
+        assert_eq!(
+            metrics[PROMETHEUS_METRICS_KEY]["endpoints"]["kafka-1"]["families"]["kafka_log_size"]["samples"][0]["value"],
+            4096.0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_collect_from_endpoint() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/metrics")
+            .with_status(200)
+            .with_body(SAMPLE_EXPOSITION)
+            .create_async()
+            .await;
+        let address = server.host_with_port();
+        let (host, port) = address.rsplit_once(':').unwrap();
+        let config = PrometheusScrapeConfig {
+            hosts: vec![host.to_string()],
+            port: port.parse().unwrap(),
+            ..Default::default()
+        };
+
+        let output = PrometheusScrapeCollector::new().collect(&config).await.unwrap();
+
+        assert_eq!(output.endpoints[host].families.len(), 4);
+        assert!(output.endpoints[host].error.is_none());
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let collector = PrometheusScrapeCollector::new();
+        let config = PrometheusScrapeConfig { hosts: vec!["kafka-1".to_string()], ..Default::default() };
+        assert!(collector.validate_config(&config).is_ok());
+
+        assert!(collector.validate_config(&PrometheusScrapeConfig::default()).is_err());
+
+        let ca_without_tls = PrometheusScrapeConfig { ca_cert: Some("/tmp/ca.pem".to_string()), ..config.clone() };
+        assert!(collector.validate_config(&ca_without_tls).is_err());
+
+        let missing_ca = PrometheusScrapeConfig { use_tls: true, ca_cert: Some("/nonexistent/ca.pem".to_string()), ..config };
+        assert!(collector.validate_config(&missing_ca).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+/// Environment variable replacing the default `kcpilot/<version>` User-Agent of outbound HTTP requests
+pub const USER_AGENT_ENV: &str = "KCPILOT_USER_AGENT";
+
+/// Timeout of a whole request, unless the caller sets its own
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Timeout of establishing a connection
+pub const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// User-Agent identifying kcpilot's requests to the services it calls
+pub fn user_agent() -> String {
+    std::env::var(USER_AGENT_ENV).ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")))
+}
+
+/// Client builder shared by every networked component: sets the User-Agent and default
+/// timeouts, which callers may still override
+pub fn client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .user_agent(user_agent())
+        .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+        .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+}
+
+/// Client for the OpenAI API, which is built on a different reqwest version. Only the connect
+/// timeout is set, as the LLM service limits whole (streamed) requests itself.
+pub fn openai_client() -> Result<openai_reqwest::Client, openai_reqwest::Error> {
+    openai_reqwest::Client::builder()
+        .user_agent(user_agent())
+        .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_clients_send_user_agent() {
+        std::env::remove_var(USER_AGENT_ENV);
+        let mut server = mockito::Server::new_async().await;
+        let expected = format!("kcpilot/{}", env!("CARGO_PKG_VERSION"));
+        let mock = server.mock("GET", "/status")
+            .match_header("user-agent", expected.as_str())
+            .with_status(200)
+            .expect(2)
+            .create_async().await;
+
+        let url = format!("{}/status", server.url());
+        let response = client_builder().build().unwrap().get(&url).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let response = openai_client().unwrap().get(&url).send().await.unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        mock.assert_async().await;
+
+        std::env::set_var(USER_AGENT_ENV, "kcpilot-audit/ops-team");
+        let configured = server.mock("GET", "/status")
+            .match_header("user-agent", Matcher::Exact("kcpilot-audit/ops-team".to_string()))
+            .with_status(204)
+            .create_async().await;
+        let response = client_builder().build().unwrap().get(&url).send().await.unwrap();
+        std::env::remove_var(USER_AGENT_ENV);
+        assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+        configured.assert_async().await;
+    }
+}
@@ -2,7 +2,10 @@ pub mod collectors;
 pub mod analyzers;
 pub mod cli;
 pub mod report;
+pub mod output;
 pub mod snapshot;
 pub mod scan;
 pub mod llm;
 pub mod analysis;
+pub mod telemetry;
+pub mod http;
@@ -4,7 +4,9 @@ use crate::snapshot::format::{
     Category, ConfigEvidence, Evidence, Finding, LogEvidence,
     Remediation, RemediationStep, RiskLevel, Severity, Snapshot,
 };
+use crate::analysis::timeline;
 use async_trait::async_trait;
+use chrono::Datelike;
 use serde_json::json;
 use std::collections::HashMap;
 
@@ -102,6 +104,7 @@ impl LlmAnalyzer {
                                 metrics: vec![],
                                 configs: vec![],
                                 raw_data: Some(json!({"llm_analysis": analysis})),
+                                timeline: Vec::new(),
                             },
                             root_cause: Some("Identified by LLM analysis".to_string()),
                             remediation: build_remediation(&analysis.recommendations),
@@ -130,15 +133,20 @@ impl LlmAnalyzer {
             .collect();
         
         if !symptoms.is_empty() {
+            // Order the log events up to the latest one, so the LLM sees their sequence
+            let timeline = timeline::build_timeline(&timeline::log_contents(snapshot), None, snapshot.timestamp.year());
+            
             // Prepare evidence
             let evidence = json!({
                 "metrics": snapshot.collectors.metrics,
                 "cluster_info": snapshot.cluster,
                 "finding_count": snapshot.findings.len(),
+                "timeline": timeline,
             });
             
             match self.service.root_cause_analysis(&symptoms, &evidence).await {
-                Ok(analysis) => {
+                Ok(mut analysis) => {
+                    analysis.timeline = Some(timeline.clone());
                     let finding = Finding {
                         id: "LLM-RCA-001".to_string(),
                         severity: Severity::High,
@@ -155,6 +163,7 @@ impl LlmAnalyzer {
                                 "confidence": analysis.confidence,
                                 "factors": analysis.contributing_factors,
                             })),
+                            timeline,
                         },
                         root_cause: Some(analysis.root_cause),
                         remediation: Remediation {
@@ -239,6 +248,7 @@ impl LlmAnalyzer {
                                 logs: vec![],
                                 metrics: vec![],
                                 raw_data: None,
+                                timeline: Vec::new(),
                             },
                             root_cause: None,
                             remediation: Remediation {
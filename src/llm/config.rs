@@ -25,6 +25,18 @@ pub struct LlmConfig {
     
     /// Enable debug logging
     pub debug: bool,
+    
+    /// Model used to embed findings when grouping similar ones
+    pub embedding_model: String,
+    
+    /// Models tried in order when the primary model keeps failing with API errors
+    #[serde(default)]
+    pub fallback_models: Vec<String>,
+    
+    /// Keep only the most recent N bytes of debug log entries in memory, written to llmdbg.txt
+    /// when a request fails, instead of logging every request to the file
+    #[serde(default)]
+    pub debug_buffer: Option<usize>,
 }
 
 impl Default for LlmConfig {
@@ -37,6 +49,9 @@ impl Default for LlmConfig {
             max_tokens: 8000,  // Increased from 4000 to 8000 for larger responses
             temperature: 0.3,
             debug: false,
+            embedding_model: "text-embedding-3-small".to_string(),
+            fallback_models: Vec::new(),
+            debug_buffer: None,
         }
     }
 }
@@ -100,6 +115,24 @@ impl LlmConfig {
             }
         }
         
+        if let Ok(embedding_model) = env::var("OPENAI_EMBEDDING_MODEL") {
+            config.embedding_model = embedding_model;
+        }
+        
+        if let Ok(fallback_models) = env::var("OPENAI_FALLBACK_MODELS") {
+            config.fallback_models = fallback_models.split(',')
+                .map(str::trim)
+                .filter(|model| !model.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        
+        if let Ok(debug_buffer) = env::var("LLM_DEBUG_BUFFER") {
+            if let Ok(max_bytes) = debug_buffer.parse::<usize>() {
+                config.debug_buffer = Some(max_bytes);
+            }
+        }
+        
         if let Ok(debug) = env::var("LLM_DEBUG") {
             config.debug = debug.to_lowercase() == "true" || debug == "1";
         }
@@ -141,6 +174,64 @@ impl LlmConfig {
     }
 }
 
+/// Price per million tokens for a model, in US dollars
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub prompt_per_million_usd: f64,
+    pub completion_per_million_usd: f64,
+}
+
+impl ModelPricing {
+    /// Estimated cost of a number of prompt and completion tokens
+    pub fn cost_usd(&self, prompt_tokens: u64, completion_tokens: u64) -> f64 {
+        (prompt_tokens as f64 * self.prompt_per_million_usd
+            + completion_tokens as f64 * self.completion_per_million_usd)
+            / 1_000_000.0
+    }
+}
+
+/// Published list prices, most specific model prefix first
+const MODEL_PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4-turbo", 10.00, 30.00),
+    ("gpt-4", 30.00, 60.00),
+    ("gpt-3.5-turbo", 0.50, 1.50),
+];
+
+/// Look up the price of a model, matching dated variants such as `gpt-4o-2024-08-06`
+pub fn model_pricing(model: &str) -> Option<ModelPricing> {
+    let model_lower = model.to_lowercase();
+    MODEL_PRICES
+        .iter()
+        .find(|(prefix, _, _)| model_lower.starts_with(prefix))
+        .map(|&(_, prompt, completion)| ModelPricing {
+            prompt_per_million_usd: prompt,
+            completion_per_million_usd: completion,
+        })
+}
+
+/// Context window of each model in tokens, prompt and response together, most specific
+/// model prefix first
+const MODEL_CONTEXT_WINDOWS: &[(&str, usize)] = &[
+    ("gpt-4o-mini", 128_000),
+    ("gpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("gpt-4-32k", 32_768),
+    ("gpt-4", 8_192),
+    ("gpt-3.5-turbo-16k", 16_385),
+    ("gpt-3.5-turbo", 16_385),
+];
+
+/// Look up the context window of a model, matching dated variants such as `gpt-4o-2024-08-06`
+pub fn model_context_window(model: &str) -> Option<usize> {
+    let model_lower = model.to_lowercase();
+    MODEL_CONTEXT_WINDOWS
+        .iter()
+        .find(|(prefix, _)| model_lower.starts_with(prefix))
+        .map(|&(_, tokens)| tokens)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,6 +261,9 @@ mod tests {
             max_tokens: 1000,
             temperature: 0.5,
             debug: false,
+            embedding_model: "text-embedding-3-small".to_string(),
+            fallback_models: Vec::new(),
+            debug_buffer: None,
         };
 
         assert!(config.validate().is_ok());
@@ -221,6 +315,8 @@ mod tests {
         env::remove_var("LLM_MAX_TOKENS");
         env::remove_var("LLM_TEMPERATURE");
         env::remove_var("LLM_DEBUG");
+        env::remove_var("OPENAI_FALLBACK_MODELS");
+        env::remove_var("LLM_DEBUG_BUFFER");
     }
 
     #[test]
@@ -264,6 +360,8 @@ mod tests {
         env::set_var("LLM_MAX_TOKENS", "2000");
         env::set_var("LLM_TEMPERATURE", "0.7");
         env::set_var("LLM_DEBUG", "true");
+        env::set_var("OPENAI_FALLBACK_MODELS", "gpt-4o-mini, gpt-4-turbo,");
+        env::set_var("LLM_DEBUG_BUFFER", "200");
         
         let result = LlmConfig::from_env_no_dotenv();
         assert!(result.is_ok());
@@ -276,6 +374,8 @@ mod tests {
         assert_eq!(config.max_tokens, 2000);
         assert_eq!(config.temperature, 0.7);
         assert!(config.debug);
+        assert_eq!(config.fallback_models, vec!["gpt-4o-mini", "gpt-4-turbo"]);
+        assert_eq!(config.debug_buffer, Some(200));
         
         setup_clean_env();
     }
@@ -360,4 +460,33 @@ mod tests {
         
         setup_clean_env();
     }
+
+    #[test]
+    fn test_model_pricing_lookup() {
+        let mini = model_pricing("gpt-4o-mini").unwrap();
+        assert_eq!(mini.prompt_per_million_usd, 0.15);
+
+        let dated = model_pricing("GPT-4o-2024-08-06").unwrap();
+        assert_eq!(dated.prompt_per_million_usd, 2.50);
+        assert_eq!(dated.completion_per_million_usd, 10.00);
+
+        assert_eq!(model_pricing("gpt-4-turbo-preview").unwrap().prompt_per_million_usd, 10.00);
+        assert!(model_pricing("llama3:70b").is_none());
+    }
+
+    #[test]
+    fn test_model_pricing_cost() {
+        let pricing = model_pricing("gpt-4o").unwrap();
+        // 1M prompt tokens at $2.50 + 500k completion tokens at $10.00
+        assert!((pricing.cost_usd(1_000_000, 500_000) - 7.50).abs() < 1e-9);
+        assert_eq!(pricing.cost_usd(0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_model_context_window_lookup() {
+        assert_eq!(model_context_window("gpt-4o-2024-08-06"), Some(128_000));
+        assert_eq!(model_context_window("gpt-4-32k-0613"), Some(32_768));
+        assert_eq!(model_context_window("gpt-4"), Some(8_192));
+        assert_eq!(model_context_window("llama3:70b"), None);
+    }
 }
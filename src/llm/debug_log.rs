@@ -0,0 +1,191 @@
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// File LLM debug logging writes to, and ring buffers are dumped to
+pub const DEBUG_LOG_FILE: &str = "llmdbg.txt";
+
+/// The most recent debug log entries, oldest first, holding at most `max_bytes` of text;
+/// older entries are dropped to make room for new ones
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugRingBuffer {
+    max_bytes: usize,
+    bytes: usize,
+    entries: VecDeque<String>,
+}
+
+impl DebugRingBuffer {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            bytes: 0,
+            entries: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, mut entry: String) {
+        if self.max_bytes == 0 {
+            return;
+        }
+        // Of an entry larger than the whole buffer, such as a long prompt, keep the end
+        if entry.len() > self.max_bytes {
+            let start = entry.ceil_char_boundary(entry.len() - self.max_bytes);
+            entry.drain(..start);
+        }
+        while self.bytes + entry.len() > self.max_bytes {
+            let Some(oldest) = self.entries.pop_front() else { break };
+            self.bytes -= oldest.len();
+        }
+        self.bytes += entry.len();
+        self.entries.push_back(entry);
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Size of the buffered entries' text
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Write the entries oldest first, one per line, and empty the buffer.
+    /// Returns the number of entries written.
+    pub fn dump(&mut self, out: &mut impl Write) -> io::Result<usize> {
+        let count = self.entries.len();
+        self.bytes = 0;
+        for entry in self.entries.drain(..) {
+            writeln!(out, "{}", entry)?;
+        }
+        out.flush()?;
+        Ok(count)
+    }
+}
+
+/// Where LLM debug log entries go: straight to a file, or into a ring buffer
+/// that is only written to a file on demand or when a request fails
+pub enum DebugLog {
+    File(Mutex<File>),
+    Ring {
+        buffer: Mutex<DebugRingBuffer>,
+        dump_path: PathBuf,
+    },
+}
+
+impl DebugLog {
+    /// Append to the file at `path`
+    pub fn file(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::File(Mutex::new(file)))
+    }
+
+    /// Keep the most recent `max_bytes` of entries, to be dumped to `dump_path`
+    pub fn ring(max_bytes: usize, dump_path: impl Into<PathBuf>) -> Self {
+        Self::Ring {
+            buffer: Mutex::new(DebugRingBuffer::new(max_bytes)),
+            dump_path: dump_path.into(),
+        }
+    }
+
+    pub fn write(&self, entry: String) {
+        match self {
+            Self::File(file) => {
+                if let Ok(mut file) = file.lock() {
+                    let _ = writeln!(file, "{}", entry);
+                    let _ = file.flush();
+                }
+            }
+            Self::Ring { buffer, .. } => {
+                if let Ok(mut buffer) = buffer.lock() {
+                    buffer.push(entry);
+                }
+            }
+        }
+    }
+
+    /// Append the buffered entries to the dump file and empty the buffer. Returns the number
+    /// of entries written, which is always 0 when logging straight to a file.
+    pub fn dump(&self) -> io::Result<usize> {
+        let Self::Ring { buffer, dump_path } = self else {
+            return Ok(0);
+        };
+        let mut buffer = buffer.lock().unwrap_or_else(|e| e.into_inner());
+        if buffer.is_empty() {
+            return Ok(0);
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(dump_path)?;
+        buffer.dump(&mut file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_keeps_most_recent_entries() {
+        // Room for three 7 byte entries
+        let mut buffer = DebugRingBuffer::new(24);
+        for i in 1..=5 {
+            buffer.push(format!("entry {}", i));
+        }
+
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.bytes(), 21);
+        assert_eq!(buffer.entries().collect::<Vec<_>>(), vec!["entry 3", "entry 4", "entry 5"]);
+
+        // A large entry pushes out as many small ones as it needs
+        buffer.push("a longer entry".to_string());
+        assert_eq!(buffer.entries().collect::<Vec<_>>(), vec!["entry 5", "a longer entry"]);
+        assert_eq!(buffer.bytes(), 21);
+
+        let mut disabled = DebugRingBuffer::new(0);
+        disabled.push("dropped".to_string());
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn test_ring_buffer_keeps_end_of_oversized_entry() {
+        let mut buffer = DebugRingBuffer::new(10);
+        buffer.push("short".to_string());
+        buffer.push("prompt: ünder-replicated".to_string());
+
+        assert_eq!(buffer.entries().collect::<Vec<_>>(), vec!["replicated"]);
+        assert_eq!(buffer.bytes(), 10);
+
+        // Cut at a character boundary, so slightly under the limit
+        buffer.push(format!("{}x", "ü".repeat(6)));
+        assert_eq!(buffer.entries().collect::<Vec<_>>(), vec!["üüüüx"]);
+        assert_eq!(buffer.bytes(), 9);
+    }
+
+    #[test]
+    fn test_ring_dump_appends_and_empties_buffer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEBUG_LOG_FILE);
+        // Room for two of the 9 byte entries
+        let log = DebugLog::ring(20, &path);
+        for entry in ["request 1", "request 2", "request 3"] {
+            log.write(entry.to_string());
+        }
+
+        assert_eq!(log.dump().unwrap(), 2);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "request 2\nrequest 3\n");
+
+        // Nothing new to dump; later entries are appended after the earlier dump
+        assert_eq!(log.dump().unwrap(), 0);
+        log.write("request 4".to_string());
+        assert_eq!(log.dump().unwrap(), 1);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "request 2\nrequest 3\nrequest 4\n");
+    }
+}
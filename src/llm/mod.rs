@@ -1,8 +1,9 @@
 pub mod service;
 pub mod analyzer;
 pub mod config;
+pub mod debug_log;
 pub mod prompts;
 
-pub use service::{LlmService, LlmServiceError};
+pub use service::{LlmService, LlmServiceError, ModelUsage, UsageReport};
 pub use config::LlmConfig;
 pub use analyzer::LlmAnalyzer;
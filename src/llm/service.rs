@@ -1,16 +1,21 @@
-use crate::llm::config::LlmConfig;
+use crate::llm::config::{model_pricing, LlmConfig};
+use crate::llm::debug_log::{DebugLog, DEBUG_LOG_FILE};
+use crate::snapshot::format::TimelineEvent;
 use async_openai::{
     config::OpenAIConfig,
+    error::OpenAIError,
     types::{
         ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
         ChatCompletionRequestUserMessageArgs, ChatCompletionRequestAssistantMessageArgs,
-        CreateChatCompletionRequestArgs,
+        ChatCompletionStreamOptions, CreateChatCompletionRequest, CreateChatCompletionRequestArgs,
+        CreateEmbeddingRequestArgs,
     },
     Client,
 };
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
 use serde_json::Value;
-use std::fs::OpenOptions;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::time::Duration;
 use thiserror::Error;
@@ -34,15 +39,46 @@ pub enum LlmServiceError {
     #[error("Rate limit exceeded")]
     RateLimitExceeded,
     
+    #[error("Authentication failed: {0}")]
+    AuthenticationFailed(String),
+    
     #[error("Other error: {0}")]
     Other(String),
 }
 
+impl LlmServiceError {
+    /// Whether another model may succeed where this error failed: provider errors, rate
+    /// limits and timeouts, but not rejected credentials, which no other model fixes
+    pub fn allows_fallback(&self) -> bool {
+        match self {
+            Self::ApiError(_) | Self::RateLimitExceeded | Self::Timeout(_) => true,
+            Self::AuthenticationFailed(_) | Self::ConfigError(_) | Self::ParseError(_) | Self::Other(_) => false,
+        }
+    }
+}
+
+/// HTTP status of a failed request, when the provider answered with one. Streaming requests
+/// only keep the status in the event source error message, "Invalid status code: 401 Unauthorized".
+fn http_status(error: &OpenAIError) -> Option<u16> {
+    match error {
+        OpenAIError::Reqwest(e) => e.status().map(|status| status.as_u16()),
+        OpenAIError::StreamError(message) => message
+            .strip_prefix("Invalid status code: ")?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok(),
+        _ => None,
+    }
+}
+
 /// Service for interacting with LLM APIs
 pub struct LlmService {
     client: Client<OpenAIConfig>,
     config: LlmConfig,
-    debug_file: Option<Mutex<std::fs::File>>,
+    debug_log: Option<DebugLog>,
+    usage: Mutex<UsageReport>,
+    stream_output: bool,
 }
 
 impl LlmService {
@@ -58,12 +94,19 @@ impl LlmService {
             openai_config = openai_config.with_api_base(api_base);
         }
         
-        let client = Client::with_config(openai_config);
+        let http_client = crate::http::openai_client()
+            .map_err(|e| LlmServiceError::ConfigError(format!("Failed to create HTTP client: {}", e)))?;
+        let client = Client::with_config(openai_config).with_http_client(http_client);
         
+        let usage = Mutex::new(UsageReport::new(&config.model));
+        let debug_log = config.debug_buffer.map(|max_bytes| DebugLog::ring(max_bytes, DEBUG_LOG_FILE));
+
         Ok(Self { 
             client, 
             config,
-            debug_file: None,
+            debug_log,
+            usage,
+            stream_output: false,
         })
     }
     
@@ -94,15 +137,12 @@ impl LlmService {
         service.with_debug_file(enable_debug)
     }
     
-    /// Enable debug logging to file
+    /// Enable debug logging to file, replacing the in-memory debug buffer
     pub fn with_debug_file(mut self, enable_debug: bool) -> Result<Self, LlmServiceError> {
         if enable_debug {
-            let file = OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open("llmdbg.txt")
+            let debug_log = DebugLog::file(Path::new(DEBUG_LOG_FILE))
                 .map_err(|e| LlmServiceError::Other(format!("Failed to open debug file: {}", e)))?;
-            self.debug_file = Some(Mutex::new(file));
+            self.debug_log = Some(debug_log);
             self.log_debug("==================================================");
             self.log_debug("=== LLM Debug Logging Session Started ===");
             self.log_debug("==================================================");
@@ -118,24 +158,215 @@ impl LlmService {
         Ok(self)
     }
     
-    /// Log debug information to file if debug mode is enabled
+    /// Keep the most recent `max_bytes` of debug log entries in memory, written to `dump_path`
+    /// when a chat request fails or on [`Self::dump_debug_log`]
+    pub fn with_debug_buffer(mut self, max_bytes: usize, dump_path: impl Into<PathBuf>) -> Self {
+        self.debug_log = Some(DebugLog::ring(max_bytes, dump_path));
+        self
+    }
+    
+    /// Write the buffered debug log entries to their dump file. Returns the number of entries
+    /// written, 0 unless the service keeps its debug log in memory.
+    pub fn dump_debug_log(&self) -> std::io::Result<usize> {
+        match &self.debug_log {
+            Some(debug_log) => debug_log.dump(),
+            None => Ok(0),
+        }
+    }
+    
+    /// Print response tokens to stderr as they arrive
+    pub fn with_stream_output(mut self, stream_output: bool) -> Self {
+        self.stream_output = stream_output;
+        self
+    }
+    
+    /// Token usage accumulated over all requests made by this service
+    pub fn usage_report(&self) -> UsageReport {
+        self.usage.lock().map(|usage| usage.clone()).unwrap_or_else(|e| e.into_inner().clone())
+    }
+    
+    /// Configuration the service was created with
+    pub fn config(&self) -> &LlmConfig {
+        &self.config
+    }
+    
+    /// Log debug information to file or the debug buffer if debug mode is enabled
     fn log_debug(&self, message: &str) {
-        if let Some(ref file_mutex) = self.debug_file {
-            if let Ok(mut file) = file_mutex.lock() {
-                let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-                let _ = writeln!(file, "[{}] {}", timestamp, message);
-                let _ = file.flush();
-            }
+        if let Some(debug_log) = &self.debug_log {
+            let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+            debug_log.write(format!("[{}] {}", timestamp, message));
         }
     }
     
-    /// Send a chat completion request
+    /// Send a chat completion request and wait for the whole response.
+    /// With stream output enabled, tokens are also printed to stderr as they arrive.
+    /// When the model fails with an API error, the configured fallback models are tried in turn.
     pub async fn chat(&self, messages: Vec<ChatMessage>) -> Result<String, LlmServiceError> {
+        let mut models = std::iter::once(&self.config.model).chain(&self.config.fallback_models);
+        let mut model = models.next().expect("primary model");
+        // Once a model streamed part of its answer, the answers of fallback models are not printed
+        let mut streamed = false;
+        loop {
+            let error = match self.chat_with_model(model, messages.clone(), &mut streamed).await {
+                Ok(content) => return Ok(content),
+                Err(e) => e,
+            };
+            match models.next() {
+                Some(fallback) if error.allows_fallback() => {
+                    if streamed {
+                        eprintln!();
+                    }
+                    tracing::warn!("LLM model {} failed ({}), falling back to {}", model, error, fallback);
+                    self.log_debug(&format!("Model {} failed: {}. Falling back to {}", model, error, fallback));
+                    model = fallback;
+                }
+                _ => return Err(self.request_failed(error)),
+            }
+        }
+    }
+    
+    /// Log a failed request and write the debug buffer, so the context that led to it is kept
+    fn request_failed(&self, error: LlmServiceError) -> LlmServiceError {
+        self.log_debug(&format!("Request failed: {}", error));
+        if let Err(e) = self.dump_debug_log() {
+            tracing::warn!("Failed to write the LLM debug buffer: {}", e);
+        }
+        error
+    }
+    
+    /// Send a chat completion request to one model and wait for the whole response. With stream
+    /// output enabled, tokens are printed unless an earlier model already `streamed` some.
+    async fn chat_with_model(&self, model: &str, messages: Vec<ChatMessage>, streamed: &mut bool) -> Result<String, LlmServiceError> {
+        let stream_output = self.stream_output && !*streamed;
+        let mut chunks = std::pin::pin!(self.model_stream(model.to_string(), messages));
+        let mut content = String::new();
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk?;
+            if stream_output {
+                eprint!("{}", chunk);
+                let _ = std::io::stderr().flush();
+                *streamed = true;
+            }
+            content.push_str(&chunk);
+        }
+        if stream_output && !content.is_empty() {
+            eprintln!();
+        }
+        
+        // Check for empty response content
+        if content.is_empty() {
+            if self.debug_log.is_some() {
+                self.log_debug("ERROR: Response content is empty");
+                self.log_debug("The model returned an empty response, which often indicates:");
+                self.log_debug("1. Token limit reached before any output could be generated");
+                self.log_debug("2. Model issue (check if model name is valid)");
+                self.log_debug("3. API configuration issue");
+            }
+            return Err(LlmServiceError::ParseError(
+                format!("Empty response from model. Token limit may be too low (current: {}). Try increasing with LLM_MAX_TOKENS env var.", 
+                    self.config.max_tokens)
+            ));
+        }
+        
+        // Log the response content if debug mode is enabled
+        if self.debug_log.is_some() {
+            self.log_debug("\n--- Response Content ---");
+            self.log_debug(&format!("Response length: {} characters", content.len()));
+            self.log_debug(&format!("Response preview (first 500 chars): {}", 
+                if content.len() > 500 { 
+                    format!("{}...", &content[..content.floor_char_boundary(500)]) 
+                } else { 
+                    content.clone() 
+                }
+            ));
+            self.log_debug(&format!("\n--- Full Response ---\n{}", content));
+            self.log_debug("\n==== END OF REQUEST/RESPONSE ====");
+            self.log_debug(&format!("Completed at: {}\n", Local::now().format("%Y-%m-%d %H:%M:%S")));
+        }
+        
+        if self.config.debug {
+            tracing::debug!("Received response: {} chars", content.len());
+        }
+        
+        Ok(content)
+    }
+    
+    /// Stream a chat completion, yielding pieces of the response text as the provider sends them.
+    /// The configured timeout applies to the whole stream, not to each chunk.
+    pub fn chat_stream(&self, messages: Vec<ChatMessage>) -> impl Stream<Item = Result<String, LlmServiceError>> + '_ {
+        self.model_stream(self.config.model.clone(), messages)
+    }
+    
+    fn model_stream(&self, model: String, messages: Vec<ChatMessage>) -> impl Stream<Item = Result<String, LlmServiceError>> + '_ {
+        let timeout_secs = self.config.timeout_secs;
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(timeout_secs);
+        
+        let chunks = stream::once(self.open_stream(model, messages)).try_flatten();
+        stream::unfold(Some(Box::pin(chunks)), move |state| async move {
+            let mut chunks = state?;
+            match tokio::time::timeout_at(deadline, chunks.next()).await {
+                Ok(Some(Ok(text))) => Some((Ok(text), Some(chunks))),
+                Ok(Some(Err(e))) => Some((Err(e), None)),
+                Ok(None) => None,
+                Err(_) => {
+                    let timeout_msg = format!(
+                        "Request timed out after {} seconds. Consider increasing timeout with --llm-timeout flag or LLM_REQUEST_TIMEOUT env var", 
+                        timeout_secs
+                    );
+                    self.log_debug(&format!("ERROR: {}", timeout_msg));
+                    tracing::error!("{}", timeout_msg);
+                    Some((Err(LlmServiceError::Timeout(timeout_secs)), None))
+                }
+            }
+        })
+    }
+    
+    /// Send a streaming request, returning the non-empty content deltas of its chunks
+    async fn open_stream(
+        &self,
+        model: String,
+        messages: Vec<ChatMessage>,
+    ) -> Result<impl Stream<Item = Result<String, LlmServiceError>> + '_, LlmServiceError> {
+        let request = self.build_request(&model, messages)?;
+        
+        // Log that we're sending the request
+        if self.debug_log.is_some() {
+            self.log_debug(&format!("Sending request to LLM API (timeout: {} seconds)...", self.config.timeout_secs));
+        }
+        
+        let chunks = self.client.chat().create_stream(request).await
+            .map_err(|e| self.api_error(e))?;
+        
+        Ok(chunks
+            .map(move |chunk| {
+                let chunk = chunk.map_err(|e| self.api_error(e))?;
+                if let Some(usage) = &chunk.usage {
+                    if let Ok(mut report) = self.usage.lock() {
+                        report.record(&model, usage.prompt_tokens, usage.completion_tokens);
+                    }
+                    if self.debug_log.is_some() {
+                        self.log_debug("--- LLM Response Received ---");
+                        self.log_debug(&format!("Tokens used - Prompt: {}, Completion: {}, Total: {}", 
+                            usage.prompt_tokens, usage.completion_tokens, usage.total_tokens));
+                        if usage.completion_tokens >= (self.config.max_tokens as u32).saturating_sub(10) {
+                            self.log_debug("WARNING: Completion tokens nearly at max limit - increase max_tokens!");
+                        }
+                    }
+                }
+                Ok(chunk.choices.into_iter().next()
+                    .and_then(|choice| choice.delta.content)
+                    .unwrap_or_default())
+            })
+            .try_filter(|text| future::ready(!text.is_empty())))
+    }
+    
+    /// Build a streaming chat completion request, logging it when debug mode is enabled
+    fn build_request(&self, model: &str, messages: Vec<ChatMessage>) -> Result<CreateChatCompletionRequest, LlmServiceError> {
         // Log the request if debug mode is enabled
-        if self.debug_file.is_some() {
+        if self.debug_log.is_some() {
             self.log_debug("\n==== NEW LLM REQUEST ====");
             self.log_debug(&format!("Timestamp: {}", Local::now().format("%Y-%m-%d %H:%M:%S")));
-            self.log_debug(&format!("Model: {}", self.config.model));
+            self.log_debug(&format!("Model: {}", model));
             self.log_debug(&format!("Max tokens: {}", self.config.max_tokens));
             self.log_debug(&format!("Number of messages: {}", messages.len()));
             self.log_debug("--- Messages ---");
@@ -164,13 +395,15 @@ impl LlmService {
         // Newer models (gpt-4o, gpt-4-turbo, etc.) only support default temperature
         let mut request_builder = CreateChatCompletionRequestArgs::default();
         request_builder
-            .model(&self.config.model)
+            .model(model)
             .messages(openai_messages)
-            .max_completion_tokens(self.config.max_tokens);
+            .max_completion_tokens(self.config.max_tokens)
+            // Token usage is only reported in a final chunk when asked for
+            .stream_options(ChatCompletionStreamOptions { include_usage: true });
         
         // Only set custom temperature for models that support it
         // Newer models like gpt-4o, gpt-4-turbo only accept default temperature (1.0)
-        let model_lower = self.config.model.to_lowercase();
+        let model_lower = model.to_lowercase();
         let skip_temperature = model_lower.contains("gpt-4o") 
             || model_lower.contains("gpt-4-turbo")
             || model_lower.contains("gpt-5");
@@ -181,7 +414,7 @@ impl LlmService {
                 tracing::debug!("Setting temperature to {}", self.config.temperature);
             }
         } else if self.config.debug {
-            tracing::debug!("Skipping temperature parameter for model {} (uses default 1.0)", self.config.model);
+            tracing::debug!("Skipping temperature parameter for model {} (uses default 1.0)", model);
         }
         
         let request = request_builder
@@ -190,108 +423,46 @@ impl LlmService {
         
         if self.config.debug {
             tracing::debug!("Sending request to OpenAI: model={}, messages_count={}", 
-                          self.config.model, request.messages.len());
-        }
-        
-        // Log that we're sending the request
-        if self.debug_file.is_some() {
-            self.log_debug(&format!("Sending request to LLM API (timeout: {} seconds)...", self.config.timeout_secs));
+                          model, request.messages.len());
         }
         
-        let response = tokio::time::timeout(
-            Duration::from_secs(self.config.timeout_secs),
-            self.client.chat().create(request)
-        )
-        .await
-        .map_err(|_| {
-            let timeout_msg = format!(
-                "Request timed out after {} seconds. Consider increasing timeout with --llm-timeout flag or LLM_REQUEST_TIMEOUT env var", 
-                self.config.timeout_secs
-            );
-            if self.debug_file.is_some() {
-                self.log_debug(&format!("ERROR: {}", timeout_msg));
-            }
-            tracing::error!("{}", timeout_msg);
-            LlmServiceError::Timeout(self.config.timeout_secs)
-        })?
-        .map_err(|e| {
-            if self.debug_file.is_some() {
-                self.log_debug(&format!("ERROR: API request failed: {}", e));
-            }
-            if e.to_string().contains("rate limit") {
-                LlmServiceError::RateLimitExceeded
-            } else {
-                LlmServiceError::ApiError(e.to_string())
-            }
-        })?;
-        
-        // Log raw response details
-        if self.debug_file.is_some() {
-            self.log_debug("--- LLM Response Received ---");
-            self.log_debug(&format!("Number of choices: {}", response.choices.len()));
-            if let Some(usage) = &response.usage {
-                self.log_debug(&format!("Tokens used - Prompt: {}, Completion: {}, Total: {}", 
-                    usage.prompt_tokens, usage.completion_tokens, usage.total_tokens));
-            }
-        }
+        Ok(request)
+    }
+    
+    /// Embed each input with the configured embedding model, returning one vector per input
+    /// in input order
+    pub async fn embed(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>, LlmServiceError> {
+        let count = inputs.len();
+        let request = CreateEmbeddingRequestArgs::default()
+            .model(&self.config.embedding_model)
+            .input(inputs)
+            .build()
+            .map_err(|e| LlmServiceError::ApiError(e.to_string()))?;
         
-        let content = response
-            .choices
-            .first()
-            .and_then(|choice| choice.message.content.as_ref())
-            .ok_or_else(|| {
-                if self.debug_file.is_some() {
-                    self.log_debug("ERROR: No response content in API response");
-                    self.log_debug("This usually means the model hit the token limit without generating output.");
-                    self.log_debug("Try: 1) Using a shorter prompt, 2) Increasing max_tokens, 3) Using a different model");
-                }
-                LlmServiceError::ParseError("No response content - likely hit token limit".to_string())
-            })?
-            .to_string();
+        let timeout_secs = self.config.timeout_secs;
+        let response = tokio::time::timeout(Duration::from_secs(timeout_secs), self.client.embeddings().create(request))
+            .await
+            .map_err(|_| LlmServiceError::Timeout(timeout_secs))?
+            .map_err(|e| self.api_error(e))?;
         
-        // Check for empty response content
-        if content.is_empty() {
-            if self.debug_file.is_some() {
-                self.log_debug("ERROR: Response content is empty");
-                self.log_debug("The model returned an empty response, which often indicates:");
-                self.log_debug("1. Token limit reached before any output could be generated");
-                self.log_debug("2. Model issue (check if model name is valid)");
-                self.log_debug("3. API configuration issue");
-                if let Some(usage) = &response.usage {
-                    self.log_debug(&format!("Token usage: prompt={}, completion={}, total={}", 
-                        usage.prompt_tokens, usage.completion_tokens, usage.total_tokens));
-                    if usage.completion_tokens >= (self.config.max_tokens as u32).saturating_sub(10) {
-                        self.log_debug("WARNING: Completion tokens nearly at max limit - increase max_tokens!");
-                    }
-                }
-            }
-            return Err(LlmServiceError::ParseError(
-                format!("Empty response from model. Token limit may be too low (current: {}). Try increasing with LLM_MAX_TOKENS env var.", 
-                    self.config.max_tokens)
-            ));
+        let mut data = response.data;
+        if data.len() != count {
+            return Err(LlmServiceError::ParseError(format!("Expected {} embeddings, got {}", count, data.len())));
         }
-        
-        // Log the response content if debug mode is enabled
-        if self.debug_file.is_some() {
-            self.log_debug("\n--- Response Content ---");
-            self.log_debug(&format!("Response length: {} characters", content.len()));
-            self.log_debug(&format!("Response preview (first 500 chars): {}", 
-                if content.len() > 500 { 
-                    format!("{}...", &content[..500]) 
-                } else { 
-                    content.clone() 
-                }
-            ));
-            self.log_debug(&format!("\n--- Full Response ---\n{}", content));
-            self.log_debug("\n==== END OF REQUEST/RESPONSE ====");
-            self.log_debug(&format!("Completed at: {}\n", Local::now().format("%Y-%m-%d %H:%M:%S")));
+        data.sort_by_key(|embedding| embedding.index);
+        Ok(data.into_iter().map(|embedding| embedding.embedding).collect())
+    }
+    
+    fn api_error(&self, e: OpenAIError) -> LlmServiceError {
+        if self.debug_log.is_some() {
+            self.log_debug(&format!("ERROR: API request failed: {}", e));
         }
-        
-        if self.config.debug {
-            tracing::debug!("Received response: {} chars", content.len());
+        match http_status(&e) {
+            Some(401 | 403) => LlmServiceError::AuthenticationFailed(e.to_string()),
+            Some(429) => LlmServiceError::RateLimitExceeded,
+            _ if e.to_string().contains("rate limit") => LlmServiceError::RateLimitExceeded,
+            _ => LlmServiceError::ApiError(e.to_string()),
         }
-        
-        Ok(content)
     }
     
     /// Analyze Kafka logs using LLM
@@ -352,7 +523,7 @@ impl LlmService {
         
         // Parse the response to extract script and metadata
         Ok(RemediationScript {
-            script: response.clone(),
+            script: extract_script(&response),
             risk_level: "medium".to_string(),
             estimated_duration_minutes: 10,
             requires_downtime: false,
@@ -490,13 +661,25 @@ pub struct RemediationScript {
     pub requires_downtime: bool,
 }
 
+/// Take the first fenced code block of a response, or the whole response when there is none
+fn extract_script(response: &str) -> String {
+    let Some((_, after_fence)) = response.split_once("```") else {
+        return response.trim().to_string();
+    };
+    // Skip the language tag on the opening fence line
+    let body = after_fence.split_once('\n').map(|(_, body)| body).unwrap_or("");
+    let script = body.split_once("```").map(|(script, _)| script).unwrap_or(body);
+    script.trim().to_string()
+}
+
 /// Root cause analysis result
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RootCauseAnalysis {
     pub root_cause: String,
     pub confidence: f64,
     pub contributing_factors: Vec<String>,
-    pub timeline: Option<String>,
+    /// Log events leading up to the issue, set by the caller from the collected logs
+    pub timeline: Option<Vec<TimelineEvent>>,
 }
 
 /// Configuration recommendation
@@ -508,3 +691,371 @@ pub struct ConfigRecommendation {
     pub justification: String,
     pub impact: String,
 }
+
+/// Token usage and estimated cost of the requests made during a run
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct UsageReport {
+    pub model: String,
+    pub requests: u32,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total: u64,
+    /// `None` when a model that answered is not in the price table
+    pub estimated_cost_usd: Option<f64>,
+    /// Usage of each model that answered, in the order they were first used. Lists fallback
+    /// models next to the configured one when they answered some of the requests.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<ModelUsage>,
+}
+
+/// Token usage and estimated cost of the requests answered by one model
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct ModelUsage {
+    pub model: String,
+    pub requests: u32,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// `None` when the model is not in the price table
+    pub estimated_cost_usd: Option<f64>,
+}
+
+impl UsageReport {
+    /// Empty report for the configured `model`
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            requests: 0,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            total: 0,
+            estimated_cost_usd: model_pricing(model).map(|_| 0.0),
+            models: Vec::new(),
+        }
+    }
+
+    /// Add the token counts reported for a single completion by `model`
+    pub fn record(&mut self, model: &str, prompt_tokens: u32, completion_tokens: u32) {
+        let index = match self.models.iter().position(|usage| usage.model == model) {
+            Some(index) => index,
+            None => {
+                self.models.push(ModelUsage {
+                    model: model.to_string(),
+                    requests: 0,
+                    prompt_tokens: 0,
+                    completion_tokens: 0,
+                    estimated_cost_usd: None,
+                });
+                self.models.len() - 1
+            }
+        };
+        let usage = &mut self.models[index];
+        usage.requests += 1;
+        usage.prompt_tokens += prompt_tokens as u64;
+        usage.completion_tokens += completion_tokens as u64;
+        usage.estimated_cost_usd = model_pricing(&usage.model)
+            .map(|pricing| pricing.cost_usd(usage.prompt_tokens, usage.completion_tokens));
+
+        self.requests += 1;
+        self.prompt_tokens += prompt_tokens as u64;
+        self.completion_tokens += completion_tokens as u64;
+        self.total = self.prompt_tokens + self.completion_tokens;
+        self.estimated_cost_usd = self.models.iter().map(|usage| usage.estimated_cost_usd).sum();
+    }
+}
+
+impl std::fmt::Display for UsageReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let models = match self.models.as_slice() {
+            [] => self.model.clone(),
+            [usage] => usage.model.clone(),
+            models => models.iter()
+                .map(|usage| format!("{} ({} requests)", usage.model, usage.requests))
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        write!(
+            f,
+            "{} tokens ({} prompt, {} completion) over {} requests to {}",
+            self.total, self.prompt_tokens, self.completion_tokens, self.requests, models
+        )?;
+        match self.estimated_cost_usd {
+            Some(cost) => write!(f, ", estimated cost ${:.4}", cost),
+            None => write!(f, ", no price known for this model"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_accumulates_across_requests() {
+        let mut usage = UsageReport::new("gpt-4o");
+        usage.record("gpt-4o", 1200, 300);
+        usage.record("gpt-4o", 800, 200);
+
+        assert_eq!(usage.requests, 2);
+        assert_eq!(usage.prompt_tokens, 2000);
+        assert_eq!(usage.completion_tokens, 500);
+        assert_eq!(usage.total, 2500);
+
+        // 2000 * $2.50/M + 500 * $10.00/M
+        let cost = usage.estimated_cost_usd.unwrap();
+        assert!((cost - 0.01).abs() < 1e-9);
+        assert!(usage.to_string().contains("estimated cost $0.0100"));
+    }
+
+    #[test]
+    fn test_usage_for_unknown_model_has_no_cost() {
+        let mut usage = UsageReport::new("mistral-large");
+        assert!(usage.estimated_cost_usd.is_none());
+
+        usage.record("mistral-large", 100, 50);
+        assert_eq!(usage.total, 150);
+        assert!(usage.estimated_cost_usd.is_none());
+        assert!(usage.to_string().contains("no price known"));
+    }
+
+    #[test]
+    fn test_usage_recorded_per_model() {
+        let mut usage = UsageReport::new("gpt-4o");
+        usage.record("gpt-4o", 1200, 300);
+        usage.record("gpt-4o-mini", 10_000, 2_000);
+        usage.record("gpt-4o", 800, 200);
+
+        assert_eq!(usage.requests, 3);
+        assert_eq!(usage.total, 14_500);
+        assert_eq!(usage.models.len(), 2);
+        assert_eq!(usage.models[1].model, "gpt-4o-mini");
+        assert_eq!(usage.models[1].requests, 1);
+        assert_eq!(usage.models[1].prompt_tokens, 10_000);
+
+        // Each model is priced at its own rate
+        let per_model: f64 = usage.models.iter().filter_map(|usage| usage.estimated_cost_usd).sum();
+        assert!((usage.estimated_cost_usd.unwrap() - per_model).abs() < 1e-12);
+        assert!((usage.models[0].estimated_cost_usd.unwrap() - 0.01).abs() < 1e-9);
+        assert!(usage.to_string().contains("over 3 requests to gpt-4o (2 requests), gpt-4o-mini (1 requests)"));
+
+        // An unpriced fallback model leaves the total cost unknown
+        usage.record("mistral-large", 100, 50);
+        assert!(usage.estimated_cost_usd.is_none());
+    }
+
+    #[test]
+    fn test_extract_script_from_fenced_block() {
+        let response = "Here is the script:\n\n```bash\n#!/bin/bash\nkafka-configs.sh --alter\n```\n\nRun it carefully.";
+        assert_eq!(extract_script(response), "#!/bin/bash\nkafka-configs.sh --alter");
+
+        assert_eq!(extract_script("  kafka-topics.sh --list\n"), "kafka-topics.sh --list");
+    }
+
+    #[test]
+    fn test_service_starts_with_empty_usage() {
+        let config = LlmConfig {
+            api_key: "test-key".to_string(),
+            ..Default::default()
+        };
+        let service = LlmService::new(config).unwrap();
+
+        let usage = service.usage_report();
+        assert_eq!(usage.model, "gpt-4o");
+        assert_eq!(usage.total, 0);
+        assert_eq!(usage.estimated_cost_usd, Some(0.0));
+    }
+
+    fn stream_chunk(content: Option<&str>, usage: Option<serde_json::Value>) -> String {
+        let choices = match content {
+            Some(content) => serde_json::json!([{"index": 0, "delta": {"content": content}, "finish_reason": null}]),
+            None => serde_json::json!([]),
+        };
+        let chunk = serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion.chunk",
+            "created": 1700000000,
+            "model": "gpt-4o",
+            "choices": choices,
+            "usage": usage,
+        });
+        format!("data: {}\n\n", chunk)
+    }
+
+    fn mock_service(api_base: String, timeout_secs: u64) -> LlmService {
+        LlmService::new(LlmConfig {
+            api_key: "test-key".to_string(),
+            api_base: Some(api_base),
+            timeout_secs,
+            ..Default::default()
+        }).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_chat_collects_streamed_chunks() {
+        let mut server = mockito::Server::new_async().await;
+        let body = [
+            stream_chunk(Some("Under-replicated "), None),
+            stream_chunk(Some("partitions on "), None),
+            stream_chunk(Some("broker 2"), None),
+            stream_chunk(None, Some(serde_json::json!({"prompt_tokens": 50, "completion_tokens": 6, "total_tokens": 56}))),
+            "data: [DONE]\n\n".to_string(),
+        ].concat();
+        let mock = server.mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "stream": true,
+                "stream_options": {"include_usage": true},
+            })))
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(body)
+            .expect(2)
+            .create_async()
+            .await;
+        let service = mock_service(server.url(), 30);
+
+        let chunks: Vec<String> = service.chat_stream(vec![ChatMessage::user("What is wrong?")])
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(chunks, vec!["Under-replicated ", "partitions on ", "broker 2"]);
+
+        let response = service.chat(vec![ChatMessage::user("What is wrong?")]).await.unwrap();
+        assert_eq!(response, chunks.concat());
+
+        let usage = service.usage_report();
+        assert_eq!(usage.requests, 2);
+        assert_eq!(usage.total, 112);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_timeout_covers_whole_stream() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/chat/completions")
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_chunked_body(|w| {
+                // Each chunk arrives well within the timeout, but together they exceed it
+                for _ in 0..4 {
+                    w.write_all(stream_chunk(Some("slow "), None).as_bytes())?;
+                    w.flush()?;
+                    std::thread::sleep(Duration::from_millis(400));
+                }
+                w.write_all(b"data: [DONE]\n\n")
+            })
+            .create_async()
+            .await;
+        let service = mock_service(server.url(), 1);
+
+        let err = service.chat(vec![ChatMessage::user("hi")]).await.unwrap_err();
+
+        assert!(matches!(err, LlmServiceError::Timeout(1)), "unexpected error: {:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_chat_stream_reports_api_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/chat/completions")
+            .with_status(401)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error": {"message": "Invalid API key", "type": "invalid_request_error", "param": null, "code": "invalid_api_key"}}"#)
+            .create_async()
+            .await;
+        let service = mock_service(server.url(), 30);
+
+        let err = service.chat(vec![ChatMessage::user("hi")]).await.unwrap_err();
+
+        assert!(matches!(err, LlmServiceError::AuthenticationFailed(_)), "unexpected error: {:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_chat_falls_back_to_next_model() {
+        let mut server = mockito::Server::new_async().await;
+        let primary = server.mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({"model": "gpt-4o"})))
+            .with_status(500)
+            .with_header("content-type", "application/json")
+            // Mentions of authentication in the message do not make a server error an auth failure
+            .with_body(r#"{"error": {"message": "Upstream authentication service returned 401", "type": "server_error", "param": null, "code": null}}"#)
+            .create_async()
+            .await;
+        let fallback = server.mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({"model": "gpt-4o-mini"})))
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body([
+                stream_chunk(Some("Fallback answer"), None),
+                stream_chunk(None, Some(serde_json::json!({"prompt_tokens": 40, "completion_tokens": 2, "total_tokens": 42}))),
+                "data: [DONE]\n\n".to_string(),
+            ].concat())
+            .create_async()
+            .await;
+        let service = LlmService::new(LlmConfig {
+            api_key: "test-key".to_string(),
+            api_base: Some(server.url()),
+            fallback_models: vec!["gpt-4o-mini".to_string()],
+            ..Default::default()
+        }).unwrap();
+
+        let response = service.chat(vec![ChatMessage::user("What is wrong?")]).await.unwrap();
+
+        assert_eq!(response, "Fallback answer");
+        let usage = service.usage_report();
+        assert_eq!(usage.model, "gpt-4o");
+        assert_eq!(usage.models.len(), 1);
+        assert_eq!(usage.models[0].model, "gpt-4o-mini");
+        assert_eq!(usage.models[0].prompt_tokens, 40);
+        primary.assert_async().await;
+        fallback.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_auth_errors_do_not_fall_back() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({"model": "gpt-4o"})))
+            .with_status(401)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error": {"message": "Invalid API key", "type": "invalid_request_error", "param": null, "code": "invalid_api_key"}}"#)
+            .create_async()
+            .await;
+        let fallback = server.mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({"model": "gpt-4o-mini"})))
+            .expect(0)
+            .create_async()
+            .await;
+        let service = LlmService::new(LlmConfig {
+            api_key: "test-key".to_string(),
+            api_base: Some(server.url()),
+            fallback_models: vec!["gpt-4o-mini".to_string()],
+            ..Default::default()
+        }).unwrap();
+
+        let err = service.chat(vec![ChatMessage::user("hi")]).await.unwrap_err();
+
+        assert!(matches!(err, LlmServiceError::AuthenticationFailed(_)), "unexpected error: {:?}", err);
+        assert!(!err.allows_fallback());
+        fallback.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_debug_buffer_dumped_on_error() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/chat/completions")
+            .with_status(500)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error": {"message": "The server had an error", "type": "server_error", "param": null, "code": null}}"#)
+            .create_async()
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let dump_path = dir.path().join("llmdbg.txt");
+        let service = mock_service(server.url(), 30).with_debug_buffer(64 * 1024, &dump_path);
+
+        service.chat(vec![ChatMessage::user("Why is broker 2 lagging?")]).await.unwrap_err();
+
+        let dumped = std::fs::read_to_string(&dump_path).unwrap();
+        assert!(dumped.contains("==== NEW LLM REQUEST ===="));
+        assert!(dumped.contains("Why is broker 2 lagging?"));
+        assert!(dumped.lines().last().unwrap().contains("Request failed: "));
+        assert_eq!(service.dump_debug_log().unwrap(), 0);
+    }
+}
@@ -1,43 +1,104 @@
 use anyhow::Result;
 use clap::Parser;
-use kcpilot::cli::commands::{Cli, Commands};
-use kcpilot::cli::handlers::{handle_scan_command, handle_analyze_command, handle_task_command, handle_ssh_test_command, handle_config_command};
+use std::process::ExitCode;
+use kcpilot::cli::commands::{Cli, Commands, ScanDisplayArgs, ScanOutputFormat};
+use kcpilot::cli::handlers::{handle_scan_command, handle_batch_command, handle_analyze_command, handle_task_command, handle_ssh_test_command, handle_config_command, handle_schema_command, handle_watch_command, handle_serve_command, handle_redact_command};
 use kcpilot::cli::utils::{init_logging, print_info};
+use tokio_util::sync::CancellationToken;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let cli = Cli::parse();
 
-    init_logging(cli.verbose, &cli.log_format);
+    // Keep stdout clean for commands that print machine-readable output there
+    let logs_to_stderr = matches!(
+        &cli.command,
+        Commands::Scan { display: ScanDisplayArgs { output_format: ScanOutputFormat::Json, .. }, .. }
+            | Commands::Schema { output: None, .. }
+    ) || matches!(&cli.command, Commands::Analyze { report, .. } | Commands::Watch { report, .. } if report.writes_to_stdout());
+    init_logging(cli.verbose, &cli.log_format, logs_to_stderr);
 
-    match cli.command {
+    let result = match cli.command {
         Commands::Scan {
             bastion,
             output,
-            broker,
-        } => handle_scan_command(bastion, output, broker).await,
+            brokers,
+            collection,
+            display,
+        } => handle_scan_command(bastion, output, brokers, collection, display, cancel_on_ctrl_c()).await.map(success),
 
-        Commands::Analyze { scanned_data, report, output, llmdbg, llm_timeout } => {
-            handle_analyze_command(scanned_data, report, output, llmdbg, llm_timeout).await
+        Commands::Batch { inventory, output, concurrency, since } => {
+            handle_batch_command(inventory, output, concurrency, since, cancel_on_ctrl_c()).await.map(success)
+        }
+
+        Commands::Analyze { scanned_data, report, llm, export_scripts, baseline, fail_on, only_analyzer } => {
+            handle_analyze_command(scanned_data, report, llm, export_scripts, baseline, fail_on.map(Into::into), only_analyzer)
+                .await
+                .map(ExitCode::from)
+        }
+
+        Commands::Watch { dir, report, llm, settle_ms } => {
+            handle_watch_command(dir, report, llm, settle_ms).await.map(success)
+        }
+
+        Commands::Serve { snapshot, port, bind } => {
+            handle_serve_command(snapshot, bind, port).await.map(success)
+        }
+
+        #[cfg(feature = "tui")]
+        Commands::Browse { snapshot } => {
+            kcpilot::cli::handlers::handle_browse_command(snapshot).await.map(success)
+        }
+
+        Commands::Redact { input, output, level } => {
+            handle_redact_command(input, output, level.into()).map(success)
+        }
+
+        Commands::Schema { kind, output } => {
+            handle_schema_command(kind, output).map(success)
         }
 
         Commands::Config => {
-            handle_config_command()
+            handle_config_command().map(success)
         }
 
         Commands::Info => {
             print_info();
-            Ok(())
+            Ok(ExitCode::SUCCESS)
         }
 
         Commands::Task { action } => {
-            handle_task_command(action).await
+            handle_task_command(action).await.map(success)
         }
 
         Commands::TestSsh { bastion } => {
-            handle_ssh_test_command(bastion).await
+            handle_ssh_test_command(bastion).await.map(success)
+        }
+    };
+
+    kcpilot::telemetry::shutdown();
+    result
+}
+
+fn success(_: ()) -> ExitCode {
+    ExitCode::SUCCESS
+}
+
+/// Cancel the returned token on the first Ctrl-C and exit immediately on the second. The
+/// scan's commands run in their own process group, so the Ctrl-C does not interrupt them.
+fn cancel_on_ctrl_c() -> CancellationToken {
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            eprintln!("\n⏹  Cancelling scan once the cluster-wide collection or broker in progress finishes... (press Ctrl-C again to abort)");
+            token.cancel();
+            if tokio::signal::ctrl_c().await.is_ok() {
+                std::process::exit(130);
+            }
         }
-    }
+    });
+    cancel
 }
 
 
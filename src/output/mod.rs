@@ -0,0 +1,95 @@
+pub mod sink;
+
+use sink::{is_remote, sink_for, upload_dir, SinkResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// An `--output` destination. Local destinations are written in place; object store
+/// destinations are written to a local staging path first and uploaded by `finish`.
+#[derive(Debug, Clone)]
+pub struct StagedOutput {
+    destination: PathBuf,
+    local: PathBuf,
+}
+
+impl StagedOutput {
+    pub fn new(destination: PathBuf) -> Self {
+        let local = if is_remote(&destination) {
+            let name = destination.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            std::env::temp_dir().join(format!("kcpilot-{}-{}", uuid::Uuid::new_v4(), name))
+        } else {
+            destination.clone()
+        };
+        Self { destination, local }
+    }
+
+    /// Path the output is written to before `finish`
+    pub fn local_path(&self) -> &Path {
+        &self.local
+    }
+
+    pub fn destination(&self) -> &Path {
+        &self.destination
+    }
+
+    pub fn is_remote(&self) -> bool {
+        self.local != self.destination
+    }
+
+    /// Upload a staged directory under the destination prefix and remove the local copy.
+    /// When the upload fails the local copy is kept so the scan is not lost.
+    pub async fn finish_dir(&self) -> SinkResult<()> {
+        if !self.is_remote() {
+            return Ok(());
+        }
+        let sink = sink_for(&self.destination)?;
+        match upload_dir(sink.as_ref(), &self.local).await {
+            Ok(count) => {
+                info!("☁️  Uploaded {} file(s) to {}", count, sink.root());
+                fs::remove_dir_all(&self.local)?;
+                Ok(())
+            }
+            Err(e) => {
+                warn!("Upload to {} failed, scan data kept in {}", sink.root(), self.local.display());
+                Err(e)
+            }
+        }
+    }
+
+    /// Upload a staged file to the destination object and remove the local copy
+    pub async fn finish_file(&self) -> SinkResult<()> {
+        if !self.is_remote() {
+            return Ok(());
+        }
+        let destination = self.destination.to_string_lossy();
+        let (parent, name) = destination.rsplit_once('/').unwrap_or(("", &destination));
+        let sink = sink_for(Path::new(parent))?;
+        sink.put(name, fs::read(&self.local)?).await?;
+        info!("☁️  Uploaded {}", destination);
+        fs::remove_file(&self.local)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_output_written_in_place() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let output = StagedOutput::new(dir.path().join("report.json"));
+        assert!(!output.is_remote());
+        assert_eq!(output.local_path(), dir.path().join("report.json"));
+
+        fs::write(output.local_path(), "{}").unwrap();
+        output.finish_file().await.unwrap();
+        assert!(output.local_path().exists());
+
+        let remote = StagedOutput::new(PathBuf::from("s3://bucket/reports/report.json"));
+        assert!(remote.is_remote());
+        assert!(remote.local_path().starts_with(std::env::temp_dir()));
+        assert!(remote.local_path().to_string_lossy().ends_with("report.json"));
+    }
+}
@@ -0,0 +1,268 @@
+use async_trait::async_trait;
+use object_store::aws::AmazonS3Builder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutPayload};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, warn};
+use walkdir::WalkDir;
+
+/// URL schemes written to an object store instead of the local filesystem
+pub const REMOTE_SCHEMES: [&str; 2] = ["s3://", "gs://"];
+
+/// Result type for sink operations
+pub type SinkResult<T> = Result<T, SinkError>;
+
+/// Errors that can occur while writing output
+#[derive(Debug, thiserror::Error)]
+pub enum SinkError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Object store error: {0}")]
+    ObjectStoreError(#[from] object_store::Error),
+
+    #[error("Invalid output destination: {0}")]
+    InvalidDestination(String),
+}
+
+/// Where scan results and reports end up
+#[async_trait]
+pub trait OutputSink: Send + Sync {
+    /// Write one file at `location`, a `/`-separated path relative to the sink's root
+    async fn put(&self, location: &str, bytes: Vec<u8>) -> SinkResult<()>;
+
+    /// Remove a file written earlier, used to clean up after a failed upload
+    async fn delete(&self, location: &str) -> SinkResult<()>;
+
+    /// Root of the sink for messages, e.g. `s3://bucket/prefix` or a directory
+    fn root(&self) -> String;
+}
+
+/// Whether an `--output` value names an object store location rather than a local path
+pub fn is_remote(destination: &Path) -> bool {
+    let destination = destination.to_string_lossy();
+    REMOTE_SCHEMES.iter().any(|scheme| destination.starts_with(scheme))
+}
+
+/// Sink for an `--output` value: `s3://bucket/prefix`, `gs://bucket/prefix` or a local directory.
+/// Object store credentials and region come from the usual environment variables
+/// (`AWS_ACCESS_KEY_ID`, `AWS_REGION`, `GOOGLE_SERVICE_ACCOUNT`, ...).
+pub fn sink_for(destination: &Path) -> SinkResult<Box<dyn OutputSink>> {
+    if !is_remote(destination) {
+        return Ok(Box::new(LocalFs::new(destination.to_path_buf())));
+    }
+
+    // "s3://bucket/some/prefix" stores under "some/prefix" of the bucket
+    let url = destination.to_string_lossy();
+    let (scheme, path) = url.split_once("://").unwrap_or_default();
+    let (bucket, prefix) = path.split_once('/').unwrap_or((path, ""));
+    if bucket.is_empty() {
+        return Err(SinkError::InvalidDestination(format!("{} does not name a bucket", url)));
+    }
+
+    let store: Arc<dyn ObjectStore> = match scheme {
+        "s3" => Arc::new(AmazonS3Builder::from_env().with_url(url.as_ref()).build()?),
+        _ => Arc::new(GoogleCloudStorageBuilder::from_env().with_url(url.as_ref()).build()?),
+    };
+    Ok(Box::new(ObjectStoreSink::new(store, prefix, url.trim_end_matches('/'))))
+}
+
+/// Writes into a directory on the local filesystem
+pub struct LocalFs {
+    root: PathBuf,
+}
+
+impl LocalFs {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+}
+
+#[async_trait]
+impl OutputSink for LocalFs {
+    async fn put(&self, location: &str, bytes: Vec<u8>) -> SinkResult<()> {
+        let path = self.root.join(location);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    async fn delete(&self, location: &str) -> SinkResult<()> {
+        fs::remove_file(self.root.join(location))?;
+        Ok(())
+    }
+
+    fn root(&self) -> String {
+        self.root.display().to_string()
+    }
+}
+
+/// Writes objects under a prefix of an S3 or GCS bucket, or any other `object_store` backend
+pub struct ObjectStoreSink {
+    store: Arc<dyn ObjectStore>,
+    prefix: String,
+    url: String,
+}
+
+impl ObjectStoreSink {
+    pub fn new(store: Arc<dyn ObjectStore>, prefix: &str, url: &str) -> Self {
+        Self {
+            store,
+            prefix: prefix.trim_matches('/').to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    fn object_path(&self, location: &str) -> ObjectPath {
+        if self.prefix.is_empty() {
+            ObjectPath::from(location)
+        } else {
+            ObjectPath::from(format!("{}/{}", self.prefix, location))
+        }
+    }
+}
+
+#[async_trait]
+impl OutputSink for ObjectStoreSink {
+    async fn put(&self, location: &str, bytes: Vec<u8>) -> SinkResult<()> {
+        self.store.put(&self.object_path(location), PutPayload::from(bytes)).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, location: &str) -> SinkResult<()> {
+        self.store.delete(&self.object_path(location)).await?;
+        Ok(())
+    }
+
+    fn root(&self) -> String {
+        self.url.clone()
+    }
+}
+
+/// Upload every file under `dir`, keeping paths relative to it. When an upload fails,
+/// the files already uploaded are removed so no half-written scan is left behind.
+pub async fn upload_dir(sink: &dyn OutputSink, dir: &Path) -> SinkResult<usize> {
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+
+    let mut uploaded: Vec<String> = Vec::new();
+    for file in files {
+        let location = file.strip_prefix(dir).unwrap_or(&file)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let result = match fs::read(&file) {
+            Ok(bytes) => sink.put(&location, bytes).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            warn!("Upload of {} to {} failed, removing {} uploaded file(s)", location, sink.root(), uploaded.len());
+            for done in &uploaded {
+                if let Err(cleanup) = sink.delete(done).await {
+                    warn!("Could not remove {}/{}: {}", sink.root(), done, cleanup);
+                }
+            }
+            return Err(e);
+        }
+        debug!("Uploaded {}/{}", sink.root(), location);
+        uploaded.push(location);
+    }
+
+    Ok(uploaded.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use object_store::memory::InMemory;
+    use tempfile::TempDir;
+
+    fn scan_dir() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("brokers/broker_1/configs")).unwrap();
+        fs::write(dir.path().join("scan_metadata.json"), "{}").unwrap();
+        fs::write(dir.path().join("brokers/broker_1/configs/server.properties"), "broker.id=1").unwrap();
+        fs::write(dir.path().join("brokers/broker_1/configs/zz_fail.properties"), "x").unwrap();
+        dir
+    }
+
+    async fn object_names(store: &InMemory) -> Vec<String> {
+        let objects: Vec<_> = store.list(None).try_collect().await.unwrap();
+        let mut names: Vec<String> = objects.into_iter().map(|o| o.location.to_string()).collect();
+        names.sort();
+        names
+    }
+
+    /// Fails uploads of files named `*fail*`, like an object store rejecting a request mid-scan
+    struct FailingSink(ObjectStoreSink);
+
+    #[async_trait]
+    impl OutputSink for FailingSink {
+        async fn put(&self, location: &str, bytes: Vec<u8>) -> SinkResult<()> {
+            if location.contains("fail") {
+                return Err(SinkError::InvalidDestination(location.to_string()));
+            }
+            self.0.put(location, bytes).await
+        }
+
+        async fn delete(&self, location: &str) -> SinkResult<()> {
+            self.0.delete(location).await
+        }
+
+        fn root(&self) -> String {
+            self.0.root()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_dir_to_object_store() {
+        let dir = scan_dir();
+        let store = Arc::new(InMemory::new());
+        let sink = ObjectStoreSink::new(store.clone(), "/scans/prod/", "memory://scans/prod");
+
+        assert_eq!(upload_dir(&sink, dir.path()).await.unwrap(), 3);
+        assert_eq!(object_names(&store).await, vec![
+            "scans/prod/brokers/broker_1/configs/server.properties",
+            "scans/prod/brokers/broker_1/configs/zz_fail.properties",
+            "scans/prod/scan_metadata.json",
+        ]);
+        let content = store.get(&ObjectPath::from("scans/prod/scan_metadata.json")).await.unwrap().bytes().await.unwrap();
+        assert_eq!(content.as_ref(), b"{}");
+    }
+
+    #[tokio::test]
+    async fn test_failed_upload_removes_partial_output() {
+        let dir = scan_dir();
+        let store = Arc::new(InMemory::new());
+        let sink = FailingSink(ObjectStoreSink::new(store.clone(), "scans", "memory://scans"));
+
+        assert!(upload_dir(&sink, dir.path()).await.is_err());
+        assert!(object_names(&store).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_local_sink_and_destinations() {
+        let target = TempDir::new().unwrap();
+        let sink = LocalFs::new(target.path().join("copy"));
+        assert_eq!(upload_dir(&sink, scan_dir().path()).await.unwrap(), 3);
+        assert_eq!(fs::read_to_string(target.path().join("copy/brokers/broker_1/configs/server.properties")).unwrap(), "broker.id=1");
+
+        assert!(is_remote(Path::new("s3://bucket/prefix")));
+        assert!(is_remote(Path::new("gs://bucket")));
+        assert!(!is_remote(Path::new("./s3/scan")));
+        assert!(matches!(sink_for(Path::new("s3:///prefix")), Err(SinkError::InvalidDestination(_))));
+    }
+}
@@ -0,0 +1,180 @@
+use crate::snapshot::format::{Finding, Snapshot};
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use crate::report::output::write_atomic;
+use std::io::Write;
+use std::path::Path;
+
+/// Index findings are written to unless another one is configured
+pub const DEFAULT_ES_INDEX: &str = "kafkapilot-findings";
+
+/// Action line of the `_bulk` API preceding each document
+#[derive(Debug, Serialize)]
+struct ActionLine<'a> {
+    index: IndexAction<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct IndexAction<'a> {
+    #[serde(rename = "_index")]
+    index: &'a str,
+}
+
+/// One finding as an Elasticsearch document, stamped with the scan time and cluster
+#[derive(Debug, Serialize)]
+struct FindingDocument<'a> {
+    #[serde(rename = "@timestamp")]
+    timestamp: String,
+    /// Ahead of the finding, so it is near the start of the file for rerun detection
+    report_generated_at: &'a str,
+    cluster_id: Option<&'a str>,
+    #[serde(flatten)]
+    finding: &'a Finding,
+}
+
+/// Elasticsearch bulk report generator: an index action line followed by the finding document,
+/// for each finding, ready to be sent with `POST _bulk`
+pub struct EsBulkReporter {
+    index: String,
+    force: bool,
+}
+
+impl Default for EsBulkReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EsBulkReporter {
+    pub fn new() -> Self {
+        Self {
+            index: DEFAULT_ES_INDEX.to_string(),
+            force: false,
+        }
+    }
+
+    /// Index the documents are written to (default: `kafkapilot-findings`)
+    pub fn with_index(mut self, index: impl Into<String>) -> Self {
+        self.index = index.into();
+        self
+    }
+
+    /// Overwrite an existing output file even when kcpilot did not write it
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Write the report to a file, or to stdout when the path is `-`
+    pub fn save_report(&self, snapshot: &Snapshot, findings: &[Finding], output_path: &Path) -> Result<()> {
+        if output_path == Path::new("-") || output_path == Path::new("") {
+            let stdout = std::io::stdout();
+            self.write_report(snapshot, findings, stdout.lock())
+        } else {
+            let mut buffer = Vec::new();
+            self.write_report(snapshot, findings, &mut buffer)?;
+            Ok(write_atomic(output_path, &buffer, self.force)?)
+        }
+    }
+
+    /// Write an action line and a document line per finding. The bulk API requires the
+    /// body to end with a newline, which the last document line provides.
+    pub fn write_report<W: Write>(&self, snapshot: &Snapshot, findings: &[Finding], mut writer: W) -> Result<()> {
+        let cluster_id = snapshot.cluster.id.as_deref();
+        let timestamp = snapshot.timestamp.to_rfc3339();
+        let report_generated_at = Utc::now().to_rfc3339();
+        let action = ActionLine { index: IndexAction { index: &self.index } };
+
+        for finding in findings {
+            serde_json::to_writer(&mut writer, &action)?;
+            writer.write_all(b"\n")?;
+            serde_json::to_writer(&mut writer, &FindingDocument {
+                timestamp: timestamp.clone(),
+                report_generated_at: &report_generated_at,
+                cluster_id,
+                finding,
+            })?;
+            writer.write_all(b"\n")?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::{Category, Severity, SnapshotMetadata};
+
+    fn finding(id: &str, severity: Severity) -> Finding {
+        Finding {
+            id: id.to_string(),
+            title: "Under-replicated partitions".to_string(),
+            severity,
+            category: Category::Availability,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_action_and_document_lines_alternate() {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+        snapshot.cluster.id = Some("prod-cluster".to_string());
+        let findings = vec![
+            finding("A-001", Severity::Critical),
+            finding("B-002", Severity::Low),
+            finding("C-003", Severity::Medium),
+        ];
+
+        let mut output = Vec::new();
+        EsBulkReporter::new().write_report(&snapshot, &findings, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.ends_with('\n'));
+        let lines: Vec<serde_json::Value> = output.lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2 * findings.len());
+
+        for (pair, finding) in lines.chunks(2).zip(&findings) {
+            assert_eq!(pair[0], serde_json::json!({"index": {"_index": DEFAULT_ES_INDEX}}));
+            assert_eq!(pair[1]["id"], finding.id.as_str());
+            assert_eq!(pair[1]["@timestamp"], snapshot.timestamp.to_rfc3339());
+            assert_eq!(pair[1]["cluster_id"], "prod-cluster");
+        }
+    }
+
+    #[test]
+    fn test_configured_index() {
+        let snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+
+        let mut output = Vec::new();
+        EsBulkReporter::new().with_index("kafka-prod")
+            .write_report(&snapshot, &[finding("A-001", Severity::High)], &mut output)
+            .unwrap();
+        let action: serde_json::Value = serde_json::from_str(String::from_utf8(output).unwrap().lines().next().unwrap()).unwrap();
+
+        assert_eq!(action["index"]["_index"], "kafka-prod");
+    }
+
+    #[test]
+    fn test_saved_report_replaced_on_rerun() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("es.ndjson");
+        let snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+        let reporter = EsBulkReporter::new();
+
+        reporter.save_report(&snapshot, &[finding("A-001", Severity::High)], &path).unwrap();
+        reporter.save_report(&snapshot, &[finding("B-002", Severity::Low)], &path).unwrap();
+        // A run without findings writes an empty body, which the next run replaces too
+        reporter.save_report(&snapshot, &[], &path).unwrap();
+        reporter.save_report(&snapshot, &[finding("C-003", Severity::Medium)], &path).unwrap();
+
+        let output = std::fs::read_to_string(&path).unwrap();
+        let document: serde_json::Value = serde_json::from_str(output.lines().nth(1).unwrap()).unwrap();
+        assert_eq!(document["id"], "C-003");
+        assert!(document["report_generated_at"].is_string());
+    }
+}
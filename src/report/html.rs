@@ -0,0 +1,958 @@
+use super::markdown::{finding_anchors, MarkdownReporter, LAG_CRITICAL_THRESHOLD, LAG_WARNING_THRESHOLD};
+use super::output::write_atomic;
+use super::scoring::{HealthScoreMethod, SeverityPenalties};
+use super::{stable_finding_id, ClusterOverview, ReportGenerator, ReportResult};
+use crate::collectors::admin::{AdminCollectorOutput, PartitionInfo, TopicInfo};
+use crate::snapshot::format::{ConsumerGroupLag, Finding, Severity, Snapshot};
+use comrak::nodes::{Ast, AstNode, NodeHtmlBlock, NodeValue};
+use comrak::{format_html, parse_document, Arena, Options};
+use regex::Regex;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Directory next to the report that `with_topic_pages` writes the topic pages to
+pub const TOPIC_PAGES_DIR: &str = "topics";
+
+/// Stylesheet inlined into the page so the report is a single self-contained file
+const STYLESHEET: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif;
+       line-height: 1.5; color: #1f2328; max-width: 1100px; margin: 0 auto; padding: 2rem; }
+h1, h2 { border-bottom: 1px solid #d1d9e0; padding-bottom: .3em; }
+h1 a.anchor, h2 a.anchor, h3 a.anchor, h4 a.anchor { display: none; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #d1d9e0; padding: 6px 13px; text-align: left; }
+tr:nth-child(2n) { background: #f6f8fa; }
+code { background: #eff1f3; border-radius: 6px; padding: .2em .4em; font-size: 85%; }
+pre { background: #f6f8fa; border-radius: 6px; padding: 16px; overflow: auto; }
+pre code { background: none; padding: 0; }
+details { margin: 1em 0; }
+summary { cursor: pointer; font-size: 1.1em; }
+.comparison { display: grid; grid-template-columns: repeat(3, 1fr); gap: 1rem; }
+.comparison section { border-left: 4px solid; padding: 0 1rem; }
+.comparison .new { border-color: #cf222e; }
+.comparison .new h2 { color: #cf222e; }
+.comparison .resolved { border-color: #1a7f37; }
+.comparison .resolved h2 { color: #1a7f37; }
+.comparison .persistent { border-color: #8c959f; }
+.comparison .persistent h2 { color: #6e7781; }
+.comparison ul { padding-left: 1.2em; }
+table.sortable th { cursor: pointer; user-select: none; }
+table.sortable th[aria-sort="ascending"]::after { content: " \25B2"; }
+table.sortable th[aria-sort="descending"]::after { content: " \25BC"; }
+td.lag-ok { background: #dafbe1; }
+td.lag-warning { background: #fff8c5; }
+td.lag-critical { background: #ffebe9; color: #cf222e; font-weight: 600; }
+.finding-controls { display: flex; flex-wrap: wrap; gap: .5rem 1rem; align-items: center; margin: 1em 0; }
+@media (prefers-color-scheme: dark) {
+  body { background: #0d1117; color: #e6edf3; }
+  h1, h2 { border-color: #30363d; }
+  th, td { border-color: #30363d; }
+  tr:nth-child(2n) { background: #161b22; }
+  code, pre { background: #161b22; }
+  a { color: #4493f8; }
+  td.lag-ok { background: #12261e; }
+  td.lag-warning { background: #272115; }
+  td.lag-critical { background: #25171c; color: #ff7b72; }
+}
+"#;
+
+/// Script inlined into the page: clicking a header of a `sortable` table sorts its rows by
+/// that column, numerically for headers marked `data-sort="number"`
+const SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable th").forEach(function (th) {
+  th.addEventListener("click", function () {
+    var table = th.closest("table"), body = table.tBodies[0];
+    var column = th.cellIndex, numeric = th.dataset.sort === "number";
+    var ascending = th.getAttribute("aria-sort") !== "ascending";
+    table.querySelectorAll("th").forEach(function (other) { other.removeAttribute("aria-sort"); });
+    th.setAttribute("aria-sort", ascending ? "ascending" : "descending");
+    Array.from(body.rows).sort(function (a, b) {
+      var x = a.cells[column].textContent, y = b.cells[column].textContent;
+      var order = numeric ? Number(x) - Number(y) : x.localeCompare(y);
+      return ascending ? order : -order;
+    }).forEach(function (row) { body.appendChild(row); });
+  });
+});
+
+var severityFilter = document.getElementById("severity-filter");
+var categoryFilter = document.getElementById("category-filter");
+function filterFindings() {
+  var severity = severityFilter.value, category = categoryFilter.value;
+  document.querySelectorAll("div.finding").forEach(function (finding) {
+    finding.hidden = Boolean((severity && finding.dataset.severity !== severity)
+      || (category && finding.dataset.category !== category));
+  });
+  // A group without a finding left to show is hidden as a whole
+  document.querySelectorAll("details").forEach(function (group) {
+    var findings = Array.from(group.querySelectorAll("div.finding"));
+    group.hidden = findings.length > 0 && findings.every(function (finding) { return finding.hidden; });
+  });
+}
+if (severityFilter && categoryFilter) {
+  severityFilter.addEventListener("change", filterFindings);
+  categoryFilter.addEventListener("change", filterFindings);
+  document.getElementById("collapse-all").addEventListener("click", function () {
+    document.querySelectorAll("details").forEach(function (group) { group.open = false; });
+  });
+  document.getElementById("expand-all").addEventListener("click", function () {
+    document.querySelectorAll("details").forEach(function (group) { group.open = true; });
+  });
+}
+"#;
+
+/// Parts of the report page built outside of markdown and added after sanitizing
+#[derive(Default)]
+struct ReportWidgets {
+    /// Sortable table in place of the markdown consumer group table
+    consumer_group_table: Option<String>,
+    /// Filter and collapse controls put under the `Detailed Findings` heading
+    finding_controls: Option<String>,
+    /// `data-` attributes of the element wrapping each finding, by the finding's anchor
+    finding_attributes: HashMap<String, String>,
+}
+
+/// Raw HTML the markdown report writes itself: finding group `<details>` and finding anchors,
+/// whose opening and closing tags are parsed as separate inline HTML
+fn trusted_html() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(
+        r#"^(?:<details open>|</details>|<summary><strong>[A-Za-z ]+</strong> \(\d+ findings?\)</summary>|<a id="[\w-]+">|</a>)$"#
+    ).expect("valid regex"))
+}
+
+fn is_trusted_html(html: &str) -> bool {
+    html.lines().map(str::trim).filter(|line| !line.is_empty()).all(|line| trusted_html().is_match(line))
+}
+
+/// Links to web pages, mail addresses, anchors and relative paths; `javascript:` and other schemes are not
+fn is_safe_url(url: &str) -> bool {
+    let url = url.trim().to_lowercase();
+    ["http://", "https://", "mailto:"].iter().any(|scheme| url.starts_with(scheme))
+        || url.split(['/', '?', '#']).next().is_none_or(|first| !first.contains(':'))
+}
+
+/// Neutralize markup coming from findings and collected data: raw HTML other than what the
+/// markdown report writes itself is turned into text, and links and images with unsafe URLs
+/// lose their target
+fn sanitize<'a>(arena: &'a Arena<AstNode<'a>>, root: &'a AstNode<'a>) {
+    for node in root.descendants() {
+        let start = node.data.borrow().sourcepos.start;
+        let mut ast = node.data.borrow_mut();
+        match &mut ast.value {
+            NodeValue::HtmlInline(html) if !is_trusted_html(html) => {
+                ast.value = NodeValue::Text(std::mem::take(html));
+            }
+            NodeValue::HtmlBlock(block) if !is_trusted_html(&block.literal) => {
+                let text = std::mem::take(&mut block.literal).trim_end().to_string();
+                ast.value = NodeValue::Paragraph;
+                drop(ast);
+                node.append(arena.alloc(AstNode::new(RefCell::new(Ast::new(NodeValue::Text(text), start)))));
+            }
+            NodeValue::Link(link) | NodeValue::Image(link) if !is_safe_url(&link.url) => {
+                link.url.clear();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// File name of a topic's page. Topic names may contain `.` and, in collected data that did
+/// not come from Kafka, `/`; every byte other than ASCII letters, digits and `-` is written as
+/// `_` and its hex code, so distinct topics never share a page and no name leaves the directory.
+pub fn topic_page_file_name(topic: &str) -> String {
+    let mut name = String::with_capacity(topic.len() + 5);
+    for byte in topic.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' {
+            name.push(byte as char);
+        } else {
+            let _ = write!(name, "_{:02X}", byte);
+        }
+    }
+    name.push_str(".html");
+    name
+}
+
+/// Escape text for a markdown table cell
+fn table_cell(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('|', "\\|").replace('`', "\\`").replace('*', "\\*").replace('_', "\\_")
+}
+
+/// Escape text outside of markdown, e.g. the page title
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// CSS class of a consumer group's total lag cell, by the thresholds the markdown report uses
+fn lag_class(total_lag: i64) -> &'static str {
+    if total_lag >= LAG_CRITICAL_THRESHOLD {
+        "lag-critical"
+    } else if total_lag >= LAG_WARNING_THRESHOLD {
+        "lag-warning"
+    } else {
+        "lag-ok"
+    }
+}
+
+/// Consumer groups as a sortable table, most lagging first. Group names and states come from
+/// the cluster and are written as escaped text.
+fn consumer_group_table(groups: &[ConsumerGroupLag]) -> String {
+    let mut html = String::from(
+        "<table class=\"sortable\">\n<thead>\n<tr><th>Group</th><th>State</th>\
+         <th data-sort=\"number\">Members</th><th data-sort=\"number\" aria-sort=\"descending\">Total Lag</th></tr>\n\
+         </thead>\n<tbody>\n"
+    );
+    for group in groups {
+        let _ = writeln!(
+            html,
+            "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td class=\"{}\">{}</td></tr>",
+            escape_text(&group.group),
+            escape_text(group.state.as_deref().unwrap_or("Unknown")),
+            group.members,
+            lag_class(group.total_lag),
+            group.total_lag,
+        );
+    }
+    html.push_str("</tbody>\n</table>\n");
+    html
+}
+
+/// Name a severity or category is serialized with, used as its filter value
+fn serde_name<T: serde::Serialize>(value: T) -> String {
+    serde_json::to_value(value).ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn html_block<'a>(arena: &'a Arena<AstNode<'a>>, node: &'a AstNode<'a>, literal: String) -> &'a AstNode<'a> {
+    let start = node.data.borrow().sourcepos.start;
+    arena.alloc(AstNode::new(RefCell::new(Ast::new(NodeValue::HtmlBlock(NodeHtmlBlock { block_type: 6, literal }), start))))
+}
+
+/// Anchor of a finding's `<a id="...">` paragraph, as the markdown report writes it
+fn finding_anchor<'a>(node: &'a AstNode<'a>) -> Option<String> {
+    if !matches!(node.data.borrow().value, NodeValue::Paragraph) {
+        return None;
+    }
+    let first = node.first_child()?;
+    let value = &first.data.borrow().value;
+    match value {
+        NodeValue::HtmlInline(html) => html.strip_prefix("<a id=\"")?.strip_suffix("\">").map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Put the filter controls under the `Detailed Findings` heading and wrap each finding, from
+/// its anchor up to the next finding or the end of its group, in a `div` the filters can hide
+fn mark_findings<'a>(arena: &'a Arena<AstNode<'a>>, root: &'a AstNode<'a>, controls: String, attributes: &HashMap<String, String>) {
+    let mut controls = Some(controls);
+    let mut open: Option<&'a AstNode<'a>> = None;
+    for node in root.children().collect::<Vec<_>>() {
+        let is_findings_heading = matches!(&node.data.borrow().value, NodeValue::Heading(heading) if heading.level == 2)
+            && inline_text(node) == "Detailed Findings";
+        let ends_group = matches!(&node.data.borrow().value, NodeValue::HtmlBlock(block) if block.literal.trim() == "</details>");
+        let finding = finding_anchor(node).and_then(|anchor| attributes.get(&anchor));
+
+        if is_findings_heading {
+            if let Some(controls) = controls.take() {
+                node.insert_after(html_block(arena, node, controls));
+            }
+        } else if ends_group || finding.is_some() {
+            if open.take().is_some() {
+                node.insert_before(html_block(arena, node, "</div>\n".to_string()));
+            }
+            if let Some(attributes) = finding {
+                node.insert_before(html_block(arena, node, format!("<div class=\"finding\" {}>\n", attributes)));
+                open = Some(node);
+            }
+        }
+    }
+    if let Some(node) = open {
+        root.append(html_block(arena, node, "</div>\n".to_string()));
+    }
+}
+
+/// Text of a node's inline children, e.g. a heading's title
+fn inline_text<'a>(node: &'a AstNode<'a>) -> String {
+    node.descendants()
+        .filter_map(|child| match &child.data.borrow().value {
+            NodeValue::Text(text) | NodeValue::Code(comrak::nodes::NodeCode { literal: text, .. }) => Some(text.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Swap the markdown table under the report's `Consumer Groups` heading for `table`
+fn replace_consumer_group_table<'a>(root: &'a AstNode<'a>, table: String) {
+    let heading = root.children().find(|node| {
+        matches!(&node.data.borrow().value, NodeValue::Heading(heading) if heading.level == 2)
+            && inline_text(node) == "Consumer Groups"
+    });
+    let Some(node) = heading.and_then(|heading| heading.next_sibling()) else {
+        return;
+    };
+    if !matches!(node.data.borrow().value, NodeValue::Table(_)) {
+        return;
+    }
+    while let Some(row) = node.first_child() {
+        row.detach();
+    }
+    node.data.borrow_mut().value = NodeValue::HtmlBlock(NodeHtmlBlock { block_type: 6, literal: table });
+}
+
+fn broker_list(ids: &[i32]) -> String {
+    if ids.is_empty() {
+        return "-".to_string();
+    }
+    ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+fn partition_status(partition: &PartitionInfo) -> &'static str {
+    if partition.leader.is_none() || !partition.offline_replicas.is_empty() {
+        "Offline"
+    } else if partition.isr.len() < partition.replicas.len() {
+        "Under-replicated"
+    } else {
+        "In sync"
+    }
+}
+
+/// Findings of two scans of a cluster, matched by their stable id
+#[derive(Debug, Default)]
+pub struct FindingComparison<'a> {
+    /// Only in the newer scan
+    pub new: Vec<&'a Finding>,
+    /// Only in the older scan
+    pub resolved: Vec<&'a Finding>,
+    /// In both scans, as reported by the newer one
+    pub persistent: Vec<&'a Finding>,
+}
+
+impl<'a> FindingComparison<'a> {
+    pub fn new(old: &'a [Finding], new: &'a [Finding]) -> Self {
+        let old_ids: HashSet<&str> = old.iter().map(|f| stable_finding_id(&f.id)).collect();
+        let new_ids: HashSet<&str> = new.iter().map(|f| stable_finding_id(&f.id)).collect();
+        let (persistent, added) = new.iter().partition(|f| old_ids.contains(stable_finding_id(&f.id)));
+        Self {
+            new: added,
+            resolved: old.iter().filter(|f| !new_ids.contains(stable_finding_id(&f.id))).collect(),
+            persistent,
+        }
+    }
+}
+
+/// Signed difference for the comparison table, e.g. `+2`, `-1` or `0`
+fn delta(old: f64, new: f64) -> String {
+    let change = new - old;
+    if change.abs() < 0.5 {
+        "0".to_string()
+    } else {
+        format!("{:+.0}", change)
+    }
+}
+
+/// HTML report generator: the markdown report rendered to a standalone page
+pub struct HtmlReporter {
+    markdown: MarkdownReporter,
+    score_method: HealthScoreMethod,
+    force: bool,
+    topic_pages: bool,
+}
+
+impl HtmlReporter {
+    pub fn new() -> Self {
+        Self {
+            markdown: MarkdownReporter::new(),
+            score_method: HealthScoreMethod::default(),
+            force: false,
+            topic_pages: false,
+        }
+    }
+
+    /// Choose how the health score is calculated (default: flat)
+    pub fn with_score_method(mut self, method: HealthScoreMethod) -> Self {
+        self.markdown = self.markdown.with_score_method(method);
+        self.score_method = method;
+        self
+    }
+
+    /// Use an LLM-written executive summary instead of the templated one, when present
+    pub fn with_executive_summary(mut self, summary: Option<String>) -> Self {
+        self.markdown = self.markdown.with_executive_summary(summary);
+        self
+    }
+
+    /// Overwrite an existing output file even when kcpilot did not write it
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Also write a page per topic (partition layout, replication and configs) and a topics
+    /// index linked from the report, into a `topics` directory next to the report
+    pub fn with_topic_pages(mut self, enabled: bool) -> Self {
+        self.topic_pages = enabled;
+        self
+    }
+
+    /// Generate an HTML report and save it to a file
+    pub fn save_report(&self, snapshot: &Snapshot, findings: &[Finding], output_path: &Path) -> ReportResult<()> {
+        let content = self.render(snapshot, findings)?;
+        write_atomic(output_path, content.as_bytes(), self.force)?;
+
+        if self.topic_pages {
+            let topics = Self::topics(snapshot)?;
+            let dir = output_path.parent().unwrap_or(Path::new("")).join(TOPIC_PAGES_DIR);
+            fs::create_dir_all(&dir)?;
+            let report_name = output_path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+            let index = Self::render_page("Topics", &Self::topic_index(&topics, &report_name))?;
+            write_atomic(&dir.join("index.html"), index.as_bytes(), self.force)?;
+            for topic in &topics {
+                let page = Self::render_page(&format!("Topic {}", topic.name), &Self::topic_page(topic))?;
+                write_atomic(&dir.join(topic_page_file_name(&topic.name)), page.as_bytes(), self.force)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Topics of the admin collector, by name
+    fn topics(snapshot: &Snapshot) -> ReportResult<Vec<TopicInfo>> {
+        let mut topics = match &snapshot.collectors.admin {
+            Some(admin) => serde_json::from_value::<AdminCollectorOutput>(admin.clone())?.topics,
+            None => Vec::new(),
+        };
+        topics.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(topics)
+    }
+
+    fn topic_index(topics: &[TopicInfo], report_name: &str) -> String {
+        let mut md = format!("# Topics\n\n[Back to the report](<../{}>)\n\n", report_name);
+        if topics.is_empty() {
+            md.push_str("No topic metadata was collected.\n");
+            return md;
+        }
+        md.push_str("| Topic | Partitions | Replication factor | Under-replicated | Offline |\n");
+        md.push_str("|-------|------------|--------------------|------------------|---------|\n");
+        for topic in topics {
+            let count = |status| topic.partitions.iter().filter(|p| partition_status(p) == status).count();
+            let _ = writeln!(
+                md,
+                "| [{}]({}) | {} | {} | {} | {} |",
+                table_cell(&topic.name),
+                topic_page_file_name(&topic.name),
+                topic.partitions.len(),
+                topic.replication_factor,
+                count("Under-replicated"),
+                count("Offline"),
+            );
+        }
+        md
+    }
+
+    fn topic_page(topic: &TopicInfo) -> String {
+        let mut md = format!("# Topic {}\n\n[All topics](index.html)\n\n", table_cell(&topic.name));
+        md.push_str("| Property | Value |\n|----------|-------|\n");
+        let _ = writeln!(md, "| Partitions | {} |", topic.partitions.len());
+        let _ = writeln!(md, "| Replication factor | {} |", topic.replication_factor);
+        let _ = writeln!(md, "| Internal | {} |", if topic.is_internal { "yes" } else { "no" });
+
+        md.push_str("\n## Partitions\n\n");
+        md.push_str("| Partition | Leader | Replicas | ISR | Status |\n");
+        md.push_str("|-----------|--------|----------|-----|--------|\n");
+        for partition in &topic.partitions {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {} | {} |",
+                partition.id,
+                partition.leader.map_or("-".to_string(), |leader| leader.to_string()),
+                broker_list(&partition.replicas),
+                broker_list(&partition.isr),
+                partition_status(partition),
+            );
+        }
+
+        md.push_str("\n## Configuration\n\n");
+        if topic.configs.is_empty() {
+            md.push_str("No configuration was collected for this topic.\n");
+        } else {
+            md.push_str("| Config | Value | Source |\n|--------|-------|--------|\n");
+            for entry in topic.configs.values() {
+                let value = entry.value.as_deref().map_or("(sensitive)".to_string(), table_cell);
+                let source = serde_json::to_value(entry.source).ok()
+                    .and_then(|source| source.as_str().map(str::to_string))
+                    .unwrap_or_default();
+                let _ = writeln!(md, "| {} | {} | {} |", table_cell(&entry.name), value, source);
+            }
+        }
+        md
+    }
+
+    /// Save a page comparing two scans of a cluster: their health scores and sizes side by side,
+    /// and which findings of `old` are resolved, which persist and which are new in `new`
+    pub fn save_comparison(&self, old: &Snapshot, new: &Snapshot, output_path: &Path) -> ReportResult<()> {
+        let content = self.render_comparison(old, new);
+        write_atomic(output_path, content.as_bytes(), self.force)
+    }
+
+    /// Render the comparison page. Findings and collected data are written as escaped text, so
+    /// unlike the report it does not go through markdown.
+    pub fn render_comparison(&self, old: &Snapshot, new: &Snapshot) -> String {
+        let comparison = FindingComparison::new(&old.findings, &new.findings);
+        let score = |snapshot: &Snapshot| self.score_method.score(SeverityPenalties::STANDARD, snapshot, &snapshot.findings);
+        let (old_overview, new_overview) = (ClusterOverview::from_snapshot(old), ClusterOverview::from_snapshot(new));
+        let count = |count: Option<usize>| count.map_or("-".to_string(), |count| count.to_string());
+
+        let mut body = String::from("<h1>Kafka Cluster Comparison</h1>\n<table>\n");
+        body.push_str("<tr><th></th><th>Previous scan</th><th>Current scan</th><th>Change</th></tr>\n");
+        let _ = writeln!(
+            body,
+            "<tr><td>Scanned</td><td>{}</td><td>{}</td><td></td></tr>",
+            old.timestamp.format("%Y-%m-%d %H:%M UTC"),
+            new.timestamp.format("%Y-%m-%d %H:%M UTC"),
+        );
+        let mut row = |label: &str, old: String, new: String, change: String| {
+            let _ = writeln!(body, "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>", label, old, new, change);
+        };
+        let (old_score, new_score) = (score(old), score(new));
+        row("Health score", format!("{:.0}/100", old_score), format!("{:.0}/100", new_score), delta(old_score, new_score));
+        row(
+            "Brokers",
+            old_overview.broker_count.to_string(),
+            new_overview.broker_count.to_string(),
+            delta(old_overview.broker_count as f64, new_overview.broker_count as f64),
+        );
+        let topic_change = match (old_overview.topic_count, new_overview.topic_count) {
+            (Some(old), Some(new)) => delta(old as f64, new as f64),
+            _ => String::new(),
+        };
+        row("Topics", count(old_overview.topic_count), count(new_overview.topic_count), topic_change);
+        row(
+            "Findings",
+            old.findings.len().to_string(),
+            new.findings.len().to_string(),
+            delta(old.findings.len() as f64, new.findings.len() as f64),
+        );
+        body.push_str("</table>\n<div class=\"comparison\">\n");
+
+        for (class, heading, findings) in [
+            ("new", "New", &comparison.new),
+            ("resolved", "Resolved", &comparison.resolved),
+            ("persistent", "Persistent", &comparison.persistent),
+        ] {
+            let _ = writeln!(body, "<section class=\"{}\">\n<h2>{} ({})</h2>", class, heading, findings.len());
+            if findings.is_empty() {
+                body.push_str("<p>None</p>\n");
+            } else {
+                body.push_str("<ul>\n");
+                for finding in findings.iter() {
+                    let _ = writeln!(
+                        body,
+                        "<li>{} <strong>{:?}</strong> {} <code>{}</code></li>",
+                        finding.severity.icon(),
+                        finding.severity,
+                        escape_text(&finding.title),
+                        escape_text(stable_finding_id(&finding.id)),
+                    );
+                }
+                body.push_str("</ul>\n");
+            }
+            body.push_str("</section>\n");
+        }
+        body.push_str("</div>\n");
+
+        Self::page("Kafka Cluster Comparison", &body)
+    }
+
+    /// Render the whole HTML page
+    pub fn render(&self, snapshot: &Snapshot, findings: &[Finding]) -> ReportResult<String> {
+        let mut md = self.markdown.generate_markdown(snapshot, findings)?;
+        if self.topic_pages {
+            let _ = write!(md, "\n[Topic details]({}/index.html)\n", TOPIC_PAGES_DIR);
+        }
+        let body = Self::render_markdown(&md, &self.widgets(snapshot, findings))?;
+        Ok(Self::page("Kafka Cluster Health Report", &body))
+    }
+
+    /// Consumer group table and finding filters of the report
+    fn widgets(&self, snapshot: &Snapshot, findings: &[Finding]) -> ReportWidgets {
+        let consumer_groups = self.markdown.consumer_groups(snapshot);
+        let mut widgets = ReportWidgets {
+            consumer_group_table: (!consumer_groups.is_empty()).then(|| consumer_group_table(&consumer_groups)),
+            ..Default::default()
+        };
+        if findings.is_empty() {
+            return widgets;
+        }
+
+        let mut controls = String::from(
+            "<div class=\"finding-controls\">\n<label>Severity <select id=\"severity-filter\">\n<option value=\"\">All</option>\n"
+        );
+        for severity in [Severity::Critical, Severity::High, Severity::Medium, Severity::Low, Severity::Info] {
+            let count = findings.iter().filter(|f| f.severity == severity).count();
+            if count > 0 {
+                let _ = writeln!(controls, "<option value=\"{}\">{:?} ({})</option>", serde_name(severity), severity, count);
+            }
+        }
+        controls.push_str("</select></label>\n<label>Category <select id=\"category-filter\">\n<option value=\"\">All</option>\n");
+        for (category, count) in self.markdown.count_categories(findings) {
+            let _ = writeln!(
+                controls,
+                "<option value=\"{}\">{} ({})</option>",
+                serde_name(category), self.markdown.format_category(category), count
+            );
+        }
+        controls.push_str(
+            "</select></label>\n<button type=\"button\" id=\"collapse-all\">Collapse all</button>\n\
+             <button type=\"button\" id=\"expand-all\">Expand all</button>\n</div>\n"
+        );
+        widgets.finding_controls = Some(controls);
+
+        widgets.finding_attributes = finding_anchors(findings).into_iter()
+            .zip(findings)
+            .map(|(anchor, finding)| (
+                anchor,
+                format!("data-severity=\"{}\" data-category=\"{}\"", serde_name(finding.severity), serde_name(finding.category)),
+            ))
+            .collect();
+        widgets
+    }
+
+    /// Render markdown to a standalone page with the shared stylesheet
+    fn render_page(title: &str, md: &str) -> ReportResult<String> {
+        Ok(Self::page(title, &Self::render_markdown(md, &ReportWidgets::default())?))
+    }
+
+    /// Render markdown to sanitized HTML and add the report's widgets to it
+    fn render_markdown(md: &str, widgets: &ReportWidgets) -> ReportResult<String> {
+        let mut options = Options::default();
+        options.extension.table = true;
+        options.extension.strikethrough = true;
+        options.extension.autolink = true;
+        // Same anchors as the markdown table of contents links to
+        options.extension.header_ids = Some(String::new());
+        // The report embeds <details> groups and finding anchors as raw HTML; `sanitize` turns
+        // any other raw HTML, e.g. from finding text or collected log lines, into text
+        options.render.unsafe_ = true;
+        options.extension.tagfilter = true;
+        let arena = Arena::new();
+        let root = parse_document(&arena, md, &options);
+        sanitize(&arena, root);
+        if let Some(table) = &widgets.consumer_group_table {
+            replace_consumer_group_table(root, table.clone());
+        }
+        if let Some(controls) = &widgets.finding_controls {
+            mark_findings(&arena, root, controls.clone(), &widgets.finding_attributes);
+        }
+        let mut body = Vec::new();
+        format_html(root, &options, &mut body)?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    /// Standalone page around `body`, with the shared stylesheet and script
+    fn page(title: &str, body: &str) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+             <meta name=\"generator\" content=\"KCPilot\">\n\
+             <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}<script>{}</script>\n</body>\n</html>\n",
+            escape_text(title), STYLESHEET, body, SCRIPT
+        )
+    }
+}
+
+impl ReportGenerator for HtmlReporter {
+    fn generate(&self, snapshot: &Snapshot, output_path: &Path) -> ReportResult<()> {
+        self.save_report(snapshot, &snapshot.findings, output_path)
+    }
+
+    fn name(&self) -> &'static str {
+        "html"
+    }
+}
+
+impl Default for HtmlReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::{Category, Evidence, LogEvidence, Severity, SnapshotMetadata};
+
+    #[test]
+    fn test_report_rendered_as_standalone_page() {
+        let snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+        let findings = vec![Finding {
+            id: "CONN-001".to_string(),
+            title: "Connections near max.connections <script>alert(1)</script>".to_string(),
+            severity: Severity::High,
+            category: Category::Performance,
+            ..Default::default()
+        }];
+
+        let html = HtmlReporter::new().render(&snapshot, &findings).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>"));
+        assert!(html.contains("Kafka Cluster Health Report"));
+        assert!(html.contains("<details open>"));
+        assert!(html.contains("CONN-001"));
+        assert!(!html.contains("<script>alert"));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+
+    #[test]
+    fn test_findings_grouped_with_filters() {
+        let snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+        let finding = |id: &str, severity, category| Finding {
+            id: id.to_string(),
+            title: format!("Finding {}", id),
+            severity,
+            category,
+            ..Default::default()
+        };
+        let findings = vec![
+            finding("F-1", Severity::Low, Category::Configuration),
+            finding("F-2", Severity::High, Category::Performance),
+            finding("F-3", Severity::Critical, Category::Configuration),
+            finding("F-4", Severity::Medium, Category::Configuration),
+        ];
+
+        let html = HtmlReporter::new().render(&snapshot, &findings).unwrap();
+
+        // One group per category, with its count
+        assert_eq!(html.matches("<details open>").count(), 2);
+        assert!(html.contains("<summary><strong>Configuration</strong> (3 findings)</summary>"));
+        assert!(html.contains("<summary><strong>Performance</strong> (1 finding)</summary>"));
+
+        // Controls under the heading, offering what the findings have
+        let controls = html.find("<div class=\"finding-controls\">").unwrap();
+        assert!(html.find("Detailed Findings</h2>").unwrap() < controls);
+        assert!(controls < html.find("<details open>").unwrap());
+        assert!(html.contains("<option value=\"critical\">Critical (1)</option>"));
+        assert!(!html.contains("<option value=\"info\">"));
+        assert!(html.contains("<option value=\"configuration\">Configuration (3)</option>"));
+        assert!(html.contains("<option value=\"performance\">Performance (1)</option>"));
+        assert!(html.contains("id=\"collapse-all\"") && html.contains("id=\"expand-all\""));
+
+        // Every finding is wrapped for the filters, inside its group
+        assert_eq!(html.matches("<div class=\"finding\" ").count(), 4);
+        assert!(html.contains("<div class=\"finding\" data-severity=\"critical\" data-category=\"configuration\">\n<p><a id=\"finding-f-3\"></a></p>"));
+        assert!(html.contains("<div class=\"finding\" data-severity=\"high\" data-category=\"performance\">"));
+        let groups: Vec<&str> = html.split("<details open>").skip(1).collect();
+        for group in &groups {
+            let group = &group[..group.find("</details>").unwrap()];
+            assert_eq!(group.matches("<div class=\"finding\"").count(), group.matches("\n</div>\n").count(), "{}", group);
+        }
+        assert_eq!(groups[0].matches("<div class=\"finding\"").count(), 3);
+    }
+
+    #[test]
+    fn test_consumer_group_table() {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+        let html = HtmlReporter::new().render(&snapshot, &[]).unwrap();
+        assert!(!html.contains("Consumer Groups"));
+        assert!(!html.contains("<table class=\"sortable\">"));
+
+        snapshot.collectors.custom.insert(crate::snapshot::format::CONSUMER_LAG_KEY.to_string(), serde_json::json!([
+            {"group": "billing", "state": "Stable", "members": 3, "total_lag": 12},
+            {"group": "audit<b>", "state": "Empty", "members": 0, "total_lag": 250000},
+            {"group": "search", "state": "Stable", "members": 2, "total_lag": 5000}
+        ]));
+        let html = HtmlReporter::new().render(&snapshot, &[]).unwrap();
+
+        assert!(html.contains("Consumer Groups</h2>\n<table class=\"sortable\">"), "{}", html);
+        assert!(html.contains("<th data-sort=\"number\" aria-sort=\"descending\">Total Lag</th>"));
+        assert!(html.contains("<tr><td><code>audit&lt;b&gt;</code></td><td>Empty</td><td>0</td><td class=\"lag-critical\">250000</td></tr>"));
+        assert!(html.contains("<tr><td><code>search</code></td><td>Stable</td><td>2</td><td class=\"lag-warning\">5000</td></tr>"));
+        assert!(html.contains("<tr><td><code>billing</code></td><td>Stable</td><td>3</td><td class=\"lag-ok\">12</td></tr>"));
+        // The markdown table and its icons are replaced, not kept next to the sortable one
+        assert!(!html.contains("🔴"));
+        assert!(html.find("audit&lt;b&gt;").unwrap() < html.find(">billing<").unwrap());
+    }
+
+    #[test]
+    fn test_untrusted_html_escaped() {
+        let snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+        let findings = vec![Finding {
+            id: "LLM-001".to_string(),
+            title: "Broker restarted <img src=x onerror=alert(1)>".to_string(),
+            description: "See [the runbook](javascript:alert(2)) and [the docs](https://kafka.apache.org/documentation/).\n\n\
+                          <div onmouseover=\"alert(3)\">block</div>".to_string(),
+            severity: Severity::High,
+            category: Category::Availability,
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: vec![LogEvidence {
+                    level: "ERROR".to_string(),
+                    // A backtick closes the code span the message is written in
+                    message: "failed` <img src=y onerror=alert(4)> `".to_string(),
+                    source_file: "server.log".to_string(),
+                    line_number: None,
+                    timestamp: String::new(),
+                    count: 1,
+                }],
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            ..Default::default()
+        }];
+
+        let html = HtmlReporter::new().render(&snapshot, &findings).unwrap();
+
+        assert!(!html.contains("<img"), "{}", html);
+        assert!(!html.contains("<div onmouseover"));
+        assert!(html.contains("&lt;img src=x onerror=alert(1)&gt;"));
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains("href=\"https://kafka.apache.org/documentation/\""));
+        // The report's own markup is kept
+        assert!(html.contains("<details open>"));
+        assert!(html.contains("<summary><strong>Availability</strong> (1 finding)</summary>"));
+        assert!(html.contains("<a id=\"finding-llm-001\"></a>"));
+    }
+
+    #[test]
+    fn test_saved_report_is_a_single_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("report.html");
+        let snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+
+        HtmlReporter::new().save_report(&snapshot, &[], &path).unwrap();
+
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from("report.html")]);
+        let html = std::fs::read_to_string(&path).unwrap();
+        assert!(html.contains(&format!("<style>{}</style>", STYLESHEET)));
+        assert!(!html.contains("<link") && !html.contains("src=\""));
+    }
+
+    fn snapshot_with_topics(names: &[&str]) -> Snapshot {
+        let topics: Vec<_> = names.iter()
+            .map(|name| serde_json::json!({
+                "name": name,
+                "partitions": [
+                    {"id": 0, "leader": 1, "replicas": [1, 2], "isr": [1, 2], "offline_replicas": []},
+                    {"id": 1, "leader": 2, "replicas": [2, 1], "isr": [2], "offline_replicas": []},
+                ],
+                "replication_factor": 2,
+                "is_internal": false,
+                "configs": {
+                    "retention.ms": {
+                        "name": "retention.ms", "value": "604800000", "source": "dynamic_topic",
+                        "is_default": false, "is_read_only": false, "is_sensitive": false
+                    }
+                },
+            }))
+            .collect();
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+        snapshot.collectors.admin = Some(serde_json::json!({
+            "cluster": {"cluster_id": null, "controller_id": 1, "broker_count": 2, "topic_count": names.len(), "partition_count": 2 * names.len()},
+            "brokers": [
+                {"id": 1, "host": "kafka-1", "port": 9092, "rack": null},
+                {"id": 2, "host": "kafka-2", "port": 9092, "rack": null},
+            ],
+            "topics": topics,
+        }));
+        snapshot
+    }
+
+    #[test]
+    fn test_topic_pages_written() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("report.html");
+        let snapshot = snapshot_with_topics(&["orders", "payments.v1"]);
+        let reporter = HtmlReporter::new().with_topic_pages(true);
+
+        reporter.save_report(&snapshot, &[], &path).unwrap();
+
+        let report = std::fs::read_to_string(&path).unwrap();
+        assert!(report.contains("href=\"topics/index.html\""));
+        let index = std::fs::read_to_string(dir.path().join("topics/index.html")).unwrap();
+        assert!(index.contains("href=\"orders.html\""));
+        assert!(index.contains("href=\"payments_2Ev1.html\""));
+        assert!(index.contains("href=\"../report.html\""));
+        assert!(index.contains(&format!("<style>{}</style>", STYLESHEET)));
+
+        let page = std::fs::read_to_string(dir.path().join("topics/payments_2Ev1.html")).unwrap();
+        assert!(page.contains("<title>Topic payments.v1</title>"));
+        assert!(page.contains("<td>Under-replicated</td>"));
+        assert!(page.contains("<td>604800000</td>"));
+        assert!(page.contains("<td>dynamic_topic</td>"));
+
+        // Pages of an earlier run are replaced without --force
+        reporter.save_report(&snapshot, &[], &path).unwrap();
+    }
+
+    #[test]
+    fn test_topic_pages_disabled_by_default() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("report.html");
+
+        HtmlReporter::new().save_report(&snapshot_with_topics(&["orders"]), &[], &path).unwrap();
+
+        assert!(!dir.path().join(TOPIC_PAGES_DIR).exists());
+    }
+
+    #[test]
+    fn test_topic_page_names_stay_in_directory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("report.html");
+        let snapshot = snapshot_with_topics(&["../../escaped", "a/b", "a_2Fb"]);
+
+        HtmlReporter::new().with_topic_pages(true).save_report(&snapshot, &[], &path).unwrap();
+
+        let mut pages: Vec<String> = std::fs::read_dir(dir.path().join(TOPIC_PAGES_DIR)).unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        pages.sort();
+        assert_eq!(pages, vec!["_2E_2E_2F_2E_2E_2Fescaped.html", "a_2Fb.html", "a_5F2Fb.html", "index.html"]);
+        let mut entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+        entries.sort();
+        assert_eq!(entries, vec![std::ffi::OsString::from("report.html"), std::ffi::OsString::from(TOPIC_PAGES_DIR)]);
+    }
+
+    #[test]
+    fn test_comparison_sections() {
+        let finding = |id: &str, severity| Finding {
+            id: id.to_string(),
+            title: format!("{} <b>finding</b>", id),
+            severity,
+            ..Default::default()
+        };
+        let mut old = snapshot_with_topics(&["orders"]);
+        old.findings = vec![
+            finding(&format!("LEADER-IMBALANCE-{}", uuid::Uuid::new_v4()), Severity::Medium),
+            finding("TLS-001", Severity::High),
+            finding("GC-001", Severity::High),
+        ];
+        let mut new = snapshot_with_topics(&["orders", "payments"]);
+        new.findings = vec![
+            finding(&format!("LEADER-IMBALANCE-{}", uuid::Uuid::new_v4()), Severity::Medium),
+            finding("CONN-001", Severity::Critical),
+        ];
+
+        let comparison = FindingComparison::new(&old.findings, &new.findings);
+        assert_eq!(comparison.new.len(), 1);
+        assert_eq!(comparison.resolved.len(), 2);
+        assert_eq!(comparison.persistent.len(), 1);
+
+        let html = HtmlReporter::new().render_comparison(&old, &new);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<section class=\"new\">\n<h2>New (1)</h2>"));
+        assert!(html.contains("<section class=\"resolved\">\n<h2>Resolved (2)</h2>"));
+        assert!(html.contains("<section class=\"persistent\">\n<h2>Persistent (1)</h2>"));
+        assert!(html.contains("<code>CONN-001</code>"));
+        assert!(html.contains("<code>LEADER-IMBALANCE</code>"));
+        assert!(html.contains("<tr><td>Topics</td><td>1</td><td>2</td><td>+1</td></tr>"));
+        assert!(html.contains("<tr><td>Health score</td><td>62/100</td><td>67/100</td><td>+5</td></tr>"));
+        assert!(html.contains("CONN-001 &lt;b&gt;finding&lt;/b&gt;"));
+        assert!(!html.contains("<b>"));
+    }
+}
@@ -0,0 +1,345 @@
+use crate::snapshot::format::{Finding, Severity};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{debug, info};
+
+use super::{stable_finding_id, ReportError, ReportResult};
+
+/// Environment variables configuring the Jira export
+pub const JIRA_URL_ENV: &str = "JIRA_URL";
+pub const JIRA_PROJECT_ENV: &str = "JIRA_PROJECT";
+pub const JIRA_EMAIL_ENV: &str = "JIRA_EMAIL";
+pub const JIRA_API_TOKEN_ENV: &str = "JIRA_API_TOKEN";
+
+/// Prefix of the label that ties an issue to a finding across scans
+const FINDING_LABEL_PREFIX: &str = "kcpilot-finding-id-";
+
+/// Credentials for the Jira REST API
+#[derive(Debug, Clone)]
+pub enum JiraAuth {
+    /// Jira Cloud: account email and API token
+    Basic { email: String, api_token: String },
+    /// Jira Data Center / Server personal access token
+    Bearer(String),
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    issues: Vec<IssueRef>,
+}
+
+#[derive(Deserialize)]
+struct IssueRef {
+    key: String,
+}
+
+/// Creates one Jira issue per finding, skipping findings that already have an open issue
+pub struct JiraReporter {
+    base_url: String,
+    project_key: String,
+    auth: JiraAuth,
+    issue_type: String,
+    client: reqwest::Client,
+}
+
+impl JiraReporter {
+    pub fn new(base_url: impl Into<String>, project_key: impl Into<String>, auth: JiraAuth) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            project_key: project_key.into(),
+            auth,
+            issue_type: "Task".to_string(),
+            client: crate::http::client_builder()
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Build a reporter from `JIRA_URL`, `JIRA_PROJECT` and `JIRA_API_TOKEN`, using basic auth
+    /// when `JIRA_EMAIL` is also set. Returns `None` unless all required variables are set.
+    pub fn from_env() -> Option<Self> {
+        let base_url = std::env::var(JIRA_URL_ENV).ok()?;
+        let project_key = std::env::var(JIRA_PROJECT_ENV).ok()?;
+        let api_token = std::env::var(JIRA_API_TOKEN_ENV).ok()?;
+        let auth = match std::env::var(JIRA_EMAIL_ENV) {
+            Ok(email) => JiraAuth::Basic { email, api_token },
+            Err(_) => JiraAuth::Bearer(api_token),
+        };
+        Some(Self::new(base_url, project_key, auth))
+    }
+
+    /// Issue type of created issues (default: Task)
+    pub fn with_issue_type(mut self, issue_type: impl Into<String>) -> Self {
+        self.issue_type = issue_type.into();
+        self
+    }
+
+    /// Create an issue for each finding at `min_severity` or worse that has no open issue yet,
+    /// returning the keys of the created issues
+    pub async fn create_issues(&self, findings: &[Finding], min_severity: Severity) -> ReportResult<Vec<String>> {
+        let mut created = Vec::new();
+
+        for finding in findings.iter().filter(|f| f.severity <= min_severity) {
+            let label = finding_label(finding);
+            if let Some(existing) = self.find_open_issue(&label).await? {
+                debug!("Finding {} already tracked in {}", finding.id, existing);
+                continue;
+            }
+
+            let key = self.create_issue(finding, &label).await?;
+            info!("Created Jira issue {} for finding {}", key, finding.id);
+            created.push(key);
+        }
+
+        Ok(created)
+    }
+
+    async fn find_open_issue(&self, label: &str) -> ReportResult<Option<String>> {
+        let jql = format!(
+            "project = \"{}\" AND labels = \"{}\" AND statusCategory != Done",
+            self.project_key, label
+        );
+        let response = self.authorize(self.client.get(format!("{}/rest/api/2/search", self.base_url)))
+            .query(&[("jql", jql.as_str()), ("fields", "key"), ("maxResults", "1")])
+            .send()
+            .await
+            .map_err(|e| ReportError::Other(format!("Failed to search Jira issues: {}", e)))?;
+
+        let body = Self::check_status(response, "Jira issue search").await?;
+        let search: SearchResponse = serde_json::from_str(&body)?;
+        Ok(search.issues.into_iter().next().map(|issue| issue.key))
+    }
+
+    async fn create_issue(&self, finding: &Finding, label: &str) -> ReportResult<String> {
+        let payload = self.issue_payload(finding, label);
+        let response = self.authorize(self.client.post(format!("{}/rest/api/2/issue", self.base_url)))
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| ReportError::Other(format!("Failed to create Jira issue for {}: {}", finding.id, e)))?;
+
+        let body = Self::check_status(response, &format!("Jira issue creation for {}", finding.id)).await?;
+        let issue: IssueRef = serde_json::from_str(&body)?;
+        Ok(issue.key)
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.auth {
+            JiraAuth::Basic { email, api_token } => request.basic_auth(email, Some(api_token)),
+            JiraAuth::Bearer(token) => request.bearer_auth(token),
+        }
+    }
+
+    async fn check_status(response: reqwest::Response, target: &str) -> ReportResult<String> {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(ReportError::DeliveryFailed {
+                target: target.to_string(),
+                status: status.as_u16(),
+                body,
+            });
+        }
+        Ok(body)
+    }
+
+    /// Fields of the `POST /rest/api/2/issue` request for a finding
+    fn issue_payload(&self, finding: &Finding, label: &str) -> Value {
+        let category = serde_json::to_value(finding.category)
+            .ok()
+            .and_then(|c| c.as_str().map(str::to_string))
+            .unwrap_or_else(|| "other".to_string());
+
+        json!({
+            "fields": {
+                "project": { "key": self.project_key },
+                "issuetype": { "name": self.issue_type },
+                "summary": truncate(&format!("[kcpilot] {}", finding.title), 255),
+                "description": issue_description(finding),
+                "priority": { "name": jira_priority(finding.severity) },
+                "labels": ["kcpilot", format!("kcpilot-{}", category), label],
+            }
+        })
+    }
+}
+
+/// Label identifying the finding, stable across scans
+fn finding_label(finding: &Finding) -> String {
+    // Jira labels cannot contain spaces
+    format!("{}{}", FINDING_LABEL_PREFIX, stable_finding_id(&finding.id).replace(' ', "_"))
+}
+
+fn jira_priority(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "Highest",
+        Severity::High => "High",
+        Severity::Medium => "Medium",
+        Severity::Low => "Low",
+        Severity::Info => "Lowest",
+    }
+}
+
+/// Issue description in Jira wiki markup
+fn issue_description(finding: &Finding) -> String {
+    let mut description = format!(
+        "{}\n\nh3. Impact\n{}\n",
+        finding.description, finding.impact
+    );
+
+    if let Some(root_cause) = &finding.root_cause {
+        description.push_str(&format!("\nh3. Root cause\n{}\n", root_cause));
+    }
+
+    if !finding.remediation.steps.is_empty() {
+        description.push_str("\nh3. Remediation\n");
+        for step in &finding.remediation.steps {
+            description.push_str(&format!("# {}\n", step.description));
+            if let Some(command) = &step.command {
+                description.push_str(&format!("{{code:bash}}{}{{code}}\n", command));
+            }
+        }
+    }
+
+    description.push_str(&format!("\n_Reported by kcpilot, finding {}_", finding.id));
+    description
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    text.chars().take(max_chars).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::{Category, RemediationStep};
+    use mockito::Matcher;
+
+    const RULE_FINDING_ID: &str = "FND-006-3f2b8c1e-6a4d-4c7e-9b1a-2d5e8f0a1b2c";
+
+    fn finding(id: &str, severity: Severity) -> Finding {
+        let mut finding = Finding {
+            id: id.to_string(),
+            title: "Connectors in FAILED state".to_string(),
+            description: "1 connector has failed".to_string(),
+            severity,
+            category: Category::Client,
+            impact: "Data is not flowing".to_string(),
+            ..Default::default()
+        };
+        finding.remediation.steps.push(RemediationStep {
+            order: 1,
+            description: "Restart the failed connector".to_string(),
+            command: Some("curl -X POST http://connect:8083/connectors/jdbc-sink/restart".to_string()),
+            verification: None,
+            can_automate: true,
+        });
+        finding
+    }
+
+    fn reporter(server: &mockito::Server) -> JiraReporter {
+        JiraReporter::new(
+            server.url(),
+            "OPS",
+            JiraAuth::Basic { email: "ops@example.com".to_string(), api_token: "token".to_string() },
+        )
+    }
+
+    fn search_mock(server: &mut mockito::Server, label: &str, issues: Value) -> mockito::Mock {
+        server.mock("GET", "/rest/api/2/search")
+            .match_query(Matcher::UrlEncoded(
+                "jql".to_string(),
+                format!("project = \"OPS\" AND labels = \"{}\" AND statusCategory != Done", label),
+            ))
+            .with_status(200)
+            .with_body(json!({ "issues": issues }).to_string())
+    }
+
+    #[test]
+    fn test_finding_label_is_stable() {
+        assert_eq!(finding_label(&finding(RULE_FINDING_ID, Severity::High)), "kcpilot-finding-id-FND-006");
+    }
+
+    #[tokio::test]
+    async fn test_create_issue_for_new_finding() {
+        let mut server = mockito::Server::new_async().await;
+        let search = search_mock(&mut server, "kcpilot-finding-id-FND-006", json!([]))
+            .expect(1)
+            .create_async()
+            .await;
+        let create = server.mock("POST", "/rest/api/2/issue")
+            .match_header("authorization", Matcher::Regex("^Basic ".to_string()))
+            .match_body(Matcher::PartialJson(json!({
+                "fields": {
+                    "project": { "key": "OPS" },
+                    "summary": "[kcpilot] Connectors in FAILED state",
+                    "priority": { "name": "Highest" },
+                    "labels": ["kcpilot", "kcpilot-client", "kcpilot-finding-id-FND-006"],
+                }
+            })))
+            .with_status(201)
+            .with_body(r#"{"id":"10001","key":"OPS-42","self":"https://jira/rest/api/2/issue/10001"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let findings = vec![
+            finding(RULE_FINDING_ID, Severity::Critical),
+            finding("FND-007-low", Severity::Low),
+        ];
+        let created = reporter(&server).create_issues(&findings, Severity::High).await.unwrap();
+
+        assert_eq!(created, vec!["OPS-42"]);
+        search.assert_async().await;
+        create.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_existing_issue_is_not_duplicated() {
+        let mut server = mockito::Server::new_async().await;
+        search_mock(&mut server, "kcpilot-finding-id-FND-006", json!([{ "key": "OPS-7" }]))
+            .expect(1)
+            .create_async()
+            .await;
+        let create = server.mock("POST", "/rest/api/2/issue").expect(0).create_async().await;
+
+        let created = reporter(&server)
+            .create_issues(&[finding(RULE_FINDING_ID, Severity::Critical)], Severity::High)
+            .await
+            .unwrap();
+
+        assert!(created.is_empty());
+        create.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_reports_http_errors() {
+        let mut server = mockito::Server::new_async().await;
+        search_mock(&mut server, "kcpilot-finding-id-FND-006", json!([])).create_async().await;
+        server.mock("POST", "/rest/api/2/issue")
+            .with_status(400)
+            .with_body(r#"{"errors":{"priority":"Priority name 'Highest' is not valid"}}"#)
+            .create_async()
+            .await;
+
+        let err = reporter(&server)
+            .create_issues(&[finding(RULE_FINDING_ID, Severity::Critical)], Severity::High)
+            .await
+            .unwrap_err();
+
+        match err {
+            ReportError::DeliveryFailed { status, body, .. } => {
+                assert_eq!(status, 400);
+                assert!(body.contains("Priority name"));
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_description_lists_remediation_steps() {
+        let description = issue_description(&finding("FND-001", Severity::High));
+
+        assert!(description.contains("h3. Impact\nData is not flowing"));
+        assert!(description.contains("# Restart the failed connector\n{code:bash}curl -X POST"));
+    }
+}
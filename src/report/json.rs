@@ -1,13 +1,17 @@
-use crate::snapshot::format::{Finding, Snapshot, Severity};
+use crate::llm::UsageReport;
+use crate::report::scoring::{HealthScoreMethod, SeverityPenalties};
+use crate::report::ClusterOverview;
+use crate::snapshot::format::{CollectionEnvironment, Finding, Snapshot, Severity};
+use schemars::schema::RootSchema;
+use schemars::{schema_for, JsonSchema};
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::Write;
+use crate::report::output::write_atomic;
 use std::path::Path;
 use chrono::Utc;
 use anyhow::Result;
 
 /// JSON report structure with all relevant data
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct JsonReport {
     /// Report metadata
     pub metadata: ReportMetadata,
@@ -25,25 +29,33 @@ pub struct JsonReport {
     pub health_score: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ReportMetadata {
     pub report_generated_at: String,
     pub tool_version: String,
     pub scan_timestamp: String,
     pub output_directory: String,
+    /// LLM token usage of the analysis run, absent for static analysis
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llm_usage: Option<UsageReport>,
+    /// Host, platform and versions the scan ran with, absent for scans that did not record them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection_environment: Option<CollectionEnvironment>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ClusterInfo {
     pub cluster_id: Option<String>,
     pub cluster_mode: Option<String>,
     pub broker_count: usize,
     pub topic_count: Option<usize>,
+    #[serde(default)]
+    pub internal_topic_count: Option<usize>,
     pub partition_count: Option<usize>,
     pub bastion_host: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Summary {
     pub total_findings: usize,
     pub critical_count: usize,
@@ -53,8 +65,22 @@ pub struct Summary {
     pub info_count: usize,
 }
 
+/// JSON Schema of the JSON report, for tools that validate or generate code from it
+pub fn report_schema() -> RootSchema {
+    schema_for!(JsonReport)
+}
+
+/// JSON Schema of a single finding, an element of the report's `findings`
+pub fn finding_schema() -> RootSchema {
+    schema_for!(Finding)
+}
+
 /// JSON report generator
-pub struct JsonReporter;
+pub struct JsonReporter {
+    llm_usage: Option<UsageReport>,
+    score_method: HealthScoreMethod,
+    force: bool,
+}
 
 impl Default for JsonReporter {
     fn default() -> Self {
@@ -64,7 +90,29 @@ impl Default for JsonReporter {
 
 impl JsonReporter {
     pub fn new() -> Self {
-        Self
+        Self {
+            llm_usage: None,
+            score_method: HealthScoreMethod::default(),
+            force: false,
+        }
+    }
+    
+    /// Include LLM token usage in the report metadata
+    pub fn with_llm_usage(mut self, usage: Option<UsageReport>) -> Self {
+        self.llm_usage = usage;
+        self
+    }
+
+    /// Choose how the health score is calculated (default: flat)
+    pub fn with_score_method(mut self, method: HealthScoreMethod) -> Self {
+        self.score_method = method;
+        self
+    }
+
+    /// Overwrite an existing output file even when kcpilot did not write it
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
     }
     
     /// Generate and save JSON report
@@ -78,15 +126,14 @@ impl JsonReporter {
         if output_path == Path::new("-") || output_path == Path::new("") {
             println!("{}", json);
         } else {
-            let mut file = File::create(output_path)?;
-            file.write_all(json.as_bytes())?;
+            write_atomic(output_path, json.as_bytes(), self.force)?;
         }
         
         Ok(())
     }
     
     /// Generate the JSON report structure
-    fn generate_report(&self, snapshot: &Snapshot, findings: &[Finding]) -> JsonReport {
+    pub(crate) fn generate_report(&self, snapshot: &Snapshot, findings: &[Finding]) -> JsonReport {
         // Count findings by severity
         let mut critical_count = 0;
         let mut high_count = 0;
@@ -104,33 +151,17 @@ impl JsonReporter {
             }
         }
         
-        // Calculate health score (100 - penalties)
-        let health_score = (100.0
-            - (critical_count as f64 * 20.0)
-            - (high_count as f64 * 10.0)
-            - (medium_count as f64 * 5.0)
-            - (low_count as f64 * 2.0)
-            - (info_count as f64 * 0.5))
-            .max(0.0);
+        let health_score = self.score_method.score(SeverityPenalties::JSON, snapshot, findings);
         
-        // Extract cluster info
+        let overview = ClusterOverview::from_snapshot(snapshot);
         let cluster_info = ClusterInfo {
             cluster_id: snapshot.cluster.id.clone(),
-            cluster_mode: Some(format!("{:?}", snapshot.cluster.mode).to_lowercase()),
-            broker_count: snapshot.collectors.admin.as_ref()
-                .and_then(|admin| admin.get("brokers"))
-                .and_then(|brokers| brokers.as_array())
-                .map(|arr| arr.len())
-                .unwrap_or(0),
-            topic_count: snapshot.collectors.admin.as_ref()
-                .and_then(|admin| admin.get("topics"))
-                .and_then(|topics| topics.as_array())
-                .map(|arr| arr.len()),
-            partition_count: snapshot.collectors.admin.as_ref()
-                .and_then(|admin| admin.get("partitions"))
-                .and_then(|partitions| partitions.as_array())
-                .map(|arr| arr.len()),
-            bastion_host: snapshot.metadata.environment.clone(),
+            cluster_mode: Some(overview.mode_name()),
+            broker_count: overview.broker_count,
+            topic_count: overview.topic_count,
+            internal_topic_count: overview.internal_topic_count,
+            partition_count: overview.partition_count,
+            bastion_host: snapshot.metadata.environment.as_ref().and_then(|environment| environment.bastion.clone()),
         };
         
         JsonReport {
@@ -139,6 +170,8 @@ impl JsonReporter {
                 tool_version: snapshot.metadata.tool_version.clone(),
                 scan_timestamp: snapshot.timestamp.to_rfc3339(),
                 output_directory: snapshot.metadata.collection_id.clone(),
+                llm_usage: self.llm_usage.clone(),
+                collection_environment: snapshot.metadata.environment.clone(),
             },
             cluster_info,
             findings: findings.to_vec(),
@@ -158,7 +191,7 @@ impl JsonReporter {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::snapshot::format::{ClusterMode, CollectorOutputs, SnapshotMetadata, ClusterSnapshot, Category, Evidence, Remediation, RiskLevel};
+    use crate::snapshot::format::{ClusterMode, CollectorOutputs, SnapshotMetadata, ClusterSnapshot, Category, Evidence, LogEvidence, MetricEvidence, Remediation, RemediationStep, RiskLevel, TimelineEvent};
     use chrono::DateTime;
     use std::collections::HashMap;
 
@@ -172,7 +205,15 @@ mod tests {
                 collection_duration_ms: 60000,
                 collectors_used: vec!["admin".to_string()],
                 redaction_applied: false,
-                environment: Some("test-bastion".to_string()),
+                redaction_report: Default::default(),
+                environment: Some(CollectionEnvironment {
+                    os: Some("linux".to_string()),
+                    kcpilot_version: Some("1.0.0".to_string()),
+                    scan_host: Some("ops-laptop".to_string()),
+                    bastion: Some("test-bastion".to_string()),
+                    librdkafka_version: Some("2.3.0".to_string()),
+                    ..Default::default()
+                }),
                 tags: HashMap::new(),
             },
             cluster: ClusterSnapshot {
@@ -219,6 +260,7 @@ mod tests {
                     logs: Vec::new(),
                     configs: Vec::new(),
                     raw_data: None,
+                    timeline: Vec::new(),
                 },
                 root_cause: Some("Configuration mismatch".to_string()),
                 remediation: Remediation {
@@ -243,6 +285,7 @@ mod tests {
                     logs: Vec::new(),
                     configs: Vec::new(),
                     raw_data: None,
+                    timeline: Vec::new(),
                 },
                 root_cause: Some("Resource contention".to_string()),
                 remediation: Remediation {
@@ -267,6 +310,7 @@ mod tests {
                     logs: Vec::new(),
                     configs: Vec::new(),
                     raw_data: None,
+                    timeline: Vec::new(),
                 },
                 root_cause: None,
                 remediation: Remediation {
@@ -300,6 +344,9 @@ mod tests {
         assert_eq!(report.cluster_info.broker_count, 2);
         assert_eq!(report.cluster_info.topic_count, Some(3));
         assert_eq!(report.cluster_info.bastion_host, Some("test-bastion".to_string()));
+        let environment = report.metadata.collection_environment.as_ref().unwrap();
+        assert_eq!(environment.scan_host.as_deref(), Some("ops-laptop"));
+        assert_eq!(environment.librdkafka_version.as_deref(), Some("2.3.0"));
 
         // Test findings
         assert_eq!(report.findings.len(), 3);
@@ -318,6 +365,56 @@ mod tests {
         assert_eq!(report.health_score, 65.0);
     }
 
+    #[test]
+    fn test_schema_validates_report() {
+        let mut findings = create_test_findings();
+        let evidence = &mut findings[0].evidence;
+        evidence.metrics.push(MetricEvidence {
+            name: "under_replicated_partitions".to_string(),
+            value: 12.0,
+            threshold: Some(0.0),
+            unit: None,
+            source: "kafka_exporter".to_string(),
+            timestamp: DateTime::parse_from_rfc3339("2023-01-01T11:58:00Z").unwrap().into(),
+        });
+        evidence.logs.push(LogEvidence {
+            level: "ERROR".to_string(),
+            message: "Shrinking ISR".to_string(),
+            source_file: "server.log".to_string(),
+            line_number: Some(42),
+            timestamp: "2023-01-01T11:57:00Z".to_string(),
+            count: 3,
+        });
+        evidence.raw_data = Some(serde_json::json!({"partitions": ["orders-0"]}));
+        evidence.timeline.push(TimelineEvent {
+            timestamp: DateTime::parse_from_rfc3339("2023-01-01T11:57:00Z").unwrap().into(),
+            source: "broker_1/server.log".to_string(),
+            message: "WARN Shrinking ISR".to_string(),
+        });
+        findings[0].remediation.steps.push(RemediationStep {
+            order: 1,
+            description: "Restart broker 2".to_string(),
+            command: Some("systemctl restart kafka".to_string()),
+            verification: None,
+            can_automate: true,
+        });
+        findings[0].metadata.insert("analyzer".to_string(), serde_json::json!("replication"));
+
+        let reporter = JsonReporter::new().with_llm_usage(Some(UsageReport::new("gpt-4o")));
+        let report = serde_json::to_value(reporter.generate_report(&create_test_snapshot(), &findings)).unwrap();
+
+        let schema = jsonschema::JSONSchema::compile(&serde_json::to_value(report_schema()).unwrap()).unwrap();
+        if let Err(errors) = schema.validate(&report) {
+            panic!("report does not match its schema: {:?}", errors.map(|e| e.to_string()).collect::<Vec<_>>());
+        }
+
+        let finding = jsonschema::JSONSchema::compile(&serde_json::to_value(finding_schema()).unwrap()).unwrap();
+        assert!(finding.is_valid(&report["findings"][0]));
+        let mut invalid = report["findings"][0].clone();
+        invalid["severity"] = serde_json::json!("severe");
+        assert!(!finding.is_valid(&invalid));
+    }
+
     #[test]
     fn test_health_score_calculation() {
         let reporter = JsonReporter::new();
@@ -336,6 +433,7 @@ mod tests {
                 logs: Vec::new(),
                 configs: Vec::new(),
                 raw_data: None,
+                timeline: Vec::new(),
             },
             root_cause: None,
             remediation: Remediation {
@@ -365,6 +463,7 @@ mod tests {
                 logs: Vec::new(),
                 configs: Vec::new(),
                 raw_data: None,
+                timeline: Vec::new(),
             },
             root_cause: None,
             remediation: Remediation {
@@ -400,6 +499,7 @@ mod tests {
                     logs: Vec::new(),
                     configs: Vec::new(),
                     raw_data: None,
+                    timeline: Vec::new(),
                 },
                 root_cause: None,
                 remediation: Remediation {
@@ -424,6 +524,7 @@ mod tests {
                     logs: Vec::new(),
                     configs: Vec::new(),
                     raw_data: None,
+                    timeline: Vec::new(),
                 },
                 root_cause: None,
                 remediation: Remediation {
@@ -448,6 +549,7 @@ mod tests {
                     logs: Vec::new(),
                     configs: Vec::new(),
                     raw_data: None,
+                    timeline: Vec::new(),
                 },
                 root_cause: None,
                 remediation: Remediation {
@@ -472,6 +574,7 @@ mod tests {
                     logs: Vec::new(),
                     configs: Vec::new(),
                     raw_data: None,
+                    timeline: Vec::new(),
                 },
                 root_cause: None,
                 remediation: Remediation {
@@ -510,4 +613,23 @@ mod tests {
         assert_eq!(report.cluster_info.topic_count, None);
         assert_eq!(report.cluster_info.partition_count, None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_llm_usage_in_metadata() {
+        let snapshot = create_test_snapshot();
+
+        let report = JsonReporter::new().generate_report(&snapshot, &[]);
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json["metadata"].get("llm_usage").is_none());
+
+        let mut usage = UsageReport::new("gpt-4o-mini");
+        usage.record("gpt-4o-mini", 10_000, 2_000);
+        let report = JsonReporter::new()
+            .with_llm_usage(Some(usage))
+            .generate_report(&snapshot, &[]);
+        let json = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["metadata"]["llm_usage"]["total"], 12_000);
+        assert!(json["metadata"]["llm_usage"]["estimated_cost_usd"].as_f64().unwrap() > 0.0);
+    }
+}
@@ -1,15 +1,25 @@
-use super::{ReportGenerator, ReportResult};
-use crate::snapshot::format::{Finding, Severity, Snapshot, Category, RiskLevel};
-use std::fs::File;
-use std::io::Write;
+use super::scoring::{HealthScoreMethod, SeverityPenalties};
+use super::output::write_atomic;
+use super::{ClusterOverview, ReportGenerator, ReportResult};
+use crate::snapshot::format::{Finding, Severity, Snapshot, Category, RiskLevel, ConsumerGroupLag, CONSUMER_LAG_KEY};
+use std::collections::HashMap;
 use std::path::Path;
 use chrono::Utc;
 
+/// Total lag above which a consumer group is highlighted as a warning
+pub(crate) const LAG_WARNING_THRESHOLD: i64 = 1_000;
+
+/// Total lag above which a consumer group is highlighted as critical
+pub(crate) const LAG_CRITICAL_THRESHOLD: i64 = 100_000;
+
 /// Markdown report generator for file output
 pub struct MarkdownReporter {
     include_toc: bool,
     include_recommendations: bool,
     include_evidence: bool,
+    score_method: HealthScoreMethod,
+    executive_summary: Option<String>,
+    force: bool,
 }
 
 impl MarkdownReporter {
@@ -18,6 +28,9 @@ impl MarkdownReporter {
             include_toc: true,
             include_recommendations: true,
             include_evidence: true,
+            score_method: HealthScoreMethod::default(),
+            executive_summary: None,
+            force: false,
         }
     }
 
@@ -36,19 +49,34 @@ impl MarkdownReporter {
         self
     }
 
+    /// Choose how the health score is calculated (default: flat)
+    pub fn with_score_method(mut self, method: HealthScoreMethod) -> Self {
+        self.score_method = method;
+        self
+    }
+
+    /// Use an LLM-written executive summary instead of the templated one, when present
+    pub fn with_executive_summary(mut self, summary: Option<String>) -> Self {
+        self.executive_summary = summary;
+        self
+    }
+
+    /// Overwrite an existing output file even when kcpilot did not write it
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
     /// Generate a markdown report and save it to a file
     pub fn save_report(&self, snapshot: &Snapshot, findings: &[Finding], output_path: &Path) -> ReportResult<()> {
         let content = self.generate_markdown(snapshot, findings)?;
-        
-        let mut file = File::create(output_path)?;
-        file.write_all(content.as_bytes())?;
-        
-        Ok(())
+        write_atomic(output_path, content.as_bytes(), self.force)
     }
 
     /// Generate the markdown content
-    fn generate_markdown(&self, snapshot: &Snapshot, findings: &[Finding]) -> ReportResult<String> {
+    pub(crate) fn generate_markdown(&self, snapshot: &Snapshot, findings: &[Finding]) -> ReportResult<String> {
         let mut md = String::new();
+        let consumer_groups = self.consumer_groups(snapshot);
         
         // Header
         md.push_str("# Kafka Cluster Health Report\n\n");
@@ -56,17 +84,34 @@ impl MarkdownReporter {
         md.push_str(&format!("📅 **Report Date**: {}\n\n", Utc::now().format("%Y-%m-%d %H:%M:%S UTC")));
         
         // Table of Contents
+        let anchors = finding_anchors(findings);
         if self.include_toc {
             md.push_str("## Table of Contents\n\n");
-            md.push_str("- [Executive Summary](#executive-summary)\n");
-            md.push_str("- [Cluster Information](#cluster-information)\n");
-            md.push_str("- [Health Score](#health-score)\n");
-            md.push_str("- [Findings Summary](#findings-summary)\n");
-            md.push_str("- [Detailed Findings](#detailed-findings)\n");
-            if self.include_recommendations {
-                md.push_str("- [Recommendations](#recommendations)\n");
+            md.push_str(&toc_entry("Executive Summary"));
+            md.push_str(&toc_entry("Cluster Information"));
+            if !consumer_groups.is_empty() {
+                md.push_str(&toc_entry("Consumer Groups"));
+            }
+            md.push_str(&toc_entry("Health Score"));
+            md.push_str(&toc_entry("Findings Summary"));
+            md.push_str(&toc_entry("Detailed Findings"));
+            for severity in [Severity::Critical, Severity::High, Severity::Medium, Severity::Low, Severity::Info] {
+                let entries: Vec<usize> = (0..findings.len())
+                    .filter(|&i| findings[i].severity == severity)
+                    .collect();
+                if entries.is_empty() {
+                    continue;
+                }
+                md.push_str(&format!("  - {} {:?} ({})\n", severity.icon(), severity, entries.len()));
+                for i in entries {
+                    md.push_str(&format!("    - [{}](#{})\n", findings[i].title.replace(['[', ']'], ""), anchors[i]));
+                }
             }
-            md.push_str("- [Next Steps](#next-steps)\n\n");
+            if self.include_recommendations && !findings.is_empty() {
+                md.push_str(&toc_entry("Recommendations"));
+            }
+            md.push_str(&toc_entry("Next Steps"));
+            md.push('\n');
         }
 
         // Executive Summary
@@ -74,7 +119,10 @@ impl MarkdownReporter {
         let (critical, high, medium, low, info) = self.count_severities(findings);
         let total = findings.len();
         
-        if total == 0 {
+        if let Some(summary) = &self.executive_summary {
+            md.push_str(summary);
+            md.push_str("\n\n");
+        } else if total == 0 {
             md.push_str("✅ **No issues found!** Your Kafka cluster appears to be healthy.\n\n");
         } else {
             md.push_str(&format!("⚠️ **{} issue(s) detected** in your Kafka cluster requiring attention.\n\n", total));
@@ -99,12 +147,20 @@ impl MarkdownReporter {
             md.push_str(&format!("| **Kafka Version** | {} |\n", version));
         }
         
-        let mode_display = match &snapshot.cluster.mode {
+        let overview = ClusterOverview::from_snapshot(snapshot);
+        let mode_display = match &overview.mode {
             crate::snapshot::format::ClusterMode::Kraft => "KRaft (modern, Zookeeper-free)",
             crate::snapshot::format::ClusterMode::Zookeeper => "Zookeeper (legacy)",
             crate::snapshot::format::ClusterMode::Unknown => "Unknown",
         };
         md.push_str(&format!("| **Mode** | {} |\n", mode_display));
+        md.push_str(&format!("| **Brokers** | {} |\n", overview.broker_count));
+        if let Some(topics) = overview.topic_count {
+            md.push_str(&format!("| **Topics** | {} ({} internal) |\n", topics, overview.internal_topic_count.unwrap_or(0)));
+        }
+        if let Some(partitions) = overview.partition_count {
+            md.push_str(&format!("| **Partitions** | {} |\n", partitions));
+        }
         if let Some(provider) = &snapshot.cluster.cloud_provider {
             md.push_str(&format!("| **Cloud Provider** | {} |\n", provider));
         }
@@ -115,9 +171,14 @@ impl MarkdownReporter {
         md.push_str(&format!("| **Collection ID** | `{}` |\n", snapshot.metadata.collection_id));
         md.push('\n');
 
+        // Consumer Groups
+        if !consumer_groups.is_empty() {
+            self.write_consumer_groups(&mut md, &consumer_groups);
+        }
+
         // Health Score
         md.push_str("## Health Score\n\n");
-        let health_score = self.calculate_health_score(findings);
+        let health_score = self.score_method.score(SeverityPenalties::STANDARD, snapshot, findings);
         let score_emoji = if health_score >= 80.0 {
             "🟢"
         } else if health_score >= 60.0 {
@@ -193,12 +254,22 @@ impl MarkdownReporter {
         if findings.is_empty() {
             md.push_str("No issues were detected during the analysis.\n\n");
         } else {
-            // Group findings by severity
-            let mut sorted_findings = findings.to_vec();
-            sorted_findings.sort_by_key(|f| f.severity);
-            
-            for (idx, finding) in sorted_findings.iter().enumerate() {
-                self.write_finding(&mut md, idx + 1, finding)?;
+            // Group findings by category in collapsible blocks, most severe first within each group
+            let mut num = 0;
+            for (category, count) in self.count_categories(findings) {
+                let mut group: Vec<(usize, &Finding)> = findings.iter()
+                    .enumerate()
+                    .filter(|(_, f)| std::mem::discriminant(&f.category) == std::mem::discriminant(&category))
+                    .collect();
+                group.sort_by_key(|(_, f)| f.severity);
+
+                md.push_str(&format!("<details open>\n<summary><strong>{}</strong> ({} finding{})</summary>\n\n",
+                                    self.format_category(category), count, if count == 1 { "" } else { "s" }));
+                for (i, finding) in group {
+                    num += 1;
+                    self.write_finding(&mut md, num, &anchors[i], finding)?;
+                }
+                md.push_str("</details>\n\n");
             }
         }
 
@@ -245,11 +316,12 @@ impl MarkdownReporter {
         Ok(md)
     }
 
-    fn write_finding(&self, md: &mut String, num: usize, finding: &Finding) -> ReportResult<()> {
+    fn write_finding(&self, md: &mut String, num: usize, anchor: &str, finding: &Finding) -> ReportResult<()> {
         let severity_icon = finding.severity.icon();
         let severity_text = format!("{:?}", finding.severity);
         
-        // Finding header
+        // Finding header, with an explicit anchor since the emoji and number make the heading's own slug unstable
+        md.push_str(&format!("<a id=\"{}\"></a>\n\n", anchor));
         md.push_str(&format!("### {} Finding #{}: {}\n\n", severity_icon, num, finding.title));
         
         // Metadata table
@@ -276,7 +348,8 @@ impl MarkdownReporter {
         // Evidence
         if self.include_evidence && (!finding.evidence.metrics.is_empty() || 
                                     !finding.evidence.logs.is_empty() || 
-                                    !finding.evidence.configs.is_empty()) {
+                                    !finding.evidence.configs.is_empty() ||
+                                    !finding.evidence.timeline.is_empty()) {
             md.push_str("#### Evidence\n\n");
             
             // Config evidence
@@ -331,6 +404,20 @@ impl MarkdownReporter {
                 }
                 md.push('\n');
             }
+            
+            // Sequence of log events around the issue
+            if !finding.evidence.timeline.is_empty() {
+                md.push_str("**Timeline:**\n\n");
+                md.push_str("| Time (UTC) | Source | Event |\n");
+                md.push_str("|------------|--------|-------|\n");
+                for event in &finding.evidence.timeline {
+                    md.push_str(&format!("| {} | {} | `{}` |\n",
+                                       event.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                                       event.source,
+                                       event.message.chars().take(120).collect::<String>().replace('|', "\\|")));
+                }
+                md.push('\n');
+            }
         }
         
         // Remediation
@@ -367,6 +454,38 @@ impl MarkdownReporter {
         Ok(())
     }
 
+    /// Read consumer group lag data, most lagging groups first
+    pub(crate) fn consumer_groups(&self, snapshot: &Snapshot) -> Vec<ConsumerGroupLag> {
+        let mut groups: Vec<ConsumerGroupLag> = snapshot.collectors.custom.get(CONSUMER_LAG_KEY)
+            .and_then(|data| serde_json::from_value(data.clone()).ok())
+            .unwrap_or_default();
+        groups.sort_by_key(|g| std::cmp::Reverse(g.total_lag));
+        groups
+    }
+
+    fn write_consumer_groups(&self, md: &mut String, groups: &[ConsumerGroupLag]) {
+        md.push_str("## Consumer Groups\n\n");
+        md.push_str("| Group | State | Members | Total Lag |\n");
+        md.push_str("|-------|-------|---------|-----------|\n");
+
+        for group in groups {
+            let lag_icon = if group.total_lag >= LAG_CRITICAL_THRESHOLD {
+                "🔴"
+            } else if group.total_lag >= LAG_WARNING_THRESHOLD {
+                "🟡"
+            } else {
+                "🟢"
+            };
+            md.push_str(&format!("| `{}` | {} | {} | {} {} |\n",
+                                group.group,
+                                group.state.as_deref().unwrap_or("Unknown"),
+                                group.members,
+                                lag_icon,
+                                group.total_lag));
+        }
+        md.push('\n');
+    }
+
     fn count_severities(&self, findings: &[Finding]) -> (usize, usize, usize, usize, usize) {
         let critical = findings.iter().filter(|f| f.severity == Severity::Critical).count();
         let high = findings.iter().filter(|f| f.severity == Severity::High).count();
@@ -376,7 +495,7 @@ impl MarkdownReporter {
         (critical, high, medium, low, info)
     }
 
-    fn count_categories(&self, findings: &[Finding]) -> Vec<(Category, usize)> {
+    pub(crate) fn count_categories(&self, findings: &[Finding]) -> Vec<(Category, usize)> {
         let mut result = vec![];
         
         // Count each category type manually
@@ -398,11 +517,11 @@ impl MarkdownReporter {
             }
         }
         
-        result.sort_by(|a, b| b.1.cmp(&a.1));
+        result.sort_by_key(|b| std::cmp::Reverse(b.1));
         result
     }
 
-    fn format_category(&self, category: Category) -> &'static str {
+    pub(crate) fn format_category(&self, category: Category) -> &'static str {
         match category {
             Category::ClusterHygiene => "Cluster Hygiene",
             Category::Performance => "Performance",
@@ -422,23 +541,6 @@ impl MarkdownReporter {
             RiskLevel::High => "High 🔴",
         }
     }
-
-    fn calculate_health_score(&self, findings: &[Finding]) -> f64 {
-        let mut score = 100.0;
-        
-        for finding in findings {
-            let penalty = match finding.severity {
-                Severity::Critical => 25.0,
-                Severity::High => 15.0,
-                Severity::Medium => 8.0,
-                Severity::Low => 3.0,
-                Severity::Info => 0.0,
-            };
-            score -= penalty;
-        }
-        
-        f64::max(score, 0.0)
-    }
 }
 
 impl ReportGenerator for MarkdownReporter {
@@ -456,3 +558,177 @@ impl Default for MarkdownReporter {
         Self::new()
     }
 }
+
+/// Anchor of a heading as GitHub and GitLab generate it: lowercase, punctuation
+/// and emoji dropped, spaces turned into hyphens
+pub fn slugify(heading: &str) -> String {
+    heading.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+fn toc_entry(heading: &str) -> String {
+    format!("- [{}](#{})\n", heading, slugify(heading))
+}
+
+/// One anchor per finding derived from its id, suffixed like GitHub does when ids repeat
+pub(crate) fn finding_anchors(findings: &[Finding]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    findings.iter()
+        .map(|finding| {
+            let base = format!("finding-{}", slugify(&finding.id));
+            let count = seen.entry(base.clone()).or_insert(0);
+            let anchor = if *count == 0 { base } else { format!("{}-{}", base, count) };
+            *count += 1;
+            anchor
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+
+    fn create_test_snapshot() -> Snapshot {
+        Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()))
+    }
+
+    #[test]
+    fn test_consumer_groups_section_present() {
+        let mut snapshot = create_test_snapshot();
+        snapshot.collectors.custom.insert(CONSUMER_LAG_KEY.to_string(), serde_json::json!([
+            {"group": "billing", "state": "Stable", "members": 3, "total_lag": 12},
+            {"group": "audit", "state": "Empty", "members": 0, "total_lag": 250000},
+            {"group": "search", "state": "Stable", "members": 2, "total_lag": 5000}
+        ]));
+
+        let md = MarkdownReporter::new().generate_markdown(&snapshot, &[]).unwrap();
+
+        assert!(md.contains("- [Consumer Groups](#consumer-groups)"));
+        assert!(md.contains("## Consumer Groups"));
+        assert!(md.contains("| `audit` | Empty | 0 | 🔴 250000 |"));
+        assert!(md.contains("| `search` | Stable | 2 | 🟡 5000 |"));
+        assert!(md.contains("| `billing` | Stable | 3 | 🟢 12 |"));
+        // Most lagging group first
+        assert!(md.find("`audit`").unwrap() < md.find("`billing`").unwrap());
+    }
+
+    fn create_finding(id: &str, severity: Severity, category: Category) -> Finding {
+        Finding {
+            id: id.to_string(),
+            title: format!("Finding {}", id),
+            severity,
+            category,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_findings_grouped_by_category() {
+        let snapshot = create_test_snapshot();
+        let findings = vec![
+            create_finding("F-1", Severity::Low, Category::Configuration),
+            create_finding("F-2", Severity::High, Category::Performance),
+            create_finding("F-3", Severity::Critical, Category::Configuration),
+            create_finding("F-4", Severity::Medium, Category::Configuration),
+        ];
+
+        let md = MarkdownReporter::new().generate_markdown(&snapshot, &findings).unwrap();
+
+        assert_eq!(md.matches("<details open>").count(), 2);
+        assert_eq!(md.matches("</details>").count(), 2);
+        assert!(md.contains("<summary><strong>Configuration</strong> (3 findings)</summary>"));
+        assert!(md.contains("<summary><strong>Performance</strong> (1 finding)</summary>"));
+
+        // Larger group first, most severe finding first within the group
+        let config_group = md.find("<strong>Configuration</strong>").unwrap();
+        let perf_group = md.find("<strong>Performance</strong>").unwrap();
+        assert!(config_group < perf_group);
+        assert!(md.find("`F-3`").unwrap() < md.find("`F-4`").unwrap());
+        assert!(md.find("`F-4`").unwrap() < md.find("`F-1`").unwrap());
+    }
+
+    #[test]
+    fn test_consumer_groups_section_absent_without_data() {
+        let snapshot = create_test_snapshot();
+
+        let md = MarkdownReporter::new().generate_markdown(&snapshot, &[]).unwrap();
+
+        assert!(!md.contains("Consumer Groups"));
+    }
+
+    #[test]
+    fn test_ai_executive_summary_embedded() {
+        let snapshot = create_test_snapshot();
+        let findings = vec![create_finding("A", Severity::Critical, Category::Availability)];
+        let narrative = "Replication is at risk on two brokers.\n\n1. Restore the ISR of orders";
+
+        let md = MarkdownReporter::new()
+            .with_executive_summary(Some(narrative.to_string()))
+            .generate_markdown(&snapshot, &findings)
+            .unwrap();
+        let summary = &md[md.find("## Executive Summary").unwrap()..md.find("## Cluster Information").unwrap()];
+        assert!(summary.contains(narrative));
+        assert!(!summary.contains("issue(s) detected"));
+
+        // Without a narrative the templated summary is used
+        let md = MarkdownReporter::new().generate_markdown(&snapshot, &findings).unwrap();
+        assert!(md.contains("⚠️ **1 issue(s) detected**"));
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Executive Summary"), "executive-summary");
+        assert_eq!(slugify("CONFIG-BROKER-ID-001"), "config-broker-id-001");
+        assert_eq!(slugify("FND-006: Disk (usage) > 90%!"), "fnd-006-disk-usage--90");
+        assert_eq!(slugify("under_replicated.partitions"), "under_replicatedpartitions");
+    }
+
+    #[test]
+    fn test_toc_links_every_section_and_finding() {
+        let snapshot = create_test_snapshot();
+        let findings = vec![
+            create_finding("F-1", Severity::Low, Category::Configuration),
+            create_finding("F-2", Severity::High, Category::Performance),
+            create_finding("F-2", Severity::High, Category::Performance),
+            create_finding("FND-006-3f2b8c1e", Severity::Critical, Category::Configuration),
+        ];
+
+        let md = MarkdownReporter::new().generate_markdown(&snapshot, &findings).unwrap();
+        let toc = &md[md.find("## Table of Contents").unwrap()..md.find("## Executive Summary").unwrap()];
+
+        let links: Vec<&str> = toc.lines()
+            .filter_map(|line| line.split_once("](#").map(|(_, rest)| rest.trim_end_matches(')')))
+            .collect();
+
+        // Section links match the slug of a heading in the document
+        for section in ["executive-summary", "cluster-information", "health-score", "findings-summary",
+                        "detailed-findings", "recommendations", "next-steps"] {
+            assert!(links.contains(&section), "missing TOC entry for {}", section);
+        }
+        for link in links.iter().filter(|l| !l.starts_with("finding-")) {
+            assert!(md.lines().any(|line| line.starts_with("## ") && slugify(&line[3..]) == *link),
+                    "no heading for #{}", link);
+        }
+
+        // Every finding has a TOC entry pointing at the anchor right above its heading
+        let finding_links: Vec<&&str> = links.iter().filter(|l| l.starts_with("finding-")).collect();
+        assert_eq!(finding_links.len(), findings.len());
+        for anchor in ["finding-f-1", "finding-f-2", "finding-f-2-1", "finding-fnd-006-3f2b8c1e"] {
+            assert!(links.contains(&anchor), "missing TOC entry for {}", anchor);
+            let tag = format!("<a id=\"{}\"></a>\n\n### ", anchor);
+            assert_eq!(md.matches(&tag).count(), 1, "missing anchor {}", anchor);
+        }
+
+        // Findings are listed by severity in the TOC
+        assert!(toc.find("Critical (1)").unwrap() < toc.find("High (2)").unwrap());
+        assert!(toc.find("High (2)").unwrap() < toc.find("Low (1)").unwrap());
+    }
+}
@@ -1,9 +1,98 @@
 pub mod terminal;
 pub mod markdown;
+pub mod html;
 pub mod json;
+pub mod ndjson;
+pub mod esbulk;
+pub mod runbook;
+pub mod output;
+pub mod pagerduty;
+pub mod scoring;
+pub mod scripts;
+pub mod symbols;
+pub mod jira;
 
-use crate::snapshot::format::Snapshot;
-use std::path::Path;
+use crate::analyzers::server_properties;
+use crate::scan::listeners::parse_listeners;
+use crate::snapshot::format::{Category, ClusterMode, Finding, Snapshot};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+/// Finding id without the UUID suffix that rule findings carry, so the same
+/// problem keeps one id across scans even as the affected resources change
+pub fn stable_finding_id(id: &str) -> &str {
+    match id.len().checked_sub(37) {
+        Some(split) if id.as_bytes()[split] == b'-' && uuid::Uuid::parse_str(&id[split + 1..]).is_ok() => &id[..split],
+        _ => id,
+    }
+}
+
+/// Findings in one of `categories`, in their original order; all findings when no category is given
+pub fn filter_by_category(findings: &[Finding], categories: &[Category]) -> Vec<Finding> {
+    findings.iter()
+        .filter(|finding| categories.is_empty() || categories.contains(&finding.category))
+        .cloned()
+        .collect()
+}
+
+/// Cluster size and mode shown by every report, read once from the snapshot so all formats agree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClusterOverview {
+    pub mode: ClusterMode,
+    /// Brokers in the admin metadata, or the scanned broker directories without it
+    pub broker_count: usize,
+    /// Topic counts are `None` when the scan has no topic metadata
+    pub topic_count: Option<usize>,
+    pub internal_topic_count: Option<usize>,
+    /// Partitions over all topics
+    pub partition_count: Option<usize>,
+}
+
+impl ClusterOverview {
+    pub fn from_snapshot(snapshot: &Snapshot) -> Self {
+        let admin = snapshot.collectors.admin.as_ref();
+        let topics = admin
+            .and_then(|admin| admin.get("topics"))
+            .and_then(|topics| topics.as_array());
+
+        let broker_count = admin
+            .and_then(|admin| admin.get("brokers"))
+            .and_then(|brokers| brokers.as_array())
+            .map(|brokers| brokers.len())
+            .or_else(|| snapshot.collectors.custom.get("brokers")
+                .and_then(|brokers| brokers.as_object())
+                .map(|brokers| brokers.len()))
+            .unwrap_or(0);
+
+        let internal_topic_count = topics.map(|topics| topics.iter()
+            .filter(|topic| topic.get("is_internal").and_then(|internal| internal.as_bool()).unwrap_or(false)
+                || topic.get("name").and_then(|name| name.as_str()).is_some_and(|name| name.starts_with("__")))
+            .count());
+
+        let partition_count = topics.map(|topics| topics.iter()
+            .map(|topic| topic.get("partitions")
+                .and_then(|partitions| partitions.as_array())
+                .map(|partitions| partitions.len())
+                .unwrap_or(0))
+            .sum());
+
+        Self {
+            mode: snapshot.cluster.mode,
+            broker_count,
+            topic_count: topics.map(|topics| topics.len()),
+            internal_topic_count,
+            partition_count,
+        }
+    }
+
+    /// Mode as a lowercase name, e.g. `kraft`
+    pub fn mode_name(&self) -> String {
+        format!("{:?}", self.mode).to_lowercase()
+    }
+}
 
 /// Result type for report operations
 pub type ReportResult<T> = Result<T, ReportError>;
@@ -20,6 +109,19 @@ pub enum ReportError {
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
     
+    #[error("{target} failed with HTTP {status}: {body}")]
+    DeliveryFailed {
+        target: String,
+        status: u16,
+        body: String,
+    },
+    
+    #[error("Refusing to overwrite {}, which was not written by kcpilot; pass --force to replace it", .0.display())]
+    ForeignFileExists(PathBuf),
+    
+    #[error("{} is already being written by another report", .0.display())]
+    PathCollision(PathBuf),
+    
     #[error("Other error: {0}")]
     Other(String),
 }
@@ -32,3 +134,366 @@ pub trait ReportGenerator {
     /// Get generator name
     fn name(&self) -> &'static str;
 }
+
+/// Real names replaced by `--anonymize`, keyed by real name. Written to a local file
+/// so findings in a shared report can be traced back to the actual hosts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnonymizationMap {
+    /// Lowercased hostname or address to pseudonym, e.g. `kafka-1.prod.internal` to `broker-1`
+    pub hosts: BTreeMap<String, String>,
+    /// Lowercased cluster name to pseudonym, e.g. `payments` to `cluster-a`
+    pub clusters: BTreeMap<String, String>,
+}
+
+/// Consistently replaces broker hostnames and cluster names with pseudonyms in everything
+/// a report is built from: cluster info, collected data, findings and their evidence
+pub struct Anonymizer {
+    map: AnonymizationMap,
+    pseudonyms: HashMap<String, String>,
+    pattern: Option<Regex>,
+}
+
+/// Whether a hostname is only reachable from the broker itself, or binds every interface
+fn is_local_host(host: &str) -> bool {
+    matches!(host, "" | "localhost" | "0.0.0.0" | "::" | "::1") || host.starts_with("127.")
+}
+
+/// Hostnames of the scanned brokers: from their scan info, `hostname -f`, admin metadata and listeners
+fn snapshot_hostnames(snapshot: &Snapshot) -> BTreeSet<String> {
+    let mut hosts = BTreeSet::new();
+
+    if let Some(brokers) = snapshot.collectors.custom.get("brokers").and_then(|b| b.as_object()) {
+        for broker in brokers.values() {
+            let info_host = broker.get("broker_info.json").and_then(|info| info.get("hostname"));
+            let system_host = broker.get("system").and_then(|system| system.get("hostname.txt"));
+            hosts.extend([info_host, system_host].into_iter().flatten().filter_map(|h| h.as_str()).map(str::to_string));
+        }
+    }
+
+    if let Some(brokers) = snapshot.collectors.admin.as_ref().and_then(|a| a.get("brokers")).and_then(|b| b.as_array()) {
+        hosts.extend(brokers.iter().filter_map(|b| b.get("host").and_then(|h| h.as_str())).map(str::to_string));
+    }
+
+    for (_, props) in server_properties(snapshot) {
+        for key in ["listeners", "advertised.listeners"] {
+            if let Some(value) = props.get(key) {
+                hosts.extend(parse_listeners(value).into_iter().map(|listener| listener.host));
+            }
+        }
+    }
+
+    hosts.into_iter()
+        .map(|host| host.trim().to_lowercase())
+        .filter(|host| !is_local_host(host))
+        .collect()
+}
+
+/// `cluster-a` ... `cluster-z`, then `cluster-27` and up
+fn cluster_pseudonym(index: usize) -> String {
+    match u8::try_from(index).ok().filter(|i| *i < 26) {
+        Some(i) => format!("cluster-{}", (b'a' + i) as char),
+        None => format!("cluster-{}", index + 1),
+    }
+}
+
+impl Anonymizer {
+    /// Assign pseudonyms to every hostname and cluster name found in the snapshot.
+    /// Names are numbered in sorted order, so the same snapshot always yields the same mapping.
+    pub fn from_snapshot(snapshot: &Snapshot) -> Self {
+        let mut map = AnonymizationMap::default();
+
+        // Fully qualified names first, so a broker's short name shares its pseudonym
+        let (qualified, short): (Vec<String>, Vec<String>) = snapshot_hostnames(snapshot).into_iter()
+            .partition(|host| host.contains('.') && host.parse::<IpAddr>().is_err());
+        let mut next = 1;
+        for host in &qualified {
+            let pseudonym = format!("broker-{}", next);
+            next += 1;
+            let label = host.split('.').next().unwrap_or(host);
+            let ambiguous = qualified.iter().filter(|other| other.split('.').next() == Some(label)).count() > 1;
+            if !ambiguous {
+                map.hosts.entry(label.to_string()).or_insert_with(|| pseudonym.clone());
+            }
+            map.hosts.insert(host.clone(), pseudonym);
+        }
+        for host in short {
+            map.hosts.entry(host).or_insert_with(|| {
+                next += 1;
+                format!("broker-{}", next - 1)
+            });
+        }
+
+        let clusters: BTreeSet<String> = snapshot.cluster.name.iter()
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect();
+        for (i, cluster) in clusters.into_iter().enumerate() {
+            map.clusters.insert(cluster, cluster_pseudonym(i));
+        }
+
+        Self::new(map)
+    }
+
+    pub fn new(map: AnonymizationMap) -> Self {
+        let pseudonyms: HashMap<String, String> = map.hosts.iter()
+            .chain(map.clusters.iter())
+            .map(|(real, pseudonym)| (real.clone(), pseudonym.clone()))
+            .collect();
+
+        // Longest names first, so `kafka-1.prod.internal` wins over `kafka-1`
+        let mut names: Vec<&String> = pseudonyms.keys().collect();
+        names.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+        let pattern = (!names.is_empty()).then(|| {
+            let alternation = names.iter().map(|name| regex::escape(name)).collect::<Vec<_>>().join("|");
+            RegexBuilder::new(&alternation).case_insensitive(true).build().expect("escaped names form a valid regex")
+        });
+
+        Self { map, pseudonyms, pattern }
+    }
+
+    pub fn map(&self) -> &AnonymizationMap {
+        &self.map
+    }
+
+    /// Replace every real name in `text` that stands on its own, so `kafka-1` is not
+    /// rewritten inside `kafka-10` or `kafka-1.other.domain`
+    pub fn anonymize_text(&self, text: &str) -> String {
+        let Some(pattern) = &self.pattern else {
+            return text.to_string();
+        };
+        let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+
+        let mut result = String::with_capacity(text.len());
+        let mut copied = 0;
+        let mut pos = 0;
+        while let Some(m) = pattern.find_at(text, pos) {
+            let before = text[..m.start()].chars().next_back();
+            let mut after = text[m.end()..].chars();
+            let standalone = !before.is_some_and(|c| is_name_char(c) || c == '.')
+                && match after.next() {
+                    Some('.') => !after.next().is_some_and(|c| c.is_ascii_alphanumeric()),
+                    Some(c) => !is_name_char(c),
+                    None => true,
+                };
+
+            if standalone {
+                result.push_str(&text[copied..m.start()]);
+                result.push_str(&self.pseudonyms[&m.as_str().to_lowercase()]);
+                copied = m.end();
+                pos = m.end();
+            } else {
+                pos = m.start() + text[m.start()..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        result.push_str(&text[copied..]);
+        result
+    }
+
+    /// Anonymize every string and object key in a JSON value
+    fn anonymize_value(&self, value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::String(text) => serde_json::Value::String(self.anonymize_text(&text)),
+            serde_json::Value::Array(items) => items.into_iter().map(|item| self.anonymize_value(item)).collect(),
+            serde_json::Value::Object(fields) => fields.into_iter()
+                .map(|(key, field)| (self.anonymize_text(&key), self.anonymize_value(field)))
+                .collect(),
+            other => other,
+        }
+    }
+
+    fn anonymize<T: Serialize + for<'de> Deserialize<'de>>(&self, value: &T) -> ReportResult<T> {
+        Ok(serde_json::from_value(self.anonymize_value(serde_json::to_value(value)?))?)
+    }
+
+    /// Copy of the snapshot with cluster info and all collected data anonymized.
+    /// The scanning host and bastion are not cluster names, so they are dropped rather than mapped.
+    pub fn anonymize_snapshot(&self, snapshot: &Snapshot) -> ReportResult<Snapshot> {
+        let mut anonymized = self.anonymize(snapshot)?;
+        if let Some(environment) = anonymized.metadata.environment.as_mut() {
+            environment.scan_host = None;
+            environment.bastion = None;
+        }
+        Ok(anonymized)
+    }
+
+    /// Copies of the findings with titles, descriptions, evidence and remediation anonymized
+    pub fn anonymize_findings(&self, findings: &[Finding]) -> ReportResult<Vec<Finding>> {
+        self.anonymize(&findings.to_vec())
+    }
+
+    /// Write the mapping back to real names; it must stay with whoever ran the scan
+    pub fn save_map(&self, path: &Path) -> ReportResult<()> {
+        std::fs::write(path, serde_json::to_string_pretty(&self.map)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::{
+        Category, CollectionEnvironment, ConfigEvidence, Evidence, Remediation, RiskLevel, Severity, SnapshotMetadata
+    };
+    use serde_json::json;
+
+    fn snapshot() -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.cluster.name = Some("payments-prod".to_string());
+        snapshot.metadata.environment = Some(CollectionEnvironment {
+            os: Some("linux".to_string()),
+            scan_host: Some("ops-jumpbox-7".to_string()),
+            bastion: Some("payments-bastion".to_string()),
+            ..Default::default()
+        });
+        snapshot.collectors.custom.insert("brokers".to_string(), json!({
+            "broker_1": {"broker_info.json": {"id": 1, "hostname": "kafka-1.payments.internal"}},
+            "broker_2": {"broker_info.json": {"id": 2, "hostname": "kafka-2.payments.internal"}},
+        }));
+        snapshot.collectors.config = Some(json!({
+            "broker_1/server.properties": "listeners=PLAINTEXT://0.0.0.0:9092\nadvertised.listeners=PLAINTEXT://kafka-1.payments.internal:9092",
+            "broker_10/server.properties": "advertised.listeners=PLAINTEXT://10.0.0.12:9092",
+        }));
+        snapshot
+    }
+
+    fn finding(description: &str) -> Finding {
+        Finding {
+            id: "SEC-001".to_string(),
+            severity: Severity::High,
+            category: Category::Security,
+            title: "PLAINTEXT listeners exposed on kafka-2".to_string(),
+            description: description.to_string(),
+            impact: "payments-prod accepts unauthenticated clients".to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs: vec![ConfigEvidence {
+                    resource_type: "broker".to_string(),
+                    resource_name: "kafka-1.payments.internal".to_string(),
+                    config_key: "advertised.listeners".to_string(),
+                    current_value: "PLAINTEXT://kafka-1.payments.internal:9092".to_string(),
+                    recommended_value: None,
+                    reason: "Exposed listener".to_string(),
+                    source_files: vec!["broker_1/server.properties".to_string()],
+                }],
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: Vec::new(),
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: None,
+                rollback_plan: None,
+            },
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_filter_by_category() {
+        let findings: Vec<Finding> = [
+            ("SEC-001", Category::Security),
+            ("PERF-001", Category::Performance),
+            ("AVAIL-001", Category::Availability),
+            ("CFG-001", Category::Configuration),
+        ].into_iter()
+            .map(|(id, category)| Finding { id: id.to_string(), category, ..Default::default() })
+            .collect();
+
+        let scoped = filter_by_category(&findings, &[Category::Security, Category::Availability]);
+        let ids: Vec<&str> = scoped.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["SEC-001", "AVAIL-001"]);
+
+        assert_eq!(filter_by_category(&findings, &[]).len(), 4);
+        assert!(filter_by_category(&findings, &[Category::Capacity]).is_empty());
+    }
+
+    #[test]
+    fn test_cluster_overview_matches_across_reporters() {
+        let mut snapshot = snapshot();
+        snapshot.cluster.mode = ClusterMode::Kraft;
+        let partitions = |count: usize| (0..count).map(|id| json!({"id": id, "leader": 1, "replicas": [1, 2], "isr": [1, 2]})).collect::<Vec<_>>();
+        snapshot.collectors.admin = Some(json!({
+            "brokers": [{"id": 1, "host": "kafka-1"}, {"id": 2, "host": "kafka-2"}, {"id": 3, "host": "kafka-3"}],
+            "topics": [
+                {"name": "orders", "partitions": partitions(6), "is_internal": false},
+                {"name": "payments", "partitions": partitions(3), "is_internal": false},
+                {"name": "__consumer_offsets", "partitions": partitions(50), "is_internal": true},
+            ],
+        }));
+
+        let overview = ClusterOverview::from_snapshot(&snapshot);
+        assert_eq!(overview, ClusterOverview {
+            mode: ClusterMode::Kraft,
+            broker_count: 3,
+            topic_count: Some(3),
+            internal_topic_count: Some(1),
+            partition_count: Some(59),
+        });
+
+        let json = json::JsonReporter::new().generate_report(&snapshot, &[]).cluster_info;
+        assert_eq!(json.cluster_mode.as_deref(), Some("kraft"));
+        assert_eq!((json.broker_count, json.topic_count, json.internal_topic_count, json.partition_count), (3, Some(3), Some(1), Some(59)));
+
+        let markdown = markdown::MarkdownReporter::new().generate_markdown(&snapshot, &[]).unwrap();
+        assert!(markdown.contains("| **Brokers** | 3 |\n| **Topics** | 3 (1 internal) |\n| **Partitions** | 59 |\n"));
+
+        let html = html::HtmlReporter::new().render(&snapshot, &[]).unwrap();
+        assert!(html.contains("<td><strong>Partitions</strong></td>\n<td>59</td>"));
+
+        let terminal = terminal::TerminalReporter::new().with_colors(false).render(&snapshot, &[]);
+        assert!(terminal.contains("  Brokers:         3\n  Topics:          3 (1 internal)\n  Partitions:      59\n"));
+
+        assert_eq!(scoring::ClusterScale::from_snapshot(&snapshot).partitions, 59);
+
+        // Without admin data the scanned broker directories are counted and topics are unknown
+        snapshot.collectors.admin = None;
+        let overview = ClusterOverview::from_snapshot(&snapshot);
+        assert_eq!((overview.broker_count, overview.topic_count, overview.partition_count), (2, None, None));
+    }
+
+    #[test]
+    fn test_same_name_maps_to_same_pseudonym() {
+        let anonymizer = Anonymizer::from_snapshot(&snapshot());
+        let hosts = &anonymizer.map().hosts;
+
+        assert_eq!(hosts["kafka-1.payments.internal"], "broker-1");
+        assert_eq!(hosts["kafka-1"], "broker-1");
+        assert_eq!(hosts["kafka-2.payments.internal"], "broker-2");
+        assert_eq!(hosts["10.0.0.12"], "broker-3");
+        assert_eq!(anonymizer.map().clusters["payments-prod"], "cluster-a");
+
+        assert_eq!(
+            anonymizer.anonymize_text("KAFKA-1.payments.internal:9092 and kafka-1 lag behind kafka-2."),
+            "broker-1:9092 and broker-1 lag behind broker-2."
+        );
+        // Longer names that merely start with a known one are left alone
+        assert_eq!(anonymizer.anonymize_text("kafka-10 kafka-1.other.net"), "kafka-10 kafka-1.other.net");
+    }
+
+    #[test]
+    fn test_real_names_absent_from_anonymized_output() {
+        let snapshot = snapshot();
+        let findings = vec![finding("Broker kafka-1.payments.internal (10.0.0.12) of payments-prod advertises PLAINTEXT")];
+        let anonymizer = Anonymizer::from_snapshot(&snapshot);
+
+        let anonymized_snapshot = anonymizer.anonymize_snapshot(&snapshot).unwrap();
+        let anonymized_findings = anonymizer.anonymize_findings(&findings).unwrap();
+        assert_eq!(anonymized_snapshot.cluster.name.as_deref(), Some("cluster-a"));
+        assert_eq!(anonymized_findings[0].description, "Broker broker-1 (broker-3) of cluster-a advertises PLAINTEXT");
+        assert_eq!(anonymized_findings[0].evidence.configs[0].current_value, "PLAINTEXT://broker-1:9092");
+
+        let output = format!(
+            "{}{}",
+            serde_json::to_string(&anonymized_snapshot).unwrap(),
+            serde_json::to_string(&anonymized_findings).unwrap()
+        );
+        assert_eq!(anonymized_snapshot.metadata.environment.unwrap().os.as_deref(), Some("linux"));
+        for real in ["kafka-1", "kafka-2", "payments.internal", "10.0.0.12", "payments-prod", "ops-jumpbox-7", "payments-bastion"] {
+            assert!(!output.contains(real), "{} leaked into anonymized output", real);
+        }
+    }
+}
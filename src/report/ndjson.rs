@@ -0,0 +1,162 @@
+use crate::snapshot::format::{Finding, Snapshot};
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use crate::report::output::write_atomic;
+use std::io::Write;
+use std::path::Path;
+
+/// First line of an NDJSON report
+#[derive(Debug, Serialize)]
+struct MetadataLine<'a> {
+    record: &'static str,
+    cluster_id: Option<&'a str>,
+    scan_timestamp: String,
+    report_generated_at: String,
+    tool_version: &'a str,
+    finding_count: usize,
+}
+
+/// One finding per line, tagged with the cluster so lines can be ingested on their own
+#[derive(Debug, Serialize)]
+struct FindingLine<'a> {
+    record: &'static str,
+    cluster_id: Option<&'a str>,
+    #[serde(flatten)]
+    finding: &'a Finding,
+}
+
+/// JSON Lines report generator: a metadata line followed by one compact line per finding
+pub struct NdjsonReporter {
+    force: bool,
+}
+
+impl Default for NdjsonReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NdjsonReporter {
+    pub fn new() -> Self {
+        Self { force: false }
+    }
+
+    /// Overwrite an existing output file even when kcpilot did not write it
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Write the report to a file, or to stdout when the path is `-`
+    pub fn save_report(&self, snapshot: &Snapshot, findings: &[Finding], output_path: &Path) -> Result<()> {
+        if output_path == Path::new("-") || output_path == Path::new("") {
+            let stdout = std::io::stdout();
+            self.write_report(snapshot, findings, stdout.lock())
+        } else {
+            let mut buffer = Vec::new();
+            self.write_report(snapshot, findings, &mut buffer)?;
+            Ok(write_atomic(output_path, &buffer, self.force)?)
+        }
+    }
+
+    /// Write the metadata line and one line per finding
+    pub fn write_report<W: Write>(&self, snapshot: &Snapshot, findings: &[Finding], mut writer: W) -> Result<()> {
+        let cluster_id = snapshot.cluster.id.as_deref();
+
+        let metadata = MetadataLine {
+            record: "metadata",
+            cluster_id,
+            scan_timestamp: snapshot.timestamp.to_rfc3339(),
+            report_generated_at: Utc::now().to_rfc3339(),
+            tool_version: &snapshot.metadata.tool_version,
+            finding_count: findings.len(),
+        };
+        serde_json::to_writer(&mut writer, &metadata)?;
+        writer.write_all(b"\n")?;
+
+        for finding in findings {
+            serde_json::to_writer(&mut writer, &FindingLine { record: "finding", cluster_id, finding })?;
+            writer.write_all(b"\n")?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::{Category, Evidence, Remediation, RiskLevel, Severity, SnapshotMetadata};
+    use std::collections::HashMap;
+
+    fn finding(id: &str, severity: Severity) -> Finding {
+        Finding {
+            id: id.to_string(),
+            title: "Under-replicated partitions".to_string(),
+            description: "Partitions are missing replicas\nacross two brokers".to_string(),
+            severity,
+            category: Category::Availability,
+            impact: "Reduced durability".to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: Vec::new(),
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: None,
+                rollback_plan: None,
+            },
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_one_line_per_finding() {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+        snapshot.cluster.id = Some("prod-cluster".to_string());
+        let findings = vec![finding("A-001", Severity::Critical), finding("B-002", Severity::Low)];
+
+        let mut output = Vec::new();
+        NdjsonReporter::new().write_report(&snapshot, &findings, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), findings.len() + 1);
+        let parsed: Vec<serde_json::Value> = lines.iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(parsed[0]["record"], "metadata");
+        assert_eq!(parsed[0]["cluster_id"], "prod-cluster");
+        assert_eq!(parsed[0]["scan_timestamp"], snapshot.timestamp.to_rfc3339());
+        assert_eq!(parsed[0]["finding_count"], 2);
+
+        assert_eq!(parsed[1]["record"], "finding");
+        assert_eq!(parsed[1]["cluster_id"], "prod-cluster");
+        assert_eq!(parsed[1]["id"], "A-001");
+        assert_eq!(parsed[1]["severity"], "critical");
+        assert_eq!(parsed[2]["description"], "Partitions are missing replicas\nacross two brokers");
+    }
+
+    #[test]
+    fn test_no_findings_writes_metadata_only() {
+        let snapshot = Snapshot::new(SnapshotMetadata::new("1.0.0".to_string()));
+
+        let mut output = Vec::new();
+        NdjsonReporter::new().write_report(&snapshot, &[], &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(output.lines().count(), 1);
+        let metadata: serde_json::Value = serde_json::from_str(output.trim_end()).unwrap();
+        assert!(metadata["cluster_id"].is_null());
+    }
+}
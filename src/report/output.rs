@@ -0,0 +1,184 @@
+use super::{ReportError, ReportResult};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Text every kcpilot report or script carries near its start, used to tell our own
+/// earlier output apart from files that merely share its name
+const KCPILOT_MARKERS: [&str; 4] = [
+    // JSON, NDJSON and Elasticsearch bulk reports
+    "\"report_generated_at\"",
+    // Markdown reports and runbooks
+    "Generated by **KCPilot**",
+    // HTML reports and topic pages
+    "<meta name=\"generator\" content=\"KCPilot\">",
+    // Remediation scripts
+    "GENERATED BY KCPILOT",
+];
+
+/// How much of an existing file is searched for a marker
+const MARKER_SCAN_BYTES: u64 = 8 * 1024;
+
+/// Output paths currently being written by this process
+fn in_flight() -> &'static Mutex<HashSet<PathBuf>> {
+    static IN_FLIGHT: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+    IN_FLIGHT.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Claim on an output path, released when dropped
+struct PathClaim(PathBuf);
+
+impl PathClaim {
+    /// Claim `path`, failing when another report in this process is writing it right now
+    fn acquire(path: &Path) -> ReportResult<Self> {
+        let key = claim_key(path);
+        let mut claimed = in_flight().lock().unwrap_or_else(|e| e.into_inner());
+        if !claimed.insert(key.clone()) {
+            return Err(ReportError::PathCollision(path.to_path_buf()));
+        }
+        Ok(Self(key))
+    }
+}
+
+impl Drop for PathClaim {
+    fn drop(&mut self) {
+        in_flight().lock().unwrap_or_else(|e| e.into_inner()).remove(&self.0);
+    }
+}
+
+/// Absolute form of `path`, so `report.md` and `./report.md` collide. The file itself
+/// may not exist yet, so only its directory is canonicalized.
+fn claim_key(path: &Path) -> PathBuf {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    match (fs::canonicalize(parent), path.file_name()) {
+        (Ok(dir), Some(name)) => dir.join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Whether `path` starts like something kcpilot wrote. An empty file, such as a bulk report
+/// without findings, has nothing to lose and counts as kcpilot output.
+pub fn is_kcpilot_output(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let mut head = Vec::new();
+    if file.take(MARKER_SCAN_BYTES).read_to_end(&mut head).is_err() {
+        return false;
+    }
+    let head = String::from_utf8_lossy(&head);
+    head.is_empty() || KCPILOT_MARKERS.iter().any(|marker| head.contains(marker))
+}
+
+/// Write a report file so readers only ever see the old or the new content: the bytes go
+/// to a temporary file next to `path`, which is then renamed over it. An existing file
+/// that kcpilot did not write is left alone unless `force` is set.
+pub fn write_atomic(path: &Path, contents: &[u8], force: bool) -> ReportResult<()> {
+    let _claim = PathClaim::acquire(path)?;
+
+    if !force && path.exists() && !is_kcpilot_output(path) {
+        return Err(ReportError::ForeignFileExists(path.to_path_buf()));
+    }
+
+    let file_name = path.file_name()
+        .ok_or_else(|| ReportError::Other(format!("{} is not a file path", path.display())))?;
+    let temp_path = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        uuid::Uuid::new_v4().simple()
+    ));
+
+    let written = File::create(&temp_path).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|()| fs::rename(&temp_path, path)) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+    use tempfile::TempDir;
+
+    fn report(n: usize) -> String {
+        format!("{{\"metadata\": {{\"report_generated_at\": \"now\"}}, \"body\": \"{}\"}}", n.to_string().repeat(50_000))
+    }
+
+    #[test]
+    fn test_concurrent_writes_leave_complete_files() {
+        let dir = Arc::new(TempDir::new().unwrap());
+        let shared = dir.path().join("report.json");
+
+        let handles: Vec<_> = (0..8)
+            .map(|n| {
+                let dir = Arc::clone(&dir);
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    // Every thread writes its own file, and all race for the shared one
+                    write_atomic(&dir.path().join(format!("report-{n}.json")), report(n).as_bytes(), false).unwrap();
+                    match write_atomic(&shared, report(n).as_bytes(), false) {
+                        Ok(()) | Err(ReportError::PathCollision(_)) => {}
+                        Err(e) => panic!("unexpected error: {e}"),
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        for n in 0..8 {
+            let content = fs::read_to_string(dir.path().join(format!("report-{n}.json"))).unwrap();
+            assert_eq!(content, report(n));
+        }
+        let content = fs::read_to_string(&shared).unwrap();
+        assert!((0..8).any(|n| content == report(n)), "shared report is a mix of writes");
+
+        // No temporary files are left behind
+        let leftovers: Vec<_> = fs::read_dir(dir.path()).unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn test_path_in_use_is_a_collision() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("report.md");
+
+        let _claim = PathClaim::acquire(&path).unwrap();
+        let err = write_atomic(&dir.path().join(".").join("report.md"), b"# Report", true).unwrap_err();
+        assert!(matches!(err, ReportError::PathCollision(_)));
+    }
+
+    #[test]
+    fn test_foreign_file_needs_force() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.md");
+        fs::write(&path, "my own notes").unwrap();
+
+        let err = write_atomic(&path, b"Generated by **KCPilot** v1", false).unwrap_err();
+        assert!(matches!(err, ReportError::ForeignFileExists(_)));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "my own notes");
+
+        write_atomic(&path, b"Generated by **KCPilot** v1", true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Generated by **KCPilot** v1");
+
+        // Now that kcpilot wrote it, later runs replace it without --force
+        write_atomic(&path, b"Generated by **KCPilot** v2", false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Generated by **KCPilot** v2");
+    }
+}
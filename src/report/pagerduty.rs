@@ -0,0 +1,235 @@
+use crate::snapshot::format::{Finding, Severity};
+use serde_json::{json, Value};
+use tracing::{debug, info};
+
+use super::{stable_finding_id, ReportError, ReportResult};
+
+/// PagerDuty Events API v2 endpoint
+pub const PAGERDUTY_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+/// Environment variable holding the integration routing key
+pub const PAGERDUTY_ROUTING_KEY_ENV: &str = "PAGERDUTY_ROUTING_KEY";
+
+/// Opens PagerDuty incidents for findings at or above a severity threshold
+pub struct PagerDutyReporter {
+    routing_key: String,
+    endpoint: String,
+    min_severity: Severity,
+    client: reqwest::Client,
+}
+
+impl PagerDutyReporter {
+    pub fn new(routing_key: impl Into<String>) -> Self {
+        Self {
+            routing_key: routing_key.into(),
+            endpoint: PAGERDUTY_EVENTS_URL.to_string(),
+            min_severity: Severity::Critical,
+            client: crate::http::client_builder()
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Send events to a different Events API endpoint
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Trigger incidents for findings at this severity or worse (default: critical)
+    pub fn with_min_severity(mut self, severity: Severity) -> Self {
+        self.min_severity = severity;
+        self
+    }
+
+    /// Send a trigger event for each finding meeting the threshold, returning how many were sent
+    pub async fn trigger(&self, findings: &[Finding]) -> ReportResult<usize> {
+        let alerting: Vec<&Finding> = findings
+            .iter()
+            .filter(|f| f.severity <= self.min_severity)
+            .collect();
+
+        if alerting.is_empty() {
+            debug!("No findings at or above {:?}, not triggering PagerDuty", self.min_severity);
+            return Ok(0);
+        }
+
+        for finding in &alerting {
+            let event = self.trigger_event(finding);
+            let response = self.client
+                .post(&self.endpoint)
+                .json(&event)
+                .send()
+                .await
+                .map_err(|e| ReportError::Other(format!("Failed to send PagerDuty event for {}: {}", finding.id, e)))?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(ReportError::DeliveryFailed {
+                    target: format!("PagerDuty event for {}", finding.id),
+                    status: status.as_u16(),
+                    body,
+                });
+            }
+        }
+
+        info!("Triggered {} PagerDuty incident(s)", alerting.len());
+        Ok(alerting.len())
+    }
+
+    /// Build the Events API v2 trigger payload for a finding
+    fn trigger_event(&self, finding: &Finding) -> Value {
+        json!({
+            "routing_key": self.routing_key,
+            "event_action": "trigger",
+            "dedup_key": dedup_key(finding),
+            "payload": {
+                "summary": truncate(&finding.title, 1024),
+                "source": "kcpilot",
+                "severity": pagerduty_severity(finding.severity),
+                "class": finding.category,
+                "custom_details": {
+                    "finding_id": finding.id,
+                    "description": finding.description,
+                    "impact": finding.impact,
+                    "remediation": finding.remediation.steps.iter()
+                        .map(|step| step.description.as_str())
+                        .collect::<Vec<_>>(),
+                },
+            },
+        })
+    }
+}
+
+/// Stable key so the same finding on repeated scans updates one incident
+pub fn dedup_key(finding: &Finding) -> String {
+    format!("kcpilot-{}", stable_finding_id(&finding.id))
+}
+
+fn pagerduty_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "critical",
+        Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low | Severity::Info => "info",
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    text.chars().take(max_chars).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::{Category, Evidence, Remediation, RiskLevel};
+    use mockito::Matcher;
+    use std::collections::HashMap;
+
+    const RULE_FINDING_ID: &str = "FND-006-3f2b8c1e-6a4d-4c7e-9b1a-2d5e8f0a1b2c";
+
+    fn finding(id: &str, severity: Severity) -> Finding {
+        Finding {
+            id: id.to_string(),
+            title: "Connectors in FAILED state".to_string(),
+            description: "1 connector has failed".to_string(),
+            severity,
+            category: Category::Client,
+            impact: "Data is not flowing".to_string(),
+            evidence: Evidence {
+                metrics: Vec::new(),
+                logs: Vec::new(),
+                configs: Vec::new(),
+                raw_data: None,
+                timeline: Vec::new(),
+            },
+            root_cause: None,
+            remediation: Remediation {
+                steps: Vec::new(),
+                script: None,
+                risk_level: RiskLevel::Low,
+                requires_downtime: false,
+                estimated_duration_minutes: None,
+                rollback_plan: None,
+            },
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_dedup_key_drops_run_uuid() {
+        assert_eq!(dedup_key(&finding(RULE_FINDING_ID, Severity::Critical)), "kcpilot-FND-006");
+        assert_eq!(dedup_key(&finding("replication-check-002", Severity::Critical)), "kcpilot-replication-check-002");
+    }
+
+    #[tokio::test]
+    async fn test_trigger_sends_critical_findings() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/v2/enqueue")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "routing_key": "test-routing-key",
+                "event_action": "trigger",
+                "dedup_key": "kcpilot-FND-006",
+                "payload": {
+                    "summary": "Connectors in FAILED state",
+                    "source": "kcpilot",
+                    "severity": "critical",
+                    "class": "client",
+                    "custom_details": { "finding_id": RULE_FINDING_ID },
+                },
+            })))
+            .with_status(202)
+            .with_body(r#"{"status":"success","dedup_key":"kcpilot-FND-006"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let reporter = PagerDutyReporter::new("test-routing-key")
+            .with_endpoint(format!("{}/v2/enqueue", server.url()));
+        let findings = vec![
+            finding(RULE_FINDING_ID, Severity::Critical),
+            finding("FND-007-x", Severity::High),
+        ];
+
+        let sent = reporter.trigger(&findings).await.unwrap();
+
+        assert_eq!(sent, 1);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_trigger_nothing_below_threshold() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", Matcher::Any).expect(0).create_async().await;
+
+        let reporter = PagerDutyReporter::new("test-routing-key")
+            .with_endpoint(server.url())
+            .with_min_severity(Severity::High);
+        let findings = vec![finding("a", Severity::Medium), finding("b", Severity::Info)];
+
+        assert_eq!(reporter.trigger(&findings).await.unwrap(), 0);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_trigger_reports_http_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/")
+            .with_status(400)
+            .with_body(r#"{"status":"invalid event","errors":["routing_key is invalid"]}"#)
+            .create_async()
+            .await;
+
+        let reporter = PagerDutyReporter::new("bad-key").with_endpoint(format!("{}/", server.url()));
+        let err = reporter.trigger(&[finding("a", Severity::Critical)]).await.unwrap_err();
+
+        match err {
+            ReportError::DeliveryFailed { status, body, .. } => {
+                assert_eq!(status, 400);
+                assert!(body.contains("routing_key is invalid"));
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use super::output::write_atomic;
+use super::{ReportGenerator, ReportResult};
+use crate::snapshot::format::{Finding, RemediationStep, Severity, Snapshot};
+use chrono::Utc;
+use std::path::Path;
+
+/// One runbook item: a remediation step and every finding it addresses
+#[derive(Debug)]
+struct RunbookStep<'a> {
+    step: &'a RemediationStep,
+    /// Severity of the most severe finding the step belongs to
+    severity: Severity,
+    finding_ids: Vec<&'a str>,
+    requires_downtime: bool,
+}
+
+/// Steps sharing a command are the same step; steps without one are told apart by description
+fn step_key(step: &RemediationStep) -> String {
+    match &step.command {
+        Some(command) => format!("command:{}", command.trim()),
+        None => format!("description:{}", step.description.trim()),
+    }
+}
+
+/// Runbook generator: the remediation steps of all findings as one ordered markdown checklist,
+/// most severe findings first, with steps shared between findings listed once
+pub struct RunbookReporter {
+    force: bool,
+}
+
+impl Default for RunbookReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunbookReporter {
+    pub fn new() -> Self {
+        Self { force: false }
+    }
+
+    /// Overwrite an existing output file even when kcpilot did not write it
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Generate the runbook and save it to a file
+    pub fn save_report(&self, snapshot: &Snapshot, findings: &[Finding], output_path: &Path) -> ReportResult<()> {
+        let content = self.generate_runbook(snapshot, findings);
+        write_atomic(output_path, content.as_bytes(), self.force)
+    }
+
+    /// Remediation steps ordered by finding severity, then by their order within the finding.
+    /// A step repeated by a later finding is merged into its first occurrence.
+    fn steps<'a>(&self, findings: &'a [Finding]) -> Vec<RunbookStep<'a>> {
+        let mut ordered: Vec<&Finding> = findings.iter().collect();
+        ordered.sort_by_key(|f| f.severity);
+
+        let mut steps: Vec<RunbookStep> = Vec::new();
+        let mut keys: Vec<String> = Vec::new();
+        for finding in ordered {
+            let mut finding_steps: Vec<&RemediationStep> = finding.remediation.steps.iter().collect();
+            finding_steps.sort_by_key(|step| step.order);
+
+            for step in finding_steps {
+                let key = step_key(step);
+                match keys.iter().position(|existing| *existing == key) {
+                    Some(index) => {
+                        let existing = &mut steps[index];
+                        if !existing.finding_ids.contains(&finding.id.as_str()) {
+                            existing.finding_ids.push(&finding.id);
+                        }
+                        existing.requires_downtime |= finding.remediation.requires_downtime;
+                    }
+                    None => {
+                        keys.push(key);
+                        steps.push(RunbookStep {
+                            step,
+                            severity: finding.severity,
+                            finding_ids: vec![&finding.id],
+                            requires_downtime: finding.remediation.requires_downtime,
+                        });
+                    }
+                }
+            }
+        }
+        steps
+    }
+
+    fn push_step(md: &mut String, number: usize, item: &RunbookStep) {
+        md.push_str(&format!(
+            "{}. [ ] {} **{:?}** {}",
+            number, item.severity.icon(), item.severity, item.step.description
+        ));
+        if item.requires_downtime {
+            md.push_str(" **(requires downtime)**");
+        }
+        md.push('\n');
+        if let Some(command) = &item.step.command {
+            md.push_str(&format!("   ```bash\n   {}\n   ```\n", command));
+        }
+        if let Some(verification) = &item.step.verification {
+            md.push_str(&format!("   - **Verify**: {}\n", verification));
+        }
+        md.push_str(&format!("   - **Findings**: {}\n", item.finding_ids.join(", ")));
+    }
+
+    /// Generate the runbook markdown
+    pub fn generate_runbook(&self, snapshot: &Snapshot, findings: &[Finding]) -> String {
+        let steps = self.steps(findings);
+        let (automatable, manual): (Vec<&RunbookStep>, Vec<&RunbookStep>) = steps.iter()
+            .partition(|item| item.step.can_automate);
+
+        let mut md = String::new();
+        md.push_str("# Kafka Remediation Runbook\n\n");
+        md.push_str(&format!("Generated by **KCPilot** v{}\n\n", snapshot.metadata.tool_version));
+        if let Some(name) = &snapshot.cluster.name {
+            md.push_str(&format!("**Cluster**: {}\n", name));
+        }
+        md.push_str(&format!("**Snapshot**: {}\n", snapshot.timestamp.format("%Y-%m-%d %H:%M:%S UTC")));
+        md.push_str(&format!("**Generated**: {}\n\n", Utc::now().format("%Y-%m-%d %H:%M:%S UTC")));
+
+        if steps.is_empty() {
+            md.push_str("No remediation steps: the findings need no action.\n");
+            return md;
+        }
+
+        let downtime = steps.iter().filter(|item| item.requires_downtime).count();
+        md.push_str(&format!(
+            "{} step(s) from {} finding(s), most severe first: {} automatable, {} manual.",
+            steps.len(), findings.len(), automatable.len(), manual.len()
+        ));
+        if downtime > 0 {
+            md.push_str(&format!(" {} step(s) belong to remediations requiring downtime; schedule a maintenance window for them.", downtime));
+        }
+        md.push_str("\n\n");
+
+        for (title, section) in [("Automatable Steps", &automatable), ("Manual Steps", &manual)] {
+            if section.is_empty() {
+                continue;
+            }
+            md.push_str(&format!("## {}\n\n", title));
+            for (i, item) in section.iter().enumerate() {
+                Self::push_step(&mut md, i + 1, item);
+            }
+            md.push('\n');
+        }
+
+        md
+    }
+}
+
+impl ReportGenerator for RunbookReporter {
+    fn generate(&self, snapshot: &Snapshot, output_path: &Path) -> ReportResult<()> {
+        self.save_report(snapshot, &snapshot.findings, output_path)
+    }
+
+    fn name(&self) -> &'static str {
+        "runbook"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::{Category, Remediation, RiskLevel, SnapshotMetadata};
+
+    fn step(order: u32, description: &str, command: Option<&str>, can_automate: bool) -> RemediationStep {
+        RemediationStep {
+            order,
+            description: description.to_string(),
+            command: command.map(str::to_string),
+            verification: None,
+            can_automate,
+        }
+    }
+
+    fn finding(id: &str, severity: Severity, steps: Vec<RemediationStep>, requires_downtime: bool) -> Finding {
+        Finding {
+            id: id.to_string(),
+            severity,
+            category: Category::Configuration,
+            remediation: Remediation {
+                steps,
+                script: None,
+                risk_level: RiskLevel::Medium,
+                requires_downtime,
+                estimated_duration_minutes: None,
+                rollback_plan: None,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_duplicate_commands_listed_once() {
+        let describe = "kafka-topics.sh --bootstrap-server <broker> --describe --under-replicated-partitions";
+        let findings = vec![
+            finding("A-001", Severity::High, vec![step(1, "List under-replicated partitions", Some(describe), true)], false),
+            finding("B-001", Severity::Medium, vec![
+                step(1, "Check which partitions lag", Some(describe), true),
+                step(2, "Restart the lagging broker", None, false),
+            ], true),
+        ];
+        let runbook = RunbookReporter::new().generate_runbook(&Snapshot::new(SnapshotMetadata::new("test".to_string())), &findings);
+
+        assert_eq!(runbook.matches(describe).count(), 1);
+        assert!(runbook.contains("   - **Findings**: A-001, B-001\n"));
+        assert!(runbook.contains("2 step(s) from 2 finding(s), most severe first: 1 automatable, 1 manual."));
+        assert!(runbook.contains("1. [ ] 🟡 **Medium** Restart the lagging broker **(requires downtime)**\n"));
+        assert!(runbook.find("## Automatable Steps").unwrap() < runbook.find("## Manual Steps").unwrap());
+    }
+
+    #[test]
+    fn test_steps_ordered_by_severity_then_step_order() {
+        let findings = vec![
+            finding("LOW-001", Severity::Low, vec![step(1, "Rename topics", None, false)], false),
+            finding("CRIT-001", Severity::Critical, vec![
+                step(2, "Restart the broker", None, false),
+                step(1, "Free disk space", None, false),
+            ], false),
+            finding("HIGH-001", Severity::High, vec![step(1, "Add a broker", None, false)], false),
+        ];
+        let reporter = RunbookReporter::new();
+        let order: Vec<&str> = reporter.steps(&findings).iter().map(|item| item.step.description.as_str()).collect();
+        assert_eq!(order, vec!["Free disk space", "Restart the broker", "Add a broker", "Rename topics"]);
+
+        let runbook = reporter.generate_runbook(&Snapshot::new(SnapshotMetadata::new("test".to_string())), &findings);
+        assert!(runbook.contains("1. [ ] 🔴 **Critical** Free disk space\n"));
+        assert!(runbook.contains("4. [ ] 🟢 **Low** Rename topics\n"));
+    }
+
+    #[test]
+    fn test_saved_runbook_replaced_on_rerun() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rb.md");
+        let snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let findings = vec![finding("CRIT-001", Severity::Critical, vec![step(1, "Free disk space", None, false)], false)];
+        let reporter = RunbookReporter::new();
+
+        reporter.save_report(&snapshot, &findings, &path).unwrap();
+        reporter.save_report(&snapshot, &[], &path).unwrap();
+
+        let runbook = std::fs::read_to_string(&path).unwrap();
+        assert!(runbook.contains("Generated by **KCPilot** v"));
+        assert!(runbook.contains("No remediation steps"));
+    }
+}
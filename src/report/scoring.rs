@@ -0,0 +1,237 @@
+use super::ClusterOverview;
+use crate::snapshot::format::{Finding, Severity, Snapshot};
+
+/// Environment variable selecting the health score methodology (`flat` or `size-normalized`)
+pub const HEALTH_SCORE_MODEL_ENV: &str = "KCPILOT_HEALTH_SCORE_MODEL";
+
+/// Clusters up to this many scale units are scored like the flat model
+const REFERENCE_SCALE_UNITS: f64 = 10.0;
+
+/// Points subtracted from 100 for each finding of a given severity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeverityPenalties {
+    pub critical: f64,
+    pub high: f64,
+    pub medium: f64,
+    pub low: f64,
+    pub info: f64,
+}
+
+impl SeverityPenalties {
+    /// Penalties of the terminal and markdown reports
+    pub const STANDARD: Self = Self { critical: 25.0, high: 15.0, medium: 8.0, low: 3.0, info: 0.0 };
+
+    /// Penalties of the JSON report
+    pub const JSON: Self = Self { critical: 20.0, high: 10.0, medium: 5.0, low: 2.0, info: 0.5 };
+
+    pub fn penalty(&self, severity: Severity) -> f64 {
+        match severity {
+            Severity::Critical => self.critical,
+            Severity::High => self.high,
+            Severity::Medium => self.medium,
+            Severity::Low => self.low,
+            Severity::Info => self.info,
+        }
+    }
+
+    fn total(&self, findings: &[Finding]) -> f64 {
+        findings.iter().map(|f| self.penalty(f.severity)).sum()
+    }
+}
+
+/// Size of the analyzed cluster
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClusterScale {
+    pub brokers: usize,
+    pub topics: usize,
+    pub partitions: usize,
+}
+
+impl ClusterScale {
+    /// Read the cluster size from admin data, falling back to the scanned broker directories
+    pub fn from_snapshot(snapshot: &Snapshot) -> Self {
+        let overview = ClusterOverview::from_snapshot(snapshot);
+        Self {
+            brokers: overview.broker_count,
+            topics: overview.topic_count.unwrap_or(0),
+            partitions: overview.partition_count.unwrap_or(0),
+        }
+    }
+
+    /// Single size measure: a broker weighs as much as 10 topics or 100 partitions
+    pub fn units(&self) -> f64 {
+        self.brokers as f64 + self.topics as f64 / 10.0 + self.partitions as f64 / 100.0
+    }
+}
+
+/// Turns findings into a 0-100 health score
+pub trait HealthScoreModel {
+    fn score(&self, findings: &[Finding], scale: &ClusterScale) -> f64;
+
+    fn name(&self) -> &'static str;
+}
+
+/// Subtracts a fixed penalty per finding regardless of cluster size
+pub struct FlatScore {
+    penalties: SeverityPenalties,
+}
+
+impl FlatScore {
+    pub fn new(penalties: SeverityPenalties) -> Self {
+        Self { penalties }
+    }
+}
+
+impl HealthScoreModel for FlatScore {
+    fn score(&self, findings: &[Finding], _scale: &ClusterScale) -> f64 {
+        (100.0 - self.penalties.total(findings)).max(0.0)
+    }
+
+    fn name(&self) -> &'static str {
+        "flat"
+    }
+}
+
+/// Scales penalties down logarithmically once the cluster is larger than a few
+/// brokers, so a handful of findings weigh less on a large cluster than on a small one
+pub struct SizeNormalizedScore {
+    penalties: SeverityPenalties,
+}
+
+impl SizeNormalizedScore {
+    pub fn new(penalties: SeverityPenalties) -> Self {
+        Self { penalties }
+    }
+
+    /// Multiplier applied to penalties: 1 up to the reference size, then shrinking with scale
+    pub fn penalty_factor(scale: &ClusterScale) -> f64 {
+        let units = scale.units();
+        if units <= REFERENCE_SCALE_UNITS {
+            1.0
+        } else {
+            1.0 / (1.0 + (units / REFERENCE_SCALE_UNITS).ln())
+        }
+    }
+}
+
+impl HealthScoreModel for SizeNormalizedScore {
+    fn score(&self, findings: &[Finding], scale: &ClusterScale) -> f64 {
+        (100.0 - self.penalties.total(findings) * Self::penalty_factor(scale)).max(0.0)
+    }
+
+    fn name(&self) -> &'static str {
+        "size-normalized"
+    }
+}
+
+/// Health score methodology used by the reporters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HealthScoreMethod {
+    #[default]
+    Flat,
+    SizeNormalized,
+}
+
+impl HealthScoreMethod {
+    /// Read the method from `KCPILOT_HEALTH_SCORE_MODEL`, defaulting to flat
+    pub fn from_env() -> Self {
+        match std::env::var(HEALTH_SCORE_MODEL_ENV) {
+            Ok(value) => value.parse().unwrap_or_else(|e| {
+                tracing::warn!("{}, using the flat health score", e);
+                Self::Flat
+            }),
+            Err(_) => Self::Flat,
+        }
+    }
+
+    pub fn model(self, penalties: SeverityPenalties) -> Box<dyn HealthScoreModel> {
+        match self {
+            Self::Flat => Box::new(FlatScore::new(penalties)),
+            Self::SizeNormalized => Box::new(SizeNormalizedScore::new(penalties)),
+        }
+    }
+
+    /// Score `findings` against the size of the cluster in `snapshot`
+    pub fn score(self, penalties: SeverityPenalties, snapshot: &Snapshot, findings: &[Finding]) -> f64 {
+        self.model(penalties).score(findings, &ClusterScale::from_snapshot(snapshot))
+    }
+}
+
+impl std::str::FromStr for HealthScoreMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "flat" => Ok(Self::Flat),
+            "size-normalized" | "size_normalized" | "normalized" => Ok(Self::SizeNormalized),
+            other => Err(format!("Unknown health score model '{}'", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::SnapshotMetadata;
+    use serde_json::json;
+
+    fn findings(severities: &[Severity]) -> Vec<Finding> {
+        severities.iter()
+            .map(|&severity| Finding { severity, ..Default::default() })
+            .collect()
+    }
+
+    fn scale(brokers: usize, topics: usize, partitions: usize) -> ClusterScale {
+        ClusterScale { brokers, topics, partitions }
+    }
+
+    #[test]
+    fn test_flat_vs_size_normalized() {
+        let findings = findings(&[Severity::High, Severity::High, Severity::Medium]);
+        let small = scale(3, 3, 9);
+        let large = scale(12, 500, 6000);
+        let flat = FlatScore::new(SeverityPenalties::STANDARD);
+        let normalized = SizeNormalizedScore::new(SeverityPenalties::STANDARD);
+
+        // The flat model ignores cluster size
+        assert_eq!(flat.score(&findings, &small), 62.0);
+        assert_eq!(flat.score(&findings, &large), 62.0);
+
+        // Small clusters score the same under both models, large ones are penalized less
+        assert_eq!(normalized.score(&findings, &small), 62.0);
+        let large_score = normalized.score(&findings, &large);
+        assert!(large_score > 80.0 && large_score < 100.0, "score {}", large_score);
+    }
+
+    #[test]
+    fn test_scores_never_go_below_zero() {
+        let findings = findings(&[Severity::Critical; 10]);
+        for method in [HealthScoreMethod::Flat, HealthScoreMethod::SizeNormalized] {
+            let model = method.model(SeverityPenalties::STANDARD);
+            assert_eq!(model.score(&findings, &scale(1, 1, 1)), 0.0, "{}", model.name());
+        }
+    }
+
+    #[test]
+    fn test_cluster_scale_from_snapshot() {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.custom.insert("brokers".to_string(), json!({"broker_1": {}, "broker_2": {}}));
+        assert_eq!(ClusterScale::from_snapshot(&snapshot), scale(2, 0, 0));
+
+        snapshot.collectors.admin = Some(json!({
+            "brokers": [{"id": 1}, {"id": 2}, {"id": 3}],
+            "topics": [
+                {"name": "orders", "partitions": [{"id": 0}, {"id": 1}]},
+                {"name": "payments", "partitions": [{"id": 0}]},
+            ],
+        }));
+        assert_eq!(ClusterScale::from_snapshot(&snapshot), scale(3, 2, 3));
+    }
+
+    #[test]
+    fn test_parse_method() {
+        assert_eq!("flat".parse::<HealthScoreMethod>().unwrap(), HealthScoreMethod::Flat);
+        assert_eq!("Size-Normalized".parse::<HealthScoreMethod>().unwrap(), HealthScoreMethod::SizeNormalized);
+        assert!("weighted".parse::<HealthScoreMethod>().is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use crate::snapshot::format::Finding;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use super::output::write_atomic;
+use super::ReportResult;
+
+/// Write each finding's remediation script to `<dir>/NN-<finding id>.sh`, returning the written paths.
+/// Existing scripts kcpilot did not write are only replaced with `force`.
+pub fn export_remediation_scripts(findings: &[Finding], dir: &Path, force: bool) -> ReportResult<Vec<PathBuf>> {
+    let scripted: Vec<(&Finding, &str)> = findings.iter()
+        .filter_map(|f| f.remediation.script.as_deref().map(|script| (f, script)))
+        .collect();
+
+    if scripted.is_empty() {
+        info!("No findings have remediation scripts to export");
+        return Ok(Vec::new());
+    }
+
+    fs::create_dir_all(dir)?;
+
+    let mut paths = Vec::new();
+    for (i, (finding, script)) in scripted.into_iter().enumerate() {
+        let path = dir.join(format!("{:02}-{}.sh", i + 1, file_stem(&finding.id)));
+        write_atomic(&path, script_with_header(finding, script).as_bytes(), force)?;
+        paths.push(path);
+    }
+
+    info!("Exported {} remediation script(s) to {}", paths.len(), dir.display());
+    Ok(paths)
+}
+
+/// Prefix a generated script with a shebang, strict mode and a warning to review it first
+fn script_with_header(finding: &Finding, script: &str) -> String {
+    // Generated scripts often start with their own shebang
+    let body = match script.strip_prefix("#!") {
+        Some(rest) => rest.split_once('\n').map(|(_, body)| body).unwrap_or(""),
+        None => script,
+    };
+
+    format!(
+        "#!/usr/bin/env bash\n\
+        #\n\
+        # Remediation script for {id}: {title}\n\
+        # Severity: {severity:?}\n\
+        # Risk level: {risk:?}, requires downtime: {downtime}\n\
+        #\n\
+        # GENERATED BY KCPILOT WITH AN LLM. Review every command before running it\n\
+        # and test it on a non-production cluster first. It is not run automatically.\n\
+        #\n\
+        set -euo pipefail\n\
+        \n\
+        {body}\n",
+        id = finding.id,
+        title = finding.title.replace('\n', " "),
+        severity = finding.severity,
+        risk = finding.remediation.risk_level,
+        downtime = finding.remediation.requires_downtime,
+        body = body.trim(),
+    )
+}
+
+fn file_stem(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::Severity;
+    use tempfile::TempDir;
+
+    fn finding(id: &str, script: Option<&str>) -> Finding {
+        let mut finding = Finding {
+            id: id.to_string(),
+            title: "Under-replicated partitions".to_string(),
+            severity: Severity::Critical,
+            ..Default::default()
+        };
+        finding.remediation.script = script.map(str::to_string);
+        finding
+    }
+
+    #[test]
+    fn test_export_writes_numbered_scripts() {
+        let dir = TempDir::new().unwrap();
+        let findings = vec![
+            finding("FND-001", Some("#!/bin/bash\nkafka-reassign-partitions.sh --execute")),
+            finding("FND-002", None),
+            finding("broker/11 config", Some("kafka-configs.sh --alter")),
+        ];
+
+        let paths = export_remediation_scripts(&findings, &dir.path().join("scripts"), false).unwrap();
+
+        assert_eq!(paths.len(), 2);
+        assert!(paths[0].ends_with("01-FND-001.sh"));
+        assert!(paths[1].ends_with("02-broker_11_config.sh"));
+
+        let content = fs::read_to_string(&paths[0]).unwrap();
+        assert!(content.starts_with("#!/usr/bin/env bash\n"));
+        assert!(content.contains("# Remediation script for FND-001: Under-replicated partitions"));
+        assert!(content.contains("Review every command before running it"));
+        assert!(content.contains("set -euo pipefail\n\nkafka-reassign-partitions.sh --execute\n"));
+        assert!(!content.contains("#!/bin/bash"));
+    }
+
+    #[test]
+    fn test_export_nothing_without_scripts() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("scripts");
+
+        let paths = export_remediation_scripts(&[finding("FND-001", None)], &out, false).unwrap();
+
+        assert!(paths.is_empty());
+        assert!(!out.exists());
+    }
+}
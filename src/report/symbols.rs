@@ -0,0 +1,118 @@
+use std::borrow::Cow;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether terminal output is limited to ASCII
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// ASCII stand-ins for the symbols used in terminal output. Other symbols and emoji are
+/// decoration and are dropped.
+const SYMBOLS: &[(char, &str)] = &[
+    ('✅', "[OK]"),
+    ('✓', "[OK]"),
+    ('✔', "[OK]"),
+    ('❌', "[FAIL]"),
+    ('✗', "[FAIL]"),
+    ('⚠', "[WARN]"),
+    ('⏭', "[SKIP]"),
+    ('⏹', "[STOP]"),
+    ('ℹ', "[INFO]"),
+    ('🔴', "[CRIT]"),
+    ('🟠', "[HIGH]"),
+    ('🟡', "[MED]"),
+    ('🟢', "[LOW]"),
+    ('═', "="),
+    ('─', "-"),
+    ('━', "-"),
+    ('│', "|"),
+    ('•', "*"),
+    ('→', "->"),
+    ('←', "<-"),
+    ('…', "..."),
+    ('—', "-"),
+    ('–', "-"),
+    ('×', "x"),
+    ('≥', ">="),
+    ('≤', "<="),
+    ('‘', "'"),
+    ('’', "'"),
+    ('“', "\""),
+    ('”', "\""),
+];
+
+/// Limit terminal output to ASCII, e.g. for logs, email and terminals without UTF-8
+pub fn set_ascii(enabled: bool) {
+    ASCII.store(enabled, Ordering::Relaxed);
+}
+
+pub fn ascii_enabled() -> bool {
+    ASCII.load(Ordering::Relaxed)
+}
+
+/// Whether to use ASCII output: when asked for with `--ascii`, or when `NO_COLOR` is set
+/// or stdout is not a terminal
+pub fn detect_ascii(requested: bool) -> bool {
+    requested || std::env::var_os("NO_COLOR").is_some() || !std::io::stdout().is_terminal()
+}
+
+/// `text` with its symbols replaced by their ASCII stand-ins and emoji dropped, along with
+/// the space that followed an emoji starting a word. Non-ASCII letters are kept.
+pub fn to_ascii(text: &str) -> String {
+    let mut ascii = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let replacement = SYMBOLS.iter().find(|(symbol, _)| *symbol == c).map(|(_, replacement)| *replacement);
+        if c.is_ascii() || (replacement.is_none() && c.is_alphanumeric()) {
+            ascii.push(c);
+            continue;
+        }
+
+        // Emoji variation selectors and joiners only modify the symbol before them
+        while chars.next_if(|next| matches!(next, '\u{fe0e}' | '\u{fe0f}' | '\u{200d}')).is_some() {}
+        match replacement {
+            Some(replacement) => ascii.push_str(replacement),
+            None if at_word_start(&ascii) => {
+                chars.next_if_eq(&' ');
+            }
+            None => {}
+        }
+    }
+    ascii
+}
+
+/// Whether the next character starts a word, looking past color escape sequences
+fn at_word_start(text: &str) -> bool {
+    let mut text = text;
+    while let Some(escape) = text.rfind('\x1b') {
+        let sequence = &text[escape..];
+        if !(sequence.starts_with("\x1b[") && sequence.ends_with('m')) {
+            break;
+        }
+        text = &text[..escape];
+    }
+    text.is_empty() || text.ends_with(char::is_whitespace)
+}
+
+/// `text` as it should be printed: unchanged, or in ASCII when that is enabled
+pub fn render(text: &str) -> Cow<'_, str> {
+    if ascii_enabled() {
+        Cow::Owned(to_ascii(text))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_ascii() {
+        assert_eq!(to_ascii("✅ Scan complete"), "[OK] Scan complete");
+        assert_eq!(to_ascii("  ⚠️  2 brokers unreachable"), "  [WARN]  2 brokers unreachable");
+        assert_eq!(to_ascii("  📊 System info... ✓"), "  System info... [OK]");
+        assert_eq!(to_ascii("═══ ─── • a → b"), "=== --- * a -> b");
+        assert_eq!(to_ascii("\n\x1b[1;97m🔍 Findings\x1b[0m"), "\n\x1b[1;97mFindings\x1b[0m");
+        assert_eq!(to_ascii("ℹ️ Broker on zürich-1 ☕"), "[INFO] Broker on zürich-1 ");
+    }
+}
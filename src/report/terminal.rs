@@ -1,6 +1,9 @@
-use super::{ReportGenerator, ReportResult};
+use super::scoring::{HealthScoreMethod, SeverityPenalties};
+use super::symbols;
+use super::{ClusterOverview, ReportGenerator, ReportResult};
 use crate::snapshot::format::{Finding, Severity, Snapshot};
 use colored::Colorize;
+use std::fmt::{self, Write};
 use std::path::Path;
 
 /// Terminal formatting constants
@@ -11,6 +14,8 @@ const SEPARATOR_WIDTH: usize = 40;
 pub struct TerminalReporter {
     verbose: bool,
     use_colors: bool,
+    ascii: bool,
+    score_method: HealthScoreMethod,
 }
 
 impl Default for TerminalReporter {
@@ -24,6 +29,8 @@ impl TerminalReporter {
         Self {
             verbose: false,
             use_colors: true,
+            ascii: false,
+            score_method: HealthScoreMethod::default(),
         }
     }
     
@@ -36,108 +43,92 @@ impl TerminalReporter {
         self.use_colors = use_colors;
         self
     }
+
+    /// Replace emoji and box-drawing characters with ASCII
+    pub fn with_ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// Choose how the health score is calculated (default: flat)
+    pub fn with_score_method(mut self, method: HealthScoreMethod) -> Self {
+        self.score_method = method;
+        self
+    }
     
     pub fn print_snapshot(&self, snapshot: &Snapshot) -> ReportResult<()> {
-        self.print_header()?;
-        self.print_cluster_info(snapshot)?;
-        self.print_summary(snapshot)?;
-        self.print_findings(snapshot)?;
-        self.print_footer()?;
-        Ok(())
+        self.report(snapshot, &snapshot.findings)
     }
     
     /// Report with external findings (e.g., from LLM analyzer)
     pub fn report(&self, snapshot: &Snapshot, findings: &[Finding]) -> ReportResult<()> {
-        self.print_header()?;
-        self.print_cluster_info(snapshot)?;
-        self.print_summary_with_findings(snapshot, findings)?;
-        self.print_findings_list(findings)?;
-        self.print_footer()?;
+        print!("{}", self.render(snapshot, findings));
         Ok(())
     }
+
+    /// The report as printed, in ASCII when enabled
+    pub fn render(&self, snapshot: &Snapshot, findings: &[Finding]) -> String {
+        let mut out = String::new();
+        self.write_report(&mut out, snapshot, findings).expect("writing to a String does not fail");
+        if self.ascii {
+            symbols::to_ascii(&out)
+        } else {
+            out
+        }
+    }
+
+    fn write_report(&self, out: &mut String, snapshot: &Snapshot, findings: &[Finding]) -> fmt::Result {
+        self.write_header(out)?;
+        self.write_cluster_info(out, snapshot)?;
+        self.write_summary(out, snapshot, findings)?;
+        self.write_findings(out, findings)?;
+        self.write_footer(out)
+    }
     
-    fn print_header(&self) -> ReportResult<()> {
-        println!("\n{}", "═".repeat(TERMINAL_WIDTH).bright_blue());
-        println!("{}", "KCPILOT HEALTH REPORT".bright_white().bold());
-        println!("{}", "═".repeat(TERMINAL_WIDTH).bright_blue());
+    fn write_header(&self, out: &mut String) -> fmt::Result {
+        writeln!(out, "\n{}", "═".repeat(TERMINAL_WIDTH).bright_blue())?;
+        writeln!(out, "{}", "KCPILOT HEALTH REPORT".bright_white().bold())?;
+        writeln!(out, "{}", "═".repeat(TERMINAL_WIDTH).bright_blue())?;
         Ok(())
     }
     
-    fn print_cluster_info(&self, snapshot: &Snapshot) -> ReportResult<()> {
-        println!("\n{}", "📊 Cluster Information".bright_white().bold());
-        println!("{}", "─".repeat(SEPARATOR_WIDTH).bright_black());
+    fn write_cluster_info(&self, out: &mut String, snapshot: &Snapshot) -> fmt::Result {
+        writeln!(out, "\n{}", "📊 Cluster Information".bright_white().bold())?;
+        writeln!(out, "{}", "─".repeat(SEPARATOR_WIDTH).bright_black())?;
         
         if let Some(id) = &snapshot.cluster.id {
-            println!("  Cluster ID:      {}", id.bright_cyan());
+            writeln!(out, "  Cluster ID:      {}", id.bright_cyan())?;
         }
         if let Some(name) = &snapshot.cluster.name {
-            println!("  Cluster Name:    {}", name.bright_cyan());
+            writeln!(out, "  Cluster Name:    {}", name.bright_cyan())?;
         }
         if let Some(version) = &snapshot.cluster.version {
-            println!("  Kafka Version:   {}", version.bright_cyan());
+            writeln!(out, "  Kafka Version:   {}", version.bright_cyan())?;
         }
         
-        let mode_display = match &snapshot.cluster.mode {
+        let overview = ClusterOverview::from_snapshot(snapshot);
+        let mode_display = match &overview.mode {
             crate::snapshot::format::ClusterMode::Kraft => "KRaft (modern, Zookeeper-free)".bright_green(),
             crate::snapshot::format::ClusterMode::Zookeeper => "Zookeeper (legacy)".bright_yellow(),
             crate::snapshot::format::ClusterMode::Unknown => "Unknown".bright_red(),
         };
-        println!("  Mode:            {}", mode_display);
-        println!("  Timestamp:       {}", snapshot.timestamp.format("%Y-%m-%d %H:%M:%S UTC"));
-        println!("  Tool Version:    {}", snapshot.metadata.tool_version);
-        
-        Ok(())
-    }
-    
-    fn print_summary(&self, snapshot: &Snapshot) -> ReportResult<()> {
-        println!("\n{}", "📈 Analysis Summary".bright_white().bold());
-        println!("{}", "─".repeat(SEPARATOR_WIDTH).bright_black());
-        
-        let total_findings = snapshot.findings.len();
-        let critical = snapshot.findings.iter().filter(|f| f.severity == Severity::Critical).count();
-        let high = snapshot.findings.iter().filter(|f| f.severity == Severity::High).count();
-        let medium = snapshot.findings.iter().filter(|f| f.severity == Severity::Medium).count();
-        let low = snapshot.findings.iter().filter(|f| f.severity == Severity::Low).count();
-        let info = snapshot.findings.iter().filter(|f| f.severity == Severity::Info).count();
-        
-        println!("  Total Findings:  {}", total_findings.to_string().bright_yellow());
-        
-        if critical > 0 {
-            println!("  🔴  Critical:    {}", critical.to_string().bright_red().bold());
+        writeln!(out, "  Mode:            {}", mode_display)?;
+        writeln!(out, "  Brokers:         {}", overview.broker_count)?;
+        if let Some(topics) = overview.topic_count {
+            writeln!(out, "  Topics:          {} ({} internal)", topics, overview.internal_topic_count.unwrap_or(0))?;
         }
-        if high > 0 {
-            println!("  🟠  High:        {}", high.to_string().bright_red());
+        if let Some(partitions) = overview.partition_count {
+            writeln!(out, "  Partitions:      {}", partitions)?;
         }
-        if medium > 0 {
-            println!("  🟡  Medium:      {}", medium.to_string().bright_yellow());
-        }
-        if low > 0 {
-            println!("  🟢  Low:         {}", low.to_string().bright_green());
-        }
-        if info > 0 {
-            println!("  ℹ️  Info:        {}", info.to_string().bright_blue());
-        }
-        
-        // Calculate health score
-        let health_score = self.calculate_health_score(snapshot);
-        let score_color = if health_score >= 80.0 {
-            "green"
-        } else if health_score >= 60.0 {
-            "yellow"
-        } else {
-            "red"
-        };
-        
-        println!("\n  Health Score:    {}/100", 
-                 format!("{:.0}", health_score).color(score_color).bold());
+        writeln!(out, "  Timestamp:       {}", snapshot.timestamp.format("%Y-%m-%d %H:%M:%S UTC"))?;
+        writeln!(out, "  Tool Version:    {}", snapshot.metadata.tool_version)?;
         
         Ok(())
     }
     
-    /// Print summary with external findings (avoiding snapshot clone)
-    fn print_summary_with_findings(&self, _snapshot: &Snapshot, findings: &[Finding]) -> ReportResult<()> {
-        println!("\n{}", "📈 Analysis Summary".bright_white().bold());
-        println!("{}", "─".repeat(SEPARATOR_WIDTH).bright_black());
+    fn write_summary(&self, out: &mut String, snapshot: &Snapshot, findings: &[Finding]) -> fmt::Result {
+        writeln!(out, "\n{}", "📈 Analysis Summary".bright_white().bold())?;
+        writeln!(out, "{}", "─".repeat(SEPARATOR_WIDTH).bright_black())?;
         
         let total_findings = findings.len();
         let critical = findings.iter().filter(|f| f.severity == Severity::Critical).count();
@@ -146,26 +137,26 @@ impl TerminalReporter {
         let low = findings.iter().filter(|f| f.severity == Severity::Low).count();
         let info = findings.iter().filter(|f| f.severity == Severity::Info).count();
         
-        println!("  Total Findings:  {}", total_findings.to_string().bright_yellow());
+        writeln!(out, "  Total Findings:  {}", total_findings.to_string().bright_yellow())?;
         
         if critical > 0 {
-            println!("  🔴  Critical:    {}", critical.to_string().bright_red().bold());
+            writeln!(out, "  🔴  Critical:    {}", critical.to_string().bright_red().bold())?;
         }
         if high > 0 {
-            println!("  🟠  High:        {}", high.to_string().bright_red());
+            writeln!(out, "  🟠  High:        {}", high.to_string().bright_red())?;
         }
         if medium > 0 {
-            println!("  🟡  Medium:      {}", medium.to_string().bright_yellow());
+            writeln!(out, "  🟡  Medium:      {}", medium.to_string().bright_yellow())?;
         }
         if low > 0 {
-            println!("  🟢  Low:         {}", low.to_string().bright_green());
+            writeln!(out, "  🟢  Low:         {}", low.to_string().bright_green())?;
         }
         if info > 0 {
-            println!("  ℹ️  Info:        {}", info.to_string().bright_blue());
+            writeln!(out, "  ℹ️  Info:        {}", info.to_string().bright_blue())?;
         }
         
         // Calculate health score based on findings
-        let health_score = self.calculate_health_score_from_findings(findings);
+        let health_score = self.score_method.score(SeverityPenalties::STANDARD, snapshot, findings);
         let score_color = if health_score >= 80.0 {
             "green"
         } else if health_score >= 60.0 {
@@ -174,46 +165,29 @@ impl TerminalReporter {
             "red"
         };
         
-        println!("\n  Health Score:    {}/100", 
-                 format!("{:.0}", health_score).color(score_color).bold());
+        writeln!(out, "\n  Health Score:    {}/100", 
+                 format!("{:.0}", health_score).color(score_color).bold())?;
         
         Ok(())
     }
     
-    /// Print findings list (avoiding snapshot clone)
-    fn print_findings_list(&self, findings: &[Finding]) -> ReportResult<()> {
+    fn write_findings(&self, out: &mut String, findings: &[Finding]) -> fmt::Result {
         if findings.is_empty() {
-            println!("\n✅ {}", "No issues found! Your cluster appears healthy.".bright_green());
+            writeln!(out, "\n✅ {}", "No issues found! Your cluster appears healthy.".bright_green())?;
             return Ok(());
         }
         
-        println!("\n{}", "🔍 Findings".bright_white().bold());
-        println!("{}", "═".repeat(TERMINAL_WIDTH).bright_black());
+        writeln!(out, "\n{}", "🔍 Findings".bright_white().bold())?;
+        writeln!(out, "{}", "═".repeat(TERMINAL_WIDTH).bright_black())?;
         
         for (idx, finding) in findings.iter().enumerate() {
-            self.print_finding(idx + 1, finding)?;
-        }
-        
-        Ok(())
-    }
-    
-    fn print_findings(&self, snapshot: &Snapshot) -> ReportResult<()> {
-        if snapshot.findings.is_empty() {
-            println!("\n✅ {}", "No issues found! Your cluster appears healthy.".bright_green());
-            return Ok(());
-        }
-        
-        println!("\n{}", "🔍 Findings".bright_white().bold());
-        println!("{}", "═".repeat(TERMINAL_WIDTH).bright_black());
-        
-        for (idx, finding) in snapshot.findings.iter().enumerate() {
-            self.print_finding(idx + 1, finding)?;
+            self.write_finding(out, idx + 1, finding)?;
         }
         
         Ok(())
     }
     
-    fn print_finding(&self, num: usize, finding: &Finding) -> ReportResult<()> {
+    fn write_finding(&self, out: &mut String, num: usize, finding: &Finding) -> fmt::Result {
         let severity_icon = finding.severity.icon();
         let severity_text = format!("{:?}", finding.severity);
         let severity_colored = match finding.severity {
@@ -224,125 +198,104 @@ impl TerminalReporter {
             Severity::Info => severity_text.bright_blue(),
         };
         
-        println!("\n{} Finding #{}: {}", severity_icon, num, finding.title.bright_white().bold());
-        println!("  Severity:  {}", severity_colored);
-        println!("  Category:  {:?}", finding.category);
-        println!("  ID:        {}", finding.id.bright_black());
+        writeln!(out, "\n{} Finding #{}: {}", severity_icon, num, finding.title.bright_white().bold())?;
+        writeln!(out, "  Severity:  {}", severity_colored)?;
+        writeln!(out, "  Category:  {:?}", finding.category)?;
+        writeln!(out, "  ID:        {}", finding.id.bright_black())?;
         
-        println!("\n  {}", "Description:".underline());
+        writeln!(out, "\n  {}", "Description:".underline())?;
         for line in finding.description.lines() {
-            println!("    {}", line);
+            writeln!(out, "    {}", line)?;
         }
         
-        println!("\n  {}", "Impact:".underline());
+        writeln!(out, "\n  {}", "Impact:".underline())?;
         for line in finding.impact.lines() {
-            println!("    {}", line.bright_yellow());
+            writeln!(out, "    {}", line.bright_yellow())?;
         }
         
         if let Some(root_cause) = &finding.root_cause {
-            println!("\n  {}", "Root Cause:".underline());
+            writeln!(out, "\n  {}", "Root Cause:".underline())?;
             for line in root_cause.lines() {
-                println!("    {}", line);
+                writeln!(out, "    {}", line)?;
             }
         }
         
         // Print evidence summary
         if !finding.evidence.metrics.is_empty() || !finding.evidence.logs.is_empty() || !finding.evidence.configs.is_empty() {
-            println!("\n  {}", "Evidence:".underline());
+            writeln!(out, "\n  {}", "Evidence:".underline())?;
             
             // Print config evidence with source files
             for config in &finding.evidence.configs {
-                println!("    • Config {}: {} = {}", 
+                writeln!(out, "    • Config {}: {} = {}", 
                          config.config_key.bright_cyan(),
                          config.resource_name,
-                         config.current_value.bright_yellow());
+                         config.current_value.bright_yellow())?;
                 if let Some(recommended) = &config.recommended_value {
-                    println!("      Recommended: {}", recommended.bright_green());
+                    writeln!(out, "      Recommended: {}", recommended.bright_green())?;
                 }
                 if !config.source_files.is_empty() {
-                    println!("      {}", "Affected files:".bright_red().underline());
+                    writeln!(out, "      {}", "Affected files:".bright_red().underline())?;
                     for file in &config.source_files {
-                        println!("        • {}", file.bright_white());
+                        writeln!(out, "        • {}", file.bright_white())?;
                     }
                 }
             }
             
             for metric in &finding.evidence.metrics {
-                println!("    • {}: {} {}", 
+                writeln!(out, "    • {}: {} {}", 
                          metric.name.bright_cyan(), 
                          metric.value, 
-                         metric.unit.as_deref().unwrap_or(""));
+                         metric.unit.as_deref().unwrap_or(""))?;
             }
             for log in finding.evidence.logs.iter().take(2) {
-                println!("    • {} ({}x): {}", 
+                writeln!(out, "    • {} ({}x): {}", 
                          log.level.bright_red(), 
                          log.count,
-                         log.message.chars().take(80).collect::<String>());
+                         log.message.chars().take(80).collect::<String>())?;
             }
         }
         
         // Print remediation steps
         if !finding.remediation.steps.is_empty() {
-            println!("\n  {}", "Remediation Steps:".underline().bright_green());
+            writeln!(out, "\n  {}", "Remediation Steps:".underline().bright_green())?;
             for step in &finding.remediation.steps {
-                println!("    {}. {}", step.order, step.description);
+                writeln!(out, "    {}. {}", step.order, step.description)?;
                 if self.verbose {
                     if let Some(cmd) = &step.command {
-                        println!("       Command: {}", cmd.bright_black());
+                        writeln!(out, "       Command: {}", cmd.bright_black())?;
                     }
                 }
             }
             
-            println!("\n  Risk Level: {:?} | Downtime Required: {}", 
+            writeln!(out, "\n  Risk Level: {:?} | Downtime Required: {}", 
                      finding.remediation.risk_level,
-                     if finding.remediation.requires_downtime { "Yes".red() } else { "No".green() });
+                     if finding.remediation.requires_downtime { "Yes".red() } else { "No".green() })?;
             
             if let Some(duration) = finding.remediation.estimated_duration_minutes {
-                println!("  Estimated Duration: {} minutes", duration);
+                writeln!(out, "  Estimated Duration: {} minutes", duration)?;
             }
         }
         
-        println!("\n{}", "─".repeat(80).bright_black());
+        writeln!(out, "\n{}", "─".repeat(80).bright_black())?;
         
         Ok(())
     }
     
-    fn print_footer(&self) -> ReportResult<()> {
-        println!("\n{}", "💡 Next Steps".bright_white().bold());
-        println!("{}", "─".repeat(SEPARATOR_WIDTH).bright_black());
-        println!("  1. Address critical and high severity findings first");
-        println!("  2. Review remediation scripts before applying");
-        println!("  3. Test changes in a non-production environment");
-        println!("  4. Monitor cluster after applying fixes");
-        
-        println!("\n{}", "═".repeat(TERMINAL_WIDTH).bright_blue());
-        println!("{}", "Report generated by KCPilot".bright_black());
-        println!("{}", "For support, visit: https://softwaremill.github.io/kcpilot/".bright_black());
-        println!();
+    fn write_footer(&self, out: &mut String) -> fmt::Result {
+        writeln!(out, "\n{}", "💡 Next Steps".bright_white().bold())?;
+        writeln!(out, "{}", "─".repeat(SEPARATOR_WIDTH).bright_black())?;
+        writeln!(out, "  1. Address critical and high severity findings first")?;
+        writeln!(out, "  2. Review remediation scripts before applying")?;
+        writeln!(out, "  3. Test changes in a non-production environment")?;
+        writeln!(out, "  4. Monitor cluster after applying fixes")?;
+        
+        writeln!(out, "\n{}", "═".repeat(TERMINAL_WIDTH).bright_blue())?;
+        writeln!(out, "{}", "Report generated by KCPilot".bright_black())?;
+        writeln!(out, "{}", "For support, visit: https://softwaremill.github.io/kcpilot/".bright_black())?;
+        writeln!(out)?;
         
         Ok(())
     }
-    
-    fn calculate_health_score(&self, snapshot: &Snapshot) -> f64 {
-        self.calculate_health_score_from_findings(&snapshot.findings)
-    }
-    
-    fn calculate_health_score_from_findings(&self, findings: &[Finding]) -> f64 {
-        let mut score: f64 = 100.0;
-        
-        for finding in findings {
-            let penalty = match finding.severity {
-                Severity::Critical => 25.0,
-                Severity::High => 15.0,
-                Severity::Medium => 8.0,
-                Severity::Low => 3.0,
-                Severity::Info => 0.0,
-            };
-            score -= penalty;
-        }
-        
-        score.max(0.0)
-    }
 }
 
 impl ReportGenerator for TerminalReporter {
@@ -354,3 +307,42 @@ impl ReportGenerator for TerminalReporter {
         "terminal"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::format::{Category, SnapshotMetadata};
+
+    #[test]
+    fn test_ascii_report_matches_emoji_report() {
+        let snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let findings = vec![
+            Finding {
+                id: "replication-001".to_string(),
+                title: "Under-replicated partitions".to_string(),
+                severity: Severity::Critical,
+                category: Category::Availability,
+                ..Default::default()
+            },
+            Finding {
+                id: "config-001".to_string(),
+                title: "Deprecated setting".to_string(),
+                severity: Severity::Info,
+                category: Category::Configuration,
+                ..Default::default()
+            },
+        ];
+
+        let emoji = TerminalReporter::new().render(&snapshot, &findings);
+        let ascii = TerminalReporter::new().with_ascii(true).render(&snapshot, &findings);
+
+        assert!(emoji.contains("🔴 Finding #1: "));
+        assert!(emoji.contains(&"═".repeat(TERMINAL_WIDTH)));
+        assert!(ascii.is_ascii(), "{}", ascii);
+        assert!(ascii.contains("[CRIT] Finding #1: "));
+        assert!(ascii.contains("[INFO] Finding #2: "));
+        assert!(ascii.contains(&"=".repeat(TERMINAL_WIDTH)));
+        assert_eq!(ascii, symbols::to_ascii(&emoji));
+        assert_eq!(ascii.lines().count(), emoji.lines().count());
+    }
+}
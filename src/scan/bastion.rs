@@ -3,20 +3,77 @@ use std::process::Command;
 use tracing::{debug, info};
 
 use crate::scan::types::BrokerInfo;
+use crate::scan::listeners::{split_host_port, DEFAULT_BROKER_PORT};
+
+/// Separates the hops of a `--bastion` chain, e.g. `jump-eu,kafka-bastion`
+pub const BASTION_HOP_SEPARATOR: char = ',';
+
+/// Exit status ssh uses for its own errors, e.g. a connection that timed out or was refused,
+/// as opposed to the status of the remote command
+pub const SSH_ERROR_EXIT_CODE: i32 = 255;
+
+/// ssh could not reach the host or lost the connection; the command may not have run at all
+#[derive(Debug, thiserror::Error)]
+#[error("SSH connection failed: {0}")]
+pub struct SshTransportError(pub String);
+
+/// Whether `error` is a transport failure worth retrying: ssh itself failing or a timeout.
+/// A command that ran and failed, e.g. not found or permission denied, fails again on retry.
+pub fn is_transport_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause.is::<SshTransportError>()
+            || cause.is::<tokio::time::error::Elapsed>()
+            || cause.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == std::io::ErrorKind::TimedOut)
+    })
+}
+
+/// Output of a probing command, `None` when it failed on the remote host. Transport failures
+/// are returned, so that discovery retries them instead of moving on.
+pub(crate) fn probe_output(result: Result<String>) -> Result<Option<String>> {
+    match result {
+        Ok(output) => Ok(Some(output)),
+        Err(e) if is_transport_error(&e) => Err(e),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Check a `--bastion` value: one SSH alias, or a comma-separated chain of aliases
+/// where every hop but the last is a ProxyJump host and the last one is the bastion
+pub fn validate_bastion_chain(bastion: &str) -> Result<()> {
+    for hop in bastion.split(BASTION_HOP_SEPARATOR) {
+        if hop.is_empty() {
+            return Err(anyhow::anyhow!("Invalid bastion chain '{}': empty hop", bastion));
+        }
+        if hop.starts_with('-') || hop.chars().any(char::is_whitespace) {
+            return Err(anyhow::anyhow!("Invalid bastion chain '{}': '{}' is not an SSH host", bastion, hop));
+        }
+    }
+    Ok(())
+}
+
+/// `ssh` arguments that reach the bastion: its alias, or for a chain `-J <jump hosts> <bastion>`
+pub fn bastion_ssh_args(bastion: &str) -> Vec<String> {
+    match bastion.rsplit_once(BASTION_HOP_SEPARATOR) {
+        Some((jumps, target)) => vec!["-J".to_string(), jumps.to_string(), target.to_string()],
+        None => vec![bastion.to_string()],
+    }
+}
 
 /// Execute command on bastion via SSH
 pub fn run_command_on_bastion(bastion_alias: Option<&String>, command: &str) -> Result<String> {
     if let Some(bastion_alias) = bastion_alias {
         let output = Command::new("ssh")
-            .arg(bastion_alias)
+            .args(bastion_ssh_args(bastion_alias))
             .arg(command)
             .output()
             .context(format!("Failed to execute command on bastion: {}", command))?;
             
+        let stderr = String::from_utf8_lossy(&output.stderr);
         if output.status.success() {
             Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else if output.status.code() == Some(SSH_ERROR_EXIT_CODE) {
+            Err(SshTransportError(stderr.trim().to_string()).into())
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
             Err(anyhow::anyhow!("Command failed on bastion: {}", stderr))
         }
     } else {
@@ -47,79 +104,79 @@ pub fn check_ssh_agent(bastion_alias: Option<&String>) -> Result<()> {
 /// Run SSH diagnostics to help debug connection issues
 pub async fn run_ssh_diagnostics(bastion_alias: &str, sample_broker: &BrokerInfo) {
     // Test bastion connectivity
-    print!("  • Bastion connectivity... ");
+    banner_step!("  • Bastion connectivity... ");
     let bastion_test = Command::new("ssh")
         .arg("-o")
         .arg("ConnectTimeout=5")
         .arg("-o")
         .arg("StrictHostKeyChecking=no")
-        .arg(bastion_alias)
+        .args(bastion_ssh_args(bastion_alias))
         .arg("echo 'bastion-ok'")
         .output();
         
     match bastion_test {
         Ok(result) if result.status.success() => {
-            println!("✅ Connected");
+            banner!("✅ Connected");
             let output = String::from_utf8_lossy(&result.stdout);
             if !output.contains("bastion-ok") {
-                println!("    ⚠️ Unexpected output: {}", output.trim());
+                banner!("    ⚠️ Unexpected output: {}", output.trim());
             }
         }
         Ok(result) => {
-            println!("❌ Failed (exit code: {})", result.status.code().unwrap_or(-1));
+            banner!("❌ Failed (exit code: {})", result.status.code().unwrap_or(-1));
             let stderr = String::from_utf8_lossy(&result.stderr);
             if !stderr.is_empty() {
-                println!("    Error: {}", stderr.trim());
+                banner!("    Error: {}", stderr.trim());
             }
         }
         Err(e) => {
-            println!("❌ Error executing SSH: {}", e);
+            banner!("❌ Error executing SSH: {}", e);
             return;
         }
     }
     
     // Test SSH agent forwarding
-    print!("  • SSH agent forwarding... ");
+    banner_step!("  • SSH agent forwarding... ");
     let agent_test = Command::new("ssh")
         .arg("-A")
         .arg("-o")
         .arg("ConnectTimeout=5")
         .arg("-o")
         .arg("StrictHostKeyChecking=no")
-        .arg(bastion_alias)
+        .args(bastion_ssh_args(bastion_alias))
         .arg("ssh-add -l")
         .output();
         
     match agent_test {
         Ok(result) if result.status.success() => {
-            println!("✅ Working");
+            banner!("✅ Working");
             let output = String::from_utf8_lossy(&result.stdout);
             let key_count = output.lines().count();
-            println!("    SSH keys available: {}", key_count);
+            banner!("    SSH keys available: {}", key_count);
         }
         Ok(result) => {
-            println!("❌ Failed");
+            banner!("❌ Failed");
             let stderr = String::from_utf8_lossy(&result.stderr);
             if stderr.contains("agent has no identities") {
-                println!("    Issue: No SSH keys in agent");
-                println!("    Solution: Run 'ssh-add ~/.ssh/your-key'");
+                banner!("    Issue: No SSH keys in agent");
+                banner!("    Solution: Run 'ssh-add ~/.ssh/your-key'");
             } else if !stderr.is_empty() {
-                println!("    Error: {}", stderr.trim());
+                banner!("    Error: {}", stderr.trim());
             }
         }
         Err(e) => {
-            println!("❌ Error: {}", e);
+            banner!("❌ Error: {}", e);
         }
     }
     
     // Test broker hostname resolution from bastion
-    print!("  • Sample broker hostname resolution... ");
+    banner_step!("  • Sample broker hostname resolution... ");
     let resolve_test = Command::new("ssh")
         .arg("-o")
         .arg("ConnectTimeout=5")
         .arg("-o")
         .arg("StrictHostKeyChecking=no")
-        .arg(bastion_alias)
+        .args(bastion_ssh_args(bastion_alias))
         .arg(format!("host {} || getent hosts {} || echo 'resolution-failed'", 
             sample_broker.hostname, sample_broker.hostname))
         .output();
@@ -128,17 +185,17 @@ pub async fn run_ssh_diagnostics(bastion_alias: &str, sample_broker: &BrokerInfo
         Ok(result) if result.status.success() => {
             let output = String::from_utf8_lossy(&result.stdout);
             if output.contains("resolution-failed") {
-                println!("❌ Cannot resolve {}", sample_broker.hostname);
-                println!("    This suggests DNS or hostname configuration issues");
+                banner!("❌ Cannot resolve {}", sample_broker.hostname);
+                banner!("    This suggests DNS or hostname configuration issues");
             } else {
-                println!("✅ {} resolves", sample_broker.hostname);
+                banner!("✅ {} resolves", sample_broker.hostname);
             }
         }
         Ok(_) => {
-            println!("⚠️ Cannot test hostname resolution");
+            banner!("⚠️ Cannot test hostname resolution");
         }
         Err(e) => {
-            println!("❌ Error: {}", e);
+            banner!("❌ Error: {}", e);
         }
     }
 }
@@ -161,7 +218,7 @@ pub async fn test_broker_access(bastion_alias: Option<&String>, broker: &BrokerI
                 .arg("ConnectTimeout=10")
                 .arg("-o") 
                 .arg("StrictHostKeyChecking=no")
-                .arg(alias)
+                .args(bastion_ssh_args(alias))
                 .arg(ssh_command)
                 .output();
             
@@ -231,25 +288,29 @@ pub fn check_kafkactl_availability(bastion_alias: Option<&String>) -> bool {
 pub async fn discover_brokers_with_bastion_admin_client(bastion_alias: Option<&String>, broker_address: &str) -> Result<Vec<BrokerInfo>> {
     info!("Attempting broker discovery using simple connection test on bastion");
     
+    let (host, port) = split_host_port(broker_address);
+    let port = port.unwrap_or(DEFAULT_BROKER_PORT);
+
     // Method 1: Try a simple netcat/telnet test to verify connectivity
     let connectivity_test = format!(
         "timeout 3 bash -c 'echo > /dev/tcp/{}/{}' 2>/dev/null && echo 'CONNECTED'",
-        broker_address.split(':').next().unwrap_or("unknown"),
-        broker_address.split(':').nth(1).unwrap_or("9092")
+        host,
+        port
     );
     
-    match run_command_on_bastion(bastion_alias, &connectivity_test) {
-        Ok(output) => {
+    match probe_output(run_command_on_bastion(bastion_alias, &connectivity_test))? {
+        Some(output) => {
             if output.contains("CONNECTED") {
                 info!("Successfully verified connectivity to broker via TCP test");
-                let hostname = broker_address.split(':').next().unwrap_or("unknown").to_string();
+                let hostname = host.clone();
                 return Ok(vec![BrokerInfo {
                     id: 0, // Will be determined during data collection
                     hostname,
+                    listeners: Vec::new(),
                 }]);
             }
         }
-        Err(_) => {
+        None => {
             debug!("TCP connectivity test failed");
         }
     }
@@ -257,21 +318,22 @@ pub async fn discover_brokers_with_bastion_admin_client(bastion_alias: Option<&S
     // Method 2: Try to resolve the hostname 
     let hostname_test = format!(
         "nslookup {} >/dev/null 2>&1 && echo 'RESOLVABLE'",
-        broker_address.split(':').next().unwrap_or("unknown")
+        host
     );
     
-    match run_command_on_bastion(bastion_alias, &hostname_test) {
-        Ok(output) => {
+    match probe_output(run_command_on_bastion(bastion_alias, &hostname_test))? {
+        Some(output) => {
             if output.contains("RESOLVABLE") {
                 info!("Hostname resolution successful, assuming broker is available");
-                let hostname = broker_address.split(':').next().unwrap_or("unknown").to_string();
+                let hostname = host.clone();
                 return Ok(vec![BrokerInfo {
                     id: 0, // Will be determined during data collection
                     hostname,
+                    listeners: Vec::new(),
                 }]);
             }
         }
-        Err(_) => {
+        None => {
             debug!("Hostname resolution test failed");
         }
     }
@@ -279,24 +341,46 @@ pub async fn discover_brokers_with_bastion_admin_client(bastion_alias: Option<&S
     // Method 3: Basic ping test
     let ping_test = format!(
         "ping -c 1 -W 3 {} >/dev/null 2>&1 && echo 'PINGABLE'",
-        broker_address.split(':').next().unwrap_or("unknown")
+        host
     );
     
-    match run_command_on_bastion(bastion_alias, &ping_test) {
-        Ok(output) => {
+    match probe_output(run_command_on_bastion(bastion_alias, &ping_test))? {
+        Some(output) => {
             if output.contains("PINGABLE") {
                 info!("Ping test successful, assuming broker is available");
-                let hostname = broker_address.split(':').next().unwrap_or("unknown").to_string();
+                let hostname = host.clone();
                 return Ok(vec![BrokerInfo {
                     id: 0, // Will be determined during data collection
                     hostname,
+                    listeners: Vec::new(),
                 }]);
             }
         }
-        Err(_) => {
+        None => {
             debug!("Ping test failed");
         }
     }
     
     Ok(Vec::new())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bastion_ssh_args() {
+        assert_eq!(bastion_ssh_args("kafka-bastion"), vec!["kafka-bastion"]);
+        assert_eq!(bastion_ssh_args("jump-eu,kafka-bastion"), vec!["-J", "jump-eu", "kafka-bastion"]);
+        assert_eq!(bastion_ssh_args("edge,jump-eu,kafka-bastion"), vec!["-J", "edge,jump-eu", "kafka-bastion"]);
+    }
+
+    #[test]
+    fn test_validate_bastion_chain() {
+        assert!(validate_bastion_chain("kafka-bastion").is_ok());
+        assert!(validate_bastion_chain("admin@jump-eu:2222,kafka-bastion").is_ok());
+        assert!(validate_bastion_chain("jump-eu,,kafka-bastion").is_err());
+        assert!(validate_bastion_chain("jump-eu,").is_err());
+        assert!(validate_bastion_chain("jump-eu, kafka-bastion").is_err());
+        assert!(validate_bastion_chain("-oProxyCommand=evil,kafka-bastion").is_err());
+    }
+}
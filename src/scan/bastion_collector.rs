@@ -2,9 +2,12 @@ use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 
-use super::ClusterData;
+use super::bastion::bastion_ssh_args;
+use super::collector::{CollectorSelection, ScanCollector};
+use super::consumer_lag::{self, CONSUMER_GROUP_DESCRIPTIONS_FILE, CONSUMER_LAG_FILE};
+use super::manifest::{ManifestRecorder, ManifestWriter};
+use super::{scan_command, ClusterData};
 
 /// Discovery method used for broker/topic discovery
 #[derive(Debug, Clone)]
@@ -13,6 +16,8 @@ pub enum DiscoveryMethod {
     KafkaTools { kafka_installation_path: String, discovery_broker: String },
     /// Using kafkactl (fallback when no --broker provided)
     Kafkactl,
+    /// Brokers listed in a --broker-list file, without discovery; topics come from kafkactl
+    BrokerList { path: String },
 }
 
 /// Collector for bastion-level data (kafkactl, metrics, etc.)
@@ -20,6 +25,8 @@ pub struct BastionCollector {
     bastion_alias: Option<String>,  // None means we're running locally on the bastion
     output_dir: PathBuf,
     discovery_method: Option<DiscoveryMethod>,
+    collectors: CollectorSelection,
+    files: ManifestWriter,
 }
 
 impl BastionCollector {
@@ -28,29 +35,43 @@ impl BastionCollector {
             bastion_alias,
             output_dir,
             discovery_method: None,
+            collectors: CollectorSelection::all(),
+            files: ManifestWriter::default(),
         }
     }
 
+    /// Record the collected files in the scan manifest
+    pub fn with_manifest(mut self, manifest: &ManifestRecorder) -> Self {
+        self.files = manifest.writer("cluster", None);
+        self
+    }
+
     /// Set the discovery method to use for topic and other cluster resource discovery
     pub fn with_discovery_method(mut self, discovery_method: DiscoveryMethod) -> Self {
         self.discovery_method = Some(discovery_method);
         self
     }
 
+    /// Only run the cluster collectors in `collectors`
+    pub fn with_collectors(mut self, collectors: CollectorSelection) -> Self {
+        self.collectors = collectors;
+        self
+    }
+
     /// Execute command on bastion (either locally or via SSH)
     fn run_on_bastion(&self, command: &str) -> Result<String> {
         let output = match &self.bastion_alias {
             Some(alias) => {
                 // Remote execution via SSH
-                Command::new("ssh")
-                    .arg(alias)
+                scan_command("ssh")
+                    .args(bastion_ssh_args(alias))
                     .arg(command)
                     .output()
                     .context(format!("Failed to execute on bastion: {}", command))?
             }
             None => {
                 // Local execution (we're on the bastion)
-                Command::new("sh")
+                scan_command("sh")
                     .arg("-c")
                     .arg(command)
                     .output()
@@ -84,93 +105,136 @@ impl BastionCollector {
         let kafkactl_dir = self.output_dir.join("cluster").join("kafkactl");
         
         // Check if kafkactl is available
-        if !self.check_kafkactl_availability() {
-            println!("⚠️  kafkactl not available {} - skipping kafkactl data collection", location);
-            println!("   Continuing with other data collection methods...");
+        if !self.collectors.includes(ScanCollector::Admin) {
+            banner!("⏭️  Skipping kafkactl data collection (admin collector not selected)\n");
+        } else if !self.check_kafkactl_availability() {
+            banner!("⚠️  kafkactl not available {} - skipping kafkactl data collection", location);
+            banner!("   Continuing with other data collection methods...");
             
             // Create empty kafkactl directory to maintain expected structure
             fs::create_dir_all(&kafkactl_dir)?;
-            fs::write(kafkactl_dir.join("unavailable.txt"), 
+            self.files.write(kafkactl_dir.join("unavailable.txt"), 
                 "kafkactl tool was not available on the bastion host during scan")?;
         } else {
-            println!("📊 Collecting kafkactl data {}...", location);
+            banner!("📊 Collecting kafkactl data {}...", location);
             
             // Get broker list
-            print!("  • Getting broker list... ");
+            banner_step!("  • Getting broker list... ");
             if let Ok(brokers) = self.run_on_bastion("kafkactl get brokers -o yaml") {
-                fs::write(kafkactl_dir.join("brokers.yaml"), &brokers)?;
+                self.files.write(kafkactl_dir.join("brokers.yaml"), &brokers)?;
                 kafkactl_data.insert("brokers".to_string(), brokers);
-                println!("✓");
+                banner!("✓");
             } else {
-                println!("⚠");
+                banner!("⚠");
             }
             
             // Get topics using the appropriate discovery method
-            println!("📋 Topic Discovery Method:");
+            banner!("📋 Topic Discovery Method:");
             match &self.discovery_method {
                 Some(DiscoveryMethod::KafkaTools { kafka_installation_path, discovery_broker }) => {
-                    println!("   🔧 Using kafka-topics.sh from installation path: {}", kafka_installation_path);
-                    println!("   🎯 Discovery broker: {}", discovery_broker);
+                    banner!("   🔧 Using kafka-topics.sh from installation path: {}", kafka_installation_path);
+                    banner!("   🎯 Discovery broker: {}", discovery_broker);
                     let tools_dir = self.output_dir.join("cluster").join("tools");
                     self.collect_topics_with_kafka_tools(kafka_installation_path, discovery_broker, &tools_dir, &mut kafkactl_data)?;
                 }
                 Some(DiscoveryMethod::Kafkactl) => {
-                    println!("   🛠️  Using kafkactl (explicit method)");
+                    banner!("   🛠️  Using kafkactl (explicit method)");
+                    self.collect_topics_with_kafkactl(&kafkactl_dir, &mut kafkactl_data)?;
+                }
+                Some(DiscoveryMethod::BrokerList { path }) => {
+                    banner!("   🛠️  Using kafkactl (brokers listed in {})", path);
                     self.collect_topics_with_kafkactl(&kafkactl_dir, &mut kafkactl_data)?;
                 }
                 None => {
-                    println!("   🛠️  Using kafkactl (default fallback)");
+                    banner!("   🛠️  Using kafkactl (default fallback)");
                     self.collect_topics_with_kafkactl(&kafkactl_dir, &mut kafkactl_data)?;
                 }
             }
             
             // Get consumer groups
-            print!("  • Getting consumer groups... ");
+            banner_step!("  • Getting consumer groups... ");
             if let Ok(consumer_groups) = self.run_on_bastion("kafkactl get consumer-groups -o yaml") {
-                fs::write(kafkactl_dir.join("consumer_groups.yaml"), &consumer_groups)?;
+                self.files.write(kafkactl_dir.join("consumer_groups.yaml"), &consumer_groups)?;
+                banner!("✓");
+                self.collect_consumer_lag(&consumer_groups, &kafkactl_dir)?;
                 kafkactl_data.insert("consumer_groups".to_string(), consumer_groups);
-                println!("✓");
             } else {
-                println!("⚠");
+                banner!("⚠");
             }
             
             // Get individual broker configs
-            println!("  • Getting broker configurations:");
+            banner!("  • Getting broker configurations:");
             for broker_id in [11, 12, 13, 14, 15, 16] {
-                print!("    - Broker {}... ", broker_id);
+                banner_step!("    - Broker {}... ", broker_id);
                 if let Ok(config) = self.run_on_bastion(&format!("kafkactl describe broker {} -o yaml", broker_id)) {
-                    fs::write(
+                    self.files.write(
                         kafkactl_dir.join(format!("broker_{}_config.yaml", broker_id)),
                         &config,
                     )?;
                     kafkactl_data.insert(format!("broker_{}_config", broker_id), config);
-                    println!("✓");
+                    banner!("✓");
                 } else {
-                    println!("⚠");
+                    banner!("⚠");
                 }
             }
+            
+            banner!("✅ Kafkactl data collected\n");
         }
         
-        println!("✅ Kafkactl data collected\n");
+        // Feature levels need kafka-features.sh, which is only known with --broker
+        let features = match &self.discovery_method {
+            Some(DiscoveryMethod::KafkaTools { kafka_installation_path, discovery_broker })
+                if self.collectors.includes(ScanCollector::Admin) => {
+                self.collect_features_with_kafka_tools(kafka_installation_path, discovery_broker)?
+            }
+            _ => None,
+        };
         
         // Collect kafka_exporter metrics
-        println!("📈 Collecting kafka_exporter metrics...");
+        let metrics = if self.collectors.includes(ScanCollector::Metrics) {
+            self.collect_exporter_metrics()?
+        } else {
+            None
+        };
+        
+        // Collect bastion system info
+        let bastion_info = if self.collectors.includes(ScanCollector::Bastion) {
+            self.collect_bastion_info()?
+        } else {
+            HashMap::new()
+        };
+        
+        Ok(ClusterData {
+            kafkactl_data,
+            metrics,
+            bastion_info,
+            features,
+        })
+    }
+
+    /// Scrape kafka_exporter on the bastion
+    fn collect_exporter_metrics(&self) -> Result<Option<String>> {
+        banner!("📈 Collecting kafka_exporter metrics...");
         let metrics_dir = self.output_dir.join("metrics").join("kafka_exporter");
         let metrics = self.run_on_bastion("curl -s http://localhost:9308/metrics").ok();
         
         if let Some(ref m) = metrics {
-            fs::write(metrics_dir.join("prometheus_metrics.txt"), m)?;
+            self.files.write(metrics_dir.join("prometheus_metrics.txt"), m)?;
             if m.lines().count() > 0 {
-                println!("✅ Kafka exporter metrics collected ({} lines)\n", m.lines().count());
+                banner!("✅ Kafka exporter metrics collected ({} lines)\n", m.lines().count());
             } else {
-                println!("⚠️  No metrics from kafka_exporter\n");
+                banner!("⚠️  No metrics from kafka_exporter\n");
             }
         } else {
-            println!("⚠️  No metrics from kafka_exporter\n");
+            banner!("⚠️  No metrics from kafka_exporter\n");
         }
         
-        // Collect bastion system info
-        println!("💻 Collecting bastion system info...");
+        Ok(metrics)
+    }
+
+    /// Collect system information of the bastion host
+    fn collect_bastion_info(&self) -> Result<HashMap<String, String>> {
+        banner!("💻 Collecting bastion system info...");
         let mut bastion_info = HashMap::new();
         let system_dir = self.output_dir.join("system").join("bastion");
         
@@ -186,18 +250,44 @@ impl BastionCollector {
         
         for (name, cmd) in commands {
             if let Ok(output) = self.run_on_bastion(cmd) {
-                fs::write(system_dir.join(format!("{}.txt", name)), &output)?;
+                self.files.write(system_dir.join(format!("{}.txt", name)), &output)?;
                 bastion_info.insert(name.to_string(), output);
             }
         }
         
-        println!("✅ Bastion system info collected");
+        banner!("✅ Bastion system info collected");
         
-        Ok(ClusterData {
-            kafkactl_data,
-            metrics,
-            bastion_info,
-        })
+        Ok(bastion_info)
+    }
+
+    /// Collect finalized feature levels, including the KRaft metadata version, with kafka-features.sh
+    fn collect_features_with_kafka_tools(
+        &self,
+        kafka_installation_path: &str,
+        discovery_broker: &str,
+    ) -> Result<Option<String>> {
+        banner!("🏷️  Collecting cluster feature levels...");
+        
+        let hostname = discovery_broker.split(':').next().unwrap_or(discovery_broker);
+        let features_cmd = format!(
+            "ssh -o StrictHostKeyChecking=no {} '{}/kafka-features.sh --bootstrap-server localhost:9092 describe'",
+            hostname,
+            kafka_installation_path
+        );
+        
+        match self.run_on_bastion(&features_cmd) {
+            Ok(output) => {
+                let feature_count = super::features::parse_kafka_features(&output).len();
+                self.files.write(self.output_dir.join("features").join(super::features::KAFKA_FEATURES_FILE), &output)?;
+                banner!("✅ {} feature levels collected\n", feature_count);
+                Ok(Some(output))
+            }
+            Err(_) => {
+                // kafka-features.sh is missing before Kafka 2.7 and fails against ZooKeeper clusters without features
+                banner!("⚠️  kafka-features.sh describe failed - skipping feature levels\n");
+                Ok(None)
+            }
+        }
     }
 
     /// Collect topics using kafka-topics command (preferred method when --broker is provided)
@@ -208,7 +298,7 @@ impl BastionCollector {
         tools_dir: &Path,
         kafkactl_data: &mut HashMap<String, String>
     ) -> Result<()> {
-        print!("  • Getting topics (kafka-tools)... ");
+        banner_step!("  • Getting topics (kafka-tools)... ");
         
         let hostname = discovery_broker.split(':').next().unwrap_or(discovery_broker);
         let kafka_topics_cmd = format!(
@@ -217,15 +307,15 @@ impl BastionCollector {
             kafka_installation_path
         );
         
-        println!();
-        println!("     🔍 Command: ssh {} '{}/kafka-topics.sh --bootstrap-server localhost:9092 --describe'", hostname, kafka_installation_path);
+        banner!();
+        banner!("     🔍 Command: ssh {} '{}/kafka-topics.sh --bootstrap-server localhost:9092 --describe'", hostname, kafka_installation_path);
         
         if let Ok(topics_output) = self.run_on_bastion(&kafka_topics_cmd) {
             let topic_count = topics_output.lines()
                 .filter(|line| line.starts_with("Topic:"))
                 .count();
             
-            fs::write(tools_dir.join("topics_kafka_tools.txt"), &topics_output)?;
+            self.files.write(tools_dir.join("topics_kafka_tools.txt"), &topics_output)?;
             kafkactl_data.insert("topics_kafka_tools".to_string(), topics_output.clone());
             
             // Also get list format for compatibility
@@ -237,16 +327,16 @@ impl BastionCollector {
             
             if let Ok(topics_list) = self.run_on_bastion(&list_cmd) {
                 let list_topic_count = topics_list.lines().filter(|line| !line.trim().is_empty()).count();
-                fs::write(tools_dir.join("topics_list.txt"), &topics_list)?;
+                self.files.write(tools_dir.join("topics_list.txt"), &topics_list)?;
                 kafkactl_data.insert("topics_list".to_string(), topics_list);
-                println!("     ✅ Successfully collected {} topics using kafka-tools.sh", list_topic_count);
+                banner!("     ✅ Successfully collected {} topics using kafka-tools.sh", list_topic_count);
             }
             
-            println!("     📝 Detailed topic info: {} topic descriptions", topic_count);
-            println!("     💾 Saved to: topics_kafka_tools.txt, topics_list.txt");
+            banner!("     📝 Detailed topic info: {} topic descriptions", topic_count);
+            banner!("     💾 Saved to: topics_kafka_tools.txt, topics_list.txt");
         } else {
-            println!("     ❌ kafka-topics.sh command failed");
-            println!("     🔄 Falling back to kafkactl method...");
+            banner!("     ❌ kafka-topics.sh command failed");
+            banner!("     🔄 Falling back to kafkactl method...");
             let kafkactl_dir = self.output_dir.join("cluster").join("kafkactl");
             self.collect_topics_with_kafkactl(&kafkactl_dir, kafkactl_data)?;
         }
@@ -254,27 +344,52 @@ impl BastionCollector {
         Ok(())
     }
 
+    /// Describe each consumer group in `consumer_groups` (kafkactl YAML) and save the
+    /// descriptions and the lag, state and member count of each group
+    fn collect_consumer_lag(&self, consumer_groups: &str, kafkactl_dir: &Path) -> Result<()> {
+        let names = consumer_lag::parse_group_names(consumer_groups);
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        banner_step!("  • Describing {} consumer group(s)... ", names.len());
+        let mut descriptions = String::new();
+        for name in &names {
+            if let Ok(description) = self.run_on_bastion(&consumer_lag::describe_group_command(name)) {
+                descriptions.push_str("---\n");
+                descriptions.push_str(&description);
+                descriptions.push('\n');
+            }
+        }
+        self.files.write(kafkactl_dir.join(CONSUMER_GROUP_DESCRIPTIONS_FILE), &descriptions)?;
+
+        let lag = consumer_lag::parse_group_descriptions(&descriptions);
+        self.files.write(kafkactl_dir.join(CONSUMER_LAG_FILE), &serde_json::to_string_pretty(&lag)?)?;
+        banner!("✓ ({} with lag data)", lag.len());
+        Ok(())
+    }
+
     /// Collect topics using kafkactl (fallback method)
     fn collect_topics_with_kafkactl(
         &self,
         kafkactl_dir: &Path,
         kafkactl_data: &mut HashMap<String, String>
     ) -> Result<()> {
-        print!("  • Getting topics (kafkactl)... ");
-        println!();
-        println!("     🔍 Command: kafkactl get topics -o yaml");
+        banner_step!("  • Getting topics (kafkactl)... ");
+        banner!();
+        banner!("     🔍 Command: kafkactl get topics -o yaml");
         
         if let Ok(topics) = self.run_on_bastion("kafkactl get topics -o yaml") {
             let topic_count = topics.lines()
                 .filter(|line| line.trim().starts_with("- name:"))
                 .count();
             
-            fs::write(kafkactl_dir.join("topics.yaml"), &topics)?;
+            self.files.write(kafkactl_dir.join("topics.yaml"), &topics)?;
             kafkactl_data.insert("topics".to_string(), topics.clone());
-            println!("     ✅ Successfully collected {} topics using kafkactl", topic_count);
+            banner!("     ✅ Successfully collected {} topics using kafkactl", topic_count);
             
             // Get topic details - extract topic names from YAML and get detailed info
-            print!("     🔍 Getting detailed topic descriptions... ");
+            banner_step!("     🔍 Getting detailed topic descriptions... ");
             let mut topics_detailed = String::new();
             let mut detailed_count = 0;
             
@@ -294,13 +409,13 @@ impl BastionCollector {
                     }
                 }
             }
-            fs::write(kafkactl_dir.join("topics_detailed.yaml"), &topics_detailed)?;
+            self.files.write(kafkactl_dir.join("topics_detailed.yaml"), &topics_detailed)?;
             kafkactl_data.insert("topics_detailed".to_string(), topics_detailed);
-            println!("✅ ({} detailed descriptions)", detailed_count);
-            println!("     💾 Saved to: topics.yaml, topics_detailed.yaml");
+            banner!("✅ ({} detailed descriptions)", detailed_count);
+            banner!("     💾 Saved to: topics.yaml, topics_detailed.yaml");
         } else {
-            println!("     ❌ kafkactl command failed");
-            println!("     ⚠️  No topic data could be collected");
+            banner!("     ❌ kafkactl command failed");
+            banner!("     ⚠️  No topic data could be collected");
         }
         
         Ok(())
@@ -0,0 +1,396 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use super::{ScanMetadata, Scanner};
+
+/// File written to the batch output directory summarizing every cluster scan
+pub const BATCH_INDEX_FILE: &str = "batch_index.json";
+
+/// Clusters to scan in one batch, read from a YAML inventory file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Inventory {
+    pub clusters: Vec<ClusterTarget>,
+}
+
+/// One cluster of the inventory, with the same settings as `kcpilot scan`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterTarget {
+    /// Unique name, also used as the cluster's output directory
+    pub name: String,
+    /// SSH bastion alias or comma-separated ProxyJump chain; scans locally when omitted
+    #[serde(default)]
+    pub bastion: Option<String>,
+    /// Broker `hostname:port` to discover the cluster from; uses kafkactl when omitted
+    #[serde(default)]
+    pub broker: Option<String>,
+}
+
+impl Inventory {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read inventory {}", path.display()))?;
+
+        let inventory: Inventory = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse inventory {}", path.display()))?;
+
+        inventory.validate()?;
+        Ok(inventory)
+    }
+
+    /// Cluster names must be unique and usable as directory names
+    pub fn validate(&self) -> Result<()> {
+        if self.clusters.is_empty() {
+            anyhow::bail!("Inventory does not list any clusters");
+        }
+
+        let mut names = HashSet::new();
+        for cluster in &self.clusters {
+            let valid = !cluster.name.is_empty()
+                && cluster.name != "."
+                && cluster.name != ".."
+                && cluster.name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+            if !valid {
+                anyhow::bail!(
+                    "Invalid cluster name '{}': use only letters, digits, '-', '_' and '.'",
+                    cluster.name
+                );
+            }
+            if !names.insert(cluster.name.as_str()) {
+                anyhow::bail!("Duplicate cluster name '{}' in inventory", cluster.name);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Outcome of scanning one cluster of a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchScanStatus {
+    Completed,
+    /// Cancelled part-way; the output holds partial data
+    Partial,
+    Failed,
+    /// Not started because the batch was cancelled
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchClusterResult {
+    pub name: String,
+    pub output_directory: String,
+    pub status: BatchScanStatus,
+    pub broker_count: usize,
+    pub accessible_brokers: usize,
+    pub error: Option<String>,
+}
+
+/// Combined index of a batch run, saved as `batch_index.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchIndex {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub clusters: Vec<BatchClusterResult>,
+}
+
+impl BatchIndex {
+    pub fn failed(&self) -> usize {
+        self.clusters.iter().filter(|c| c.status == BatchScanStatus::Failed).count()
+    }
+}
+
+/// Scans every cluster of an inventory into `<output_dir>/<cluster name>`
+pub struct BatchScanner {
+    inventory: Inventory,
+    output_dir: PathBuf,
+    concurrency: usize,
+    log_since: Option<DateTime<Utc>>,
+    cancel: CancellationToken,
+}
+
+impl BatchScanner {
+    pub fn new(inventory: Inventory, output_dir: PathBuf) -> Self {
+        Self {
+            inventory,
+            output_dir,
+            concurrency: 1,
+            log_since: None,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Scan up to `concurrency` clusters at a time (default: one after another)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Bound broker log collection of every cluster to entries written at or after `since`
+    pub fn with_log_since(mut self, since: Option<DateTime<Utc>>) -> Self {
+        self.log_since = since;
+        self
+    }
+
+    /// Stop the running scans and skip the remaining clusters when `cancel` fires
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Scan all clusters and write the combined index. A failing cluster does not stop the others.
+    pub async fn run(&self) -> Result<BatchIndex> {
+        self.run_with(scan_cluster).await
+    }
+
+    /// Scan all clusters with `scan`, up to `concurrency` at a time. Scans make blocking SSH
+    /// calls, so each one runs on its own blocking thread rather than on the async runtime.
+    async fn run_with<S, F>(&self, scan: S) -> Result<BatchIndex>
+    where
+        S: Fn(ClusterScan) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<ScanMetadata>>,
+    {
+        let started_at = Utc::now();
+        fs::create_dir_all(&self.output_dir)
+            .with_context(|| format!("Failed to create {}", self.output_dir.display()))?;
+
+        let scan = Arc::new(scan);
+        let permits = Arc::new(Semaphore::new(self.concurrency));
+        let mut scans = JoinSet::new();
+        let mut clusters: Vec<BatchClusterResult> = Vec::with_capacity(self.inventory.clusters.len());
+        for (position, cluster) in self.inventory.clusters.iter().enumerate() {
+            let output_dir = self.output_dir.join(&cluster.name);
+            clusters.push(BatchClusterResult {
+                name: cluster.name.clone(),
+                output_directory: output_dir.display().to_string(),
+                status: BatchScanStatus::Skipped,
+                broker_count: 0,
+                accessible_brokers: 0,
+                error: None,
+            });
+
+            let permit = permits.clone().acquire_owned().await?;
+            if self.cancel.is_cancelled() {
+                warn!("Batch cancelled, skipping cluster {}", cluster.name);
+                continue;
+            }
+
+            info!("Scanning cluster {} into {}", cluster.name, output_dir.display());
+            let job = ClusterScan {
+                cluster: cluster.clone(),
+                output_dir,
+                log_since: self.log_since,
+                // Progress bars of concurrent scans would draw over each other
+                show_progress: self.concurrency == 1,
+                cancel: self.cancel.clone(),
+            };
+            let scan = scan.clone();
+            let runtime = Handle::current();
+            scans.spawn_blocking(move || {
+                let _permit = permit;
+                (position, runtime.block_on(scan(job)))
+            });
+        }
+
+        while let Some(joined) = scans.join_next().await {
+            let (position, outcome) = joined.context("Cluster scan panicked")?;
+            let result = &mut clusters[position];
+            match outcome {
+                Ok(metadata) => {
+                    result.status = if metadata.partial { BatchScanStatus::Partial } else { BatchScanStatus::Completed };
+                    result.broker_count = metadata.broker_count;
+                    result.accessible_brokers = metadata.accessible_brokers;
+                }
+                Err(e) => {
+                    error!("Scan of cluster {} failed: {:#}", result.name, e);
+                    result.status = BatchScanStatus::Failed;
+                    result.error = Some(format!("{:#}", e));
+                }
+            }
+        }
+
+        let index = BatchIndex {
+            started_at,
+            finished_at: Utc::now(),
+            clusters,
+        };
+        fs::write(
+            self.output_dir.join(BATCH_INDEX_FILE),
+            serde_json::to_string_pretty(&index)?,
+        )?;
+
+        Ok(index)
+    }
+}
+
+/// One cluster of a batch, with the settings its scan runs with
+struct ClusterScan {
+    cluster: ClusterTarget,
+    output_dir: PathBuf,
+    log_since: Option<DateTime<Utc>>,
+    show_progress: bool,
+    cancel: CancellationToken,
+}
+
+async fn scan_cluster(job: ClusterScan) -> Result<ScanMetadata> {
+    // Created up front so every cluster has a directory, even when discovery fails
+    fs::create_dir_all(&job.output_dir)
+        .with_context(|| format!("Failed to create {}", job.output_dir.display()))?;
+
+    let mut scanner = Scanner::new(job.cluster.bastion.clone())?
+        .with_output_dir(job.output_dir)
+        .with_log_since(job.log_since)
+        .with_progress(job.show_progress)
+        .with_cancellation(job.cancel)
+        .discover(job.cluster.broker.as_deref())
+        .await?;
+
+    Ok(scanner.scan().await?.metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const INVENTORY: &str = r#"
+clusters:
+  - name: prod-eu
+    bastion: kafka-bastion-eu
+    broker: kafka-1.eu.internal:9092
+  - name: staging
+    broker: localhost:9092
+  - name: legacy
+    bastion: legacy-bastion
+"#;
+
+    fn inventory(yaml: &str) -> Result<Inventory> {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("inventory.yaml");
+        fs::write(&path, yaml).unwrap();
+        Inventory::from_file(&path)
+    }
+
+    #[test]
+    fn test_parse_inventory() {
+        let inventory = inventory(INVENTORY).unwrap();
+
+        assert_eq!(inventory.clusters.len(), 3);
+        assert_eq!(inventory.clusters[0].name, "prod-eu");
+        assert_eq!(inventory.clusters[0].bastion.as_deref(), Some("kafka-bastion-eu"));
+        assert_eq!(inventory.clusters[0].broker.as_deref(), Some("kafka-1.eu.internal:9092"));
+        assert!(inventory.clusters[1].bastion.is_none());
+        assert!(inventory.clusters[2].broker.is_none());
+    }
+
+    #[test]
+    fn test_invalid_inventories() {
+        assert!(inventory("clusters: []").is_err());
+        assert!(inventory("clusters:\n  - name: a\n  - name: a\n").is_err());
+        assert!(inventory("clusters:\n  - name: ../escape\n").is_err());
+        assert!(inventory("clusters:\n  - bastion: no-name\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_cluster_does_not_stop_batch() {
+        let output = TempDir::new().unwrap();
+        // Broker addresses without a host fail discovery before anything is contacted
+        let inventory = Inventory {
+            clusters: vec![
+                ClusterTarget { name: "first".to_string(), bastion: None, broker: Some(":9092".to_string()) },
+                ClusterTarget { name: "second".to_string(), bastion: None, broker: Some(":9093".to_string()) },
+            ],
+        };
+
+        let index = BatchScanner::new(inventory, output.path().to_path_buf())
+            .with_concurrency(2)
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(index.clusters.len(), 2);
+        assert_eq!(index.failed(), 2);
+        assert!(index.clusters[0].error.as_deref().unwrap().contains("hostname[:port]"));
+        assert!(output.path().join("first").is_dir());
+        assert!(output.path().join("second").is_dir());
+
+        let saved: BatchIndex = serde_json::from_str(
+            &fs::read_to_string(output.path().join(BATCH_INDEX_FILE)).unwrap()
+        ).unwrap();
+        assert_eq!(saved.clusters[1].name, "second");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_scans_overlap() {
+        let output = TempDir::new().unwrap();
+        let inventory = Inventory {
+            clusters: ["a", "b", "c"].iter()
+                .map(|name| ClusterTarget { name: name.to_string(), bastion: None, broker: None })
+                .collect(),
+        };
+        let running = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let most_running = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let (now, most) = (running.clone(), most_running.clone());
+        let index = BatchScanner::new(inventory, output.path().to_path_buf())
+            .with_concurrency(2)
+            .run_with(move |job| {
+                let (now, most) = (now.clone(), most.clone());
+                async move {
+                    use std::sync::atomic::Ordering;
+                    let current = now.fetch_add(1, Ordering::SeqCst) + 1;
+                    most.fetch_max(current, Ordering::SeqCst);
+                    // Blocks its thread the way an SSH command does
+                    std::thread::sleep(std::time::Duration::from_millis(200));
+                    now.fetch_sub(1, Ordering::SeqCst);
+                    Ok(ScanMetadata {
+                        scan_timestamp: Utc::now().to_rfc3339(),
+                        bastion: None,
+                        is_local: true,
+                        broker_count: 1,
+                        output_directory: job.output_dir.display().to_string(),
+                        scan_version: "1.0".to_string(),
+                        accessible_brokers: 1,
+                        cluster_mode: None,
+                        partial: false,
+                        environment: None,
+                        cluster_id: None,
+                    })
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(most_running.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert!(index.clusters.iter().all(|c| c.status == BatchScanStatus::Completed));
+        let names: Vec<&str> = index.clusters.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_batch_skips_clusters() {
+        let output = TempDir::new().unwrap();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let index = BatchScanner::new(inventory(INVENTORY).unwrap(), output.path().to_path_buf())
+            .with_cancellation(cancel)
+            .run()
+            .await
+            .unwrap();
+
+        assert!(index.clusters.iter().all(|c| c.status == BatchScanStatus::Skipped));
+        assert!(output.path().join(BATCH_INDEX_FILE).exists());
+    }
+}
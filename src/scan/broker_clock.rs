@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// File under a broker's `system` directory the broker's clock reading is saved to
+pub const BROKER_CLOCK_FILE: &str = "broker_clock.json";
+
+/// Command printing the broker's time in milliseconds since the epoch
+pub const CLOCK_COMMAND: &str = "date +%s%3N";
+
+/// A broker's clock, read over SSH between two readings of the scan host's clock
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BrokerClock {
+    /// Broker time in milliseconds since the epoch; `None` when `date` printed nothing usable
+    #[serde(default)]
+    pub broker_time_ms: Option<i64>,
+    /// Scan host time when the command was sent
+    pub sent_at: DateTime<Utc>,
+    /// Scan host time when its output came back
+    pub received_at: DateTime<Utc>,
+}
+
+impl BrokerClock {
+    pub fn round_trip_ms(&self) -> i64 {
+        (self.received_at - self.sent_at).num_milliseconds().max(0)
+    }
+
+    /// How far the broker's clock is ahead of the scan host's, taking the broker's reading
+    /// to fall in the middle of the round trip
+    pub fn offset_ms(&self) -> Option<i64> {
+        let midpoint = self.sent_at.timestamp_millis() + self.round_trip_ms() / 2;
+        self.broker_time_ms.map(|broker_time| broker_time - midpoint)
+    }
+
+    /// The broker read its clock somewhere within the round trip, so the offset is off by
+    /// at most half of it
+    pub fn uncertainty_ms(&self) -> i64 {
+        (self.round_trip_ms() + 1) / 2
+    }
+}
+
+/// Milliseconds since the epoch from `date +%s%3N` output. A `date` without `%N` support
+/// prints the format characters, or seconds only, which are rejected.
+pub fn parse_epoch_millis(output: &str) -> Option<i64> {
+    let line = output.lines().map(str::trim).find(|line| !line.is_empty())?;
+    line.parse::<i64>().ok().filter(|millis| *millis >= 100_000_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_epoch_millis() {
+        assert_eq!(parse_epoch_millis("1728900000123\n"), Some(1_728_900_000_123));
+        // date without %N support
+        assert_eq!(parse_epoch_millis("1728900000%3N\n"), None);
+        assert_eq!(parse_epoch_millis("1728900000\n"), None);
+    }
+}
@@ -1,17 +1,34 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use super::{BrokerData, BrokerInfo};
-use super::enhanced_log_discovery::EnhancedLogDiscovery;
+use super::{scan_command, BrokerData, BrokerInfo};
+use super::bastion::bastion_ssh_args;
+use super::collector::{CollectorSelection, ScanCollector};
+use super::manifest::{parse_sha256sum, ManifestRecorder, ManifestWriter, PreviousScan};
+use super::enhanced_log_discovery::{EnhancedLogDiscovery, LogCollectionBudget};
+use super::peer_latency::{self, PeerLatency};
+use super::broker_clock::{parse_epoch_millis, BrokerClock, BROKER_CLOCK_FILE, CLOCK_COMMAND};
+use super::connections::{CONNECTIONS_COMMAND, CONNECTIONS_FILE};
+use super::file_descriptors::{limits_command, open_fds_command, parse_open_fds, FileDescriptorUsage, FILE_DESCRIPTORS_FILE};
+use super::process_start::{parse_process_start, process_start_command, ProcessStart, PROCESS_START_FILE};
+use super::tls_certificates::{certificate_command, certificate_stores, CertificateCheck, TLS_CERTIFICATES_FILE};
+use crate::scan::cluster_detection::parse_server_properties;
 
 /// Collector for individual broker data
 pub struct BrokerCollector {
     bastion_alias: Option<String>,  // None means we're running locally on the bastion
     broker: BrokerInfo,
     output_dir: PathBuf,
+    peers: Vec<BrokerInfo>,
+    log_since: Option<DateTime<Utc>>,
+    log_budget: LogCollectionBudget,
+    collectors: CollectorSelection,
+    files: ManifestWriter,
+    previous_scan: Option<PreviousScan>,
 }
 
 impl BrokerCollector {
@@ -20,40 +37,101 @@ impl BrokerCollector {
             bastion_alias,
             broker,
             output_dir,
+            peers: Vec::new(),
+            log_since: None,
+            log_budget: LogCollectionBudget::default(),
+            collectors: CollectorSelection::all(),
+            files: ManifestWriter::default(),
+            previous_scan: None,
         }
     }
+
+    /// Record the collected files in the scan manifest, as coming from this broker
+    pub fn with_manifest(mut self, manifest: &ManifestRecorder) -> Self {
+        self.files = manifest.writer("broker", Some(self.broker.id));
+        self
+    }
+
+    /// Copy config files forward from `previous` instead of fetching them when they are unchanged
+    pub fn with_previous_scan(mut self, previous: Option<PreviousScan>) -> Self {
+        self.previous_scan = previous;
+        self
+    }
+
+    /// Set the other brokers of the cluster to measure network latency to
+    pub fn with_peers(mut self, peers: Vec<BrokerInfo>) -> Self {
+        self.peers = peers;
+        self
+    }
+
+    /// Only collect log entries written at or after `since`
+    pub fn with_log_since(mut self, since: Option<DateTime<Utc>>) -> Self {
+        self.log_since = since;
+        self
+    }
+
+    /// Bound the lines of each log and the bytes of all logs collected from the broker
+    pub fn with_log_budget(mut self, budget: LogCollectionBudget) -> Self {
+        self.log_budget = budget;
+        self
+    }
+
+    /// Only run the broker collectors in `collectors`
+    pub fn with_collectors(mut self, collectors: CollectorSelection) -> Self {
+        self.collectors = collectors;
+        self
+    }
     
-    /// Execute command on broker through bastion (using agent forwarding)
-    fn run_on_broker(&self, command: &str) -> Result<String> {
+    /// Command that runs `command` on the broker, through the bastion when there is one
+    fn broker_command(&self, command: &str) -> Command {
         let ssh_command = format!(
             "ssh -o StrictHostKeyChecking=no {} '{}'",
             self.broker.hostname,
             command
         );
         
-        let output = match &self.bastion_alias {
+        match &self.bastion_alias {
             Some(alias) => {
-                // Remote bastion: SSH to bastion, then SSH to broker
-                Command::new("ssh")
-                    .arg("-A")  // Agent forwarding
-                    .arg(alias)
-                    .arg(ssh_command)
-                    .output()
-                    .context(format!("Failed to execute on broker {} via bastion: {}", self.broker.id, command))?
+                // Remote bastion: SSH to bastion (through any jump hosts), then SSH to broker
+                let mut ssh = scan_command("ssh");
+                ssh.arg("-A")  // Agent forwarding
+                    .args(bastion_ssh_args(alias))
+                    .arg(ssh_command);
+                ssh
             }
             None => {
                 // Local bastion: SSH directly to broker
-                Command::new("sh")
-                    .arg("-c")
-                    .arg(&ssh_command)
-                    .output()
-                    .context(format!("Failed to execute on broker {}: {}", self.broker.id, command))?
+                let mut sh = scan_command("sh");
+                sh.arg("-c").arg(ssh_command);
+                sh
             }
-        };
+        }
+    }
+
+    /// Execute command on broker through bastion (using agent forwarding)
+    fn run_on_broker(&self, command: &str) -> Result<String> {
+        let output = self.broker_command(command)
+            .output()
+            .context(format!("Failed to execute on broker {}: {}", self.broker.id, command))?;
         
         Ok(String::from_utf8_lossy(&output.stdout).into_owned())
     }
 
+    /// Contents of the config file at `path`, saved as `configs/<filename>`. With a previous scan,
+    /// the file is hashed on the broker first and the earlier copy is reused when it is unchanged.
+    fn read_config(&self, filename: &str, path: &str, sudo: bool) -> Result<String> {
+        let sudo = if sudo { "sudo " } else { "" };
+        if let Some(previous) = &self.previous_scan {
+            let hash = self.run_on_broker(&format!("{}sha256sum '{}' 2>/dev/null", sudo, path))?;
+            let manifest_path = format!("brokers/broker_{}/configs/{}", self.broker.id, filename);
+            if let Some(contents) = parse_sha256sum(&hash).and_then(|hash| previous.unchanged(&manifest_path, hash)) {
+                banner!("♻️  {} unchanged since the previous scan, copied forward", filename);
+                return Ok(String::from_utf8_lossy(&contents).into_owned());
+            }
+        }
+        self.run_on_broker(&format!("{}cat '{}' 2>/dev/null", sudo, path))
+    }
+
     /// Enhanced config discovery - parse process arguments to get actual runtime config files
     async fn collect_configs_enhanced_discovery(&self, ps_output: &str) -> Result<HashMap<String, (String, String)>> {
         let mut configs = HashMap::new();
@@ -66,7 +144,7 @@ impl BrokerCollector {
         for part in parts.iter() {
             // Look for server.properties file arguments
             if part.ends_with("server.properties") {
-                if let Ok(content) = self.run_on_broker(&format!("sudo cat '{}' 2>/dev/null", part)) {
+                if let Ok(content) = self.read_config("server.properties", part, true) {
                     if !content.is_empty() && !content.contains("No such file") {
                         configs.insert("server.properties".to_string(), (content, part.to_string()));
                     }
@@ -82,7 +160,7 @@ impl BrokerCollector {
                     let log4j_path = &part[eq_pos + 1..];
                     let clean_path = log4j_path.strip_prefix("file:").unwrap_or(log4j_path);
                     
-                    if let Ok(content) = self.run_on_broker(&format!("sudo cat '{}' 2>/dev/null", clean_path)) {
+                    if let Ok(content) = self.read_config("log4j.properties", clean_path, true) {
                         if !content.is_empty() && !content.contains("No such file") {
                             configs.insert("log4j.properties".to_string(), (content, clean_path.to_string()));
                         }
@@ -93,7 +171,7 @@ impl BrokerCollector {
                 if let Some(eq_pos) = part.find('=') {
                     let log4j_path = &part[eq_pos + 1..];
                     
-                    if let Ok(content) = self.run_on_broker(&format!("sudo cat '{}' 2>/dev/null", log4j_path)) {
+                    if let Ok(content) = self.read_config("log4j2.xml", log4j_path, true) {
                         if !content.is_empty() && !content.contains("No such file") {
                             configs.insert("log4j2.xml".to_string(), (content, log4j_path.to_string()));
                         }
@@ -116,7 +194,7 @@ impl BrokerCollector {
                         // Try to get environment file if mentioned in service
                         if let Ok(service_cat_output) = self.run_on_broker(&format!("systemctl cat {} 2>/dev/null", service_name)) {
                             if let Some(env_file_path) = self.extract_environment_file_from_service(&service_cat_output) {
-                                if let Ok(env_content) = self.run_on_broker(&format!("sudo cat '{}' 2>/dev/null", env_file_path)) {
+                                if let Ok(env_content) = self.read_config("kafka.env", &env_file_path, true) {
                                     if !env_content.is_empty() && !env_content.contains("No such file") {
                                         configs.insert("kafka.env".to_string(), (env_content, env_file_path));
                                     }
@@ -173,7 +251,7 @@ impl BrokerCollector {
     
     /// Discover Kafka installation path using systemctl and ps aux methods
     async fn discover_kafka_installation_path(&self) -> Option<String> {
-        println!("  🔍 Discovering Kafka installation path...");
+        banner!("  🔍 Discovering Kafka installation path...");
         
         // Method 1: Try systemctl approach first
         if let Ok(ps_output) = self.run_on_broker("ps aux | grep -E 'kafka\\.Kafka[^a-zA-Z]' | grep -v grep") {
@@ -192,7 +270,7 @@ impl BrokerCollector {
                                                 let before_script = &exec_start[..start_pos];
                                                 if let Some(bin_pos) = before_script.rfind("/bin/") {
                                                     let kafka_path = &before_script[..bin_pos + 4];
-                                                    println!("    ✅ Found Kafka path via systemctl: {}", kafka_path);
+                                                    banner!("    ✅ Found Kafka path via systemctl: {}", kafka_path);
                                                     return Some(kafka_path.to_string());
                                                 }
                                             }
@@ -222,7 +300,7 @@ impl BrokerCollector {
                             if let Some(libs_pos) = first_jar.rfind("/libs/") {
                                 let kafka_base = &first_jar[..libs_pos];
                                 let kafka_path = format!("{}/bin", kafka_base);
-                                println!("    ✅ Found Kafka path via ps aux classpath: {}", kafka_path);
+                                banner!("    ✅ Found Kafka path via ps aux classpath: {}", kafka_path);
                                 return Some(kafka_path);
                             }
                         }
@@ -237,7 +315,7 @@ impl BrokerCollector {
                     if part.contains("kafka-server-start") && part.contains("/bin/") {
                         if let Some(bin_pos) = part.rfind("/bin/") {
                             let kafka_path = &part[..bin_pos + 4];
-                            println!("    ✅ Found Kafka path via ps aux script: {}", kafka_path);
+                            banner!("    ✅ Found Kafka path via ps aux script: {}", kafka_path);
                             return Some(kafka_path.to_string());
                         }
                     }
@@ -253,14 +331,14 @@ impl BrokerCollector {
                         // Common pattern: /opt/kafka/bin/../logs -> /opt/kafka/bin
                         if logs_dir.contains("/../logs") {
                             let kafka_path = logs_dir.replace("/../logs", "");
-                            println!("    ✅ Found Kafka path via logs directory: {}", kafka_path);
+                            banner!("    ✅ Found Kafka path via logs directory: {}", kafka_path);
                             return Some(kafka_path);
                         }
                         // Alternative: /opt/kafka/logs -> /opt/kafka/bin
                         if logs_dir.contains("/logs") {
                             let base_path = logs_dir.trim_end_matches("/logs");
                             let kafka_path = format!("{}/bin", base_path);
-                            println!("    ✅ Found Kafka path via logs directory (alt): {}", kafka_path);
+                            banner!("    ✅ Found Kafka path via logs directory (alt): {}", kafka_path);
                             return Some(kafka_path);
                         }
                     }
@@ -268,10 +346,27 @@ impl BrokerCollector {
             }
         }
         
-        println!("    ⚠️  Could not discover Kafka installation path");
+        banner!("    ⚠️  Could not discover Kafka installation path");
         None
     }
     
+    /// Ping a peer broker from this broker
+    fn measure_peer_latency(&self, peer: &BrokerInfo) -> PeerLatency {
+        match self.run_on_broker(&peer_latency::ping_command(&peer.hostname)) {
+            Ok(output) => PeerLatency::from_ping_output(peer.id, &peer.hostname, &output),
+            Err(e) => PeerLatency {
+                peer_id: peer.id,
+                peer_hostname: peer.hostname.clone(),
+                reachable: false,
+                packet_loss_percent: None,
+                rtt_min_ms: None,
+                rtt_avg_ms: None,
+                rtt_max_ms: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
     /// Collect all data from this broker
     pub async fn collect_all(&self) -> Result<BrokerData> {
         let broker_dir = self.output_dir.join("brokers").join(format!("broker_{}", self.broker.id));
@@ -283,6 +378,7 @@ impl BrokerCollector {
         fs::create_dir_all(broker_dir.join("metrics"))?;
         fs::create_dir_all(broker_dir.join("system"))?;
         fs::create_dir_all(broker_dir.join("data"))?;
+        fs::create_dir_all(broker_dir.join("network"))?;
         
         // Save broker info
         let broker_info_json = serde_json::json!({
@@ -290,7 +386,7 @@ impl BrokerCollector {
             "hostname": self.broker.hostname,
             "collection_timestamp": chrono::Utc::now().to_rfc3339(),
         });
-        fs::write(
+        self.files.write(
             broker_dir.join("broker_info.json"),
             serde_json::to_string_pretty(&broker_info_json)?,
         )?;
@@ -300,21 +396,71 @@ impl BrokerCollector {
         
         // Store the discovered path for later use
         if let Some(ref path) = kafka_installation_path {
-            fs::write(
+            self.files.write(
                 broker_dir.join("system").join("kafka_installation_path.txt"),
                 path,
             )?;
         }
 
-        // 2. Collect system information
-        print!("  📊 System info... ");
         let mut system_info = HashMap::new();
-        
-        // Add kafka installation path to system info if discovered
         if let Some(ref path) = kafka_installation_path {
             system_info.insert("kafka_installation_path".to_string(), path.clone());
         }
         
+        // 2. System and JVM information
+        if self.collectors.includes(ScanCollector::System) {
+            system_info.extend(self.collect_system_info(&broker_dir)?);
+        }
+        
+        // 3. Configuration files
+        let configs = if self.collectors.includes(ScanCollector::Configs) {
+            self.collect_configs(&broker_dir).await?
+        } else {
+            HashMap::new()
+        };
+        
+        // TLS certificate expiry of the keystores and truststores the configuration names
+        if let Some(server_properties) = configs.get("server.properties") {
+            self.collect_tls_certificates(&broker_dir, server_properties)?;
+        }
+        
+        // 4. Log files
+        let logs = if self.collectors.includes(ScanCollector::Logs) {
+            self.collect_logs(&broker_dir).await?
+        } else {
+            HashMap::new()
+        };
+        
+        // 5. Data directories
+        let data_dirs = if self.collectors.includes(ScanCollector::Data) {
+            self.collect_data_dirs(&broker_dir)?
+        } else {
+            Vec::new()
+        };
+        
+        // 6. Listening sockets and peer latency
+        if self.collectors.includes(ScanCollector::Network) {
+            if let Some(network) = self.collect_network(&broker_dir)? {
+                system_info.insert("network".to_string(), network);
+            }
+        }
+        
+        Ok(BrokerData {
+            broker_id: self.broker.id,
+            hostname: self.broker.hostname.clone(),
+            accessible: true,
+            system_info,
+            configs,
+            logs,
+            data_dirs,
+        })
+    }
+
+    /// Host and JVM information of the broker
+    fn collect_system_info(&self, broker_dir: &Path) -> Result<HashMap<String, String>> {
+        banner_step!("  📊 System info... ");
+        let mut system_info = HashMap::new();
+        
         let system_commands = vec![
             ("hostname", "hostname -f"),
             ("uptime", "uptime"),
@@ -328,21 +474,22 @@ impl BrokerCollector {
         
         for (name, cmd) in system_commands {
             if let Ok(output) = self.run_on_broker(cmd) {
-                fs::write(broker_dir.join("system").join(format!("{}.txt", name)), &output)?;
+                self.files.write(broker_dir.join("system").join(format!("{}.txt", name)), &output)?;
                 system_info.insert(name.to_string(), output);
             }
         }
-        println!("✓");
+        self.collect_clock(broker_dir)?;
+        banner!("✓");
         
-        // 3. Java/JVM information
-        print!("  ☕ Java/JVM info... ");
+        // Java/JVM information
+        banner_step!("  ☕ Java/JVM info... ");
         if let Ok(java_version) = self.run_on_broker("java -version 2>&1") {
-            fs::write(broker_dir.join("system").join("java_version.txt"), &java_version)?;
+            self.files.write(broker_dir.join("system").join("java_version.txt"), &java_version)?;
             system_info.insert("java_version".to_string(), java_version);
         }
         
         if let Ok(kafka_process) = self.run_on_broker("ps aux | grep -E 'kafka\\.Kafka' | grep -v grep") {
-            fs::write(broker_dir.join("system").join("kafka_process.txt"), &kafka_process)?;
+            self.files.write(broker_dir.join("system").join("kafka_process.txt"), &kafka_process)?;
             system_info.insert("kafka_process".to_string(), kafka_process);
         }
         
@@ -351,14 +498,73 @@ impl BrokerCollector {
             let pid = kafka_pid.trim();
             if !pid.is_empty() {
                 if let Ok(jstat) = self.run_on_broker(&format!("jstat -gc {} 2>/dev/null", pid)) {
-                    fs::write(broker_dir.join("metrics").join("jstat_gc.txt"), &jstat)?;
+                    self.files.write(broker_dir.join("metrics").join("jstat_gc.txt"), &jstat)?;
+                }
+                if let Ok(pid) = pid.parse() {
+                    self.collect_file_descriptors(broker_dir, pid)?;
+                    self.collect_process_start(broker_dir, pid)?;
                 }
             }
         }
-        println!("✓");
+        banner!("✓");
         
-        // 4. Configuration files - Using enhanced discovery first, fallback to find
-        print!("  📝 Configuration files (enhanced discovery)... ");
+        Ok(system_info)
+    }
+
+    /// Open descriptors and open files limit of the Kafka process. Entries of /proc the SSH user
+    /// may not read are recorded as missing rather than failing the collection.
+    fn collect_file_descriptors(&self, broker_dir: &Path, pid: u32) -> Result<()> {
+        let usage = FileDescriptorUsage {
+            pid,
+            open_fds: self.run_on_broker(&open_fds_command(pid))
+                .ok()
+                .and_then(|output| parse_open_fds(&output)),
+            limits: self.run_on_broker(&limits_command(pid)).unwrap_or_default(),
+        };
+        self.files.write(
+            broker_dir.join("system").join(FILE_DESCRIPTORS_FILE),
+            serde_json::to_string_pretty(&usage)?,
+        )?;
+        Ok(())
+    }
+
+    /// Start time of the Kafka process, telling how long the broker has been up
+    fn collect_process_start(&self, broker_dir: &Path, pid: u32) -> Result<()> {
+        let collected_at = Utc::now();
+        let start = ProcessStart {
+            pid,
+            started_at: self.run_on_broker(&process_start_command(pid))
+                .ok()
+                .and_then(|output| parse_process_start(&output, collected_at)),
+            collected_at,
+        };
+        self.files.write(
+            broker_dir.join("system").join(PROCESS_START_FILE),
+            serde_json::to_string_pretty(&start)?,
+        )?;
+        Ok(())
+    }
+
+    /// The broker's clock, read between two readings of the local clock so the clock skew
+    /// analysis can allow for the SSH round trip
+    fn collect_clock(&self, broker_dir: &Path) -> Result<()> {
+        let sent_at = Utc::now();
+        let output = self.run_on_broker(CLOCK_COMMAND);
+        let clock = BrokerClock {
+            broker_time_ms: output.ok().and_then(|output| parse_epoch_millis(&output)),
+            sent_at,
+            received_at: Utc::now(),
+        };
+        self.files.write(
+            broker_dir.join("system").join(BROKER_CLOCK_FILE),
+            serde_json::to_string_pretty(&clock)?,
+        )?;
+        Ok(())
+    }
+
+    /// Configuration files, found with enhanced discovery first and falling back to find
+    async fn collect_configs(&self, broker_dir: &Path) -> Result<HashMap<String, String>> {
+        banner_step!("  📝 Configuration files (enhanced discovery)... ");
         let mut configs = HashMap::new();
         
         // Try enhanced discovery first - parse Kafka process for actual runtime config paths
@@ -371,7 +577,7 @@ impl BrokerCollector {
             
             if let Ok(enhanced_configs) = enhanced_configs {
                 for (filename, (content, source)) in enhanced_configs {
-                    fs::write(broker_dir.join("configs").join(&filename), &content)?;
+                    self.files.write(broker_dir.join("configs").join(&filename), &content)?;
                     configs.insert(filename.clone(), content.clone());
                     configs.insert(format!("{}_source", filename.replace('.', "_")), format!("enhanced:{}", source));
                     
@@ -384,14 +590,14 @@ impl BrokerCollector {
                 }
                 
                 if !configs.is_empty() {
-                    println!("✅ Enhanced discovery found {} config files", configs.len());
+                    banner!("✅ Enhanced discovery found {} config files", configs.len());
                 }
             }
         }
         
         // Fallback: Use find command for any missing config files
         if !server_props_found {
-            println!("⚠️  Enhanced discovery failed for server.properties, falling back to find");
+            banner!("⚠️  Enhanced discovery failed for server.properties, falling back to find");
             let find_commands = vec![
                 "find /etc -name 'server.properties' 2>/dev/null | head -1",
                 "find /opt -name 'server.properties' 2>/dev/null | head -1", 
@@ -403,13 +609,13 @@ impl BrokerCollector {
                 if let Ok(output) = self.run_on_broker(find_cmd) {
                     let config_path = output.trim();
                     if !config_path.is_empty() {
-                        if let Ok(content) = self.run_on_broker(&format!("sudo cat '{}' 2>/dev/null", config_path)) {
+                        if let Ok(content) = self.read_config("server.properties", config_path, true) {
                             if !content.is_empty() && !content.contains("No such file") {
-                                fs::write(broker_dir.join("configs").join("server.properties"), &content)?;
+                                self.files.write(broker_dir.join("configs").join("server.properties"), &content)?;
                                 configs.insert("server.properties".to_string(), content.clone());
                                 configs.insert("server_properties_source".to_string(), format!("fallback_find:{}", config_path));
                                 server_props_found = true;
-                                println!("✅ Found server.properties via find fallback: {}", config_path);
+                                banner!("✅ Found server.properties via find fallback: {}", config_path);
                                 
                                 break;
                             }
@@ -430,9 +636,9 @@ impl BrokerCollector {
             ];
             
             for path in standard_paths {
-                if let Ok(content) = self.run_on_broker(&format!("cat {} 2>/dev/null", path)) {
+                if let Ok(content) = self.read_config("server.properties", path, false) {
                     if !content.is_empty() && !content.contains("No such file") {
-                        fs::write(broker_dir.join("configs").join("server.properties"), &content)?;
+                        self.files.write(broker_dir.join("configs").join("server.properties"), &content)?;
                         configs.insert("server.properties".to_string(), content.clone());
                         configs.insert("config_source".to_string(), format!("standard:{}", path));
                         
@@ -444,7 +650,7 @@ impl BrokerCollector {
         
         // Fallback for log4j configuration if enhanced discovery missed it
         if !log4j_found {
-            println!("⚠️  Enhanced discovery failed for log4j.properties, falling back to find");
+            banner!("⚠️  Enhanced discovery failed for log4j.properties, falling back to find");
             let log4j_locations = vec![
                 "find /etc -name 'log4j*.properties' 2>/dev/null | head -1",
                 "find /opt -name 'log4j*.properties' 2>/dev/null | head -1",
@@ -454,13 +660,13 @@ impl BrokerCollector {
                 if let Ok(output) = self.run_on_broker(cmd) {
                     let log4j_path = output.trim();
                     if !log4j_path.is_empty() && log4j_path != "log4j*.properties" {
-                        if let Ok(content) = self.run_on_broker(&format!("sudo cat '{}' 2>/dev/null", log4j_path)) {
+                        if let Ok(content) = self.read_config("log4j.properties", log4j_path, true) {
                             if !content.is_empty() && !content.contains("No such file") {
-                                fs::write(broker_dir.join("configs").join("log4j.properties"), &content)?;
+                                self.files.write(broker_dir.join("configs").join("log4j.properties"), &content)?;
                                 configs.insert("log4j.properties".to_string(), content);
                                 configs.insert("log4j_properties_source".to_string(), format!("fallback_find:{}", log4j_path));
                                 log4j_found = true;
-                                println!("✅ Found log4j.properties via find fallback: {}", log4j_path);
+                                banner!("✅ Found log4j.properties via find fallback: {}", log4j_path);
                                 break;
                             }
                         }
@@ -471,7 +677,7 @@ impl BrokerCollector {
         
         // Fallback for systemd service file if enhanced discovery missed it
         if !service_found {
-            println!("⚠️  Enhanced discovery failed for kafka.service, falling back to standard paths");
+            banner!("⚠️  Enhanced discovery failed for kafka.service, falling back to standard paths");
             let service_names = vec!["kafka", "kafka.service", "confluent-kafka", "apache-kafka"];
             for service_name in service_names {
                 let service_paths = vec![
@@ -481,13 +687,13 @@ impl BrokerCollector {
                 ];
                 
                 for service_path in service_paths {
-                    if let Ok(content) = self.run_on_broker(&format!("sudo cat '{}' 2>/dev/null", service_path)) {
+                    if let Ok(content) = self.read_config("kafka.service", &service_path, true) {
                         if !content.is_empty() && !content.contains("No such file") {
-                            fs::write(broker_dir.join("configs").join("kafka.service"), &content)?;
+                            self.files.write(broker_dir.join("configs").join("kafka.service"), &content)?;
                             configs.insert("kafka.service".to_string(), content);
                             configs.insert("kafka_service_source".to_string(), format!("fallback_standard:{}", service_path));
                             service_found = true;
-                            println!("✅ Found kafka.service via standard paths: {}", service_path);
+                            banner!("✅ Found kafka.service via standard paths: {}", service_path);
                             break;
                         }
                     }
@@ -500,22 +706,26 @@ impl BrokerCollector {
         
         // Log summary of configuration discovery results
         if log4j_found {
-            println!("ℹ️  Log4j configuration found and collected");
+            banner!("ℹ️  Log4j configuration found and collected");
         }
         
         let enhanced_count = configs.values().filter(|v| v.contains("enhanced:")).count();
         let fallback_count = configs.len() - enhanced_count;
         
         if enhanced_count > 0 && fallback_count > 0 {
-            println!("✓ (found {} config files: {} enhanced, {} fallback)", configs.len(), enhanced_count, fallback_count);
+            banner!("✓ (found {} config files: {} enhanced, {} fallback)", configs.len(), enhanced_count, fallback_count);
         } else if enhanced_count > 0 {
-            println!("✓ (found {} config files via enhanced discovery)", configs.len());
+            banner!("✓ (found {} config files via enhanced discovery)", configs.len());
         } else {
-            println!("✓ (found {} config files via fallback methods)", configs.len());
+            banner!("✓ (found {} config files via fallback methods)", configs.len());
         }
         
-        // 5. Log files - Using enhanced discovery (process → systemd → config → logs)
-        print!("  📜 Log files (enhanced discovery)... ");
+        Ok(configs)
+    }
+
+    /// Log files, found with enhanced discovery (process → systemd → config → logs)
+    async fn collect_logs(&self, broker_dir: &Path) -> Result<HashMap<String, String>> {
+        banner_step!("  📜 Log files (enhanced discovery)... ");
         let mut logs = HashMap::new();
         
         // Initialize enhanced log discovery with the current SSH setup
@@ -525,14 +735,16 @@ impl BrokerCollector {
             Some(self.broker.hostname.clone())
         };
         
-        let enhanced_discovery = EnhancedLogDiscovery::new(ssh_target.clone());
+        let enhanced_discovery = EnhancedLogDiscovery::new(ssh_target.clone())
+            .with_since(self.log_since)
+            .with_budget(self.log_budget.clone());
         
         // Run the enhanced discovery chain
         match enhanced_discovery.discover_logs().await {
             Ok(discovery_result) => {
                 // Save comprehensive discovery metadata
                 let discovery_json = serde_json::to_string_pretty(&discovery_result)?;
-                fs::write(broker_dir.join("logs").join("enhanced_discovery_metadata.json"), discovery_json)?;
+                self.files.write(broker_dir.join("logs").join("enhanced_discovery_metadata.json"), discovery_json)?;
                 
                 // Use discovered logs
                 logs = discovery_result.discovered_logs.clone();
@@ -553,15 +765,16 @@ impl BrokerCollector {
                             safe_name
                         };
                         
-                        fs::write(broker_dir.join("logs").join(&final_name), log_content)?;
+                        self.files.write(broker_dir.join("logs").join(&final_name), log_content)?;
                     }
                 }
                 
-                if logs.is_empty() {
+                // A GC log alone does not replace the broker's own logs
+                if logs.keys().all(|name| name.starts_with("gc_")) {
                     // Ultimate fallback to basic journald
                     if let Ok(content) = self.run_on_broker("journalctl -n 500 --no-pager 2>/dev/null | grep -i kafka") {
                         if !content.is_empty() {
-                            fs::write(broker_dir.join("logs").join("system_journald.log"), &content)?;
+                            self.files.write(broker_dir.join("logs").join("system_journald.log"), &content)?;
                             logs.insert("system_journald_fallback".to_string(), content);
                         }
                     }
@@ -571,13 +784,13 @@ impl BrokerCollector {
                 let steps = discovery_result.discovery_steps.len();
                 let warnings = discovery_result.warnings.len();
                 if warnings > 0 {
-                    println!("✓ ({} logs, {} steps, {} warnings)", logs.len(), steps, warnings);
+                    banner!("✓ ({} logs, {} steps, {} warnings)", logs.len(), steps, warnings);
                 } else {
-                    println!("✓ ({} logs, {} discovery steps)", logs.len(), steps);
+                    banner!("✓ ({} logs, {} discovery steps)", logs.len(), steps);
                 }
             }
             Err(e) => {
-                println!("⚠️ (enhanced discovery failed: {}, using fallback)", e);
+                banner!("⚠️ (enhanced discovery failed: {}, using fallback)", e);
                 
                 // Direct fallback to hardcoded paths
                 let hardcoded_commands = vec![
@@ -590,7 +803,7 @@ impl BrokerCollector {
                 for (name, cmd) in hardcoded_commands {
                     if let Ok(content) = self.run_on_broker(cmd) {
                         if !content.is_empty() {
-                            fs::write(broker_dir.join("logs").join(name), &content)?;
+                            self.files.write(broker_dir.join("logs").join(name), &content)?;
                             logs.insert(format!("fallback_{}", name), content);
                         }
                     }
@@ -600,7 +813,7 @@ impl BrokerCollector {
                 if logs.is_empty() {
                     if let Ok(content) = self.run_on_broker("journalctl -n 500 --no-pager 2>/dev/null | grep -i kafka") {
                         if !content.is_empty() {
-                            fs::write(broker_dir.join("logs").join("system_kafka_logs.log"), &content)?;
+                            self.files.write(broker_dir.join("logs").join("system_kafka_logs.log"), &content)?;
                             logs.insert("system_kafka_logs".to_string(), content);
                         }
                     }
@@ -608,8 +821,12 @@ impl BrokerCollector {
             }
         }
         
-        // 6. Data directories
-        print!("  💾 Data directories... ");
+        Ok(logs)
+    }
+
+    /// Directories in log.dirs, with their sizes
+    fn collect_data_dirs(&self, broker_dir: &Path) -> Result<Vec<String>> {
+        banner_step!("  💾 Data directories... ");
         let mut data_dirs = Vec::new();
         
         if let Ok(log_dirs_config) = self.run_on_broker(
@@ -617,7 +834,7 @@ impl BrokerCollector {
         ) {
             let log_dirs = log_dirs_config.trim();
             if !log_dirs.is_empty() {
-                fs::write(broker_dir.join("data").join("log_dirs.txt"), log_dirs)?;
+                self.files.write(broker_dir.join("data").join("log_dirs.txt"), log_dirs)?;
                 
                 // Get size of each directory
                 let mut dir_sizes = String::new();
@@ -630,30 +847,106 @@ impl BrokerCollector {
                 }
                 
                 if !dir_sizes.is_empty() {
-                    fs::write(broker_dir.join("data").join("directory_sizes.txt"), dir_sizes)?;
+                    self.files.write(broker_dir.join("data").join("directory_sizes.txt"), dir_sizes)?;
                 }
             }
         }
-        println!("✓");
+        banner!("✓");
         
-        // 7. Network information
-        print!("  🌐 Network info... ");
+        Ok(data_dirs)
+    }
+
+    /// Expiry date and subject of the first certificate of each keystore and truststore in
+    /// server.properties. Brokers without TLS stores get no file.
+    fn collect_tls_certificates(&self, broker_dir: &Path, server_properties: &str) -> Result<()> {
+        let stores = certificate_stores(&parse_server_properties(server_properties));
+        if stores.is_empty() {
+            return Ok(());
+        }
+
+        banner_step!("  🔐 TLS certificates ({} stores)... ", stores.len());
+        let checks: Vec<CertificateCheck> = stores.into_iter()
+            .map(|(setting, path, store_type)| CertificateCheck {
+                output: self.run_on_broker(&certificate_command(&path, &store_type)).unwrap_or_default(),
+                setting,
+                path,
+            })
+            .collect();
+        self.files.write(
+            broker_dir.join("system").join(TLS_CERTIFICATES_FILE),
+            serde_json::to_string_pretty(&checks)?,
+        )?;
+
+        let unreadable = checks.iter().filter(|check| check.output.trim().is_empty()).count();
+        if unreadable == 0 {
+            banner!("✓");
+        } else {
+            banner!("⚠️  {} unreadable", unreadable);
+        }
+        Ok(())
+    }
+
+    /// Listening sockets and latency to peer brokers, returning the sockets
+    fn collect_network(&self, broker_dir: &Path) -> Result<Option<String>> {
+        banner_step!("  🌐 Network info... ");
+        let mut listening = None;
         if let Ok(network) = self.run_on_broker(
             "netstat -tuln 2>/dev/null | grep -E '9092|9093|9094' || ss -tuln | grep -E '9092|9093|9094'"
         ) {
-            fs::write(broker_dir.join("system").join("network.txt"), &network)?;
-            system_info.insert("network".to_string(), network);
+            self.files.write(broker_dir.join("system").join("network.txt"), &network)?;
+            listening = Some(network);
+        }
+        // Established connections, counted per listener by the connection analyzer
+        if let Ok(connections) = self.run_on_broker(CONNECTIONS_COMMAND) {
+            self.files.write(broker_dir.join("system").join(CONNECTIONS_FILE), &connections)?;
+        }
+        banner!("✓");
+
+        // Latency to peer brokers
+        let peers: Vec<&BrokerInfo> = self.peers.iter().filter(|p| p.id != self.broker.id).collect();
+        if !peers.is_empty() {
+            banner_step!("  📶 Peer latency ({} brokers)... ", peers.len());
+            let peer_latency: Vec<PeerLatency> = peers.iter()
+                .map(|peer| self.measure_peer_latency(peer))
+                .collect();
+            let unreachable = peer_latency.iter().filter(|l| !l.reachable).count();
+            self.files.write(
+                broker_dir.join("network").join("peer_latency.json"),
+                serde_json::to_string_pretty(&peer_latency)?,
+            )?;
+            if unreachable == 0 {
+                banner!("✓");
+            } else {
+                banner!("⚠️  {} unreachable", unreachable);
+            }
         }
-        println!("✓");
         
-        Ok(BrokerData {
-            broker_id: self.broker.id,
-            hostname: self.broker.hostname.clone(),
-            accessible: true,
-            system_info,
-            configs,
-            logs,
-            data_dirs,
-        })
+        Ok(listening)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command_args(bastion: Option<&str>) -> Vec<String> {
+        let broker = BrokerInfo { id: 1, hostname: "kafka-1.internal".to_string(), listeners: Vec::new() };
+        let collector = BrokerCollector::new(bastion.map(str::to_string), broker, PathBuf::from("scan"));
+        collector.broker_command("hostname").get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_broker_command_through_bastion_chain() {
+        assert_eq!(
+            command_args(Some("jump-eu,kafka-bastion")),
+            vec!["-A", "-J", "jump-eu", "kafka-bastion", "ssh -o StrictHostKeyChecking=no kafka-1.internal 'hostname'"]
+        );
+        assert_eq!(
+            command_args(Some("kafka-bastion")),
+            vec!["-A", "kafka-bastion", "ssh -o StrictHostKeyChecking=no kafka-1.internal 'hostname'"]
+        );
+        assert_eq!(command_args(None), vec!["-c", "ssh -o StrictHostKeyChecking=no kafka-1.internal 'hostname'"]);
+    }
+}
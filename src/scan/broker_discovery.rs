@@ -1,10 +1,73 @@
 use anyhow::Result;
-use tracing::{debug, info};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::time::Duration;
+use tracing::{debug, info, warn};
 
-use crate::scan::types::BrokerInfo;
-use crate::scan::bastion::run_command_on_bastion;
-use crate::collectors::admin::AdminCollector;
-use crate::collectors::{KafkaConfig, Collector};
+use crate::scan::types::{BrokerInfo, ListenerInfo};
+use crate::scan::bastion::{is_transport_error, probe_output, run_command_on_bastion};
+use crate::scan::listeners::{format_address, split_host_port, DEFAULT_BROKER_PORT};
+use crate::scan::manifest::ManifestRecorder;
+use crate::collectors::admin::{AdminCollector, AdminCollectorOutput};
+use crate::collectors::{CollectionContext, CollectorError, CollectorRegistry, CollectorResult, KafkaConfig, RedactionLevel};
+use crate::snapshot::format::RedactionReport;
+
+/// How often a broker discovery method is retried before falling through to the next one
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total attempts per method, including the first
+    pub attempts: u32,
+    /// Wait before the second attempt, doubled for each further attempt
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            initial_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Try each method once
+    pub fn none() -> Self {
+        Self {
+            attempts: 1,
+            initial_backoff: Duration::ZERO,
+        }
+    }
+}
+
+/// Run a discovery method until it succeeds or `policy.attempts` is used up,
+/// returning the last error. An empty broker list counts as success. Only transport
+/// failures (ssh exit code 255, timeouts) are retried; any other error is returned at once.
+pub async fn discover_with_retry<F, Fut>(policy: RetryPolicy, method: &str, mut discover: F) -> Result<Vec<BrokerInfo>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Vec<BrokerInfo>>>,
+{
+    let attempts = policy.attempts.max(1);
+    let mut backoff = policy.initial_backoff;
+    let mut attempt = 1;
+    loop {
+        info!("Broker discovery using {} (attempt {}/{})", method, attempt, attempts);
+        match discover().await {
+            Ok(brokers) => return Ok(brokers),
+            Err(e) if attempt < attempts && is_transport_error(&e) => {
+                warn!("Broker discovery using {} failed: {}. Retrying in {:?}", method, e, backoff);
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            Err(e) => {
+                warn!("Broker discovery using {} failed after {} attempt(s): {}", method, attempt, e);
+                return Err(e);
+            }
+        }
+    }
+}
 
 /// Discover brokers from kafkactl when no broker parameter is provided
 pub async fn discover_brokers_from_kafkactl(bastion_alias: Option<&String>) -> Result<Vec<BrokerInfo>> {
@@ -60,6 +123,7 @@ pub fn parse_kafkactl_brokers(yaml_output: &str) -> Result<Vec<BrokerInfo>> {
                     current_broker = Some(BrokerInfo {
                         id,
                         hostname: String::new(),
+                        listeners: Vec::new(),
                     });
                     in_broker_section = true;
                 }
@@ -73,13 +137,8 @@ pub fn parse_kafkactl_brokers(yaml_output: &str) -> Result<Vec<BrokerInfo>> {
                 if line.starts_with("address:") {
                     if let Some(addr_str) = line.strip_prefix("address:") {
                         let address = addr_str.trim().trim_matches('"');
-                        // Extract hostname from hostname:port format
-                        let hostname = if let Some(colon_pos) = address.find(':') {
-                            &address[..colon_pos]
-                        } else {
-                            address
-                        };
-                        broker.hostname = hostname.to_string();
+                        // Extract hostname from hostname:port or [ipv6]:port format
+                        broker.hostname = split_host_port(address).0;
                     }
                 } else if line.starts_with("port:") || line.starts_with("rack:") {
                     // Additional fields we might want to capture later
@@ -105,18 +164,123 @@ pub fn parse_kafkactl_brokers(yaml_output: &str) -> Result<Vec<BrokerInfo>> {
     Ok(valid_brokers)
 }
 
-/// Discover brokers from a single known broker using Kafka admin API (local mode)
-pub async fn discover_brokers_from_single_local(broker_address: &str) -> Result<Vec<BrokerInfo>> {
+/// Name of the listener holding the address a broker was given with in a `--broker-list` file,
+/// until the listeners of its server.properties are collected
+pub const BROKER_LIST_LISTENER: &str = "BROKER_LIST";
+
+/// Parse a `--broker-list` file: one `hostname[:port]` or `[ipv6][:port]` per line, with
+/// `default_port` for lines without one. Blank lines and `#` comments are skipped. Brokers are
+/// numbered 1, 2, ... in file order. Every malformed or repeated entry is reported, with its
+/// line number, in a single error.
+pub fn parse_broker_list(content: &str, default_port: u16) -> Result<Vec<BrokerInfo>> {
+    let mut brokers: Vec<BrokerInfo> = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut malformed = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let entry = line.split('#').next().unwrap_or_default().trim();
+        if entry.is_empty() {
+            continue;
+        }
+
+        let (host, port) = split_host_port(entry);
+        let has_port = match entry.strip_prefix('[') {
+            Some(rest) => rest.contains("]:"),
+            None => entry.matches(':').count() == 1,
+        };
+        if host.is_empty() || host.contains(char::is_whitespace) || (has_port && port.is_none()) || port == Some(0) {
+            malformed.push(format!("line {}: '{}' is not hostname:port", line_number, entry));
+            continue;
+        }
+
+        let port = port.unwrap_or(default_port);
+        let address = format_address(&host, port);
+        if let Some(first) = seen.insert(address.clone(), line_number) {
+            malformed.push(format!("line {}: '{}' repeats line {}", line_number, entry, first));
+            continue;
+        }
+        brokers.push(BrokerInfo {
+            id: brokers.len() as i32 + 1,
+            hostname: host.clone(),
+            listeners: vec![ListenerInfo { name: BROKER_LIST_LISTENER.to_string(), host, port }],
+        });
+    }
+
+    if !malformed.is_empty() {
+        return Err(anyhow::anyhow!("Malformed broker list entries:\n  {}", malformed.join("\n  ")));
+    }
+    if brokers.is_empty() {
+        return Err(anyhow::anyhow!("Broker list has no brokers"));
+    }
+    Ok(brokers)
+}
+
+/// Directory of the scan output holding each collector's output as `<name>.json`
+pub const COLLECTORS_DIR: &str = "collectors";
+
+/// Save the successful outputs of `results`, redacted at `ctx.redaction_level`, to
+/// `cluster/collectors/<name>.json` and record them in `manifest`. Failures are logged.
+pub fn save_collector_outputs(
+    registry: &CollectorRegistry,
+    results: &HashMap<String, CollectorResult<serde_json::Value>>,
+    ctx: &CollectionContext,
+    output_dir: &std::path::Path,
+    manifest: &ManifestRecorder,
+) -> Result<()> {
+    let collectors_dir = output_dir.join("cluster").join(COLLECTORS_DIR);
+    let mut report = RedactionReport::default();
+    let mut names: Vec<&String> = results.keys().collect();
+    names.sort();
+    for name in names {
+        match &results[name] {
+            Ok(output) => {
+                let redacted = registry.redact(name, output.clone(), ctx, &mut report);
+                std::fs::create_dir_all(&collectors_dir)?;
+                manifest.writer(name, None)
+                    .write(collectors_dir.join(format!("{}.json", name)), serde_json::to_string_pretty(&redacted)?)?;
+            }
+            Err(e) => warn!("Collector {} failed: {}", name, e),
+        }
+    }
+    if !report.is_empty() {
+        info!("🔒 Redacted {} value(s) in {} field(s) of collector outputs", report.count, report.fields_redacted.len());
+    }
+    Ok(())
+}
+
+/// Discover brokers from a single known broker using Kafka admin API (local mode).
+/// The admin collector's output is saved, redacted at `redaction_level`, to
+/// `cluster/collectors/admin.json` and recorded in `manifest`.
+pub async fn discover_brokers_from_single_local(
+    broker_address: &str,
+    output_dir: &std::path::Path,
+    redaction_level: RedactionLevel,
+    manifest: &ManifestRecorder,
+) -> Result<Vec<BrokerInfo>> {
     info!("Running local broker discovery");
-    let admin_collector = AdminCollector::new();
-    let kafka_config = KafkaConfig {
-        bootstrap_servers: vec![broker_address.to_string()],
-        timeout_secs: 30,
-        ..Default::default()
+    let ctx = CollectionContext {
+        kafka_config: KafkaConfig {
+            bootstrap_servers: vec![broker_address.to_string()],
+            timeout_secs: 30,
+            ..Default::default()
+        },
+        metadata: HashMap::new(),
+        dry_run: false,
+        redaction_level,
     };
-    
-    // Use the collector to discover all brokers
-    match admin_collector.collect(&kafka_config).await {
+    // Discovery only needs cluster metadata; the optional collectors run later in the scan
+    let mut registry = CollectorRegistry::new();
+    registry.register_admin_collector();
+    let mut results = registry.run_all(&ctx).await;
+    save_collector_outputs(&registry, &results, &ctx, output_dir, manifest)?;
+
+    // Discovery needs the real broker hostnames, so it uses the unredacted admin output
+    let admin_result = results
+        .remove("admin")
+        .unwrap_or_else(|| Err(CollectorError::Other("admin collector not registered".to_string())))
+        .and_then(|output| Ok(serde_json::from_value::<AdminCollectorOutput>(output)?));
+    match admin_result {
         Ok(admin_output) => {
             // Convert AdminBrokerInfo to our BrokerInfo
             let discovered_brokers: Vec<BrokerInfo> = admin_output
@@ -125,6 +289,7 @@ pub async fn discover_brokers_from_single_local(broker_address: &str) -> Result<
                 .map(|b| BrokerInfo {
                     id: b.id,
                     hostname: b.host,
+                    listeners: Vec::new(),
                 })
                 .collect();
             
@@ -135,18 +300,32 @@ pub async fn discover_brokers_from_single_local(broker_address: &str) -> Result<
             
             Ok(discovered_brokers)
         }
-        Err(e) => {
-            Err(anyhow::anyhow!("Broker discovery failed: {}", e))
-        }
+        Err(e) => match AdminCollector::new().check_client_support(&ctx.kafka_config) {
+            // A minimal install without a usable librdkafka can still be scanned over SSH
+            Err(unsupported) => {
+                warn!("⚠️  {}", unsupported);
+                warn!("Continuing with SSH-based collection of {} only; the snapshot will have no admin data", broker_address);
+                let (hostname, _) = split_host_port(broker_address);
+                // The broker id is only known from the admin API
+                Ok(vec![BrokerInfo { id: 1, hostname, listeners: Vec::new() }])
+            }
+            Ok(()) => Err(anyhow::anyhow!("Broker discovery failed: {}", e)),
+        },
     }
 }
 
 /// Discover brokers using Kafka installation path method
-pub async fn discover_brokers_using_installation_path(bastion_alias: Option<&String>, broker_address: &str, output_dir: &std::path::Path) -> Result<Vec<BrokerInfo>> {
+pub async fn discover_brokers_using_installation_path(
+    bastion_alias: Option<&String>,
+    broker_address: &str,
+    output_dir: &std::path::Path,
+    manifest: &ManifestRecorder,
+) -> Result<Vec<BrokerInfo>> {
     info!("🔍 Starting installation path-based broker discovery");
     
     // Step 1: SSH to the given broker and discover Kafka installation path
-    let hostname = broker_address.split(':').next().unwrap_or("unknown");
+    let (hostname, port) = split_host_port(broker_address);
+    let hostname = hostname.as_str();
     info!("Step 1: SSH to broker {} to discover Kafka installation path", hostname);
     
     let kafka_installation_path = discover_kafka_installation_path_on_broker(bastion_alias, hostname).await?;
@@ -156,9 +335,10 @@ pub async fn discover_brokers_using_installation_path(bastion_alias: Option<&Str
     info!("Step 2: Using {} to discover all brokers in cluster", kafka_installation_path);
     
     let command = format!(
-        "ssh -o StrictHostKeyChecking=no {} '{}/kafka-broker-api-versions.sh --bootstrap-server localhost:9092 | awk \"/id/{{print \\$1}}\"'",
+        "ssh -o StrictHostKeyChecking=no {} '{}/kafka-broker-api-versions.sh --bootstrap-server localhost:{} | awk \"/id/{{print \\$1}}\"'",
         hostname,
-        kafka_installation_path
+        kafka_installation_path,
+        port.unwrap_or(DEFAULT_BROKER_PORT)
     );
     
     info!("Executing broker discovery command via bastion");
@@ -167,7 +347,7 @@ pub async fn discover_brokers_using_installation_path(bastion_alias: Option<&Str
     // Save the raw broker discovery output to tools directory
     let tools_dir = output_dir.join("cluster").join("tools");
     std::fs::create_dir_all(&tools_dir)?;
-    std::fs::write(tools_dir.join("broker_discovery_kafka_tools.txt"), &output)?;
+    manifest.writer("discovery", None).write(tools_dir.join("broker_discovery_kafka_tools.txt"), &output)?;
     info!("💾 Saved broker discovery output to cluster/tools/broker_discovery_kafka_tools.txt");
     
     // Step 3: Parse the output to extract broker hostnames
@@ -177,12 +357,13 @@ pub async fn discover_brokers_using_installation_path(bastion_alias: Option<&Str
     for line in output.lines() {
         let line = line.trim();
         if !line.is_empty() && line.contains(':') {
-            // Expected format: hostname:port
-            let hostname = line.split(':').next().unwrap_or(line).to_string();
+            // Expected format: hostname:port or [ipv6]:port, followed by the broker id
+            let hostname = split_host_port(line.split_whitespace().next().unwrap_or(line)).0;
             
             discovered_brokers.push(BrokerInfo {
                 id: broker_id,
                 hostname,
+                listeners: Vec::new(),
             });
             broker_id += 1;
         }
@@ -208,7 +389,7 @@ async fn discover_kafka_installation_path_on_broker(bastion_alias: Option<&Strin
         broker_hostname
     );
     
-    if let Ok(output) = run_command_on_bastion(bastion_alias, &systemctl_command) {
+    if let Some(output) = probe_output(run_command_on_bastion(bastion_alias, &systemctl_command))? {
         let path = output.trim();
         if !path.is_empty() && path.contains("/") {
             let kafka_bin_path = format!("{}/bin", path);
@@ -223,7 +404,7 @@ async fn discover_kafka_installation_path_on_broker(bastion_alias: Option<&Strin
         broker_hostname
     );
     
-    if let Ok(output) = run_command_on_bastion(bastion_alias, &ps_command) {
+    if let Some(output) = probe_output(run_command_on_bastion(bastion_alias, &ps_command))? {
         let line = output.trim();
         
         // Look for classpath argument that contains kafka installation libs
@@ -285,7 +466,7 @@ async fn discover_kafka_installation_path_on_broker(bastion_alias: Option<&Strin
             broker_hostname, path
         );
         
-        if let Ok(output) = run_command_on_bastion(bastion_alias, &test_command) {
+        if let Some(output) = probe_output(run_command_on_bastion(bastion_alias, &test_command))? {
             if output.trim() == "EXISTS" {
                 info!("Found Kafka path via fallback: {}", path);
                 return Ok(path.to_string());
@@ -303,8 +484,8 @@ pub async fn discover_brokers_with_metadata_shell(bastion_alias: Option<&String>
         broker_address
     );
     
-    match run_command_on_bastion(bastion_alias, &command) {
-        Ok(output) => {
+    match probe_output(run_command_on_bastion(bastion_alias, &command))? {
+        Some(output) => {
             let mut brokers = Vec::new();
             for line in output.lines() {
                 // Parse broker information from metadata shell output
@@ -314,7 +495,7 @@ pub async fn discover_brokers_with_metadata_shell(bastion_alias: Option<&String>
             }
             Ok(brokers)
         }
-        Err(_) => Ok(Vec::new()), // Tool not available
+        None => Ok(Vec::new()), // Tool not available
     }
 }
 
@@ -325,21 +506,22 @@ pub async fn discover_brokers_with_api_versions(bastion_alias: Option<&String>,
         broker_address
     );
     
-    match run_command_on_bastion(bastion_alias, &command) {
-        Ok(output) => {
+    match probe_output(run_command_on_bastion(bastion_alias, &command))? {
+        Some(output) => {
             if output.contains("successfully connected") || output.contains("ApiVersion") {
                 // For now, if we can connect, assume we can discover at least this broker
                 // TODO: Enhanced parsing to extract multiple broker IDs from API versions output
-                let hostname = broker_address.split(':').next().unwrap_or("unknown").to_string();
+                let hostname = split_host_port(broker_address).0;
                 Ok(vec![BrokerInfo {
                     id: 0, // Will be determined later during data collection
                     hostname,
+                    listeners: Vec::new(),
                 }])
             } else {
                 Ok(Vec::new())
             }
         }
-        Err(_) => Ok(Vec::new()), // Tool not available or failed
+        None => Ok(Vec::new()), // Tool not available or failed
     }
 }
 
@@ -353,6 +535,70 @@ pub async fn discover_brokers_from_configs(bastion_alias: Option<&String>, _brok
     Ok(Vec::new())
 }
 
+/// `cluster.id` of a broker's `meta.properties`
+pub fn parse_meta_properties_cluster_id(content: &str) -> Option<String> {
+    content.lines()
+        .filter_map(|line| line.trim().strip_prefix("cluster.id="))
+        .map(|id| id.trim().to_string())
+        .find(|id| !id.is_empty())
+}
+
+/// Cluster id a broker reports: from the admin API when the brokers are reachable from here,
+/// otherwise from the `meta.properties` in its log dirs, read over SSH through the bastion
+pub async fn fetch_broker_cluster_id(bastion_alias: Option<&String>, broker: &BrokerInfo, port: u16) -> Option<String> {
+    match bastion_alias {
+        None => {
+            let config = KafkaConfig {
+                bootstrap_servers: vec![format_address(&broker.hostname, port)],
+                timeout_secs: 10,
+                ..Default::default()
+            };
+            AdminCollector::fetch_cluster_id(&config)
+                .inspect_err(|e| debug!("Failed to fetch the cluster id of broker {}: {}", broker.id, e))
+                .ok()
+                .flatten()
+        }
+        Some(_) => {
+            let command = format!(
+                "ssh -o StrictHostKeyChecking=no {} 'sudo find / -xdev -maxdepth 5 -name meta.properties 2>/dev/null | head -1 | xargs -r sudo grep -h \"^cluster.id=\"'",
+                broker.hostname
+            );
+            run_command_on_bastion(bastion_alias, &command)
+                .inspect_err(|e| debug!("Failed to read meta.properties of broker {}: {}", broker.id, e))
+                .ok()
+                .and_then(|output| parse_meta_properties_cluster_id(&output))
+        }
+    }
+}
+
+/// Check that the discovered brokers form one cluster and return its id. Brokers that report
+/// no id are skipped with a warning; brokers of different clusters are an error, so that a
+/// bastion seeing several clusters never produces a mixed scan.
+pub fn verify_cluster_id(reported: &[(BrokerInfo, Option<String>)]) -> Result<Option<String>> {
+    let mut clusters: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for (broker, cluster_id) in reported {
+        match cluster_id {
+            Some(cluster_id) => clusters.entry(cluster_id.as_str())
+                .or_default()
+                .push(format!("{} ({})", broker.id, broker.hostname)),
+            None => warn!("Broker {} ({}) did not report a cluster id", broker.id, broker.hostname),
+        }
+    }
+
+    match clusters.len() {
+        0 => Ok(None),
+        1 => Ok(clusters.into_keys().next().map(str::to_string)),
+        _ => Err(anyhow::anyhow!(
+            "Discovered brokers belong to {} different clusters: {}. Pass --broker of the cluster to scan.",
+            clusters.len(),
+            clusters.iter()
+                .map(|(cluster_id, brokers)| format!("cluster {} has brokers {}", cluster_id, brokers.join(", ")))
+                .collect::<Vec<_>>()
+                .join("; ")
+        )),
+    }
+}
+
 /// Parse a single line from kafka-metadata-shell output to extract broker info
 fn parse_metadata_shell_broker_line(line: &str) -> Option<BrokerInfo> {
     // Expected format might be something like "Broker 11: kafka-host:9092"
@@ -362,14 +608,159 @@ fn parse_metadata_shell_broker_line(line: &str) -> Option<BrokerInfo> {
         if parts.len() >= 2 {
             if let Ok(id) = parts[1].trim_end_matches(':').parse::<i32>() {
                 if let Some(address) = parts.get(2) {
-                    let hostname = address.split(':').next().unwrap_or(address).to_string();
+                    let hostname = split_host_port(address).0;
                     return Some(BrokerInfo {
                         id,
                         hostname,
+                        listeners: Vec::new(),
                     });
                 }
             }
         }
     }
     None
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::bastion::SshTransportError;
+    use std::cell::Cell;
+
+    fn instant_retries(attempts: u32) -> RetryPolicy {
+        RetryPolicy { attempts, initial_backoff: Duration::ZERO }
+    }
+
+    fn broker(id: i32) -> BrokerInfo {
+        BrokerInfo { id, hostname: format!("kafka-{}", id), listeners: Vec::new() }
+    }
+
+    #[test]
+    fn test_parse_broker_list() {
+        let content = "\
+# Production brokers
+kafka-1.internal:9092
+
+kafka-2.internal:9093   # moved to 9093 after the migration
+  kafka-3.internal
+[2001:db8::4]:9092
+";
+        let brokers = parse_broker_list(content, 19092).unwrap();
+
+        let addresses: Vec<(i32, &str, u16)> = brokers.iter()
+            .map(|b| (b.id, b.hostname.as_str(), b.listeners[0].port))
+            .collect();
+        assert_eq!(addresses, vec![
+            (1, "kafka-1.internal", 9092),
+            (2, "kafka-2.internal", 9093),
+            (3, "kafka-3.internal", 19092),
+            (4, "2001:db8::4", 9092),
+        ]);
+        assert!(brokers.iter().all(|b| b.listeners[0].name == BROKER_LIST_LISTENER));
+    }
+
+    #[test]
+    fn test_parse_broker_list_reports_malformed_entries() {
+        let content = "kafka-1:9092\nkafka-2:port\n:9092\nkafka 3:9092\nkafka-1:9092\nkafka-4:0\n";
+        let error = parse_broker_list(content, 9092).unwrap_err().to_string();
+
+        assert!(error.contains("line 2: 'kafka-2:port'"), "{}", error);
+        assert!(error.contains("line 3: ':9092'"), "{}", error);
+        assert!(error.contains("line 4: 'kafka 3:9092'"), "{}", error);
+        assert!(error.contains("line 5: 'kafka-1:9092' repeats line 1"), "{}", error);
+        assert!(error.contains("line 6: 'kafka-4:0'"), "{}", error);
+
+        assert!(parse_broker_list("# no brokers yet\n\n", 9092).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retry_recovers_from_transient_failure() {
+        let calls = Cell::new(0);
+        let result = discover_with_retry(instant_retries(3), "flaky", || {
+            calls.set(calls.get() + 1);
+            let call = calls.get();
+            async move {
+                if call == 1 {
+                    Err(SshTransportError("ssh: connect to host bastion port 22: Operation timed out".to_string()).into())
+                } else {
+                    Ok(vec![broker(1), broker(2)])
+                }
+            }
+        }).await;
+
+        assert_eq!(result.unwrap().len(), 2);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_attempts() {
+        let calls = Cell::new(0);
+        let result = discover_with_retry(instant_retries(3), "unavailable", || {
+            calls.set(calls.get() + 1);
+            async { Err(SshTransportError("ssh: connect to host kafka-1 port 22: Connection refused".to_string()).into()) }
+        }).await;
+
+        assert!(result.unwrap_err().to_string().contains("Connection refused"));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_skips_command_failures() {
+        for error in ["kafka-metadata-shell.sh: command not found", "cat: /etc/kafka/server.properties: Permission denied"] {
+            let calls = Cell::new(0);
+            let result = discover_with_retry(instant_retries(3), "failing", || {
+                calls.set(calls.get() + 1);
+                async move { Err(anyhow::anyhow!("Command failed on bastion: {}", error)) }
+            }).await;
+
+            assert!(result.is_err());
+            assert_eq!(calls.get(), 1, "{}", error);
+        }
+
+        // A failed probe is a missing tool, a failed connection is retried
+        assert_eq!(probe_output(Err(anyhow::anyhow!("command not found"))).unwrap(), None);
+        let timeout = std::io::Error::new(std::io::ErrorKind::TimedOut, "read timed out");
+        assert!(probe_output(Err(anyhow::Error::new(timeout).context("Failed to execute command"))).is_err());
+    }
+
+    #[test]
+    fn test_verify_cluster_id() {
+        let same = vec![
+            (broker(1), Some("MkU3OEVBNTcwNTJENDM2Qk".to_string())),
+            (broker(2), Some("MkU3OEVBNTcwNTJENDM2Qk".to_string())),
+            (broker(3), None),
+        ];
+        assert_eq!(verify_cluster_id(&same).unwrap().as_deref(), Some("MkU3OEVBNTcwNTJENDM2Qk"));
+        assert_eq!(verify_cluster_id(&[(broker(1), None)]).unwrap(), None);
+
+        let mixed = vec![
+            (broker(1), Some("prod".to_string())),
+            (broker(2), Some("staging".to_string())),
+            (broker(3), Some("prod".to_string())),
+        ];
+        assert_eq!(
+            verify_cluster_id(&mixed).unwrap_err().to_string(),
+            "Discovered brokers belong to 2 different clusters: cluster prod has brokers 1 (kafka-1), 3 (kafka-3); \
+             cluster staging has brokers 2 (kafka-2). Pass --broker of the cluster to scan."
+        );
+    }
+
+    #[test]
+    fn test_parse_meta_properties_cluster_id() {
+        let meta = "#\n#Mon Jun 03 10:00:00 UTC 2024\nnode.id=1\nversion=1\ncluster.id=MkU3OEVBNTcwNTJENDM2Qk\n";
+        assert_eq!(parse_meta_properties_cluster_id(meta).as_deref(), Some("MkU3OEVBNTcwNTJENDM2Qk"));
+        assert_eq!(parse_meta_properties_cluster_id("version=0\nbroker.id=1\n"), None);
+    }
+
+    #[tokio::test]
+    async fn test_retry_does_not_repeat_success() {
+        let calls = Cell::new(0);
+        let result = discover_with_retry(RetryPolicy::none(), "empty", || {
+            calls.set(calls.get() + 1);
+            async { Ok(Vec::new()) }
+        }).await;
+
+        assert!(result.unwrap().is_empty());
+        assert_eq!(calls.get(), 1);
+    }
+}
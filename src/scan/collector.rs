@@ -1,3 +1,159 @@
 // Re-export the collectors from their respective modules
 pub use super::bastion_collector::{BastionCollector, DiscoveryMethod};
-pub use super::broker_collector::BrokerCollector;
\ No newline at end of file
+pub use super::broker_collector::BrokerCollector;
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// Part of a scan that can be run on its own with `kcpilot scan --only`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ScanCollector {
+    /// Brokers, topics, consumer groups and feature levels, from kafkactl or the Kafka CLI tools
+    Admin,
+    /// kafka_exporter metrics scraped on the bastion
+    Metrics,
+    /// System information of the bastion host
+    Bastion,
+    /// System and JVM information of each broker host
+    System,
+    /// Broker configuration files
+    Configs,
+    /// Broker log files
+    Logs,
+    /// Broker data directories and their sizes
+    Data,
+    /// Broker listening sockets and latency to peer brokers
+    Network,
+}
+
+impl ScanCollector {
+    pub const ALL: [ScanCollector; 8] = [
+        Self::Admin,
+        Self::Metrics,
+        Self::Bastion,
+        Self::System,
+        Self::Configs,
+        Self::Logs,
+        Self::Data,
+        Self::Network,
+    ];
+
+    /// Collectors run on the bastion rather than on each broker
+    pub const CLUSTER: [ScanCollector; 3] = [Self::Admin, Self::Metrics, Self::Bastion];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Metrics => "metrics",
+            Self::Bastion => "bastion",
+            Self::System => "system",
+            Self::Configs => "configs",
+            Self::Logs => "logs",
+            Self::Data => "data",
+            Self::Network => "network",
+        }
+    }
+}
+
+impl fmt::Display for ScanCollector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ScanCollector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        Self::ALL.into_iter()
+            .find(|collector| collector.name() == name)
+            .ok_or_else(|| format!(
+                "unknown collector '{}'; available collectors: {}",
+                s,
+                Self::ALL.map(ScanCollector::name).join(", ")
+            ))
+    }
+}
+
+/// Collectors a scan runs: all of them, or only those named with `--only`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CollectorSelection {
+    only: Option<BTreeSet<ScanCollector>>,
+}
+
+impl CollectorSelection {
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Only the given collectors; an empty list selects all of them
+    pub fn only(collectors: impl IntoIterator<Item = ScanCollector>) -> Self {
+        let only: BTreeSet<ScanCollector> = collectors.into_iter().collect();
+        Self { only: (!only.is_empty()).then_some(only) }
+    }
+
+    pub fn includes(&self, collector: ScanCollector) -> bool {
+        self.only.as_ref().is_none_or(|only| only.contains(&collector))
+    }
+
+    pub fn includes_any(&self, collectors: &[ScanCollector]) -> bool {
+        collectors.iter().any(|collector| self.includes(*collector))
+    }
+
+    /// Whether anything has to be collected from the brokers themselves
+    pub fn includes_brokers(&self) -> bool {
+        ScanCollector::ALL.iter()
+            .filter(|collector| !ScanCollector::CLUSTER.contains(collector))
+            .any(|collector| self.includes(*collector))
+    }
+
+    /// Whether some collectors are left out
+    pub fn is_restricted(&self) -> bool {
+        self.only.is_some()
+    }
+}
+
+impl fmt::Display for CollectorSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = ScanCollector::ALL.into_iter()
+            .filter(|collector| self.includes(*collector))
+            .map(ScanCollector::name)
+            .collect();
+        f.write_str(&names.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_collector_names() {
+        assert_eq!("logs".parse::<ScanCollector>(), Ok(ScanCollector::Logs));
+        assert_eq!(" Admin ".parse::<ScanCollector>(), Ok(ScanCollector::Admin));
+
+        let err = "jmx".parse::<ScanCollector>().unwrap_err();
+        assert_eq!(err, "unknown collector 'jmx'; available collectors: admin, metrics, bastion, system, configs, logs, data, network");
+    }
+
+    #[test]
+    fn test_selection() {
+        let all = CollectorSelection::all();
+        assert!(ScanCollector::ALL.iter().all(|c| all.includes(*c)));
+        assert!(!all.is_restricted());
+        assert_eq!(CollectorSelection::only([]), all);
+
+        let logs = CollectorSelection::only([ScanCollector::Logs]);
+        assert!(logs.includes(ScanCollector::Logs));
+        assert!(!logs.includes(ScanCollector::Admin));
+        assert!(!logs.includes_any(&ScanCollector::CLUSTER));
+        assert!(logs.includes_brokers());
+        assert_eq!(logs.to_string(), "logs");
+
+        let admin = CollectorSelection::only([ScanCollector::Admin, ScanCollector::Metrics]);
+        assert!(!admin.includes_brokers());
+        assert_eq!(admin.to_string(), "admin, metrics");
+    }
+}
@@ -0,0 +1,5 @@
+/// Connection table a broker's established TCP connections are saved to
+pub const CONNECTIONS_FILE: &str = "connections.txt";
+
+/// Command printing a broker's established TCP connections, with ss or netstat
+pub const CONNECTIONS_COMMAND: &str = "ss -tn state established 2>/dev/null || netstat -tn 2>/dev/null | grep ESTABLISHED";
@@ -0,0 +1,164 @@
+use crate::snapshot::format::ConsumerGroupLag;
+use serde::Deserialize;
+
+/// File under the cluster's `kafkactl` directory the consumer group descriptions are saved to
+pub const CONSUMER_GROUP_DESCRIPTIONS_FILE: &str = "consumer_group_descriptions.yaml";
+
+/// File under the cluster's `kafkactl` directory the lag of each consumer group is saved to
+pub const CONSUMER_LAG_FILE: &str = "consumer_lag.json";
+
+/// Command describing one consumer group, with its state, members and per-partition lag
+pub fn describe_group_command(group: &str) -> String {
+    format!("kafkactl describe consumer-group '{}' -o yaml", group.replace('\'', r"'\''"))
+}
+
+#[derive(Debug, Deserialize)]
+struct GroupName {
+    name: String,
+}
+
+/// Names of the groups in `kafkactl get consumer-groups -o yaml` output, which is either one
+/// YAML list or one document per group
+pub fn parse_group_names(yaml: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(yaml) {
+        match serde_yaml::Value::deserialize(document) {
+            Ok(value @ serde_yaml::Value::Sequence(_)) => names.extend(
+                serde_yaml::from_value::<Vec<GroupName>>(value).unwrap_or_default().into_iter().map(|group| group.name)
+            ),
+            Ok(value) => names.extend(serde_yaml::from_value::<GroupName>(value).ok().map(|group| group.name)),
+            Err(_) => break,
+        }
+    }
+    names
+}
+
+#[derive(Debug, Deserialize)]
+struct GroupDescription {
+    group: GroupInfo,
+    #[serde(default)]
+    topics: Vec<TopicDescription>,
+    #[serde(default)]
+    members: Vec<serde_yaml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GroupInfo {
+    name: String,
+    #[serde(default)]
+    state: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TopicDescription {
+    name: String,
+    #[serde(default)]
+    partitions: Vec<PartitionDescription>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PartitionDescription {
+    #[serde(default)]
+    lag: i64,
+}
+
+/// Lag summary of each group in `kafkactl describe consumer-group -o yaml` outputs, joined
+/// into YAML documents. Partitions without a committed offset report a negative lag, which
+/// is not counted. Documents that do not describe a group are skipped.
+pub fn parse_group_descriptions(yaml: &str) -> Vec<ConsumerGroupLag> {
+    let mut groups = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(yaml) {
+        let Ok(description) = GroupDescription::deserialize(document) else {
+            continue;
+        };
+        groups.push(ConsumerGroupLag {
+            group: description.group.name,
+            state: description.group.state,
+            members: description.members.len(),
+            total_lag: description.topics.iter()
+                .flat_map(|topic| &topic.partitions)
+                .map(|partition| partition.lag.max(0))
+                .sum(),
+            topics: description.topics.into_iter().map(|topic| topic.name).collect(),
+        });
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTIONS: &str = "\
+---
+group:
+  name: billing
+  protocol: range
+  protocolType: consumer
+  state: Stable
+topics:
+- name: orders
+  partitions:
+  - partition: 0
+    oldestOffset: 0
+    newestOffset: 1200
+    consumerOffset: 1190
+    lead: 1190
+    lag: 10
+  - partition: 1
+    oldestOffset: 0
+    newestOffset: 900
+    consumerOffset: -1
+    lead: 0
+    lag: -1
+- name: payments
+  partitions:
+  - partition: 0
+    oldestOffset: 0
+    newestOffset: 40
+    consumerOffset: 35
+    lead: 35
+    lag: 5
+members:
+- clientHost: /10.0.0.5
+  consumerId: billing-1
+  clientId: billing
+- clientHost: /10.0.0.6
+  consumerId: billing-2
+  clientId: billing
+---
+group:
+  name: old-reporting
+  protocolType: consumer
+  state: Empty
+topics:
+- name: reports
+  partitions:
+  - partition: 0
+    lag: 5000
+---
+consumer group 'gone' not found
+";
+
+    #[test]
+    fn test_parse_group_descriptions() {
+        let groups = parse_group_descriptions(DESCRIPTIONS);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].group, "billing");
+        assert_eq!(groups[0].state.as_deref(), Some("Stable"));
+        assert_eq!(groups[0].members, 2);
+        assert_eq!(groups[0].total_lag, 15);
+        assert_eq!(groups[0].topics, vec!["orders", "payments"]);
+        assert_eq!(groups[1].group, "old-reporting");
+        assert_eq!(groups[1].members, 0);
+        assert_eq!(groups[1].total_lag, 5000);
+    }
+
+    #[test]
+    fn test_parse_group_names() {
+        assert_eq!(parse_group_names("- name: billing\n  protocolType: consumer\n- name: audit\n"), vec!["billing", "audit"]);
+        assert_eq!(parse_group_names("name: billing\n---\nname: audit\n"), vec!["billing", "audit"]);
+        assert!(parse_group_names("").is_empty());
+        assert_eq!(describe_group_command("it's"), r"kafkactl describe consumer-group 'it'\''s' -o yaml");
+    }
+}
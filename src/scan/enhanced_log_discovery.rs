@@ -1,93 +1,318 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use std::collections::HashMap;
 use std::path::Path;
-use std::process::Command;
 use tracing::{debug, info, warn};
 
+use super::bastion::bastion_ssh_args;
+use super::scan_command;
+use super::ssh_multiplex::{multiplexing_enabled, SshConnection};
 use super::log_discovery::{
-    EnhancedLogResult, LogOutputInfo,
+    EnhancedLogResult, LogFileLocation, LogOutputInfo,
     process_parser::ProcessParser,
     systemd_parser::SystemdParser,
     llm_log_analyzer::LlmLogAnalyzer,
 };
 
+/// Number of trailing lines collected from each log when no time window is given
+pub const DEFAULT_LOG_LINES: usize = 500;
+
+/// Upper bound on the lines collected from each log for a `--since` window
+pub const MAX_SINCE_LOG_LINES: usize = 10_000;
+
+/// Upper bound on the bytes of rotated history collected for each log file
+pub const MAX_ROTATED_LOG_BYTES: usize = 1024 * 1024;
+
+/// Bytes of logs collected from each broker by default
+pub const DEFAULT_LOG_BUDGET_BYTES: usize = 50 * 1024 * 1024;
+
+/// Exit code of the awk filter when no line carried a parseable timestamp
+const NO_TIMESTAMPS_EXIT_CODE: i32 = 3;
+
 /// Enhanced log discovery following the process → systemd → config → logs chain
 pub struct EnhancedLogDiscovery {
     bastion_alias: Option<String>,
     broker_hostname: Option<String>,
+    /// Connection to the first SSH hop, shared by all commands
+    ssh: Option<SshConnection>,
+    since: Option<DateTime<Utc>>,
+    budget: LogCollectionBudget,
+}
+
+/// Limits on the logs collected from a broker. Logs of the prioritized types are collected
+/// first, so a large request log can't crowd the server log out of the budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogCollectionBudget {
+    /// Trailing lines collected from each log when no time window is given
+    pub max_lines_per_file: usize,
+    /// Bytes collected from all logs of a broker together
+    pub max_total_bytes: usize,
+    /// Log types collected first, in this order: `server`, `controller`, `journald`, `gc`, ...
+    pub prioritize: Vec<String>,
+}
+
+impl Default for LogCollectionBudget {
+    fn default() -> Self {
+        Self {
+            max_lines_per_file: DEFAULT_LOG_LINES,
+            max_total_bytes: DEFAULT_LOG_BUDGET_BYTES,
+            prioritize: ["server", "controller", "state-change", "journald", "gc"]
+                .map(str::to_string)
+                .to_vec(),
+        }
+    }
+}
+
+impl LogCollectionBudget {
+    /// Position of a log type in collection order; types that aren't prioritized come last
+    pub fn rank(&self, log_type: &str) -> usize {
+        self.prioritize.iter()
+            .position(|prioritized| prioritized == log_type)
+            .unwrap_or(self.prioritize.len())
+    }
+}
+
+/// What is left of a `LogCollectionBudget` while collecting, and the logs it cut
+#[derive(Debug)]
+pub struct LogBudgetUsage {
+    remaining: usize,
+    pub truncated: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+impl LogBudgetUsage {
+    pub fn new(budget: &LogCollectionBudget) -> Self {
+        Self {
+            remaining: budget.max_total_bytes,
+            truncated: Vec::new(),
+            skipped: Vec::new(),
+        }
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Record a log left out without collecting it
+    pub fn skip(&mut self, name: &str) {
+        self.skipped.push(name.to_string());
+    }
+
+    /// Charge a collected log to the budget. A log that doesn't fit keeps its latest whole
+    /// lines and spends the rest of the budget; `None` when nothing of it fits.
+    pub fn admit(&mut self, name: &str, content: String) -> Option<String> {
+        if content.len() <= self.remaining {
+            self.remaining -= content.len();
+            return Some(content);
+        }
+
+        let cut = content.len() - self.remaining;
+        self.remaining = 0;
+        let start = content.as_bytes()[cut..].iter()
+            .position(|&b| b == b'\n')
+            .map_or(content.len(), |newline| cut + newline + 1);
+        if start == content.len() {
+            self.skip(name);
+            return None;
+        }
+        self.truncated.push(name.to_string());
+        Some(content[start..].to_string())
+    }
+}
+
+/// Parse a `--since` value: RFC 3339, `YYYY-MM-DD HH:MM:SS` in UTC, or an age such as `30m`, `6h` or `2d`
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    if let Ok(timestamp) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+        return Ok(timestamp.and_utc());
+    }
+
+    let (amount, unit) = value.split_at(value.len().saturating_sub(1));
+    let amount: i64 = amount.parse()
+        .map_err(|_| format!("Invalid --since value '{}': expected a timestamp or an age like 30m, 6h, 2d", value))?;
+    let age = match unit {
+        "s" => chrono::Duration::seconds(amount),
+        "m" => chrono::Duration::minutes(amount),
+        "h" => chrono::Duration::hours(amount),
+        "d" => chrono::Duration::days(amount),
+        _ => return Err(format!("Invalid --since unit in '{}': use s, m, h or d", value)),
+    };
+
+    Ok(Utc::now() - age)
+}
+
+/// Build the journalctl command collecting a service's logs: entries newer than `since` when
+/// set, otherwise the last `max_lines`
+pub fn journalctl_command(service_name: &str, since: Option<DateTime<Utc>>, max_lines: usize) -> String {
+    match since {
+        Some(since) => format!(
+            "journalctl -u {} --since @{} -n {} --no-pager 2>/dev/null",
+            service_name, since.timestamp(), MAX_SINCE_LOG_LINES
+        ),
+        None => format!("journalctl -u {} -n {} --no-pager 2>/dev/null", service_name, max_lines),
+    }
+}
+
+/// awk program keeping lines logged at or after `cutoff` (a `YYYY-MM-DD HH:MM:SS` string).
+/// Lines without a timestamp, such as stack traces, follow the preceding entry.
+/// Exits with `NO_TIMESTAMPS_EXIT_CODE` when no line had a timestamp at all.
+pub fn timestamp_filter_program() -> String {
+    let timestamp = "[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9][ T][0-9][0-9]:[0-9][0-9]:[0-9][0-9]";
+    format!(
+        r#"/^\[?{}/ {{ seen = 1; ts = substr($0, substr($0, 1, 1) == "[" ? 2 : 1, 19); sub(/T/, " ", ts); keep = ts >= cutoff }} keep {{ print }} END {{ if (!seen) exit {} }}"#,
+        timestamp, NO_TIMESTAMPS_EXIT_CODE
+    )
+}
+
+/// Build the command collecting a log file, keeping only entries newer than `since` when set
+/// and its last `max_lines` otherwise. The cutoff is rendered in the broker's local time, which is what log4j timestamps use,
+/// and collection falls back to the last lines of the file when its timestamps can't be parsed.
+pub fn file_log_command(path: &Path, since: Option<DateTime<Utc>>, max_lines: usize) -> String {
+    let tail = format!("sudo tail -{} '{}' 2>/dev/null", max_lines, path.display());
+    match since {
+        Some(since) => format!(
+            r#"(sudo awk -v cutoff="$(date -d @{} '+%Y-%m-%d %H:%M:%S')" '{}' '{}' 2>/dev/null || {}) | tail -n {}"#,
+            since.timestamp(),
+            timestamp_filter_program(),
+            path.display(),
+            tail,
+            MAX_SINCE_LOG_LINES
+        ),
+        None => tail,
+    }
+}
+
+/// Build the command collecting the rotated copies of a log file, oldest first: `server.log.1`,
+/// `server.log.2024-06-01` and log4j2's `server-2024-06-01.log.gz`, decompressing gzipped ones.
+/// Only the last `max_bytes` are kept, after dropping entries older than `since` when set.
+pub fn rotated_log_command(path: &Path, since: Option<DateTime<Utc>>, max_bytes: usize) -> String {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let read = format!(
+        r#"sudo ls -1tr '{}'.* '{}/{}'-*.gz 2>/dev/null | while IFS= read -r f; do case "$f" in *.gz) sudo zcat "$f";; *) sudo cat "$f";; esac; done 2>/dev/null"#,
+        path.display(),
+        dir.display(),
+        stem
+    );
+    match since {
+        Some(since) => format!(
+            r#"{} | awk -v cutoff="$(date -d @{} '+%Y-%m-%d %H:%M:%S')" '{}' | tail -c {}"#,
+            read,
+            since.timestamp(),
+            timestamp_filter_program(),
+            max_bytes
+        ),
+        None => format!("{} | tail -c {}", read, max_bytes),
+    }
+}
+
+/// Prepend the rotated history of a log to its current content. History cut at `max_bytes`
+/// starts mid-line, so that partial first line is dropped.
+pub fn with_rotated_history(rotated: &str, current: String, max_bytes: usize) -> String {
+    let rotated = if rotated.len() >= max_bytes {
+        rotated.split_once('\n').map_or("", |(_, rest)| rest)
+    } else {
+        rotated
+    };
+    if rotated.trim().is_empty() {
+        return current;
+    }
+    if rotated.ends_with('\n') {
+        format!("{}{}", rotated, current)
+    } else {
+        format!("{}\n{}", rotated, current)
+    }
+}
+
+/// Quote a command so it reaches the next shell of an SSH chain unchanged
+fn shell_quote(command: &str) -> String {
+    format!("'{}'", command.replace('\'', r"'\''"))
 }
 
 impl EnhancedLogDiscovery {
     pub fn new(ssh_executor: Option<String>) -> Self {
         // Parse the SSH executor string to extract bastion and broker info
-        match ssh_executor {
+        let (bastion_alias, broker_hostname) = match ssh_executor {
             Some(ssh_str) if ssh_str.contains("ssh") => {
                 // Format: "bastion ssh -o StrictHostKeyChecking=no broker"
                 let parts: Vec<&str> = ssh_str.split_whitespace().collect();
                 if parts.len() >= 4 && parts[1] == "ssh" {
-                    Self {
-                        bastion_alias: Some(parts[0].to_string()),
-                        broker_hostname: Some(parts[parts.len() - 1].to_string()),
-                    }
+                    (Some(parts[0].to_string()), Some(parts[parts.len() - 1].to_string()))
                 } else {
-                    Self {
-                        bastion_alias: None,
-                        broker_hostname: Some(ssh_str),
-                    }
+                    (None, Some(ssh_str))
                 }
             }
-            Some(hostname) => {
-                Self {
-                    bastion_alias: None,
-                    broker_hostname: Some(hostname),
-                }
-            }
-            None => {
-                Self {
-                    bastion_alias: None,
-                    broker_hostname: None,
-                }
+            Some(hostname) => (None, Some(hostname)),
+            None => (None, None),
+        };
+
+        // Commands share one connection to the first SSH hop: the bastion, or the broker itself
+        let ssh = match (&bastion_alias, &broker_hostname) {
+            (Some(bastion), Some(_)) => {
+                let mut target: Vec<String> = ["-A", "-o", "StrictHostKeyChecking=no"].map(str::to_string).to_vec();
+                target.extend(bastion_ssh_args(bastion));
+                Some(target)
             }
+            (None, Some(broker)) => Some(vec!["-o".to_string(), "StrictHostKeyChecking=no".to_string(), broker.clone()]),
+            _ => None,
         }
+        .map(|target| SshConnection::new(target).with_multiplexing(multiplexing_enabled()));
+
+        Self {
+            bastion_alias,
+            broker_hostname,
+            ssh,
+            since: None,
+            budget: LogCollectionBudget::default(),
+        }
+    }
+
+    /// Only collect log entries written at or after the given time
+    pub fn with_since(mut self, since: Option<DateTime<Utc>>) -> Self {
+        self.since = since;
+        self
+    }
+
+    /// Bound the lines of each log and the bytes of all logs collected
+    pub fn with_budget(mut self, budget: LogCollectionBudget) -> Self {
+        self.budget = budget;
+        self
     }
 
     /// Execute command either locally or via SSH
     fn execute(&self, command: &str) -> Result<String> {
         debug!("🔧 Executing command: {}", command);
         
-        let output = match (&self.bastion_alias, &self.broker_hostname) {
-            (Some(bastion), Some(broker)) => {
+        let output = match (&self.bastion_alias, &self.broker_hostname, &self.ssh) {
+            (Some(bastion), Some(broker), Some(ssh)) => {
                 debug!("   → via SSH chain: {} -> {}", bastion, broker);
-                // SSH to bastion, then SSH to broker
-                let ssh_chain_command = format!("ssh -o StrictHostKeyChecking=no {} '{}'", broker, command);
-                Command::new("ssh")
-                    .arg("-A") // Enable agent forwarding
-                    .arg("-o")
-                    .arg("StrictHostKeyChecking=no")
-                    .arg(bastion)
+                // SSH to bastion (with agent forwarding), then SSH to broker
+                let ssh_chain_command = format!("ssh -o StrictHostKeyChecking=no {} {}", broker, shell_quote(command));
+                ssh.command()
                     .arg(&ssh_chain_command)
                     .output()
                     .context(format!("Failed to execute via SSH chain: {}", command))?
             }
-            (None, Some(broker)) => {
+            (None, Some(broker), Some(ssh)) => {
                 debug!("   → via direct SSH to: {}", broker);
-                Command::new("ssh")
-                    .arg("-o")
-                    .arg("StrictHostKeyChecking=no")
-                    .arg(broker)
+                ssh.command()
                     .arg(command)
                     .output()
                     .context(format!("Failed to execute via SSH: {}", command))?
             }
-            (None, None) => {
+            (None, None, _) => {
                 debug!("   → locally");
-                Command::new("sh")
+                scan_command("sh")
                     .arg("-c")
                     .arg(command)
                     .output()
                     .context(format!("Failed to execute locally: {}", command))?
             }
-            (Some(_), None) => {
+            _ => {
                 return Err(anyhow::anyhow!("Invalid SSH configuration: bastion specified but no broker hostname"));
             }
         };
@@ -129,7 +354,10 @@ impl EnhancedLogDiscovery {
             discovered_logs: HashMap::new(),
             discovery_steps: Vec::new(),
             warnings: Vec::new(),
+            truncated_logs: Vec::new(),
+            skipped_logs: Vec::new(),
         };
+        let mut usage = LogBudgetUsage::new(&self.budget);
 
         // Create a closure that captures self for execution
         let executor = |cmd: &str| self.execute(cmd);
@@ -170,7 +398,10 @@ impl EnhancedLogDiscovery {
                                 if let Some(log4j_path) = &process_info.log4j_path {
                                     info!("🔍 Step 4: Analyzing log4j configuration...");
                                     info!("   Log4j path: {}", log4j_path.display());
-                                    match self.analyze_log4j(log4j_path, &enhanced_systemd.environment_vars).await {
+                                    // JVM system properties take precedence over the service environment
+                                    let mut log4j_vars = enhanced_systemd.environment_vars.clone();
+                                    log4j_vars.extend(process_info.jvm_args.clone());
+                                    match self.analyze_log4j(log4j_path, &log4j_vars).await {
                                         Ok(log_output_info) => {
                                             info!("✅ Step 4: Analyzed log4j configuration");
                                             info!("   Found {} log files", log_output_info.log_files.len());
@@ -182,7 +413,7 @@ impl EnhancedLogDiscovery {
                                             
                                             // Step 5: Collect actual log content
                                             info!("🔍 Step 5: Collecting logs from discovered locations...");
-                                            match self.collect_logs(&log_output_info, &enhanced_systemd.service_name).await {
+                                            match self.collect_logs(&log_output_info, &enhanced_systemd.service_name, &mut usage).await {
                                                 Ok(logs) => {
                                                     info!("✅ Step 5: Collected {} log sources", logs.len());
                                                     for (name, content) in &logs {
@@ -235,6 +466,38 @@ impl EnhancedLogDiscovery {
             result.warnings.push("Falling back to basic discovery methods".to_string());
         }
 
+        // GC logs are written by the JVM, not log4j, so their path comes from the process arguments
+        if let Some(gc_log) = result.process_info.as_ref().and_then(|p| p.gc_log_path.clone()) {
+            let log_name = format!("gc_{}", gc_log.file_name().unwrap_or_default().to_string_lossy());
+            let collected = if usage.is_exhausted() {
+                None
+            } else {
+                Some(self.execute(&file_log_command(&gc_log, self.since, self.budget.max_lines_per_file)))
+            };
+            match collected {
+                None => usage.skip(&log_name),
+                Some(Ok(content)) if !content.trim().is_empty() => {
+                    if let Some(content) = usage.admit(&log_name, content) {
+                        info!("   ✅ Collected {}: {} lines", log_name, content.lines().count());
+                        result.discovered_logs.insert(log_name, content);
+                        result.discovery_steps.push("Collected GC log from JVM arguments".to_string());
+                    }
+                }
+                Some(Ok(_)) => info!("   ⚠️  GC log is empty: {}", gc_log.display()),
+                Some(Err(e)) => {
+                    warn!("Failed to collect GC log {}: {}", gc_log.display(), e);
+                    result.warnings.push(format!("Failed to collect GC log: {}", e));
+                }
+            }
+        }
+
+        if !usage.truncated.is_empty() || !usage.skipped.is_empty() {
+            warn!("Log budget of {} bytes reached: {} logs truncated, {} skipped",
+                  self.budget.max_total_bytes, usage.truncated.len(), usage.skipped.len());
+        }
+        result.truncated_logs = usage.truncated;
+        result.skipped_logs = usage.skipped;
+
         info!("📊 Enhanced log discovery complete: {} steps, {} logs, {} warnings", 
               result.discovery_steps.len(), 
               result.discovered_logs.len(), 
@@ -265,32 +528,74 @@ impl EnhancedLogDiscovery {
         }
 
         // Try LLM analysis first, fall back to basic parsing
-        LlmLogAnalyzer::analyze(&log4j_content, env_vars).await
+        LlmLogAnalyzer::analyze(Path::new(&clean_path), &log4j_content, env_vars).await
     }
 
-    /// Collect actual logs based on the analysis
-    async fn collect_logs(&self, log_info: &LogOutputInfo, service_name: &str) -> Result<HashMap<String, String>> {
-        info!("Collecting logs from discovered locations...");
+    /// Collect actual logs based on the analysis, prioritized log types first, until the budget is spent
+    async fn collect_logs(&self, log_info: &LogOutputInfo, service_name: &str, usage: &mut LogBudgetUsage) -> Result<HashMap<String, String>> {
+        match self.since {
+            Some(since) => info!("Collecting logs written since {} from discovered locations...", since),
+            None => info!("Collecting logs from discovered locations..."),
+        }
         let mut discovered_logs = HashMap::new();
         let mut successful_collections = 0;
         let mut failed_collections = 0;
-        
-        // Collect file-based logs
-        for log_file in &log_info.log_files {
+
+        // File-based logs, and journald in place of the service's stdout
+        let mut sources: Vec<Option<&LogFileLocation>> = log_info.log_files.iter().map(Some).collect();
+        if log_info.uses_journald || log_info.uses_stdout {
+            sources.push(None);
+        }
+        sources.sort_by_key(|source| self.budget.rank(source.map_or("journald", |log_file| log_file.log_type.as_str())));
+
+        for source in sources {
+            let Some(log_file) = source else {
+                if usage.is_exhausted() {
+                    usage.skip("journald_service_logs");
+                    continue;
+                }
+                info!("   Trying to collect journald logs for service: {}", service_name);
+                match self.execute(&journalctl_command(service_name, self.since, self.budget.max_lines_per_file)) {
+                    Ok(journald_content) => {
+                        if journald_content.trim().is_empty() {
+                            info!("   ⚠️  No journald logs available");
+                        } else if let Some(journald_content) = usage.admit("journald_service_logs", journald_content) {
+                            let lines = journald_content.lines().count();
+                            info!("   ✅ Collected journald logs: {} lines", lines);
+                            discovered_logs.insert("journald_service_logs".to_string(), journald_content);
+                            successful_collections += 1;
+                        }
+                    }
+                    Err(e) => {
+                        info!("   ❌ Failed to collect journald logs: {}", e);
+                        failed_collections += 1;
+                    }
+                }
+                continue;
+            };
+
             let log_name = format!("{}_{}", log_file.log_type, log_file.path.file_name().unwrap_or_default().to_string_lossy());
-            
+            if usage.is_exhausted() {
+                usage.skip(&log_name);
+                continue;
+            }
+
             info!("   Trying to collect: {} -> {}", log_file.appender_name, log_file.path.display());
-            
+
             // Try to collect log file content
-            match self.execute(&format!("sudo tail -500 '{}' 2>/dev/null", log_file.path.display())) {
+            match self.execute(&file_log_command(&log_file.path, self.since, self.budget.max_lines_per_file)) {
                 Ok(content) => {
-                    if !content.trim().is_empty() {
+                    // Rotated and gzipped copies hold the history of past incidents
+                    let rotated = self.execute(&rotated_log_command(&log_file.path, self.since, MAX_ROTATED_LOG_BYTES))
+                        .unwrap_or_default();
+                    let content = with_rotated_history(&rotated, content, MAX_ROTATED_LOG_BYTES);
+                    if content.trim().is_empty() {
+                        info!("   ⚠️  File exists but is empty: {}", log_file.path.display());
+                    } else if let Some(content) = usage.admit(&log_name, content) {
                         let lines = content.lines().count();
                         info!("   ✅ Collected {}: {} lines", log_name, lines);
                         discovered_logs.insert(log_name, content);
                         successful_collections += 1;
-                    } else {
-                        info!("   ⚠️  File exists but is empty: {}", log_file.path.display());
                     }
                 }
                 Err(e) => {
@@ -299,30 +604,208 @@ impl EnhancedLogDiscovery {
                 }
             }
         }
-        
-        // Collect journald logs if needed
-        if log_info.uses_journald || log_info.uses_stdout {
-            info!("   Trying to collect journald logs for service: {}", service_name);
-            match self.execute(&format!("journalctl -u {} -n 500 --no-pager 2>/dev/null", service_name)) {
-                Ok(journald_content) => {
-                    if !journald_content.trim().is_empty() {
-                        let lines = journald_content.lines().count();
-                        info!("   ✅ Collected journald logs: {} lines", lines);
-                        discovered_logs.insert("journald_service_logs".to_string(), journald_content);
-                        successful_collections += 1;
-                    } else {
-                        info!("   ⚠️  No journald logs available");
-                    }
-                }
-                Err(e) => {
-                    info!("   ❌ Failed to collect journald logs: {}", e);
-                    failed_collections += 1;
-                }
-            }
-        }
-        
+
         info!("✅ Collection summary - {} successful, {} failed", successful_collections, failed_collections);
         
         Ok(discovered_logs)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::process::{Command, Stdio};
+
+    fn cutoff() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 15, 10, 0, 0).unwrap()
+    }
+
+    fn run_filter(cutoff: &str, input: &str) -> Option<std::process::Output> {
+        let mut child = Command::new("awk")
+            .arg("-v")
+            .arg(format!("cutoff={}", cutoff))
+            .arg(timestamp_filter_program())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .ok()?;
+        child.stdin.take()?.write_all(input.as_bytes()).ok()?;
+        child.wait_with_output().ok()
+    }
+
+    #[test]
+    fn test_journalctl_command_without_since() {
+        assert_eq!(
+            journalctl_command("kafka.service", None, DEFAULT_LOG_LINES),
+            "journalctl -u kafka.service -n 500 --no-pager 2>/dev/null"
+        );
+    }
+
+    #[test]
+    fn test_journalctl_command_with_since() {
+        assert_eq!(
+            journalctl_command("kafka.service", Some(cutoff()), DEFAULT_LOG_LINES),
+            "journalctl -u kafka.service --since @1705312800 -n 10000 --no-pager 2>/dev/null"
+        );
+    }
+
+    #[test]
+    fn test_file_log_command_without_since() {
+        let path = PathBuf::from("/var/log/kafka/server.log");
+        assert_eq!(file_log_command(&path, None, DEFAULT_LOG_LINES), "sudo tail -500 '/var/log/kafka/server.log' 2>/dev/null");
+    }
+
+    #[test]
+    fn test_file_log_command_with_since() {
+        let path = PathBuf::from("/var/log/kafka/server.log");
+        let command = file_log_command(&path, Some(cutoff()), DEFAULT_LOG_LINES);
+
+        assert!(command.starts_with(r#"(sudo awk -v cutoff="$(date -d @1705312800 '+%Y-%m-%d %H:%M:%S')" '/^"#));
+        assert!(command.contains("'/var/log/kafka/server.log' 2>/dev/null || sudo tail -500 '/var/log/kafka/server.log'"));
+        assert!(command.ends_with("| tail -n 10000"));
+    }
+
+    #[test]
+    fn test_log_budget_caps_total_bytes() {
+        let budget = LogCollectionBudget { max_total_bytes: 40, ..Default::default() };
+        let mut usage = LogBudgetUsage::new(&budget);
+
+        let server = usage.admit("server_server.log", "line 1\nline 2\n".to_string());
+        assert_eq!(server.as_deref(), Some("line 1\nline 2\n"));
+        let controller = usage.admit("controller_controller.log", "old entry 1\nold entry 2\nnew entry 3\n".to_string());
+        assert_eq!(controller.as_deref(), Some("old entry 2\nnew entry 3\n"));
+        assert!(usage.is_exhausted());
+        assert_eq!(usage.admit("request_request.log", "x\n".to_string()), None);
+
+        assert_eq!(usage.truncated, vec!["controller_controller.log"]);
+        assert_eq!(usage.skipped, vec!["request_request.log"]);
+        assert!(server.unwrap().len() + controller.unwrap().len() <= budget.max_total_bytes);
+    }
+
+    #[test]
+    fn test_log_budget_prioritizes_log_types() {
+        let budget = LogCollectionBudget {
+            prioritize: vec!["controller".to_string(), "journald".to_string()],
+            ..Default::default()
+        };
+        let mut types = vec!["request", "server", "journald", "controller"];
+        types.sort_by_key(|log_type| budget.rank(log_type));
+
+        assert_eq!(types, vec!["controller", "journald", "request", "server"]);
+        assert_eq!(LogCollectionBudget::default().rank("server"), 0);
+    }
+
+    /// Run a collection command locally, where tests have no sudo
+    fn run_local(command: &str) -> String {
+        let output = Command::new("sh").arg("-c").arg(command.replace("sudo ", "")).output().unwrap();
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    fn write_log(path: &Path, content: &str, age_secs: u64) {
+        let file = std::fs::File::create(path).unwrap();
+        if path.extension().is_some_and(|ext| ext == "gz") {
+            let mut encoder = flate2::write::GzEncoder::new(&file, flate2::Compression::default());
+            encoder.write_all(content.as_bytes()).unwrap();
+            encoder.finish().unwrap();
+        } else {
+            (&file).write_all(content.as_bytes()).unwrap();
+        }
+        file.set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(age_secs)).unwrap();
+    }
+
+    #[test]
+    fn test_rotated_and_gzipped_logs_read_oldest_first() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let log = dir.path().join("server.log");
+        write_log(&dir.path().join("server-2024-05-30.log.gz"), "[2024-05-30 08:00:00,000] INFO oldest\n", 4000);
+        write_log(&dir.path().join("server.log.2024-05-31.gz"), "[2024-05-31 08:00:00,000] INFO older\n", 3000);
+        write_log(&dir.path().join("server.log.2024-06-01"), "[2024-06-01 08:00:00,000] INFO newest rotated\n", 2000);
+        write_log(&dir.path().join("controller.log.1"), "[2024-06-01 09:00:00,000] INFO other log\n", 1000);
+        write_log(&log, "[2024-06-02 08:00:00,000] INFO current\n", 0);
+
+        let rotated = run_local(&rotated_log_command(&log, None, MAX_ROTATED_LOG_BYTES));
+        assert_eq!(
+            rotated,
+            "[2024-05-30 08:00:00,000] INFO oldest\n[2024-05-31 08:00:00,000] INFO older\n[2024-06-01 08:00:00,000] INFO newest rotated\n"
+        );
+
+        let since = Utc.with_ymd_and_hms(2024, 5, 31, 0, 0, 0).unwrap();
+        let recent = run_local(&rotated_log_command(&log, Some(since), MAX_ROTATED_LOG_BYTES));
+        assert!(!recent.contains("oldest"));
+        assert!(recent.starts_with("[2024-05-31 08:00:00,000] INFO older"));
+
+        let capped = run_local(&rotated_log_command(&log, None, 50));
+        assert_eq!(capped.len(), 50);
+        assert!(capped.ends_with("INFO newest rotated\n"));
+    }
+
+    #[test]
+    fn test_with_rotated_history() {
+        let current = "current\n".to_string();
+        assert_eq!(with_rotated_history("", current.clone(), 100), "current\n");
+        assert_eq!(with_rotated_history("old\nolder", current.clone(), 100), "old\nolder\ncurrent\n");
+        assert_eq!(with_rotated_history("tial line\nwhole line\n", current, 21), "whole line\ncurrent\n");
+    }
+
+    #[test]
+    fn test_timestamp_filter_program() {
+        let program = timestamp_filter_program();
+
+        assert!(program.starts_with(r"/^\[?[0-9][0-9][0-9][0-9]-"));
+        assert!(program.contains("keep = ts >= cutoff"));
+        assert!(program.ends_with("END { if (!seen) exit 3 }"));
+        assert!(!program.contains('\''), "program must fit in single quotes");
+    }
+
+    #[test]
+    fn test_timestamp_filter_keeps_newer_entries() {
+        let log = "[2024-01-15 09:59:59,001] INFO old entry (kafka.server)\n\
+                   java.lang.Exception: old trace\n\
+                   [2024-01-15 10:00:00,000] WARN new entry (kafka.server)\n\
+                   \tat kafka.Foo.bar(Foo.scala:1)\n\
+                   2024-01-15T10:05:00,000 ERROR iso entry\n";
+        let Some(output) = run_filter("2024-01-15 10:00:00", log) else {
+            return; // awk not available
+        };
+
+        let filtered = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success());
+        assert!(!filtered.contains("old"));
+        assert!(filtered.contains("new entry"));
+        assert!(filtered.contains("Foo.scala"));
+        assert!(filtered.contains("iso entry"));
+    }
+
+    #[test]
+    fn test_timestamp_filter_flags_unparseable_logs() {
+        let Some(output) = run_filter("2024-01-15 10:00:00", "no timestamps here\n") else {
+            return; // awk not available
+        };
+
+        assert!(output.stdout.is_empty());
+        assert_eq!(output.status.code(), Some(NO_TIMESTAMPS_EXIT_CODE));
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("echo hi"), "'echo hi'");
+        assert_eq!(shell_quote("cat '/a b'"), r"'cat '\''/a b'\'''");
+    }
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("2024-01-15T10:00:00Z").unwrap(), cutoff());
+        assert_eq!(parse_since("2024-01-15T12:00:00+02:00").unwrap(), cutoff());
+        assert_eq!(parse_since("2024-01-15 10:00:00").unwrap(), cutoff());
+
+        let age = Utc::now() - parse_since("2h").unwrap();
+        assert!((age.num_seconds() - 7200).abs() < 5);
+
+        assert!(parse_since("yesterday").is_err());
+        assert!(parse_since("5w").is_err());
+        assert!(parse_since("").is_err());
+    }
+}
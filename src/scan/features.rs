@@ -0,0 +1,121 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::sync::OnceLock;
+
+/// Feature controlling the KRaft metadata format and the operations brokers may use
+pub const METADATA_VERSION_FEATURE: &str = "metadata.version";
+
+/// File under `features/` holding the raw `kafka-features.sh describe` output
+pub const KAFKA_FEATURES_FILE: &str = "kafka_features.txt";
+
+/// One feature reported by `kafka-features.sh describe`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeatureLevel {
+    pub name: String,
+    pub supported_min: Option<String>,
+    pub supported_max: Option<String>,
+    /// Level the cluster has finalized; `None` when the feature is not enabled
+    pub finalized: Option<String>,
+    pub epoch: Option<i64>,
+}
+
+fn field_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // "Feature: metadata.version	SupportedMinVersion: 3.0-IV1	SupportedMaxVersion: 3.7-IV4	FinalizedVersionLevel: 3.6-IV2	Epoch: 5"
+    REGEX.get_or_init(|| Regex::new(r"(\w+):\s*(\S+)").expect("valid regex"))
+}
+
+/// Parse the output of `kafka-features.sh describe`, one feature per `Feature:` line
+pub fn parse_kafka_features(output: &str) -> Vec<FeatureLevel> {
+    output.lines()
+        .filter(|line| line.trim_start().starts_with("Feature:"))
+        .filter_map(|line| {
+            let mut feature = FeatureLevel {
+                name: String::new(),
+                supported_min: None,
+                supported_max: None,
+                finalized: None,
+                epoch: None,
+            };
+            for caps in field_regex().captures_iter(line) {
+                // Features that were never finalized print "-"
+                let value = Some(caps[2].to_string()).filter(|v| v != "-");
+                match &caps[1] {
+                    "Feature" => feature.name = caps[2].to_string(),
+                    "SupportedMinVersion" => feature.supported_min = value,
+                    "SupportedMaxVersion" => feature.supported_max = value,
+                    "FinalizedVersionLevel" | "FinalizedMaxVersion" => feature.finalized = value,
+                    "Epoch" => feature.epoch = value.and_then(|v| v.parse().ok()),
+                    _ => {}
+                }
+            }
+            (!feature.name.is_empty()).then_some(feature)
+        })
+        .collect()
+}
+
+/// Kafka release of a metadata version level, e.g. `(3, 6)` for `3.6-IV2`
+pub fn metadata_version_release(level: &str) -> Option<(u32, u32)> {
+    let release = level.split('-').next()?;
+    let mut parts = release.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// Compare two metadata version levels such as `3.6-IV2` and `3.7-IV0`
+pub fn compare_metadata_versions(a: &str, b: &str) -> Option<Ordering> {
+    let iv = |level: &str| -> u32 {
+        level.split_once("-IV")
+            .and_then(|(_, iv)| iv.parse().ok())
+            .unwrap_or(0)
+    };
+    Some(metadata_version_release(a)?.cmp(&metadata_version_release(b)?).then(iv(a).cmp(&iv(b))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIBE_KRAFT: &str = "\
+Feature: kraft.version\tSupportedMinVersion: 0\tSupportedMaxVersion: 1\tFinalizedVersionLevel: 0\tEpoch: 12
+Feature: metadata.version\tSupportedMinVersion: 3.0-IV1\tSupportedMaxVersion: 3.7-IV4\tFinalizedVersionLevel: 3.6-IV2\tEpoch: 12
+";
+
+    #[test]
+    fn test_parse_kafka_features() {
+        let features = parse_kafka_features(DESCRIBE_KRAFT);
+
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[1], FeatureLevel {
+            name: METADATA_VERSION_FEATURE.to_string(),
+            supported_min: Some("3.0-IV1".to_string()),
+            supported_max: Some("3.7-IV4".to_string()),
+            finalized: Some("3.6-IV2".to_string()),
+            epoch: Some(12),
+        });
+        assert_eq!(features[0].finalized.as_deref(), Some("0"));
+    }
+
+    #[test]
+    fn test_parse_unfinalized_and_noise() {
+        let output = "WARNING: some tool warning\n\
+            Feature: metadata.version\tSupportedMinVersion: 3.0-IV1\tSupportedMaxVersion: 3.3-IV3\tFinalizedVersionLevel: -\tEpoch: -\n";
+        let features = parse_kafka_features(output);
+
+        assert_eq!(features.len(), 1);
+        assert_eq!(features[0].finalized, None);
+        assert_eq!(features[0].epoch, None);
+        assert!(parse_kafka_features("Error while executing feature command").is_empty());
+    }
+
+    #[test]
+    fn test_compare_metadata_versions() {
+        assert_eq!(metadata_version_release("3.6-IV2"), Some((3, 6)));
+        assert_eq!(metadata_version_release("latest"), None);
+        assert_eq!(compare_metadata_versions("3.6-IV2", "3.7-IV0"), Some(Ordering::Less));
+        assert_eq!(compare_metadata_versions("3.7-IV4", "3.7-IV1"), Some(Ordering::Greater));
+        assert_eq!(compare_metadata_versions("3.10-IV0", "3.9-IV0"), Some(Ordering::Greater));
+    }
+}
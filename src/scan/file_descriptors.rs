@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// File under a broker's `system` directory the Kafka process's descriptor usage is saved to
+pub const FILE_DESCRIPTORS_FILE: &str = "file_descriptors.json";
+
+/// Open descriptors and limits of a broker's Kafka process, as collected
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileDescriptorUsage {
+    pub pid: u32,
+    /// Entries of `/proc/PID/fd`, or `None` when the directory could not be read
+    #[serde(default)]
+    pub open_fds: Option<u64>,
+    /// Content of `/proc/PID/limits`, empty when it could not be read
+    #[serde(default)]
+    pub limits: String,
+}
+
+/// Count of descriptors from `ls /proc/PID/fd | wc -l`. A running JVM always holds descriptors,
+/// so 0 means the directory was not readable, e.g. permission denied without sudo.
+pub fn parse_open_fds(output: &str) -> Option<u64> {
+    output.trim().parse().ok().filter(|count| *count > 0)
+}
+
+/// Command counting the open descriptors of a process
+pub fn open_fds_command(pid: u32) -> String {
+    format!("sudo ls /proc/{}/fd 2>/dev/null | wc -l", pid)
+}
+
+/// Command printing the resource limits of a process
+pub fn limits_command(pid: u32) -> String {
+    format!("sudo cat /proc/{}/limits 2>/dev/null", pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_open_fds() {
+        assert_eq!(parse_open_fds("  4213\n"), Some(4213));
+        // Permission denied on /proc leaves wc counting nothing
+        assert_eq!(parse_open_fds("0\n"), None);
+        assert_eq!(parse_open_fds(""), None);
+    }
+}
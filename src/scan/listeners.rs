@@ -0,0 +1,181 @@
+use anyhow::Result;
+use std::collections::HashMap;
+
+use crate::scan::types::ListenerInfo;
+
+/// Port used when a broker address or listener does not name one
+pub const DEFAULT_BROKER_PORT: u16 = 9092;
+
+/// Split `host:port`, `[ipv6]:port`, `[ipv6]` or `host` into host and optional port.
+/// A bare IPv6 address without brackets is taken as a host without port.
+pub fn split_host_port(address: &str) -> (String, Option<u16>) {
+    let address = address.trim();
+
+    if let Some(rest) = address.strip_prefix('[') {
+        if let Some((host, after)) = rest.split_once(']') {
+            let port = after.strip_prefix(':').and_then(|p| p.parse().ok());
+            return (host.to_string(), port);
+        }
+    }
+
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => (host.to_string(), port.parse().ok()),
+        _ => (address.to_string(), None),
+    }
+}
+
+/// Parse a `--broker` address, falling back to `default_port` when it has none
+pub fn parse_broker_address(address: &str, default_port: u16) -> Result<(String, u16)> {
+    let (host, port) = split_host_port(address);
+    if host.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Broker address must be in format hostname[:port] or [ipv6][:port], got: {}", address
+        ));
+    }
+    Ok((host, port.unwrap_or(default_port)))
+}
+
+/// `host:port`, with IPv6 hosts in brackets
+pub fn format_address(host: &str, port: u16) -> String {
+    if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+/// Parse a `listeners`/`advertised.listeners` value such as
+/// `CLIENTS://0.0.0.0:9092,CLUSTER://[::1]:9093`
+pub fn parse_listeners(value: &str) -> Vec<ListenerInfo> {
+    value.split(',')
+        .filter_map(|listener| {
+            let (name, address) = listener.trim().split_once("://")?;
+            let (host, port) = split_host_port(address);
+            Some(ListenerInfo {
+                name: name.trim().to_string(),
+                host,
+                port: port?,
+            })
+        })
+        .collect()
+}
+
+/// Parse a `listener.security.protocol.map` value such as `CLIENTS:SASL_SSL,CLUSTER:SSL` into
+/// upper-cased listener names and protocols; entries without a `name:protocol` pair are returned as errors
+pub fn parse_security_protocol_map(value: &str) -> Vec<Result<(String, String), String>> {
+    value.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once(':') {
+            Some((name, protocol)) if !name.trim().is_empty() && !protocol.trim().is_empty() => {
+                Ok((name.trim().to_uppercase(), protocol.trim().to_uppercase()))
+            }
+            _ => Err(entry.to_string()),
+        })
+        .collect()
+}
+
+/// The listeners a broker exposes, with advertised addresses replacing bound ones where set
+pub fn broker_listeners(properties: &HashMap<String, String>) -> Vec<ListenerInfo> {
+    let advertised = properties.get("advertised.listeners").map(|v| parse_listeners(v)).unwrap_or_default();
+    let mut listeners = properties.get("listeners").map(|v| parse_listeners(v)).unwrap_or_default();
+
+    for listener in &mut listeners {
+        if let Some(public) = advertised.iter().find(|a| a.name == listener.name) {
+            *listener = public.clone();
+        }
+    }
+    for public in advertised {
+        if !listeners.iter().any(|l| l.name == public.name) {
+            listeners.push(public);
+        }
+    }
+    listeners
+}
+
+/// The listener clients should use: the first that is neither a controller nor the
+/// inter-broker listener, else the inter-broker one
+pub fn client_listener<'a>(listeners: &'a [ListenerInfo], properties: &HashMap<String, String>) -> Option<&'a ListenerInfo> {
+    let controllers: Vec<&str> = properties.get("controller.listener.names")
+        .map(|names| names.split(',').map(str::trim).collect())
+        .unwrap_or_default();
+    let inter_broker = properties.get("inter.broker.listener.name").map(|name| name.trim());
+
+    let candidates = || listeners.iter().filter(|l| !controllers.contains(&l.name.as_str()));
+    candidates()
+        .find(|l| Some(l.name.as_str()) != inter_broker)
+        .or_else(|| candidates().next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::cluster_detection::parse_server_properties;
+
+    const MULTI_LISTENER_CONFIG: &str = "\
+node.id=13
+process.roles=broker,controller
+controller.listener.names=CONTROLLER
+inter.broker.listener.name=CLUSTER
+listeners=CLUSTER://0.0.0.0:9093,CLIENTS://0.0.0.0:9192,CONTROLLER://0.0.0.0:9094
+advertised.listeners=CLUSTER://kafka-1.internal:9093,CLIENTS://kafka-1.example.com:9192";
+
+    fn listener(name: &str, host: &str, port: u16) -> ListenerInfo {
+        ListenerInfo { name: name.to_string(), host: host.to_string(), port }
+    }
+
+    #[test]
+    fn test_split_host_port() {
+        assert_eq!(split_host_port("kafka-1:9092"), ("kafka-1".to_string(), Some(9092)));
+        assert_eq!(split_host_port("kafka-1"), ("kafka-1".to_string(), None));
+        assert_eq!(split_host_port("[2001:db8::1]:9093"), ("2001:db8::1".to_string(), Some(9093)));
+        assert_eq!(split_host_port("[::1]"), ("::1".to_string(), None));
+        assert_eq!(split_host_port("2001:db8::1"), ("2001:db8::1".to_string(), None));
+
+        assert_eq!(parse_broker_address("kafka-1", 19092).unwrap(), ("kafka-1".to_string(), 19092));
+        assert!(parse_broker_address(":9092", DEFAULT_BROKER_PORT).is_err());
+        assert_eq!(format_address("2001:db8::1", 9092), "[2001:db8::1]:9092");
+    }
+
+    #[test]
+    fn test_parse_multi_listener_config() {
+        let properties = parse_server_properties(MULTI_LISTENER_CONFIG);
+
+        assert_eq!(broker_listeners(&properties), vec![
+            listener("CLUSTER", "kafka-1.internal", 9093),
+            listener("CLIENTS", "kafka-1.example.com", 9192),
+            listener("CONTROLLER", "0.0.0.0", 9094),
+        ]);
+        assert_eq!(
+            parse_listeners("PLAINTEXT://[::1]:9092, SSL://:9093"),
+            vec![listener("PLAINTEXT", "::1", 9092), listener("SSL", "", 9093)]
+        );
+    }
+
+    #[test]
+    fn test_parse_security_protocol_map() {
+        assert_eq!(
+            parse_security_protocol_map(" clients:sasl_ssl, CLUSTER:SSL,,BROKEN ,EMPTY:"),
+            vec![
+                Ok(("CLIENTS".to_string(), "SASL_SSL".to_string())),
+                Ok(("CLUSTER".to_string(), "SSL".to_string())),
+                Err("BROKEN".to_string()),
+                Err("EMPTY:".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_client_listener() {
+        let properties = parse_server_properties(MULTI_LISTENER_CONFIG);
+        let listeners = broker_listeners(&properties);
+        assert_eq!(client_listener(&listeners, &properties), Some(&listener("CLIENTS", "kafka-1.example.com", 9192)));
+
+        // With only the inter-broker and controller listeners, clients share the inter-broker one
+        let properties = parse_server_properties(
+            "controller.listener.names=CONTROLLER\ninter.broker.listener.name=PLAINTEXT\nlisteners=PLAINTEXT://:9092,CONTROLLER://:9094"
+        );
+        let listeners = broker_listeners(&properties);
+        assert_eq!(client_listener(&listeners, &properties).map(|l| l.port), Some(9092));
+    }
+}
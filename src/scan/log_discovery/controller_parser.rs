@@ -0,0 +1,182 @@
+use chrono::NaiveDateTime;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Shortest window an election rate is computed over, so a few elections logged within
+/// minutes, e.g. during a rolling restart, are not extrapolated to an hourly rate
+const MIN_RATE_WINDOW_SECS: f64 = 3600.0;
+
+/// One partition leader election read from a controller log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderElection {
+    /// Time of the log line, if it has the log4j timestamp
+    pub timestamp: Option<NaiveDateTime>,
+    /// Topic partition, e.g. `orders-3`
+    pub partition: String,
+}
+
+/// Election statistics of one controller log
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ElectionMetrics {
+    pub election_count: usize,
+    /// Seconds between the first and the last timestamped line of the log
+    pub span_secs: Option<f64>,
+    /// Elections per hour over `span_secs`, at least an hour
+    pub elections_per_hour: Option<f64>,
+    /// Elections per partition, most elected first
+    pub partitions: Vec<(String, usize)>,
+}
+
+impl ElectionMetrics {
+    pub fn from_log(content: &str) -> Self {
+        let elections = ControllerLogParser::parse(content);
+        let timestamps: Vec<NaiveDateTime> = content.lines().filter_map(line_timestamp).collect();
+        let span_secs = match (timestamps.iter().min(), timestamps.iter().max()) {
+            (Some(first), Some(last)) => Some((*last - *first).num_milliseconds() as f64 / 1000.0),
+            _ => None,
+        };
+
+        let mut per_partition: HashMap<&str, usize> = HashMap::new();
+        for election in &elections {
+            *per_partition.entry(election.partition.as_str()).or_insert(0) += 1;
+        }
+        let mut partitions: Vec<(String, usize)> = per_partition.into_iter()
+            .map(|(partition, count)| (partition.to_string(), count))
+            .collect();
+        partitions.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Self {
+            election_count: elections.len(),
+            span_secs,
+            elections_per_hour: span_secs.map(|span| elections.len() as f64 / span.max(MIN_RATE_WINDOW_SECS) * 3600.0),
+            partitions,
+        }
+    }
+}
+
+fn timestamp_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // "[2024-05-01 10:00:00,123] INFO ..."
+    REGEX.get_or_init(|| Regex::new(r"^\[(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2})[,.]\d+\]").expect("valid regex"))
+}
+
+fn line_timestamp(line: &str) -> Option<NaiveDateTime> {
+    let caps = timestamp_regex().captures(line)?;
+    NaiveDateTime::parse_from_str(&caps[1], "%Y-%m-%d %H:%M:%S").ok()
+}
+
+fn zk_regexes() -> &'static [Regex; 3] {
+    static REGEXES: OnceLock<[Regex; 3]> = OnceLock::new();
+    REGEXES.get_or_init(|| [
+        // "[Controller id=1] Starting replica leader election (PREFERRED) for partitions orders-3,orders-4 triggered by AutoTriggered"
+        Regex::new(r"Starting replica leader election \(\w+\) for partitions (.*?) triggered by").expect("valid regex"),
+        // Before Kafka 2.4: "[Controller id=1] Partition orders-3 completed preferred replica leader election. New leader is 2"
+        Regex::new(r"Partition (\S+) completed preferred replica leader election").expect("valid regex"),
+        // Offline partitions brought back by the partition state machine:
+        // "[Controller id=1 epoch=5] Changed partition orders-3 from OfflinePartition to OnlinePartition with state LeaderAndIsr(leader=2, ...)"
+        Regex::new(r"Changed partition (\S+) from OfflinePartition to OnlinePartition").expect("valid regex"),
+    ])
+}
+
+fn kraft_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // "[QuorumController id=3000] partition change for orders-3 with topic ID abc: leader: 1 -> 2, leaderEpoch: 5 -> 6"
+    // logged at debug, and at info when prefixed with UNCLEAN
+    REGEX.get_or_init(|| Regex::new(r"(?i)partition change for (\S+) with topic ID \S+: .*?\bleader: (-?\d+) -> (-?\d+)").expect("valid regex"))
+}
+
+/// Parses leader elections from ZooKeeper-mode (`KafkaController`) and KRaft (`QuorumController`) controller logs
+pub struct ControllerLogParser;
+
+impl ControllerLogParser {
+    /// Parse one line, returning the elections it records; ZooKeeper-mode batches name several partitions
+    pub fn parse_line(line: &str) -> Vec<LeaderElection> {
+        let timestamp = line_timestamp(line);
+        let election = |partition: &str| LeaderElection { timestamp, partition: partition.to_string() };
+
+        let [batch, preferred, offline] = zk_regexes();
+        if let Some(caps) = batch.captures(line) {
+            return caps[1].split(',')
+                .map(|partition| partition.trim())
+                .filter(|partition| !partition.is_empty())
+                .map(election)
+                .collect();
+        }
+        if let Some(caps) = preferred.captures(line).or_else(|| offline.captures(line)) {
+            return vec![election(&caps[1])];
+        }
+        if let Some(caps) = kraft_regex().captures(line) {
+            // A new leader of -1 takes the partition offline rather than electing anyone
+            if caps[3] != caps[2] && !caps[3].starts_with('-') {
+                return vec![election(&caps[1])];
+            }
+        }
+        Vec::new()
+    }
+
+    /// Every election in a controller log, in order
+    pub fn parse(content: &str) -> Vec<LeaderElection> {
+        content.lines().flat_map(Self::parse_line).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZK_CONTROLLER_LOG: &str = "\
+[2024-05-01 10:00:00,001] INFO [Controller id=1] Processing automatic preferred replica leader election (kafka.controller.KafkaController)
+[2024-05-01 10:00:00,010] INFO [Controller id=1] Starting replica leader election (PREFERRED) for partitions orders-3,orders-4 triggered by AutoTriggered (kafka.controller.KafkaController)
+[2024-05-01 10:20:00,500] INFO [Controller id=1 epoch=5] Changed partition orders-3 from OfflinePartition to OnlinePartition with state LeaderAndIsr(leader=2, leaderEpoch=9, isr=List(2), zkVersion=14) (state.change.logger)
+[2024-05-01 10:20:00,600] INFO [Controller id=1 epoch=5] Changed partition payments-0 from NewPartition to OnlinePartition with state LeaderAndIsr(leader=1, leaderEpoch=0, isr=List(1, 2), zkVersion=0) (state.change.logger)
+[2024-05-01 10:40:00,000] INFO [Controller id=1] Partition payments-1 completed preferred replica leader election. New leader is 3 (kafka.controller.KafkaController)
+[2024-05-01 12:00:00,000] DEBUG [Controller id=1] Topics not in preferred replica for broker 2 Map() (kafka.controller.KafkaController)
+";
+
+    const KRAFT_CONTROLLER_LOG: &str = "\
+[2024-05-01 10:00:00,000] INFO [QuorumController id=3000] Becoming the active controller at epoch 7 (org.apache.kafka.controller.QuorumController)
+[2024-05-01 10:05:00,000] DEBUG [QuorumController id=3000] partition change for orders-3 with topic ID Tzx3fTq1SbmrTQbYsNvSBA: leader: 1 -> 2, leaderEpoch: 5 -> 6, partitionEpoch: 9 -> 10 (org.apache.kafka.controller.ReplicationControlManager)
+[2024-05-01 10:05:01,000] DEBUG [QuorumController id=3000] partition change for orders-4 with topic ID Tzx3fTq1SbmrTQbYsNvSBA: isr: [1, 2, 3] -> [1, 2], partitionEpoch: 4 -> 5 (org.apache.kafka.controller.ReplicationControlManager)
+[2024-05-01 10:05:02,000] DEBUG [QuorumController id=3000] partition change for orders-5 with topic ID Tzx3fTq1SbmrTQbYsNvSBA: leader: 3 -> -1, leaderEpoch: 2 -> 3 (org.apache.kafka.controller.ReplicationControlManager)
+[2024-05-01 10:30:00,000] INFO [QuorumController id=3000] UNCLEAN partition change for orders-5 with topic ID Tzx3fTq1SbmrTQbYsNvSBA: leader: -1 -> 2, leaderEpoch: 3 -> 4 (org.apache.kafka.controller.ReplicationControlManager)
+";
+
+    #[test]
+    fn test_zookeeper_controller_elections() {
+        let elections = ControllerLogParser::parse(ZK_CONTROLLER_LOG);
+        let partitions: Vec<&str> = elections.iter().map(|e| e.partition.as_str()).collect();
+
+        // Topic creation (NewPartition -> OnlinePartition) is not an election
+        assert_eq!(partitions, vec!["orders-3", "orders-4", "orders-3", "payments-1"]);
+        assert_eq!(
+            elections[0].timestamp,
+            NaiveDateTime::parse_from_str("2024-05-01 10:00:00", "%Y-%m-%d %H:%M:%S").ok()
+        );
+    }
+
+    #[test]
+    fn test_kraft_controller_elections() {
+        let elections = ControllerLogParser::parse(KRAFT_CONTROLLER_LOG);
+        let partitions: Vec<&str> = elections.iter().map(|e| e.partition.as_str()).collect();
+
+        // ISR-only changes and a partition going offline are not elections
+        assert_eq!(partitions, vec!["orders-3", "orders-5"]);
+    }
+
+    #[test]
+    fn test_election_metrics() {
+        let metrics = ElectionMetrics::from_log(ZK_CONTROLLER_LOG);
+        assert_eq!(metrics.election_count, 4);
+        assert_eq!(metrics.span_secs, Some(7200.0));
+        assert_eq!(metrics.elections_per_hour, Some(2.0));
+        assert_eq!(metrics.partitions[0], ("orders-3".to_string(), 2));
+
+        // Half an hour of log is rated over a full hour
+        let metrics = ElectionMetrics::from_log(KRAFT_CONTROLLER_LOG);
+        assert_eq!(metrics.elections_per_hour, Some(2.0));
+
+        assert_eq!(ElectionMetrics::from_log("no timestamps here").elections_per_hour, None);
+    }
+}
@@ -0,0 +1,199 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// One stop-the-world pause read from a GC log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GcPause {
+    /// JVM uptime in seconds when the pause was logged, if the line records it
+    pub uptime_secs: Option<f64>,
+    pub duration_ms: f64,
+    /// Pause kind as logged, e.g. `Young (Normal)`, `Full`, `CMS Final Remark`
+    pub kind: String,
+}
+
+/// Pause statistics of one GC log
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GcMetrics {
+    pub pause_count: usize,
+    pub total_pause_ms: f64,
+    pub max_pause_ms: f64,
+    /// Pauses longer than the threshold given to `GcMetrics::from_pauses`
+    pub long_pause_count: usize,
+    /// Seconds of JVM uptime between the first and the last pause
+    pub span_secs: Option<f64>,
+    /// Share of `span_secs` spent paused, in percent
+    pub gc_time_percent: Option<f64>,
+    /// Pauses per minute over `span_secs`
+    pub pauses_per_minute: Option<f64>,
+}
+
+impl GcMetrics {
+    pub fn from_pauses(pauses: &[GcPause], long_pause_ms: f64) -> Self {
+        let total_pause_ms: f64 = pauses.iter().map(|p| p.duration_ms).sum();
+        let uptimes: Vec<f64> = pauses.iter().filter_map(|p| p.uptime_secs).collect();
+        let span_secs = match (uptimes.first(), uptimes.last()) {
+            (Some(first), Some(last)) if last > first => Some(last - first),
+            _ => None,
+        };
+
+        Self {
+            pause_count: pauses.len(),
+            total_pause_ms,
+            max_pause_ms: pauses.iter().map(|p| p.duration_ms).fold(0.0, f64::max),
+            long_pause_count: pauses.iter().filter(|p| p.duration_ms > long_pause_ms).count(),
+            span_secs,
+            gc_time_percent: span_secs.map(|span| total_pause_ms / 1000.0 / span * 100.0),
+            pauses_per_minute: span_secs.map(|span| pauses.len() as f64 / span * 60.0),
+        }
+    }
+}
+
+fn unified_regexes() -> &'static [Regex; 2] {
+    static REGEXES: OnceLock<[Regex; 2]> = OnceLock::new();
+    REGEXES.get_or_init(|| [
+        // "[...][12.345s][info][gc] GC(42) Pause Young (Normal) (G1 Evacuation Pause) 512M->128M(1024M) 15.234ms"
+        Regex::new(r"GC\(\d+\) Pause (.+?)(?: \d+[KMGT]?->\d+[KMGT]?\(\d+[KMGT]?\))? ([\d.]+)ms\s*$").expect("valid regex"),
+        // Uptime decoration: "[12.345s]"
+        Regex::new(r"\[(\d+\.\d+)s\]").expect("valid regex"),
+    ])
+}
+
+fn legacy_regexes() -> &'static [Regex; 3] {
+    static REGEXES: OnceLock<[Regex; 3]> = OnceLock::new();
+    REGEXES.get_or_init(|| [
+        // "12.345: [GC pause (G1 Evacuation Pause) (young), 0.0152340 secs]",
+        // "12.345: [GC (Allocation Failure) 12.345: [ParNew: ...], 0.0124567 secs] [Times: ...]"
+        Regex::new(r"\[(Full GC|GC)\s*([^\[,]*)").expect("valid regex"),
+        // Pause time of the outermost event, the last one before any "[Times: ...]"
+        Regex::new(r"[, ]([\d.]+) secs\]").expect("valid regex"),
+        // Uptime before the event: "2024-01-15T10:00:00.123+0000: 12.345: [GC"
+        Regex::new(r"(?:^|: )(\d+\.\d+): \[(?:Full GC|GC)").expect("valid regex"),
+    ])
+}
+
+/// Parses G1, CMS and Parallel GC logs in JDK 9+ unified logging and legacy (`-Xloggc`) formats
+pub struct GcLogParser;
+
+impl GcLogParser {
+    /// Parse one line, returning the pause it records if any
+    pub fn parse_line(line: &str) -> Option<GcPause> {
+        Self::parse_unified(line).or_else(|| Self::parse_legacy(line))
+    }
+
+    /// Every pause in a GC log, in order
+    pub fn parse(content: &str) -> Vec<GcPause> {
+        content.lines().filter_map(Self::parse_line).collect()
+    }
+
+    fn parse_unified(line: &str) -> Option<GcPause> {
+        let [pause, uptime] = unified_regexes();
+        let caps = pause.captures(line)?;
+        // Keep the young/mixed subtype, e.g. "Young (Normal)", and drop the logged cause
+        let kind = match caps[1].trim().split_once(" (") {
+            Some((name, rest)) if name == "Young" || name == "Mixed" => {
+                format!("{} ({})", name, rest.split(')').next().unwrap_or(rest))
+            }
+            Some((name, _)) => name.to_string(),
+            None => caps[1].trim().to_string(),
+        };
+        Some(GcPause {
+            uptime_secs: uptime.captures(line).and_then(|c| c[1].parse().ok()),
+            duration_ms: caps[2].parse().ok()?,
+            kind,
+        })
+    }
+
+    fn parse_legacy(line: &str) -> Option<GcPause> {
+        // Concurrent CMS/G1 phases run alongside the application and are not pauses
+        if line.contains("concurrent") || line.contains("Concurrent") {
+            return None;
+        }
+        let [event, secs, uptime] = legacy_regexes();
+        let caps = event.captures(line)?;
+        let duration_secs: f64 = secs.captures_iter(line.split("[Times:").next().unwrap_or(line))
+            .last()?[1]
+            .parse()
+            .ok()?;
+
+        let detail = caps[2].trim().trim_start_matches("pause").trim();
+        let kind = if &caps[1] == "Full GC" {
+            "Full".to_string()
+        } else {
+            detail.trim_start_matches('(').split(')').next().unwrap_or(detail).to_string()
+        };
+
+        Some(GcPause {
+            uptime_secs: uptime.captures(line).and_then(|c| c[1].parse().ok()),
+            duration_ms: duration_secs * 1000.0,
+            kind,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIFIED_G1: &str = "\
+[2024-01-15T10:00:00.100+0000][10.100s][info][gc,start    ] GC(7) Pause Young (Normal) (G1 Evacuation Pause)
+[2024-01-15T10:00:00.115+0000][10.115s][info][gc          ] GC(7) Pause Young (Normal) (G1 Evacuation Pause) 512M->128M(1024M) 15.234ms
+[2024-01-15T10:00:05.000+0000][15.000s][info][gc          ] GC(8) Concurrent Mark Cycle 120.500ms
+[2024-01-15T10:00:05.200+0000][15.200s][info][gc          ] GC(8) Pause Remark 600M->590M(1024M) 4.100ms
+[2024-01-15T10:01:40.000+0000][110.000s][info][gc          ] GC(9) Pause Full (G1 Compaction Pause) 1000M->400M(1024M) 1850.000ms";
+
+    const LEGACY_CMS: &str = "\
+2024-01-15T10:00:00.123+0000: 12.345: [GC (Allocation Failure) 12.345: [ParNew: 1000K->100K(2000K), 0.0123456 secs] 5000K->4000K(10000K), 0.0124567 secs] [Times: user=0.04 sys=0.00, real=0.01 secs]
+2024-01-15T10:00:01.000+0000: 13.000: [GC (CMS Initial Mark) [1 CMS-initial-mark: 4000K(8000K)] 4100K(10000K), 0.0020000 secs] [Times: user=0.01 sys=0.00, real=0.00 secs]
+2024-01-15T10:00:01.010+0000: 13.010: [CMS-concurrent-mark-start]
+2024-01-15T10:00:02.000+0000: 14.000: [CMS-concurrent-mark: 0.900/0.990 secs] [Times: user=1.00 sys=0.01, real=0.99 secs]
+2024-01-15T10:00:12.345+0000: 24.345: [Full GC (Allocation Failure)  9000K->3000K(10000K), 2.3456789 secs]";
+
+    #[test]
+    fn test_parse_unified_g1_log() {
+        let pauses = GcLogParser::parse(UNIFIED_G1);
+
+        assert_eq!(pauses.len(), 3);
+        assert_eq!(pauses[0], GcPause { uptime_secs: Some(10.115), duration_ms: 15.234, kind: "Young (Normal)".to_string() });
+        assert_eq!(pauses[1].kind, "Remark");
+        assert_eq!(pauses[2].kind, "Full");
+        assert_eq!(pauses[2].duration_ms, 1850.0);
+    }
+
+    #[test]
+    fn test_parse_legacy_g1_line() {
+        let pause = GcLogParser::parse_line(
+            "2024-01-15T10:00:00.123+0000: 12.345: [GC pause (G1 Evacuation Pause) (young), 0.0152340 secs]"
+        ).unwrap();
+        assert_eq!(pause.kind, "G1 Evacuation Pause");
+        assert_eq!(pause.uptime_secs, Some(12.345));
+        assert!((pause.duration_ms - 15.234).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_legacy_cms_log() {
+        let pauses = GcLogParser::parse(LEGACY_CMS);
+
+        let kinds: Vec<&str> = pauses.iter().map(|p| p.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["Allocation Failure", "CMS Initial Mark", "Full"]);
+        // The young collection reports the whole pause, not only the ParNew phase
+        assert!((pauses[0].duration_ms - 12.4567).abs() < 1e-9);
+        assert!((pauses[2].duration_ms - 2345.6789).abs() < 1e-9);
+        assert_eq!(pauses[2].uptime_secs, Some(24.345));
+    }
+
+    #[test]
+    fn test_gc_metrics() {
+        let metrics = GcMetrics::from_pauses(&GcLogParser::parse(UNIFIED_G1), 1000.0);
+
+        assert_eq!(metrics.pause_count, 3);
+        assert_eq!(metrics.long_pause_count, 1);
+        assert_eq!(metrics.max_pause_ms, 1850.0);
+        assert!((metrics.span_secs.unwrap() - 99.885).abs() < 1e-9);
+        assert!((metrics.gc_time_percent.unwrap() - 1.8715).abs() < 1e-3);
+
+        let empty = GcMetrics::from_pauses(&[], 1000.0);
+        assert_eq!(empty.pause_count, 0);
+        assert_eq!(empty.gc_time_percent, None);
+    }
+}
@@ -1,6 +1,6 @@
 use anyhow::Result;
 use std::collections::HashMap;
-// PathBuf not needed here
+use std::path::Path;
 use tracing::{info, warn};
 
 use crate::llm::{LlmService, service::ChatMessage};
@@ -11,8 +11,9 @@ use super::log4j_parser::Log4jParser;
 pub struct LlmLogAnalyzer;
 
 impl LlmLogAnalyzer {
-    /// Analyze log4j configuration using LLM
+    /// Analyze log4j configuration using LLM, falling back to parsing the file at `config_path`
     pub async fn analyze(
+        config_path: &Path,
         log4j_content: &str,
         env_vars: &HashMap<String, String>,
     ) -> Result<LogOutputInfo> {
@@ -30,24 +31,24 @@ impl LlmLogAnalyzer {
                         match Log4jParser::parse_llm_response(&llm_response) {
                             Ok(result) if result.log_files.is_empty() => {
                                 warn!("LLM found no log files, falling back to basic parsing");
-                                Log4jParser::parse(log4j_content, env_vars)
+                                Log4jParser::parse_file(config_path, log4j_content, env_vars)
                             }
                             Ok(result) => Ok(result),
                             Err(e) => {
                                 warn!("LLM response parsing failed: {}, falling back to basic parsing", e);
-                                Log4jParser::parse(log4j_content, env_vars)
+                                Log4jParser::parse_file(config_path, log4j_content, env_vars)
                             }
                         }
                     }
                     Err(e) => {
                         warn!("LLM analysis failed: {}, falling back to basic parsing", e);
-                        Log4jParser::parse(log4j_content, env_vars)
+                        Log4jParser::parse_file(config_path, log4j_content, env_vars)
                     }
                 }
             }
             Err(e) => {
                 warn!("LLM service not available: {}, using basic parsing", e);
-                Log4jParser::parse(log4j_content, env_vars)
+                Log4jParser::parse_file(config_path, log4j_content, env_vars)
             }
         }
     }
@@ -1,6 +1,8 @@
 use anyhow::Result;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::types::{LogOutputInfo, LogFileLocation};
 
@@ -8,10 +10,47 @@ use super::types::{LogOutputInfo, LogFileLocation};
 pub struct Log4jParser;
 
 impl Log4jParser {
-    /// Parse log4j configuration to extract log output information
+    /// Parse log4j configuration to extract log output information.
+    ///
+    /// Both `log4j.properties` and `log4j2.xml` formats are supported; the
+    /// format is detected from the content.
     pub fn parse(log4j_content: &str, env_vars: &HashMap<String, String>) -> Result<LogOutputInfo> {
+        if Self::is_xml_content(log4j_content) {
+            Self::parse_xml(log4j_content, env_vars)
+        } else {
+            Self::parse_properties(log4j_content, env_vars)
+        }
+    }
+
+    /// Parse a log4j configuration file, detecting the format from the file
+    /// extension first and falling back to content sniffing
+    pub fn parse_file(
+        config_path: &Path,
+        log4j_content: &str,
+        env_vars: &HashMap<String, String>,
+    ) -> Result<LogOutputInfo> {
+        match config_path.extension().and_then(|ext| ext.to_str()) {
+            Some("xml") => Self::parse_xml(log4j_content, env_vars),
+            Some("properties") => Self::parse_properties(log4j_content, env_vars),
+            _ => Self::parse(log4j_content, env_vars),
+        }
+    }
+
+    /// Check whether the configuration content looks like a log4j2 XML file
+    fn is_xml_content(content: &str) -> bool {
+        let trimmed = content.trim_start();
+        trimmed.starts_with("<?xml") || trimmed.starts_with("<Configuration")
+    }
+
+    /// Parse `log4j.properties` key=value configuration
+    fn parse_properties(log4j_content: &str, env_vars: &HashMap<String, String>) -> Result<LogOutputInfo> {
         let mut log_files = Vec::new();
         let mut uses_stdout = false;
+
+        // Properties defined in the file can be referenced by appenders; system
+        // properties and environment values take precedence, as in log4j itself
+        let mut variables = Self::collect_properties(log4j_content);
+        variables.extend(env_vars.iter().map(|(k, v)| (k.clone(), v.clone())));
         
         for line in log4j_content.lines() {
             let line = line.trim();
@@ -19,19 +58,9 @@ impl Log4jParser {
             // Look for file appenders
             if line.contains("appender") && line.contains(".File=") {
                 if let Some(eq_pos) = line.find('=') {
-                    let mut file_path = line[eq_pos + 1..].trim().to_string();
-                    
-                    // Resolve environment variables
-                    file_path = Self::resolve_env_vars(&file_path, env_vars);
-                    
-                    let log_type = Self::determine_log_type(&file_path);
+                    let file_path = line[eq_pos + 1..].trim();
                     let appender_name = Self::extract_appender_name(line);
-                    
-                    log_files.push(LogFileLocation {
-                        path: PathBuf::from(file_path),
-                        log_type,
-                        appender_name,
-                    });
+                    Self::add_log_file(&mut log_files, file_path, appender_name, &variables);
                 }
             }
             
@@ -49,13 +78,130 @@ impl Log4jParser {
         })
     }
 
+    /// Parse `log4j2.xml` configuration.
+    ///
+    /// Collects `<Property>` values (e.g. `kafka.logs.dir`) so they can be used
+    /// to resolve the `fileName` attribute of `<File>` and `<RollingFile>` appenders.
+    fn parse_xml(log4j_content: &str, env_vars: &HashMap<String, String>) -> Result<LogOutputInfo> {
+        let mut reader = Reader::from_str(log4j_content);
+        reader.config_mut().trim_text(true);
+
+        let mut properties = env_vars.clone();
+        let mut appenders: Vec<(String, String)> = Vec::new();
+        let mut current_property: Option<String> = None;
+        let mut uses_stdout = false;
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) if e.local_name().as_ref() == b"Property" => {
+                    current_property = Self::xml_attribute(&e, "name");
+                }
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                    match e.local_name().as_ref() {
+                        b"File" | b"RollingFile" | b"RollingRandomAccessFile" | b"RandomAccessFile" => {
+                            if let Some(file_name) = Self::xml_attribute(&e, "fileName") {
+                                let appender_name = Self::xml_attribute(&e, "name")
+                                    .unwrap_or_else(|| "detected".to_string());
+                                appenders.push((file_name, appender_name));
+                            }
+                        }
+                        b"Console" => {
+                            uses_stdout = true;
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::Text(t)) => {
+                    if let Some(name) = current_property.take() {
+                        let value = t.unescape()?.trim().to_string();
+                        // Explicit environment values take precedence over defaults
+                        properties.entry(name).or_insert(value);
+                    }
+                }
+                Ok(Event::End(_)) => {
+                    current_property = None;
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    return Err(anyhow::anyhow!(
+                        "Invalid log4j2 XML at position {}: {}",
+                        reader.error_position(),
+                        e
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        let mut log_files = Vec::new();
+        for (file_name, appender_name) in appenders {
+            // log4j2 lookups such as ${sys:kafka.logs.dir} resolve like plain properties
+            let file_name = file_name.replace("${sys:", "${").replace("${env:", "${");
+            Self::add_log_file(&mut log_files, &file_name, appender_name, &properties);
+        }
+
+        Ok(LogOutputInfo {
+            log_files,
+            uses_stdout,
+            uses_journald: uses_stdout, // If using stdout, it goes to journald
+            log4j_analysis: "Basic parsing (log4j2 XML)".to_string(),
+        })
+    }
+
+    /// Read an attribute value from an XML element
+    fn xml_attribute(element: &BytesStart, name: &str) -> Option<String> {
+        element
+            .try_get_attribute(name)
+            .ok()
+            .flatten()
+            .and_then(|attr| attr.unescape_value().ok())
+            .map(|value| value.into_owned())
+    }
+
+    /// Resolve a configured log file path and record it
+    fn add_log_file(
+        log_files: &mut Vec<LogFileLocation>,
+        file_path: &str,
+        appender_name: String,
+        env_vars: &HashMap<String, String>,
+    ) {
+        let file_path = Self::resolve_env_vars(file_path, env_vars);
+        let log_type = Self::determine_log_type(&file_path);
+
+        log_files.push(LogFileLocation {
+            path: PathBuf::from(file_path),
+            log_type,
+            appender_name,
+        });
+    }
+
+    /// Collect key=value definitions from a properties file
+    fn collect_properties(content: &str) -> HashMap<String, String> {
+        let mut properties = HashMap::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+                continue;
+            }
+
+            if let Some(eq_pos) = line.find('=') {
+                let key = line[..eq_pos].trim();
+                if !key.is_empty() {
+                    properties.insert(key.to_string(), line[eq_pos + 1..].trim().to_string());
+                }
+            }
+        }
+
+        properties
+    }
+
     /// Resolve environment variables in paths
     fn resolve_env_vars(path: &str, env_vars: &HashMap<String, String>) -> String {
-        let mut resolved_path = path.to_string();
-        
-        // Resolve known environment variables
+        let mut resolved_path = Self::substitute_vars(path, env_vars, &mut Vec::new());
+
+        // Resolve bare $VAR references
         for (key, value) in env_vars {
-            resolved_path = resolved_path.replace(&format!("${{{}}}", key), value);
             resolved_path = resolved_path.replace(&format!("${}", key), value);
         }
         
@@ -78,6 +224,40 @@ impl Log4jParser {
         resolved_path
     }
 
+    /// Recursively substitute `${var}` references.
+    ///
+    /// `resolving` holds the variables currently being expanded; a reference back
+    /// to one of them is left untouched instead of recursing forever.
+    fn substitute_vars(value: &str, vars: &HashMap<String, String>, resolving: &mut Vec<String>) -> String {
+        let mut result = String::new();
+        let mut rest = value;
+
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+
+            let Some(end) = after.find('}') else {
+                // Unterminated reference, keep as is
+                result.push_str(&rest[start..]);
+                return result;
+            };
+
+            let name = &after[..end];
+            match vars.get(name) {
+                Some(var_value) if !resolving.iter().any(|n| n == name) => {
+                    resolving.push(name.to_string());
+                    result.push_str(&Self::substitute_vars(var_value, vars, resolving));
+                    resolving.pop();
+                }
+                _ => result.push_str(&rest[start..start + 2 + end + 1]),
+            }
+            rest = &after[end + 1..];
+        }
+
+        result.push_str(rest);
+        result
+    }
+
     /// Determine log type based on file path
     fn determine_log_type(file_path: &str) -> String {
         if file_path.contains("server") {
@@ -254,6 +434,91 @@ log4j.appender.serverAppender.File=/var/log/kafka/server.log
         assert!(result.uses_journald);
     }
 
+    const SAMPLE_LOG4J2_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Configuration status="WARN">
+  <Properties>
+    <Property name="kafka.logs.dir">/var/log/kafka</Property>
+    <Property name="logPattern">[%d] %p %m (%c)%n</Property>
+  </Properties>
+  <Appenders>
+    <Console name="STDOUT" target="SYSTEM_OUT">
+      <PatternLayout pattern="${logPattern}"/>
+    </Console>
+    <RollingFile name="KafkaAppender"
+                 fileName="${sys:kafka.logs.dir}/server.log"
+                 filePattern="${sys:kafka.logs.dir}/server.log.%d{yyyy-MM-dd-HH}">
+      <PatternLayout pattern="${logPattern}"/>
+      <TimeBasedTriggeringPolicy/>
+    </RollingFile>
+    <RollingFile name="ControllerAppender"
+                 fileName="${kafka.logs.dir}/controller.log"
+                 filePattern="${kafka.logs.dir}/controller.log.%d{yyyy-MM-dd-HH}">
+      <PatternLayout pattern="${logPattern}"/>
+    </RollingFile>
+    <File name="StateChangeAppender" fileName="${kafka.logs.dir}/state-change.log"/>
+  </Appenders>
+  <Loggers>
+    <Root level="INFO">
+      <AppenderRef ref="STDOUT"/>
+      <AppenderRef ref="KafkaAppender"/>
+    </Root>
+  </Loggers>
+</Configuration>
+"#;
+
+    #[test]
+    fn test_parse_log4j2_xml() {
+        let env_vars = HashMap::new();
+
+        let result = Log4jParser::parse(SAMPLE_LOG4J2_XML, &env_vars).unwrap();
+
+        assert_eq!(result.log_files.len(), 3);
+        assert_eq!(result.log_files[0].path, PathBuf::from("/var/log/kafka/server.log"));
+        assert_eq!(result.log_files[0].log_type, "server");
+        assert_eq!(result.log_files[0].appender_name, "KafkaAppender");
+        assert_eq!(result.log_files[1].path, PathBuf::from("/var/log/kafka/controller.log"));
+        assert_eq!(result.log_files[1].log_type, "controller");
+        assert_eq!(result.log_files[1].appender_name, "ControllerAppender");
+        assert_eq!(result.log_files[2].path, PathBuf::from("/var/log/kafka/state-change.log"));
+        assert_eq!(result.log_files[2].log_type, "state-change");
+        assert!(result.uses_stdout);
+        assert!(result.uses_journald);
+    }
+
+    #[test]
+    fn test_parse_log4j2_xml_env_overrides_property() {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("kafka.logs.dir".to_string(), "/data/kafka-logs".to_string());
+
+        let result = Log4jParser::parse(SAMPLE_LOG4J2_XML, &env_vars).unwrap();
+
+        assert_eq!(result.log_files[0].path, PathBuf::from("/data/kafka-logs/server.log"));
+    }
+
+    #[test]
+    fn test_parse_file_detects_format_by_extension() {
+        let env_vars = HashMap::new();
+        let xml_without_prolog = SAMPLE_LOG4J2_XML.replace(r#"<?xml version="1.0" encoding="UTF-8"?>"#, "");
+
+        let xml = Log4jParser::parse_file(Path::new("/etc/kafka/log4j2.xml"), &xml_without_prolog, &env_vars).unwrap();
+        assert_eq!(xml.log_files.len(), 3);
+
+        let properties = Log4jParser::parse_file(
+            Path::new("/etc/kafka/log4j.properties"),
+            "log4j.appender.kafkaAppender.File=/var/log/kafka/server.log",
+            &env_vars,
+        ).unwrap();
+        assert_eq!(properties.log_files.len(), 1);
+        assert_eq!(properties.log_files[0].appender_name, "kafkaAppender");
+    }
+
+    #[test]
+    fn test_parse_log4j2_xml_invalid() {
+        let env_vars = HashMap::new();
+        let result = Log4jParser::parse("<?xml version=\"1.0\"?><Configuration><Appenders></Configuration>", &env_vars);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_resolve_env_vars() {
         let mut env_vars = HashMap::new();
@@ -265,6 +530,57 @@ log4j.appender.serverAppender.File=/var/log/kafka/server.log
         assert_eq!(resolved, "/custom/log/path/myapp.log");
     }
 
+    #[test]
+    fn test_parse_log4j_with_in_file_logs_dir() {
+        let log4j_content = r#"
+kafka.logs.dir=/data/kafka/logs
+log4j.appender.kafkaAppender=org.apache.log4j.DailyRollingFileAppender
+log4j.appender.kafkaAppender.File=${kafka.logs.dir}/server.log
+log4j.appender.stateChangeAppender.File=${kafka.logs.dir}/state-change.log
+"#;
+        let env_vars = HashMap::new();
+
+        let result = Log4jParser::parse(log4j_content, &env_vars).unwrap();
+
+        assert_eq!(result.log_files.len(), 2);
+        assert_eq!(result.log_files[0].path, PathBuf::from("/data/kafka/logs/server.log"));
+        assert_eq!(result.log_files[1].path, PathBuf::from("/data/kafka/logs/state-change.log"));
+    }
+
+    #[test]
+    fn test_parse_log4j_jvm_arg_overrides_in_file_property() {
+        let log4j_content = r#"
+kafka.logs.dir=/data/kafka/logs
+log4j.appender.kafkaAppender.File=${kafka.logs.dir}/server.log
+"#;
+        let mut jvm_args = HashMap::new();
+        jvm_args.insert("kafka.logs.dir".to_string(), "/var/log/kafka".to_string());
+
+        let result = Log4jParser::parse(log4j_content, &jvm_args).unwrap();
+
+        assert_eq!(result.log_files[0].path, PathBuf::from("/var/log/kafka/server.log"));
+    }
+
+    #[test]
+    fn test_resolve_env_vars_nested() {
+        let mut vars = HashMap::new();
+        vars.insert("base.dir".to_string(), "/data".to_string());
+        vars.insert("kafka.logs.dir".to_string(), "${base.dir}/kafka/logs".to_string());
+
+        let resolved = Log4jParser::resolve_env_vars("${kafka.logs.dir}/server.log", &vars);
+        assert_eq!(resolved, "/data/kafka/logs/server.log");
+    }
+
+    #[test]
+    fn test_resolve_env_vars_self_reference() {
+        let mut vars = HashMap::new();
+        vars.insert("a".to_string(), "${b}/x".to_string());
+        vars.insert("b".to_string(), "${a}/y".to_string());
+
+        let resolved = Log4jParser::resolve_env_vars("${a}/server.log", &vars);
+        assert_eq!(resolved, "${a}/y/x/server.log");
+    }
+
     #[test]
     fn test_resolve_env_vars_kafka_defaults() {
         let env_vars = HashMap::new();
@@ -3,5 +3,7 @@ pub mod process_parser;
 pub mod systemd_parser;
 pub mod log4j_parser;
 pub mod llm_log_analyzer;
+pub mod gc_parser;
+pub mod controller_parser;
 
 pub use types::*;
\ No newline at end of file
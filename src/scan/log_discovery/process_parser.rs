@@ -99,6 +99,13 @@ impl ProcessParser {
         let log4j_path = Self::extract_log4j_path(&command_line);
         info!("   Log4j path: {:?}", log4j_path);
 
+        // Extract -D system properties, used to resolve log4j variables
+        let jvm_args = Self::extract_jvm_args(&command_line);
+        debug!("   JVM system properties: {:?}", jvm_args);
+
+        let gc_log_path = Self::extract_gc_log_path(&command_line);
+        info!("   GC log path: {:?}", gc_log_path);
+
         Ok(KafkaProcessInfo {
             pid,
             command_line,
@@ -106,6 +113,8 @@ impl ProcessParser {
             config_paths,
             log4j_path,
             environment_vars: HashMap::new(), // Will be filled later
+            jvm_args,
+            gc_log_path,
         })
     }
 
@@ -128,6 +137,33 @@ impl ProcessParser {
         config_paths
     }
 
+    /// Extract `-Dkey=value` system properties from command line
+    fn extract_jvm_args(cmdline: &str) -> HashMap<String, String> {
+        cmdline
+            .split_whitespace()
+            .filter_map(|part| part.strip_prefix("-D"))
+            .filter_map(|prop| prop.split_once('='))
+            .filter(|(key, _)| !key.is_empty())
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    /// Extract the GC log file from `-Xlog:gc*:file=<path>:...` (JDK 9+) or `-Xloggc:<path>` (JDK 8)
+    fn extract_gc_log_path(cmdline: &str) -> Option<PathBuf> {
+        cmdline.split_whitespace().find_map(|part| {
+            if let Some(path) = part.strip_prefix("-Xloggc:") {
+                return Some(PathBuf::from(path));
+            }
+            let options = part.strip_prefix("-Xlog:")?;
+            if !options.starts_with("gc") {
+                return None;
+            }
+            let (_, output) = options.split_once(":file=")?;
+            let path = output.split(':').next()?.trim_matches('"');
+            Some(PathBuf::from(path))
+        })
+    }
+
     /// Extract log4j configuration path from command line
     fn extract_log4j_path(cmdline: &str) -> Option<PathBuf> {
         // Look for -Dlog4j.configuration or -Dlog4j2.configurationFile
@@ -222,6 +258,26 @@ mod tests {
         assert_eq!(path, Some(PathBuf::from("/opt/kafka/log4j2.xml")));
     }
 
+    #[test]
+    fn test_extract_jvm_args() {
+        let cmdline = "java -Xmx1G -Dkafka.logs.dir=/var/log/kafka -Dcom.sun.management.jmxremote -Dlog4j.configuration=file:/opt/kafka/log4j.properties kafka.Kafka";
+        let args = ProcessParser::extract_jvm_args(cmdline);
+        assert_eq!(args.len(), 2);
+        assert_eq!(args.get("kafka.logs.dir"), Some(&"/var/log/kafka".to_string()));
+        assert_eq!(args.get("log4j.configuration"), Some(&"file:/opt/kafka/log4j.properties".to_string()));
+    }
+
+    #[test]
+    fn test_extract_gc_log_path() {
+        let cmdline = "java -Xmx6G -Xlog:gc*:file=/opt/kafka/bin/../logs/kafkaServer-gc.log:time,tags:filecount=10,filesize=100M kafka.Kafka";
+        assert_eq!(ProcessParser::extract_gc_log_path(cmdline), Some(PathBuf::from("/opt/kafka/bin/../logs/kafkaServer-gc.log")));
+
+        let cmdline = "java -Xloggc:/var/log/kafka/kafkaServer-gc.log -verbose:gc kafka.Kafka";
+        assert_eq!(ProcessParser::extract_gc_log_path(cmdline), Some(PathBuf::from("/var/log/kafka/kafkaServer-gc.log")));
+
+        assert_eq!(ProcessParser::extract_gc_log_path("java -Xlog:safepoint:file=/tmp/sp.log kafka.Kafka"), None);
+    }
+
     #[test]
     fn test_extract_log4j_path_none() {
         let cmdline = "java kafka.Kafka /opt/kafka/server.properties";
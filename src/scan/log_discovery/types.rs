@@ -10,6 +10,12 @@ pub struct KafkaProcessInfo {
     pub config_paths: Vec<PathBuf>,
     pub log4j_path: Option<PathBuf>,
     pub environment_vars: HashMap<String, String>,
+    /// System properties passed to the JVM as `-Dkey=value`
+    #[serde(default)]
+    pub jvm_args: HashMap<String, String>,
+    /// GC log file named by `-Xlog:gc*:file=` or `-Xloggc:`
+    #[serde(default)]
+    pub gc_log_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,4 +50,10 @@ pub struct EnhancedLogResult {
     pub discovered_logs: HashMap<String, String>,
     pub discovery_steps: Vec<String>,
     pub warnings: Vec<String>,
+    /// Logs cut short to fit the collection budget
+    #[serde(default)]
+    pub truncated_logs: Vec<String>,
+    /// Logs not collected because the budget was spent
+    #[serde(default)]
+    pub skipped_logs: Vec<String>,
 }
\ No newline at end of file
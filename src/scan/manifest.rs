@@ -0,0 +1,270 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// File in the output directory listing every collected file with its size and hash
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// One file written by a scan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path relative to the output directory, with `/` separators
+    pub path: String,
+    pub bytes: u64,
+    /// Hex-encoded SHA-256 of the file contents
+    pub sha256: String,
+    /// What wrote the file: `cluster`, `broker`, `scanner`, `discovery` or a collector name such as `admin`
+    pub collector: String,
+    /// Broker the file was collected from; unset for cluster-wide files
+    pub broker_id: Option<i32>,
+}
+
+/// Every file of a scan output directory except the manifest itself, sorted by path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub generated_at: DateTime<Utc>,
+    pub files: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Read the manifest of a scan directory, if the scan wrote one
+    pub fn load(output_dir: &Path) -> Result<Option<Self>> {
+        let path = output_dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Files that are missing or whose contents no longer match the recorded hash
+    pub fn verify(&self, output_dir: &Path) -> Vec<String> {
+        self.files.iter()
+            .filter_map(|entry| match fs::read(output_dir.join(&entry.path)) {
+                Ok(contents) if sha256_hex(&contents) == entry.sha256 => None,
+                Ok(_) => Some(format!("{} does not match its recorded hash", entry.path)),
+                Err(_) => Some(format!("{} is missing", entry.path)),
+            })
+            .collect()
+    }
+}
+
+/// An earlier scan whose files can be copied forward instead of being fetched again, when the
+/// source still hashes to what its manifest recorded
+#[derive(Debug, Clone)]
+pub struct PreviousScan {
+    dir: PathBuf,
+    /// Manifest path to SHA-256 of every file of the earlier scan
+    hashes: HashMap<String, String>,
+}
+
+impl PreviousScan {
+    /// Load an earlier scan directory, which must have a manifest
+    pub fn load(dir: &Path) -> Result<Self> {
+        let manifest = Manifest::load(dir)?
+            .with_context(|| format!("{} has no {} to compare against", dir.display(), MANIFEST_FILE))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            hashes: manifest.files.into_iter().map(|entry| (entry.path, entry.sha256)).collect(),
+        })
+    }
+
+    /// Contents of the file at manifest path `path` in the earlier scan, when it was collected
+    /// with contents hashing to `sha256` and is still intact
+    pub fn unchanged(&self, path: &str, sha256: &str) -> Option<Vec<u8>> {
+        if !self.hashes.get(path)?.eq_ignore_ascii_case(sha256) {
+            return None;
+        }
+        let contents = fs::read(self.dir.join(path)).ok()?;
+        (sha256_hex(&contents) == sha256.to_ascii_lowercase()).then_some(contents)
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+}
+
+/// Hash from the output of `sha256sum FILE`, `None` when the file could not be read
+pub fn parse_sha256sum(output: &str) -> Option<&str> {
+    output.split_whitespace().next()
+        .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+pub fn sha256_hex(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+/// Records files and their hashes as the scan writes them, so the manifest needs no second
+/// walk over the output directory. Clones share the same records.
+#[derive(Debug, Clone, Default)]
+pub struct ManifestRecorder {
+    files: Arc<Mutex<BTreeMap<PathBuf, ManifestEntry>>>,
+}
+
+impl ManifestRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writer recording its files as coming from `collector`, and from `broker_id` when set
+    pub fn writer(&self, collector: &str, broker_id: Option<i32>) -> ManifestWriter {
+        ManifestWriter {
+            recorder: self.clone(),
+            collector: collector.to_string(),
+            broker_id,
+        }
+    }
+
+    fn record(&self, path: &Path, contents: &[u8], collector: &str, broker_id: Option<i32>) {
+        let entry = ManifestEntry {
+            path: String::new(),
+            bytes: contents.len() as u64,
+            sha256: sha256_hex(contents),
+            collector: collector.to_string(),
+            broker_id,
+        };
+        // A file written twice is listed once, with its final contents
+        self.files.lock().unwrap_or_else(|e| e.into_inner()).insert(path.to_path_buf(), entry);
+    }
+
+    /// Manifest of the recorded files, with paths made relative to `output_dir`
+    pub fn manifest(&self, output_dir: &Path) -> Manifest {
+        let files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries: Vec<ManifestEntry> = files.iter()
+            .map(|(path, entry)| {
+                let relative = path.strip_prefix(output_dir).unwrap_or(path);
+                let components: Vec<_> = relative.components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect();
+                ManifestEntry { path: components.join("/"), ..entry.clone() }
+            })
+            .collect();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Manifest { generated_at: Utc::now(), files: entries }
+    }
+
+    /// Write `manifest.json` into the output directory
+    pub fn save(&self, output_dir: &Path) -> Result<Manifest> {
+        let manifest = self.manifest(output_dir);
+        fs::write(output_dir.join(MANIFEST_FILE), serde_json::to_string_pretty(&manifest)?)?;
+        Ok(manifest)
+    }
+}
+
+/// `fs::write` that records the written file in the scan manifest
+#[derive(Debug, Clone)]
+pub struct ManifestWriter {
+    recorder: ManifestRecorder,
+    collector: String,
+    broker_id: Option<i32>,
+}
+
+impl Default for ManifestWriter {
+    /// Writer recording into a manifest of its own, for collectors used outside a scan
+    fn default() -> Self {
+        ManifestRecorder::new().writer("scanner", None)
+    }
+}
+
+impl ManifestWriter {
+    pub fn write(&self, path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+        let (path, contents) = (path.as_ref(), contents.as_ref());
+        fs::write(path, contents)?;
+        self.recorder.record(path, contents, &self.collector, self.broker_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_manifest_covers_written_files() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("brokers").join("broker_1").join("configs")).unwrap();
+        fs::create_dir_all(root.join("cluster").join("kafkactl")).unwrap();
+
+        let recorder = ManifestRecorder::new();
+        let cluster = recorder.writer("cluster", None);
+        let broker = recorder.writer("broker", Some(1));
+        cluster.write(root.join("cluster").join("kafkactl").join("brokers.yaml"), "brokers: []").unwrap();
+        broker.write(root.join("brokers").join("broker_1").join("configs").join("server.properties"), "broker.id=1\n").unwrap();
+        broker.write(root.join("brokers").join("broker_1").join("configs").join("server.properties"), "broker.id=1\nlog.dirs=/data\n").unwrap();
+        recorder.save(root).unwrap();
+
+        let manifest = Manifest::load(root).unwrap().unwrap();
+        let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["brokers/broker_1/configs/server.properties", "cluster/kafkactl/brokers.yaml"]);
+
+        let properties = &manifest.files[0];
+        assert_eq!(properties.bytes, 27);
+        assert_eq!(properties.collector, "broker");
+        assert_eq!(properties.broker_id, Some(1));
+        assert_eq!(manifest.files[1].broker_id, None);
+        assert_eq!(manifest.files[1].sha256, sha256_hex(b"brokers: []"));
+        assert!(manifest.verify(root).is_empty());
+
+        fs::write(root.join("cluster").join("kafkactl").join("brokers.yaml"), "tampered").unwrap();
+        fs::remove_file(root.join("brokers").join("broker_1").join("configs").join("server.properties")).unwrap();
+        assert_eq!(manifest.verify(root), vec![
+            "brokers/broker_1/configs/server.properties is missing",
+            "cluster/kafkactl/brokers.yaml does not match its recorded hash",
+        ]);
+    }
+
+    #[test]
+    fn test_previous_scan_reuses_unchanged_files() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let configs = root.join("brokers").join("broker_1").join("configs");
+        fs::create_dir_all(&configs).unwrap();
+
+        let recorder = ManifestRecorder::new();
+        let broker = recorder.writer("broker", Some(1));
+        broker.write(configs.join("server.properties"), "broker.id=1\n").unwrap();
+        broker.write(configs.join("log4j.properties"), "log4j.rootLogger=INFO\n").unwrap();
+        recorder.save(root).unwrap();
+
+        let previous = PreviousScan::load(root).unwrap();
+        assert_eq!(previous.len(), 2);
+
+        // Unchanged on the broker: the earlier contents are copied forward
+        let unchanged = previous.unchanged("brokers/broker_1/configs/server.properties", &sha256_hex(b"broker.id=1\n"));
+        assert_eq!(unchanged.as_deref(), Some(&b"broker.id=1\n"[..]));
+
+        // Changed on the broker, never collected, or altered since: fetched again
+        assert!(previous.unchanged("brokers/broker_1/configs/server.properties", &sha256_hex(b"broker.id=2\n")).is_none());
+        assert!(previous.unchanged("brokers/broker_2/configs/server.properties", &sha256_hex(b"broker.id=1\n")).is_none());
+        fs::write(configs.join("log4j.properties"), "edited").unwrap();
+        assert!(previous.unchanged("brokers/broker_1/configs/log4j.properties", &sha256_hex(b"log4j.rootLogger=INFO\n")).is_none());
+
+        assert!(PreviousScan::load(&configs).is_err());
+    }
+
+    #[test]
+    fn test_parse_sha256sum() {
+        let hash = sha256_hex(b"abc");
+        assert_eq!(parse_sha256sum(&format!("{}  /etc/kafka/server.properties\n", hash)), Some(hash.as_str()));
+        assert_eq!(parse_sha256sum(""), None);
+        assert_eq!(parse_sha256sum("sha256sum: /etc/kafka/server.properties: Permission denied"), None);
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+}
@@ -1,3 +1,88 @@
+use indicatif::MultiProgress;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Whether scan modules print their decorative progress banners to stdout
+static BANNERS: AtomicBool = AtomicBool::new(true);
+
+/// Progress bars being drawn, which banners are printed above instead of through
+static PROGRESS: Mutex<Option<MultiProgress>> = Mutex::new(None);
+
+/// Text of a `banner_step!` printed while progress bars are drawn, held until the `banner!`
+/// finishing its line so the bars are never redrawn in the middle of it
+static PENDING_STEP: Mutex<String> = Mutex::new(String::new());
+
+/// Turn the scan banners on or off, e.g. off when stdout carries machine-readable output
+pub fn set_banners(enabled: bool) {
+    BANNERS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn banners_enabled() -> bool {
+    BANNERS.load(Ordering::Relaxed)
+}
+
+/// Print banners above these progress bars until `None` is set
+pub(crate) fn set_progress(progress: Option<MultiProgress>) {
+    *PROGRESS.lock().unwrap_or_else(|e| e.into_inner()) = progress;
+}
+
+/// Print a banner line, above the progress bars while they are drawn
+#[doc(hidden)]
+pub fn print_banner(line: &str) {
+    let progress = PROGRESS.lock().unwrap_or_else(|e| e.into_inner());
+    match progress.as_ref() {
+        Some(multi) => {
+            let step = std::mem::take(&mut *PENDING_STEP.lock().unwrap_or_else(|e| e.into_inner()));
+            multi.suspend(|| println!("{}{}", step, line));
+        }
+        None => println!("{}", line),
+    }
+}
+
+/// Print the start of a banner line that a later `print_banner` finishes
+#[doc(hidden)]
+pub fn print_banner_step(text: &str) {
+    let progress = PROGRESS.lock().unwrap_or_else(|e| e.into_inner());
+    match progress.as_ref() {
+        Some(_) => PENDING_STEP.lock().unwrap_or_else(|e| e.into_inner()).push_str(text),
+        None => print!("{}", text),
+    }
+}
+
+/// `println!` that stays quiet when banners are turned off, in ASCII when that is enabled,
+/// and prints above the progress bars while they are drawn
+macro_rules! banner {
+    () => {
+        if $crate::scan::banners_enabled() {
+            $crate::scan::print_banner("");
+        }
+    };
+    ($($arg:tt)*) => {
+        if $crate::scan::banners_enabled() {
+            $crate::scan::print_banner(&$crate::report::symbols::render(&format!($($arg)*)));
+        }
+    };
+}
+
+/// `print!` of a step whose result a later `banner!` finishes on the same line
+macro_rules! banner_step {
+    ($($arg:tt)*) => {
+        if $crate::scan::banners_enabled() {
+            $crate::scan::print_banner_step(&$crate::report::symbols::render(&format!($($arg)*)));
+        }
+    };
+}
+
+/// Command for a process collecting scan data, such as `ssh` to a broker. It runs in its own
+/// process group, so the SIGINT of a Ctrl-C at the terminal reaches only kcpilot: the command
+/// in flight finishes and the scan stops after it instead of losing its output.
+pub(crate) fn scan_command(program: impl AsRef<std::ffi::OsStr>) -> std::process::Command {
+    let mut command = std::process::Command::new(program);
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    command
+}
+
 // Module declarations
 pub mod collector;
 pub mod bastion_collector;
@@ -9,13 +94,27 @@ pub mod scanner;
 pub mod cluster_detection;
 pub mod broker_discovery;
 pub mod bastion;
+pub mod peer_latency;
+pub mod batch;
+pub mod progress;
+pub mod listeners;
+pub mod features;
+pub mod manifest;
+pub mod ssh_multiplex;
+pub mod tls_certificates;
+pub mod file_descriptors;
+pub mod process_start;
+pub mod broker_clock;
+pub mod connections;
+pub mod consumer_lag;
 
 // Re-export types for convenience
 pub use types::{
-    ScanConfig, BrokerInfo, ScanMetadata, ScanResult, 
-    ClusterData, BrokerData, CollectionStats
+    ScanConfig, BrokerInfo, ListenerInfo, ScanMetadata, ScanResult, 
+    ClusterData, BrokerData, CollectionStats, PhaseTiming, BrokerTiming
 };
-pub use scanner::Scanner;
+pub use scanner::{Scanner, COLLECTION_STATS_FILE};
+pub use manifest::{Manifest, PreviousScan, MANIFEST_FILE};
 pub use cluster_detection::detect_cluster_mode;
 
 
@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+
+/// Number of echo requests sent to each peer broker
+pub const PING_COUNT: u32 = 5;
+
+/// Latency measured from one broker to a peer broker
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PeerLatency {
+    pub peer_id: i32,
+    pub peer_hostname: String,
+    pub reachable: bool,
+    pub packet_loss_percent: Option<f64>,
+    pub rtt_min_ms: Option<f64>,
+    pub rtt_avg_ms: Option<f64>,
+    pub rtt_max_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// Values extracted from the summary printed by `ping`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PingSummary {
+    pub packet_loss_percent: Option<f64>,
+    pub rtt_min_ms: Option<f64>,
+    pub rtt_avg_ms: Option<f64>,
+    pub rtt_max_ms: Option<f64>,
+}
+
+/// Build the command run on a broker to measure latency to a peer
+pub fn ping_command(peer_hostname: &str) -> String {
+    format!("ping -c {} -i 0.2 -W 2 -q {} 2>&1", PING_COUNT, peer_hostname)
+}
+
+/// Parse the summary lines of iputils, BSD or busybox `ping` output
+pub fn parse_ping_output(output: &str) -> PingSummary {
+    let mut summary = PingSummary::default();
+
+    for line in output.lines() {
+        let line = line.trim();
+
+        // "5 packets transmitted, 5 received, 0% packet loss, time 4005ms"
+        if line.contains("packet loss") {
+            summary.packet_loss_percent = line
+                .split(',')
+                .find(|part| part.contains("packet loss"))
+                .and_then(|part| part.trim().split('%').next())
+                .and_then(|value| value.trim().parse().ok());
+        }
+
+        // "rtt min/avg/max/mdev = 0.045/0.061/0.082/0.013 ms"
+        // "round-trip min/avg/max = 0.045/0.061/0.082 ms"
+        if line.starts_with("rtt ") || line.starts_with("round-trip ") {
+            if let Some((_, values)) = line.split_once('=') {
+                let values: Vec<f64> = values
+                    .trim()
+                    .trim_end_matches("ms")
+                    .trim()
+                    .split('/')
+                    .filter_map(|v| v.trim().parse().ok())
+                    .collect();
+                if values.len() >= 3 {
+                    summary.rtt_min_ms = Some(values[0]);
+                    summary.rtt_avg_ms = Some(values[1]);
+                    summary.rtt_max_ms = Some(values[2]);
+                }
+            }
+        }
+    }
+
+    summary
+}
+
+impl PeerLatency {
+    /// Build a latency result from raw `ping` output
+    pub fn from_ping_output(peer_id: i32, peer_hostname: &str, output: &str) -> Self {
+        let summary = parse_ping_output(output);
+        let reachable = summary.rtt_avg_ms.is_some()
+            && summary.packet_loss_percent.map(|loss| loss < 100.0).unwrap_or(true);
+
+        let error = if reachable {
+            None
+        } else {
+            output.lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(|line| line.to_string())
+                .or_else(|| Some("No response from ping".to_string()))
+        };
+
+        Self {
+            peer_id,
+            peer_hostname: peer_hostname.to_string(),
+            reachable,
+            packet_loss_percent: summary.packet_loss_percent,
+            rtt_min_ms: summary.rtt_min_ms,
+            rtt_avg_ms: summary.rtt_avg_ms,
+            rtt_max_ms: summary.rtt_max_ms,
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iputils_ping() {
+        let output = r#"PING kafka-2 (10.0.0.12) 56(84) bytes of data.
+
+--- kafka-2 ping statistics ---
+5 packets transmitted, 5 received, 0% packet loss, time 812ms
+rtt min/avg/max/mdev = 0.245/0.361/0.582/0.113 ms"#;
+
+        let summary = parse_ping_output(output);
+
+        assert_eq!(summary.packet_loss_percent, Some(0.0));
+        assert_eq!(summary.rtt_min_ms, Some(0.245));
+        assert_eq!(summary.rtt_avg_ms, Some(0.361));
+        assert_eq!(summary.rtt_max_ms, Some(0.582));
+    }
+
+    #[test]
+    fn test_parse_busybox_ping_with_loss() {
+        let output = r#"--- kafka-3 ping statistics ---
+5 packets transmitted, 4 packets received, 20.0% packet loss
+round-trip min/avg/max = 12.045/25.500/40.082 ms"#;
+
+        let summary = parse_ping_output(output);
+
+        assert_eq!(summary.packet_loss_percent, Some(20.0));
+        assert_eq!(summary.rtt_avg_ms, Some(25.5));
+    }
+
+    #[test]
+    fn test_parse_ping_with_errors_field() {
+        let output = "5 packets transmitted, 3 received, +2 errors, 40% packet loss, time 4004ms\nrtt min/avg/max/mdev = 1.0/2.0/3.0/0.5 ms";
+
+        let summary = parse_ping_output(output);
+
+        assert_eq!(summary.packet_loss_percent, Some(40.0));
+        assert_eq!(summary.rtt_max_ms, Some(3.0));
+    }
+
+    #[test]
+    fn test_unreachable_peer() {
+        let output = "--- kafka-4 ping statistics ---\n5 packets transmitted, 0 received, 100% packet loss, time 4099ms";
+
+        let latency = PeerLatency::from_ping_output(14, "kafka-4", output);
+
+        assert!(!latency.reachable);
+        assert_eq!(latency.packet_loss_percent, Some(100.0));
+        assert!(latency.rtt_avg_ms.is_none());
+        assert!(latency.error.is_some());
+    }
+
+    #[test]
+    fn test_unresolvable_peer() {
+        let latency = PeerLatency::from_ping_output(15, "kafka-5", "ping: kafka-5: Name or service not known\n");
+
+        assert!(!latency.reachable);
+        assert_eq!(latency.error.as_deref(), Some("ping: kafka-5: Name or service not known"));
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// File under a broker's `system` directory the Kafka process's start time is saved to
+pub const PROCESS_START_FILE: &str = "kafka_process_start.json";
+
+/// Start of a broker's Kafka process, as collected
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessStart {
+    pub pid: u32,
+    /// `None` when `ps` printed nothing usable
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>,
+    /// When the start time was read, the point uptime is measured at
+    pub collected_at: DateTime<Utc>,
+}
+
+impl ProcessStart {
+    pub fn uptime(&self) -> Option<Duration> {
+        self.started_at.map(|started_at| self.collected_at - started_at)
+    }
+}
+
+/// Command printing the seconds since a process started and its start time in UTC
+pub fn process_start_command(pid: u32) -> String {
+    format!("LC_ALL=C TZ=UTC ps -o etimes= -o lstart= -p {}", pid)
+}
+
+/// Start time from `ps -o etimes= -o lstart=` output read at `collected_at`. The elapsed
+/// seconds are preferred as they do not depend on the broker's clock; older `ps` versions
+/// without `etimes` leave only the `lstart` date, e.g. `Mon Oct 14 09:12:33 2024`.
+pub fn parse_process_start(output: &str, collected_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let line = output.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let (first, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    if let Ok(elapsed) = first.parse::<i64>() {
+        return Some(collected_at - Duration::seconds(elapsed));
+    }
+    parse_lstart(line).or_else(|| parse_lstart(rest))
+}
+
+/// `ps -o lstart` date, printed in UTC
+fn parse_lstart(value: &str) -> Option<DateTime<Utc>> {
+    let normalized = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    NaiveDateTime::parse_from_str(&normalized, "%a %b %d %H:%M:%S %Y").ok()
+        .map(|naive| naive.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn collected_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 10, 14, 10, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_process_start_and_uptime() {
+        let started = Utc.with_ymd_and_hms(2024, 10, 14, 9, 12, 33).unwrap();
+        assert_eq!(parse_process_start("   2847 Mon Oct 14 09:12:33 2024\n", collected_at()), Some(started));
+        // ps without etimes, and a space-padded day
+        assert_eq!(parse_process_start("Fri Oct  4 09:12:33 2024\n", collected_at()),
+            Some(Utc.with_ymd_and_hms(2024, 10, 4, 9, 12, 33).unwrap()));
+        assert_eq!(parse_process_start("", collected_at()), None);
+        assert_eq!(parse_process_start("error: process ID list syntax error", collected_at()), None);
+
+        let start = ProcessStart { pid: 4242, started_at: Some(started), collected_at: collected_at() };
+        assert_eq!(start.uptime(), Some(Duration::seconds(2847)));
+    }
+}
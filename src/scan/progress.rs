@@ -0,0 +1,108 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::io::IsTerminal;
+use std::time::Duration;
+
+/// Number of phases `Scanner::scan` reports progress for
+pub const SCAN_PHASES: u64 = 4;
+
+/// Live progress of a scan: an overall phase bar and a spinner per broker being collected.
+/// The bars are drawn on stderr, so drawing is disabled when requested or when stderr is not
+/// a terminal. While they are drawn, scan banners are printed above them.
+pub struct ScanProgress {
+    multi: Option<MultiProgress>,
+    phases: Option<ProgressBar>,
+}
+
+impl ScanProgress {
+    pub fn new(enabled: bool) -> Self {
+        if !enabled || !std::io::stderr().is_terminal() {
+            return Self::disabled();
+        }
+
+        let multi = MultiProgress::with_draw_target(ProgressDrawTarget::stderr());
+        let phases = multi.add(ProgressBar::new(SCAN_PHASES));
+        phases.set_style(
+            ProgressStyle::with_template("{prefix:.bold} [{bar:20.cyan/blue}] {pos}/{len} {msg} ({elapsed})")
+                .unwrap_or_else(|_| ProgressStyle::default_bar())
+                .progress_chars("=> "),
+        );
+        phases.set_prefix("Scan");
+        phases.enable_steady_tick(Duration::from_millis(200));
+        super::set_progress(Some(multi.clone()));
+
+        Self {
+            multi: Some(multi),
+            phases: Some(phases),
+        }
+    }
+
+    /// Progress that draws nothing
+    pub fn disabled() -> Self {
+        Self {
+            multi: None,
+            phases: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.multi.is_some()
+    }
+
+    /// Show the phase now running
+    pub fn start_phase(&self, name: &str) {
+        if let Some(phases) = &self.phases {
+            phases.set_message(name.to_string());
+        }
+    }
+
+    /// Count the current phase as done
+    pub fn finish_phase(&self) {
+        if let Some(phases) = &self.phases {
+            phases.inc(1);
+        }
+    }
+
+    /// Spinner shown while a broker is collected, `None` when progress is disabled
+    pub fn broker_spinner(&self, broker_id: i32, hostname: &str) -> Option<ProgressBar> {
+        let multi = self.multi.as_ref()?;
+        let spinner = multi.add(ProgressBar::new_spinner());
+        spinner.set_style(
+            ProgressStyle::with_template("  {spinner} broker {prefix} {msg} ({elapsed})")
+                .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+        );
+        spinner.set_prefix(broker_id.to_string());
+        spinner.set_message(hostname.to_string());
+        spinner.enable_steady_tick(Duration::from_millis(120));
+        Some(spinner)
+    }
+
+    pub fn finish(&self) {
+        if let Some(phases) = &self.phases {
+            phases.finish_with_message("done");
+        }
+    }
+}
+
+impl Drop for ScanProgress {
+    fn drop(&mut self) {
+        if self.multi.is_some() {
+            super::set_progress(None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_progress_draws_nothing() {
+        let progress = ScanProgress::new(false);
+
+        assert!(!progress.is_enabled());
+        assert!(progress.broker_spinner(1, "kafka-1").is_none());
+        progress.start_phase("Collecting cluster-wide data");
+        progress.finish_phase();
+        progress.finish();
+    }
+}
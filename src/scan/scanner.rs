@@ -1,29 +1,85 @@
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde_json;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use tracing::{error, info};
-use crate::scan::collector::{BastionCollector, BrokerCollector, DiscoveryMethod};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, info_span, warn, Instrument};
+use crate::scan::collector::{BastionCollector, BrokerCollector, CollectorSelection, DiscoveryMethod, ScanCollector};
 use crate::scan::types::{
     ScanConfig, BrokerInfo, ScanMetadata, ScanResult, 
-    ClusterData, BrokerData, CollectionStats
+    ClusterData, BrokerData, CollectionStats, PhaseTiming, BrokerTiming
 };
+use crate::scan::progress::ScanProgress;
 use crate::scan::cluster_detection::{parse_server_properties, is_kraft_mode, is_zookeeper_mode};
 use crate::scan::broker_discovery::{
     discover_brokers_from_kafkactl, discover_brokers_from_single_local,
     discover_brokers_using_installation_path, discover_brokers_with_metadata_shell,
-    discover_brokers_with_api_versions, discover_brokers_from_configs
+    discover_brokers_with_api_versions, discover_brokers_from_configs,
+    discover_with_retry, fetch_broker_cluster_id, parse_broker_list, save_collector_outputs, verify_cluster_id,
+    RetryPolicy, BROKER_LIST_LISTENER
 };
+use crate::scan::listeners::{
+    broker_listeners, client_listener, format_address, parse_broker_address, split_host_port, DEFAULT_BROKER_PORT
+};
+use crate::collectors::{CollectionContext, CollectorRegistry, KafkaConfig, RedactionLevel, CONNECT_URL_METADATA_KEY, METRICS_PORT_METADATA_KEY};
+use crate::scan::manifest::{ManifestRecorder, PreviousScan};
+use crate::scan::enhanced_log_discovery::LogCollectionBudget;
+use crate::snapshot::format::CollectionEnvironment;
 use crate::scan::bastion::{
     check_ssh_agent, run_ssh_diagnostics, test_broker_access, 
-    check_kafkactl_availability, discover_brokers_with_bastion_admin_client
+    check_kafkactl_availability, discover_brokers_with_bastion_admin_client,
+    bastion_ssh_args, validate_bastion_chain
 };
 
+/// File in the output directory holding the serialized `CollectionStats`
+pub const COLLECTION_STATS_FILE: &str = "collection_stats.json";
+
+/// Provenance of a scan run from this process: platform, versions and the host kcpilot runs on
+pub fn collection_environment(bastion: Option<String>) -> CollectionEnvironment {
+    CollectionEnvironment {
+        os: Some(std::env::consts::OS.to_string()),
+        arch: Some(std::env::consts::ARCH.to_string()),
+        kcpilot_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        scan_host: scan_host(),
+        bastion,
+        librdkafka_version: Some(rdkafka::util::get_rdkafka_version().1),
+    }
+}
+
+/// Hostname of the machine running the scan, from the environment or the `hostname` command
+fn scan_host() -> Option<String> {
+    std::env::var("HOSTNAME").ok()
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .or_else(|| {
+            let output = std::process::Command::new("hostname").output().ok()?;
+            output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+        })
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+}
+
 pub struct Scanner {
     pub config: ScanConfig,
     discovery_method: Option<DiscoveryMethod>,
     detected_cluster_mode: Option<crate::snapshot::format::ClusterMode>,
+    cancel: CancellationToken,
+    show_progress: bool,
+    discovery_retry: RetryPolicy,
+    broker_port: u16,
+    redaction_level: RedactionLevel,
+    collectors: CollectorSelection,
+    log_budget: LogCollectionBudget,
+    /// Cluster id all discovered brokers reported
+    cluster_id: Option<String>,
+    /// Every file written to the output directory, saved as `manifest.json`
+    manifest: ManifestRecorder,
+    /// Earlier scan unchanged broker configs are copied forward from
+    previous_scan: Option<PreviousScan>,
+    /// Settings enabling the optional collectors, keyed as in `CollectionContext::metadata`
+    collector_metadata: HashMap<String, String>,
 }
 
 impl Scanner {
@@ -45,6 +101,21 @@ impl Scanner {
         }
     }
     
+    /// Store the listeners from a broker's server.properties in its `BrokerInfo`
+    fn record_listeners(&mut self, broker: &BrokerInfo, server_properties_content: &str) {
+        let properties = parse_server_properties(server_properties_content);
+        let listeners = broker_listeners(&properties);
+        if let Some(client) = client_listener(&listeners, &properties) {
+            info!("Broker {} client listener: {} on port {}", broker.id, client.name, client.port);
+        }
+        
+        if let Some(known) = self.config.brokers.iter_mut()
+            .find(|b| b.id == broker.id && b.hostname == broker.hostname)
+        {
+            known.listeners = listeners;
+        }
+    }
+    
     /// Set the detected cluster mode and return it
     pub fn set_cluster_mode(&mut self, mode: crate::snapshot::format::ClusterMode) -> crate::snapshot::format::ClusterMode {
         self.detected_cluster_mode = Some(mode);
@@ -57,6 +128,10 @@ impl Scanner {
     }
     
     pub fn new(bastion_alias: Option<String>) -> Result<Self> {
+        if let Some(bastion) = &bastion_alias {
+            validate_bastion_chain(bastion)?;
+        }
+
         // Create output directory with timestamp
         let timestamp = Utc::now().format("%Y%m%d-%H%M%S");
         let output_dir = PathBuf::from(format!("kafka-scan-{}", timestamp));
@@ -69,9 +144,21 @@ impl Scanner {
                 bastion_alias,
                 output_dir,
                 brokers,
+                log_since: None,
             },
             discovery_method: None,
             detected_cluster_mode: None,
+            cancel: CancellationToken::new(),
+            show_progress: true,
+            discovery_retry: RetryPolicy::default(),
+            broker_port: DEFAULT_BROKER_PORT,
+            redaction_level: RedactionLevel::default(),
+            collectors: CollectorSelection::all(),
+            log_budget: LogCollectionBudget::default(),
+            cluster_id: None,
+            manifest: ManifestRecorder::new(),
+            previous_scan: None,
+            collector_metadata: HashMap::new(),
         })
     }
     
@@ -85,6 +172,128 @@ impl Scanner {
         self
     }
 
+    /// Stop the scan after the broker being collected when `cancel` fires
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Show progress bars while scanning (default: on, and always off when stderr is not a terminal)
+    pub fn with_progress(mut self, show_progress: bool) -> Self {
+        self.show_progress = show_progress;
+        self
+    }
+
+    /// Retry each broker discovery method on failure before trying the next one
+    pub fn with_discovery_retry(mut self, policy: RetryPolicy) -> Self {
+        self.discovery_retry = policy;
+        self
+    }
+
+    /// Port used for a `--broker` address given without one (default: 9092)
+    pub fn with_broker_port(mut self, port: u16) -> Self {
+        self.broker_port = port;
+        self
+    }
+
+    /// How much is scrubbed from collector outputs saved during local discovery (default: standard)
+    pub fn with_redaction_level(mut self, level: RedactionLevel) -> Self {
+        self.redaction_level = level;
+        self
+    }
+
+    /// Bound broker log collection to entries written at or after `since`
+    pub fn with_log_since(mut self, since: Option<DateTime<Utc>>) -> Self {
+        self.config.log_since = since;
+        self
+    }
+
+    /// Bound the lines of each broker log and the bytes of logs collected from each broker
+    pub fn with_log_budget(mut self, budget: LogCollectionBudget) -> Self {
+        self.log_budget = budget;
+        self
+    }
+
+    /// Only run the given collectors, skipping the phases none of them belong to
+    pub fn with_collectors(mut self, collectors: CollectorSelection) -> Self {
+        self.collectors = collectors;
+        self
+    }
+
+    /// Collect incrementally: broker configs whose hash matches `previous` are copied from it
+    /// instead of being fetched again
+    pub fn with_previous_scan(mut self, previous: Option<PreviousScan>) -> Self {
+        self.previous_scan = previous;
+        self
+    }
+
+    /// Collect connector status from the Kafka Connect REST API at `url`, when given
+    pub fn with_connect_url(mut self, url: Option<String>) -> Self {
+        match url {
+            Some(url) => self.collector_metadata.insert(CONNECT_URL_METADATA_KEY.to_string(), url),
+            None => self.collector_metadata.remove(CONNECT_URL_METADATA_KEY),
+        };
+        self
+    }
+
+    /// Scrape the brokers' Prometheus metrics endpoints on `port`, when given
+    pub fn with_metrics_port(mut self, port: Option<u16>) -> Self {
+        match port {
+            Some(port) => self.collector_metadata.insert(METRICS_PORT_METADATA_KEY.to_string(), port.to_string()),
+            None => self.collector_metadata.remove(METRICS_PORT_METADATA_KEY),
+        };
+        self
+    }
+
+    /// Discover the cluster from a single `hostname[:port]` broker, or from kafkactl when none is given
+    pub async fn discover(self, broker: Option<&str>) -> Result<Self> {
+        let scanner = match broker {
+            Some(broker_address) => {
+                info!("Using broker discovery from: {}", broker_address);
+                self.discover_brokers_from_single(broker_address).await?
+            }
+            None => {
+                info!("No broker provided, attempting to discover brokers from kafkactl");
+                self.discover_brokers_from_kafkactl().await?
+            }
+        };
+        scanner.verify_cluster().await
+    }
+
+    /// Check that the discovered brokers all report the same cluster id, failing when they
+    /// belong to different clusters
+    pub async fn verify_cluster(mut self) -> Result<Self> {
+        let mut reported = Vec::with_capacity(self.config.brokers.len());
+        for broker in &self.config.brokers {
+            let port = broker.listeners.iter()
+                .find(|listener| listener.name == BROKER_LIST_LISTENER)
+                .map_or(self.broker_port, |listener| listener.port);
+            let cluster_id = fetch_broker_cluster_id(self.config.bastion_alias.as_ref(), broker, port).await;
+            reported.push((broker.clone(), cluster_id));
+        }
+
+        self.cluster_id = verify_cluster_id(&reported)?;
+        match &self.cluster_id {
+            Some(cluster_id) => info!("✅ Discovered brokers belong to cluster {}", cluster_id),
+            None => warn!("⚠️  Could not verify the cluster id of the discovered brokers"),
+        }
+        Ok(self)
+    }
+
+    /// Use the brokers listed in a `--broker-list` file instead of discovering them, for
+    /// environments where discovery is unreliable. Lines without a port get the `--broker-port`.
+    pub fn with_broker_list(mut self, path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read broker list {}: {}", path.display(), e))?;
+        let brokers = parse_broker_list(&content, self.broker_port)
+            .map_err(|e| anyhow::anyhow!("Invalid broker list {}: {}", path.display(), e))?;
+        info!("Using {} brokers listed in {}, skipping discovery", brokers.len(), path.display());
+
+        self.config.brokers = brokers;
+        self.discovery_method = Some(DiscoveryMethod::BrokerList { path: path.display().to_string() });
+        Ok(self)
+    }
+
     /// Discover brokers from kafkactl when no broker parameter is provided
     pub async fn discover_brokers_from_kafkactl(mut self) -> Result<Self> {
         info!("Attempting to discover brokers from kafkactl");
@@ -116,11 +325,9 @@ impl Scanner {
     pub async fn discover_brokers_from_single(mut self, broker_address: &str) -> Result<Self> {
         info!("Discovering brokers from single broker: {}", broker_address);
         
-        // Parse hostname:port
-        let parts: Vec<&str> = broker_address.split(':').collect();
-        if parts.len() != 2 {
-            return Err(anyhow::anyhow!("Broker address must be in format hostname:port, got: {}", broker_address));
-        }
+        // Parse hostname[:port], filling in the default port and bracketing IPv6 addresses
+        let (host, port) = parse_broker_address(broker_address, self.broker_port)?;
+        let broker_address = &format_address(&host, port);
         
         // If using bastion, run discovery on the bastion via SSH
         if let Some(bastion_alias) = self.config.bastion_alias.clone() {
@@ -130,7 +337,12 @@ impl Scanner {
         
         // Local discovery using admin client
         info!("Running local broker discovery");
-        let discovered_brokers = discover_brokers_from_single_local(broker_address).await?;
+        let discovered_brokers = discover_brokers_from_single_local(
+            broker_address,
+            &self.config.output_dir,
+            self.redaction_level,
+            &self.manifest,
+        ).await?;
         self.config.brokers = discovered_brokers;
         Ok(self)
     }
@@ -140,12 +352,14 @@ impl Scanner {
         info!("Attempting broker discovery on bastion: {}", bastion_alias);
         info!("Note: kafkactl will be collected separately as data source, not used for broker discovery");
         
+        let bastion = self.config.bastion_alias.clone();
+        let bastion = bastion.as_ref();
+        let retry = self.discovery_retry;
+        
         // New Method: SSH to the broker, discover Kafka installation path, then discover all brokers
-        if let Ok(brokers) = discover_brokers_using_installation_path(
-            self.config.bastion_alias.as_ref(), 
-            broker_address, 
-            &self.config.output_dir
-        ).await {
+        if let Ok(brokers) = discover_with_retry(retry, "installation path", || {
+            discover_brokers_using_installation_path(bastion, broker_address, &self.config.output_dir, &self.manifest)
+        }).await {
             if !brokers.is_empty() {
                 info!("Successfully discovered {} brokers using installation path method", brokers.len());
                 self.config.brokers = brokers;
@@ -160,10 +374,9 @@ impl Scanner {
         }
         
         // Method 1: Run a simple admin client tool on the bastion to get broker metadata
-        if let Ok(brokers) = discover_brokers_with_bastion_admin_client(
-            self.config.bastion_alias.as_ref(), 
-            broker_address
-        ).await {
+        if let Ok(brokers) = discover_with_retry(retry, "bastion admin client", || {
+            discover_brokers_with_bastion_admin_client(bastion, broker_address)
+        }).await {
             if !brokers.is_empty() {
                 info!("Successfully discovered {} brokers using admin client on bastion", brokers.len());
                 self.config.brokers = brokers;
@@ -172,10 +385,9 @@ impl Scanner {
         }
         
         // Method 2: Use kafka-metadata-shell.sh if available
-        if let Ok(brokers) = discover_brokers_with_metadata_shell(
-            self.config.bastion_alias.as_ref(), 
-            broker_address
-        ).await {
+        if let Ok(brokers) = discover_with_retry(retry, "kafka-metadata-shell", || {
+            discover_brokers_with_metadata_shell(bastion, broker_address)
+        }).await {
             if !brokers.is_empty() {
                 info!("Successfully discovered {} brokers using kafka-metadata-shell", brokers.len());
                 self.config.brokers = brokers;
@@ -184,10 +396,9 @@ impl Scanner {
         }
         
         // Method 3: Use kafka-broker-api-versions.sh with better parsing
-        if let Ok(brokers) = discover_brokers_with_api_versions(
-            self.config.bastion_alias.as_ref(), 
-            broker_address
-        ).await {
+        if let Ok(brokers) = discover_with_retry(retry, "kafka-broker-api-versions", || {
+            discover_brokers_with_api_versions(bastion, broker_address)
+        }).await {
             if !brokers.is_empty() {
                 info!("Successfully discovered {} brokers using kafka-broker-api-versions", brokers.len());
                 self.config.brokers = brokers;
@@ -196,10 +407,9 @@ impl Scanner {
         }
         
         // Method 4: Parse server.properties files on brokers to find other brokers
-        if let Ok(brokers) = discover_brokers_from_configs(
-            self.config.bastion_alias.as_ref(), 
-            broker_address
-        ).await {
+        if let Ok(brokers) = discover_with_retry(retry, "server.properties", || {
+            discover_brokers_from_configs(bastion, broker_address)
+        }).await {
             if !brokers.is_empty() {
                 info!("Successfully discovered {} brokers from server.properties files", brokers.len());
                 self.config.brokers = brokers;
@@ -211,7 +421,8 @@ impl Scanner {
         info!("All discovery methods failed, falling back to single broker configuration");
         let fallback_brokers = vec![BrokerInfo {
             id: 0, // Unknown ID, will be determined during data collection
-            hostname: broker_address.split(':').next().unwrap_or("unknown").to_string(),
+            hostname: split_host_port(broker_address).0,
+            listeners: Vec::new(),
         }];
         
         self.config.brokers = fallback_brokers;
@@ -229,14 +440,14 @@ impl Scanner {
         fs::create_dir_all(base.join("cluster").join("tools"))?;
         fs::create_dir_all(base.join("metrics").join("kafka_exporter"))?;
         fs::create_dir_all(base.join("system").join("bastion"))?;
+        fs::create_dir_all(base.join("features"))?;
         
         info!("Created output directory: {}", base.display());
         Ok(())
     }
     
-    /// Save scan metadata
-    fn save_metadata(&self, accessible_brokers: usize) -> Result<()> {
-        let metadata = ScanMetadata {
+    fn scan_metadata(&self, accessible_brokers: usize, partial: bool) -> ScanMetadata {
+        ScanMetadata {
             scan_timestamp: Utc::now().to_rfc3339(),
             bastion: self.config.bastion_alias.clone(),
             is_local: self.config.bastion_alias.is_none(),
@@ -245,10 +456,16 @@ impl Scanner {
             scan_version: "1.0".to_string(),
             accessible_brokers,
             cluster_mode: self.detected_cluster_mode,
-        };
-        
-        let json = serde_json::to_string_pretty(&metadata)?;
-        fs::write(
+            partial,
+            environment: Some(collection_environment(self.config.bastion_alias.clone())),
+            cluster_id: self.cluster_id.clone(),
+        }
+    }
+    
+    /// Save scan metadata
+    fn save_metadata(&self, metadata: &ScanMetadata) -> Result<()> {
+        let json = serde_json::to_string_pretty(metadata)?;
+        self.manifest.writer("scanner", None).write(
             self.config.output_dir.join("scan_metadata.json"),
             json,
         )?;
@@ -256,64 +473,69 @@ impl Scanner {
         Ok(())
     }
     
-    /// Main scan execution
-    pub async fn scan(&mut self) -> Result<ScanResult> {
-        let start_time = std::time::Instant::now();
-        
-        // Phase 1: Setup
-        println!("═══════════════════════════════════════════════════════════════");
-        println!("        KCPILOT COMPREHENSIVE CLUSTER SCAN");
-        println!("═══════════════════════════════════════════════════════════════");
-        println!();
+    /// Save collection statistics next to the scan metadata
+    fn save_stats(&self, stats: &CollectionStats) -> Result<()> {
+        let json = serde_json::to_string_pretty(stats)?;
+        self.manifest.writer("scanner", None).write(
+            self.config.output_dir.join(COLLECTION_STATS_FILE),
+            json,
+        )?;
         
-        // Display scan mode
-        match &self.config.bastion_alias {
-            Some(alias) => {
-                println!("📡 Mode: Remote scan via bastion '{}'", alias);
-                println!("🔐 Checking SSH agent...");
-                check_ssh_agent(self.config.bastion_alias.as_ref())?;
-                println!("✅ SSH agent has keys loaded\n");
-            }
-            None => {
-                println!("💻 Mode: Local scan (running on bastion)");
-                println!();
-            }
+        Ok(())
+    }
+    
+    /// Run the optional collectors enabled by `collector_metadata` against the discovered brokers,
+    /// saving their outputs to `cluster/collectors/`. Returns whether any collector ran.
+    async fn run_optional_collectors(&self) -> Result<bool> {
+        let ctx = CollectionContext {
+            kafka_config: KafkaConfig {
+                bootstrap_servers: self.config.brokers.iter()
+                    .map(|broker| {
+                        let port = broker.listeners.iter()
+                            .find(|listener| listener.name == BROKER_LIST_LISTENER)
+                            .map_or(self.broker_port, |listener| listener.port);
+                        format_address(&broker.hostname, port)
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            metadata: self.collector_metadata.clone(),
+            dry_run: false,
+            redaction_level: self.redaction_level,
+        };
+        let mut registry = CollectorRegistry::new();
+        registry.register_optional_collectors(&ctx);
+        if registry.list().is_empty() {
+            return Ok(false);
         }
-        
-        // Create output directories
-        println!("📁 Creating output directory: {}", self.config.output_dir.display());
-        self.setup_output_dirs()?;
-        println!("✅ Output directory created\n");
-        
-        // Phase 2: Collect cluster-wide data from bastion
-        println!("═══════════════════════════════════════════════════════════════");
-        println!("PHASE 1: Collecting Cluster-Wide Data");
-        println!("═══════════════════════════════════════════════════════════════");
-        println!();
-        
-        let mut bastion_collector = BastionCollector::new(
-            self.config.bastion_alias.clone(),
-            self.config.output_dir.clone(),
-        );
-        
-        // Set discovery method based on how brokers were discovered
-        if let Some(discovery_method) = &self.discovery_method {
-            bastion_collector = bastion_collector.with_discovery_method(discovery_method.clone());
+
+        banner!("\n═══════════════════════════════════════════════════════════════");
+        banner!("Collecting from Kafka Connect and Metrics Endpoints");
+        banner!("═══════════════════════════════════════════════════════════════");
+        banner!();
+        let results = registry.run_all(&ctx).await;
+        save_collector_outputs(&registry, &results, &ctx, &self.config.output_dir, &self.manifest)?;
+        for (name, result) in &results {
+            if result.is_ok() {
+                banner!("  ✅ {} collected", name);
+            }
         }
-        
-        let cluster_data = bastion_collector.collect_all().await?;
-        
-        // Phase 3: Test broker connectivity
-        println!("\n═══════════════════════════════════════════════════════════════");
-        println!("PHASE 2: Testing Broker Connectivity");
-        println!("═══════════════════════════════════════════════════════════════");
-        println!();
+        banner!();
+        Ok(true)
+    }
+
+    /// Phase 2: find the brokers reachable over SSH
+    async fn test_broker_connectivity(&self) -> Vec<BrokerInfo> {
+        banner!("\n═══════════════════════════════════════════════════════════════");
+        banner!("PHASE 2: Testing Broker Connectivity");
+        banner!("═══════════════════════════════════════════════════════════════");
+        banner!();
         
         // Diagnostic information
         if let Some(alias) = &self.config.bastion_alias {
-            println!("🔍 SSH Connection Diagnostics:");
+            banner!("🔍 SSH Connection Diagnostics:");
             run_ssh_diagnostics(alias, &self.config.brokers[0]).await;
-            println!();
+            banner!();
         }
         
         let mut accessible_brokers = Vec::new();
@@ -322,73 +544,196 @@ impl Scanner {
         } else {
             "directly from bastion"
         };
-        println!("Testing broker access ({}):", connect_method);
+        banner!("Testing broker access ({}):", connect_method);
         
         for broker in &self.config.brokers {
-            print!("  • Broker {}... ", broker.id);
+            banner_step!("  • Broker {}... ", broker.id);
             
             if test_broker_access(self.config.bastion_alias.as_ref(), broker).await {
-                println!("✅ Accessible");
+                banner!("✅ Accessible");
                 accessible_brokers.push(broker.clone());
             } else {
-                println!("❌ Not accessible");
+                banner!("❌ Not accessible");
             }
         }
         
         if accessible_brokers.is_empty() {
-            println!("\n⚠️  No brokers accessible via SSH.");
+            banner!("\n⚠️  No brokers accessible via SSH.");
             
             // Provide helpful troubleshooting suggestions
             match &self.config.bastion_alias {
                 Some(alias) => {
-                    println!("\n🔧 Troubleshooting suggestions:");
-                    println!("   1. Verify SSH config for '{}':", alias);
-                    println!("      ssh -v {} 'echo test'", alias);
-                    println!("   2. Test SSH agent forwarding:");
-                    println!("      ssh -A {} 'ssh-add -l'", alias);
-                    println!("   3. Test manual broker connection:");
-                    println!("      ssh -A {} 'ssh {}'", alias, self.config.brokers[0].hostname);
-                    println!("   4. Check if broker hostnames are resolvable from bastion:");
-                    println!("      ssh {} 'host {}'", alias, self.config.brokers[0].hostname);
+                    let route = bastion_ssh_args(alias).join(" ");
+                    banner!("\n🔧 Troubleshooting suggestions:");
+                    banner!("   1. Verify SSH config for '{}':", alias);
+                    banner!("      ssh -v {} 'echo test'", route);
+                    banner!("   2. Test SSH agent forwarding:");
+                    banner!("      ssh -A {} 'ssh-add -l'", route);
+                    banner!("   3. Test manual broker connection:");
+                    banner!("      ssh -A {} 'ssh {}'", route, self.config.brokers[0].hostname);
+                    banner!("   4. Check if broker hostnames are resolvable from bastion:");
+                    banner!("      ssh {} 'host {}'", route, self.config.brokers[0].hostname);
                 }
                 None => {
-                    println!("\n🔧 Troubleshooting suggestions:");
-                    println!("   1. Test direct broker connection:");
-                    println!("      ssh {}", self.config.brokers[0].hostname);
-                    println!("   2. Check network connectivity:");
-                    println!("      ping {}", self.config.brokers[0].hostname);
+                    banner!("\n🔧 Troubleshooting suggestions:");
+                    banner!("   1. Test direct broker connection:");
+                    banner!("      ssh {}", self.config.brokers[0].hostname);
+                    banner!("   2. Check network connectivity:");
+                    banner!("      ping {}", self.config.brokers[0].hostname);
                 }
             }
             
-            println!("\n💡 Despite connectivity issues, cluster-wide data was still collected successfully.");
-            println!("   This includes kafkactl output, metrics, and bastion system information.");
+            banner!("\n💡 Despite connectivity issues, cluster-wide data was still collected successfully.");
+            banner!("   This includes kafkactl output, metrics, and bastion system information.");
         } else {
-            println!("\n✅ Found {} accessible broker(s)", accessible_brokers.len());
+            banner!("\n✅ Found {} accessible broker(s)", accessible_brokers.len());
         }
         
+        accessible_brokers
+    }
+    
+    /// Main scan execution
+    pub async fn scan(&mut self) -> Result<ScanResult> {
+        let start_time = Instant::now();
+        let mut phases = Vec::new();
+        
+        // Phase 1: Setup
+        let setup_span = info_span!("setup").entered();
+        banner!("═══════════════════════════════════════════════════════════════");
+        banner!("        KCPILOT COMPREHENSIVE CLUSTER SCAN");
+        banner!("═══════════════════════════════════════════════════════════════");
+        banner!();
+        
+        // Display scan mode
+        match &self.config.bastion_alias {
+            Some(alias) => {
+                banner!("📡 Mode: Remote scan via bastion '{}'", alias);
+                banner!("🔐 Checking SSH agent...");
+                check_ssh_agent(self.config.bastion_alias.as_ref())?;
+                banner!("✅ SSH agent has keys loaded\n");
+            }
+            None => {
+                banner!("💻 Mode: Local scan (running on bastion)");
+                banner!();
+            }
+        }
+        
+        // Create output directories
+        banner!("📁 Creating output directory: {}", self.config.output_dir.display());
+        self.setup_output_dirs()?;
+        banner!("✅ Output directory created\n");
+        drop(setup_span);
+        record_phase(&mut phases, "setup", start_time);
+        
+        let progress = ScanProgress::new(self.show_progress);
+        
+        // Phase 2: Collect cluster-wide data from bastion
+        banner!("═══════════════════════════════════════════════════════════════");
+        banner!("PHASE 1: Collecting Cluster-Wide Data");
+        banner!("═══════════════════════════════════════════════════════════════");
+        banner!();
+        
+        let mut bastion_collector = BastionCollector::new(
+            self.config.bastion_alias.clone(),
+            self.config.output_dir.clone(),
+        )
+        .with_manifest(&self.manifest)
+        .with_collectors(self.collectors.clone());
+        
+        // Set discovery method based on how brokers were discovered
+        if let Some(discovery_method) = &self.discovery_method {
+            bastion_collector = bastion_collector.with_discovery_method(discovery_method.clone());
+        }
+        
+        // Set when Ctrl-C skipped part of the scan
+        let mut partial = false;
+        
+        let cluster_collect_start = Instant::now();
+        progress.start_phase("Collecting cluster-wide data");
+        let cluster_data = if self.cancel.is_cancelled() {
+            partial = true;
+            banner!("⏹  Scan cancelled, skipping cluster-wide collection");
+            ClusterData::default()
+        } else if !self.collectors.includes_any(&ScanCollector::CLUSTER) {
+            banner!("⏭️  Skipping cluster-wide collection (collectors: {})\n", self.collectors);
+            ClusterData::default()
+        } else {
+            bastion_collector.collect_all()
+                .instrument(info_span!("cluster_collect"))
+                .await?
+        };
+        record_phase(&mut phases, "cluster_collect", cluster_collect_start);
+        progress.finish_phase();
+        
+        let connectivity_start = Instant::now();
+        progress.start_phase("Testing broker connectivity");
+        let mut accessible_brokers = Vec::new();
+        if self.cancel.is_cancelled() {
+            partial = true;
+        } else if !self.collectors.includes_brokers() {
+            banner!("⏭️  Skipping broker collection (collectors: {})\n", self.collectors);
+        } else {
+            accessible_brokers = self.test_broker_connectivity().await;
+        }
+        record_phase(&mut phases, "connectivity", connectivity_start);
+        progress.finish_phase();
+        
         // Phase 4: Collect data from accessible brokers
         let mut broker_data = Vec::new();
+        let mut broker_timings = Vec::new();
+        let broker_collect_span = info_span!("broker_collect", brokers = accessible_brokers.len());
+        let broker_collect_start = Instant::now();
+        progress.start_phase("Collecting broker data");
         
         if !accessible_brokers.is_empty() {
-            println!("\n═══════════════════════════════════════════════════════════════");
-            println!("PHASE 3: Collecting Data from Accessible Brokers");
-            println!("═══════════════════════════════════════════════════════════════");
-            println!();
+            banner!("\n═══════════════════════════════════════════════════════════════");
+            banner!("PHASE 3: Collecting Data from Accessible Brokers");
+            banner!("═══════════════════════════════════════════════════════════════");
+            banner!();
             
             for broker in accessible_brokers.iter() {
-                println!("🔍 Processing Broker {} ({})...", 
+                if self.cancel.is_cancelled() {
+                    banner!("⏹  Scan cancelled, skipping remaining brokers\n");
+                    partial = true;
+                    break;
+                }
+                
+                banner!("🔍 Processing Broker {} ({})...", 
                     broker.id, broker.hostname);
-                println!("────────────────────────────────────────");
+                banner!("────────────────────────────────────────");
                 
                 let broker_collector = BrokerCollector::new(
                     self.config.bastion_alias.clone(),
                     broker.clone(),
                     self.config.output_dir.clone(),
-                );
+                )
+                .with_peers(accessible_brokers.clone())
+                .with_log_since(self.config.log_since)
+                .with_log_budget(self.log_budget.clone())
+                .with_collectors(self.collectors.clone())
+                .with_previous_scan(self.previous_scan.clone())
+                .with_manifest(&self.manifest);
                 
-                match broker_collector.collect_all().await {
+                let broker_span = info_span!(parent: &broker_collect_span, "broker", id = broker.id);
+                let spinner = progress.broker_spinner(broker.id, &broker.hostname);
+                let broker_start = Instant::now();
+                let collected = broker_collector.collect_all().instrument(broker_span).await;
+                if let Some(spinner) = spinner {
+                    spinner.finish_and_clear();
+                }
+                broker_timings.push(BrokerTiming {
+                    broker_id: broker.id,
+                    hostname: broker.hostname.clone(),
+                    duration_secs: broker_start.elapsed().as_secs_f64(),
+                    collected: collected.is_ok(),
+                });
+                match collected {
                     Ok(data) => {
-                        println!("  ✅ Broker {} collection complete\n", broker.id);
+                        banner!("  ✅ Broker {} collection complete\n", broker.id);
+                        
+                        if let Some(server_props) = data.configs.get("server.properties") {
+                            self.record_listeners(broker, server_props);
+                        }
                         
                         // Try to detect cluster mode from server.properties if not already detected
                         if self.detected_cluster_mode.is_none() {
@@ -413,71 +758,102 @@ impl Scanner {
             }
         }
         
+        drop(broker_collect_span);
+        record_phase(&mut phases, "broker_collect", broker_collect_start);
+        progress.finish_phase();
+
+        let collectors_start = Instant::now();
+        if self.cancel.is_cancelled() {
+            partial = true;
+        } else if self.run_optional_collectors().instrument(info_span!("optional_collect")).await? {
+            record_phase(&mut phases, "optional_collect", collectors_start);
+        }
+        
+        let summary_start = Instant::now();
+        progress.start_phase("Generating summary");
+        
         // Phase 5: Generate summary
-        println!("═══════════════════════════════════════════════════════════════");
-        println!("PHASE 4: Generating Collection Summary");
-        println!("═══════════════════════════════════════════════════════════════");
-        println!();
+        banner!("═══════════════════════════════════════════════════════════════");
+        banner!("PHASE 4: Generating Collection Summary");
+        banner!("═══════════════════════════════════════════════════════════════");
+        banner!();
         
         // Save metadata
-        self.save_metadata(accessible_brokers.len())?;
+        let metadata = self.scan_metadata(accessible_brokers.len(), partial);
+        self.save_metadata(&metadata)?;
         
         // Generate summary report
-        self.generate_summary_report(&cluster_data, &broker_data)?;
+        self.generate_summary_report(&cluster_data, &broker_data, &phases, &broker_timings)?;
+        record_phase(&mut phases, "summary", summary_start);
+        progress.finish_phase();
+        progress.finish();
         
         // Calculate statistics
-        let stats = self.calculate_stats(start_time.elapsed().as_secs())?;
+        let stats = self.calculate_stats(start_time.elapsed().as_secs(), phases, broker_timings)?;
+        self.save_stats(&stats)?;
+        self.manifest.save(&self.config.output_dir)?;
         
-        println!("📊 Generating summary report...");
-        println!("✅ Summary report generated\n");
+        banner!("📊 Generating summary report...");
+        banner!("✅ Summary report generated\n");
         
         // Final output
-        println!("═══════════════════════════════════════════════════════════════");
-        println!("                    COLLECTION COMPLETE!");
-        println!("═══════════════════════════════════════════════════════════════");
-        println!();
-        println!("📊 Collection Statistics:");
-        println!("  • Total files collected: {}", stats.total_files);
-        println!("  • Total size: {} KB", stats.total_size_bytes / 1024);
-        println!("  • Output directory: {}", self.config.output_dir.display());
-        println!("  • Brokers accessed: {}/{}", accessible_brokers.len(), self.config.brokers.len());
+        banner!("═══════════════════════════════════════════════════════════════");
+        if metadata.partial {
+            banner!("              COLLECTION CANCELLED (PARTIAL SCAN)");
+        } else {
+            banner!("                    COLLECTION COMPLETE!");
+        }
+        banner!("═══════════════════════════════════════════════════════════════");
+        banner!();
+        banner!("📊 Collection Statistics:");
+        banner!("  • Total files collected: {}", stats.total_files);
+        banner!("  • Total size: {} KB", stats.total_size_bytes / 1024);
+        banner!("  • Output directory: {}", self.config.output_dir.display());
+        banner!("  • Brokers accessed: {}/{}", accessible_brokers.len(), self.config.brokers.len());
+        if metadata.partial {
+            banner!("  • Brokers collected before cancellation: {}", broker_data.len());
+        }
+        banner!("  • Phase timings:");
+        for phase in &stats.phases {
+            banner!("      {:<16} {:>8.1}s", phase.phase, phase.duration_secs);
+        }
+        if let Some(slowest) = stats.slowest_brokers().first() {
+            banner!("  • Slowest broker: {} ({}) in {:.1}s",
+                slowest.broker_id, slowest.hostname, slowest.duration_secs);
+        }
         
         // Display detected cluster mode
         match &self.detected_cluster_mode {
             Some(crate::snapshot::format::ClusterMode::Kraft) => {
-                println!("  • Cluster architecture: 🚀 KRaft (modern, Zookeeper-free)");
+                banner!("  • Cluster architecture: 🚀 KRaft (modern, Zookeeper-free)");
             }
             Some(crate::snapshot::format::ClusterMode::Zookeeper) => {
-                println!("  • Cluster architecture: ⚠️  Zookeeper-based (legacy)");
+                banner!("  • Cluster architecture: ⚠️  Zookeeper-based (legacy)");
             }
             Some(crate::snapshot::format::ClusterMode::Unknown) => {
-                println!("  • Cluster architecture: ❓ Unknown (could not determine)");
+                banner!("  • Cluster architecture: ❓ Unknown (could not determine)");
             }
             None => {
-                println!("  • Cluster architecture: ❓ Not detected (no server.properties found)");
+                banner!("  • Cluster architecture: ❓ Not detected (no server.properties found)");
             }
         }
-        println!();
-        println!("📁 Data saved to: {}/", self.config.output_dir.display());
-        println!();
-        println!("💡 To analyze the collected data:");
-        println!("  1. cd {}", self.config.output_dir.display());
-        println!("  2. Review COLLECTION_SUMMARY.md");
-        println!("  3. Check broker configs and logs");
-        println!();
-        println!("✨ Scan complete!");
+        banner!();
+        banner!("📁 Data saved to: {}/", self.config.output_dir.display());
+        banner!();
+        banner!("💡 To analyze the collected data:");
+        banner!("  1. cd {}", self.config.output_dir.display());
+        banner!("  2. Review COLLECTION_SUMMARY.md");
+        banner!("  3. Check broker configs and logs");
+        banner!();
+        if metadata.partial {
+            warn!("Scan was cancelled; the output directory holds a partial scan");
+            banner!("⏹  Partial scan saved.");
+        } else {
+            banner!("✨ Scan complete!");
+        }
         
         Ok(ScanResult {
-            metadata: ScanMetadata {
-                scan_timestamp: Utc::now().to_rfc3339(),
-                bastion: self.config.bastion_alias.clone(),
-                is_local: self.config.bastion_alias.is_none(),
-                broker_count: self.config.brokers.len(),
-                output_directory: self.config.output_dir.to_string_lossy().to_string(),
-                scan_version: "1.0".to_string(),
-                accessible_brokers: accessible_brokers.len(),
-                cluster_mode: self.detected_cluster_mode,
-            },
+            metadata,
             cluster_data,
             broker_data,
             collection_stats: stats,
@@ -486,7 +862,13 @@ impl Scanner {
     }
     
     /// Generate summary report
-    fn generate_summary_report(&self, _cluster_data: &ClusterData, broker_data: &[BrokerData]) -> Result<()> {
+    fn generate_summary_report(
+        &self,
+        _cluster_data: &ClusterData,
+        broker_data: &[BrokerData],
+        phases: &[PhaseTiming],
+        broker_timings: &[BrokerTiming],
+    ) -> Result<()> {
         let mut report = String::new();
         
         report.push_str("# Kafka Cluster Data Collection Summary\n\n");
@@ -521,7 +903,28 @@ impl Scanner {
             report.push_str("✅ Network connections and ports\n");
         }
         
-        fs::write(
+        report.push_str("\n## Collection Timing\n\n");
+        report.push_str("| Phase | Duration (s) |\n");
+        report.push_str("|-------|--------------|\n");
+        for phase in phases {
+            report.push_str(&format!("| {} | {:.1} |\n", phase.phase, phase.duration_secs));
+        }
+        
+        if !broker_timings.is_empty() {
+            let mut slowest: Vec<&BrokerTiming> = broker_timings.iter().collect();
+            slowest.sort_by(|a, b| b.duration_secs.total_cmp(&a.duration_secs));
+            
+            report.push_str("\n### Per Broker (slowest first)\n\n");
+            report.push_str("| Broker | Hostname | Duration (s) | Status |\n");
+            report.push_str("|--------|----------|--------------|--------|\n");
+            for timing in slowest {
+                let status = if timing.collected { "✅ collected" } else { "❌ failed" };
+                report.push_str(&format!("| {} | {} | {:.1} | {} |\n",
+                    timing.broker_id, timing.hostname, timing.duration_secs, status));
+            }
+        }
+        
+        self.manifest.writer("scanner", None).write(
             self.config.output_dir.join("COLLECTION_SUMMARY.md"),
             report,
         )?;
@@ -530,7 +933,12 @@ impl Scanner {
     }
     
     /// Calculate collection statistics
-    fn calculate_stats(&self, duration_secs: u64) -> Result<CollectionStats> {
+    fn calculate_stats(
+        &self,
+        duration_secs: u64,
+        phases: Vec<PhaseTiming>,
+        broker_timings: Vec<BrokerTiming>,
+    ) -> Result<CollectionStats> {
         let mut total_files = 0;
         let mut total_size = 0;
         
@@ -544,10 +952,277 @@ impl Scanner {
             }
         }
         
+        let phase_secs = |name: &str| phases.iter()
+            .find(|p| p.phase == name)
+            .map(|p| p.duration_secs)
+            .unwrap_or_default();
+        
         Ok(CollectionStats {
             total_files,
             total_size_bytes: total_size,
             duration_secs,
+            cluster_collect_secs: phase_secs("cluster_collect"),
+            broker_collect_secs: phase_secs("broker_collect"),
+            phases,
+            broker_timings,
         })
     }
-}
\ No newline at end of file
+}
+
+/// Record how long a scan phase took, for the summary and for telemetry
+fn record_phase(phases: &mut Vec<PhaseTiming>, phase: &'static str, started: Instant) {
+    let elapsed = started.elapsed();
+    crate::telemetry::record_phase_duration(phase, elapsed);
+    phases.push(PhaseTiming {
+        phase: phase.to_string(),
+        duration_secs: elapsed.as_secs_f64(),
+    });
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::{rules::RuleAnalyzer, Analyzer};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_cancelled_scan_writes_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("scan");
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let mut scanner = Scanner::new(None)
+            .unwrap()
+            .with_output_dir(output_dir.clone())
+            .with_brokers(vec![BrokerInfo { id: 1, hostname: "kafka-1".to_string(), listeners: Vec::new() }])
+            .with_cancellation(cancel);
+
+        let result = scanner.scan().await.unwrap();
+
+        assert!(result.metadata.partial);
+        assert!(result.broker_data.is_empty());
+
+        let saved: ScanMetadata = serde_json::from_str(
+            &fs::read_to_string(output_dir.join("scan_metadata.json")).unwrap()
+        ).unwrap();
+        assert!(saved.partial);
+        assert_eq!(saved.broker_count, 1);
+        assert_eq!(saved.accessible_brokers, 0);
+        assert!(output_dir.join("COLLECTION_SUMMARY.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_scanned_snapshot_carries_provenance() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("scan");
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let mut scanner = Scanner::new(None)
+            .unwrap()
+            .with_output_dir(output_dir.clone())
+            .with_brokers(vec![BrokerInfo { id: 1, hostname: "kafka-1".to_string(), listeners: Vec::new() }])
+            .with_cancellation(cancel)
+            .with_progress(false);
+        scanner.scan().await.unwrap();
+
+        let snapshot = crate::cli::utils::load_snapshot_from_directory(&output_dir).unwrap();
+        let environment = snapshot.metadata.environment.expect("scan records its environment");
+        assert_eq!(environment.os.as_deref(), Some(std::env::consts::OS));
+        assert_eq!(environment.arch.as_deref(), Some(std::env::consts::ARCH));
+        assert_eq!(environment.kcpilot_version.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+        assert_eq!(environment.bastion, None);
+        assert!(environment.librdkafka_version.is_some_and(|v| !v.is_empty()));
+
+        let remote = collection_environment(Some("prod-bastion".to_string()));
+        assert_eq!(remote.bastion.as_deref(), Some("prod-bastion"));
+    }
+
+    #[tokio::test]
+    async fn test_manifest_lists_every_written_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("scan");
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let mut scanner = Scanner::new(None)
+            .unwrap()
+            .with_output_dir(output_dir.clone())
+            .with_brokers(vec![BrokerInfo { id: 1, hostname: "kafka-1".to_string(), listeners: Vec::new() }])
+            .with_cancellation(cancel)
+            .with_progress(false);
+        scanner.scan().await.unwrap();
+
+        let manifest = crate::scan::Manifest::load(&output_dir).unwrap().expect("scan writes a manifest");
+        let mut written: Vec<String> = walkdir::WalkDir::new(&output_dir).into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.path().strip_prefix(&output_dir).unwrap().to_string_lossy().replace('\\', "/"))
+            .filter(|path| path != crate::scan::MANIFEST_FILE)
+            .collect();
+        written.sort();
+
+        let listed: Vec<String> = manifest.files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(listed, written);
+        assert!(listed.contains(&"scan_metadata.json".to_string()));
+        assert!(manifest.files.iter().all(|f| f.collector == "scanner" && f.broker_id.is_none()));
+        assert!(manifest.verify(&output_dir).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_phase_timings_recorded_without_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let mut scanner = Scanner::new(None)
+            .unwrap()
+            .with_output_dir(temp_dir.path().join("scan"))
+            .with_brokers(vec![BrokerInfo { id: 1, hostname: "kafka-1".to_string(), listeners: Vec::new() }])
+            .with_cancellation(cancel)
+            .with_progress(false);
+
+        let result = scanner.scan().await.unwrap();
+
+        let phases: Vec<&str> = result.collection_stats.phases.iter().map(|p| p.phase.as_str()).collect();
+        assert_eq!(phases, vec!["setup", "cluster_collect", "connectivity", "broker_collect", "summary"]);
+        assert!(result.collection_stats.phases.iter().all(|p| p.duration_secs >= 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_phase_durations_sum_to_total_and_are_saved() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("scan");
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let mut scanner = Scanner::new(None)
+            .unwrap()
+            .with_output_dir(output_dir.clone())
+            .with_brokers(vec![BrokerInfo { id: 1, hostname: "kafka-1".to_string(), listeners: Vec::new() }])
+            .with_cancellation(cancel)
+            .with_progress(false);
+
+        let stats = scanner.scan().await.unwrap().collection_stats;
+
+        let phase_total: f64 = stats.phases.iter().map(|p| p.duration_secs).sum();
+        assert!((phase_total - stats.duration_secs as f64).abs() < 1.0,
+            "phases sum to {}s, total {}s", phase_total, stats.duration_secs);
+        assert!(stats.cluster_collect_secs <= phase_total);
+        assert!(stats.broker_collect_secs <= phase_total);
+        assert!(stats.broker_timings.is_empty());
+
+        let saved: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(output_dir.join(COLLECTION_STATS_FILE)).unwrap()
+        ).unwrap();
+        assert!(saved["cluster_collect_secs"].is_number());
+        assert!(saved["broker_collect_secs"].is_number());
+        assert_eq!(saved["phases"].as_array().unwrap().len(), 5);
+
+        let summary = fs::read_to_string(output_dir.join("COLLECTION_SUMMARY.md")).unwrap();
+        assert!(summary.contains("## Collection Timing"));
+        assert!(summary.contains("| broker_collect |"));
+    }
+
+    #[tokio::test]
+    async fn test_connect_status_collected_into_snapshot() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/connectors").with_body(r#"["jdbc-sink"]"#).create_async().await;
+        server.mock("GET", "/connectors/jdbc-sink/status")
+            .with_body(r#"{"name": "jdbc-sink", "connector": {"state": "FAILED", "worker_id": "connect-1:8083"}, "tasks": []}"#)
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("scan");
+        let scanner = Scanner::new(None)
+            .unwrap()
+            .with_output_dir(output_dir.clone())
+            .with_brokers(vec![BrokerInfo { id: 1, hostname: "kafka-1".to_string(), listeners: Vec::new() }]);
+        assert!(!scanner.run_optional_collectors().await.unwrap());
+
+        let scanner = scanner.with_connect_url(Some(server.url()));
+        assert!(scanner.run_optional_collectors().await.unwrap());
+
+        let snapshot = crate::cli::utils::load_snapshot_from_directory(&output_dir).unwrap();
+        assert_eq!(snapshot.collectors.custom["connect"]["connectors"][0]["name"], "jdbc-sink");
+        let findings = RuleAnalyzer::new().analyze(&snapshot).await.unwrap();
+        assert!(findings.iter().any(|f| f.metadata.get("rule").and_then(|rule| rule.as_str()) == Some("failed_connectors")));
+    }
+
+    #[tokio::test]
+    async fn test_broker_metrics_scraped_into_snapshot() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/metrics")
+            .with_body("# TYPE kafka_server_brokertopicmetrics_bytesinpersec_oneminuterate gauge\n\
+                kafka_server_brokertopicmetrics_bytesinpersec_oneminuterate{topic=\"orders\"} 2048.0\n")
+            .create_async()
+            .await;
+        let (host, port) = server.host_with_port().rsplit_once(':').map(|(h, p)| (h.to_string(), p.parse().unwrap())).unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("scan");
+        let scanner = Scanner::new(None)
+            .unwrap()
+            .with_output_dir(output_dir.clone())
+            .with_brokers(vec![BrokerInfo { id: 1, hostname: host, listeners: Vec::new() }])
+            .with_metrics_port(Some(port));
+        assert!(scanner.run_optional_collectors().await.unwrap());
+
+        let snapshot = crate::cli::utils::load_snapshot_from_directory(&output_dir).unwrap();
+        let throughput = crate::analyzers::hot_topics::topic_throughput(&snapshot);
+        assert_eq!(throughput.len(), 1);
+        assert_eq!(throughput[0].topic, "orders");
+        assert_eq!(throughput[0].bytes_in_per_sec, 2048.0);
+    }
+
+    #[tokio::test]
+    async fn test_only_logs_skips_admin_collection() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("scan");
+        let manifest = ManifestRecorder::new();
+
+        let cluster_data = BastionCollector::new(None, output_dir.clone())
+            .with_manifest(&manifest)
+            .with_collectors(CollectorSelection::only([ScanCollector::Logs]))
+            .collect_all()
+            .await
+            .unwrap();
+
+        assert!(cluster_data.kafkactl_data.is_empty());
+        assert!(cluster_data.metrics.is_none());
+        assert!(cluster_data.bastion_info.is_empty());
+        assert!(!output_dir.join("cluster").join("kafkactl").join("unavailable.txt").exists());
+        assert!(manifest.manifest(&output_dir).files.is_empty());
+    }
+
+    #[test]
+    fn test_collection_stats_broker_timings_roundtrip() {
+        let stats = CollectionStats {
+            total_files: 10,
+            total_size_bytes: 2048,
+            duration_secs: 12,
+            phases: Vec::new(),
+            cluster_collect_secs: 2.5,
+            broker_collect_secs: 9.0,
+            broker_timings: vec![
+                BrokerTiming { broker_id: 1, hostname: "kafka-1".to_string(), duration_secs: 1.5, collected: true },
+                BrokerTiming { broker_id: 2, hostname: "kafka-2".to_string(), duration_secs: 7.5, collected: false },
+            ],
+        };
+
+        let json = serde_json::to_string(&stats).unwrap();
+        let parsed: CollectionStats = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.broker_timings, stats.broker_timings);
+        assert_eq!(parsed.broker_collect_secs, 9.0);
+        assert_eq!(parsed.slowest_brokers()[0].broker_id, 2);
+
+        // Stats written before per-phase fields existed still load
+        let legacy: CollectionStats = serde_json::from_str(
+            r#"{"total_files": 3, "total_size_bytes": 100, "duration_secs": 4}"#
+        ).unwrap();
+        assert!(legacy.broker_timings.is_empty());
+        assert_eq!(legacy.cluster_collect_secs, 0.0);
+    }
+}
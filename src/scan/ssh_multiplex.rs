@@ -0,0 +1,227 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use super::scan_command;
+use tracing::{debug, warn};
+
+/// Environment variable turning SSH connection multiplexing off when set to `0`, `false` or `off`
+pub const SSH_MULTIPLEX_ENV: &str = "KCPILOT_SSH_MULTIPLEX";
+
+/// Whether SSH multiplexing is enabled, which it is unless `KCPILOT_SSH_MULTIPLEX` turns it off
+pub fn multiplexing_enabled() -> bool {
+    std::env::var(SSH_MULTIPLEX_ENV)
+        .map(|value| !matches!(value.trim().to_lowercase().as_str(), "0" | "false" | "off" | "no"))
+        .unwrap_or(true)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MasterState {
+    NotStarted,
+    Running,
+    /// Setup failed or multiplexing is off: every command opens its own connection
+    Unavailable,
+}
+
+/// SSH connection to one host shared by the commands run on it. The first command starts a
+/// background `ControlMaster`, later ones reuse its socket instead of paying for a handshake
+/// each, and dropping the connection shuts the master down. When the master cannot be
+/// started, commands fall back to connecting one by one.
+#[derive(Debug)]
+pub struct SshConnection {
+    program: String,
+    /// `ssh` options and host reaching the target, e.g. `-A -J jump bastion`
+    target: Vec<String>,
+    control_path: PathBuf,
+    state: Mutex<MasterState>,
+}
+
+impl SshConnection {
+    pub fn new(target: Vec<String>) -> Self {
+        // Unix socket paths are limited to about 100 bytes, so the name stays short
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        Self {
+            program: "ssh".to_string(),
+            target,
+            control_path: std::env::temp_dir().join(format!("kcpilot-ssh-{}", &id[..12])),
+            state: Mutex::new(MasterState::NotStarted),
+        }
+    }
+
+    /// Share one connection between commands (default: on)
+    pub fn with_multiplexing(self, enabled: bool) -> Self {
+        if !enabled {
+            *self.state.lock().unwrap_or_else(|e| e.into_inner()) = MasterState::Unavailable;
+        }
+        self
+    }
+
+    #[cfg(test)]
+    fn with_program(mut self, program: impl Into<String>) -> Self {
+        self.program = program.into();
+        self
+    }
+
+    pub fn control_path(&self) -> &Path {
+        &self.control_path
+    }
+
+    fn control_path_option(&self) -> String {
+        format!("ControlPath={}", self.control_path.display())
+    }
+
+    /// `ssh` command reaching the target, through the master connection when there is one;
+    /// append the remote command to it
+    pub fn command(&self) -> Command {
+        let mut command = scan_command(&self.program);
+        if self.ensure_master() {
+            command.args(["-o", "ControlMaster=no", "-o", &self.control_path_option()]);
+        }
+        command.args(&self.target);
+        command
+    }
+
+    /// Start the master connection on first use; `false` when commands must connect on their own
+    fn ensure_master(&self) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if *state == MasterState::NotStarted {
+            *state = self.start_master();
+        }
+        *state == MasterState::Running
+    }
+
+    fn start_master(&self) -> MasterState {
+        // -N -f: authenticate, then keep the connection open in the background without a command
+        let started = scan_command(&self.program)
+            .args(["-o", "ControlMaster=yes", "-o", &self.control_path_option(), "-o", "ControlPersist=yes", "-N", "-f"])
+            .args(&self.target)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        match started {
+            Ok(status) if status.success() => {
+                debug!("SSH master connection started at {}", self.control_path.display());
+                MasterState::Running
+            }
+            Ok(status) => {
+                warn!("SSH multiplexing unavailable (exit code {}), connecting per command", status.code().unwrap_or(-1));
+                MasterState::Unavailable
+            }
+            Err(e) => {
+                warn!("SSH multiplexing unavailable ({}), connecting per command", e);
+                MasterState::Unavailable
+            }
+        }
+    }
+
+    /// Shut the master connection down, if one was started
+    pub fn close(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if *state != MasterState::Running {
+            return;
+        }
+        *state = MasterState::Unavailable;
+        let closed = Command::new(&self.program)
+            .args(["-o", &self.control_path_option(), "-O", "exit"])
+            .args(&self.target)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        if let Err(e) = closed {
+            warn!("Failed to close the SSH master connection at {}: {}", self.control_path.display(), e);
+        }
+    }
+}
+
+impl Drop for SshConnection {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    /// Executable standing in for ssh: records its arguments, one invocation per line,
+    /// and exits with `exit_code`
+    fn fake_ssh(dir: &Path, exit_code: i32) -> (PathBuf, PathBuf) {
+        let log = dir.join("invocations.log");
+        let program = dir.join("ssh");
+        std::fs::write(&program, format!("#!/bin/sh\necho \"$@\" >> '{}'\nexit {}\n", log.display(), exit_code)).unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        (program, log)
+    }
+
+    fn invocations(log: &Path) -> Vec<String> {
+        std::fs::read_to_string(log).unwrap_or_default().lines().map(str::to_string).collect()
+    }
+
+    fn process_group(pid: &str) -> String {
+        let output = Command::new("ps").args(["-o", "pgid=", "-p", pid]).output().unwrap();
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[test]
+    fn test_commands_run_outside_our_process_group() {
+        // A Ctrl-C at the terminal must not reach the command in flight
+        let connection = SshConnection::new(vec!["-c".to_string(), "ps -o pgid= -p $$".to_string()])
+            .with_program("sh")
+            .with_multiplexing(false);
+
+        let output = connection.command().output().unwrap();
+
+        let child_group = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        assert!(!child_group.is_empty());
+        assert_ne!(child_group, process_group(&std::process::id().to_string()));
+    }
+
+    #[test]
+    fn test_commands_reuse_master_until_teardown() {
+        let dir = TempDir::new().unwrap();
+        let (program, log) = fake_ssh(dir.path(), 0);
+        let connection = SshConnection::new(vec!["-A".to_string(), "bastion".to_string()])
+            .with_program(program.display().to_string());
+        let control_path = format!("ControlPath={}", connection.control_path().display());
+
+        for _ in 0..2 {
+            let command = connection.command();
+            let args: Vec<String> = command.get_args().map(|arg| arg.to_string_lossy().into_owned()).collect();
+            assert_eq!(args, vec!["-o", "ControlMaster=no", "-o", control_path.as_str(), "-A", "bastion"]);
+        }
+
+        // The master is set up once
+        let calls = invocations(&log);
+        assert_eq!(calls, vec![format!("-o ControlMaster=yes -o {} -o ControlPersist=yes -N -f -A bastion", control_path)]);
+
+        drop(connection);
+        let calls = invocations(&log);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[1], format!("-o {} -O exit -A bastion", control_path));
+    }
+
+    #[test]
+    fn test_falls_back_when_master_fails() {
+        let dir = TempDir::new().unwrap();
+        let (program, log) = fake_ssh(dir.path(), 255);
+        let connection = SshConnection::new(vec!["broker-1".to_string()])
+            .with_program(program.display().to_string());
+
+        let command = connection.command();
+        assert_eq!(command.get_args().collect::<Vec<_>>(), vec!["broker-1"]);
+        drop(connection);
+
+        // No teardown for a master that never ran
+        assert_eq!(invocations(&log).len(), 1);
+
+        let disabled = SshConnection::new(vec!["broker-1".to_string()])
+            .with_program(program.display().to_string())
+            .with_multiplexing(false);
+        assert_eq!(disabled.command().get_args().collect::<Vec<_>>(), vec!["broker-1"]);
+        drop(disabled);
+        assert_eq!(invocations(&log).len(), 1);
+    }
+}
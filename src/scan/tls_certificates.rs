@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// File in a broker's `system` directory holding the certificate checks of its keystores
+pub const TLS_CERTIFICATES_FILE: &str = "tls_certificates.json";
+
+/// Certificate check of one keystore or truststore of a broker, as collected during the scan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CertificateCheck {
+    /// server.properties setting naming the store, e.g. `listener.name.ssl.ssl.keystore.location`
+    pub setting: String,
+    pub path: String,
+    /// `openssl x509 -enddate -subject` output, empty when the store could not be read
+    pub output: String,
+}
+
+/// Keystore and truststore settings of server.properties with their paths and store types,
+/// including listener-specific ones, each path once
+pub fn certificate_stores(properties: &HashMap<String, String>) -> Vec<(String, String, String)> {
+    let mut stores: Vec<(String, String, String)> = properties.iter()
+        .filter(|(key, value)| {
+            (key.ends_with("ssl.keystore.location") || key.ends_with("ssl.truststore.location"))
+                && !value.trim().is_empty()
+        })
+        .map(|(key, path)| {
+            let type_key = key.replace(".location", ".type");
+            let store_type = properties.get(&type_key).map(|t| t.trim().to_uppercase()).unwrap_or_default();
+            (key.clone(), path.trim().to_string(), store_type)
+        })
+        .collect();
+    stores.sort();
+    let mut seen = HashSet::new();
+    stores.retain(|(_, path, _)| seen.insert(path.clone()));
+    stores
+}
+
+/// Command printing the expiry date and subject of the first certificate of a store: openssl
+/// directly for PEM files, through keytool for JKS and PKCS12 stores
+pub fn certificate_command(path: &str, store_type: &str) -> String {
+    let pem = store_type == "PEM" || [".pem", ".crt", ".cer"].iter().any(|extension| path.ends_with(extension));
+    if pem {
+        format!("sudo openssl x509 -enddate -subject -noout -in '{}' 2>/dev/null", path)
+    } else {
+        format!(
+            "sudo keytool -list -rfc -keystore '{}' </dev/null 2>/dev/null | openssl x509 -enddate -subject -noout 2>/dev/null",
+            path
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::cluster_detection::parse_server_properties;
+
+    #[test]
+    fn test_certificate_stores_from_properties() {
+        let properties = parse_server_properties("\
+listeners=SSL://:9093
+ssl.keystore.location=/etc/kafka/ssl/kafka.keystore.jks
+ssl.truststore.location=/etc/kafka/ssl/kafka.truststore.jks
+listener.name.internal.ssl.keystore.location=/etc/kafka/ssl/internal.pem
+listener.name.internal.ssl.keystore.type=PEM
+listener.name.external.ssl.keystore.location=/etc/kafka/ssl/kafka.keystore.jks
+");
+        let stores = certificate_stores(&properties);
+        assert_eq!(stores.len(), 3);
+        let internal = stores.iter().find(|(_, path, _)| path.ends_with("internal.pem")).unwrap();
+        assert_eq!(internal.2, "PEM");
+        assert!(certificate_command(&internal.1, &internal.2).starts_with("sudo openssl x509 -enddate"));
+        assert!(certificate_command("/etc/kafka/ssl/kafka.keystore.jks", "").contains("keytool -list -rfc"));
+
+        assert!(certificate_stores(&parse_server_properties("listeners=PLAINTEXT://:9092\n")).is_empty());
+    }
+}
@@ -8,6 +8,9 @@ pub struct ScanConfig {
     pub bastion_alias: Option<String>,  // None means running locally on bastion
     pub output_dir: PathBuf,
     pub brokers: Vec<BrokerInfo>,
+    /// Only collect log entries written at or after this time
+    #[serde(default)]
+    pub log_since: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 /// Information about a Kafka broker
@@ -15,6 +18,17 @@ pub struct ScanConfig {
 pub struct BrokerInfo {
     pub id: i32,
     pub hostname: String,
+    /// Listeners from the broker's server.properties, once collected
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub listeners: Vec<ListenerInfo>,
+}
+
+/// A named listener a broker accepts connections on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListenerInfo {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
 }
 
 /// Metadata about a scan operation
@@ -28,6 +42,15 @@ pub struct ScanMetadata {
     pub scan_version: String,
     pub accessible_brokers: usize,
     pub cluster_mode: Option<crate::snapshot::format::ClusterMode>,
+    /// The scan was cancelled before all brokers were collected
+    #[serde(default)]
+    pub partial: bool,
+    /// Host, platform and versions the scan ran with
+    #[serde(default)]
+    pub environment: Option<crate::snapshot::format::CollectionEnvironment>,
+    /// Cluster id every discovered broker reported
+    #[serde(default)]
+    pub cluster_id: Option<String>,
 }
 
 /// Result of a complete scan operation
@@ -41,11 +64,14 @@ pub struct ScanResult {
 }
 
 /// Cluster-level data collected during scan
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ClusterData {
     pub kafkactl_data: HashMap<String, String>,
     pub metrics: Option<String>,
     pub bastion_info: HashMap<String, String>,
+    /// Raw `kafka-features.sh describe` output, when the Kafka tools were available
+    #[serde(default)]
+    pub features: Option<String>,
 }
 
 /// Data collected from a specific broker
@@ -66,4 +92,42 @@ pub struct CollectionStats {
     pub total_files: usize,
     pub total_size_bytes: u64,
     pub duration_secs: u64,
-}
\ No newline at end of file
+    /// Time spent in each scan phase, in the order they ran
+    #[serde(default)]
+    pub phases: Vec<PhaseTiming>,
+    /// Time spent collecting cluster-wide data from the bastion
+    #[serde(default)]
+    pub cluster_collect_secs: f64,
+    /// Time spent collecting data from all accessible brokers
+    #[serde(default)]
+    pub broker_collect_secs: f64,
+    /// Time spent on each broker, in the order they were collected
+    #[serde(default)]
+    pub broker_timings: Vec<BrokerTiming>,
+}
+
+impl CollectionStats {
+    /// Brokers ordered from slowest to fastest collection
+    pub fn slowest_brokers(&self) -> Vec<&BrokerTiming> {
+        let mut timings: Vec<&BrokerTiming> = self.broker_timings.iter().collect();
+        timings.sort_by(|a, b| b.duration_secs.total_cmp(&a.duration_secs));
+        timings
+    }
+}
+
+/// Elapsed time of one scan phase
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhaseTiming {
+    pub phase: String,
+    pub duration_secs: f64,
+}
+
+/// Elapsed time of data collection from one broker
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BrokerTiming {
+    pub broker_id: i32,
+    pub hostname: String,
+    pub duration_secs: f64,
+    /// Whether collection from the broker succeeded
+    pub collected: bool,
+}
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -37,7 +38,11 @@ pub struct SnapshotMetadata {
     pub collection_duration_ms: u64,
     pub collectors_used: Vec<String>,
     pub redaction_applied: bool,
-    pub environment: Option<String>,
+    #[serde(default)]
+    pub redaction_report: RedactionReport,
+    /// Where and with what the data was collected; older snapshots stored only the bastion name
+    #[serde(default, deserialize_with = "deserialize_environment")]
+    pub environment: Option<CollectionEnvironment>,
     pub tags: HashMap<String, String>,
 }
 
@@ -49,10 +54,93 @@ impl SnapshotMetadata {
             collection_duration_ms: 0,
             collectors_used: Vec::new(),
             redaction_applied: false,
+            redaction_report: RedactionReport::default(),
             environment: None,
             tags: HashMap::new(),
         }
     }
+
+    /// Store the redactions made by a collector
+    pub fn record_redactions(&mut self, report: RedactionReport) {
+        self.redaction_applied = true;
+        self.redaction_report.merge(report);
+    }
+}
+
+/// Provenance of a scan, kept so findings can be traced back to how the data was collected
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CollectionEnvironment {
+    /// Operating system of the scanning host, e.g. `linux`
+    #[serde(default)]
+    pub os: Option<String>,
+    /// CPU architecture of the scanning host, e.g. `x86_64`
+    #[serde(default)]
+    pub arch: Option<String>,
+    /// Version of kcpilot that ran the scan
+    #[serde(default)]
+    pub kcpilot_version: Option<String>,
+    /// Hostname of the machine kcpilot ran on
+    #[serde(default)]
+    pub scan_host: Option<String>,
+    /// SSH bastion alias the brokers were reached through; `None` for local scans
+    #[serde(default)]
+    pub bastion: Option<String>,
+    /// librdkafka version linked into kcpilot, used for admin API collection
+    #[serde(default)]
+    pub librdkafka_version: Option<String>,
+}
+
+/// Accept both the structured environment and the bare bastion name of older snapshots
+fn deserialize_environment<'de, D>(deserializer: D) -> Result<Option<CollectionEnvironment>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Structured(CollectionEnvironment),
+        Bastion(String),
+    }
+
+    Ok(Option::<Stored>::deserialize(deserializer)?.map(|stored| match stored {
+        Stored::Structured(environment) => environment,
+        Stored::Bastion(bastion) => CollectionEnvironment {
+            bastion: Some(bastion),
+            ..Default::default()
+        },
+    }))
+}
+
+/// Record of what was scrubbed from collected data before it was stored
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RedactionReport {
+    /// Distinct paths of redacted fields, e.g. `admin.connection.sasl_password`
+    pub fields_redacted: Vec<String>,
+    /// Total number of values redacted, counting repeats of the same field
+    pub count: usize,
+}
+
+impl RedactionReport {
+    pub fn record(&mut self, field: impl Into<String>) {
+        let field = field.into();
+        if !self.fields_redacted.contains(&field) {
+            self.fields_redacted.push(field);
+        }
+        self.count += 1;
+    }
+
+    pub fn merge(&mut self, other: RedactionReport) {
+        for field in other.fields_redacted {
+            if !self.fields_redacted.contains(&field) {
+                self.fields_redacted.push(field);
+            }
+        }
+        self.count += other.count;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
 }
 
 /// Cluster-level snapshot data
@@ -66,22 +154,17 @@ pub struct ClusterSnapshot {
     pub region: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum ClusterMode {
     #[serde(rename = "kraft")]
     Kraft,
     #[serde(rename = "zookeeper")]
     Zookeeper,
     #[serde(rename = "unknown")]
+    #[default]
     Unknown,
 }
 
-impl Default for ClusterMode {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 /// Container for all collector outputs
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CollectorOutputs {
@@ -93,8 +176,27 @@ pub struct CollectorOutputs {
     pub custom: HashMap<String, serde_json::Value>,
 }
 
-/// Finding from analysis
+/// Key under which consumer group lag data is stored in `CollectorOutputs::custom`,
+/// a list of `ConsumerGroupLag` the scan saves to `cluster/kafkactl/consumer_lag.json`
+pub const CONSUMER_LAG_KEY: &str = "consumer_lag";
+
+/// Consumer group summary as stored under `collectors.custom["consumer_lag"]`
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsumerGroupLag {
+    pub group: String,
+    #[serde(default)]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub members: usize,
+    #[serde(default)]
+    pub total_lag: i64,
+    /// Topics the group has committed offsets for
+    #[serde(default)]
+    pub topics: Vec<String>,
+}
+
+/// Finding from analysis
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Finding {
     pub id: String,
     pub severity: Severity,
@@ -108,7 +210,7 @@ pub struct Finding {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Severity {
     #[serde(rename = "critical")]
     Critical,
@@ -144,7 +246,7 @@ impl Severity {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum Category {
     #[serde(rename = "cluster_hygiene")]
     ClusterHygiene,
@@ -165,15 +267,27 @@ pub enum Category {
 }
 
 /// Evidence supporting a finding
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Evidence {
     pub metrics: Vec<MetricEvidence>,
     pub logs: Vec<LogEvidence>,
     pub configs: Vec<ConfigEvidence>,
     pub raw_data: Option<serde_json::Value>,
+    /// Log events around the issue in chronological order, built for critical findings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timeline: Vec<TimelineEvent>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// One log event of a finding's timeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TimelineEvent {
+    pub timestamp: DateTime<Utc>,
+    /// Log the event was read from, such as `broker_1/server.log`
+    pub source: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MetricEvidence {
     pub name: String,
     pub value: f64,
@@ -183,7 +297,7 @@ pub struct MetricEvidence {
     pub timestamp: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LogEvidence {
     pub level: String,
     pub message: String,
@@ -193,7 +307,7 @@ pub struct LogEvidence {
     pub count: usize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ConfigEvidence {
     pub resource_type: String,
     pub resource_name: String,
@@ -205,7 +319,7 @@ pub struct ConfigEvidence {
 }
 
 /// Remediation information for a finding
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Remediation {
     pub steps: Vec<RemediationStep>,
     pub script: Option<String>,
@@ -215,7 +329,7 @@ pub struct Remediation {
     pub rollback_plan: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RemediationStep {
     pub order: u32,
     pub description: String,
@@ -224,7 +338,7 @@ pub struct RemediationStep {
     pub can_automate: bool,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 pub enum RiskLevel {
     #[serde(rename = "low")]
     Low,
@@ -248,6 +362,7 @@ impl Default for Finding {
                 logs: Vec::new(),
                 configs: Vec::new(),
                 raw_data: None,
+                timeline: Vec::new(),
             },
             root_cause: None,
             remediation: Remediation {
@@ -4,7 +4,9 @@ use std::collections::HashMap;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
-use tracing::{debug, info};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, info, warn};
 
 pub use format::{Snapshot, SnapshotMetadata};
 
@@ -29,9 +31,117 @@ pub enum SnapshotError {
 
 pub type SnapshotResult<T> = Result<T, SnapshotError>;
 
+/// Archive directory holding raw collected files kept out of `snapshot.json`
+pub const RAW_DATA_DIR: &str = "raw";
+
+/// Extensions of the files written by `SnapshotManager::save` and `SnapshotManager::create_archive`
+pub const SNAPSHOT_FILE_EXTENSIONS: &[&str] = &["snapshot", "tar"];
+
+/// Whether `path` names a snapshot file or archive, by its extension
+pub fn is_snapshot_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| SNAPSHOT_FILE_EXTENSIONS.contains(&extension))
+}
+
+/// Key marking a JSON value that was replaced by a reference to a raw archive member
+pub const RAW_DATA_REF_KEY: &str = "$raw_ref";
+
+/// Collected strings at least this large are moved out of the snapshot when raw data is disabled
+pub const RAW_DATA_MIN_BYTES: usize = 1024;
+
 /// Snapshot manager for saving and loading snapshots
 pub struct SnapshotManager {
     compress: bool,
+    raw_data: bool,
+}
+
+/// Reference to a raw collected file stored as a separate archive member
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RawDataRef {
+    /// Archive member path, relative to the archive root
+    pub path: String,
+    /// MD5 of the member contents
+    pub md5: String,
+    /// Size of the member in bytes
+    pub size: usize,
+}
+
+impl RawDataRef {
+    fn for_content(content: &str) -> Self {
+        let md5 = format!("{:x}", md5::compute(content.as_bytes()));
+        Self {
+            path: format!("{}/{}.txt", RAW_DATA_DIR, md5),
+            md5,
+            size: content.len(),
+        }
+    }
+
+    /// Parse a `{"$raw_ref": {...}}` placeholder, if `value` is one
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let object = value.as_object()?;
+        if object.len() != 1 {
+            return None;
+        }
+        serde_json::from_value(object.get(RAW_DATA_REF_KEY)?.clone()).ok()
+    }
+
+    fn to_value(&self) -> Value {
+        serde_json::json!({ RAW_DATA_REF_KEY: self })
+    }
+
+    /// Read the referenced member from an extracted archive, verifying its hash
+    pub fn read(&self, archive_dir: &Path) -> SnapshotResult<String> {
+        validate_archive_path(&self.path)?;
+        let content = std::fs::read_to_string(archive_dir.join(&self.path))?;
+        if format!("{:x}", md5::compute(content.as_bytes())) != self.md5 {
+            return Err(SnapshotError::InvalidFormat);
+        }
+        Ok(content)
+    }
+}
+
+/// Replace large string leaves with raw data references, collecting the member contents by path
+fn externalize_strings(value: &mut Value, members: &mut HashMap<String, Vec<u8>>) {
+    match value {
+        Value::String(content) if content.len() >= RAW_DATA_MIN_BYTES => {
+            let reference = RawDataRef::for_content(content);
+            members.entry(reference.path.clone())
+                .or_insert_with(|| std::mem::take(content).into_bytes());
+            *value = reference.to_value();
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| externalize_strings(item, members)),
+        Value::Object(object) => object.values_mut().for_each(|item| externalize_strings(item, members)),
+        _ => {}
+    }
+}
+
+/// Replace raw data references with the contents of the referenced archive members
+fn inline_strings(value: &mut Value, archive_dir: &Path) -> SnapshotResult<()> {
+    if let Some(reference) = RawDataRef::from_value(value) {
+        *value = Value::String(reference.read(archive_dir)?);
+        return Ok(());
+    }
+    match value {
+        Value::Array(items) => items.iter_mut().try_for_each(|item| inline_strings(item, archive_dir)),
+        Value::Object(object) => object.values_mut().try_for_each(|item| inline_strings(item, archive_dir)),
+        _ => Ok(()),
+    }
+}
+
+/// Move raw log, config and scan file contents out of `snapshot` into archive members.
+/// Admin, metrics and cloud summaries stay inline.
+pub fn externalize_raw_data(snapshot: &Snapshot) -> (Snapshot, HashMap<String, Vec<u8>>) {
+    let mut slim = snapshot.clone();
+    let mut members = HashMap::new();
+    let collectors = &mut slim.collectors;
+    for section in [&mut collectors.logs, &mut collectors.config].into_iter().flatten() {
+        externalize_strings(section, &mut members);
+    }
+    for section in collectors.custom.values_mut() {
+        externalize_strings(section, &mut members);
+    }
+    (slim, members)
 }
 
 /// Validate archive path to prevent directory traversal attacks
@@ -59,6 +169,31 @@ fn validate_archive_path(name: &str) -> SnapshotResult<()> {
     Ok(())
 }
 
+/// Write one archive entry under `output_dir`, rejecting paths that would escape it
+fn extract_entry<R: Read>(entry: &mut tar::Entry<R>, output_dir: &Path) -> SnapshotResult<()> {
+    let entry_path = entry.path()?.into_owned();
+    validate_archive_path(&entry_path.to_string_lossy())?;
+    
+    // Ensure the path is still within output_dir after join
+    let safe_path = output_dir.join(&entry_path);
+    if !safe_path.starts_with(output_dir) {
+        return Err(SnapshotError::InvalidFormat);
+    }
+    
+    if let Some(parent) = safe_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    
+    let mut output_file = File::create(&safe_path)?;
+    if let Err(e) = std::io::copy(entry, &mut output_file) {
+        // Leave no truncated file behind
+        drop(output_file);
+        let _ = std::fs::remove_file(&safe_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
 impl Default for SnapshotManager {
     fn default() -> Self {
         Self::new()
@@ -67,7 +202,7 @@ impl Default for SnapshotManager {
 
 impl SnapshotManager {
     pub fn new() -> Self {
-        Self { compress: true }
+        Self { compress: true, raw_data: true }
     }
     
     pub fn with_compression(mut self, compress: bool) -> Self {
@@ -75,6 +210,13 @@ impl SnapshotManager {
         self
     }
     
+    /// Keep raw collected files inside `snapshot.json` (default). When disabled, archives
+    /// store large raw contents as separate members under `raw/` and reference them by path and hash.
+    pub fn with_raw_data(mut self, raw_data: bool) -> Self {
+        self.raw_data = raw_data;
+        self
+    }
+    
     /// Save a snapshot to a file
     pub fn save(&self, snapshot: &Snapshot, path: &Path) -> SnapshotResult<()> {
         info!("Saving snapshot to {:?}", path);
@@ -152,8 +294,14 @@ impl SnapshotManager {
         let file = File::create(path)?;
         let mut archive = Builder::new(file);
         
-        // Add snapshot JSON
-        let snapshot_json = serde_json::to_vec_pretty(snapshot)?;
+        // Add snapshot JSON, with raw contents split into their own members if requested
+        let (snapshot_json, raw_members) = if self.raw_data {
+            (serde_json::to_vec_pretty(snapshot)?, HashMap::new())
+        } else {
+            let (slim, members) = externalize_raw_data(snapshot);
+            debug!("Moved {} raw file(s) out of the snapshot", members.len());
+            (serde_json::to_vec_pretty(&slim)?, members)
+        };
         let mut header = tar::Header::new_gnu();
         header.set_path("snapshot.json")?;
         header.set_size(snapshot_json.len() as u64);
@@ -161,8 +309,8 @@ impl SnapshotManager {
         header.set_cksum();
         archive.append(&header, &snapshot_json[..])?;
         
-        // Add reports
-        for (name, data) in reports {
+        // Add reports and raw data members
+        for (name, data) in reports.into_iter().chain(raw_members) {
             // Validate path to prevent directory traversal attacks
             validate_archive_path(&name)?;
             
@@ -183,42 +331,231 @@ impl SnapshotManager {
     /// Extract a snapshot archive
     pub fn extract_archive(&self, path: &Path, output_dir: &Path) -> SnapshotResult<Snapshot> {
         use tar::Archive;
-        use std::io::copy;
         
         info!("Extracting snapshot archive from {:?}", path);
         
         let file = File::open(path)?;
         let mut archive = Archive::new(file);
         
-        // Safely extract files with path validation
         for entry in archive.entries()? {
-            let mut entry = entry?;
-            let entry_path = entry.path()?;
-            
-            // Convert to string for validation
-            let path_str = entry_path.to_string_lossy();
-            validate_archive_path(&path_str)?;
-            
-            // Construct safe output path
-            let safe_path = output_dir.join(&*entry_path);
-            
-            // Ensure the path is still within output_dir after join
-            if !safe_path.starts_with(output_dir) {
-                return Err(SnapshotError::InvalidFormat);
-            }
-            
-            // Create parent directories if needed
-            if let Some(parent) = safe_path.parent() {
-                std::fs::create_dir_all(parent)?;
-            }
-            
-            // Extract the file
-            let mut output_file = File::create(&safe_path)?;
-            copy(&mut entry, &mut output_file)?;
+            extract_entry(&mut entry?, output_dir)?;
         }
         
         // Load the snapshot
         let snapshot_path = output_dir.join("snapshot.json");
         self.load(&snapshot_path)
     }
+    
+    /// Extract a snapshot archive, skipping entries that fail to extract instead of giving up
+    /// on the whole archive. Returns the snapshot with the name and error of every skipped
+    /// entry; entries with unsafe paths are skipped too. Fails only when the archive cannot be
+    /// read or `snapshot.json` itself is missing or invalid.
+    pub fn extract_archive_resilient(&self, path: &Path, output_dir: &Path) -> SnapshotResult<(Snapshot, Vec<(String, SnapshotError)>)> {
+        use tar::Archive;
+        
+        info!("Extracting snapshot archive from {:?}", path);
+        
+        let file = File::open(path)?;
+        let mut archive = Archive::new(file);
+        
+        let mut skipped = Vec::new();
+        for (index, entry) in archive.entries()?.enumerate() {
+            let mut entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    // Entries after an unreadable header cannot be located
+                    skipped.push((format!("entry #{}", index + 1), e.into()));
+                    break;
+                }
+            };
+            let name = entry.path()
+                .map(|entry_path| entry_path.to_string_lossy().into_owned())
+                .unwrap_or_else(|_| format!("entry #{}", index + 1));
+            if let Err(e) = extract_entry(&mut entry, output_dir) {
+                warn!("Skipping archive entry {}: {}", name, e);
+                skipped.push((name, e));
+            }
+        }
+        
+        let snapshot = self.load(&output_dir.join("snapshot.json"))?;
+        Ok((snapshot, skipped))
+    }
+    
+    /// Load a snapshot file, or an archive with its raw data restored, extracted to a temporary
+    /// directory that is removed afterwards. Archive members that fail to extract are skipped
+    /// unless the snapshot needs them.
+    pub fn load_file(&self, path: &Path) -> SnapshotResult<Snapshot> {
+        if path.extension().and_then(|extension| extension.to_str()) != Some("tar") {
+            return self.load(path);
+        }
+
+        let extract_dir = std::env::temp_dir().join(format!("kcpilot-archive-{}", uuid::Uuid::new_v4()));
+        let loaded = self.extract_archive_resilient(path, &extract_dir).and_then(|(mut snapshot, _skipped)| {
+            self.inline_raw_data(&mut snapshot, &extract_dir)?;
+            Ok(snapshot)
+        });
+        let _ = std::fs::remove_dir_all(&extract_dir);
+        loaded
+    }
+    
+    /// Restore raw data referenced by `snapshot` from an archive extracted to `archive_dir`
+    pub fn inline_raw_data(&self, snapshot: &mut Snapshot, archive_dir: &Path) -> SnapshotResult<()> {
+        let collectors = &mut snapshot.collectors;
+        for section in [&mut collectors.logs, &mut collectors.config].into_iter().flatten() {
+            inline_strings(section, archive_dir)?;
+        }
+        for section in collectors.custom.values_mut() {
+            inline_strings(section, archive_dir)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn snapshot_with_raw_files() -> Snapshot {
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        let server_log = "[2024-01-01 00:00:00,000] INFO Kafka started\n".repeat(200);
+        let properties = "broker.id=1\nlog.dirs=/var/lib/kafka\n".repeat(100);
+        snapshot.collectors.admin = Some(json!({"brokers": [{"id": 1}]}));
+        snapshot.collectors.logs = Some(json!({"server.log": server_log}));
+        snapshot.collectors.custom.insert("brokers".to_string(), json!({
+            "broker_1": {
+                "config": {"server.properties": properties},
+                "system": {"hostname": "kafka-1"},
+            },
+        }));
+        snapshot
+    }
+
+    fn archived_snapshot_json(manager: &SnapshotManager, snapshot: &Snapshot, dir: &Path) -> Vec<u8> {
+        let archive = dir.join("snapshot.tar");
+        manager.create_archive(snapshot, HashMap::new(), &archive).unwrap();
+        let extracted = dir.join("extracted");
+        manager.extract_archive(&archive, &extracted).unwrap();
+        std::fs::read(extracted.join("snapshot.json")).unwrap()
+    }
+
+    #[test]
+    fn test_legacy_environment_string_is_read_as_bastion() {
+        let mut value = serde_json::to_value(Snapshot::new(SnapshotMetadata::new("test".to_string()))).unwrap();
+        value["metadata"]["environment"] = json!("old-bastion");
+        let snapshot: Snapshot = serde_json::from_value(value.clone()).unwrap();
+        let environment = snapshot.metadata.environment.unwrap();
+        assert_eq!(environment.bastion.as_deref(), Some("old-bastion"));
+        assert_eq!(environment.os, None);
+
+        value["metadata"].as_object_mut().unwrap().remove("environment");
+        let snapshot: Snapshot = serde_json::from_value(value).unwrap();
+        assert!(snapshot.metadata.environment.is_none());
+    }
+
+    #[test]
+    fn test_snapshot_json_smaller_without_raw_data() {
+        let snapshot = snapshot_with_raw_files();
+        let with_raw = tempfile::tempdir().unwrap();
+        let without_raw = tempfile::tempdir().unwrap();
+
+        let full = archived_snapshot_json(&SnapshotManager::new(), &snapshot, with_raw.path());
+        let slim = archived_snapshot_json(
+            &SnapshotManager::new().with_raw_data(false), &snapshot, without_raw.path());
+
+        assert!(slim.len() * 4 < full.len(), "slim {} vs full {} bytes", slim.len(), full.len());
+        assert!(without_raw.path().join("extracted").join(RAW_DATA_DIR).is_dir());
+        assert!(!with_raw.path().join("extracted").join(RAW_DATA_DIR).exists());
+    }
+
+    #[test]
+    fn test_raw_data_round_trips_through_archive() {
+        let snapshot = snapshot_with_raw_files();
+        let dir = tempfile::tempdir().unwrap();
+        let manager = SnapshotManager::new().with_raw_data(false);
+        let archive = dir.path().join("snapshot.tar");
+        manager.create_archive(&snapshot, HashMap::new(), &archive).unwrap();
+
+        let extracted = dir.path().join("extracted");
+        let mut loaded = manager.extract_archive(&archive, &extracted).unwrap();
+
+        // Small values stay inline, large ones become references
+        let broker = &loaded.collectors.custom["brokers"]["broker_1"];
+        assert_eq!(broker["system"]["hostname"], "kafka-1");
+        let reference = RawDataRef::from_value(&broker["config"]["server.properties"]).unwrap();
+        assert!(reference.path.starts_with("raw/"));
+        assert_eq!(loaded.collectors.admin, snapshot.collectors.admin);
+
+        manager.inline_raw_data(&mut loaded, &extracted).unwrap();
+        assert_eq!(loaded.collectors.logs, snapshot.collectors.logs);
+        assert_eq!(loaded.collectors.custom, snapshot.collectors.custom);
+    }
+
+    #[test]
+    fn test_load_file_of_snapshot_and_archive() {
+        let snapshot = snapshot_with_raw_files();
+        let dir = tempfile::tempdir().unwrap();
+        let manager = SnapshotManager::new().with_raw_data(false);
+
+        let saved = dir.path().join("cluster.snapshot");
+        manager.save(&snapshot, &saved).unwrap();
+        let archive = dir.path().join("cluster.tar");
+        manager.create_archive(&snapshot, HashMap::new(), &archive).unwrap();
+        assert!(is_snapshot_file(&saved) && is_snapshot_file(&archive));
+        assert!(!is_snapshot_file(&dir.path().join("report.json")));
+
+        assert_eq!(manager.load_file(&saved).unwrap().collectors.custom, snapshot.collectors.custom);
+        // Raw data moved out of the archived snapshot is restored
+        assert_eq!(manager.load_file(&archive).unwrap().collectors.custom, snapshot.collectors.custom);
+    }
+
+    #[test]
+    fn test_resilient_extraction_skips_failing_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("snapshot.tar");
+        let snapshot_json = serde_json::to_vec(&Snapshot::new(SnapshotMetadata::new("test".to_string()))).unwrap();
+
+        let mut archive = tar::Builder::new(File::create(&archive_path).unwrap());
+        let mut append = |name: &[u8], data: &[u8]| {
+            let mut header = tar::Header::new_gnu();
+            // Written raw so the test can include names that set_path refuses
+            header.as_old_mut().name[..name.len()].copy_from_slice(name);
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            archive.append(&header, data).unwrap();
+        };
+        append(b"snapshot.json", &snapshot_json);
+        append(b"report.md", b"# Report");
+        // Its parent is the file extracted just before, so it cannot be written
+        append(b"report.md/details.md", b"details");
+        append(b"../escape.txt", b"outside");
+        append(b"summary.md", b"summary");
+        archive.finish().unwrap();
+        drop(archive);
+
+        let extracted = dir.path().join("extracted");
+        let manager = SnapshotManager::new();
+        assert!(manager.extract_archive(&archive_path, &extracted).is_err());
+        assert!(!extracted.join("summary.md").exists());
+
+        let (snapshot, skipped) = manager.extract_archive_resilient(&archive_path, &extracted).unwrap();
+        assert_eq!(snapshot.version, format::SNAPSHOT_VERSION);
+        let names: Vec<&str> = skipped.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["report.md/details.md", "../escape.txt"]);
+        assert!(matches!(skipped[0].1, SnapshotError::IoError(_)));
+        assert!(matches!(skipped[1].1, SnapshotError::InvalidFormat));
+        assert_eq!(std::fs::read_to_string(extracted.join("summary.md")).unwrap(), "summary");
+        assert!(!dir.path().join("escape.txt").exists());
+    }
+
+    #[test]
+    fn test_raw_data_hash_mismatch_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let reference = RawDataRef::for_content("original contents");
+        std::fs::create_dir_all(dir.path().join(RAW_DATA_DIR)).unwrap();
+        std::fs::write(dir.path().join(&reference.path), "tampered contents").unwrap();
+
+        assert!(matches!(reference.read(dir.path()), Err(SnapshotError::InvalidFormat)));
+    }
 }
\ No newline at end of file
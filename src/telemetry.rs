@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+/// Environment variable that enables the OTLP export of spans and metrics.
+/// Export is only available when built with the `otel` feature; otherwise
+/// the functions in this module are no-ops.
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+#[cfg(feature = "otel")]
+mod otel {
+    use opentelemetry::metrics::{Counter, Histogram};
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry::{global, KeyValue};
+    use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+    use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+    use opentelemetry_sdk::{runtime, Resource};
+    use std::sync::OnceLock;
+    use std::time::Duration;
+    use tracing::Subscriber;
+    use tracing_opentelemetry::OpenTelemetryLayer;
+    use tracing_subscriber::registry::LookupSpan;
+
+    const SERVICE_NAME: &str = "kcpilot";
+
+    static TRACER_PROVIDER: OnceLock<TracerProvider> = OnceLock::new();
+    static METER_PROVIDER: OnceLock<SdkMeterProvider> = OnceLock::new();
+
+    struct Instruments {
+        phase_duration: Histogram<f64>,
+        findings: Counter<u64>,
+    }
+
+    fn instruments() -> &'static Instruments {
+        static INSTRUMENTS: OnceLock<Instruments> = OnceLock::new();
+        INSTRUMENTS.get_or_init(|| {
+            let meter = global::meter(SERVICE_NAME);
+            Instruments {
+                phase_duration: meter
+                    .f64_histogram("kcpilot.phase.duration")
+                    .with_unit("s")
+                    .with_description("Duration of scan and analysis phases")
+                    .build(),
+                findings: meter
+                    .u64_counter("kcpilot.findings")
+                    .with_description("Number of findings produced by analysis")
+                    .build(),
+            }
+        })
+    }
+
+    fn resource() -> Resource {
+        Resource::new(vec![
+            KeyValue::new("service.name", SERVICE_NAME),
+            KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+        ])
+    }
+
+    /// Build a tracing layer exporting spans through the given provider
+    pub fn layer_for_provider<S>(provider: &TracerProvider) -> OpenTelemetryLayer<S, Tracer>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME))
+    }
+
+    pub fn otel_layer<S>() -> Option<OpenTelemetryLayer<S, Tracer>>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        std::env::var(super::OTLP_ENDPOINT_ENV).ok()?;
+
+        let span_exporter = match opentelemetry_otlp::SpanExporter::builder().with_tonic().build() {
+            Ok(exporter) => exporter,
+            Err(e) => {
+                eprintln!("Failed to create OTLP span exporter: {}", e);
+                return None;
+            }
+        };
+        let tracer_provider = TracerProvider::builder()
+            .with_batch_exporter(span_exporter, runtime::Tokio)
+            .with_resource(resource())
+            .build();
+
+        match opentelemetry_otlp::MetricExporter::builder().with_tonic().build() {
+            Ok(metric_exporter) => {
+                let reader = PeriodicReader::builder(metric_exporter, runtime::Tokio).build();
+                let meter_provider = SdkMeterProvider::builder()
+                    .with_reader(reader)
+                    .with_resource(resource())
+                    .build();
+                global::set_meter_provider(meter_provider.clone());
+                let _ = METER_PROVIDER.set(meter_provider);
+            }
+            Err(e) => eprintln!("Failed to create OTLP metric exporter: {}", e),
+        }
+
+        let layer = layer_for_provider(&tracer_provider);
+        global::set_tracer_provider(tracer_provider.clone());
+        let _ = TRACER_PROVIDER.set(tracer_provider);
+        Some(layer)
+    }
+
+    pub fn record_phase_duration(phase: &'static str, duration: Duration) {
+        instruments()
+            .phase_duration
+            .record(duration.as_secs_f64(), &[KeyValue::new("phase", phase)]);
+    }
+
+    pub fn record_findings(count: usize) {
+        instruments().findings.add(count as u64, &[]);
+    }
+
+    pub fn shutdown() {
+        if let Some(provider) = TRACER_PROVIDER.get() {
+            let _ = provider.shutdown();
+        }
+        if let Some(provider) = METER_PROVIDER.get() {
+            let _ = provider.shutdown();
+        }
+    }
+}
+
+#[cfg(feature = "otel")]
+pub use otel::{layer_for_provider, otel_layer};
+
+/// Tracing layer placeholder used when the `otel` feature is disabled
+#[cfg(not(feature = "otel"))]
+pub fn otel_layer() -> Option<tracing_subscriber::layer::Identity> {
+    None
+}
+
+/// Record how long a scan or analysis phase took
+pub fn record_phase_duration(phase: &'static str, duration: Duration) {
+    #[cfg(feature = "otel")]
+    otel::record_phase_duration(phase, duration);
+    #[cfg(not(feature = "otel"))]
+    let _ = (phase, duration);
+}
+
+/// Record the number of findings produced by an analysis run
+pub fn record_findings(count: usize) {
+    #[cfg(feature = "otel")]
+    otel::record_findings(count);
+    #[cfg(not(feature = "otel"))]
+    let _ = count;
+}
+
+/// Flush and shut down exporters before the process exits
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    otel::shutdown();
+}
+
+#[cfg(all(test, feature = "otel"))]
+mod tests {
+    use super::*;
+    use crate::cli::handlers::analyze::{run_analysis, AnalysisOptions};
+    use crate::snapshot::format::{Snapshot, SnapshotMetadata};
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use tracing::Instrument;
+    use tracing_subscriber::prelude::*;
+
+    #[tokio::test]
+    async fn test_analyze_span_exported() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry().with(layer_for_provider(&provider));
+
+        let mut snapshot = Snapshot::new(SnapshotMetadata::new("test".to_string()));
+        snapshot.collectors.config = Some(serde_json::json!({
+            "broker_1/server.properties": "broker.id=1\ndefault.replication.factor=1"
+        }));
+
+        let _guard = tracing::subscriber::set_default(subscriber);
+        run_analysis(&snapshot, None, &AnalysisOptions::default())
+            .instrument(tracing::info_span!("fake_run"))
+            .await
+            .unwrap();
+        provider.force_flush();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let names: Vec<&str> = spans.iter().map(|s| s.name.as_ref()).collect();
+        assert!(names.contains(&"analyze"), "exported spans: {:?}", names);
+        assert!(names.contains(&"fake_run"), "exported spans: {:?}", names);
+    }
+}